@@ -193,6 +193,7 @@ pub fn run() -> sc_cli::Result<()> {
 						let telemetry_worker_handle = None;
 						let program_path = None;
 						let overseer_enable_anyways = false;
+						let subsystems_config = Default::default();
 
 						polkadot_service::new_full::<rialto_runtime::RuntimeApi, ExecutorDispatch, _>(
 							config,
@@ -203,6 +204,7 @@ pub fn run() -> sc_cli::Result<()> {
 							telemetry_worker_handle,
 							program_path,
 							overseer_enable_anyways,
+							subsystems_config,
 							overseer_gen,
 						)
 							.map(|full| full.task_manager)
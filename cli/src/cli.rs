@@ -17,6 +17,7 @@
 //! Polkadot CLI library.
 
 use clap::Parser;
+use std::path::PathBuf;
 
 #[allow(missing_docs)]
 #[derive(Debug, Parser)]
@@ -70,6 +71,35 @@ pub enum Subcommand {
 	/// Key management CLI utilities
 	#[clap(subcommand)]
 	Key(sc_cli::KeySubcommand),
+
+	/// Upgrade the approval-voting entries of the parachains DB to the current schema version.
+	UpgradeApprovalDb(UpgradeApprovalDbCmd),
+}
+
+#[allow(missing_docs)]
+#[derive(Debug, Parser)]
+pub struct UpgradeApprovalDbCmd {
+	/// Only report the pending migration, opening the DB read-only.
+	#[clap(long)]
+	pub dry_run: bool,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: sc_cli::SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: sc_cli::DatabaseParams,
+}
+
+impl sc_cli::CliConfiguration for UpgradeApprovalDbCmd {
+	fn shared_params(&self) -> &sc_cli::SharedParams {
+		&self.shared_params
+	}
+
+	fn database_params(&self) -> Option<&sc_cli::DatabaseParams> {
+		Some(&self.database_params)
+	}
 }
 
 #[allow(missing_docs)]
@@ -119,12 +149,124 @@ pub struct RunCmd {
 	#[clap(long)]
 	pub jaeger_agent: Option<String>,
 
+	/// Export the collected spans via OTLP to an OpenTelemetry collector at the given endpoint,
+	/// instead of sending them to a jaeger agent.
+	///
+	/// Must be a valid URL, commonly `http://127.0.0.1:4317` for gRPC
+	/// or `http://127.0.0.1:4318/v1/traces` for HTTP.
+	#[clap(long, conflicts_with = "jaeger-agent")]
+	pub otlp_endpoint: Option<String>,
+
+	/// The protocol used to export spans to the `--otlp-endpoint`.
+	#[clap(long, default_value = "grpc", possible_values = &["grpc", "http"])]
+	pub otlp_protocol: String,
+
+	/// Print the log events of the parachain subsystems as JSON to stdout, one object per line,
+	/// in addition to the regular log output.
+	///
+	/// The `parachain` filters given with `--log` apply, `parachain=info` otherwise. The JSON
+	/// output is installed in the place of `--tracing-targets`, so the two can't be combined.
+	/// The text log output of the `sc_tracing` target is turned off, which therefore can't be
+	/// given a filter with `--log`.
+	#[clap(long)]
+	pub log_json: bool,
+
+	/// Print only one in every `N` JSON log events of a target.
+	///
+	/// Of format `<target>=<N>`, applies to all targets starting with `<target>`.
+	/// Can be given multiple times.
+	#[clap(long, requires = "log-json")]
+	pub log_json_sample: Vec<String>,
+
+	/// Print at most `N` JSON log events of a target per second.
+	///
+	/// Of format `<target>=<N>`, applies to all targets starting with `<target>`.
+	/// Can be given multiple times.
+	#[clap(long, requires = "log-json")]
+	pub log_json_rate_limit: Vec<String>,
+
 	/// Add the destination address to the `pyroscope` agent.
 	///
 	/// Must be valid socket address, of format `IP:Port`
 	/// commonly `127.0.0.1:4040`.
 	#[clap(long)]
 	pub pyroscope_server: Option<String>,
+
+	/// Interval in seconds at which the chain selection subsystem checks for stagnant blocks.
+	/// Must be positive.
+	///
+	/// Stagnant blocks are not detected unless this is set.
+	#[clap(long, value_name = "SECONDS")]
+	pub stagnant_check_interval: Option<std::num::NonZeroU64>,
+
+	/// Time in seconds after which unapproved blocks are considered stagnant by the
+	/// chain selection subsystem.
+	#[clap(long, value_name = "SECONDS")]
+	pub stagnant_timeout: Option<u64>,
+
+	/// Directory to which the provisioner writes a record of the inherent data of every
+	/// authored block, for post-mortem analysis.
+	///
+	/// Only the most recent records are kept. Nothing is written unless this is set.
+	#[clap(long)]
+	pub inherent_data_dump_dir: Option<PathBuf>,
+
+	/// File listing the SS58 encoded authority discovery keys in the order they are arranged
+	/// in the gossip topology, one per line. Meant for deterministic local test networks.
+	///
+	/// Authorities missing from the file are appended in the order of the session. Only allowed
+	/// on development and local chain specs.
+	#[clap(long)]
+	pub gossip_topology_file: Option<PathBuf>,
+
+	/// Issue approval assignments claiming multiple candidates at once.
+	///
+	/// Such assignments are only sent to peers speaking the second version of the validation
+	/// protocol, so this should only be enabled once most validators have upgraded.
+	#[clap(long)]
+	pub enable_v2_assignments: bool,
+
+	/// Maximum number of candidates of a relay chain block covered by a single approval
+	/// signature.
+	///
+	/// Approvals are signed one by one unless this is set above 1. Coalesced approvals are only
+	/// sent to peers speaking the second version of the validation protocol.
+	#[clap(long)]
+	pub max_approval_coalesce_count: Option<u32>,
+
+	/// Maximum number of ticks (500ms) a local approval is held back, waiting for the checks of
+	/// other candidates of the same relay chain block to be signed together with them.
+	///
+	/// Defaults to 2. Only relevant with `--max-approval-coalesce-count` above 1.
+	#[clap(long)]
+	pub max_approval_coalesce_wait_ticks: Option<u32>,
+
+	/// Log the unapproved candidates and their no-shows whenever the highest approved ancestor
+	/// of a chain lags behind its head by more than this many blocks.
+	///
+	/// Not reported unless this is set.
+	#[clap(long)]
+	pub approval_lag_report_threshold: Option<u32>,
+
+	/// Maximum number of collations of a single para fetched concurrently by a validator.
+	#[clap(long)]
+	pub max_collation_fetches_per_para: Option<usize>,
+
+	/// Maximum number of collations fetched concurrently by a validator across all paras.
+	#[clap(long)]
+	pub max_collation_fetches: Option<usize>,
+
+	/// Record everything the given subsystem receives to the `--overseer-recording` file, such
+	/// that it can be replayed offline.
+	///
+	/// Subsystem names are dash separated, e.g. `bitfield-distribution`. Can be given multiple
+	/// times.
+	#[clap(long, requires = "overseer-recording")]
+	pub record_subsystem: Vec<String>,
+
+	/// File the messages received by the `--record-subsystem`s are written to.
+	#[clap(long, requires = "record-subsystem")]
+	pub overseer_recording: Option<PathBuf>,
 }
 
 #[allow(missing_docs)]
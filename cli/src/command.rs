@@ -21,7 +21,7 @@ use log::info;
 use sc_cli::{Role, RuntimeVersion, SubstrateCli};
 use service::{self, HeaderBackend, IdentifyVariant};
 use sp_core::crypto::Ss58AddressFormatRegistry;
-use std::net::ToSocketAddrs;
+use std::{collections::HashMap, net::ToSocketAddrs, time::Duration};
 
 pub use crate::{error::Error, service::BlockId};
 pub use polkadot_performance_test::PerfCheckError;
@@ -252,6 +252,108 @@ fn host_perf_check() -> Result<()> {
 	}
 }
 
+/// Parse a `<target>=<N>` argument with a positive `N`.
+fn parse_target_limit(arg: &str) -> Result<(String, u32)> {
+	let (target, n) = arg
+		.split_once('=')
+		.ok_or_else(|| Error::Other(format!("Expected `<target>=<N>`, got `{}`", arg)))?;
+	let n = n.parse::<u32>().ok().filter(|n| *n > 0).ok_or_else(|| {
+		Error::Other(format!("Expected a positive number for target `{}`, got `{}`", target, n))
+	})?;
+	Ok((target.to_owned(), n))
+}
+
+/// Passes the events of the profiling layer on to the JSON output of `gum`.
+struct JsonTraceHandler<W>(gum::json::JsonOutput<W>);
+
+impl<W, O> sc_tracing::TraceHandler for JsonTraceHandler<W>
+where
+	W: Fn() -> O + Send + Sync + 'static,
+	O: std::io::Write,
+{
+	fn handle_span(&self, _span: &sc_tracing::SpanDatum) {}
+
+	fn handle_event(&self, event: &sc_tracing::TraceEvent) {
+		use gum::json::Value;
+
+		let values = &event.values;
+		let fields = values
+			.bool_values
+			.iter()
+			.map(|(k, v)| (k.clone(), Value::from(*v)))
+			.chain(values.i64_values.iter().map(|(k, v)| (k.clone(), Value::from(*v))))
+			.chain(values.u64_values.iter().map(|(k, v)| (k.clone(), Value::from(*v))))
+			.chain(values.string_values.iter().map(|(k, v)| (k.clone(), Value::from(v.clone()))));
+		self.0.print(&event.level, &event.target, fields);
+	}
+}
+
+/// The profiling targets passing the events of the parachain subsystems to the JSON output.
+///
+/// The `parachain` filters given with `--log` apply, `parachain=info` otherwise.
+fn json_logging_targets(cli: &Cli) -> String {
+	let targets: Vec<_> = cli
+		.run
+		.base
+		.shared_params
+		.log
+		.iter()
+		.flat_map(|directives| directives.split(','))
+		.filter(|directive| directive.starts_with(gum::json::TARGET_PREFIX))
+		.collect();
+	if targets.is_empty() {
+		format!("{}=info", gum::json::TARGET_PREFIX)
+	} else {
+		targets.join(",")
+	}
+}
+
+/// The target of the log lines of the profiling layer.
+const PROFILING_LOG_TARGET: &str = "sc_tracing";
+
+/// The JSON output of the `gum` log events and the targets it prints, if requested.
+///
+/// The JSON output is a handler of the profiling layer, so it can't be combined with
+/// `--tracing-targets`. The profiling layer also logs every event it receives as text under
+/// [`PROFILING_LOG_TARGET`], which is turned off to not duplicate the regular log lines, so
+/// that target must not be configured with `--log` either.
+fn json_logging(cli: &Cli) -> Result<Option<(String, gum::json::JsonOutput)>> {
+	if !cli.run.log_json {
+		return Ok(None)
+	}
+
+	if cli.run.base.import_params.tracing_targets.is_some() {
+		return Err(Error::Other("`--log-json` can't be combined with `--tracing-targets`".into()))
+	}
+	let configures_profiling_log = cli
+		.run
+		.base
+		.shared_params
+		.log
+		.iter()
+		.flat_map(|directives| directives.split(','))
+		.any(|directive| directive.trim().starts_with(PROFILING_LOG_TARGET));
+	if configures_profiling_log {
+		return Err(Error::Other(format!(
+			"`--log-json` can't be combined with a `--log` filter for `{}`",
+			PROFILING_LOG_TARGET,
+		)))
+	}
+
+	let mut limits = HashMap::<String, gum::json::TargetLimit>::new();
+	for arg in &cli.run.log_json_sample {
+		let (target, n) = parse_target_limit(arg)?;
+		limits.entry(target).or_default().sample_one_in = n;
+	}
+	for arg in &cli.run.log_json_rate_limit {
+		let (target, n) = parse_target_limit(arg)?;
+		limits.entry(target).or_default().max_per_second = Some(n);
+	}
+
+	let output = gum::json::JsonOutput::new(gum::json::JsonConfig { limits });
+	Ok(Some((json_logging_targets(cli), output)))
+}
+
 /// Launch a node, accepting arguments just like a regular node,
 /// accepts an alternative overseer generator, to adjust behavior
 /// for integration tests as needed.
@@ -261,15 +363,33 @@ pub fn run_node(run: Cli, overseer_gen: impl service::OverseerGen) -> Result<()>
 }
 
 fn run_node_inner<F>(
-	cli: Cli,
+	mut cli: Cli,
 	overseer_gen: impl service::OverseerGen,
 	logger_hook: F,
 ) -> Result<()>
 where
 	F: FnOnce(&mut sc_cli::LoggerBuilder, &sc_service::Configuration),
 {
+	let json_logging = json_logging(&cli)?;
+	if json_logging.is_some() {
+		// The profiling layer carrying the JSON output logs all events it receives as text as
+		// well, these would duplicate the regular log lines. `json_logging` made sure neither
+		// the profiling layer nor its log target are configured by the user.
+		cli.run.base.shared_params.log.push(format!("{}=off", PROFILING_LOG_TARGET));
+	}
+	let logger_hook = move |logger: &mut sc_cli::LoggerBuilder,
+	                        config: &sc_service::Configuration| {
+		logger_hook(logger, config);
+		// Installed once, as a layer of the global subscriber only receiving the JSON targets.
+		if let Some((targets, output)) = json_logging {
+			logger
+				.with_profiling(sc_tracing::TracingReceiver::Log, targets)
+				.with_custom_profiling(Box::new(JsonTraceHandler(output)));
+		}
+	};
+
 	let runner = cli
-		.create_runner_with_logger_hook::<sc_cli::RunCmd, F>(&cli.run.base, logger_hook)
+		.create_runner_with_logger_hook::<sc_cli::RunCmd, _>(&cli.run.base, logger_hook)
 		.map_err(Error::from)?;
 	let chain_spec = &runner.config().chain_spec;
 
@@ -289,14 +409,19 @@ where
 		info!("----------------------------");
 	}
 
-	let jaeger_agent = if let Some(ref jaeger_agent) = cli.run.jaeger_agent {
-		Some(
+	let jaeger_backend = if let Some(ref jaeger_agent) = cli.run.jaeger_agent {
+		Some(service::JaegerBackend::Agent(
 			jaeger_agent
 				.to_socket_addrs()
 				.map_err(Error::AddressResolutionFailure)?
 				.next()
 				.ok_or_else(|| Error::AddressResolutionMissing)?,
-		)
+		))
+	} else if let Some(ref otlp_endpoint) = cli.run.otlp_endpoint {
+		Some(service::JaegerBackend::Otlp {
+			endpoint: otlp_endpoint.clone(),
+			protocol: cli.run.otlp_protocol.parse().map_err(Error::Other)?,
+		})
 	} else {
 		None
 	};
@@ -311,9 +436,28 @@ where
 				service::IsCollator::No,
 				grandpa_pause,
 				cli.run.beefy,
-				jaeger_agent,
+				jaeger_backend,
 				None,
 				false,
+				service::SubsystemsConfig {
+					stagnant_check_interval: cli
+						.run
+						.stagnant_check_interval
+						.map(|secs| Duration::from_secs(secs.get())),
+					stagnant_timeout: cli.run.stagnant_timeout.map(Duration::from_secs),
+					inherent_data_dump_dir: cli.run.inherent_data_dump_dir,
+					gossip_topology_file: cli.run.gossip_topology_file,
+					enable_v2_assignments: cli.run.enable_v2_assignments,
+					max_approval_coalesce_count: cli.run.max_approval_coalesce_count,
+					max_approval_coalesce_wait_ticks: cli.run.max_approval_coalesce_wait_ticks,
+					approval_lag_report_threshold: cli.run.approval_lag_report_threshold,
+					max_collation_fetches_per_para: cli.run.max_collation_fetches_per_para,
+					max_collation_fetches: cli.run.max_collation_fetches,
+					overseer_recording: cli
+						.run
+						.overseer_recording
+						.map(|path| (path, cli.run.record_subsystem)),
+				},
 				overseer_gen,
 			)
 			.map(|full| full.task_manager)
@@ -544,6 +688,26 @@ pub fn run() -> Result<()> {
 			host_perf_check()
 		},
 		Some(Subcommand::Key(cmd)) => Ok(cmd.run(&cli)?),
+		Some(Subcommand::UpgradeApprovalDb(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			Ok(runner.sync_run(|config| {
+				let summary = service::upgrade_approval_voting_db(&config.database, cmd.dry_run)?;
+				if summary.from == summary.to {
+					info!("Approval-voting DB is at the current version {}", summary.to);
+				} else {
+					info!(
+						"Approval-voting DB {} from version {} to {}: {} block entries, {} candidate entries",
+						if summary.dry_run { "can be upgraded" } else { "upgraded" },
+						summary.from,
+						summary.to,
+						summary.block_entries,
+						summary.candidate_entries,
+					);
+				}
+
+				Ok::<_, Error>(())
+			})?)
+		},
 		#[cfg(feature = "try-runtime")]
 		Some(Subcommand::TryRuntime(cmd)) => {
 			let runner = cli.create_runner(cmd)?;
@@ -613,3 +777,85 @@ pub fn run() -> Result<()> {
 	}
 	Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use clap::Parser;
+	use std::{
+		io,
+		sync::{Arc, Mutex},
+	};
+	use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, Registry};
+
+	#[derive(Clone, Default)]
+	struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+	impl io::Write for Buffer {
+		fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+			self.0.lock().unwrap().extend_from_slice(buf);
+			Ok(buf.len())
+		}
+
+		fn flush(&mut self) -> io::Result<()> {
+			Ok(())
+		}
+	}
+
+	#[test]
+	fn json_output_prints_parachain_events_of_the_profiling_layer() {
+		let buffer = Buffer::default();
+		let writer = buffer.clone();
+		let output = gum::json::JsonOutput::with_writer(Default::default(), move || writer.clone());
+		// `sync` passes the filter of the profiling layer, but is not a parachain target.
+		let layer = sc_tracing::ProfilingLayer::new_with_handler(
+			Box::new(JsonTraceHandler(output)),
+			"parachain=debug,sync=info",
+		);
+
+		{
+			let _guard = Registry::default().with(layer).set_default();
+			gum::debug!(target: "parachain::test", para_id = 2000_u32, "Printed");
+			gum::trace!(target: "parachain::test", "Below the level of the filter");
+			gum::info!(target: "sync", "Not a parachain target");
+		}
+
+		let raw = buffer.0.lock().unwrap().clone();
+		let lines: Vec<gum::json::Value> = String::from_utf8(raw)
+			.unwrap()
+			.lines()
+			.map(|line| line.parse().unwrap())
+			.collect();
+		assert_eq!(lines.len(), 1);
+		assert_eq!(lines[0]["target"], "parachain::test");
+		assert_eq!(lines[0]["level"], "DEBUG");
+		assert_eq!(lines[0]["message"], "Printed");
+		assert_eq!(lines[0]["para_id"], 2000);
+	}
+
+	#[test]
+	fn json_logging_rejects_conflicting_flags() {
+		let parse = |args: &[&str]| {
+			let mut argv = vec!["polkadot", "--log-json"];
+			argv.extend_from_slice(args);
+			let cli = Cli::try_parse_from(argv).unwrap();
+			json_logging(&cli).map(|output| output.map(|(targets, _)| targets))
+		};
+
+		assert_eq!(parse(&[]).unwrap(), Some("parachain=info".to_owned()));
+		assert_eq!(
+			parse(&["--log", "parachain=debug,sync=trace"]).unwrap(),
+			Some("parachain=debug".to_owned()),
+		);
+		assert!(parse(&["--tracing-targets", "parachain=debug"]).is_err());
+		assert!(parse(&["--log", "info,sc_tracing=debug"]).is_err());
+	}
+
+	#[test]
+	fn zero_stagnant_check_interval_is_rejected() {
+		assert!(Cli::try_parse_from(["polkadot", "--stagnant-check-interval", "0"]).is_err());
+
+		let cli = Cli::try_parse_from(["polkadot", "--stagnant-check-interval", "5"]).unwrap();
+		assert_eq!(cli.run.stagnant_check_interval.map(|secs| secs.get()), Some(5));
+	}
+}
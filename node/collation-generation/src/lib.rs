@@ -15,12 +15,17 @@
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
 //! The collation generation subsystem is the interface between polkadot and the collators.
+//!
+//! A single collator node may collate for several paras, each with its own
+//! [`CollationGenerationConfig`]. The PoVs of each para are compressed according to its
+//! [`PoVCompressionConfig`](polkadot_node_primitives::PoVCompressionConfig), with its
+//! dictionary while that is registered on-chain.
 
 #![deny(missing_docs)]
 
 use futures::{channel::mpsc, future::FutureExt, join, select, sink::SinkExt, stream::StreamExt};
 use parity_scale_codec::Encode;
-use polkadot_node_primitives::{AvailableData, CollationGenerationConfig, PoV};
+use polkadot_node_primitives::{AvailableData, CollationGenerationConfig, PoV, PoVDictionary};
 use polkadot_node_subsystem::{
 	messages::{AllMessages, CollationGenerationMessage, CollatorProtocolMessage},
 	overseer, ActiveLeavesUpdate, FromOverseer, OverseerSignal, SpawnedSubsystem, SubsystemContext,
@@ -28,8 +33,8 @@ use polkadot_node_subsystem::{
 };
 use polkadot_node_subsystem_util::{
 	metrics::{self, prometheus},
-	request_availability_cores, request_persisted_validation_data, request_validation_code,
-	request_validation_code_hash, request_validators,
+	request_availability_cores, request_persisted_validation_data, request_pov_dictionary,
+	request_validation_code, request_validation_code_hash, request_validators,
 };
 use polkadot_primitives::v2::{
 	collator_signature_payload, CandidateCommitments, CandidateDescriptor, CandidateReceipt,
@@ -37,7 +42,7 @@ use polkadot_primitives::v2::{
 	ValidationCodeHash,
 };
 use sp_core::crypto::Pair;
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 mod error;
 
@@ -46,16 +51,19 @@ mod tests;
 
 const LOG_TARGET: &'static str = "parachain::collation-generation";
 
+/// The collation generation configurations, by the para they collate for.
+type Configs = HashMap<ParaId, Arc<CollationGenerationConfig>>;
+
 /// Collation Generation Subsystem
 pub struct CollationGenerationSubsystem {
-	config: Option<Arc<CollationGenerationConfig>>,
+	configs: Configs,
 	metrics: Metrics,
 }
 
 impl CollationGenerationSubsystem {
 	/// Create a new instance of the `CollationGenerationSubsystem`.
 	pub fn new(metrics: Metrics) -> Self {
-		Self { config: None, metrics }
+		Self { configs: Configs::new(), metrics }
 	}
 
 	/// Run this subsystem
@@ -118,10 +126,10 @@ impl CollationGenerationSubsystem {
 				..
 			}))) => {
 				// follow the procedure from the guide
-				if let Some(config) = &self.config {
+				if !self.configs.is_empty() {
 					let metrics = self.metrics.clone();
 					if let Err(err) = handle_new_activations(
-						config.clone(),
+						&self.configs,
 						activated.into_iter().map(|v| v.hash),
 						ctx,
 						metrics,
@@ -139,10 +147,14 @@ impl CollationGenerationSubsystem {
 			Ok(FromOverseer::Communication {
 				msg: CollationGenerationMessage::Initialize(config),
 			}) => {
-				if self.config.is_some() {
-					gum::error!(target: LOG_TARGET, "double initialization");
+				if self.configs.contains_key(&config.para_id) {
+					gum::error!(
+						target: LOG_TARGET,
+						para_id = %config.para_id,
+						"double initialization",
+					);
 				} else {
-					self.config = Some(Arc::new(config));
+					self.configs.insert(config.para_id, Arc::new(config));
 				}
 				false
 			},
@@ -177,7 +189,7 @@ where
 }
 
 async fn handle_new_activations<Context: SubsystemContext>(
-	config: Arc<CollationGenerationConfig>,
+	configs: &Configs,
 	activated: impl IntoIterator<Item = Hash>,
 	ctx: &mut Context,
 	metrics: Metrics,
@@ -225,17 +237,19 @@ async fn handle_new_activations<Context: SubsystemContext>(
 				},
 			};
 
-			if scheduled_core.para_id != config.para_id {
-				gum::trace!(
-					target: LOG_TARGET,
-					core_idx = %core_idx,
-					relay_parent = ?relay_parent,
-					our_para = %config.para_id,
-					their_para = %scheduled_core.para_id,
-					"core is not assigned to our para. Keep going.",
-				);
-				continue
-			}
+			let config = match configs.get(&scheduled_core.para_id) {
+				Some(config) => config,
+				None => {
+					gum::trace!(
+						target: LOG_TARGET,
+						core_idx = %core_idx,
+						relay_parent = ?relay_parent,
+						their_para = %scheduled_core.para_id,
+						"core is not assigned to any of our paras. Keep going.",
+					);
+					continue
+				},
+			};
 
 			// we get validation data and validation code synchronously for each core instead of
 			// within the subtask loop, because we have only a single mutable handle to the
@@ -256,8 +270,7 @@ async fn handle_new_activations<Context: SubsystemContext>(
 						target: LOG_TARGET,
 						core_idx = %core_idx,
 						relay_parent = ?relay_parent,
-						our_para = %config.para_id,
-						their_para = %scheduled_core.para_id,
+						para_id = %scheduled_core.para_id,
 						"validation data is not available",
 					);
 					continue
@@ -278,14 +291,26 @@ async fn handle_new_activations<Context: SubsystemContext>(
 						target: LOG_TARGET,
 						core_idx = %core_idx,
 						relay_parent = ?relay_parent,
-						our_para = %config.para_id,
-						their_para = %scheduled_core.para_id,
+						para_id = %scheduled_core.para_id,
 						"validation code hash is not found.",
 					);
 					continue
 				},
 			};
 
+			// Only compress with the dictionary if validators are able to get hold of it.
+			let use_dictionary = match config.pov_dictionary {
+				Some(ref dictionary) =>
+					pov_dictionary_registered(
+						relay_parent,
+						scheduled_core.para_id,
+						dictionary,
+						ctx.sender(),
+					)
+					.await?,
+				None => false,
+			};
+
 			let task_config = config.clone();
 			let mut task_sender = sender.clone();
 			let metrics = metrics.clone();
@@ -293,23 +318,33 @@ async fn handle_new_activations<Context: SubsystemContext>(
 				"collation-builder",
 				Box::pin(async move {
 					let persisted_validation_data_hash = validation_data.hash();
+					let parent_head_data_hash = validation_data.parent_head.hash();
 
-					let (collation, result_sender) =
-						match (task_config.collator)(relay_parent, &validation_data).await {
-							Some(collation) => collation.into_inner(),
-							None => {
-								gum::debug!(
-									target: LOG_TARGET,
-									para_id = %scheduled_core.para_id,
-									"collator returned no collation on collate",
-								);
-								return
-							},
-						};
+					let collate_timer = metrics.time_collate(scheduled_core.para_id);
+					let collation = (task_config.collator)(relay_parent, &validation_data).await;
+					drop(collate_timer);
+
+					let (collation, result_sender) = match collation {
+						Some(collation) => collation.into_inner(),
+						None => {
+							gum::debug!(
+								target: LOG_TARGET,
+								para_id = %scheduled_core.para_id,
+								"collator returned no collation on collate",
+							);
+							metrics
+								.on_collation_not_generated(scheduled_core.para_id, "no_collation");
+							return
+						},
+					};
 
 					// Apply compression to the block data.
 					let pov = {
-						let pov = collation.proof_of_validity.into_compressed();
+						let dictionary =
+							task_config.pov_dictionary.as_ref().filter(|_| use_dictionary);
+						let pov = collation
+							.proof_of_validity
+							.into_compressed_with(&task_config.pov_compression, dictionary);
 						let encoded_size = pov.encoded_size();
 
 						// As long as `POV_BOMB_LIMIT` is at least `max_pov_size`, this ensures
@@ -325,6 +360,10 @@ async fn handle_new_activations<Context: SubsystemContext>(
 								max_size = validation_data.max_pov_size,
 								"PoV exceeded maximum size"
 							);
+							metrics.on_collation_not_generated(
+								scheduled_core.para_id,
+								"pov_too_large",
+							);
 
 							return
 						}
@@ -352,6 +391,10 @@ async fn handle_new_activations<Context: SubsystemContext>(
 									err = ?err,
 									"failed to calculate erasure root",
 								);
+								metrics.on_collation_not_generated(
+									scheduled_core.para_id,
+									"erasure_root",
+								);
 								return
 							},
 						};
@@ -388,11 +431,16 @@ async fn handle_new_activations<Context: SubsystemContext>(
 						para_id = %scheduled_core.para_id,
 						"candidate is generated",
 					);
-					metrics.on_collation_generated();
+					metrics.on_collation_generated(scheduled_core.para_id);
 
 					if let Err(err) = task_sender
 						.send(AllMessages::CollatorProtocol(
-							CollatorProtocolMessage::DistributeCollation(ccr, pov, result_sender),
+							CollatorProtocolMessage::DistributeCollation(
+								ccr,
+								parent_head_data_hash,
+								pov,
+								result_sender,
+							),
 						))
 						.await
 					{
@@ -440,6 +488,25 @@ async fn obtain_current_validation_code_hash(
 	}
 }
 
+async fn pov_dictionary_registered(
+	relay_parent: Hash,
+	para_id: ParaId,
+	dictionary: &PoVDictionary,
+	sender: &mut impl SubsystemSender,
+) -> Result<bool, crate::error::Error> {
+	use polkadot_node_subsystem::RuntimeApiError;
+
+	match request_pov_dictionary(relay_parent, para_id, dictionary.hash(), sender)
+		.await
+		.await?
+	{
+		Ok(registered) => Ok(registered.is_some()),
+		// Dictionaries can't be registered with runtimes lacking the staging API.
+		Err(RuntimeApiError::NotSupported { .. }) => Ok(false),
+		Err(e @ RuntimeApiError::Execution { .. }) => Err(e.into()),
+	}
+}
+
 fn erasure_root(
 	n_validators: usize,
 	persisted_validation: PersistedValidationData,
@@ -454,7 +521,9 @@ fn erasure_root(
 
 #[derive(Clone)]
 struct MetricsInner {
-	collations_generated_total: prometheus::Counter<prometheus::U64>,
+	collations_generated_total: prometheus::CounterVec<prometheus::U64>,
+	collations_not_generated_total: prometheus::CounterVec<prometheus::U64>,
+	collate_per_para: prometheus::HistogramVec,
 	new_activations_overall: prometheus::Histogram,
 	new_activations_per_relay_parent: prometheus::Histogram,
 	new_activations_per_availability_core: prometheus::Histogram,
@@ -465,12 +534,37 @@ struct MetricsInner {
 pub struct Metrics(Option<MetricsInner>);
 
 impl Metrics {
-	fn on_collation_generated(&self) {
+	fn on_collation_generated(&self, para_id: ParaId) {
+		if let Some(metrics) = &self.0 {
+			metrics
+				.collations_generated_total
+				.with_label_values(&[&para_id.to_string()])
+				.inc();
+		}
+	}
+
+	fn on_collation_not_generated(&self, para_id: ParaId, reason: &'static str) {
 		if let Some(metrics) = &self.0 {
-			metrics.collations_generated_total.inc();
+			metrics
+				.collations_not_generated_total
+				.with_label_values(&[&para_id.to_string(), reason])
+				.inc();
 		}
 	}
 
+	/// Provide a timer for the collation function of a para which updates on drop.
+	fn time_collate(
+		&self,
+		para_id: ParaId,
+	) -> Option<metrics::prometheus::prometheus::HistogramTimer> {
+		self.0.as_ref().map(|metrics| {
+			metrics
+				.collate_per_para
+				.with_label_values(&[&para_id.to_string()])
+				.start_timer()
+		})
+	}
+
 	/// Provide a timer for new activations which updates on drop.
 	fn time_new_activations(&self) -> Option<metrics::prometheus::prometheus::HistogramTimer> {
 		self.0.as_ref().map(|metrics| metrics.new_activations_overall.start_timer())
@@ -499,9 +593,32 @@ impl metrics::Metrics for Metrics {
 	fn try_register(registry: &prometheus::Registry) -> Result<Self, prometheus::PrometheusError> {
 		let metrics = MetricsInner {
 			collations_generated_total: prometheus::register(
-				prometheus::Counter::new(
-					"polkadot_parachain_collations_generated_total",
-					"Number of collations generated."
+				prometheus::CounterVec::new(
+					prometheus::Opts::new(
+						"polkadot_parachain_collations_generated_total",
+						"Number of collations generated."
+					),
+					&["para_id"],
+				)?,
+				registry,
+			)?,
+			collations_not_generated_total: prometheus::register(
+				prometheus::CounterVec::new(
+					prometheus::Opts::new(
+						"polkadot_parachain_collations_not_generated_total",
+						"Number of collations which weren't generated for a scheduled core, by reason."
+					),
+					&["para_id", "reason"],
+				)?,
+				registry,
+			)?,
+			collate_per_para: prometheus::register(
+				prometheus::HistogramVec::new(
+					prometheus::HistogramOpts::new(
+						"polkadot_parachain_collation_generation_per_para_collate",
+						"Time spent within the collation function of a para",
+					),
+					&["para_id"],
 				)?,
 				registry,
 			)?,
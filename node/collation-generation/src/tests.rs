@@ -23,7 +23,8 @@ mod handle_new_activations {
 		Future,
 	};
 	use polkadot_node_primitives::{
-		BlockData, Collation, CollationResult, MaybeCompressedPoV, PoV,
+		compression::pov_dictionary_hash_of, BlockData, Collation, CollationResult,
+		MaybeCompressedPoV, PoV,
 	};
 	use polkadot_node_subsystem::{
 		errors::RuntimeApiError,
@@ -80,9 +81,15 @@ mod handle_new_activations {
 			key: CollatorPair::generate().0,
 			collator: Box::new(|_: Hash, _vd: &PersistedValidationData| TestCollator.boxed()),
 			para_id: para_id.into(),
+			pov_compression: Default::default(),
+			pov_dictionary: None,
 		})
 	}
 
+	fn configs(configs: impl IntoIterator<Item = Arc<CollationGenerationConfig>>) -> Configs {
+		configs.into_iter().map(|config| (config.para_id, config)).collect()
+	}
+
 	fn scheduled_core_for<Id: Into<ParaId>>(para_id: Id) -> ScheduledCore {
 		ScheduledCore { para_id: para_id.into(), collator: None }
 	}
@@ -116,7 +123,7 @@ mod handle_new_activations {
 		let subsystem_activated_hashes = activated_hashes.clone();
 		subsystem_test_harness(overseer, |mut ctx| async move {
 			handle_new_activations(
-				test_config(123u32),
+				&configs([test_config(123u32)]),
 				subsystem_activated_hashes,
 				&mut ctx,
 				Metrics(None),
@@ -193,9 +200,15 @@ mod handle_new_activations {
 		let (tx, _rx) = mpsc::channel(0);
 
 		subsystem_test_harness(overseer, |mut ctx| async move {
-			handle_new_activations(test_config(16), activated_hashes, &mut ctx, Metrics(None), &tx)
-				.await
-				.unwrap();
+			handle_new_activations(
+				&configs([test_config(16)]),
+				activated_hashes,
+				&mut ctx,
+				Metrics(None),
+				&tx,
+			)
+			.await
+			.unwrap();
 		});
 
 		let requested_validation_data = Arc::try_unwrap(requested_validation_data)
@@ -272,7 +285,7 @@ mod handle_new_activations {
 		};
 
 		let config = test_config(16);
-		let subsystem_config = config.clone();
+		let subsystem_configs = configs([config.clone()]);
 
 		let (tx, rx) = mpsc::channel(0);
 
@@ -281,7 +294,7 @@ mod handle_new_activations {
 		let subsystem_sent_messages = sent_messages.clone();
 		subsystem_test_harness(overseer, |mut ctx| async move {
 			handle_new_activations(
-				subsystem_config,
+				&subsystem_configs,
 				activated_hashes,
 				&mut ctx,
 				Metrics(None),
@@ -331,9 +344,12 @@ mod handle_new_activations {
 		match &sent_messages[0] {
 			AllMessages::CollatorProtocol(CollatorProtocolMessage::DistributeCollation(
 				CandidateReceipt { descriptor, .. },
+				parent_head_data_hash,
 				_pov,
 				..,
 			)) => {
+				assert_eq!(*parent_head_data_hash, test_validation_data().parent_head.hash());
+
 				// signature generation is non-deterministic, so we can't just assert that the
 				// expected descriptor is correct. What we can do is validate that the produced
 				// descriptor has a valid signature, then just copy in the generated signature
@@ -439,7 +455,7 @@ mod handle_new_activations {
 		};
 
 		let config = test_config(16u32);
-		let subsystem_config = config.clone();
+		let subsystem_configs = configs([config.clone()]);
 
 		let (tx, rx) = mpsc::channel(0);
 
@@ -448,7 +464,7 @@ mod handle_new_activations {
 		let subsystem_sent_messages = sent_messages.clone();
 		subsystem_test_harness(overseer, |mut ctx| async move {
 			handle_new_activations(
-				subsystem_config,
+				&subsystem_configs,
 				activated_hashes,
 				&mut ctx,
 				Metrics(None),
@@ -472,6 +488,7 @@ mod handle_new_activations {
 		match &sent_messages[0] {
 			AllMessages::CollatorProtocol(CollatorProtocolMessage::DistributeCollation(
 				CandidateReceipt { descriptor, .. },
+				_parent_head_data_hash,
 				_pov,
 				..,
 			)) => {
@@ -480,4 +497,214 @@ mod handle_new_activations {
 			_ => panic!("received wrong message type"),
 		}
 	}
+
+	#[test]
+	fn dispatches_cores_to_the_config_of_their_para() {
+		let activated_hashes: Vec<Hash> = vec![Hash::repeat_byte(1), Hash::repeat_byte(4)];
+
+		let overseer = |mut handle: TestSubsystemContextHandle<CollationGenerationMessage>| async move {
+			loop {
+				match handle.try_recv().await {
+					None => break,
+					Some(AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+						hash,
+						RuntimeApiRequest::AvailabilityCores(tx),
+					))) => {
+						tx.send(Ok(vec![
+							CoreState::Scheduled(scheduled_core_for(
+								(hash.as_fixed_bytes()[0] * 4) as u32,
+							)),
+							CoreState::Scheduled(scheduled_core_for(
+								(hash.as_fixed_bytes()[0] * 5) as u32,
+							)),
+						]))
+						.unwrap();
+					},
+					Some(AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+						_hash,
+						RuntimeApiRequest::PersistedValidationData(
+							_para_id,
+							_occupied_core_assumption,
+							tx,
+						),
+					))) => {
+						tx.send(Ok(Some(test_validation_data()))).unwrap();
+					},
+					Some(AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+						_hash,
+						RuntimeApiRequest::Validators(tx),
+					))) => {
+						tx.send(Ok(vec![dummy_validator(); 3])).unwrap();
+					},
+					Some(AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+						_hash,
+						RuntimeApiRequest::ValidationCodeHash(
+							_para_id,
+							OccupiedCoreAssumption::Free,
+							tx,
+						),
+					))) => {
+						tx.send(Ok(Some(ValidationCode(vec![1, 2, 3]).hash()))).unwrap();
+					},
+					Some(msg) => {
+						panic!("didn't expect any other overseer requests; got {:?}", msg)
+					},
+				}
+			}
+		};
+
+		// hash 4 schedules paras 16 and 20, hash 1 schedules paras 4 and 5.
+		let our_configs = configs([test_config(16), test_config(20), test_config(100)]);
+		let subsystem_configs = our_configs.clone();
+
+		let (tx, rx) = mpsc::channel(0);
+
+		let sent_messages = Arc::new(Mutex::new(Vec::new()));
+		let subsystem_sent_messages = sent_messages.clone();
+		subsystem_test_harness(overseer, |mut ctx| async move {
+			handle_new_activations(
+				&subsystem_configs,
+				activated_hashes,
+				&mut ctx,
+				Metrics(None),
+				&tx,
+			)
+			.await
+			.unwrap();
+
+			std::mem::drop(tx);
+
+			*subsystem_sent_messages.lock().await = rx.collect().await;
+		});
+
+		let sent_messages = Arc::try_unwrap(sent_messages)
+			.expect("subsystem should have shut down by now")
+			.into_inner();
+
+		let mut collators = sent_messages
+			.iter()
+			.map(|msg| match msg {
+				AllMessages::CollatorProtocol(CollatorProtocolMessage::DistributeCollation(
+					CandidateReceipt { descriptor, .. },
+					..,
+				)) => {
+					assert_eq!(descriptor.relay_parent, Hash::repeat_byte(4));
+					(descriptor.para_id, descriptor.collator.clone())
+				},
+				_ => panic!("received wrong message type"),
+			})
+			.collect::<Vec<_>>();
+		collators.sort_by_key(|(para_id, _)| *para_id);
+
+		assert_eq!(
+			collators,
+			vec![
+				(16.into(), our_configs[&16.into()].key.public()),
+				(20.into(), our_configs[&20.into()].key.public()),
+			],
+		);
+	}
+
+	#[test]
+	fn compresses_with_dictionary_only_while_registered() {
+		let registered_at = Hash::repeat_byte(1);
+		let activated_hashes: Vec<Hash> = vec![registered_at, Hash::repeat_byte(2)];
+
+		let dictionary = PoVDictionary::new(vec![7; 64]);
+		let config = Arc::new(CollationGenerationConfig {
+			key: CollatorPair::generate().0,
+			collator: Box::new(|_: Hash, _vd: &PersistedValidationData| TestCollator.boxed()),
+			para_id: 16.into(),
+			pov_compression: Default::default(),
+			pov_dictionary: Some(dictionary.clone()),
+		});
+
+		let expected_hash = dictionary.hash();
+		let overseer = |mut handle: TestSubsystemContextHandle<CollationGenerationMessage>| async move {
+			loop {
+				match handle.try_recv().await {
+					None => break,
+					Some(AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+						_hash,
+						RuntimeApiRequest::AvailabilityCores(tx),
+					))) => {
+						tx.send(Ok(vec![CoreState::Scheduled(scheduled_core_for(16))])).unwrap();
+					},
+					Some(AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+						_hash,
+						RuntimeApiRequest::PersistedValidationData(_, _, tx),
+					))) => {
+						tx.send(Ok(Some(test_validation_data()))).unwrap();
+					},
+					Some(AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+						_hash,
+						RuntimeApiRequest::Validators(tx),
+					))) => {
+						tx.send(Ok(vec![dummy_validator(); 3])).unwrap();
+					},
+					Some(AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+						_hash,
+						RuntimeApiRequest::ValidationCodeHash(_, _, tx),
+					))) => {
+						tx.send(Ok(Some(ValidationCode(vec![1, 2, 3]).hash()))).unwrap();
+					},
+					Some(AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+						hash,
+						RuntimeApiRequest::StagingPoVDictionary(para_id, dictionary_hash, tx),
+					))) => {
+						assert_eq!(para_id, 16.into());
+						assert_eq!(dictionary_hash, expected_hash);
+						let registered = Some(vec![7; 64]).filter(|_| hash == registered_at);
+						tx.send(Ok(registered)).unwrap();
+					},
+					Some(msg) => {
+						panic!("didn't expect any other overseer requests; got {:?}", msg)
+					},
+				}
+			}
+		};
+
+		let subsystem_configs = configs([config]);
+		let (tx, rx) = mpsc::channel(0);
+		let sent_messages = Arc::new(Mutex::new(Vec::new()));
+		let subsystem_sent_messages = sent_messages.clone();
+		subsystem_test_harness(overseer, |mut ctx| async move {
+			handle_new_activations(
+				&subsystem_configs,
+				activated_hashes,
+				&mut ctx,
+				Metrics(None),
+				&tx,
+			)
+			.await
+			.unwrap();
+
+			std::mem::drop(tx);
+
+			*subsystem_sent_messages.lock().await = rx.collect().await;
+		});
+
+		let sent_messages = Arc::try_unwrap(sent_messages)
+			.expect("subsystem should have shut down by now")
+			.into_inner();
+
+		let mut dictionaries: Vec<_> = sent_messages
+			.into_iter()
+			.map(|msg| match msg {
+				AllMessages::CollatorProtocol(CollatorProtocolMessage::DistributeCollation(
+					CandidateReceipt { descriptor, .. },
+					_,
+					pov,
+					_,
+				)) => (descriptor.relay_parent, pov_dictionary_hash_of(&pov.block_data.0)),
+				_ => panic!("received wrong message type"),
+			})
+			.collect();
+		dictionaries.sort();
+
+		assert_eq!(
+			dictionaries,
+			vec![(registered_at, Some(expected_hash)), (Hash::repeat_byte(2), None)],
+		);
+	}
 }
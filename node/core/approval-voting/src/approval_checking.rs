@@ -17,11 +17,13 @@
 //! Utilities for checking whether a candidate has been approved under a given block.
 
 use bitvec::{order::Lsb0 as BitOrderLsb0, slice::BitSlice};
-use polkadot_node_primitives::approval::DelayTranche;
-use polkadot_primitives::v2::ValidatorIndex;
+use polkadot_node_primitives::approval::{CandidateBitfield, DelayTranche};
+use polkadot_primitives::v2::{
+	CandidateHash, CandidateIndex, ValidDisputeStatementKind, ValidatorIndex,
+};
 
 use crate::{
-	persisted_entries::{ApprovalEntry, CandidateEntry, TrancheEntry},
+	persisted_entries::{ApprovalEntry, BlockEntry, CandidateEntry, TrancheEntry},
 	time::Tick,
 };
 
@@ -454,17 +456,80 @@ pub fn tranches_to_approve(
 		.expect("the underlying iterator is infinite, starts at 0, and never exits early before tranche 1; qed")
 }
 
+/// The assigned validators of an approval entry which haven't approved the candidate, although
+/// their assignment is at least `no_show_duration` ticks old at `tick_now`.
+///
+/// Only assignments up to and including `max_tranche` are considered, if given. Validators
+/// are returned in ascending order of their tranche.
+pub fn no_show_validators(
+	approval_entry: &ApprovalEntry,
+	approvals: &BitSlice<u8, BitOrderLsb0>,
+	max_tranche: Option<DelayTranche>,
+	block_tick: Tick,
+	no_show_duration: Tick,
+	tick_now: Tick,
+) -> Vec<ValidatorIndex> {
+	approval_entry
+		.tranches()
+		.iter()
+		.take_while(|t| max_tranche.map_or(true, |max| t.tranche() <= max))
+		.flat_map(|t| t.assignments())
+		.filter(|(v_index, tick)| {
+			let has_approved = match approvals.get(v_index.0 as usize) {
+				Some(approved) => *approved,
+				None => return false,
+			};
+
+			!has_approved && tick.max(&block_tick) + no_show_duration <= tick_now
+		})
+		.map(|(v_index, _)| *v_index)
+		.collect()
+}
+
+/// Resolve the candidates of a block which are covered by an approval vote, in ascending
+/// order of their index in the block. This is also the order in which the candidate hashes
+/// appear in the signing payload of a vote covering multiple candidates.
+///
+/// Fails with the first claimed candidate index which isn't part of the block.
+pub fn approved_candidates(
+	block_entry: &BlockEntry,
+	candidate_indices: &CandidateBitfield,
+) -> Result<Vec<(CandidateIndex, CandidateHash)>, CandidateIndex> {
+	candidate_indices
+		.iter_ones()
+		.map(|i| {
+			block_entry
+				.candidate(i)
+				.map(|(_, candidate_hash)| (i as CandidateIndex, *candidate_hash))
+				.ok_or(i as CandidateIndex)
+		})
+		.collect()
+}
+
+/// The kind of dispute statement an approval vote over the given candidates is signed as.
+///
+/// Votes covering a single candidate use the plain approval payload, so they can still be
+/// understood by nodes which don't know about coalesced votes.
+pub fn approval_statement_kind(candidate_hashes: &[CandidateHash]) -> ValidDisputeStatementKind {
+	match candidate_hashes {
+		[_] => ValidDisputeStatementKind::ApprovalChecking,
+		_ =>
+			ValidDisputeStatementKind::ApprovalCheckingMultipleCandidates(candidate_hashes.to_vec()),
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
 	use crate::{approval_db, BTreeMap};
 	use ::test_helpers::{dummy_candidate_receipt, dummy_hash};
 	use bitvec::{bitvec, order::Lsb0 as BitOrderLsb0, vec::BitVec};
-	use polkadot_primitives::v2::GroupIndex;
+	use polkadot_primitives::v2::{CoreIndex, GroupIndex, Hash};
+	use sp_consensus_slots::Slot;
 
 	#[test]
 	fn pending_is_not_approved() {
-		let candidate = approval_db::v1::CandidateEntry {
+		let candidate = approval_db::v2::CandidateEntry {
 			candidate: dummy_candidate_receipt(dummy_hash()),
 			session: 0,
 			block_assignments: BTreeMap::default(),
@@ -472,7 +537,7 @@ mod tests {
 		}
 		.into();
 
-		let approval_entry = approval_db::v1::ApprovalEntry {
+		let approval_entry = approval_db::v2::ApprovalEntry {
 			tranches: Vec::new(),
 			assignments: BitVec::default(),
 			our_assignment: None,
@@ -497,7 +562,7 @@ mod tests {
 
 	#[test]
 	fn exact_takes_only_assignments_up_to() {
-		let mut candidate: CandidateEntry = approval_db::v1::CandidateEntry {
+		let mut candidate: CandidateEntry = approval_db::v2::CandidateEntry {
 			candidate: dummy_candidate_receipt(dummy_hash()),
 			session: 0,
 			block_assignments: BTreeMap::default(),
@@ -509,17 +574,17 @@ mod tests {
 			candidate.mark_approval(ValidatorIndex(i));
 		}
 
-		let approval_entry = approval_db::v1::ApprovalEntry {
+		let approval_entry = approval_db::v2::ApprovalEntry {
 			tranches: vec![
-				approval_db::v1::TrancheEntry {
+				approval_db::v2::TrancheEntry {
 					tranche: 0,
 					assignments: (0..2).map(|i| (ValidatorIndex(i), 0.into())).collect(),
 				},
-				approval_db::v1::TrancheEntry {
+				approval_db::v2::TrancheEntry {
 					tranche: 1,
 					assignments: (2..5).map(|i| (ValidatorIndex(i), 1.into())).collect(),
 				},
-				approval_db::v1::TrancheEntry {
+				approval_db::v2::TrancheEntry {
 					tranche: 2,
 					assignments: (5..10).map(|i| (ValidatorIndex(i), 0.into())).collect(),
 				},
@@ -569,7 +634,7 @@ mod tests {
 
 	#[test]
 	fn one_honest_node_always_approves() {
-		let mut candidate: CandidateEntry = approval_db::v1::CandidateEntry {
+		let mut candidate: CandidateEntry = approval_db::v2::CandidateEntry {
 			candidate: dummy_candidate_receipt(dummy_hash()),
 			session: 0,
 			block_assignments: BTreeMap::default(),
@@ -581,17 +646,17 @@ mod tests {
 			candidate.mark_approval(ValidatorIndex(i));
 		}
 
-		let approval_entry = approval_db::v1::ApprovalEntry {
+		let approval_entry = approval_db::v2::ApprovalEntry {
 			tranches: vec![
-				approval_db::v1::TrancheEntry {
+				approval_db::v2::TrancheEntry {
 					tranche: 0,
 					assignments: (0..4).map(|i| (ValidatorIndex(i), 0.into())).collect(),
 				},
-				approval_db::v1::TrancheEntry {
+				approval_db::v2::TrancheEntry {
 					tranche: 1,
 					assignments: (4..6).map(|i| (ValidatorIndex(i), 1.into())).collect(),
 				},
-				approval_db::v1::TrancheEntry {
+				approval_db::v2::TrancheEntry {
 					tranche: 2,
 					assignments: (6..10).map(|i| (ValidatorIndex(i), 0.into())).collect(),
 				},
@@ -647,7 +712,7 @@ mod tests {
 		let no_show_duration = 10;
 		let needed_approvals = 4;
 
-		let mut approval_entry: ApprovalEntry = approval_db::v1::ApprovalEntry {
+		let mut approval_entry: ApprovalEntry = approval_db::v2::ApprovalEntry {
 			tranches: Vec::new(),
 			assignments: bitvec![u8, BitOrderLsb0; 0; 5],
 			our_assignment: None,
@@ -691,7 +756,7 @@ mod tests {
 		let no_show_duration = 10;
 		let needed_approvals = 4;
 
-		let mut approval_entry: ApprovalEntry = approval_db::v1::ApprovalEntry {
+		let mut approval_entry: ApprovalEntry = approval_db::v2::ApprovalEntry {
 			tranches: Vec::new(),
 			assignments: bitvec![u8, BitOrderLsb0; 0; 10],
 			our_assignment: None,
@@ -731,7 +796,7 @@ mod tests {
 		let no_show_duration = 10;
 		let needed_approvals = 4;
 
-		let mut approval_entry: ApprovalEntry = approval_db::v1::ApprovalEntry {
+		let mut approval_entry: ApprovalEntry = approval_db::v2::ApprovalEntry {
 			tranches: Vec::new(),
 			assignments: bitvec![u8, BitOrderLsb0; 0; 10],
 			our_assignment: None,
@@ -776,7 +841,7 @@ mod tests {
 		let needed_approvals = 4;
 		let n_validators = 8;
 
-		let mut approval_entry: ApprovalEntry = approval_db::v1::ApprovalEntry {
+		let mut approval_entry: ApprovalEntry = approval_db::v2::ApprovalEntry {
 			tranches: Vec::new(),
 			assignments: bitvec![u8, BitOrderLsb0; 0; n_validators],
 			our_assignment: None,
@@ -843,7 +908,7 @@ mod tests {
 		let needed_approvals = 4;
 		let n_validators = 8;
 
-		let mut approval_entry: ApprovalEntry = approval_db::v1::ApprovalEntry {
+		let mut approval_entry: ApprovalEntry = approval_db::v2::ApprovalEntry {
 			tranches: Vec::new(),
 			assignments: bitvec![u8, BitOrderLsb0; 0; n_validators],
 			our_assignment: None,
@@ -934,7 +999,7 @@ mod tests {
 		let needed_approvals = 4;
 		let n_validators = 8;
 
-		let mut approval_entry: ApprovalEntry = approval_db::v1::ApprovalEntry {
+		let mut approval_entry: ApprovalEntry = approval_db::v2::ApprovalEntry {
 			tranches: Vec::new(),
 			assignments: bitvec![u8, BitOrderLsb0; 0; n_validators],
 			our_assignment: None,
@@ -1029,7 +1094,7 @@ mod tests {
 		let no_show_duration = 10;
 		let needed_approvals = 3;
 
-		let mut candidate: CandidateEntry = approval_db::v1::CandidateEntry {
+		let mut candidate: CandidateEntry = approval_db::v2::CandidateEntry {
 			candidate: dummy_candidate_receipt(dummy_hash()),
 			session: 0,
 			block_assignments: BTreeMap::default(),
@@ -1041,10 +1106,10 @@ mod tests {
 			candidate.mark_approval(ValidatorIndex(i));
 		}
 
-		let approval_entry = approval_db::v1::ApprovalEntry {
+		let approval_entry = approval_db::v2::ApprovalEntry {
 			tranches: vec![
 				// Assignments with invalid validator indexes.
-				approval_db::v1::TrancheEntry {
+				approval_db::v2::TrancheEntry {
 					tranche: 1,
 					assignments: (2..5).map(|i| (ValidatorIndex(i), 1.into())).collect(),
 				},
@@ -1094,7 +1159,7 @@ mod tests {
 		];
 
 		for test_tranche in test_tranches {
-			let mut approval_entry: ApprovalEntry = approval_db::v1::ApprovalEntry {
+			let mut approval_entry: ApprovalEntry = approval_db::v2::ApprovalEntry {
 				tranches: Vec::new(),
 				backing_group: GroupIndex(0),
 				our_assignment: None,
@@ -1380,4 +1445,120 @@ mod tests {
 			},
 		);
 	}
+
+	fn block_entry_with_candidates(candidates: Vec<CandidateHash>) -> BlockEntry {
+		approval_db::v2::BlockEntry {
+			block_hash: dummy_hash(),
+			block_number: 1,
+			parent_hash: dummy_hash(),
+			session: 0,
+			slot: Slot::from(1),
+			relay_vrf_story: [0u8; 32],
+			approved_bitfield: bitvec![u8, BitOrderLsb0; 0; candidates.len()],
+			candidates: candidates
+				.into_iter()
+				.enumerate()
+				.map(|(i, c)| (CoreIndex(i as _), c))
+				.collect(),
+			children: Vec::new(),
+		}
+		.into()
+	}
+
+	#[test]
+	fn approved_candidates_are_resolved_in_index_order() {
+		let candidates: Vec<_> = (1u8..=3).map(|i| CandidateHash(Hash::repeat_byte(i))).collect();
+		let block_entry = block_entry_with_candidates(candidates.clone());
+
+		let indices: CandidateBitfield = vec![2 as CandidateIndex, 0].try_into().unwrap();
+		assert_eq!(
+			approved_candidates(&block_entry, &indices),
+			Ok(vec![(0, candidates[0]), (2, candidates[2])]),
+		);
+
+		let single: CandidateBitfield = (1 as CandidateIndex).into();
+		assert_eq!(approved_candidates(&block_entry, &single), Ok(vec![(1, candidates[1])]));
+	}
+
+	#[test]
+	fn approved_candidates_rejects_unknown_index() {
+		let candidates: Vec<_> = (1u8..=2).map(|i| CandidateHash(Hash::repeat_byte(i))).collect();
+		let block_entry = block_entry_with_candidates(candidates);
+
+		let indices: CandidateBitfield = vec![0 as CandidateIndex, 4].try_into().unwrap();
+		assert_eq!(approved_candidates(&block_entry, &indices), Err(4));
+	}
+
+	#[test]
+	fn no_show_validators_are_assigned_but_not_approved_in_time() {
+		let block_tick = 20;
+		let no_show_duration = 10;
+
+		let mut approval_entry: ApprovalEntry = approval_db::v2::ApprovalEntry {
+			tranches: Vec::new(),
+			assignments: bitvec![u8, BitOrderLsb0; 0; 10],
+			our_assignment: None,
+			our_approval_sig: None,
+			backing_group: GroupIndex(0),
+			approved: false,
+		}
+		.into();
+
+		approval_entry.import_assignment(0, ValidatorIndex(0), block_tick);
+		approval_entry.import_assignment(0, ValidatorIndex(1), block_tick);
+		approval_entry.import_assignment(1, ValidatorIndex(2), block_tick + 1);
+		approval_entry.import_assignment(2, ValidatorIndex(3), block_tick + 5);
+
+		let mut approvals = bitvec![u8, BitOrderLsb0; 0; 10];
+		approvals.set(0, true);
+
+		let tick_now = block_tick + 11;
+		assert_eq!(
+			no_show_validators(
+				&approval_entry,
+				&approvals,
+				None,
+				block_tick,
+				no_show_duration,
+				tick_now,
+			),
+			vec![ValidatorIndex(1), ValidatorIndex(2)],
+		);
+
+		assert_eq!(
+			no_show_validators(
+				&approval_entry,
+				&approvals,
+				Some(0),
+				block_tick,
+				no_show_duration,
+				tick_now,
+			),
+			vec![ValidatorIndex(1)],
+		);
+
+		assert_eq!(
+			no_show_validators(
+				&approval_entry,
+				&approvals,
+				None,
+				block_tick,
+				no_show_duration,
+				block_tick + 20,
+			),
+			vec![ValidatorIndex(1), ValidatorIndex(2), ValidatorIndex(3)],
+		);
+	}
+
+	#[test]
+	fn single_candidate_approvals_use_plain_statement_kind() {
+		let a = CandidateHash(Hash::repeat_byte(1));
+		let b = CandidateHash(Hash::repeat_byte(2));
+
+		assert_eq!(approval_statement_kind(&[a]), ValidDisputeStatementKind::ApprovalChecking);
+		assert_eq!(
+			approval_statement_kind(&[a, b]),
+			ValidDisputeStatementKind::ApprovalCheckingMultipleCandidates(vec![a, b]),
+		);
+	}
 }
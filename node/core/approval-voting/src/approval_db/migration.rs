@@ -0,0 +1,472 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Migration code for the approval-voting DB.
+//!
+//! The approval-voting data lives in a single column of the parachains DB, which is
+//! versioned as a whole. The schema version of the approval-voting entries is tracked
+//! separately, under a key within that column, so that entries can be upgraded in place
+//! without wiping the rest of the DB.
+
+use parity_scale_codec::{Decode, Encode};
+use polkadot_node_primitives::approval::CandidateBitfield;
+use polkadot_node_subsystem_util::database::{DBTransaction, Database};
+use polkadot_primitives::v2::{CandidateHash, CandidateIndex, Hash, ValidatorSignature};
+
+use std::collections::HashMap;
+
+use super::{
+	v1::{
+		self, block_entry_key, blocks_at_height_key, candidate_entry_key, load_decode,
+		STORED_BLOCKS_KEY,
+	},
+	v2,
+};
+
+/// The version of the approval-voting DB schema.
+pub type Version = u32;
+
+/// Current approval-voting DB schema version.
+pub const CURRENT_VERSION: Version = 2;
+
+const VERSION_KEY: &[u8] = b"Approvals_Version";
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+	#[error("Failed to access the approval-voting DB: {0}")]
+	Db(#[from] v1::Error),
+	#[error("The stored approval-voting DB version is corrupted")]
+	CorruptedVersion,
+	#[error("Future version (expected {current:?}, found {got:?})")]
+	FutureVersion { current: Version, got: Version },
+}
+
+impl From<std::io::Error> for Error {
+	fn from(err: std::io::Error) -> Self {
+		Error::Db(err.into())
+	}
+}
+
+/// The outcome of [`try_upgrade`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationSummary {
+	/// The version the DB was at.
+	pub from: Version,
+	/// The version the DB has been, or on a dry run would have been, upgraded to.
+	pub to: Version,
+	/// The number of block entries visited by the migration steps.
+	pub block_entries: usize,
+	/// The number of candidate entries rewritten by the migration steps.
+	pub candidate_entries: usize,
+	/// Whether nothing was written to the DB.
+	pub dry_run: bool,
+}
+
+/// The changes of a single migration step.
+struct Step {
+	tx: DBTransaction,
+	block_entries: usize,
+	candidate_entries: usize,
+}
+
+/// Reads the approval-voting schema version from the DB.
+///
+/// DBs without a version key holding approval-voting data predate versioning and are
+/// assumed to be at version 1. Empty DBs are assumed to be at the current version.
+pub fn current_version(store: &dyn Database, config: &v1::Config) -> Result<Version, Error> {
+	match store.get(config.col_data, VERSION_KEY)? {
+		Some(raw) => Version::decode(&mut &raw[..]).map_err(|_| Error::CorruptedVersion),
+		None if store.get(config.col_data, STORED_BLOCKS_KEY)?.is_some() => Ok(1),
+		None => Ok(CURRENT_VERSION),
+	}
+}
+
+/// Try upgrading the approval-voting entries to the current version.
+///
+/// Every migration step is written atomically together with the version it upgrades to,
+/// so an interrupted upgrade resumes from the last completed step. With `dry_run` set,
+/// entries are read and converted but nothing is written. As migration steps build upon
+/// each other, a dry run stops after the first pending step.
+pub fn try_upgrade(
+	store: &dyn Database,
+	config: &v1::Config,
+	dry_run: bool,
+) -> Result<MigrationSummary, Error> {
+	let from = current_version(store, config)?;
+	if from > CURRENT_VERSION {
+		return Err(Error::FutureVersion { current: CURRENT_VERSION, got: from })
+	}
+
+	let mut summary =
+		MigrationSummary { from, to: from, block_entries: 0, candidate_entries: 0, dry_run };
+
+	while summary.to < CURRENT_VERSION {
+		let mut step = match summary.to {
+			1 => migrate_from_version_1_to_2(store, config)?,
+			v => unreachable!("no migration step from version {}", v),
+		};
+
+		summary.to += 1;
+		summary.block_entries += step.block_entries;
+		summary.candidate_entries += step.candidate_entries;
+
+		if dry_run {
+			return Ok(summary)
+		}
+
+		step.tx.put_vec(config.col_data, VERSION_KEY, summary.to.encode());
+		store.write(step.tx)?;
+	}
+
+	if !dry_run && store.get(config.col_data, VERSION_KEY)?.is_none() {
+		let mut tx = DBTransaction::new();
+		tx.put_vec(config.col_data, VERSION_KEY, CURRENT_VERSION.encode());
+		store.write(tx)?;
+	}
+
+	Ok(summary)
+}
+
+/// Migration from version 1 to version 2:
+/// * `ApprovalEntry::our_approval_sig` records the indices of all candidates covered by the
+///   signature. Candidates of a block which share our signature were signed together.
+fn migrate_from_version_1_to_2(store: &dyn Database, config: &v1::Config) -> Result<Step, Error> {
+	let col = config.col_data;
+
+	let mut block_entries = 0;
+	let mut candidates: HashMap<CandidateHash, v1::CandidateEntry> = HashMap::new();
+	let mut approvals: HashMap<(Hash, CandidateHash), v2::OurApproval> = HashMap::new();
+
+	let stored_blocks: Option<v1::StoredBlockRange> = load_decode(store, col, STORED_BLOCKS_KEY)?;
+	for height in stored_blocks.map_or(0..0, |range| range.0..range.1) {
+		let block_hashes: Vec<Hash> =
+			load_decode(store, col, &blocks_at_height_key(height))?.unwrap_or_default();
+
+		for block_hash in block_hashes {
+			let block_entry: v1::BlockEntry =
+				match load_decode(store, col, &block_entry_key(&block_hash))? {
+					Some(b) => b,
+					None => continue,
+				};
+			block_entries += 1;
+
+			let mut signed: Vec<(ValidatorSignature, Vec<CandidateIndex>)> = Vec::new();
+			for (i, (_, candidate_hash)) in block_entry.candidates.iter().enumerate() {
+				if !candidates.contains_key(candidate_hash) {
+					match load_decode(store, col, &candidate_entry_key(candidate_hash))? {
+						Some(c) => {
+							candidates.insert(*candidate_hash, c);
+						},
+						None => continue,
+					}
+				}
+
+				let sig = candidates
+					.get(candidate_hash)
+					.and_then(|c| c.block_assignments.get(&block_hash))
+					.and_then(|a| a.our_approval_sig.clone());
+
+				if let Some(sig) = sig {
+					match signed.iter_mut().find(|(s, _)| s == &sig) {
+						Some((_, indices)) => indices.push(i as _),
+						None => signed.push((sig, vec![i as _])),
+					}
+				}
+			}
+
+			for (signature, indices) in signed {
+				let signed_candidates_indices: CandidateBitfield = indices
+					.clone()
+					.try_into()
+					.expect("signatures are only recorded with a candidate index; qed");
+
+				for i in indices {
+					let (_, candidate_hash) = block_entry.candidates[i as usize];
+					approvals.insert(
+						(block_hash, candidate_hash),
+						v2::OurApproval {
+							signature: signature.clone(),
+							signed_candidates_indices: signed_candidates_indices.clone(),
+						},
+					);
+				}
+			}
+		}
+	}
+
+	let mut tx = DBTransaction::new();
+	let candidate_entries = candidates.len();
+	for (candidate_hash, entry) in candidates {
+		let block_assignments = entry
+			.block_assignments
+			.into_iter()
+			.map(|(block_hash, a)| {
+				// Signatures under blocks which are no longer stored can't be attributed to
+				// candidate indices, but the approval entry is unreachable anyway.
+				let our_approval_sig = approvals.remove(&(block_hash, candidate_hash));
+				let approval_entry = v2::ApprovalEntry {
+					tranches: a.tranches,
+					backing_group: a.backing_group,
+					our_assignment: a.our_assignment,
+					our_approval_sig,
+					assignments: a.assignments,
+					approved: a.approved,
+				};
+
+				(block_hash, approval_entry)
+			})
+			.collect();
+
+		let entry = v2::CandidateEntry {
+			candidate: entry.candidate,
+			session: entry.session,
+			block_assignments,
+			approvals: entry.approvals,
+		};
+
+		tx.put_vec(col, &candidate_entry_key(&candidate_hash), entry.encode());
+	}
+
+	Ok(Step { tx, block_entries, candidate_entries })
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		approval_db::v2::DbBackend,
+		backend::{Backend, OverlayedBackend},
+	};
+	use polkadot_primitives::v2::{CoreIndex, GroupIndex, Id as ParaId};
+	use sp_consensus_slots::Slot;
+	use sp_keyring::Sr25519Keyring;
+	use std::sync::Arc;
+
+	use ::test_helpers::{dummy_candidate_receipt, dummy_hash};
+
+	const DATA_COL: u32 = 0;
+	const NUM_COLUMNS: u32 = 1;
+
+	const TEST_CONFIG: v1::Config = v1::Config { col_data: DATA_COL };
+
+	fn make_db() -> Arc<dyn Database> {
+		let db = kvdb_memorydb::create(NUM_COLUMNS);
+		let db = polkadot_node_subsystem_util::database::kvdb_impl::DbAdapter::new(db, &[]);
+		Arc::new(db)
+	}
+
+	fn sig(n: u8) -> ValidatorSignature {
+		Sr25519Keyring::Alice.sign(&[n]).into()
+	}
+
+	fn v1_approval_entry(our_approval_sig: Option<ValidatorSignature>) -> v1::ApprovalEntry {
+		v1::ApprovalEntry {
+			tranches: Vec::new(),
+			backing_group: GroupIndex(0),
+			our_assignment: None,
+			our_approval_sig,
+			assignments: bitvec::bitvec![u8, bitvec::order::Lsb0; 0; 4],
+			approved: false,
+		}
+	}
+
+	// Writes a version 1 DB with a single block of three candidates. The first and last
+	// candidates were approved with the same signature, the second one on its own.
+	fn write_v1_db(store: &dyn Database) -> (Hash, Vec<CandidateHash>) {
+		let block_hash = Hash::repeat_byte(1);
+		let receipts: Vec<_> = (0..3u32)
+			.map(|i| {
+				let mut c = dummy_candidate_receipt(dummy_hash());
+				c.descriptor.para_id = ParaId::from(i);
+				c
+			})
+			.collect();
+		let candidate_hashes: Vec<_> = receipts.iter().map(|c| c.hash()).collect();
+
+		let block_entry = v1::BlockEntry {
+			block_hash,
+			block_number: 1,
+			parent_hash: Hash::repeat_byte(0),
+			session: 1,
+			slot: Slot::from(1),
+			relay_vrf_story: [0u8; 32],
+			candidates: candidate_hashes
+				.iter()
+				.enumerate()
+				.map(|(i, h)| (CoreIndex(i as _), *h))
+				.collect(),
+			approved_bitfield: bitvec::bitvec![u8, bitvec::order::Lsb0; 0; 3],
+			children: Vec::new(),
+		};
+
+		let sigs = [Some(sig(1)), Some(sig(2)), Some(sig(1))];
+
+		let mut tx = DBTransaction::new();
+		tx.put_vec(DATA_COL, STORED_BLOCKS_KEY, v1::StoredBlockRange(1, 2).encode());
+		tx.put_vec(DATA_COL, &blocks_at_height_key(1), vec![block_hash].encode());
+		tx.put_vec(DATA_COL, &block_entry_key(&block_hash), block_entry.encode());
+		for (receipt, sig) in receipts.into_iter().zip(sigs) {
+			let entry = v1::CandidateEntry {
+				candidate: receipt.clone(),
+				session: 1,
+				block_assignments: vec![(block_hash, v1_approval_entry(sig))].into_iter().collect(),
+				approvals: bitvec::bitvec![u8, bitvec::order::Lsb0; 0; 4],
+			};
+
+			tx.put_vec(DATA_COL, &candidate_entry_key(&receipt.hash()), entry.encode());
+		}
+		store.write(tx).unwrap();
+
+		(block_hash, candidate_hashes)
+	}
+
+	fn our_approval(
+		store: &dyn Database,
+		block_hash: Hash,
+		candidate_hash: CandidateHash,
+	) -> Option<v2::OurApproval> {
+		v2::load_candidate_entry(store, &TEST_CONFIG, &candidate_hash)
+			.unwrap()
+			.unwrap()
+			.block_assignments
+			.get(&block_hash)
+			.unwrap()
+			.our_approval_sig
+			.clone()
+	}
+
+	#[test]
+	fn empty_db_is_at_current_version() {
+		let store = make_db();
+
+		let summary = try_upgrade(&*store, &TEST_CONFIG, false).unwrap();
+		assert_eq!(summary.from, CURRENT_VERSION);
+		assert_eq!(summary.to, CURRENT_VERSION);
+		assert_eq!(store.get(DATA_COL, VERSION_KEY).unwrap(), Some(CURRENT_VERSION.encode()));
+	}
+
+	#[test]
+	fn future_version_is_rejected() {
+		let store = make_db();
+
+		let mut tx = DBTransaction::new();
+		tx.put_vec(DATA_COL, VERSION_KEY, (CURRENT_VERSION + 1).encode());
+		store.write(tx).unwrap();
+
+		assert!(matches!(
+			try_upgrade(&*store, &TEST_CONFIG, false),
+			Err(Error::FutureVersion { current: CURRENT_VERSION, got }) if got == CURRENT_VERSION + 1
+		));
+	}
+
+	#[test]
+	fn migrate_from_version_1_groups_shared_signatures() {
+		let store = make_db();
+		let (block_hash, candidate_hashes) = write_v1_db(&*store);
+		assert_eq!(current_version(&*store, &TEST_CONFIG).unwrap(), 1);
+
+		let summary = try_upgrade(&*store, &TEST_CONFIG, false).unwrap();
+		assert_eq!(
+			summary,
+			MigrationSummary {
+				from: 1,
+				to: 2,
+				block_entries: 1,
+				candidate_entries: 3,
+				dry_run: false
+			},
+		);
+		assert_eq!(current_version(&*store, &TEST_CONFIG).unwrap(), 2);
+
+		let shared: CandidateBitfield = vec![0, 2].try_into().unwrap();
+		let single: CandidateBitfield = vec![1].try_into().unwrap();
+		assert_eq!(
+			our_approval(&*store, block_hash, candidate_hashes[0]),
+			Some(v2::OurApproval { signature: sig(1), signed_candidates_indices: shared.clone() }),
+		);
+		assert_eq!(
+			our_approval(&*store, block_hash, candidate_hashes[1]),
+			Some(v2::OurApproval { signature: sig(2), signed_candidates_indices: single }),
+		);
+		assert_eq!(
+			our_approval(&*store, block_hash, candidate_hashes[2]),
+			Some(v2::OurApproval { signature: sig(1), signed_candidates_indices: shared }),
+		);
+
+		// Upgrading again is a no-op.
+		let summary = try_upgrade(&*store, &TEST_CONFIG, false).unwrap();
+		assert_eq!((summary.from, summary.to, summary.candidate_entries), (2, 2, 0));
+	}
+
+	#[test]
+	fn migrated_entries_round_trip_through_backend() {
+		let store = make_db();
+		let (block_hash, candidate_hashes) = write_v1_db(&*store);
+		try_upgrade(&*store, &TEST_CONFIG, false).unwrap();
+
+		let raw_before: Vec<_> = candidate_hashes
+			.iter()
+			.map(|h| store.get(DATA_COL, &candidate_entry_key(h)).unwrap())
+			.collect();
+		let raw_block_before = store.get(DATA_COL, &block_entry_key(&block_hash)).unwrap();
+
+		let mut backend = DbBackend::new(store.clone(), TEST_CONFIG);
+		let mut overlay = OverlayedBackend::new(&backend);
+		let block_entry = overlay.load_block_entry(&block_hash).unwrap().unwrap();
+		overlay.write_block_entry(block_entry);
+		for candidate_hash in &candidate_hashes {
+			let entry = overlay.load_candidate_entry(candidate_hash).unwrap().unwrap();
+			overlay.write_candidate_entry(entry);
+		}
+		let ops = overlay.into_write_ops();
+		backend.write(ops).unwrap();
+
+		let raw_after: Vec<_> = candidate_hashes
+			.iter()
+			.map(|h| store.get(DATA_COL, &candidate_entry_key(h)).unwrap())
+			.collect();
+		assert_eq!(raw_before, raw_after);
+		assert_eq!(raw_block_before, store.get(DATA_COL, &block_entry_key(&block_hash)).unwrap());
+	}
+
+	#[test]
+	fn dry_run_does_not_write() {
+		let store = make_db();
+		let (block_hash, candidate_hashes) = write_v1_db(&*store);
+
+		let summary = try_upgrade(&*store, &TEST_CONFIG, true).unwrap();
+		assert_eq!(
+			summary,
+			MigrationSummary {
+				from: 1,
+				to: 2,
+				block_entries: 1,
+				candidate_entries: 3,
+				dry_run: true
+			},
+		);
+
+		assert_eq!(current_version(&*store, &TEST_CONFIG).unwrap(), 1);
+		let entry: v1::CandidateEntry =
+			load_decode(&*store, DATA_COL, &candidate_entry_key(&candidate_hashes[0]))
+				.unwrap()
+				.unwrap();
+		assert_eq!(
+			entry.block_assignments.get(&block_hash).unwrap().our_approval_sig,
+			Some(sig(1))
+		);
+	}
+}
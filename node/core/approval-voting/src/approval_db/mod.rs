@@ -29,5 +29,10 @@
 //! As such, we provide a function from this module to clear the database on start-up.
 //! In the future, we may use a temporary DB which doesn't need to be wiped, but for the
 //! time being we share the same DB with the rest of Substrate.
+//!
+//! Entries are nevertheless versioned, and upgraded in place on start-up by the
+//! [`migration`] module.
 
+pub mod migration;
 pub mod v1;
+pub mod v2;
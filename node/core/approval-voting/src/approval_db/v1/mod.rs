@@ -15,11 +15,14 @@
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
 //! Version 1 of the DB schema.
+//!
+//! Entries which did not change in later versions are re-exported from there. The rest is
+//! only kept around to migrate existing databases, see [`super::migration`].
 
 use parity_scale_codec::{Decode, Encode};
-use polkadot_node_primitives::approval::{AssignmentCert, DelayTranche};
+use polkadot_node_primitives::approval::{AssignmentCertV2, DelayTranche};
 use polkadot_node_subsystem::{SubsystemError, SubsystemResult};
-use polkadot_node_subsystem_util::database::{DBTransaction, Database};
+use polkadot_node_subsystem_util::database::Database;
 use polkadot_primitives::v2::{
 	BlockNumber, CandidateHash, CandidateReceipt, CoreIndex, GroupIndex, Hash, SessionIndex,
 	ValidatorIndex, ValidatorSignature,
@@ -27,108 +30,9 @@ use polkadot_primitives::v2::{
 use sp_consensus_slots::Slot;
 
 use bitvec::{order::Lsb0 as BitOrderLsb0, vec::BitVec};
-use std::{collections::BTreeMap, sync::Arc};
+use std::collections::BTreeMap;
 
-use crate::{
-	backend::{Backend, BackendWriteOp},
-	persisted_entries,
-};
-
-const STORED_BLOCKS_KEY: &[u8] = b"Approvals_StoredBlocks";
-
-#[cfg(test)]
-pub mod tests;
-
-/// `DbBackend` is a concrete implementation of the higher-level Backend trait
-pub struct DbBackend {
-	inner: Arc<dyn Database>,
-	config: Config,
-}
-
-impl DbBackend {
-	/// Create a new [`DbBackend`] with the supplied key-value store and
-	/// config.
-	pub fn new(db: Arc<dyn Database>, config: Config) -> Self {
-		DbBackend { inner: db, config }
-	}
-}
-
-impl Backend for DbBackend {
-	fn load_block_entry(
-		&self,
-		block_hash: &Hash,
-	) -> SubsystemResult<Option<persisted_entries::BlockEntry>> {
-		load_block_entry(&*self.inner, &self.config, block_hash).map(|e| e.map(Into::into))
-	}
-
-	fn load_candidate_entry(
-		&self,
-		candidate_hash: &CandidateHash,
-	) -> SubsystemResult<Option<persisted_entries::CandidateEntry>> {
-		load_candidate_entry(&*self.inner, &self.config, candidate_hash).map(|e| e.map(Into::into))
-	}
-
-	fn load_blocks_at_height(&self, block_height: &BlockNumber) -> SubsystemResult<Vec<Hash>> {
-		load_blocks_at_height(&*self.inner, &self.config, block_height)
-	}
-
-	fn load_all_blocks(&self) -> SubsystemResult<Vec<Hash>> {
-		load_all_blocks(&*self.inner, &self.config)
-	}
-
-	fn load_stored_blocks(&self) -> SubsystemResult<Option<StoredBlockRange>> {
-		load_stored_blocks(&*self.inner, &self.config)
-	}
-
-	/// Atomically write the list of operations, with later operations taking precedence over prior.
-	fn write<I>(&mut self, ops: I) -> SubsystemResult<()>
-	where
-		I: IntoIterator<Item = BackendWriteOp>,
-	{
-		let mut tx = DBTransaction::new();
-		for op in ops {
-			match op {
-				BackendWriteOp::WriteStoredBlockRange(stored_block_range) => {
-					tx.put_vec(
-						self.config.col_data,
-						&STORED_BLOCKS_KEY,
-						stored_block_range.encode(),
-					);
-				},
-				BackendWriteOp::WriteBlocksAtHeight(h, blocks) => {
-					tx.put_vec(self.config.col_data, &blocks_at_height_key(h), blocks.encode());
-				},
-				BackendWriteOp::DeleteBlocksAtHeight(h) => {
-					tx.delete(self.config.col_data, &blocks_at_height_key(h));
-				},
-				BackendWriteOp::WriteBlockEntry(block_entry) => {
-					let block_entry: BlockEntry = block_entry.into();
-					tx.put_vec(
-						self.config.col_data,
-						&block_entry_key(&block_entry.block_hash),
-						block_entry.encode(),
-					);
-				},
-				BackendWriteOp::DeleteBlockEntry(hash) => {
-					tx.delete(self.config.col_data, &block_entry_key(&hash));
-				},
-				BackendWriteOp::WriteCandidateEntry(candidate_entry) => {
-					let candidate_entry: CandidateEntry = candidate_entry.into();
-					tx.put_vec(
-						self.config.col_data,
-						&candidate_entry_key(&candidate_entry.candidate.hash()),
-						candidate_entry.encode(),
-					);
-				},
-				BackendWriteOp::DeleteCandidateEntry(candidate_hash) => {
-					tx.delete(self.config.col_data, &candidate_entry_key(&candidate_hash));
-				},
-			}
-		}
-
-		self.inner.write(tx).map_err(|e| e.into())
-	}
-}
+pub(crate) const STORED_BLOCKS_KEY: &[u8] = b"Approvals_StoredBlocks";
 
 /// A range from earliest..last block number stored within the DB.
 #[derive(Encode, Decode, Debug, Clone, PartialEq)]
@@ -152,7 +56,9 @@ pub struct Config {
 /// Details pertaining to our assignment on a block.
 #[derive(Encode, Decode, Debug, Clone, PartialEq)]
 pub struct OurAssignment {
-	pub cert: AssignmentCert,
+	/// Our assignment certificate. `AssignmentCertV2` is encoded identically to the
+	/// previously stored `AssignmentCert` for the `RelayVRFModulo` and `RelayVRFDelay` kinds.
+	pub cert: AssignmentCertV2,
 	pub tranche: DelayTranche,
 	pub validator_index: ValidatorIndex,
 	// Whether the assignment has been triggered already.
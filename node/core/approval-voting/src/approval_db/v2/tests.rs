@@ -21,8 +21,10 @@ use crate::{
 	backend::{Backend, OverlayedBackend},
 	ops::{add_block_entry, canonicalize, force_approve, NewCandidateInfo},
 };
+use bitvec::{order::Lsb0 as BitOrderLsb0, vec::BitVec};
 use polkadot_node_subsystem_util::database::Database;
-use polkadot_primitives::v2::Id as ParaId;
+use polkadot_primitives::v2::{CoreIndex, Id as ParaId};
+use sp_consensus_slots::Slot;
 use std::{collections::HashMap, sync::Arc};
 
 use ::test_helpers::{dummy_candidate_receipt, dummy_candidate_receipt_bad_sig, dummy_hash};
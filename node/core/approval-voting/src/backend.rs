@@ -27,7 +27,7 @@ use polkadot_primitives::v2::{BlockNumber, CandidateHash, Hash};
 use std::collections::HashMap;
 
 use super::{
-	approval_db::v1::StoredBlockRange,
+	approval_db::v2::StoredBlockRange,
 	persisted_entries::{BlockEntry, CandidateEntry},
 };
 
@@ -18,7 +18,8 @@
 
 use parity_scale_codec::{Decode, Encode};
 use polkadot_node_primitives::approval::{
-	self as approval_types, AssignmentCert, AssignmentCertKind, DelayTranche, RelayVRFStory,
+	self as approval_types, AssignmentCertKindV2, AssignmentCertV2, CoreBitfield, DelayTranche,
+	RelayVRFStory,
 };
 use polkadot_primitives::v2::{
 	AssignmentId, AssignmentPair, CandidateHash, CoreIndex, GroupIndex, SessionInfo, ValidatorIndex,
@@ -36,7 +37,7 @@ use super::LOG_TARGET;
 /// Details pertaining to our assignment on a block.
 #[derive(Debug, Clone, Encode, Decode, PartialEq)]
 pub struct OurAssignment {
-	cert: AssignmentCert,
+	cert: AssignmentCertV2,
 	tranche: DelayTranche,
 	validator_index: ValidatorIndex,
 	// Whether the assignment has been triggered already.
@@ -44,7 +45,7 @@ pub struct OurAssignment {
 }
 
 impl OurAssignment {
-	pub(crate) fn cert(&self) -> &AssignmentCert {
+	pub(crate) fn cert(&self) -> &AssignmentCertV2 {
 		&self.cert
 	}
 
@@ -65,8 +66,8 @@ impl OurAssignment {
 	}
 }
 
-impl From<crate::approval_db::v1::OurAssignment> for OurAssignment {
-	fn from(entry: crate::approval_db::v1::OurAssignment) -> Self {
+impl From<crate::approval_db::v2::OurAssignment> for OurAssignment {
+	fn from(entry: crate::approval_db::v2::OurAssignment) -> Self {
 		OurAssignment {
 			cert: entry.cert,
 			tranche: entry.tranche,
@@ -76,7 +77,7 @@ impl From<crate::approval_db::v1::OurAssignment> for OurAssignment {
 	}
 }
 
-impl From<OurAssignment> for crate::approval_db::v1::OurAssignment {
+impl From<OurAssignment> for crate::approval_db::v2::OurAssignment {
 	fn from(entry: OurAssignment) -> Self {
 		Self {
 			cert: entry.cert,
@@ -132,6 +133,43 @@ fn assigned_core_transcript(core_index: CoreIndex) -> Transcript {
 	t
 }
 
+fn relay_vrf_modulo_compact_transcript(relay_vrf_story: RelayVRFStory) -> Transcript {
+	let mut t = Transcript::new(approval_types::RELAY_VRF_MODULO_COMPACT_CONTEXT);
+	t.append_message(b"RC-VRF", &relay_vrf_story.0);
+	t
+}
+
+// Derive up to `num_samples` distinct cores from a single VRF output. The result is sorted
+// ascending.
+fn relay_vrf_modulo_cores(vrf_in_out: &VRFInOut, num_samples: u32, n_cores: u32) -> Vec<CoreIndex> {
+	let seed: [u8; 32] = vrf_in_out.make_bytes(approval_types::CORES_RANDOMNESS_CONTEXT);
+
+	let mut t = Transcript::new(approval_types::CORES_RANDOMNESS_CONTEXT);
+	t.append_message(b"seed", &seed);
+
+	let mut cores = Vec::with_capacity(num_samples as usize);
+	for sample in 0..num_samples {
+		let mut bytes = [0u8; 4];
+		sample.using_encoded(|s| t.append_message(b"sample", s));
+		t.challenge_bytes(b"core", &mut bytes);
+
+		// interpret as little-endian u32.
+		let core = CoreIndex(u32::from_le_bytes(bytes) % n_cores);
+		if !cores.contains(&core) {
+			cores.push(core);
+		}
+	}
+
+	cores.sort();
+	cores
+}
+
+fn assigned_cores_transcript(core_bitfield: &CoreBitfield) -> Transcript {
+	let mut t = Transcript::new(approval_types::ASSIGNED_CORES_CONTEXT);
+	core_bitfield.using_encoded(|s| t.append_message(b"cores", s));
+	t
+}
+
 /// Information about the world assignments are being produced in.
 #[derive(Clone)]
 pub(crate) struct Config {
@@ -170,16 +208,17 @@ pub(crate) trait AssignmentCriteria {
 		relay_vrf_story: RelayVRFStory,
 		config: &Config,
 		leaving_cores: Vec<(CandidateHash, CoreIndex, GroupIndex)>,
+		enable_v2_assignments: bool,
 	) -> HashMap<CoreIndex, OurAssignment>;
 
 	fn check_assignment_cert(
 		&self,
-		claimed_core_index: CoreIndex,
+		claimed_core_indices: CoreBitfield,
 		validator_index: ValidatorIndex,
 		config: &Config,
 		relay_vrf_story: RelayVRFStory,
-		assignment: &AssignmentCert,
-		backing_group: GroupIndex,
+		assignment: &AssignmentCertV2,
+		backing_groups: Vec<GroupIndex>,
 	) -> Result<DelayTranche, InvalidAssignment>;
 }
 
@@ -192,26 +231,27 @@ impl AssignmentCriteria for RealAssignmentCriteria {
 		relay_vrf_story: RelayVRFStory,
 		config: &Config,
 		leaving_cores: Vec<(CandidateHash, CoreIndex, GroupIndex)>,
+		enable_v2_assignments: bool,
 	) -> HashMap<CoreIndex, OurAssignment> {
-		compute_assignments(keystore, relay_vrf_story, config, leaving_cores)
+		compute_assignments(keystore, relay_vrf_story, config, leaving_cores, enable_v2_assignments)
 	}
 
 	fn check_assignment_cert(
 		&self,
-		claimed_core_index: CoreIndex,
+		claimed_core_indices: CoreBitfield,
 		validator_index: ValidatorIndex,
 		config: &Config,
 		relay_vrf_story: RelayVRFStory,
-		assignment: &AssignmentCert,
-		backing_group: GroupIndex,
+		assignment: &AssignmentCertV2,
+		backing_groups: Vec<GroupIndex>,
 	) -> Result<DelayTranche, InvalidAssignment> {
 		check_assignment_cert(
-			claimed_core_index,
+			claimed_core_indices,
 			validator_index,
 			config,
 			relay_vrf_story,
 			assignment,
-			backing_group,
+			backing_groups,
 		)
 	}
 }
@@ -226,11 +266,15 @@ impl AssignmentCriteria for RealAssignmentCriteria {
 /// The idea is that most assignments are never triggered and fall by the wayside.
 ///
 /// This will not assign to anything the local validator was part of the backing group for.
+///
+/// With `enable_v2_assignments`, all tranche 0 assignments are covered by a single
+/// `RelayVRFModuloCompact` certificate instead of one `RelayVRFModulo` certificate per core.
 pub(crate) fn compute_assignments(
 	keystore: &LocalKeystore,
 	relay_vrf_story: RelayVRFStory,
 	config: &Config,
 	leaving_cores: impl IntoIterator<Item = (CandidateHash, CoreIndex, GroupIndex)> + Clone,
+	enable_v2_assignments: bool,
 ) -> HashMap<CoreIndex, OurAssignment> {
 	if config.n_cores == 0 ||
 		config.assignment_keys.is_empty() ||
@@ -288,15 +332,27 @@ pub(crate) fn compute_assignments(
 
 	let mut assignments = HashMap::new();
 
-	// First run `RelayVRFModulo` for each sample.
-	compute_relay_vrf_modulo_assignments(
-		&assignments_key,
-		index,
-		config,
-		relay_vrf_story.clone(),
-		leaving_cores.iter().cloned(),
-		&mut assignments,
-	);
+	if enable_v2_assignments {
+		// First run `RelayVRFModuloCompact` once for all samples.
+		compute_relay_vrf_modulo_compact_assignments(
+			&assignments_key,
+			index,
+			config,
+			relay_vrf_story.clone(),
+			leaving_cores.iter().map(|(_, core)| *core).collect(),
+			&mut assignments,
+		);
+	} else {
+		// First run `RelayVRFModulo` for each sample.
+		compute_relay_vrf_modulo_assignments(
+			&assignments_key,
+			index,
+			config,
+			relay_vrf_story.clone(),
+			leaving_cores.iter().cloned(),
+			&mut assignments,
+		);
+	}
 
 	// Then run `RelayVRFDelay` once for the whole block.
 	compute_relay_vrf_delay_assignments(
@@ -353,8 +409,8 @@ fn compute_relay_vrf_modulo_assignments(
 		if let Some((vrf_in_out, vrf_proof, _)) = maybe_assignment {
 			// Sanity: `core` is always initialized to non-default here, as the closure above
 			// has been executed.
-			let cert = AssignmentCert {
-				kind: AssignmentCertKind::RelayVRFModulo { sample: rvm_sample },
+			let cert = AssignmentCertV2 {
+				kind: AssignmentCertKindV2::RelayVRFModulo { sample: rvm_sample },
 				vrf: (
 					approval_types::VRFOutput(vrf_in_out.to_output()),
 					approval_types::VRFProof(vrf_proof),
@@ -372,6 +428,71 @@ fn compute_relay_vrf_modulo_assignments(
 	}
 }
 
+fn compute_relay_vrf_modulo_compact_assignments(
+	assignments_key: &schnorrkel::Keypair,
+	validator_index: ValidatorIndex,
+	config: &Config,
+	relay_vrf_story: RelayVRFStory,
+	leaving_cores: Vec<CoreIndex>,
+	assignments: &mut HashMap<CoreIndex, OurAssignment>,
+) {
+	let mut assigned_cores = Vec::new();
+
+	let maybe_assignment = {
+		// Extra scope to ensure borrowing instead of moving `assigned_cores`
+		// into closure.
+		let assigned_cores = &mut assigned_cores;
+		assignments_key.vrf_sign_extra_after_check(
+			relay_vrf_modulo_compact_transcript(relay_vrf_story),
+			|vrf_in_out| {
+				*assigned_cores = relay_vrf_modulo_cores(
+					&vrf_in_out,
+					config.relay_vrf_modulo_samples,
+					config.n_cores,
+				)
+				.into_iter()
+				.filter(|core| leaving_cores.contains(core))
+				.collect();
+
+				let core_bitfield = CoreBitfield::try_from(assigned_cores.clone()).ok()?;
+
+				gum::trace!(
+					target: LOG_TARGET,
+					cores = ?assigned_cores,
+					?validator_index,
+					tranche = 0,
+					"RelayVRFModuloCompact Assignment."
+				);
+
+				Some(assigned_cores_transcript(&core_bitfield))
+			},
+		)
+	};
+
+	if let Some((vrf_in_out, vrf_proof, _)) = maybe_assignment {
+		let core_bitfield = CoreBitfield::try_from(assigned_cores.clone())
+			.expect("the closure above only signs for a non-empty set of cores; qed");
+
+		let cert = AssignmentCertV2 {
+			kind: AssignmentCertKindV2::RelayVRFModuloCompact { core_bitfield },
+			vrf: (
+				approval_types::VRFOutput(vrf_in_out.to_output()),
+				approval_types::VRFProof(vrf_proof),
+			),
+		};
+
+		// All assignments of type RelayVRFModuloCompact have tranche 0 and share the same cert.
+		for core in assigned_cores {
+			assignments.entry(core).or_insert(OurAssignment {
+				cert: cert.clone(),
+				tranche: 0,
+				validator_index,
+				triggered: false,
+			});
+		}
+	}
+}
+
 fn compute_relay_vrf_delay_assignments(
 	assignments_key: &schnorrkel::Keypair,
 	validator_index: ValidatorIndex,
@@ -390,8 +511,8 @@ fn compute_relay_vrf_delay_assignments(
 			config.zeroth_delay_tranche_width,
 		);
 
-		let cert = AssignmentCert {
-			kind: AssignmentCertKind::RelayVRFDelay { core_index: core },
+		let cert = AssignmentCertV2 {
+			kind: AssignmentCertKindV2::RelayVRFDelay { core_index: core },
 			vrf: (
 				approval_types::VRFOutput(vrf_in_out.to_output()),
 				approval_types::VRFProof(vrf_proof),
@@ -442,21 +563,24 @@ impl std::error::Error for InvalidAssignment {}
 /// Checks the crypto of an assignment cert. Failure conditions:
 ///   * Validator index out of bounds
 ///   * VRF signature check fails
-///   * VRF output doesn't match assigned core
-///   * Core is not covered by extra data in signature
+///   * VRF output doesn't match assigned cores
+///   * Cores are not covered by extra data in signature
 ///   * Core index out of bounds
 ///   * Sample is out of bounds
 ///   * Validator is present in backing group.
 ///
+/// `claimed_core_indices` and `backing_groups` are expected to be of the same length and in the same
+/// order, i.e. the n-th claimed core was backed by the n-th group.
+///
 /// This function does not check whether the core is actually a valid assignment or not. That should be done
 /// outside the scope of this function.
 pub(crate) fn check_assignment_cert(
-	claimed_core_index: CoreIndex,
+	claimed_core_indices: CoreBitfield,
 	validator_index: ValidatorIndex,
 	config: &Config,
 	relay_vrf_story: RelayVRFStory,
-	assignment: &AssignmentCert,
-	backing_group: GroupIndex,
+	assignment: &AssignmentCertV2,
+	backing_groups: Vec<GroupIndex>,
 ) -> Result<DelayTranche, InvalidAssignment> {
 	let validator_public = config
 		.assignment_keys
@@ -466,29 +590,79 @@ pub(crate) fn check_assignment_cert(
 	let public = schnorrkel::PublicKey::from_bytes(validator_public.as_slice())
 		.map_err(|_| InvalidAssignment)?;
 
-	if claimed_core_index.0 >= config.n_cores {
+	// Each claimed core must have exactly one backing group.
+	if claimed_core_indices.count_ones() == 0 ||
+		claimed_core_indices.count_ones() != backing_groups.len()
+	{
 		return Err(InvalidAssignment)
 	}
 
 	// Check that the validator was not part of the backing group
 	// and not already assigned.
-	let is_in_backing =
-		is_in_backing_group(&config.validator_groups, validator_index, backing_group);
+	for (claimed_core, backing_group) in claimed_core_indices.iter_ones().zip(backing_groups.iter())
+	{
+		if claimed_core >= config.n_cores as usize {
+			return Err(InvalidAssignment)
+		}
 
-	if is_in_backing {
-		return Err(InvalidAssignment)
+		let is_in_backing =
+			is_in_backing_group(&config.validator_groups, validator_index, *backing_group);
+
+		if is_in_backing {
+			return Err(InvalidAssignment)
+		}
 	}
 
 	let &(ref vrf_output, ref vrf_proof) = &assignment.vrf;
-	match assignment.kind {
-		AssignmentCertKind::RelayVRFModulo { sample } => {
-			if sample >= config.relay_vrf_modulo_samples {
+	match &assignment.kind {
+		AssignmentCertKindV2::RelayVRFModuloCompact { core_bitfield } => {
+			// The claimed cores must be exactly the ones signed over.
+			if core_bitfield.iter_ones().ne(claimed_core_indices.iter_ones()) {
+				return Err(InvalidAssignment)
+			}
+
+			let (vrf_in_out, _) = public
+				.vrf_verify_extra(
+					relay_vrf_modulo_compact_transcript(relay_vrf_story),
+					&vrf_output.0,
+					&vrf_proof.0,
+					assigned_cores_transcript(core_bitfield),
+				)
+				.map_err(|_| InvalidAssignment)?;
+
+			// ensure that the `vrf_in_out` actually gives us the claimed cores.
+			let resulting_cores = relay_vrf_modulo_cores(
+				&vrf_in_out,
+				config.relay_vrf_modulo_samples,
+				config.n_cores,
+			);
+
+			if core_bitfield
+				.iter_ones()
+				.all(|core| resulting_cores.contains(&CoreIndex(core as u32)))
+			{
+				Ok(0)
+			} else {
+				Err(InvalidAssignment)
+			}
+		},
+		AssignmentCertKindV2::RelayVRFModulo { sample } => {
+			if claimed_core_indices.count_ones() != 1 {
 				return Err(InvalidAssignment)
 			}
 
+			if *sample >= config.relay_vrf_modulo_samples {
+				return Err(InvalidAssignment)
+			}
+
+			let claimed_core_index = claimed_core_indices
+				.first_one()
+				.map(|core| CoreIndex(core as u32))
+				.ok_or(InvalidAssignment)?;
+
 			let (vrf_in_out, _) = public
 				.vrf_verify_extra(
-					relay_vrf_modulo_transcript(relay_vrf_story, sample),
+					relay_vrf_modulo_transcript(relay_vrf_story, *sample),
 					&vrf_output.0,
 					&vrf_proof.0,
 					assigned_core_transcript(claimed_core_index),
@@ -502,14 +676,16 @@ pub(crate) fn check_assignment_cert(
 				Err(InvalidAssignment)
 			}
 		},
-		AssignmentCertKind::RelayVRFDelay { core_index } => {
-			if core_index != claimed_core_index {
+		AssignmentCertKindV2::RelayVRFDelay { core_index } => {
+			if claimed_core_indices.count_ones() != 1 ||
+				claimed_core_indices.first_one() != Some(core_index.0 as usize)
+			{
 				return Err(InvalidAssignment)
 			}
 
 			let (vrf_in_out, _) = public
 				.vrf_verify(
-					relay_vrf_delay_transcript(relay_vrf_story, core_index),
+					relay_vrf_delay_transcript(relay_vrf_story, *core_index),
 					&vrf_output.0,
 					&vrf_proof.0,
 				)
@@ -625,6 +801,7 @@ mod tests {
 				n_delay_tranches: 40,
 			},
 			vec![(c_a, CoreIndex(0), GroupIndex(1)), (c_b, CoreIndex(1), GroupIndex(0))],
+			false,
 		);
 
 		// Note that alice is in group 0, which was the backing group for core 1.
@@ -660,6 +837,7 @@ mod tests {
 				n_delay_tranches: 40,
 			},
 			vec![(c_a, CoreIndex(0), GroupIndex(0)), (c_b, CoreIndex(1), GroupIndex(1))],
+			false,
 		);
 
 		assert_eq!(assignments.len(), 1);
@@ -687,25 +865,88 @@ mod tests {
 				n_delay_tranches: 40,
 			},
 			vec![],
+			false,
 		);
 
 		assert!(assignments.is_empty());
 	}
 
+	#[test]
+	fn compact_assignment_covers_multiple_cores() {
+		let keystore = futures::executor::block_on(make_keystore(&[Sr25519Keyring::Alice]));
+
+		let n_cores = 10;
+		let relay_vrf_story = RelayVRFStory([42u8; 32]);
+		let assignments = compute_assignments(
+			&keystore,
+			relay_vrf_story,
+			&Config {
+				assignment_keys: assignment_keys_plus_random(&[Sr25519Keyring::Alice], 9),
+				validator_groups: basic_groups(10, n_cores),
+				n_cores: n_cores as u32,
+				zeroth_delay_tranche_width: 10,
+				relay_vrf_modulo_samples: 5,
+				n_delay_tranches: 40,
+			},
+			// Alice backs core 0, all other cores are assignable.
+			(0..n_cores)
+				.map(|i| {
+					(
+						CandidateHash(Hash::repeat_byte(i as u8)),
+						CoreIndex(i as u32),
+						GroupIndex(i as u32),
+					)
+				})
+				.collect::<Vec<_>>(),
+			true,
+		);
+
+		assert!(assignments.get(&CoreIndex(0)).is_none());
+
+		let compact_certs = assignments
+			.iter()
+			.filter_map(|(core, a)| match a.cert().kind {
+				AssignmentCertKindV2::RelayVRFModuloCompact { ref core_bitfield } => {
+					assert_eq!(a.tranche(), 0);
+					assert!(core_bitfield.bit_at(*core));
+					Some(a.cert().clone())
+				},
+				_ => None,
+			})
+			.collect::<Vec<_>>();
+
+		// All cores claimed in tranche 0 share a single certificate.
+		assert!(!compact_certs.is_empty());
+		assert!(compact_certs.iter().all(|c| c == &compact_certs[0]));
+		assert!(!assignments
+			.values()
+			.any(|a| matches!(a.cert().kind, AssignmentCertKindV2::RelayVRFModulo { .. })));
+	}
+
 	struct MutatedAssignment {
-		core: CoreIndex,
-		cert: AssignmentCert,
-		group: GroupIndex,
+		cores: CoreBitfield,
+		cert: AssignmentCertV2,
+		groups: Vec<GroupIndex>,
 		own_group: GroupIndex,
 		val_index: ValidatorIndex,
 		config: Config,
 	}
 
+	fn map_cores(cores: &CoreBitfield, f: impl Fn(u32) -> u32) -> CoreBitfield {
+		cores
+			.iter_ones()
+			.map(|core| CoreIndex(f(core as u32)))
+			.collect::<Vec<_>>()
+			.try_into()
+			.expect("mapping preserves non-emptiness; qed")
+	}
+
 	// This fails if the closure requests to skip everything.
 	fn check_mutated_assignments(
 		n_validators: usize,
 		n_cores: usize,
 		rotation_offset: usize,
+		enable_v2_assignments: bool,
 		f: impl Fn(&mut MutatedAssignment) -> Option<bool>, // None = skip
 	) {
 		let keystore = futures::executor::block_on(make_keystore(&[Sr25519Keyring::Alice]));
@@ -738,13 +979,20 @@ mod tests {
 					)
 				})
 				.collect::<Vec<_>>(),
+			enable_v2_assignments,
 		);
 
 		let mut counted = 0;
 		for (core, assignment) in assignments {
+			let cores = match assignment.cert.kind {
+				AssignmentCertKindV2::RelayVRFModuloCompact { ref core_bitfield } =>
+					core_bitfield.clone(),
+				_ => core.into(),
+			};
+
 			let mut mutated = MutatedAssignment {
-				core,
-				group: group_for_core(core.0 as _),
+				groups: cores.iter_ones().map(group_for_core).collect(),
+				cores,
 				cert: assignment.cert,
 				own_group: GroupIndex(0),
 				val_index: ValidatorIndex(0),
@@ -759,12 +1007,12 @@ mod tests {
 			counted += 1;
 
 			let is_good = check_assignment_cert(
-				mutated.core,
+				mutated.cores,
 				mutated.val_index,
 				&mutated.config,
 				relay_vrf_story.clone(),
 				&mutated.cert,
-				mutated.group,
+				mutated.groups,
 			)
 			.is_ok();
 
@@ -776,28 +1024,41 @@ mod tests {
 
 	#[test]
 	fn computed_assignments_pass_checks() {
-		check_mutated_assignments(200, 100, 25, |_| Some(true));
+		check_mutated_assignments(200, 100, 25, false, |_| Some(true));
+	}
+
+	#[test]
+	fn computed_compact_assignments_pass_checks() {
+		check_mutated_assignments(200, 100, 25, true, |_| Some(true));
 	}
 
 	#[test]
 	fn check_rejects_claimed_core_out_of_bounds() {
-		check_mutated_assignments(200, 100, 25, |m| {
-			m.core.0 += 100;
+		check_mutated_assignments(200, 100, 25, false, |m| {
+			m.cores = map_cores(&m.cores, |core| core + 100);
 			Some(false)
 		});
 	}
 
 	#[test]
 	fn check_rejects_in_backing_group() {
-		check_mutated_assignments(200, 100, 25, |m| {
-			m.group = m.own_group;
+		check_mutated_assignments(200, 100, 25, false, |m| {
+			m.groups[0] = m.own_group;
+			Some(false)
+		});
+	}
+
+	#[test]
+	fn check_rejects_compact_in_backing_group() {
+		check_mutated_assignments(200, 100, 25, true, |m| {
+			m.groups[0] = m.own_group;
 			Some(false)
 		});
 	}
 
 	#[test]
 	fn check_rejects_nonexistent_key() {
-		check_mutated_assignments(200, 100, 25, |m| {
+		check_mutated_assignments(200, 100, 25, false, |m| {
 			m.val_index.0 += 200;
 			Some(false)
 		});
@@ -805,9 +1066,9 @@ mod tests {
 
 	#[test]
 	fn check_rejects_delay_bad_vrf() {
-		check_mutated_assignments(40, 10, 8, |m| {
+		check_mutated_assignments(40, 10, 8, false, |m| {
 			match m.cert.kind.clone() {
-				AssignmentCertKind::RelayVRFDelay { .. } => {
+				AssignmentCertKindV2::RelayVRFDelay { .. } => {
 					m.cert.vrf = garbage_vrf();
 					Some(false)
 				},
@@ -818,9 +1079,22 @@ mod tests {
 
 	#[test]
 	fn check_rejects_modulo_bad_vrf() {
-		check_mutated_assignments(200, 100, 25, |m| {
+		check_mutated_assignments(200, 100, 25, false, |m| {
+			match m.cert.kind.clone() {
+				AssignmentCertKindV2::RelayVRFModulo { .. } => {
+					m.cert.vrf = garbage_vrf();
+					Some(false)
+				},
+				_ => None, // skip everything else.
+			}
+		});
+	}
+
+	#[test]
+	fn check_rejects_compact_bad_vrf() {
+		check_mutated_assignments(200, 100, 25, true, |m| {
 			match m.cert.kind.clone() {
-				AssignmentCertKind::RelayVRFModulo { .. } => {
+				AssignmentCertKindV2::RelayVRFModuloCompact { .. } => {
 					m.cert.vrf = garbage_vrf();
 					Some(false)
 				},
@@ -831,9 +1105,9 @@ mod tests {
 
 	#[test]
 	fn check_rejects_modulo_sample_out_of_bounds() {
-		check_mutated_assignments(200, 100, 25, |m| {
+		check_mutated_assignments(200, 100, 25, false, |m| {
 			match m.cert.kind.clone() {
-				AssignmentCertKind::RelayVRFModulo { sample } => {
+				AssignmentCertKindV2::RelayVRFModulo { sample } => {
 					m.config.relay_vrf_modulo_samples = sample;
 					Some(false)
 				},
@@ -844,10 +1118,10 @@ mod tests {
 
 	#[test]
 	fn check_rejects_delay_claimed_core_wrong() {
-		check_mutated_assignments(200, 100, 25, |m| {
+		check_mutated_assignments(200, 100, 25, false, |m| {
 			match m.cert.kind.clone() {
-				AssignmentCertKind::RelayVRFDelay { .. } => {
-					m.core = CoreIndex((m.core.0 + 1) % 100);
+				AssignmentCertKindV2::RelayVRFDelay { .. } => {
+					m.cores = map_cores(&m.cores, |core| (core + 1) % 100);
 					Some(false)
 				},
 				_ => None, // skip everything else.
@@ -857,10 +1131,36 @@ mod tests {
 
 	#[test]
 	fn check_rejects_modulo_core_wrong() {
-		check_mutated_assignments(200, 100, 25, |m| {
+		check_mutated_assignments(200, 100, 25, false, |m| {
+			match m.cert.kind.clone() {
+				AssignmentCertKindV2::RelayVRFModulo { .. } => {
+					m.cores = map_cores(&m.cores, |core| (core + 1) % 100);
+					Some(false)
+				},
+				_ => None, // skip everything else.
+			}
+		});
+	}
+
+	#[test]
+	fn check_rejects_compact_claimed_cores_mismatch() {
+		check_mutated_assignments(200, 100, 25, true, |m| {
+			match m.cert.kind.clone() {
+				AssignmentCertKindV2::RelayVRFModuloCompact { .. } => {
+					m.cores = map_cores(&m.cores, |core| (core + 1) % 100);
+					Some(false)
+				},
+				_ => None, // skip everything else.
+			}
+		});
+	}
+
+	#[test]
+	fn check_rejects_compact_claimed_cores_count_mismatch() {
+		check_mutated_assignments(200, 100, 25, true, |m| {
 			match m.cert.kind.clone() {
-				AssignmentCertKind::RelayVRFModulo { .. } => {
-					m.core = CoreIndex((m.core.0 + 1) % 100);
+				AssignmentCertKindV2::RelayVRFModuloCompact { .. } => {
+					m.groups.push(GroupIndex(1));
 					Some(false)
 				},
 				_ => None, // skip everything else.
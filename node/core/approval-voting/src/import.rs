@@ -56,7 +56,7 @@ use futures::{channel::oneshot, prelude::*};
 
 use std::collections::HashMap;
 
-use super::approval_db::v1;
+use super::approval_db::v2;
 use crate::{
 	backend::{Backend, OverlayedBackend},
 	criteria::{AssignmentCriteria, OurAssignment},
@@ -81,6 +81,7 @@ struct ImportedBlockInfoEnv<'a> {
 	session_window: &'a Option<RollingSessionWindow>,
 	assignment_criteria: &'a (dyn AssignmentCriteria + Send + Sync),
 	keystore: &'a LocalKeystore,
+	enable_v2_assignments: bool,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -242,6 +243,7 @@ async fn imported_block_info(
 								.iter()
 								.map(|(c_hash, _, core, group)| (*c_hash, *core, *group))
 								.collect(),
+							env.enable_v2_assignments,
 						);
 
 						(assignments, slot, relay_vrf)
@@ -406,6 +408,7 @@ pub(crate) async fn handle_new_head(
 				session_window: &state.session_window,
 				assignment_criteria: &*state.assignment_criteria,
 				keystore: &state.keystore,
+				enable_v2_assignments: state.enable_v2_assignments,
 			};
 
 			match imported_block_info(ctx, env, block_hash, &block_header).await {
@@ -518,7 +521,7 @@ pub(crate) async fn handle_new_head(
 			ctx.send_message(ChainSelectionMessage::Approved(block_hash)).await;
 		}
 
-		let block_entry = v1::BlockEntry {
+		let block_entry = v2::BlockEntry {
 			block_hash,
 			parent_hash: block_header.parent_hash,
 			block_number: block_header.number,
@@ -604,7 +607,7 @@ pub(crate) async fn handle_new_head(
 #[cfg(test)]
 pub(crate) mod tests {
 	use super::*;
-	use crate::approval_db::v1::DbBackend;
+	use crate::approval_db::v2::DbBackend;
 	use ::test_helpers::{dummy_candidate_receipt, dummy_hash};
 	use assert_matches::assert_matches;
 	use merlin::Transcript;
@@ -623,7 +626,8 @@ pub(crate) mod tests {
 	use std::{pin::Pin, sync::Arc};
 
 	use crate::{
-		approval_db::v1::Config as DatabaseConfig, criteria, BlockEntry, APPROVAL_SESSIONS,
+		approval_db::v2::Config as DatabaseConfig, criteria, BlockEntry, PendingApprovals,
+		APPROVAL_SESSIONS,
 	};
 
 	const DATA_COL: u32 = 0;
@@ -650,6 +654,11 @@ pub(crate) mod tests {
 			slot_duration_millis: 6_000,
 			clock: Box::new(MockClock::default()),
 			assignment_criteria: Box::new(MockAssignmentCriteria),
+			enable_v2_assignments: false,
+			pending_approvals: PendingApprovals::new(1, 0),
+			approval_coalescing_supported: false,
+			approval_lag_report_threshold: None,
+			no_show_stats: Default::default(),
 		}
 	}
 
@@ -677,19 +686,21 @@ pub(crate) mod tests {
 				polkadot_primitives::v2::CoreIndex,
 				polkadot_primitives::v2::GroupIndex,
 			)>,
+			_enable_v2_assignments: bool,
 		) -> HashMap<polkadot_primitives::v2::CoreIndex, criteria::OurAssignment> {
 			HashMap::new()
 		}
 
 		fn check_assignment_cert(
 			&self,
-			_claimed_core_index: polkadot_primitives::v2::CoreIndex,
+			_claimed_core_indices: polkadot_node_primitives::approval::CoreBitfield,
 			_validator_index: polkadot_primitives::v2::ValidatorIndex,
 			_config: &criteria::Config,
 			_relay_vrf_story: polkadot_node_primitives::approval::RelayVRFStory,
-			_assignment: &polkadot_node_primitives::approval::AssignmentCert,
-			_backing_group: polkadot_primitives::v2::GroupIndex,
-		) -> Result<polkadot_node_primitives::approval::DelayTranche, criteria::InvalidAssignment> {
+			_assignment: &polkadot_node_primitives::approval::AssignmentCertV2,
+			_backing_groups: Vec<polkadot_primitives::v2::GroupIndex>,
+		) -> Result<polkadot_node_primitives::approval::DelayTranche, criteria::InvalidAssignment>
+		{
 			Ok(0)
 		}
 	}
@@ -783,6 +794,7 @@ pub(crate) mod tests {
 					session_window: &Some(session_window),
 					assignment_criteria: &MockAssignmentCriteria,
 					keystore: &LocalKeystore::in_memory(),
+					enable_v2_assignments: false,
 				};
 
 				let info = imported_block_info(&mut ctx, env, hash, &header).await.unwrap();
@@ -891,6 +903,7 @@ pub(crate) mod tests {
 					session_window: &Some(session_window),
 					assignment_criteria: &MockAssignmentCriteria,
 					keystore: &LocalKeystore::in_memory(),
+					enable_v2_assignments: false,
 				};
 
 				let info = imported_block_info(&mut ctx, env, hash, &header).await;
@@ -989,6 +1002,7 @@ pub(crate) mod tests {
 					session_window: &session_window,
 					assignment_criteria: &MockAssignmentCriteria,
 					keystore: &LocalKeystore::in_memory(),
+					enable_v2_assignments: false,
 				};
 
 				let info = imported_block_info(&mut ctx, env, hash, &header).await;
@@ -1088,6 +1102,7 @@ pub(crate) mod tests {
 					session_window: &session_window,
 					assignment_criteria: &MockAssignmentCriteria,
 					keystore: &LocalKeystore::in_memory(),
+					enable_v2_assignments: false,
 				};
 
 				let info = imported_block_info(&mut ctx, env, hash, &header).await.unwrap();
@@ -1217,7 +1232,7 @@ pub(crate) mod tests {
 
 		let mut state = single_session_state(session, session_info);
 		overlay_db.write_block_entry(
-			v1::BlockEntry {
+			v2::BlockEntry {
 				block_hash: parent_hash.clone(),
 				parent_hash: Default::default(),
 				block_number: 4,
@@ -1252,7 +1267,7 @@ pub(crate) mod tests {
 				// the first candidate should be insta-approved
 				// the second should not
 				let entry: BlockEntry =
-					v1::load_block_entry(db_writer.as_ref(), &TEST_CONFIG, &hash)
+					v2::load_block_entry(db_writer.as_ref(), &TEST_CONFIG, &hash)
 						.unwrap()
 						.unwrap()
 						.into();
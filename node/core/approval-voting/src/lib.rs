@@ -24,7 +24,8 @@
 use polkadot_node_jaeger as jaeger;
 use polkadot_node_primitives::{
 	approval::{
-		BlockApprovalMeta, DelayTranche, IndirectAssignmentCert, IndirectSignedApprovalVote,
+		AssignmentCertKindV2, AssignmentCertV2, BlockApprovalMeta, CandidateBitfield, CoreBitfield,
+		DelayTranche, IndirectAssignmentCertV2, IndirectSignedApprovalVoteV2,
 	},
 	SignedDisputeStatement, ValidationResult, APPROVAL_EXECUTION_TIMEOUT,
 };
@@ -33,9 +34,10 @@ use polkadot_node_subsystem::{
 	messages::{
 		ApprovalCheckError, ApprovalCheckResult, ApprovalDistributionMessage,
 		ApprovalVotingMessage, AssignmentCheckError, AssignmentCheckResult,
-		AvailabilityRecoveryMessage, BlockDescription, CandidateValidationMessage, ChainApiMessage,
-		ChainSelectionMessage, DisputeCoordinatorMessage, HighestApprovedAncestorBlock,
-		RuntimeApiMessage, RuntimeApiRequest,
+		AvailabilityRecoveryMessage, BlockDescription, BlockNoShows, CandidateNoShows,
+		CandidateValidationMessage, ChainApiMessage, ChainSelectionMessage,
+		DisputeCoordinatorMessage, HighestApprovedAncestorBlock, RuntimeApiMessage,
+		RuntimeApiRequest,
 	},
 	overseer::{self, SubsystemSender as _},
 	FromOverseer, OverseerSignal, SpawnedSubsystem, SubsystemContext, SubsystemError,
@@ -43,6 +45,7 @@ use polkadot_node_subsystem::{
 };
 use polkadot_node_subsystem_util::{
 	database::Database,
+	has_required_runtime,
 	metrics::{self, prometheus},
 	rolling_session_window::{
 		new_session_window_size, RollingSessionWindow, SessionWindowSize, SessionWindowUpdate,
@@ -50,10 +53,13 @@ use polkadot_node_subsystem_util::{
 	},
 	TimeoutExt,
 };
-use polkadot_primitives::v2::{
-	ApprovalVote, BlockNumber, CandidateHash, CandidateIndex, CandidateReceipt, DisputeStatement,
-	GroupIndex, Hash, SessionIndex, SessionInfo, ValidDisputeStatementKind, ValidatorId,
-	ValidatorIndex, ValidatorPair, ValidatorSignature,
+use polkadot_primitives::{
+	v2::{
+		ApprovalVote, BlockNumber, CandidateHash, CandidateIndex, CandidateReceipt,
+		DisputeStatement, GroupIndex, Hash, SessionIndex, SessionInfo, ValidDisputeStatementKind,
+		ValidatorId, ValidatorIndex, ValidatorPair, ValidatorSignature,
+	},
+	vstaging::{ApprovalVoteMultipleCandidates, MAX_APPROVAL_COALESCE_COUNT},
 };
 use sc_keystore::LocalKeystore;
 use sp_application_crypto::Pair;
@@ -70,6 +76,7 @@ use futures::{
 use std::{
 	collections::{
 		btree_map::Entry as BTMEntry, hash_map::Entry as HMEntry, BTreeMap, HashMap, HashSet,
+		VecDeque,
 	},
 	sync::Arc,
 	time::Duration,
@@ -77,11 +84,11 @@ use std::{
 
 use approval_checking::RequiredTranches;
 use criteria::{AssignmentCriteria, RealAssignmentCriteria};
-use persisted_entries::{ApprovalEntry, BlockEntry, CandidateEntry};
+use persisted_entries::{ApprovalEntry, BlockEntry, CandidateEntry, OurApproval};
 use time::{slot_number_to_tick, Clock, ClockExt, SystemClock, Tick};
 
 mod approval_checking;
-mod approval_db;
+pub mod approval_db;
 mod backend;
 mod criteria;
 mod import;
@@ -90,7 +97,7 @@ mod persisted_entries;
 mod time;
 
 use crate::{
-	approval_db::v1::{Config as DatabaseConfig, DbBackend},
+	approval_db::v2::{Config as DatabaseConfig, DbBackend},
 	backend::{Backend, OverlayedBackend},
 };
 
@@ -103,6 +110,7 @@ const APPROVAL_CHECKING_TIMEOUT: Duration = Duration::from_secs(120);
 const APPROVAL_CACHE_SIZE: usize = 1024;
 const TICK_TOO_FAR_IN_FUTURE: Tick = 20; // 10 seconds.
 const APPROVAL_DELAY: Tick = 2;
+const MAX_NO_SHOW_REPORTS: usize = 1024;
 const LOG_TARGET: &str = "parachain::approval-voting";
 
 /// Configuration for the approval voting subsystem
@@ -113,6 +121,26 @@ pub struct Config {
 	/// The slot duration of the consensus algorithm, in milliseconds. Should be evenly
 	/// divisible by 500.
 	pub slot_duration_millis: u64,
+	/// Whether to produce `RelayVRFModuloCompact` assignment certificates, which cover all
+	/// tranche 0 assignments of a block with a single VRF. Peers which are not aware of these
+	/// certificates are unable to process them, so this should only be enabled once the
+	/// network has upgraded.
+	pub enable_v2_assignments: bool,
+	/// The maximum number of candidates of a relay block whose local approvals are signed
+	/// together, with a single signature. A value of `1` disables coalescing. Peers which are
+	/// not aware of coalesced approvals are unable to process them, so this should only be
+	/// raised once the network has upgraded. Approvals are only coalesced on relay chains whose
+	/// runtime accepts such votes in disputes, and never for more than
+	/// [`MAX_APPROVAL_COALESCE_COUNT`] candidates.
+	pub max_approval_coalesce_count: u32,
+	/// The maximum number of ticks (500ms) a local approval is held back, waiting for other
+	/// checks on candidates of the same relay block to finish so it can be signed together
+	/// with them.
+	pub max_approval_coalesce_wait_ticks: u32,
+	/// The number of blocks the highest approved ancestor of a chain may lag behind its head
+	/// before a report of the unapproved candidates and their no-shows is logged. `None`
+	/// disables the report.
+	pub approval_lag_report_threshold: Option<BlockNumber>,
 }
 
 // The mode of the approval voting subsystem. It should start in a `Syncing` mode when it first
@@ -137,6 +165,10 @@ pub struct ApprovalVotingSubsystem {
 	keystore: Arc<LocalKeystore>,
 	db_config: DatabaseConfig,
 	slot_duration_millis: u64,
+	enable_v2_assignments: bool,
+	max_approval_coalesce_count: u32,
+	max_approval_coalesce_wait_ticks: u32,
+	approval_lag_report_threshold: Option<BlockNumber>,
 	db: Arc<dyn Database>,
 	mode: Mode,
 	metrics: Metrics,
@@ -147,7 +179,9 @@ struct MetricsInner {
 	imported_candidates_total: prometheus::Counter<prometheus::U64>,
 	assignments_produced: prometheus::Histogram,
 	approvals_produced_total: prometheus::CounterVec<prometheus::U64>,
+	candidates_per_approval_signature: prometheus::Histogram,
 	no_shows_total: prometheus::Counter<prometheus::U64>,
+	finalized_no_shows_total: prometheus::Counter<prometheus::U64>,
 	wakeups_triggered_total: prometheus::Counter<prometheus::U64>,
 	candidate_approval_time_ticks: prometheus::Histogram,
 	block_approval_time_ticks: prometheus::Histogram,
@@ -202,12 +236,24 @@ impl Metrics {
 		}
 	}
 
+	fn on_approval_signed(&self, n_candidates: usize) {
+		if let Some(metrics) = &self.0 {
+			metrics.candidates_per_approval_signature.observe(n_candidates as f64);
+		}
+	}
+
 	fn on_no_shows(&self, n: usize) {
 		if let Some(metrics) = &self.0 {
 			metrics.no_shows_total.inc_by(n as u64);
 		}
 	}
 
+	fn on_finalized_no_shows(&self, n: usize) {
+		if let Some(metrics) = &self.0 {
+			metrics.finalized_no_shows_total.inc_by(n as u64);
+		}
+	}
+
 	fn on_wakeup(&self) {
 		if let Some(metrics) = &self.0 {
 			metrics.wakeups_triggered_total.inc();
@@ -266,6 +312,15 @@ impl metrics::Metrics for Metrics {
 				)?,
 				registry,
 			)?,
+			candidates_per_approval_signature: prometheus::register(
+				prometheus::Histogram::with_opts(
+					prometheus::HistogramOpts::new(
+						"polkadot_parachain_approvals_candidates_per_signature",
+						"Number of candidates covered by each local approval signature",
+					).buckets(vec![1.0, 2.0, 3.0, 4.0, 6.0, 8.0, 12.0, 16.0]),
+				)?,
+				registry,
+			)?,
 			no_shows_total: prometheus::register(
 				prometheus::Counter::new(
 					"polkadot_parachain_approvals_no_shows_total",
@@ -273,6 +328,13 @@ impl metrics::Metrics for Metrics {
 				)?,
 				registry,
 			)?,
+			finalized_no_shows_total: prometheus::register(
+				prometheus::Counter::new(
+					"polkadot_parachain_approvals_finalized_no_shows_total",
+					"Number of no-shows in finalized blocks",
+				)?,
+				registry,
+			)?,
 			wakeups_triggered_total: prometheus::register(
 				prometheus::Counter::new(
 					"polkadot_parachain_approvals_wakeups_total",
@@ -334,6 +396,10 @@ impl ApprovalVotingSubsystem {
 		ApprovalVotingSubsystem {
 			keystore,
 			slot_duration_millis: config.slot_duration_millis,
+			enable_v2_assignments: config.enable_v2_assignments,
+			max_approval_coalesce_count: config.max_approval_coalesce_count,
+			max_approval_coalesce_wait_ticks: config.max_approval_coalesce_wait_ticks,
+			approval_lag_report_threshold: config.approval_lag_report_threshold,
 			db,
 			db_config: DatabaseConfig { col_data: config.col_data },
 			mode: Mode::Syncing(sync_oracle),
@@ -480,10 +546,147 @@ impl Wakeups {
 	}
 }
 
+// Local approvals which are held back so they can be signed together with approvals for other
+// candidates of the same relay block, finishing their checks soon after.
+struct PendingApprovals {
+	// The maximum number of candidates covered by a single signature.
+	max_count: usize,
+	// The maximum number of ticks an approval is held back for.
+	max_wait: Tick,
+	// Relay Block -> (Our Validator Index, [Candidate Hash], Deadline)
+	by_block: HashMap<Hash, (ValidatorIndex, Vec<CandidateHash>, Tick)>,
+	// Tick -> [Relay Block]
+	deadlines: BTreeMap<Tick, Vec<Hash>>,
+}
+
+impl PendingApprovals {
+	fn new(max_count: u32, max_wait: Tick) -> Self {
+		PendingApprovals {
+			max_count: max_count.min(MAX_APPROVAL_COALESCE_COUNT) as usize,
+			max_wait,
+			by_block: HashMap::new(),
+			deadlines: BTreeMap::new(),
+		}
+	}
+
+	// Whether local approvals are coalesced at all.
+	fn is_enabled(&self) -> bool {
+		self.max_count > 1
+	}
+
+	// Notes a local approval awaiting its signature. Returns `true` if enough approvals are
+	// pending for the block that they should be signed right away.
+	fn note(
+		&mut self,
+		block_hash: Hash,
+		validator_index: ValidatorIndex,
+		candidate_hash: CandidateHash,
+		tick_now: Tick,
+	) -> bool {
+		let deadlines = &mut self.deadlines;
+		let deadline = tick_now + self.max_wait;
+		let (_, candidates, _) = self.by_block.entry(block_hash).or_insert_with(|| {
+			deadlines.entry(deadline).or_default().push(block_hash);
+			(validator_index, Vec::new(), deadline)
+		});
+
+		if !candidates.contains(&candidate_hash) {
+			candidates.push(candidate_hash);
+		}
+
+		candidates.len() >= self.max_count
+	}
+
+	// Takes the approvals pending for a block, if any, along with the deadline of the block.
+	fn take(&mut self, block_hash: &Hash) -> Option<(ValidatorIndex, Vec<CandidateHash>)> {
+		let (validator_index, candidates, deadline) = self.by_block.remove(block_hash)?;
+
+		// The deadline is already gone if it was reached.
+		if let BTMEntry::Occupied(mut entry) = self.deadlines.entry(deadline) {
+			entry.get_mut().retain(|h| h != block_hash);
+			if entry.get().is_empty() {
+				let _ = entry.remove();
+			}
+		}
+
+		Some((validator_index, candidates))
+	}
+
+	// Returns the next block whose pending approvals should be signed. This future never returns
+	// if there are no pending approvals.
+	async fn next(&mut self, clock: &(dyn Clock + Sync)) -> Hash {
+		match self.deadlines.keys().next().copied() {
+			None => future::pending().await,
+			Some(tick) => {
+				clock.wait(tick).await;
+				match self.deadlines.entry(tick) {
+					BTMEntry::Vacant(_) => {
+						panic!("entry is known to exist since it was the first key; qed")
+					},
+					BTMEntry::Occupied(mut entry) => {
+						let block_hash = entry.get_mut().pop()
+							.expect("entries are created non-empty in `note` and removed here once empty; qed");
+
+						if entry.get().is_empty() {
+							let _ = entry.remove();
+						}
+
+						block_hash
+					},
+				}
+			},
+		}
+	}
+}
+
+// No-shows of the candidates of finalized blocks.
+#[derive(Default)]
+struct NoShowStats {
+	// Reports of the most recently finalized blocks, oldest first.
+	blocks: VecDeque<BlockNoShows>,
+	// Session -> Validator Index -> Number of No-Shows
+	per_validator: BTreeMap<SessionIndex, HashMap<ValidatorIndex, u32>>,
+}
+
+impl NoShowStats {
+	// Records the no-shows of a finalized block. Counts of sessions before `earliest_session`
+	// are dropped.
+	fn note_finalized(&mut self, report: BlockNoShows, earliest_session: SessionIndex) {
+		let counts = self.per_validator.entry(report.session).or_default();
+		for no_show in report.candidates.iter().flat_map(|c| c.no_shows.iter()) {
+			*counts.entry(*no_show).or_default() += 1;
+		}
+
+		self.per_validator = self.per_validator.split_off(&earliest_session);
+
+		if self.blocks.len() >= MAX_NO_SHOW_REPORTS {
+			let _ = self.blocks.pop_front();
+		}
+		self.blocks.push_back(report);
+	}
+
+	fn block(&self, block_hash: &Hash) -> Option<BlockNoShows> {
+		self.blocks.iter().rev().find(|r| &r.block_hash == block_hash).cloned()
+	}
+
+	// The validators of a session with at least one no-show, in ascending order.
+	fn validators(&self, session: SessionIndex) -> Vec<(ValidatorIndex, u32)> {
+		let mut counts: Vec<_> = self
+			.per_validator
+			.get(&session)
+			.map(|c| c.iter().map(|(v, n)| (*v, *n)).collect())
+			.unwrap_or_default();
+
+		counts.sort();
+		counts
+	}
+}
+
 struct ApprovalStatus {
 	required_tranches: RequiredTranches,
 	tranche_now: DelayTranche,
 	block_tick: Tick,
+	no_show_duration: Tick,
 }
 
 #[derive(Copy, Clone)]
@@ -582,6 +785,13 @@ struct State {
 	slot_duration_millis: u64,
 	clock: Box<dyn Clock + Send + Sync>,
 	assignment_criteria: Box<dyn AssignmentCriteria + Send + Sync>,
+	enable_v2_assignments: bool,
+	pending_approvals: PendingApprovals,
+	// Whether the runtime at the most recent leaf accepts approval votes covering multiple
+	// candidates.
+	approval_coalescing_supported: bool,
+	approval_lag_report_threshold: Option<BlockNumber>,
+	no_show_stats: NoShowStats,
 }
 
 impl State {
@@ -648,7 +858,8 @@ impl State {
 				session_info.needed_approvals as _,
 			);
 
-			let status = ApprovalStatus { required_tranches, block_tick, tranche_now };
+			let status =
+				ApprovalStatus { required_tranches, block_tick, tranche_now, no_show_duration };
 
 			Some((approval_entry, status))
 		} else {
@@ -667,10 +878,13 @@ enum Action {
 	},
 	LaunchApproval {
 		candidate_hash: CandidateHash,
-		indirect_cert: IndirectAssignmentCert,
+		indirect_cert: IndirectAssignmentCertV2,
 		assignment_tranche: DelayTranche,
 		relay_block_hash: Hash,
-		candidate_index: CandidateIndex,
+		claimed_candidate_indices: CandidateBitfield,
+		// Whether the certificate still needs to be distributed. Certificates covering
+		// multiple candidates are only distributed once.
+		distribute_assignment: bool,
 		session: SessionIndex,
 		candidate: CandidateReceipt,
 		backing_group: GroupIndex,
@@ -684,6 +898,7 @@ enum Action {
 	},
 	NoteApprovedInChainSelection(Hash),
 	IssueApproval(CandidateHash, ApprovalVoteRequest),
+	SignApprovals(Hash),
 	BecomeActive,
 	Conclude,
 }
@@ -706,6 +921,14 @@ where
 		slot_duration_millis: subsystem.slot_duration_millis,
 		clock,
 		assignment_criteria,
+		enable_v2_assignments: subsystem.enable_v2_assignments,
+		pending_approvals: PendingApprovals::new(
+			subsystem.max_approval_coalesce_count,
+			subsystem.max_approval_coalesce_wait_ticks as Tick,
+		),
+		approval_coalescing_supported: false,
+		approval_lag_report_threshold: subsystem.approval_lag_report_threshold,
+		no_show_stats: NoShowStats::default(),
 	};
 
 	let mut wakeups = Wakeups::default();
@@ -738,6 +961,9 @@ where
 					&subsystem.metrics,
 				)?
 			}
+			block_hash = state.pending_approvals.next(&*state.clock).fuse() => {
+				vec![Action::SignApprovals(block_hash)]
+			}
 			next_msg = ctx.recv().fuse() => {
 				let mut actions = handle_from_overseer(
 					&mut ctx,
@@ -883,12 +1109,33 @@ async fn handle_actions(
 
 				actions_iter = next_actions.into_iter();
 			},
+			Action::SignApprovals(block_hash) => {
+				let mut sender = ctx.sender().clone();
+				let next_actions: Vec<Action> = match state.pending_approvals.take(&block_hash) {
+					Some((validator_index, candidate_hashes)) => sign_approvals(
+						&mut sender,
+						state,
+						overlayed_db,
+						metrics,
+						block_hash,
+						validator_index,
+						candidate_hashes,
+					)?,
+					None => Vec::new(),
+				}
+				.into_iter()
+				.chain(actions_iter)
+				.collect();
+
+				actions_iter = next_actions.into_iter();
+			},
 			Action::LaunchApproval {
 				candidate_hash,
 				indirect_cert,
 				assignment_tranche,
 				relay_block_hash,
-				candidate_index,
+				claimed_candidate_indices,
+				distribute_assignment,
 				session,
 				candidate,
 				backing_group,
@@ -902,10 +1149,12 @@ async fn handle_actions(
 				let block_hash = indirect_cert.block_hash;
 				let validator_index = indirect_cert.validator;
 
-				ctx.send_unbounded_message(ApprovalDistributionMessage::DistributeAssignment(
-					indirect_cert,
-					candidate_index,
-				));
+				if distribute_assignment {
+					ctx.send_unbounded_message(ApprovalDistributionMessage::DistributeAssignment(
+						indirect_cert,
+						claimed_candidate_indices,
+					));
+				}
 
 				match approvals_cache.get(&candidate_hash) {
 					Some(ApprovalOutcome::Approved) => {
@@ -1006,6 +1255,11 @@ fn distribution_messages_for_activation(
 			session: block_entry.session(),
 		});
 
+		// Certificates and approvals covering multiple candidates are only distributed
+		// once per block. Approvals are distributed after all assignments of the block.
+		let mut distributed_assignments = HashSet::new();
+		let mut approvals: Vec<IndirectSignedApprovalVoteV2> = Vec::new();
+
 		for (i, (_, candidate_hash)) in block_entry.candidates().iter().enumerate() {
 			let candidate_entry = match db.load_candidate_entry(&candidate_hash)? {
 				Some(c) => c,
@@ -1023,36 +1277,39 @@ fn distribution_messages_for_activation(
 
 			match candidate_entry.approval_entry(&block_hash) {
 				Some(approval_entry) => {
-					match approval_entry.local_statements() {
-						(None, None) | (None, Some(_)) => {}, // second is impossible case.
-						(Some(assignment), None) => {
+					let mut distribute_assignment = |assignment: &criteria::OurAssignment| {
+						let claimed_candidate_indices =
+							cert_candidate_indices(assignment.cert(), i as _, &block_entry);
+
+						if distributed_assignments.insert(claimed_candidate_indices.clone()) {
 							messages.push(ApprovalDistributionMessage::DistributeAssignment(
-								IndirectAssignmentCert {
+								IndirectAssignmentCertV2 {
 									block_hash,
 									validator: assignment.validator_index(),
 									cert: assignment.cert().clone(),
 								},
-								i as _,
+								claimed_candidate_indices,
 							));
+						}
+					};
+
+					match approval_entry.local_statements() {
+						(None, None) | (None, Some(_)) => {}, // second is impossible case.
+						(Some(assignment), None) => {
+							distribute_assignment(&assignment);
 						},
-						(Some(assignment), Some(approval_sig)) => {
-							messages.push(ApprovalDistributionMessage::DistributeAssignment(
-								IndirectAssignmentCert {
-									block_hash,
-									validator: assignment.validator_index(),
-									cert: assignment.cert().clone(),
-								},
-								i as _,
-							));
+						(Some(assignment), Some(our_approval)) => {
+							distribute_assignment(&assignment);
 
-							messages.push(ApprovalDistributionMessage::DistributeApproval(
-								IndirectSignedApprovalVote {
+							let candidate_indices = our_approval.signed_candidates_indices();
+							if approvals.iter().all(|a| &a.candidate_indices != candidate_indices) {
+								approvals.push(IndirectSignedApprovalVoteV2 {
 									block_hash,
-									candidate_index: i as _,
+									candidate_indices: candidate_indices.clone(),
 									validator: assignment.validator_index(),
-									signature: approval_sig,
-								},
-							))
+									signature: our_approval.signature().clone(),
+								});
+							}
 						},
 					}
 				},
@@ -1066,6 +1323,8 @@ fn distribution_messages_for_activation(
 				},
 			}
 		}
+
+		messages.extend(approvals.into_iter().map(ApprovalDistributionMessage::DistributeApproval));
 	}
 
 	messages[0] = ApprovalDistributionMessage::NewBlocks(approval_meta);
@@ -1089,6 +1348,16 @@ async fn handle_from_overseer(
 
 			for activated in update.activated {
 				let head = activated.hash;
+
+				if state.pending_approvals.is_enabled() {
+					state.approval_coalescing_supported = has_required_runtime(
+						head,
+						RuntimeApiRequest::APPROVAL_COALESCING_RUNTIME_REQUIREMENT,
+						ctx.sender(),
+					)
+					.await;
+				}
+
 				match import::handle_new_head(ctx, state, db, head, &*last_finalized_height).await {
 					Err(e) => return Err(SubsystemError::with_origin("db", e)),
 					Ok(block_imported_candidates) => {
@@ -1142,6 +1411,8 @@ async fn handle_from_overseer(
 			gum::debug!(target: LOG_TARGET, ?block_hash, ?block_number, "Block finalized");
 			*last_finalized_height = Some(block_number);
 
+			note_finalized_no_shows(state, db, metrics, block_hash)?;
+
 			crate::ops::canonicalize(db, block_number, block_hash)
 				.map_err(|e| SubsystemError::with_origin("db", e))?;
 
@@ -1153,9 +1424,9 @@ async fn handle_from_overseer(
 			vec![Action::Conclude]
 		},
 		FromOverseer::Communication { msg } => match msg {
-			ApprovalVotingMessage::CheckAndImportAssignment(a, claimed_core, res) => {
+			ApprovalVotingMessage::CheckAndImportAssignment(a, claimed_candidates, res) => {
 				let (check_outcome, actions) =
-					check_and_import_assignment(state, db, a, claimed_core)?;
+					check_and_import_assignment(state, db, a, claimed_candidates)?;
 				let _ = res.send(check_outcome);
 
 				actions
@@ -1166,7 +1437,7 @@ async fn handle_from_overseer(
 				})?
 				.0,
 			ApprovalVotingMessage::ApprovedAncestor(target, lower_bound, res) => {
-				match handle_approved_ancestor(ctx, db, target, lower_bound, wakeups).await {
+				match handle_approved_ancestor(ctx, state, db, target, lower_bound, wakeups).await {
 					Ok(v) => {
 						let _ = res.send(v);
 					},
@@ -1176,6 +1447,16 @@ async fn handle_from_overseer(
 					},
 				}
 
+				Vec::new()
+			},
+			ApprovalVotingMessage::GetBlockNoShows(block_hash, res) => {
+				let _ = res.send(state.no_show_stats.block(&block_hash));
+
+				Vec::new()
+			},
+			ApprovalVotingMessage::GetValidatorNoShows(session, res) => {
+				let _ = res.send(state.no_show_stats.validators(session));
+
 				Vec::new()
 			},
 		},
@@ -1184,8 +1465,91 @@ async fn handle_from_overseer(
 	Ok(actions)
 }
 
+// Computes the no-shows of the candidates included by a block, as of now.
+fn block_no_shows(
+	state: &State,
+	db: &OverlayedBackend<'_, impl Backend>,
+	block_entry: &BlockEntry,
+) -> SubsystemResult<BlockNoShows> {
+	let mut candidates = Vec::with_capacity(block_entry.candidates().len());
+	for (_, candidate_hash) in block_entry.candidates() {
+		let candidate_entry = match db.load_candidate_entry(candidate_hash)? {
+			Some(c) => c,
+			None => continue,
+		};
+
+		let (approval_entry, status) = match state.approval_status(block_entry, &candidate_entry) {
+			Some(s) => s,
+			None => continue,
+		};
+
+		let needed_tranche = match status.required_tranches {
+			RequiredTranches::Exact { needed, .. } => Some(needed),
+			RequiredTranches::All | RequiredTranches::Pending { .. } => None,
+		};
+
+		let no_shows = approval_checking::no_show_validators(
+			approval_entry,
+			candidate_entry.approvals(),
+			needed_tranche,
+			status.block_tick,
+			status.no_show_duration,
+			status.block_tick + status.tranche_now as Tick,
+		);
+
+		candidates.push(CandidateNoShows {
+			candidate_hash: *candidate_hash,
+			needed_tranche,
+			no_shows,
+		});
+	}
+
+	Ok(BlockNoShows {
+		block_hash: block_entry.block_hash(),
+		block_number: block_entry.block_number(),
+		session: block_entry.session(),
+		candidates,
+	})
+}
+
+// Records the no-shows of all blocks finalized along with `finalized_hash`. Must be called
+// before the finalized blocks are pruned from the DB.
+fn note_finalized_no_shows(
+	state: &mut State,
+	db: &OverlayedBackend<'_, impl Backend>,
+	metrics: &Metrics,
+	finalized_hash: Hash,
+) -> SubsystemResult<()> {
+	let earliest_session = match state.session_window.as_ref() {
+		Some(w) => w.earliest_session(),
+		None => return Ok(()),
+	};
+
+	// All ancestors still in the DB have been unfinalized until now.
+	let mut finalized = Vec::new();
+	let mut next = Some(finalized_hash);
+	while let Some(block_hash) = next {
+		next = db.load_block_entry(&block_hash)?.map(|block_entry| {
+			let parent_hash = block_entry.parent_hash();
+			finalized.push(block_entry);
+			parent_hash
+		});
+	}
+
+	for block_entry in finalized.into_iter().rev() {
+		let report = block_no_shows(state, db, &block_entry)?;
+
+		metrics.on_finalized_no_shows(report.candidates.iter().map(|c| c.no_shows.len()).sum());
+
+		state.no_show_stats.note_finalized(report, earliest_session);
+	}
+
+	Ok(())
+}
+
 async fn handle_approved_ancestor(
 	ctx: &mut (impl SubsystemContext + overseer::SubsystemContext),
+	state: &State,
 	db: &OverlayedBackend<'_, impl Backend>,
 	target: Hash,
 	lower_bound: BlockNumber,
@@ -1242,6 +1606,7 @@ async fn handle_approved_ancestor(
 	};
 
 	let mut block_descriptions = Vec::new();
+	let mut unapproved_blocks = Vec::new();
 
 	let mut bits: BitVec<u8, Lsb0> = Default::default();
 	for (i, block_hash) in std::iter::once(target).chain(ancestry).enumerate() {
@@ -1269,6 +1634,12 @@ async fn handle_approved_ancestor(
 		// even if traversing millions of blocks this is fairly cheap and always dwarfed by the
 		// disk lookups.
 		bits.push(entry.is_fully_approved());
+		if state.approval_lag_report_threshold.is_some() &&
+			!entry.is_fully_approved() &&
+			unapproved_blocks.len() < MAX_TRACING_WINDOW
+		{
+			unapproved_blocks.push(entry.clone());
+		}
 		if entry.is_fully_approved() {
 			if all_approved_max.is_none() {
 				// First iteration of the loop is target, i = 0. After that,
@@ -1411,6 +1782,33 @@ async fn handle_approved_ancestor(
 		},
 	);
 
+	// All unapproved blocks are above the highest approved ancestor, as every block below it
+	// has to be approved.
+	let lag = target_number - all_approved_max.map_or(lower_bound, |(_, number)| number);
+	if state.approval_lag_report_threshold.map_or(false, |threshold| lag > threshold) {
+		for block_entry in unapproved_blocks {
+			let report = block_no_shows(state, db, &block_entry)?;
+			for candidate in report.candidates {
+				if block_entry.is_candidate_approved(&candidate.candidate_hash) {
+					continue
+				}
+
+				gum::info!(
+					target: LOG_TARGET,
+					?target,
+					lag,
+					block_hash = ?report.block_hash,
+					block_number = report.block_number,
+					session = report.session,
+					candidate_hash = ?candidate.candidate_hash,
+					needed_tranche = ?candidate.needed_tranche,
+					no_shows = ?candidate.no_shows,
+					"Approval checking is lagging behind",
+				);
+			}
+		}
+	}
+
 	// `reverse()` to obtain the ascending order from lowest to highest
 	// block within the candidates, which is the expected order
 	block_descriptions.reverse();
@@ -1520,8 +1918,8 @@ fn schedule_wakeup_action(
 fn check_and_import_assignment(
 	state: &State,
 	db: &mut OverlayedBackend<'_, impl Backend>,
-	assignment: IndirectAssignmentCert,
-	candidate_index: CandidateIndex,
+	assignment: IndirectAssignmentCertV2,
+	candidate_indices: CandidateBitfield,
 ) -> SubsystemResult<(AssignmentCheckResult, Vec<Action>)> {
 	let tick_now = state.clock.tick_now();
 
@@ -1547,34 +1945,36 @@ fn check_and_import_assignment(
 			)),
 	};
 
-	let (claimed_core_index, assigned_candidate_hash) =
-		match block_entry.candidate(candidate_index as usize) {
-			Some((c, h)) => (*c, *h),
+	// Load all claimed candidates along with the cores they occupy.
+	let mut claimed = Vec::with_capacity(candidate_indices.count_ones());
+	for candidate_index in candidate_indices.iter_ones() {
+		let candidate_index = candidate_index as CandidateIndex;
+		let (claimed_core_index, assigned_candidate_hash) =
+			match block_entry.candidate(candidate_index as usize) {
+				Some((c, h)) => (*c, *h),
+				None =>
+					return Ok((
+						AssignmentCheckResult::Bad(AssignmentCheckError::InvalidCandidateIndex(
+							candidate_index,
+						)),
+						Vec::new(),
+					)), // no candidate at core.
+			};
+
+		let candidate_entry = match db.load_candidate_entry(&assigned_candidate_hash)? {
+			Some(c) => c,
 			None =>
 				return Ok((
-					AssignmentCheckResult::Bad(AssignmentCheckError::InvalidCandidateIndex(
+					AssignmentCheckResult::Bad(AssignmentCheckError::InvalidCandidate(
 						candidate_index,
+						assigned_candidate_hash,
 					)),
 					Vec::new(),
-				)), // no candidate at core.
-		};
-
-	let mut candidate_entry = match db.load_candidate_entry(&assigned_candidate_hash)? {
-		Some(c) => c,
-		None =>
-			return Ok((
-				AssignmentCheckResult::Bad(AssignmentCheckError::InvalidCandidate(
-					candidate_index,
-					assigned_candidate_hash,
 				)),
-				Vec::new(),
-			)),
-	};
+		};
 
-	let res = {
-		// import the assignment.
-		let approval_entry = match candidate_entry.approval_entry_mut(&assignment.block_hash) {
-			Some(a) => a,
+		let backing_group = match candidate_entry.approval_entry(&assignment.block_hash) {
+			Some(a) => a.backing_group(),
 			None =>
 				return Ok((
 					AssignmentCheckResult::Bad(AssignmentCheckError::Internal(
@@ -1585,43 +1985,69 @@ fn check_and_import_assignment(
 				)),
 		};
 
-		let res = state.assignment_criteria.check_assignment_cert(
-			claimed_core_index,
-			assignment.validator,
-			&criteria::Config::from(session_info),
-			block_entry.relay_vrf_story(),
-			&assignment.cert,
-			approval_entry.backing_group(),
-		);
+		claimed.push((claimed_core_index, backing_group, assigned_candidate_hash, candidate_entry));
+	}
 
-		let tranche = match res {
-			Err(crate::criteria::InvalidAssignment) =>
-				return Ok((
-					AssignmentCheckResult::Bad(AssignmentCheckError::InvalidCert(
-						assignment.validator,
-					)),
-					Vec::new(),
+	// The criteria expect the backing groups in the order of the claimed cores.
+	claimed.sort_by_key(|(core, _, _, _)| *core);
+
+	let claimed_core_indices = match CoreBitfield::try_from(
+		claimed.iter().map(|(core, _, _, _)| *core).collect::<Vec<_>>(),
+	) {
+		Ok(bitfield) => bitfield,
+		Err(_) =>
+			return Ok((
+				AssignmentCheckResult::Bad(AssignmentCheckError::InvalidBitfield(
+					assignment.block_hash,
 				)),
-			Ok(tranche) => {
-				let current_tranche =
-					state.clock.tranche_now(state.slot_duration_millis, block_entry.slot());
+				Vec::new(),
+			)),
+	};
 
-				let too_far_in_future = current_tranche + TICK_TOO_FAR_IN_FUTURE as DelayTranche;
+	let res = state.assignment_criteria.check_assignment_cert(
+		claimed_core_indices,
+		assignment.validator,
+		&criteria::Config::from(session_info),
+		block_entry.relay_vrf_story(),
+		&assignment.cert,
+		claimed.iter().map(|(_, group, _, _)| *group).collect(),
+	);
 
-				if tranche >= too_far_in_future {
-					return Ok((AssignmentCheckResult::TooFarInFuture, Vec::new()))
-				}
+	let tranche = match res {
+		Err(crate::criteria::InvalidAssignment) =>
+			return Ok((
+				AssignmentCheckResult::Bad(AssignmentCheckError::InvalidCert(assignment.validator)),
+				Vec::new(),
+			)),
+		Ok(tranche) => {
+			let current_tranche =
+				state.clock.tranche_now(state.slot_duration_millis, block_entry.slot());
 
-				tranche
-			},
-		};
+			let too_far_in_future = current_tranche + TICK_TOO_FAR_IN_FUTURE as DelayTranche;
+
+			if tranche >= too_far_in_future {
+				return Ok((AssignmentCheckResult::TooFarInFuture, Vec::new()))
+			}
+
+			tranche
+		},
+	};
+
+	let mut is_duplicate = true;
+	let mut actions = Vec::new();
 
-		let is_duplicate = approval_entry.is_assigned(assignment.validator);
+	// Import the assignment into every claimed candidate.
+	for (_, _, assigned_candidate_hash, mut candidate_entry) in claimed {
+		let approval_entry = candidate_entry
+			.approval_entry_mut(&assignment.block_hash)
+			.expect("approval entry was checked to exist above; qed");
+
+		let is_duplicate_for_candidate = approval_entry.is_assigned(assignment.validator);
 		approval_entry.import_assignment(tranche, assignment.validator, tick_now);
 
-		if is_duplicate {
-			AssignmentCheckResult::AcceptedDuplicate
-		} else {
+		if !is_duplicate_for_candidate {
+			is_duplicate = false;
+
 			gum::trace!(
 				target: LOG_TARGET,
 				validator = assignment.validator.0,
@@ -1629,28 +2055,32 @@ fn check_and_import_assignment(
 				para_id = ?candidate_entry.candidate_receipt().descriptor.para_id,
 				"Imported assignment.",
 			);
-
-			AssignmentCheckResult::Accepted
 		}
-	};
 
-	let mut actions = Vec::new();
+		// We've imported a new approval, so we need to schedule a wake-up for when that might no-show.
+		if let Some((approval_entry, status)) =
+			state.approval_status(&block_entry, &candidate_entry)
+		{
+			actions.extend(schedule_wakeup_action(
+				approval_entry,
+				block_entry.block_hash(),
+				block_entry.block_number(),
+				assigned_candidate_hash,
+				status.block_tick,
+				tick_now,
+				status.required_tranches,
+			));
+		}
 
-	// We've imported a new approval, so we need to schedule a wake-up for when that might no-show.
-	if let Some((approval_entry, status)) = state.approval_status(&block_entry, &candidate_entry) {
-		actions.extend(schedule_wakeup_action(
-			approval_entry,
-			block_entry.block_hash(),
-			block_entry.block_number(),
-			assigned_candidate_hash,
-			status.block_tick,
-			tick_now,
-			status.required_tranches,
-		));
+		// We also write the candidate entry as it now contains the new candidate.
+		db.write_candidate_entry(candidate_entry.into());
 	}
 
-	// We also write the candidate entry as it now contains the new candidate.
-	db.write_candidate_entry(candidate_entry.into());
+	let res = if is_duplicate {
+		AssignmentCheckResult::AcceptedDuplicate
+	} else {
+		AssignmentCheckResult::Accepted
+	};
 
 	Ok((res, actions))
 }
@@ -1659,7 +2089,7 @@ fn check_and_import_approval<T>(
 	state: &State,
 	db: &mut OverlayedBackend<'_, impl Backend>,
 	metrics: &Metrics,
-	approval: IndirectSignedApprovalVote,
+	approval: IndirectSignedApprovalVoteV2,
 	with_response: impl FnOnce(ApprovalCheckResult) -> T,
 ) -> SubsystemResult<(Vec<Action>, T)> {
 	macro_rules! respond_early {
@@ -1687,12 +2117,16 @@ fn check_and_import_approval<T>(
 		},
 	};
 
-	let approved_candidate_hash = match block_entry.candidate(approval.candidate_index as usize) {
-		Some((_, h)) => *h,
-		None => respond_early!(ApprovalCheckResult::Bad(
-			ApprovalCheckError::InvalidCandidateIndex(approval.candidate_index),
-		)),
-	};
+	let approved_candidates =
+		match approval_checking::approved_candidates(&block_entry, &approval.candidate_indices) {
+			Ok(c) if !c.is_empty() => c,
+			Ok(_) => respond_early!(ApprovalCheckResult::Bad(ApprovalCheckError::InvalidBitfield(
+				approval.block_hash
+			),)),
+			Err(candidate_index) => respond_early!(ApprovalCheckResult::Bad(
+				ApprovalCheckError::InvalidCandidateIndex(candidate_index),
+			)),
+		};
 
 	let pubkey = match session_info.validators.get(approval.validator.0 as usize) {
 		Some(k) => k,
@@ -1701,86 +2135,121 @@ fn check_and_import_approval<T>(
 		)),
 	};
 
-	// Transform the approval vote into the wrapper used to import statements into disputes.
-	// This also does signature checking.
-	let signed_dispute_statement = match SignedDisputeStatement::new_checked(
-		DisputeStatement::Valid(ValidDisputeStatementKind::ApprovalChecking),
-		approved_candidate_hash,
-		block_entry.session(),
-		pubkey.clone(),
-		approval.signature.clone(),
-	) {
-		Err(_) => respond_early!(ApprovalCheckResult::Bad(ApprovalCheckError::InvalidSignature(
-			approval.validator
-		),)),
-		Ok(s) => s,
-	};
+	let session = block_entry.session();
+	let approved_candidate_hashes: Vec<_> = approved_candidates.iter().map(|(_, h)| *h).collect();
+	let statement_kind = approval_checking::approval_statement_kind(&approved_candidate_hashes);
+
+	let mut candidates = Vec::with_capacity(approved_candidates.len());
+	for (candidate_index, approved_candidate_hash) in approved_candidates {
+		let statement = DisputeStatement::Valid(statement_kind.clone());
+
+		// Transform the approval vote into the wrapper used to import statements into disputes.
+		// This also does signature checking. The signing payload is the same for all
+		// approved candidates, so the signature only needs to be checked once.
+		let signed_dispute_statement = if candidates.is_empty() {
+			match SignedDisputeStatement::new_checked(
+				statement,
+				approved_candidate_hash,
+				session,
+				pubkey.clone(),
+				approval.signature.clone(),
+			) {
+				Err(_) => respond_early!(ApprovalCheckResult::Bad(
+					ApprovalCheckError::InvalidSignature(approval.validator),
+				)),
+				Ok(s) => s,
+			}
+		} else {
+			SignedDisputeStatement::new_unchecked_from_trusted_source(
+				statement,
+				approved_candidate_hash,
+				session,
+				pubkey.clone(),
+				approval.signature.clone(),
+			)
+		};
 
-	let candidate_entry = match db.load_candidate_entry(&approved_candidate_hash)? {
-		Some(c) => c,
-		None => {
-			respond_early!(ApprovalCheckResult::Bad(ApprovalCheckError::InvalidCandidate(
-				approval.candidate_index,
-				approved_candidate_hash
-			),))
-		},
-	};
+		let candidate_entry = match db.load_candidate_entry(&approved_candidate_hash)? {
+			Some(c) => c,
+			None => {
+				respond_early!(ApprovalCheckResult::Bad(ApprovalCheckError::InvalidCandidate(
+					candidate_index,
+					approved_candidate_hash
+				),))
+			},
+		};
 
-	// Don't accept approvals until assignment.
-	match candidate_entry.approval_entry(&approval.block_hash) {
-		None => {
-			respond_early!(ApprovalCheckResult::Bad(ApprovalCheckError::Internal(
-				approval.block_hash,
-				approved_candidate_hash
-			),))
-		},
-		Some(e) if !e.is_assigned(approval.validator) => {
-			respond_early!(ApprovalCheckResult::Bad(ApprovalCheckError::NoAssignment(
-				approval.validator
-			),))
-		},
-		_ => {},
+		// Don't accept approvals until assignment.
+		match candidate_entry.approval_entry(&approval.block_hash) {
+			None => {
+				respond_early!(ApprovalCheckResult::Bad(ApprovalCheckError::Internal(
+					approval.block_hash,
+					approved_candidate_hash
+				),))
+			},
+			Some(e) if !e.is_assigned(approval.validator) => {
+				respond_early!(ApprovalCheckResult::Bad(ApprovalCheckError::NoAssignment(
+					approval.validator
+				),))
+			},
+			_ => {},
+		}
+
+		candidates.push((approved_candidate_hash, candidate_entry, signed_dispute_statement));
 	}
 
 	// importing the approval can be heavy as it may trigger acceptance for a series of blocks.
 	let t = with_response(ApprovalCheckResult::Accepted);
 
-	gum::trace!(
-		target: LOG_TARGET,
-		validator_index = approval.validator.0,
-		validator = ?pubkey,
-		candidate_hash = ?approved_candidate_hash,
-		para_id = ?candidate_entry.candidate_receipt().descriptor.para_id,
-		"Importing approval vote",
-	);
+	let mut actions = Vec::new();
+	let mut block_entry = Some(block_entry);
+	for (approved_candidate_hash, candidate_entry, signed_dispute_statement) in candidates {
+		// The block entry may have been updated by importing the previous approval.
+		let block_entry = match block_entry.take() {
+			Some(b) => b,
+			None => match db.load_block_entry(&approval.block_hash)? {
+				Some(b) => b,
+				None => break,
+			},
+		};
 
-	let inform_disputes_action = if !candidate_entry.has_approved(approval.validator) {
-		// The approval voting system requires a separate approval for each assignment
-		// to the candidate. It's possible that there are semi-duplicate approvals,
-		// but we only need to inform the dispute coordinator about the first expressed
-		// opinion by the validator about the candidate.
-		Some(Action::InformDisputeCoordinator {
-			candidate_hash: approved_candidate_hash,
-			candidate_receipt: candidate_entry.candidate_receipt().clone(),
-			session: block_entry.session(),
-			dispute_statement: signed_dispute_statement,
-			validator_index: approval.validator,
-		})
-	} else {
-		None
-	};
+		gum::trace!(
+			target: LOG_TARGET,
+			validator_index = approval.validator.0,
+			validator = ?pubkey,
+			candidate_hash = ?approved_candidate_hash,
+			para_id = ?candidate_entry.candidate_receipt().descriptor.para_id,
+			"Importing approval vote",
+		);
 
-	let mut actions = advance_approval_state(
-		state,
-		db,
-		&metrics,
-		block_entry,
-		approved_candidate_hash,
-		candidate_entry,
-		ApprovalStateTransition::RemoteApproval(approval.validator),
-	);
+		let inform_disputes_action = if !candidate_entry.has_approved(approval.validator) {
+			// The approval voting system requires a separate approval for each assignment
+			// to the candidate. It's possible that there are semi-duplicate approvals,
+			// but we only need to inform the dispute coordinator about the first expressed
+			// opinion by the validator about the candidate.
+			Some(Action::InformDisputeCoordinator {
+				candidate_hash: approved_candidate_hash,
+				candidate_receipt: candidate_entry.candidate_receipt().clone(),
+				session,
+				dispute_statement: signed_dispute_statement,
+				validator_index: approval.validator,
+			})
+		} else {
+			None
+		};
+
+		actions.extend(advance_approval_state(
+			state,
+			db,
+			&metrics,
+			block_entry,
+			approved_candidate_hash,
+			candidate_entry,
+			ApprovalStateTransition::RemoteApproval(approval.validator),
+		));
 
-	actions.extend(inform_disputes_action);
+		actions.extend(inform_disputes_action);
+	}
 
 	Ok((actions, t))
 }
@@ -1788,7 +2257,7 @@ fn check_and_import_approval<T>(
 #[derive(Debug)]
 enum ApprovalStateTransition {
 	RemoteApproval(ValidatorIndex),
-	LocalApproval(ValidatorIndex, ValidatorSignature),
+	LocalApproval(ValidatorIndex, OurApproval),
 	WakeupProcessed,
 }
 
@@ -1918,8 +2387,8 @@ fn advance_approval_state(
 			approval_entry.mark_approved();
 		}
 
-		if let ApprovalStateTransition::LocalApproval(_, ref sig) = transition {
-			approval_entry.import_approval_sig(sig.clone());
+		if let ApprovalStateTransition::LocalApproval(_, ref our_approval) = transition {
+			approval_entry.import_approval_sig(our_approval.clone());
 		}
 
 		actions.extend(schedule_wakeup_action(
@@ -1982,6 +2451,28 @@ fn should_trigger_assignment(
 	}
 }
 
+// Returns the indices of all candidates in the block which are covered by the given certificate.
+// Only `RelayVRFModuloCompact` certificates may cover more than the candidate at `candidate_index`.
+fn cert_candidate_indices(
+	cert: &AssignmentCertV2,
+	candidate_index: CandidateIndex,
+	block_entry: &BlockEntry,
+) -> CandidateBitfield {
+	match cert.kind {
+		AssignmentCertKindV2::RelayVRFModuloCompact { ref core_bitfield } => block_entry
+			.candidates()
+			.iter()
+			.enumerate()
+			.filter(|(_, (core, _))| core_bitfield.bit_at(*core))
+			.map(|(i, _)| i as CandidateIndex)
+			.collect::<Vec<_>>()
+			.try_into()
+			.unwrap_or_else(|_| candidate_index.into()),
+		AssignmentCertKindV2::RelayVRFModulo { .. } |
+		AssignmentCertKindV2::RelayVRFDelay { .. } => candidate_index.into(),
+	}
+}
+
 fn process_wakeup(
 	state: &State,
 	db: &mut OverlayedBackend<'_, impl Backend>,
@@ -2082,13 +2573,40 @@ fn process_wakeup(
 	};
 
 	if let Some((cert, val_index, tranche)) = maybe_cert {
-		let indirect_cert =
-			IndirectAssignmentCert { block_hash: relay_block, validator: val_index, cert };
-
 		let index_in_candidate =
 			block_entry.candidates().iter().position(|(_, h)| &candidate_hash == h);
 
 		if let Some(i) = index_in_candidate {
+			let claimed_candidate_indices =
+				cert_candidate_indices(&cert, i as CandidateIndex, &block_entry);
+
+			// A certificate covering multiple candidates has already been distributed if our
+			// assignment to any of the other candidates was triggered before.
+			let mut distribute_assignment = true;
+			for other in claimed_candidate_indices.iter_ones().filter(|other| *other != i) {
+				let other_hash = match block_entry.candidate(other) {
+					Some((_, h)) => *h,
+					None => continue,
+				};
+
+				let already_triggered = db
+					.load_candidate_entry(&other_hash)?
+					.and_then(|e| {
+						e.approval_entry(&relay_block)
+							.and_then(|a| a.our_assignment())
+							.map(|a| a.triggered())
+					})
+					.unwrap_or(false);
+
+				if already_triggered {
+					distribute_assignment = false;
+					break
+				}
+			}
+
+			let indirect_cert =
+				IndirectAssignmentCertV2 { block_hash: relay_block, validator: val_index, cert };
+
 			gum::trace!(
 				target: LOG_TARGET,
 				?candidate_hash,
@@ -2103,7 +2621,8 @@ fn process_wakeup(
 				indirect_cert,
 				assignment_tranche: tranche,
 				relay_block_hash: relay_block,
-				candidate_index: i as _,
+				claimed_candidate_indices,
+				distribute_assignment,
 				session: block_entry.session(),
 				candidate: candidate_receipt,
 				backing_group,
@@ -2354,6 +2873,9 @@ async fn launch_approval(
 
 // Issue and import a local approval vote. Should only be invoked after approval checks
 // have been done.
+//
+// If approvals are coalesced, the vote is held back until enough candidates of the block
+// are approved or the coalescing deadline is reached.
 async fn issue_approval(
 	ctx: &mut impl SubsystemSender,
 	state: &mut State,
@@ -2371,20 +2893,71 @@ async fn issue_approval(
 		},
 	};
 
-	let candidate_index = match block_entry.candidates().iter().position(|e| e.1 == candidate_hash)
+	if !block_entry.candidates().iter().any(|e| e.1 == candidate_hash) {
+		gum::warn!(
+			target: LOG_TARGET,
+			"Candidate hash {} is not present in the block entry's candidates for relay block {}",
+			candidate_hash,
+			block_entry.parent_hash(),
+		);
+
+		metrics.on_approval_error();
+		return Ok(Vec::new())
+	}
+
+	if !state.pending_approvals.is_enabled() || !state.approval_coalescing_supported {
+		return sign_approvals(
+			ctx,
+			state,
+			db,
+			metrics,
+			block_hash,
+			validator_index,
+			vec![candidate_hash],
+		)
+	}
+
+	let tick_now = state.clock.tick_now();
+	if !state
+		.pending_approvals
+		.note(block_hash, validator_index, candidate_hash, tick_now)
 	{
-		None => {
-			gum::warn!(
-				target: LOG_TARGET,
-				"Candidate hash {} is not present in the block entry's candidates for relay block {}",
-				candidate_hash,
-				block_entry.parent_hash(),
-			);
+		gum::trace!(
+			target: LOG_TARGET,
+			?candidate_hash,
+			?block_hash,
+			validator_index = validator_index.0,
+			"Holding back approval vote to sign it together with others",
+		);
 
-			metrics.on_approval_error();
+		return Ok(Vec::new())
+	}
+
+	match state.pending_approvals.take(&block_hash) {
+		Some((validator_index, candidate_hashes)) =>
+			sign_approvals(ctx, state, db, metrics, block_hash, validator_index, candidate_hashes),
+		None => Ok(Vec::new()),
+	}
+}
+
+// Sign local approval votes for the given candidates of a block with a single signature,
+// import them and dispatch the vote to approval distribution.
+fn sign_approvals(
+	ctx: &mut impl SubsystemSender,
+	state: &State,
+	db: &mut OverlayedBackend<'_, impl Backend>,
+	metrics: &Metrics,
+	block_hash: Hash,
+	validator_index: ValidatorIndex,
+	candidate_hashes: Vec<CandidateHash>,
+) -> SubsystemResult<Vec<Action>> {
+	let block_entry = match db.load_block_entry(&block_hash)? {
+		Some(b) => b,
+		None => {
+			// not a cause for alarm - just lost a race with pruning, most likely.
+			metrics.on_approval_stale();
 			return Ok(Vec::new())
 		},
-		Some(idx) => idx,
 	};
 
 	let session_info = match state.session_info(block_entry.session()) {
@@ -2402,35 +2975,31 @@ async fn issue_approval(
 		},
 	};
 
-	let candidate_hash = match block_entry.candidate(candidate_index as usize) {
-		Some((_, h)) => h.clone(),
-		None => {
-			gum::warn!(
-				target: LOG_TARGET,
-				"Received malformed request to approve out-of-bounds candidate index {} included at block {:?}",
-				candidate_index,
-				block_hash,
-			);
+	// Candidates are signed in the order of their index in the block.
+	let mut candidates = Vec::with_capacity(candidate_hashes.len());
+	for (candidate_index, (_, candidate_hash)) in block_entry.candidates().iter().enumerate() {
+		if !candidate_hashes.contains(candidate_hash) {
+			continue
+		}
 
-			metrics.on_approval_error();
-			return Ok(Vec::new())
-		},
-	};
+		match db.load_candidate_entry(candidate_hash)? {
+			Some(c) => candidates.push((candidate_index as CandidateIndex, *candidate_hash, c)),
+			None => {
+				gum::warn!(
+					target: LOG_TARGET,
+					"Missing entry for candidate index {} included at block {:?}",
+					candidate_index,
+					block_hash,
+				);
 
-	let candidate_entry = match db.load_candidate_entry(&candidate_hash)? {
-		Some(c) => c,
-		None => {
-			gum::warn!(
-				target: LOG_TARGET,
-				"Missing entry for candidate index {} included at block {:?}",
-				candidate_index,
-				block_hash,
-			);
+				metrics.on_approval_error();
+			},
+		}
+	}
 
-			metrics.on_approval_error();
-			return Ok(Vec::new())
-		},
-	};
+	if candidates.is_empty() {
+		return Ok(Vec::new())
+	}
 
 	let validator_pubkey = match session_info.validators.get(validator_index.0 as usize) {
 		Some(p) => p,
@@ -2448,7 +3017,13 @@ async fn issue_approval(
 	};
 
 	let session = block_entry.session();
-	let sig = match sign_approval(&state.keystore, &validator_pubkey, candidate_hash, session) {
+	let signed_candidate_hashes: Vec<_> = candidates.iter().map(|(_, h, _)| *h).collect();
+	let sig = match sign_approval(
+		&state.keystore,
+		&validator_pubkey,
+		&signed_candidate_hashes,
+		session,
+	) {
 		Some(sig) => sig,
 		None => {
 			gum::warn!(
@@ -2463,60 +3038,86 @@ async fn issue_approval(
 		},
 	};
 
-	// Record our statement in the dispute coordinator for later
-	// participation in disputes on the same candidate.
-	let signed_dispute_statement = SignedDisputeStatement::new_checked(
-		DisputeStatement::Valid(ValidDisputeStatementKind::ApprovalChecking),
-		candidate_hash,
-		session,
-		validator_pubkey.clone(),
-		sig.clone(),
-	)
-	.expect("Statement just signed; should pass checks; qed");
+	metrics.on_approval_signed(candidates.len());
 
-	gum::trace!(
-		target: LOG_TARGET,
-		?candidate_hash,
-		?block_hash,
-		validator_index = validator_index.0,
-		"Issuing approval vote",
-	);
+	let statement_kind = approval_checking::approval_statement_kind(&signed_candidate_hashes);
+	let candidate_indices: CandidateBitfield = candidates
+		.iter()
+		.map(|(i, _, _)| *i)
+		.collect::<Vec<_>>()
+		.try_into()
+		.expect("candidates is not empty; qed");
 
-	let candidate_receipt = candidate_entry.candidate_receipt().clone();
+	let mut actions = Vec::new();
+	let mut inform_disputes_actions = Vec::new();
+	let mut block_entry = Some(block_entry);
+	for (candidate_index, candidate_hash, candidate_entry) in candidates {
+		// The block entry may have been updated by importing the previous approval.
+		let block_entry = match block_entry.take() {
+			Some(b) => b,
+			None => match db.load_block_entry(&block_hash)? {
+				Some(b) => b,
+				None => break,
+			},
+		};
 
-	let inform_disputes_action = if candidate_entry.has_approved(validator_index) {
-		// The approval voting system requires a separate approval for each assignment
-		// to the candidate. It's possible that there are semi-duplicate approvals,
-		// but we only need to inform the dispute coordinator about the first expressed
-		// opinion by the validator about the candidate.
-		Some(Action::InformDisputeCoordinator {
+		// Record our statement in the dispute coordinator for later
+		// participation in disputes on the same candidate.
+		let signed_dispute_statement = SignedDisputeStatement::new_checked(
+			DisputeStatement::Valid(statement_kind.clone()),
 			candidate_hash,
-			candidate_receipt,
 			session,
-			dispute_statement: signed_dispute_statement,
-			validator_index,
-		})
-	} else {
-		None
-	};
+			validator_pubkey.clone(),
+			sig.clone(),
+		)
+		.expect("Statement just signed; should pass checks; qed");
 
-	let mut actions = advance_approval_state(
-		state,
-		db,
-		metrics,
-		block_entry,
-		candidate_hash,
-		candidate_entry,
-		ApprovalStateTransition::LocalApproval(validator_index as _, sig.clone()),
-	);
+		gum::trace!(
+			target: LOG_TARGET,
+			?candidate_hash,
+			?block_hash,
+			candidate_index,
+			validator_index = validator_index.0,
+			"Issuing approval vote",
+		);
 
-	metrics.on_approval_produced();
+		let candidate_receipt = candidate_entry.candidate_receipt().clone();
+
+		if candidate_entry.has_approved(validator_index) {
+			// The approval voting system requires a separate approval for each assignment
+			// to the candidate. It's possible that there are semi-duplicate approvals,
+			// but we only need to inform the dispute coordinator about the first expressed
+			// opinion by the validator about the candidate.
+			inform_disputes_actions.push(Action::InformDisputeCoordinator {
+				candidate_hash,
+				candidate_receipt,
+				session,
+				dispute_statement: signed_dispute_statement,
+				validator_index,
+			});
+		}
+
+		actions.extend(advance_approval_state(
+			state,
+			db,
+			metrics,
+			block_entry,
+			candidate_hash,
+			candidate_entry,
+			ApprovalStateTransition::LocalApproval(
+				validator_index as _,
+				OurApproval::new(sig.clone(), candidate_indices.clone()),
+			),
+		));
+
+		metrics.on_approval_produced();
+	}
 
 	// dispatch to approval distribution.
 	ctx.send_unbounded_message(
-		ApprovalDistributionMessage::DistributeApproval(IndirectSignedApprovalVote {
+		ApprovalDistributionMessage::DistributeApproval(IndirectSignedApprovalVoteV2 {
 			block_hash,
-			candidate_index: candidate_index as _,
+			candidate_indices,
 			validator: validator_index,
 			signature: sig,
 		})
@@ -2524,21 +3125,24 @@ async fn issue_approval(
 	);
 
 	// dispatch to dispute coordinator.
-	actions.extend(inform_disputes_action);
+	actions.extend(inform_disputes_actions);
 
 	Ok(actions)
 }
 
-// Sign an approval vote. Fails if the key isn't present in the store.
+// Sign an approval vote for one or more candidates. Fails if the key isn't present in the store.
 fn sign_approval(
 	keystore: &LocalKeystore,
 	public: &ValidatorId,
-	candidate_hash: CandidateHash,
+	candidate_hashes: &[CandidateHash],
 	session_index: SessionIndex,
 ) -> Option<ValidatorSignature> {
 	let key = keystore.key_pair::<ValidatorPair>(public).ok().flatten()?;
 
-	let payload = ApprovalVote(candidate_hash).signing_payload(session_index);
+	let payload = match candidate_hashes {
+		[candidate_hash] => ApprovalVote(*candidate_hash).signing_payload(session_index),
+		_ => ApprovalVoteMultipleCandidates(candidate_hashes).signing_payload(session_index),
+	};
 
 	Some(key.sign(&payload[..]))
 }
@@ -20,7 +20,9 @@
 //! Within that context, things are plain-old-data. Within this module,
 //! data and logic are intertwined.
 
-use polkadot_node_primitives::approval::{AssignmentCert, DelayTranche, RelayVRFStory};
+use polkadot_node_primitives::approval::{
+	AssignmentCertV2, CandidateBitfield, DelayTranche, RelayVRFStory,
+};
 use polkadot_primitives::v2::{
 	BlockNumber, CandidateHash, CandidateReceipt, CoreIndex, GroupIndex, Hash, SessionIndex,
 	ValidatorIndex, ValidatorSignature,
@@ -53,8 +55,8 @@ impl TrancheEntry {
 	}
 }
 
-impl From<crate::approval_db::v1::TrancheEntry> for TrancheEntry {
-	fn from(entry: crate::approval_db::v1::TrancheEntry) -> Self {
+impl From<crate::approval_db::v2::TrancheEntry> for TrancheEntry {
+	fn from(entry: crate::approval_db::v2::TrancheEntry) -> Self {
 		TrancheEntry {
 			tranche: entry.tranche,
 			assignments: entry.assignments.into_iter().map(|(v, t)| (v, t.into())).collect(),
@@ -62,7 +64,7 @@ impl From<crate::approval_db::v1::TrancheEntry> for TrancheEntry {
 	}
 }
 
-impl From<TrancheEntry> for crate::approval_db::v1::TrancheEntry {
+impl From<TrancheEntry> for crate::approval_db::v2::TrancheEntry {
 	fn from(entry: TrancheEntry) -> Self {
 		Self {
 			tranche: entry.tranche,
@@ -71,6 +73,51 @@ impl From<TrancheEntry> for crate::approval_db::v1::TrancheEntry {
 	}
 }
 
+/// Our approval signature for a candidate, which may cover other candidates of the block too.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OurApproval {
+	signature: ValidatorSignature,
+	signed_candidates_indices: CandidateBitfield,
+}
+
+impl OurApproval {
+	/// Create a new approval covering the given candidates of the block.
+	pub fn new(
+		signature: ValidatorSignature,
+		signed_candidates_indices: CandidateBitfield,
+	) -> Self {
+		OurApproval { signature, signed_candidates_indices }
+	}
+
+	/// Get the approval signature.
+	pub fn signature(&self) -> &ValidatorSignature {
+		&self.signature
+	}
+
+	/// Get the indices of all candidates of the block covered by the signature.
+	pub fn signed_candidates_indices(&self) -> &CandidateBitfield {
+		&self.signed_candidates_indices
+	}
+}
+
+impl From<crate::approval_db::v2::OurApproval> for OurApproval {
+	fn from(approval: crate::approval_db::v2::OurApproval) -> Self {
+		OurApproval {
+			signature: approval.signature,
+			signed_candidates_indices: approval.signed_candidates_indices,
+		}
+	}
+}
+
+impl From<OurApproval> for crate::approval_db::v2::OurApproval {
+	fn from(approval: OurApproval) -> Self {
+		Self {
+			signature: approval.signature,
+			signed_candidates_indices: approval.signed_candidates_indices,
+		}
+	}
+}
+
 /// Metadata regarding approval of a particular candidate within the context of some
 /// particular block.
 #[derive(Debug, Clone, PartialEq)]
@@ -78,7 +125,7 @@ pub struct ApprovalEntry {
 	tranches: Vec<TrancheEntry>,
 	backing_group: GroupIndex,
 	our_assignment: Option<OurAssignment>,
-	our_approval_sig: Option<ValidatorSignature>,
+	our_approval_sig: Option<OurApproval>,
 	// `n_validators` bits.
 	assignments: BitVec<u8, BitOrderLsb0>,
 	approved: bool,
@@ -90,7 +137,7 @@ impl ApprovalEntry {
 		tranches: Vec<TrancheEntry>,
 		backing_group: GroupIndex,
 		our_assignment: Option<OurAssignment>,
-		our_approval_sig: Option<ValidatorSignature>,
+		our_approval_sig: Option<OurApproval>,
 		// `n_validators` bits.
 		assignments: BitVec<u8, BitOrderLsb0>,
 		approved: bool,
@@ -107,7 +154,7 @@ impl ApprovalEntry {
 	pub fn trigger_our_assignment(
 		&mut self,
 		tick_now: Tick,
-	) -> Option<(AssignmentCert, ValidatorIndex, DelayTranche)> {
+	) -> Option<(AssignmentCertV2, ValidatorIndex, DelayTranche)> {
 		let our = self.our_assignment.as_mut().and_then(|a| {
 			if a.triggered() {
 				return None
@@ -125,7 +172,7 @@ impl ApprovalEntry {
 	}
 
 	/// Import our local approval vote signature for this candidate.
-	pub fn import_approval_sig(&mut self, approval_sig: ValidatorSignature) {
+	pub fn import_approval_sig(&mut self, approval_sig: OurApproval) {
 		self.our_approval_sig = Some(approval_sig);
 	}
 
@@ -209,7 +256,7 @@ impl ApprovalEntry {
 	/// Get the assignment cert & approval signature.
 	///
 	/// The approval signature will only be `Some` if the assignment is too.
-	pub fn local_statements(&self) -> (Option<OurAssignment>, Option<ValidatorSignature>) {
+	pub fn local_statements(&self) -> (Option<OurAssignment>, Option<OurApproval>) {
 		let approval_sig = self.our_approval_sig.clone();
 		if let Some(our_assignment) = self.our_assignment.as_ref().filter(|a| a.triggered()) {
 			(Some(our_assignment.clone()), approval_sig)
@@ -219,8 +266,8 @@ impl ApprovalEntry {
 	}
 }
 
-impl From<crate::approval_db::v1::ApprovalEntry> for ApprovalEntry {
-	fn from(entry: crate::approval_db::v1::ApprovalEntry) -> Self {
+impl From<crate::approval_db::v2::ApprovalEntry> for ApprovalEntry {
+	fn from(entry: crate::approval_db::v2::ApprovalEntry) -> Self {
 		ApprovalEntry {
 			tranches: entry.tranches.into_iter().map(Into::into).collect(),
 			backing_group: entry.backing_group,
@@ -232,7 +279,7 @@ impl From<crate::approval_db::v1::ApprovalEntry> for ApprovalEntry {
 	}
 }
 
-impl From<ApprovalEntry> for crate::approval_db::v1::ApprovalEntry {
+impl From<ApprovalEntry> for crate::approval_db::v2::ApprovalEntry {
 	fn from(entry: ApprovalEntry) -> Self {
 		Self {
 			tranches: entry.tranches.into_iter().map(Into::into).collect(),
@@ -290,8 +337,8 @@ impl CandidateEntry {
 	}
 }
 
-impl From<crate::approval_db::v1::CandidateEntry> for CandidateEntry {
-	fn from(entry: crate::approval_db::v1::CandidateEntry) -> Self {
+impl From<crate::approval_db::v2::CandidateEntry> for CandidateEntry {
+	fn from(entry: crate::approval_db::v2::CandidateEntry) -> Self {
 		CandidateEntry {
 			candidate: entry.candidate,
 			session: entry.session,
@@ -305,7 +352,7 @@ impl From<crate::approval_db::v1::CandidateEntry> for CandidateEntry {
 	}
 }
 
-impl From<CandidateEntry> for crate::approval_db::v1::CandidateEntry {
+impl From<CandidateEntry> for crate::approval_db::v2::CandidateEntry {
 	fn from(entry: CandidateEntry) -> Self {
 		Self {
 			candidate: entry.candidate,
@@ -414,8 +461,8 @@ impl BlockEntry {
 	}
 }
 
-impl From<crate::approval_db::v1::BlockEntry> for BlockEntry {
-	fn from(entry: crate::approval_db::v1::BlockEntry) -> Self {
+impl From<crate::approval_db::v2::BlockEntry> for BlockEntry {
+	fn from(entry: crate::approval_db::v2::BlockEntry) -> Self {
 		BlockEntry {
 			block_hash: entry.block_hash,
 			parent_hash: entry.parent_hash,
@@ -430,7 +477,7 @@ impl From<crate::approval_db::v1::BlockEntry> for BlockEntry {
 	}
 }
 
-impl From<BlockEntry> for crate::approval_db::v1::BlockEntry {
+impl From<BlockEntry> for crate::approval_db::v2::BlockEntry {
 	fn from(entry: BlockEntry) -> Self {
 		Self {
 			block_hash: entry.block_hash,
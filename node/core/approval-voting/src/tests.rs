@@ -17,8 +17,8 @@
 use super::*;
 use polkadot_node_primitives::{
 	approval::{
-		AssignmentCert, AssignmentCertKind, DelayTranche, VRFOutput, VRFProof,
-		RELAY_VRF_MODULO_CONTEXT,
+		AssignmentCertKindV2, AssignmentCertV2, CandidateBitfield, CoreBitfield, DelayTranche,
+		IndirectSignedApprovalVote, VRFOutput, VRFProof, RELAY_VRF_MODULO_CONTEXT,
 	},
 	AvailableData, BlockData, PoV,
 };
@@ -50,7 +50,7 @@ use std::{
 };
 
 use super::{
-	approval_db::v1::StoredBlockRange,
+	approval_db::v2::StoredBlockRange,
 	backend::BackendWriteOp,
 	import::tests::{
 		garbage_vrf, AllowedSlots, BabeEpoch, BabeEpochConfiguration, CompatibleDigestItem, Digest,
@@ -108,7 +108,7 @@ fn make_sync_oracle(val: bool) -> (Box<dyn SyncOracle + Send>, TestSyncOracleHan
 
 #[cfg(test)]
 pub mod test_constants {
-	use crate::approval_db::v1::Config as DatabaseConfig;
+	use crate::approval_db::v2::Config as DatabaseConfig;
 	const DATA_COL: u32 = 0;
 	pub(crate) const NUM_COLUMNS: u32 = 1;
 
@@ -233,18 +233,19 @@ where
 			polkadot_primitives::v2::CoreIndex,
 			polkadot_primitives::v2::GroupIndex,
 		)>,
+		_enable_v2_assignments: bool,
 	) -> HashMap<polkadot_primitives::v2::CoreIndex, criteria::OurAssignment> {
 		self.0()
 	}
 
 	fn check_assignment_cert(
 		&self,
-		_claimed_core_index: polkadot_primitives::v2::CoreIndex,
+		_claimed_core_indices: CoreBitfield,
 		validator_index: ValidatorIndex,
 		_config: &criteria::Config,
 		_relay_vrf_story: polkadot_node_primitives::approval::RelayVRFStory,
-		_assignment: &polkadot_node_primitives::approval::AssignmentCert,
-		_backing_group: polkadot_primitives::v2::GroupIndex,
+		_assignment: &AssignmentCertV2,
+		_backing_groups: Vec<polkadot_primitives::v2::GroupIndex>,
 	) -> Result<polkadot_node_primitives::approval::DelayTranche, criteria::InvalidAssignment> {
 		self.1(validator_index)
 	}
@@ -375,7 +376,7 @@ impl Backend for TestStore {
 	}
 }
 
-fn garbage_assignment_cert(kind: AssignmentCertKind) -> AssignmentCert {
+fn garbage_assignment_cert(kind: AssignmentCertKindV2) -> AssignmentCertV2 {
 	let ctx = schnorrkel::signing_context(RELAY_VRF_MODULO_CONTEXT);
 	let msg = b"test-garbage";
 	let mut prng = rand_core::OsRng;
@@ -383,7 +384,7 @@ fn garbage_assignment_cert(kind: AssignmentCertKind) -> AssignmentCert {
 	let (inout, proof, _) = keypair.vrf_sign(ctx.bytes(msg));
 	let out = inout.to_output();
 
-	AssignmentCert { kind, vrf: (VRFOutput(out), VRFProof(proof)) }
+	AssignmentCertV2 { kind, vrf: (VRFOutput(out), VRFProof(proof)) }
 }
 
 fn sign_approval(
@@ -394,6 +395,15 @@ fn sign_approval(
 	key.sign(&ApprovalVote(candidate_hash).signing_payload(session_index)).into()
 }
 
+fn sign_approval_multiple_candidates(
+	key: Sr25519Keyring,
+	candidate_hashes: &[CandidateHash],
+	session_index: SessionIndex,
+) -> ValidatorSignature {
+	key.sign(&ApprovalVoteMultipleCandidates(candidate_hashes).signing_payload(session_index))
+		.into()
+}
+
 type VirtualOverseer = test_helpers::TestSubsystemContextHandle<ApprovalVotingMessage>;
 
 #[derive(Default)]
@@ -484,6 +494,10 @@ fn test_harness<T: Future<Output = VirtualOverseer>>(
 			Config {
 				col_data: test_constants::TEST_CONFIG.col_data,
 				slot_duration_millis: SLOT_DURATION_MILLIS,
+				enable_v2_assignments: false,
+				max_approval_coalesce_count: 1,
+				max_approval_coalesce_wait_ticks: 0,
+				approval_lag_report_threshold: None,
 			},
 			Arc::new(db),
 			Arc::new(keystore),
@@ -585,7 +599,8 @@ async fn check_and_import_approval(
 		overseer,
 		FromOverseer::Communication {
 			msg: ApprovalVotingMessage::CheckAndImportApproval(
-				IndirectSignedApprovalVote { block_hash, candidate_index, validator, signature },
+				IndirectSignedApprovalVote { block_hash, candidate_index, validator, signature }
+					.into(),
 				tx,
 			),
 		},
@@ -625,12 +640,14 @@ async fn check_and_import_assignment(
 		overseer,
 		FromOverseer::Communication {
 			msg: ApprovalVotingMessage::CheckAndImportAssignment(
-				IndirectAssignmentCert {
+				IndirectAssignmentCertV2 {
 					block_hash,
 					validator,
-					cert: garbage_assignment_cert(AssignmentCertKind::RelayVRFModulo { sample: 0 }),
+					cert: garbage_assignment_cert(AssignmentCertKindV2::RelayVRFModulo {
+						sample: 0,
+					}),
 				},
-				candidate_index,
+				candidate_index.into(),
 				tx,
 			),
 		},
@@ -1071,14 +1088,14 @@ fn blank_subsystem_act_on_bad_block() {
 			&mut virtual_overseer,
 			FromOverseer::Communication {
 				msg: ApprovalVotingMessage::CheckAndImportAssignment(
-					IndirectAssignmentCert {
+					IndirectAssignmentCertV2 {
 						block_hash: bad_block_hash.clone(),
 						validator: 0u32.into(),
-						cert: garbage_assignment_cert(AssignmentCertKind::RelayVRFModulo {
+						cert: garbage_assignment_cert(AssignmentCertKindV2::RelayVRFModulo {
 							sample: 0,
 						}),
 					},
-					0u32,
+					0u32.into(),
 					tx,
 				),
 			},
@@ -1745,14 +1762,14 @@ fn linear_import_act_on_leaf() {
 			&mut virtual_overseer,
 			FromOverseer::Communication {
 				msg: ApprovalVotingMessage::CheckAndImportAssignment(
-					IndirectAssignmentCert {
+					IndirectAssignmentCertV2 {
 						block_hash: head,
 						validator: 0u32.into(),
-						cert: garbage_assignment_cert(AssignmentCertKind::RelayVRFModulo {
+						cert: garbage_assignment_cert(AssignmentCertKindV2::RelayVRFModulo {
 							sample: 0,
 						}),
 					},
-					0u32,
+					0u32.into(),
 					tx,
 				),
 			},
@@ -1815,14 +1832,14 @@ fn forkful_import_at_same_height_act_on_leaf() {
 				&mut virtual_overseer,
 				FromOverseer::Communication {
 					msg: ApprovalVotingMessage::CheckAndImportAssignment(
-						IndirectAssignmentCert {
+						IndirectAssignmentCertV2 {
 							block_hash: head,
 							validator: 0u32.into(),
-							cert: garbage_assignment_cert(AssignmentCertKind::RelayVRFModulo {
+							cert: garbage_assignment_cert(AssignmentCertKindV2::RelayVRFModulo {
 								sample: 0,
 							}),
 						},
-						0u32,
+						0u32.into(),
 						tx,
 					),
 				},
@@ -2118,6 +2135,434 @@ fn subsystem_import_checked_approval_sets_one_block_bit_at_a_time() {
 	});
 }
 
+#[test]
+fn subsystem_accepts_compact_assignment_for_multiple_candidates() {
+	let config = HarnessConfig::default();
+	let store = config.backend();
+	test_harness(config, |test_harness| async move {
+		let TestHarness { mut virtual_overseer, sync_oracle_handle: _sync_oracle_handle, .. } =
+			test_harness;
+
+		assert_matches!(
+			overseer_recv(&mut virtual_overseer).await,
+			AllMessages::ChainApi(ChainApiMessage::FinalizedBlockNumber(rx)) => {
+				rx.send(Ok(0)).unwrap();
+			}
+		);
+
+		let block_hash = Hash::repeat_byte(0x01);
+
+		let candidate_receipt1 = {
+			let mut receipt = dummy_candidate_receipt(block_hash);
+			receipt.descriptor.para_id = 1.into();
+			receipt
+		};
+		let candidate_receipt2 = {
+			let mut receipt = dummy_candidate_receipt(block_hash);
+			receipt.descriptor.para_id = 2.into();
+			receipt
+		};
+		let candidate_hash1 = candidate_receipt1.hash();
+		let candidate_hash2 = candidate_receipt2.hash();
+		let validator = ValidatorIndex(0);
+
+		ChainBuilder::new()
+			.add_block(
+				block_hash,
+				ChainBuilder::GENESIS_HASH,
+				1,
+				BlockConfig {
+					slot: Slot::from(0),
+					candidates: Some(vec![
+						(candidate_receipt1, CoreIndex(0), GroupIndex(1)),
+						(candidate_receipt2, CoreIndex(1), GroupIndex(1)),
+					]),
+					session_info: None,
+				},
+			)
+			.build(&mut virtual_overseer)
+			.await;
+
+		let claimed_candidates: CandidateBitfield = vec![0, 1].try_into().unwrap();
+		let cert = garbage_assignment_cert(AssignmentCertKindV2::RelayVRFModuloCompact {
+			core_bitfield: vec![CoreIndex(0), CoreIndex(1)].try_into().unwrap(),
+		});
+
+		for expected in [AssignmentCheckResult::Accepted, AssignmentCheckResult::AcceptedDuplicate]
+		{
+			let (tx, rx) = oneshot::channel();
+			overseer_send(
+				&mut virtual_overseer,
+				FromOverseer::Communication {
+					msg: ApprovalVotingMessage::CheckAndImportAssignment(
+						IndirectAssignmentCertV2 { block_hash, validator, cert: cert.clone() },
+						claimed_candidates.clone(),
+						tx,
+					),
+				},
+			)
+			.await;
+
+			assert_eq!(rx.await, Ok(expected));
+		}
+
+		// Sleep to get a consistent read on the database.
+		futures_timer::Delay::new(Duration::from_millis(100)).await;
+
+		for candidate_hash in [candidate_hash1, candidate_hash2] {
+			let candidate_entry = store.load_candidate_entry(&candidate_hash).unwrap().unwrap();
+			assert!(candidate_entry.approval_entry(&block_hash).unwrap().is_assigned(validator));
+		}
+
+		virtual_overseer
+	});
+}
+
+#[test]
+fn subsystem_rejects_assignment_claiming_unknown_candidate() {
+	test_harness(HarnessConfig::default(), |test_harness| async move {
+		let TestHarness { mut virtual_overseer, sync_oracle_handle: _sync_oracle_handle, .. } =
+			test_harness;
+
+		assert_matches!(
+			overseer_recv(&mut virtual_overseer).await,
+			AllMessages::ChainApi(ChainApiMessage::FinalizedBlockNumber(rx)) => {
+				rx.send(Ok(0)).unwrap();
+			}
+		);
+
+		let block_hash = Hash::repeat_byte(0x01);
+		let candidate_receipt = dummy_candidate_receipt(block_hash);
+
+		ChainBuilder::new()
+			.add_block(
+				block_hash,
+				ChainBuilder::GENESIS_HASH,
+				1,
+				BlockConfig {
+					slot: Slot::from(0),
+					candidates: Some(vec![(candidate_receipt, CoreIndex(0), GroupIndex(1))]),
+					session_info: None,
+				},
+			)
+			.build(&mut virtual_overseer)
+			.await;
+
+		// Candidate 0 exists, but candidate 1 does not.
+		let claimed_candidates: CandidateBitfield = vec![0, 1].try_into().unwrap();
+		let (tx, rx) = oneshot::channel();
+		overseer_send(
+			&mut virtual_overseer,
+			FromOverseer::Communication {
+				msg: ApprovalVotingMessage::CheckAndImportAssignment(
+					IndirectAssignmentCertV2 {
+						block_hash,
+						validator: ValidatorIndex(0),
+						cert: garbage_assignment_cert(
+							AssignmentCertKindV2::RelayVRFModuloCompact {
+								core_bitfield: vec![CoreIndex(0), CoreIndex(1)].try_into().unwrap(),
+							},
+						),
+					},
+					claimed_candidates,
+					tx,
+				),
+			},
+		)
+		.await;
+
+		assert_eq!(
+			rx.await,
+			Ok(AssignmentCheckResult::Bad(AssignmentCheckError::InvalidCandidateIndex(1))),
+		);
+
+		virtual_overseer
+	});
+}
+
+// Imports a compact assignment by `validator` covering both candidates of a block with two
+// candidates, returning the block hash and the candidate hashes.
+async fn import_block_with_two_assigned_candidates(
+	virtual_overseer: &mut VirtualOverseer,
+	validator: ValidatorIndex,
+) -> (Hash, [CandidateHash; 2]) {
+	let block_hash = Hash::repeat_byte(0x01);
+
+	let candidate_receipt1 = {
+		let mut receipt = dummy_candidate_receipt(block_hash);
+		receipt.descriptor.para_id = 1.into();
+		receipt
+	};
+	let candidate_receipt2 = {
+		let mut receipt = dummy_candidate_receipt(block_hash);
+		receipt.descriptor.para_id = 2.into();
+		receipt
+	};
+	let candidate_hashes = [candidate_receipt1.hash(), candidate_receipt2.hash()];
+
+	ChainBuilder::new()
+		.add_block(
+			block_hash,
+			ChainBuilder::GENESIS_HASH,
+			1,
+			BlockConfig {
+				slot: Slot::from(0),
+				candidates: Some(vec![
+					(candidate_receipt1, CoreIndex(0), GroupIndex(1)),
+					(candidate_receipt2, CoreIndex(1), GroupIndex(1)),
+				]),
+				session_info: None,
+			},
+		)
+		.build(virtual_overseer)
+		.await;
+
+	let (tx, rx) = oneshot::channel();
+	overseer_send(
+		virtual_overseer,
+		FromOverseer::Communication {
+			msg: ApprovalVotingMessage::CheckAndImportAssignment(
+				IndirectAssignmentCertV2 {
+					block_hash,
+					validator,
+					cert: garbage_assignment_cert(AssignmentCertKindV2::RelayVRFModuloCompact {
+						core_bitfield: vec![CoreIndex(0), CoreIndex(1)].try_into().unwrap(),
+					}),
+				},
+				vec![0 as CandidateIndex, 1].try_into().unwrap(),
+				tx,
+			),
+		},
+	)
+	.await;
+
+	assert_eq!(rx.await, Ok(AssignmentCheckResult::Accepted));
+
+	(block_hash, candidate_hashes)
+}
+
+#[test]
+fn subsystem_accepts_approval_for_multiple_candidates() {
+	let config = HarnessConfig::default();
+	let store = config.backend();
+	test_harness(config, |test_harness| async move {
+		let TestHarness { mut virtual_overseer, sync_oracle_handle: _sync_oracle_handle, .. } =
+			test_harness;
+
+		assert_matches!(
+			overseer_recv(&mut virtual_overseer).await,
+			AllMessages::ChainApi(ChainApiMessage::FinalizedBlockNumber(rx)) => {
+				rx.send(Ok(0)).unwrap();
+			}
+		);
+
+		let validator = ValidatorIndex(0);
+		let session_index = 1;
+		let (block_hash, candidate_hashes) =
+			import_block_with_two_assigned_candidates(&mut virtual_overseer, validator).await;
+
+		let signature = sign_approval_multiple_candidates(
+			Sr25519Keyring::Alice,
+			&candidate_hashes,
+			session_index,
+		);
+
+		let (tx, rx) = oneshot::channel();
+		overseer_send(
+			&mut virtual_overseer,
+			FromOverseer::Communication {
+				msg: ApprovalVotingMessage::CheckAndImportApproval(
+					IndirectSignedApprovalVoteV2 {
+						block_hash,
+						candidate_indices: vec![0 as CandidateIndex, 1].try_into().unwrap(),
+						validator,
+						signature,
+					},
+					tx,
+				),
+			},
+		)
+		.await;
+
+		assert_eq!(rx.await, Ok(ApprovalCheckResult::Accepted));
+
+		// The dispute coordinator is informed about the vote on each candidate.
+		let mut informed = HashSet::new();
+		while informed.len() < candidate_hashes.len() {
+			match overseer_recv(&mut virtual_overseer).await {
+				AllMessages::DisputeCoordinator(DisputeCoordinatorMessage::ImportStatements {
+					candidate_hash,
+					statements,
+					..
+				}) => {
+					assert_matches!(
+						statements[0].0.statement(),
+						DisputeStatement::Valid(
+							ValidDisputeStatementKind::ApprovalCheckingMultipleCandidates(c)
+						) => {
+							assert_eq!(c, &candidate_hashes.to_vec());
+						}
+					);
+					informed.insert(candidate_hash);
+				},
+				AllMessages::ChainSelection(ChainSelectionMessage::Approved(_)) => {},
+				msg => panic!("Unexpected message: {:?}", msg),
+			}
+		}
+
+		// Sleep to get a consistent read on the database.
+		futures_timer::Delay::new(Duration::from_millis(100)).await;
+
+		for candidate_hash in candidate_hashes {
+			let candidate_entry = store.load_candidate_entry(&candidate_hash).unwrap().unwrap();
+			assert!(candidate_entry.has_approved(validator));
+		}
+
+		virtual_overseer
+	});
+}
+
+#[test]
+fn subsystem_rejects_approval_for_multiple_candidates_with_wrong_signature() {
+	test_harness(HarnessConfig::default(), |test_harness| async move {
+		let TestHarness { mut virtual_overseer, sync_oracle_handle: _sync_oracle_handle, .. } =
+			test_harness;
+
+		assert_matches!(
+			overseer_recv(&mut virtual_overseer).await,
+			AllMessages::ChainApi(ChainApiMessage::FinalizedBlockNumber(rx)) => {
+				rx.send(Ok(0)).unwrap();
+			}
+		);
+
+		let validator = ValidatorIndex(0);
+		let session_index = 1;
+		let (block_hash, candidate_hashes) =
+			import_block_with_two_assigned_candidates(&mut virtual_overseer, validator).await;
+
+		// The signature only covers the first candidate, but the vote claims both.
+		let signature = sign_approval_multiple_candidates(
+			Sr25519Keyring::Alice,
+			&candidate_hashes[..1],
+			session_index,
+		);
+
+		let (tx, rx) = oneshot::channel();
+		overseer_send(
+			&mut virtual_overseer,
+			FromOverseer::Communication {
+				msg: ApprovalVotingMessage::CheckAndImportApproval(
+					IndirectSignedApprovalVoteV2 {
+						block_hash,
+						candidate_indices: vec![0 as CandidateIndex, 1].try_into().unwrap(),
+						validator,
+						signature,
+					},
+					tx,
+				),
+			},
+		)
+		.await;
+
+		assert_eq!(
+			rx.await,
+			Ok(ApprovalCheckResult::Bad(ApprovalCheckError::InvalidSignature(validator))),
+		);
+
+		virtual_overseer
+	});
+}
+
+#[test]
+fn no_shows_of_finalized_blocks_are_recorded() {
+	test_harness(HarnessConfig::default(), |test_harness| async move {
+		let TestHarness { mut virtual_overseer, clock, sync_oracle_handle: _sync_oracle_handle } =
+			test_harness;
+
+		assert_matches!(
+			overseer_recv(&mut virtual_overseer).await,
+			AllMessages::ChainApi(ChainApiMessage::FinalizedBlockNumber(rx)) => {
+				rx.send(Ok(0)).unwrap();
+			}
+		);
+
+		let validator = ValidatorIndex(0);
+		let session_index = 1;
+		let (block_hash, candidate_hashes) =
+			import_block_with_two_assigned_candidates(&mut virtual_overseer, validator).await;
+
+		// The assignment is well beyond its no-show deadline without an approval.
+		clock.inner.lock().set_tick(100);
+		futures_timer::Delay::new(Duration::from_millis(100)).await;
+
+		overseer_signal(&mut virtual_overseer, OverseerSignal::BlockFinalized(block_hash, 1)).await;
+
+		let (tx, rx) = oneshot::channel();
+		overseer_send(
+			&mut virtual_overseer,
+			FromOverseer::Communication {
+				msg: ApprovalVotingMessage::GetBlockNoShows(block_hash, tx),
+			},
+		)
+		.await;
+
+		let report = rx.await.unwrap().unwrap();
+		assert_eq!(report.block_hash, block_hash);
+		assert_eq!(report.session, session_index);
+		assert_eq!(report.candidates.len(), candidate_hashes.len());
+		for (candidate, candidate_hash) in report.candidates.iter().zip(candidate_hashes) {
+			assert_eq!(candidate.candidate_hash, candidate_hash);
+			assert_eq!(candidate.no_shows, vec![validator]);
+		}
+
+		let (tx, rx) = oneshot::channel();
+		overseer_send(
+			&mut virtual_overseer,
+			FromOverseer::Communication {
+				msg: ApprovalVotingMessage::GetValidatorNoShows(session_index, tx),
+			},
+		)
+		.await;
+
+		assert_eq!(rx.await.unwrap(), vec![(validator, 2)]);
+
+		virtual_overseer
+	});
+}
+
+#[test]
+fn pending_approvals_are_signed_when_full_or_due() {
+	let block_a = Hash::repeat_byte(0x01);
+	let block_b = Hash::repeat_byte(0x02);
+	let candidate_a = CandidateHash(Hash::repeat_byte(0x0a));
+	let candidate_b = CandidateHash(Hash::repeat_byte(0x0b));
+	let candidate_c = CandidateHash(Hash::repeat_byte(0x0c));
+	let validator = ValidatorIndex(0);
+
+	let mut pending = PendingApprovals::new(2, 4);
+	assert!(pending.is_enabled());
+	assert!(!PendingApprovals::new(1, 4).is_enabled());
+
+	assert!(!pending.note(block_a, validator, candidate_a, 10));
+	// Duplicates don't count towards the limit.
+	assert!(!pending.note(block_a, validator, candidate_a, 11));
+	assert!(!pending.note(block_b, validator, candidate_c, 12));
+	assert!(pending.note(block_a, validator, candidate_b, 12));
+
+	// Deadlines are kept per block, starting from the first noted approval.
+	assert_eq!(pending.deadlines.keys().copied().collect::<Vec<_>>(), vec![14, 16]);
+
+	// Taking the approvals of a block drops its deadline.
+	assert_eq!(pending.take(&block_a), Some((validator, vec![candidate_a, candidate_b])));
+	assert_eq!(pending.take(&block_a), None);
+	assert_eq!(pending.deadlines.keys().copied().collect::<Vec<_>>(), vec![16]);
+
+	let clock = MockClock::new(20);
+	assert_eq!(futures::executor::block_on(pending.next(&clock)), block_b);
+	assert_eq!(pending.take(&block_b), Some((validator, vec![candidate_c])));
+	assert!(pending.deadlines.is_empty());
+}
+
 fn approved_ancestor_test(
 	skip_approval: impl Fn(BlockNumber) -> bool,
 	approved_height: BlockNumber,
@@ -2270,8 +2715,10 @@ fn subsystem_validate_approvals_cache() {
 			let mut assignments = HashMap::new();
 			let _ = assignments.insert(
 				CoreIndex(0),
-				approval_db::v1::OurAssignment {
-					cert: garbage_assignment_cert(AssignmentCertKind::RelayVRFModulo { sample: 0 }),
+				approval_db::v2::OurAssignment {
+					cert: garbage_assignment_cert(AssignmentCertKindV2::RelayVRFModulo {
+						sample: 0,
+					}),
 					tranche: 0,
 					validator_index: ValidatorIndex(0),
 					triggered: false,
@@ -2381,9 +2828,9 @@ pub async fn handle_double_assignment_import(
 		overseer_recv(virtual_overseer).await,
 		AllMessages::ApprovalDistribution(ApprovalDistributionMessage::DistributeAssignment(
 			_,
-			c_index,
+			c_indices,
 		)) => {
-			assert_eq!(candidate_index, c_index);
+			assert_eq!(c_indices, candidate_index.into());
 		}
 	);
 
@@ -2396,9 +2843,9 @@ pub async fn handle_double_assignment_import(
 	for msg in vec![first_message, second_message].into_iter() {
 		match msg {
 			AllMessages::ApprovalDistribution(
-				ApprovalDistributionMessage::DistributeAssignment(_, c_index),
+				ApprovalDistributionMessage::DistributeAssignment(_, c_indices),
 			) => {
-				assert_eq!(candidate_index, c_index);
+				assert_eq!(c_indices, candidate_index.into());
 			},
 			AllMessages::CandidateValidation(
 				CandidateValidationMessage::ValidateFromExhaustive(_, _, _, _, timeout, tx),
@@ -2484,8 +2931,10 @@ where
 			let mut assignments = HashMap::new();
 			let _ = assignments.insert(
 				CoreIndex(0),
-				approval_db::v1::OurAssignment {
-					cert: garbage_assignment_cert(AssignmentCertKind::RelayVRFModulo { sample: 0 }),
+				approval_db::v2::OurAssignment {
+					cert: garbage_assignment_cert(AssignmentCertKindV2::RelayVRFModulo {
+						sample: 0,
+					}),
 					tranche: our_assigned_tranche,
 					validator_index: ValidatorIndex(0),
 					triggered: false,
@@ -3148,3 +3597,19 @@ fn waits_until_approving_assignments_are_old_enough() {
 		virtual_overseer
 	});
 }
+
+#[test]
+fn candidate_bitfields_are_checked_on_decode() {
+	use parity_scale_codec::{Decode, Encode};
+
+	let with_trailing_zeros = bitvec::bitvec![u8, bitvec::order::Lsb0; 0, 1, 0, 0].encode();
+	let bitfield = CandidateBitfield::decode(&mut &with_trailing_zeros[..]).unwrap();
+	assert_eq!(bitfield, CandidateBitfield::from(1 as CandidateIndex));
+	assert_eq!(bitfield.len(), 2);
+
+	let all_zeros = bitvec::bitvec![u8, bitvec::order::Lsb0; 0; 4].encode();
+	assert!(CandidateBitfield::decode(&mut &all_zeros[..]).is_err());
+
+	let empty = bitvec::vec::BitVec::<u8, bitvec::order::Lsb0>::new().encode();
+	assert!(CandidateBitfield::decode(&mut &empty[..]).is_err());
+}
@@ -32,40 +32,49 @@ use futures::{
 
 use polkadot_node_primitives::{
 	AvailableData, InvalidCandidate, PoV, SignedDisputeStatement, SignedFullStatement, Statement,
-	ValidationResult, BACKING_EXECUTION_TIMEOUT,
+	UncheckedSignedFullStatement, ValidationResult, BACKING_EXECUTION_TIMEOUT,
 };
 use polkadot_node_subsystem_util::{
 	self as util,
+	database::Database,
+	has_required_runtime,
 	metrics::{self, prometheus},
 	request_from_runtime, request_session_index_for_child, request_validator_groups,
 	request_validators, FromJobCommand, JobSender, Validator,
 };
-use polkadot_primitives::v2::{
-	BackedCandidate, CandidateCommitments, CandidateHash, CandidateReceipt, CollatorId,
-	CommittedCandidateReceipt, CoreIndex, CoreState, Hash, Id as ParaId, SessionIndex,
-	SigningContext, ValidatorId, ValidatorIndex, ValidatorSignature, ValidityAttestation,
+use polkadot_primitives::{
+	v2::{
+		BackedCandidate, CandidateCommitments, CandidateHash, CandidateReceipt, CollatorId,
+		CommittedCandidateReceipt, CoreIndex, CoreState, Hash, Id as ParaId, SessionIndex,
+		SigningContext, ValidatorId, ValidatorIndex, ValidatorSignature, ValidityAttestation,
+	},
+	vstaging::{BackingMisbehavior, BackingMisbehaviorReport},
 };
 use polkadot_subsystem::{
 	jaeger,
 	messages::{
 		AllMessages, AvailabilityDistributionMessage, AvailabilityStoreMessage,
 		CandidateBackingMessage, CandidateValidationMessage, CollatorProtocolMessage,
-		DisputeCoordinatorMessage, ProvisionableData, ProvisionerMessage, RuntimeApiRequest,
-		StatementDistributionMessage, ValidationFailed,
+		DisputeCoordinatorMessage, ProvisionableData, ProvisionerMessage, RuntimeApiMessage,
+		RuntimeApiRequest, StatementDistributionMessage, ValidationFailed,
 	},
 	overseer, ActivatedLeaf, PerLeafSpan, Stage, SubsystemSender,
 };
 use sp_keystore::SyncCryptoStorePtr;
 use statement_table::{
-	generic::AttestedCandidate as TableAttestedCandidate,
+	generic::{AttestedCandidate as TableAttestedCandidate, MultipleCandidates},
 	v2::{
-		SignedStatement as TableSignedStatement, Statement as TableStatement,
-		Summary as TableSummary,
+		Misbehavior as TableMisbehavior, SignedStatement as TableSignedStatement,
+		Statement as TableStatement, Summary as TableSummary,
 	},
 	Context as TableContextTrait, Table,
 };
 use thiserror::Error;
 
+mod persisted;
+pub use persisted::IssuedStatementsConfig;
+use persisted::IssuedStatementsStore;
+
 #[cfg(test)]
 mod tests;
 
@@ -98,6 +107,28 @@ pub enum Error {
 	UtilError(#[from] util::Error),
 }
 
+/// Configuration of the candidate backing jobs.
+#[derive(Clone)]
+pub struct CandidateBackingConfig {
+	keystore: SyncCryptoStorePtr,
+	issued_statements: IssuedStatementsStore,
+}
+
+impl CandidateBackingConfig {
+	/// Create a configuration signing statements with keys of the given keystore and
+	/// persisting the issued statements in the given DB.
+	pub fn new(
+		keystore: SyncCryptoStorePtr,
+		db: Arc<dyn Database>,
+		issued_statements_config: IssuedStatementsConfig,
+	) -> Self {
+		CandidateBackingConfig {
+			keystore,
+			issued_statements: IssuedStatementsStore::new(db, issued_statements_config),
+		}
+	}
+}
+
 /// PoV data to validate.
 enum PoVData {
 	/// Already available (from candidate selection).
@@ -165,6 +196,7 @@ pub struct CandidateBackingJob {
 	/// The candidates that are includable, by hash. Each entry here indicates
 	/// that we've sent the provisioner the backed candidate.
 	backed: HashSet<CandidateHash>,
+	statement_store: IssuedStatementsStore,
 	keystore: SyncCryptoStorePtr,
 	table: Table<TableContext>,
 	table_context: TableContext,
@@ -728,6 +760,22 @@ impl CandidateBackingJob {
 		root_span: &jaeger::Span,
 	) -> Result<Option<SignedFullStatement>, Error> {
 		if let Some(signed_statement) = self.sign_statement(statement).await {
+			// The statement must be persisted before anyone sees it, so we don't issue a
+			// conflicting one after a restart.
+			if let Err(err) = self.statement_store.write(
+				self.session_index,
+				self.parent,
+				&signed_statement.clone().into_unchecked(),
+			) {
+				gum::warn!(
+					target: LOG_TARGET,
+					relay_parent = ?self.parent,
+					?err,
+					"Failed to persist issued statement, not distributing it",
+				);
+				return Ok(None)
+			}
+
 			self.import_statement(sender, &signed_statement, root_span).await?;
 			let smsg = StatementDistributionMessage::Share(self.parent, signed_statement.clone());
 			sender.send_unbounded_message(smsg);
@@ -738,11 +786,84 @@ impl CandidateBackingJob {
 		}
 	}
 
+	/// Import and distribute again the statements we issued for this relay parent before
+	/// a restart, so we don't issue conflicting ones.
+	async fn restore_issued_statements(
+		&mut self,
+		sender: &mut JobSender<impl SubsystemSender>,
+		statements: Vec<UncheckedSignedFullStatement>,
+		root_span: &jaeger::Span,
+	) -> Result<(), Error> {
+		let validator = match self.table_context.validator.as_ref() {
+			Some(v) => (v.id(), v.index()),
+			None => return Ok(()),
+		};
+		let signing_context =
+			SigningContext { parent_hash: self.parent, session_index: self.session_index };
+
+		for statement in statements {
+			if statement.unchecked_validator_index() != validator.1 {
+				continue
+			}
+			let statement = match statement.try_into_checked(&signing_context, &validator.0) {
+				Ok(s) => s,
+				Err(_) => {
+					gum::warn!(
+						target: LOG_TARGET,
+						relay_parent = ?self.parent,
+						"Persisted statement has an invalid signature",
+					);
+					continue
+				},
+			};
+
+			let candidate_hash = statement.payload().candidate_hash();
+			if let Statement::Seconded(_) = statement.payload() {
+				self.seconded = Some(candidate_hash);
+			}
+			self.issued_statements.insert(candidate_hash);
+
+			gum::debug!(
+				target: LOG_TARGET,
+				?candidate_hash,
+				relay_parent = ?self.parent,
+				"Restored issued statement",
+			);
+
+			self.import_statement(sender, &statement, root_span).await?;
+			let smsg = StatementDistributionMessage::Share(self.parent, statement);
+			sender.send_unbounded_message(smsg);
+		}
+
+		Ok(())
+	}
+
 	/// Check if there have happened any new misbehaviors and issue necessary messages.
 	async fn issue_new_misbehaviors(&mut self, sender: &mut JobSender<impl SubsystemSender>) {
 		// collect the misbehaviors to avoid double mutable self borrow issues
 		let misbehaviors: Vec<_> = self.table.drain_misbehaviors().collect();
 		for (validator_id, report) in misbehaviors {
+			// Conflicting statements are provable on-chain, so report them to the runtime as
+			// well, if it supports such reports. The result of the submission is of no interest
+			// to us.
+			if let Some(report) = self.on_chain_misbehavior_report(validator_id, &report) {
+				if has_required_runtime(
+					self.parent,
+					RuntimeApiRequest::BACKING_MISBEHAVIOR_REPORT_RUNTIME_REQUIREMENT,
+					sender.subsystem_sender(),
+				)
+				.await
+				{
+					let (tx, _rx) = oneshot::channel();
+					sender
+						.send_message(RuntimeApiMessage::Request(
+							self.parent,
+							RuntimeApiRequest::StagingSubmitBackingMisbehaviorReport(report, tx),
+						))
+						.await;
+				}
+			}
+
 			sender
 				.send_message(ProvisionerMessage::ProvisionableData(
 					self.parent,
@@ -752,6 +873,35 @@ impl CandidateBackingJob {
 		}
 	}
 
+	/// Convert a misbehavior detected by the statement table into a report which can be
+	/// checked by the runtime, if it is of a kind the runtime can punish.
+	fn on_chain_misbehavior_report(
+		&self,
+		validator_index: ValidatorIndex,
+		misbehavior: &TableMisbehavior,
+	) -> Option<BackingMisbehaviorReport> {
+		let misbehavior = match misbehavior {
+			TableMisbehavior::MultipleCandidates(MultipleCandidates { first, second }) =>
+				BackingMisbehavior::MultipleCandidates {
+					first: (first.0.hash(), first.1.clone()),
+					second: (second.0.hash(), second.1.clone()),
+				},
+			// Seconding a candidate implies its validity, so an additional `Valid` statement is
+			// redundant rather than conflicting. Double signatures and unauthorized statements
+			// are not conflicting statements signed by the offender either.
+			TableMisbehavior::ValidityDoubleVote(_) |
+			TableMisbehavior::UnauthorizedStatement(_) |
+			TableMisbehavior::DoubleSign(_) => return None,
+		};
+
+		Some(BackingMisbehaviorReport {
+			session_index: self.session_index,
+			relay_parent: self.parent,
+			validator_index,
+			misbehavior,
+		})
+	}
+
 	/// Import a statement into the statement table and return the summary of the import.
 	async fn import_statement(
 		&mut self,
@@ -1162,19 +1312,20 @@ impl CandidateBackingJob {
 impl util::JobTrait for CandidateBackingJob {
 	type ToJob = CandidateBackingMessage;
 	type Error = Error;
-	type RunArgs = SyncCryptoStorePtr;
+	type RunArgs = CandidateBackingConfig;
 	type Metrics = Metrics;
 
 	const NAME: &'static str = "candidate-backing-job";
 
 	fn run<S: SubsystemSender>(
 		leaf: ActivatedLeaf,
-		keystore: SyncCryptoStorePtr,
+		config: CandidateBackingConfig,
 		metrics: Metrics,
 		rx_to: mpsc::Receiver<Self::ToJob>,
 		mut sender: JobSender<S>,
 	) -> Pin<Box<dyn Future<Output = Result<(), Self::Error>> + Send>> {
 		let parent = leaf.hash;
+		let CandidateBackingConfig { keystore, issued_statements: statement_store } = config;
 		async move {
 			macro_rules! try_runtime_api {
 				($x: expr) => {
@@ -1277,7 +1428,7 @@ impl util::JobTrait for CandidateBackingJob {
 			let _span = span.child("wait-for-job");
 
 			let (background_tx, background_rx) = mpsc::channel(16);
-			let job = CandidateBackingJob {
+			let mut job = CandidateBackingJob {
 				parent,
 				session_index,
 				assignment,
@@ -1288,6 +1439,7 @@ impl util::JobTrait for CandidateBackingJob {
 				seconded: None,
 				unbacked_candidates: HashMap::new(),
 				backed: HashSet::new(),
+				statement_store,
 				keystore,
 				table: Table::default(),
 				table_context,
@@ -1297,6 +1449,12 @@ impl util::JobTrait for CandidateBackingJob {
 			};
 			drop(_span);
 
+			let restored = job.statement_store.load(session_index, parent);
+			if !restored.is_empty() {
+				let _span = span.child("restore-issued-statements");
+				job.restore_issued_statements(&mut sender, restored, &span).await?;
+			}
+
 			job.run_loop(sender, rx_to, span).await
 		}
 		.boxed()
@@ -0,0 +1,161 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Persistence of the statements issued by this node.
+//!
+//! The statement table of a backing job only lives in memory, so a validator restarting
+//! while a relay parent is still active would forget which candidates it already seconded
+//! or attested and could end up equivocating. To prevent that, every statement we sign is
+//! written to the DB before it is distributed, and the statements issued for a relay parent
+//! are loaded again once a job for it is started.
+//!
+//! Every statement is stored under its own key, prefixed by the session and the relay parent
+//! it was issued in. Statements are pruned once they are more than a session older than the
+//! most recent statements written, by deleting the prefixes of their sessions.
+
+use std::sync::{
+	atomic::{AtomicU32, Ordering},
+	Arc,
+};
+
+use parity_scale_codec::{Decode, Encode};
+
+use polkadot_node_primitives::UncheckedSignedFullStatement;
+use polkadot_node_subsystem_util::database::{DBTransaction, Database};
+use polkadot_primitives::v2::{CandidateHash, Hash, SessionIndex};
+
+use crate::LOG_TARGET;
+
+const STATEMENTS_PREFIX: &[u8; 17] = b"BackingStatements";
+
+/// Configuration of the persisted statements issued by candidate backing.
+///
+/// The column needs to support iteration and removal by prefix.
+#[derive(Debug, Clone)]
+pub struct IssuedStatementsConfig {
+	/// The column of the DB the statements are stored in.
+	pub col_data: u32,
+}
+
+/// The statements issued by this node per relay parent, backed by the DB.
+///
+/// Cloning the store is cheap and all clones share the session older statements were pruned
+/// for.
+#[derive(Clone)]
+pub(crate) struct IssuedStatementsStore {
+	db: Arc<dyn Database>,
+	config: IssuedStatementsConfig,
+	// The newest session we pruned the statements of older sessions for.
+	pruned_for: Arc<AtomicU32>,
+}
+
+impl IssuedStatementsStore {
+	/// Create a store persisting statements in the given DB.
+	pub(crate) fn new(db: Arc<dyn Database>, config: IssuedStatementsConfig) -> Self {
+		IssuedStatementsStore { db, config, pruned_for: Arc::new(AtomicU32::new(0)) }
+	}
+
+	/// Load the statements issued for a relay parent of the given session. Their signatures are
+	/// not checked.
+	pub(crate) fn load(
+		&self,
+		session_index: SessionIndex,
+		relay_parent: Hash,
+	) -> Vec<UncheckedSignedFullStatement> {
+		let prefix = relay_parent_prefix(session_index, relay_parent);
+		self.db
+			.iter_with_prefix(self.config.col_data, &prefix)
+			.filter_map(|(_, raw)| match UncheckedSignedFullStatement::decode(&mut &raw[..]) {
+				Ok(statement) => Some(statement),
+				Err(err) => {
+					gum::warn!(
+						target: LOG_TARGET,
+						?relay_parent,
+						?err,
+						"Failed to decode issued statement",
+					);
+					None
+				},
+			})
+			.collect()
+	}
+
+	/// Write a statement issued for a relay parent of the given session.
+	///
+	/// The first statement of a new session prunes the statements of sessions older than the
+	/// previous one.
+	pub(crate) fn write(
+		&self,
+		session_index: SessionIndex,
+		relay_parent: Hash,
+		statement: &UncheckedSignedFullStatement,
+	) -> Result<(), std::io::Error> {
+		let col = self.config.col_data;
+		let mut tx = DBTransaction::new();
+
+		if self.pruned_for.fetch_max(session_index, Ordering::Relaxed) < session_index {
+			self.prune(&mut tx, session_index);
+		}
+
+		let candidate_hash = statement.unchecked_payload().candidate_hash();
+		tx.put_vec(
+			col,
+			&statement_key(session_index, relay_parent, candidate_hash),
+			statement.encode(),
+		);
+		self.db.write(tx)
+	}
+
+	/// Delete the statements of sessions older than the one before `session_index`.
+	fn prune(&self, tx: &mut DBTransaction, session_index: SessionIndex) {
+		let col = self.config.col_data;
+		let keep_from = session_index.saturating_sub(1);
+
+		// Keys are ordered by session, so the first one is of the oldest session we have.
+		let oldest_session = self
+			.db
+			.iter_with_prefix(col, STATEMENTS_PREFIX)
+			.next()
+			.and_then(|(key, _)| decode_session(&key));
+
+		if let Some(oldest_session) = oldest_session {
+			for session in oldest_session..keep_from {
+				tx.delete_prefix(col, &session_prefix(session));
+			}
+		}
+	}
+}
+
+fn session_prefix(session_index: SessionIndex) -> Vec<u8> {
+	(STATEMENTS_PREFIX, session_index.to_be_bytes()).encode()
+}
+
+fn relay_parent_prefix(session_index: SessionIndex, relay_parent: Hash) -> Vec<u8> {
+	(STATEMENTS_PREFIX, session_index.to_be_bytes(), relay_parent).encode()
+}
+
+fn statement_key(
+	session_index: SessionIndex,
+	relay_parent: Hash,
+	candidate_hash: CandidateHash,
+) -> Vec<u8> {
+	(STATEMENTS_PREFIX, session_index.to_be_bytes(), relay_parent, candidate_hash).encode()
+}
+
+fn decode_session(key: &[u8]) -> Option<SessionIndex> {
+	let session = key.get(STATEMENTS_PREFIX.len()..STATEMENTS_PREFIX.len() + 4)?;
+	Some(SessionIndex::from_be_bytes(session.try_into().ok()?))
+}
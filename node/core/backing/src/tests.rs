@@ -23,6 +23,7 @@ use assert_matches::assert_matches;
 use futures::{future, Future};
 use polkadot_node_primitives::{BlockData, InvalidCandidate};
 use polkadot_node_subsystem_test_helpers as test_helpers;
+use polkadot_node_subsystem_util::TimeoutExt;
 use polkadot_primitives::v2::{
 	CandidateDescriptor, CollatorId, GroupRotationInfo, HeadData, PersistedValidationData,
 	ScheduledCore,
@@ -36,7 +37,7 @@ use sp_keyring::Sr25519Keyring;
 use sp_keystore::{CryptoStore, SyncCryptoStore};
 use sp_tracing as _;
 use statement_table::v2::Misbehavior;
-use std::collections::HashMap;
+use std::{collections::HashMap, time::Duration};
 
 fn validator_pubkeys(val_ids: &[Sr25519Keyring]) -> Vec<ValidatorId> {
 	val_ids.iter().map(|v| v.public().into()).collect()
@@ -145,16 +146,32 @@ impl Default for TestState {
 
 type VirtualOverseer = test_helpers::TestSubsystemContextHandle<CandidateBackingMessage>;
 
+fn make_db() -> Arc<dyn Database> {
+	Arc::new(polkadot_node_subsystem_util::database::kvdb_impl::DbAdapter::new(
+		kvdb_memorydb::create(1),
+		&[0],
+	))
+}
+
 fn test_harness<T: Future<Output = VirtualOverseer>>(
 	keystore: SyncCryptoStorePtr,
 	test: impl FnOnce(VirtualOverseer) -> T,
+) {
+	test_harness_with_db(keystore, make_db(), test)
+}
+
+fn test_harness_with_db<T: Future<Output = VirtualOverseer>>(
+	keystore: SyncCryptoStorePtr,
+	db: Arc<dyn Database>,
+	test: impl FnOnce(VirtualOverseer) -> T,
 ) {
 	let pool = sp_core::testing::TaskExecutor::new();
 
 	let (context, virtual_overseer) = test_helpers::make_subsystem_context(pool.clone());
 
+	let config = CandidateBackingConfig::new(keystore, db, IssuedStatementsConfig { col_data: 0 });
 	let subsystem =
-		CandidateBackingSubsystem::new(pool.clone(), keystore, Metrics(None)).run(context);
+		CandidateBackingSubsystem::new(pool.clone(), config, Metrics(None)).run(context);
 
 	let test_fut = test(virtual_overseer);
 
@@ -1841,3 +1858,145 @@ fn observes_backing_even_if_not_validator() {
 		virtual_overseer
 	});
 }
+
+// Test that the statements issued before a restart are restored, so no other candidate is
+// seconded for the same relay parent afterwards.
+#[test]
+fn issued_statements_survive_restart() {
+	let test_state = TestState::default();
+	let db = make_db();
+
+	let pov = PoV { block_data: BlockData(vec![42, 43, 44]) };
+	let expected_head_data = test_state.head_data.get(&test_state.chain_ids[0]).unwrap().clone();
+	let candidate = TestCandidateBuilder {
+		para_id: test_state.chain_ids[0],
+		relay_parent: test_state.relay_parent,
+		pov_hash: pov.hash(),
+		head_data: expected_head_data.clone(),
+		erasure_root: make_erasure_root(&test_state, pov.clone()),
+		..Default::default()
+	}
+	.build();
+
+	let pov_b = PoV { block_data: BlockData(vec![45, 46, 47]) };
+	let candidate_b = TestCandidateBuilder {
+		para_id: test_state.chain_ids[0],
+		relay_parent: test_state.relay_parent,
+		pov_hash: pov_b.hash(),
+		head_data: expected_head_data.clone(),
+		erasure_root: make_erasure_root(&test_state, pov_b.clone()),
+		..Default::default()
+	}
+	.build();
+
+	let (test_state, candidate, candidate_b) = (&test_state, &candidate, &candidate_b);
+	let (pov, pov_b) = (&pov, &pov_b);
+
+	test_harness_with_db(
+		test_state.keystore.clone(),
+		db.clone(),
+		|mut virtual_overseer| async move {
+			test_startup(&mut virtual_overseer, &test_state).await;
+
+			let second = CandidateBackingMessage::Second(
+				test_state.relay_parent,
+				candidate.to_plain(),
+				pov.clone(),
+			);
+			virtual_overseer.send(FromOverseer::Communication { msg: second }).await;
+
+			assert_matches!(
+				virtual_overseer.recv().await,
+				AllMessages::CandidateValidation(
+					CandidateValidationMessage::ValidateFromChainState(_, _, _, tx)
+				) => {
+					tx.send(Ok(ValidationResult::Valid(
+						candidate.commitments.clone(),
+						test_state.validation_data.clone(),
+					)))
+					.unwrap();
+				}
+			);
+
+			assert_matches!(
+				virtual_overseer.recv().await,
+				AllMessages::AvailabilityStore(
+					AvailabilityStoreMessage::StoreAvailableData { tx, .. }
+				) => {
+					tx.send(Ok(())).unwrap();
+				}
+			);
+
+			test_dispute_coordinator_notifications(
+				&mut virtual_overseer,
+				candidate.hash(),
+				test_state.session(),
+				vec![ValidatorIndex(0)],
+			)
+			.await;
+
+			assert_matches!(
+				virtual_overseer.recv().await,
+				AllMessages::StatementDistribution(StatementDistributionMessage::Share(_, _))
+			);
+			assert_matches!(
+				virtual_overseer.recv().await,
+				AllMessages::CollatorProtocol(CollatorProtocolMessage::Seconded(_, _))
+			);
+
+			virtual_overseer
+		},
+	);
+
+	test_harness_with_db(test_state.keystore.clone(), db, |mut virtual_overseer| async move {
+		test_startup(&mut virtual_overseer, &test_state).await;
+
+		// The statement issued before the restart is imported and distributed again.
+		test_dispute_coordinator_notifications(
+			&mut virtual_overseer,
+			candidate.hash(),
+			test_state.session(),
+			vec![ValidatorIndex(0)],
+		)
+		.await;
+
+		assert_matches!(
+			virtual_overseer.recv().await,
+			AllMessages::StatementDistribution(
+				StatementDistributionMessage::Share(parent_hash, statement)
+			) if parent_hash == test_state.relay_parent => {
+				assert_eq!(statement.validator_index(), ValidatorIndex(0));
+				assert_matches!(
+					statement.payload(),
+					Statement::Seconded(c) if c.hash() == candidate.hash()
+				);
+			}
+		);
+
+		// We must not second another candidate for the same relay parent.
+		let second = CandidateBackingMessage::Second(
+			test_state.relay_parent,
+			candidate_b.to_plain(),
+			pov_b.clone(),
+		);
+		virtual_overseer.send(FromOverseer::Communication { msg: second }).await;
+
+		let (tx, rx) = oneshot::channel();
+		let msg = CandidateBackingMessage::GetBackedCandidates(
+			test_state.relay_parent,
+			vec![candidate.hash()],
+			tx,
+		);
+		virtual_overseer.send(FromOverseer::Communication { msg }).await;
+		assert!(rx.await.unwrap().is_empty());
+
+		assert!(virtual_overseer.recv().timeout(Duration::from_millis(100)).await.is_none());
+
+		virtual_overseer
+			.send(FromOverseer::Signal(OverseerSignal::ActiveLeaves(
+				ActiveLeavesUpdate::stop_work(test_state.relay_parent),
+			)))
+			.await;
+		virtual_overseer
+	});
+}
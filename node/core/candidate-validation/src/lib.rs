@@ -27,7 +27,9 @@ use polkadot_node_core_pvf::{
 	InvalidCandidate as WasmInvalidCandidate, PrepareError, Pvf, ValidationError, ValidationHost,
 };
 use polkadot_node_primitives::{
-	BlockData, InvalidCandidate, PoV, ValidationResult, POV_BOMB_LIMIT, VALIDATION_CODE_BOMB_LIMIT,
+	compression::{decompress_pov, pov_dictionary_hash_of},
+	BlockData, InvalidCandidate, PoV, PoVDictionary, ValidationResult, POV_BOMB_LIMIT,
+	VALIDATION_CODE_BOMB_LIMIT,
 };
 use polkadot_node_subsystem::{
 	errors::RuntimeApiError,
@@ -172,21 +174,33 @@ where
 					response_sender,
 				) => {
 					let bg = {
+						let mut sender = ctx.sender().clone();
 						let metrics = metrics.clone();
 						let validation_host = validation_host.clone();
 
 						async move {
 							let _timer = metrics.time_validate_from_exhaustive();
-							let res = validate_candidate_exhaustive(
-								validation_host,
-								persisted_validation_data,
-								validation_code,
-								candidate_receipt,
-								pov,
-								timeout,
-								&metrics,
+							let res = match fetch_pov_dictionary(
+								&mut sender,
+								&candidate_receipt.descriptor,
+								&pov,
 							)
-							.await;
+							.await
+							{
+								Ok(pov_dictionary) =>
+									validate_candidate_exhaustive(
+										validation_host,
+										persisted_validation_data,
+										validation_code,
+										candidate_receipt,
+										pov,
+										pov_dictionary,
+										timeout,
+										&metrics,
+									)
+									.await,
+								Err(e) => Err(e),
+							};
 
 							metrics.on_validation_event(&res);
 							let _ = response_sender.send(res);
@@ -278,6 +292,53 @@ where
 	.await
 }
 
+/// Fetch the dictionary the PoV of a candidate was compressed with, if any, from the state of
+/// the relay parent of the candidate.
+///
+/// `None` is returned if the PoV wasn't compressed with a dictionary, or if the dictionary isn't
+/// registered for the para at the relay parent, in which case the PoV fails to decompress.
+async fn fetch_pov_dictionary<Sender>(
+	sender: &mut Sender,
+	descriptor: &CandidateDescriptor,
+	pov: &PoV,
+) -> Result<Option<PoVDictionary>, ValidationFailed>
+where
+	Sender: SubsystemSender,
+{
+	let dictionary_hash = match pov_dictionary_hash_of(&pov.block_data.0) {
+		Some(hash) => hash,
+		None => return Ok(None),
+	};
+
+	let (tx, rx) = oneshot::channel();
+	sender
+		.send_message(
+			RuntimeApiMessage::Request(
+				descriptor.relay_parent,
+				RuntimeApiRequest::StagingPoVDictionary(descriptor.para_id, dictionary_hash, tx),
+			)
+			.into(),
+		)
+		.await;
+
+	match rx.await {
+		Ok(Ok(dictionary)) => Ok(dictionary.map(PoVDictionary::new)),
+		// Dictionaries can't be registered with runtimes lacking the staging API.
+		Ok(Err(RuntimeApiError::NotSupported { .. })) => Ok(None),
+		Ok(Err(e)) => {
+			gum::debug!(
+				target: LOG_TARGET,
+				relay_parent = ?descriptor.relay_parent,
+				err = ?e,
+				"Runtime API request internal error"
+			);
+
+			Err(ValidationFailed("PoV dictionary: Bad request".into()))
+		},
+		Err(_) => Err(ValidationFailed("PoV dictionary: Request dropped".into())),
+	}
+}
+
 async fn precheck_pvf<Sender>(
 	sender: &mut Sender,
 	mut validation_backend: impl ValidationBackend,
@@ -453,12 +514,15 @@ where
 			None => return Ok(ValidationResult::Invalid(InvalidCandidate::BadParent)),
 		};
 
+	let pov_dictionary = fetch_pov_dictionary(sender, &candidate_receipt.descriptor, &pov).await?;
+
 	let validation_result = validate_candidate_exhaustive(
 		validation_host,
 		validation_data,
 		validation_code,
 		candidate_receipt.clone(),
 		pov,
+		pov_dictionary,
 		timeout,
 		metrics,
 	)
@@ -499,6 +563,7 @@ async fn validate_candidate_exhaustive(
 	validation_code: ValidationCode,
 	candidate_receipt: CandidateReceipt,
 	pov: Arc<PoV>,
+	pov_dictionary: Option<PoVDictionary>,
 	timeout: Duration,
 	metrics: &Metrics,
 ) -> Result<ValidationResult, ValidationFailed> {
@@ -535,12 +600,13 @@ async fn validate_candidate_exhaustive(
 	};
 
 	let raw_block_data =
-		match sp_maybe_compressed_blob::decompress(&pov.block_data.0, POV_BOMB_LIMIT) {
-			Ok(block_data) => BlockData(block_data.to_vec()),
+		match decompress_pov(&pov.block_data.0, POV_BOMB_LIMIT, pov_dictionary.as_ref()) {
+			Ok(block_data) => BlockData(block_data),
 			Err(e) => {
 				gum::debug!(target: LOG_TARGET, err=?e, "Invalid PoV code");
 
-				// If the PoV is invalid, the candidate certainly is.
+				// If the PoV is invalid, the candidate certainly is. This includes PoVs
+				// compressed with a dictionary which isn't registered at the relay parent.
 				return Ok(ValidationResult::Invalid(InvalidCandidate::PoVDecompressionFailure))
 			},
 		};
@@ -19,6 +19,7 @@ use ::test_helpers::{dummy_hash, make_valid_candidate_descriptor};
 use assert_matches::assert_matches;
 use futures::executor;
 use polkadot_node_core_pvf::PrepareError;
+use polkadot_node_primitives::{compress_pov, PoVCompressionConfig};
 use polkadot_node_subsystem::messages::AllMessages;
 use polkadot_node_subsystem_test_helpers as test_helpers;
 use polkadot_node_subsystem_util::reexports::SubsystemContext;
@@ -423,6 +424,7 @@ fn candidate_validation_ok_is_ok() {
 		validation_code,
 		candidate_receipt,
 		Arc::new(pov),
+		None,
 		Duration::from_secs(0),
 		&Default::default(),
 	))
@@ -474,6 +476,7 @@ fn candidate_validation_bad_return_is_invalid() {
 		validation_code,
 		candidate_receipt,
 		Arc::new(pov),
+		None,
 		Duration::from_secs(0),
 		&Default::default(),
 	))
@@ -518,6 +521,7 @@ fn candidate_validation_timeout_is_internal_error() {
 		validation_code,
 		candidate_receipt,
 		Arc::new(pov),
+		None,
 		Duration::from_secs(0),
 		&Default::default(),
 	));
@@ -562,6 +566,7 @@ fn candidate_validation_commitment_hash_mismatch_is_invalid() {
 		validation_code,
 		candidate_receipt,
 		Arc::new(pov),
+		None,
 		Duration::from_secs(0),
 		&Default::default(),
 	))
@@ -607,6 +612,7 @@ fn candidate_validation_code_mismatch_is_invalid() {
 		validation_code,
 		candidate_receipt,
 		Arc::new(pov),
+		None,
 		Duration::from_secs(0),
 		&Default::default(),
 	))
@@ -663,6 +669,7 @@ fn compressed_code_works() {
 		validation_code,
 		candidate_receipt,
 		Arc::new(pov),
+		None,
 		Duration::from_secs(0),
 		&Default::default(),
 	));
@@ -710,6 +717,7 @@ fn code_decompression_failure_is_invalid() {
 		validation_code,
 		candidate_receipt,
 		Arc::new(pov),
+		None,
 		Duration::from_secs(0),
 		&Default::default(),
 	));
@@ -758,6 +766,7 @@ fn pov_decompression_failure_is_invalid() {
 		validation_code,
 		candidate_receipt,
 		Arc::new(pov),
+		None,
 		Duration::from_secs(0),
 		&Default::default(),
 	));
@@ -765,6 +774,130 @@ fn pov_decompression_failure_is_invalid() {
 	assert_matches!(v, Ok(ValidationResult::Invalid(InvalidCandidate::PoVDecompressionFailure)));
 }
 
+#[test]
+fn pov_compressed_with_dictionary_requires_it() {
+	let validation_data =
+		PersistedValidationData { max_pov_size: POV_BOMB_LIMIT as u32, ..Default::default() };
+	let head_data = HeadData(vec![1, 1, 1]);
+	let validation_code = ValidationCode(vec![2; 16]);
+
+	let dictionary = PoVDictionary::new(vec![3; 64]);
+	let pov = compress_pov(
+		PoV { block_data: BlockData(vec![3; 1024]) },
+		&PoVCompressionConfig::default(),
+		Some(&dictionary),
+	);
+	assert_eq!(pov_dictionary_hash_of(&pov.block_data.0), Some(dictionary.hash()));
+
+	let descriptor = make_valid_candidate_descriptor(
+		1.into(),
+		dummy_hash(),
+		validation_data.hash(),
+		pov.hash(),
+		validation_code.hash(),
+		head_data.hash(),
+		dummy_hash(),
+		Sr25519Keyring::Alice,
+	);
+
+	let validation_result = WasmValidationResult {
+		head_data,
+		new_validation_code: None,
+		upward_messages: Vec::new(),
+		horizontal_messages: Vec::new(),
+		processed_downward_messages: 0,
+		hrmp_watermark: 0,
+	};
+
+	let commitments = CandidateCommitments {
+		head_data: validation_result.head_data.clone(),
+		upward_messages: validation_result.upward_messages.clone(),
+		horizontal_messages: validation_result.horizontal_messages.clone(),
+		new_validation_code: validation_result.new_validation_code.clone(),
+		processed_downward_messages: validation_result.processed_downward_messages,
+		hrmp_watermark: validation_result.hrmp_watermark,
+	};
+
+	let candidate_receipt = CandidateReceipt { descriptor, commitments_hash: commitments.hash() };
+
+	let validate = |pov_dictionary| {
+		executor::block_on(validate_candidate_exhaustive(
+			MockValidateCandidateBackend::with_hardcoded_result(Ok(validation_result.clone())),
+			validation_data.clone(),
+			validation_code.clone(),
+			candidate_receipt.clone(),
+			Arc::new(pov.clone()),
+			pov_dictionary,
+			Duration::from_secs(0),
+			&Default::default(),
+		))
+	};
+
+	assert_matches!(validate(Some(dictionary)), Ok(ValidationResult::Valid(_, _)));
+	assert_matches!(
+		validate(None),
+		Ok(ValidationResult::Invalid(InvalidCandidate::PoVDecompressionFailure))
+	);
+	assert_matches!(
+		validate(Some(PoVDictionary::new(vec![4; 64]))),
+		Ok(ValidationResult::Invalid(InvalidCandidate::PoVDecompressionFailure))
+	);
+}
+
+#[test]
+fn pov_dictionary_is_fetched_at_the_relay_parent() {
+	let relay_parent = [2; 32].into();
+	let para_id = 5.into();
+
+	let dictionary = PoVDictionary::new(vec![3; 64]);
+	let pov = compress_pov(
+		PoV { block_data: BlockData(vec![3; 1024]) },
+		&PoVCompressionConfig::default(),
+		Some(&dictionary),
+	);
+
+	let descriptor = make_valid_candidate_descriptor(
+		para_id,
+		relay_parent,
+		dummy_hash(),
+		pov.hash(),
+		dummy_hash(),
+		dummy_hash(),
+		dummy_hash(),
+		Sr25519Keyring::Alice,
+	);
+
+	let pool = TaskExecutor::new();
+	let (mut ctx, mut ctx_handle) =
+		test_helpers::make_subsystem_context::<AllMessages, _>(pool.clone());
+
+	let (fetch_fut, fetch_result) =
+		fetch_pov_dictionary(ctx.sender(), &descriptor, &pov).remote_handle();
+
+	let test_fut = async move {
+		assert_matches!(
+			ctx_handle.recv().await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+				rp,
+				RuntimeApiRequest::StagingPoVDictionary(p, hash, tx),
+			)) => {
+				assert_eq!(rp, relay_parent);
+				assert_eq!(p, para_id);
+				assert_eq!(hash, dictionary.hash());
+
+				let _ = tx.send(Ok(Some(dictionary.data().to_vec())));
+			}
+		);
+
+		assert_matches!(fetch_result.await, Ok(Some(fetched)) => {
+			assert_eq!(fetched, dictionary);
+		});
+	};
+
+	let test_fut = future::join(test_fut, fetch_fut);
+	executor::block_on(test_fut);
+}
+
 struct MockPreCheckBackend {
 	result: Result<(), PrepareError>,
 }
@@ -19,10 +19,13 @@
 use polkadot_node_primitives::BlockWeight;
 use polkadot_node_subsystem::{
 	errors::ChainApiError,
-	messages::{ChainApiMessage, ChainSelectionMessage},
+	messages::{BlockViability, ChainApiMessage, ChainSelectionMessage, NonViableReason},
 	overseer, FromOverseer, OverseerSignal, SpawnedSubsystem, SubsystemContext, SubsystemError,
 };
-use polkadot_node_subsystem_util::database::Database;
+use polkadot_node_subsystem_util::{
+	database::Database,
+	metrics::{self, prometheus},
+};
 use polkadot_primitives::v2::{BlockNumber, ConsensusLog, Hash, Header};
 
 use futures::{channel::oneshot, future::Either, prelude::*};
@@ -46,9 +49,9 @@ const LOG_TARGET: &str = "parachain::chain-selection";
 /// Timestamp based on the 1 Jan 1970 UNIX base, which is persistent across node restarts and OS reboots.
 type Timestamp = u64;
 
-// If a block isn't approved in 120 seconds, nodes will abandon it
-// and begin building on another chain.
-const STAGNANT_TIMEOUT: Timestamp = 120;
+/// The default timeout for blocks to be approved. If a block isn't approved in 120 seconds,
+/// nodes will abandon it and begin building on another chain.
+pub const DEFAULT_STAGNANT_TIMEOUT: Duration = Duration::from_secs(120);
 
 #[derive(Debug, Clone)]
 enum Approval {
@@ -93,6 +96,21 @@ impl ViabilityCriteria {
 	fn is_parent_viable(&self) -> bool {
 		self.earliest_unviable_ancestor.is_none()
 	}
+
+	fn non_viable_reasons(&self) -> Vec<NonViableReason> {
+		let mut reasons = Vec::new();
+		if self.explicitly_reverted {
+			reasons.push(NonViableReason::RevertedByDispute);
+		}
+		if self.approval.is_stagnant() {
+			reasons.push(NonViableReason::Stagnant);
+		}
+		if let Some(ancestor) = self.earliest_unviable_ancestor {
+			reasons.push(NonViableReason::NonViableAncestor(ancestor));
+		}
+
+		reasons
+	}
 }
 
 // Light entries describing leaves of the chain.
@@ -182,6 +200,13 @@ impl BlockEntry {
 			self.viability.earliest_unviable_ancestor.or(Some(self.block_hash))
 		}
 	}
+
+	fn block_viability(&self) -> BlockViability {
+		BlockViability {
+			approved: matches!(self.viability.approval, Approval::Approved),
+			non_viable_reasons: self.viability.non_viable_reasons(),
+		}
+	}
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -214,6 +239,51 @@ impl Error {
 	}
 }
 
+#[derive(Clone)]
+struct MetricsInner {
+	stagnant_leaves: prometheus::Gauge<prometheus::U64>,
+	reverted_leaves: prometheus::Gauge<prometheus::U64>,
+}
+
+/// Chain selection metrics.
+#[derive(Default, Clone)]
+pub struct Metrics(Option<MetricsInner>);
+
+impl Metrics {
+	fn is_enabled(&self) -> bool {
+		self.0.is_some()
+	}
+
+	fn on_unviable_leaves(&self, leaves: &crate::tree::UnviableLeaves) {
+		if let Some(metrics) = &self.0 {
+			metrics.stagnant_leaves.set(leaves.stagnant as u64);
+			metrics.reverted_leaves.set(leaves.reverted as u64);
+		}
+	}
+}
+
+impl metrics::Metrics for Metrics {
+	fn try_register(registry: &prometheus::Registry) -> Result<Self, prometheus::PrometheusError> {
+		let metrics = MetricsInner {
+			stagnant_leaves: prometheus::register(
+				prometheus::Gauge::new(
+					"polkadot_parachain_chain_selection_stagnant_leaves",
+					"Number of leaves which aren't viable due to a stagnant block in their ancestry.",
+				)?,
+				registry,
+			)?,
+			reverted_leaves: prometheus::register(
+				prometheus::Gauge::new(
+					"polkadot_parachain_chain_selection_reverted_leaves",
+					"Number of leaves which aren't viable due to a reverted block in their ancestry.",
+				)?,
+				registry,
+			)?,
+		};
+		Ok(Metrics(Some(metrics)))
+	}
+}
+
 /// A clock used for fetching the current timestamp.
 pub trait Clock {
 	/// Get the current timestamp.
@@ -247,7 +317,7 @@ impl Clock for SystemClock {
 	}
 }
 
-/// The interval, in seconds to check for stagnant blocks.
+/// The interval at which to check for stagnant blocks.
 #[derive(Debug, Clone)]
 pub struct StagnantCheckInterval(Option<Duration>);
 
@@ -266,7 +336,10 @@ impl Default for StagnantCheckInterval {
 
 impl StagnantCheckInterval {
 	/// Create a new stagnant-check interval wrapping the given duration.
+	///
+	/// Panics if the interval is zero.
 	pub fn new(interval: Duration) -> Self {
+		assert!(!interval.is_zero(), "stagnant-check interval must be non-zero");
 		StagnantCheckInterval(Some(interval))
 	}
 
@@ -301,19 +374,24 @@ pub struct Config {
 	pub col_data: u32,
 	/// How often to check for stagnant blocks.
 	pub stagnant_check_interval: StagnantCheckInterval,
+	/// How long to wait for a block to be approved before considering it stagnant.
+	///
+	/// Stagnation is tracked in whole seconds, so this is rounded up to the next second.
+	pub stagnant_timeout: Duration,
 }
 
 /// The chain selection subsystem.
 pub struct ChainSelectionSubsystem {
 	config: Config,
 	db: Arc<dyn Database>,
+	metrics: Metrics,
 }
 
 impl ChainSelectionSubsystem {
-	/// Create a new instance of the subsystem with the given config
-	/// and key-value store.
-	pub fn new(config: Config, db: Arc<dyn Database>) -> Self {
-		ChainSelectionSubsystem { config, db }
+	/// Create a new instance of the subsystem with the given config,
+	/// key-value store and metrics.
+	pub fn new(config: Config, db: Arc<dyn Database>, metrics: Metrics) -> Self {
+		ChainSelectionSubsystem { config, db, metrics }
 	}
 }
 
@@ -329,26 +407,49 @@ where
 		);
 
 		SpawnedSubsystem {
-			future: run(ctx, backend, self.config.stagnant_check_interval, Box::new(SystemClock))
-				.map(Ok)
-				.boxed(),
+			future: run(
+				ctx,
+				backend,
+				self.config.stagnant_check_interval,
+				self.config.stagnant_timeout,
+				Box::new(SystemClock),
+				self.metrics,
+			)
+			.map(Ok)
+			.boxed(),
 			name: "chain-selection-subsystem",
 		}
 	}
 }
 
+// Converts a duration to a `Timestamp` offset, rounding up so that blocks are never considered
+// stagnant before the full duration has passed.
+fn timestamp_ceil(duration: Duration) -> Timestamp {
+	duration.as_secs() + u64::from(duration.subsec_nanos() > 0)
+}
+
 async fn run<Context, B>(
 	mut ctx: Context,
 	mut backend: B,
 	stagnant_check_interval: StagnantCheckInterval,
+	stagnant_timeout: Duration,
 	clock: Box<dyn Clock + Send + Sync>,
+	metrics: Metrics,
 ) where
 	Context: SubsystemContext<Message = ChainSelectionMessage>,
 	Context: overseer::SubsystemContext<Message = ChainSelectionMessage>,
 	B: Backend,
 {
 	loop {
-		let res = run_until_error(&mut ctx, &mut backend, &stagnant_check_interval, &*clock).await;
+		let res = run_until_error(
+			&mut ctx,
+			&mut backend,
+			&stagnant_check_interval,
+			timestamp_ceil(stagnant_timeout),
+			&*clock,
+			&metrics,
+		)
+		.await;
 		match res {
 			Err(e) => {
 				e.trace();
@@ -372,7 +473,9 @@ async fn run_until_error<Context, B>(
 	ctx: &mut Context,
 	backend: &mut B,
 	stagnant_check_interval: &StagnantCheckInterval,
+	stagnant_timeout: Timestamp,
 	clock: &(dyn Clock + Sync),
+	metrics: &Metrics,
 ) -> Result<(), Error>
 where
 	Context: SubsystemContext<Message = ChainSelectionMessage>,
@@ -393,7 +496,7 @@ where
 							let write_ops = handle_active_leaf(
 								ctx,
 								&*backend,
-								clock.timestamp_now() + STAGNANT_TIMEOUT,
+								clock.timestamp_now() + stagnant_timeout,
 								leaf.hash,
 							).await?;
 
@@ -401,11 +504,11 @@ where
 						}
 					}
 					FromOverseer::Signal(OverseerSignal::BlockFinalized(h, n)) => {
-						handle_finalized_block(backend, h, n)?
+						handle_finalized_block(backend, h, n)?;
 					}
 					FromOverseer::Communication { msg } => match msg {
 						ChainSelectionMessage::Approved(hash) => {
-							handle_approved_block(backend, hash)?
+							handle_approved_block(backend, hash)?;
 						}
 						ChainSelectionMessage::Leaves(tx) => {
 							let leaves = load_leaves(ctx, &*backend).await?;
@@ -424,11 +527,19 @@ where
 
 							let _ = tx.send(best_containing);
 						}
+						ChainSelectionMessage::Viability(hash, tx) => {
+							let viability = backend
+								.load_block_entry(&hash)?
+								.map(|entry| entry.block_viability());
+
+							let _ = tx.send(viability);
+						}
 					}
 				}
 			}
 			_ = stagnant_check_stream.next().fuse() => {
 				detect_stagnant(backend, clock.timestamp_now())?;
+				note_unviable_leaves(&*backend, metrics)?;
 			}
 		}
 	}
@@ -641,6 +752,16 @@ fn detect_stagnant(backend: &mut impl Backend, now: Timestamp) -> Result<(), Err
 	backend.write(ops)
 }
 
+// Update the metrics of unviable leaves. This walks the whole tree of unfinalized
+// blocks, so it is only done on the stagnant check and skipped when metrics are disabled.
+fn note_unviable_leaves(backend: &impl Backend, metrics: &Metrics) -> Result<(), Error> {
+	if metrics.is_enabled() {
+		metrics.on_unviable_leaves(&crate::tree::count_unviable_leaves(backend)?);
+	}
+
+	Ok(())
+}
+
 // Load the leaves from the backend. If there are no leaves, then return
 // the finalized block.
 async fn load_leaves(
@@ -226,6 +226,7 @@ impl Clock for TestClock {
 }
 
 const TEST_STAGNANT_INTERVAL: Duration = Duration::from_millis(20);
+const STAGNANT_TIMEOUT: Timestamp = DEFAULT_STAGNANT_TIMEOUT.as_secs();
 
 type VirtualOverseer = test_helpers::TestSubsystemContextHandle<ChainSelectionMessage>;
 
@@ -241,7 +242,9 @@ fn test_harness<T: Future<Output = VirtualOverseer>>(
 		context,
 		backend.clone(),
 		StagnantCheckInterval::new(TEST_STAGNANT_INTERVAL),
+		DEFAULT_STAGNANT_TIMEOUT,
 		Box::new(clock.clone()),
+		Metrics::default(),
 	);
 
 	let test_fut = test(backend, clock, virtual_overseer);
@@ -633,6 +636,20 @@ async fn approve_block(
 	write_rx.await.unwrap()
 }
 
+#[test]
+fn stagnant_timeout_is_rounded_up_to_whole_seconds() {
+	assert_eq!(timestamp_ceil(Duration::from_secs(120)), 120);
+	assert_eq!(timestamp_ceil(Duration::from_millis(1500)), 2);
+	assert_eq!(timestamp_ceil(Duration::from_millis(500)), 1);
+	assert_eq!(timestamp_ceil(Duration::ZERO), 0);
+}
+
+#[test]
+#[should_panic]
+fn zero_stagnant_check_interval_panics() {
+	StagnantCheckInterval::new(Duration::ZERO);
+}
+
 #[test]
 fn no_op_subsystem_run() {
 	test_harness(|_, _, virtual_overseer| async move { virtual_overseer });
@@ -2008,3 +2025,129 @@ fn stagnant_makes_childless_parent_leaf() {
 		virtual_overseer
 	})
 }
+
+async fn block_viability(
+	virtual_overseer: &mut VirtualOverseer,
+	block_hash: Hash,
+) -> Option<BlockViability> {
+	let (tx, rx) = oneshot::channel();
+	virtual_overseer
+		.send(FromOverseer::Communication { msg: ChainSelectionMessage::Viability(block_hash, tx) })
+		.await;
+
+	rx.await.unwrap()
+}
+
+#[test]
+fn viability_query_reports_reversion() {
+	test_harness(|backend, _, mut virtual_overseer| async move {
+		let finalized_number = 0;
+		let finalized_hash = Hash::repeat_byte(0);
+
+		// F <- A1 <- A2 <- A3.
+		//
+		// A3 reverts A1
+
+		let (a3_hash, chain_a) =
+			construct_chain_on_base(vec![1, 2, 3], finalized_number, finalized_hash, |h| {
+				if h.number == 3 {
+					add_reversions(h, Some(1))
+				}
+			});
+
+		let (_, a1_hash, _) = extract_info_from_chain(0, &chain_a);
+
+		import_blocks_into(
+			&mut virtual_overseer,
+			&backend,
+			Some((finalized_number, finalized_hash)),
+			chain_a.clone(),
+		)
+		.await;
+
+		assert_eq!(
+			block_viability(&mut virtual_overseer, a1_hash).await,
+			Some(BlockViability {
+				approved: false,
+				non_viable_reasons: vec![NonViableReason::RevertedByDispute],
+			}),
+		);
+		assert_eq!(
+			block_viability(&mut virtual_overseer, a3_hash).await,
+			Some(BlockViability {
+				approved: false,
+				non_viable_reasons: vec![NonViableReason::NonViableAncestor(a1_hash)],
+			}),
+		);
+		assert_eq!(block_viability(&mut virtual_overseer, finalized_hash).await, None);
+
+		assert_eq!(
+			crate::tree::count_unviable_leaves(&backend).unwrap(),
+			crate::tree::UnviableLeaves { stagnant: 0, reverted: 1 },
+		);
+
+		virtual_overseer
+	});
+}
+
+#[test]
+fn viability_query_reports_stagnant() {
+	test_harness(|backend, clock, mut virtual_overseer| async move {
+		let finalized_number = 0;
+		let finalized_hash = Hash::repeat_byte(0);
+
+		// F <- A1 <- A2
+
+		let (a2_hash, chain_a) =
+			construct_chain_on_base(vec![1, 2], finalized_number, finalized_hash, |h| {
+				salt_header(h, b"a");
+			});
+
+		let (_, a1_hash, _) = extract_info_from_chain(0, &chain_a);
+
+		import_chains_into_empty(
+			&mut virtual_overseer,
+			&backend,
+			finalized_number,
+			finalized_hash,
+			vec![chain_a.clone()],
+		)
+		.await;
+
+		approve_block(&mut virtual_overseer, &backend, a2_hash).await;
+
+		assert_eq!(
+			block_viability(&mut virtual_overseer, a2_hash).await,
+			Some(BlockViability { approved: true, non_viable_reasons: Vec::new() }),
+		);
+
+		{
+			let (_, write_rx) = backend.await_next_write();
+			clock.inc_by(STAGNANT_TIMEOUT);
+
+			write_rx.await.unwrap();
+		}
+
+		assert_eq!(
+			block_viability(&mut virtual_overseer, a1_hash).await,
+			Some(BlockViability {
+				approved: false,
+				non_viable_reasons: vec![NonViableReason::Stagnant],
+			}),
+		);
+		assert_eq!(
+			block_viability(&mut virtual_overseer, a2_hash).await,
+			Some(BlockViability {
+				approved: true,
+				non_viable_reasons: vec![NonViableReason::NonViableAncestor(a1_hash)],
+			}),
+		);
+
+		assert_eq!(
+			crate::tree::count_unviable_leaves(&backend).unwrap(),
+			crate::tree::UnviableLeaves { stagnant: 1, reverted: 0 },
+		);
+
+		virtual_overseer
+	})
+}
@@ -561,3 +561,55 @@ pub(super) fn detect_stagnant<'a, B: 'a + Backend>(
 
 	Ok(backend)
 }
+
+/// The number of leaves which aren't viable, by the reason of their unviability.
+#[derive(Debug, Default, PartialEq)]
+pub(super) struct UnviableLeaves {
+	/// Leaves with a stagnant block, but no reverted block, in their ancestry.
+	pub(super) stagnant: usize,
+	/// Leaves with a reverted block in their ancestry.
+	pub(super) reverted: usize,
+}
+
+/// Count the leaves of the tree of unfinalized blocks which aren't viable.
+///
+/// Reversion takes precedence over stagnation, as blocks can still be approved after
+/// becoming stagnant but reversions are final.
+pub(super) fn count_unviable_leaves(backend: &impl Backend) -> Result<UnviableLeaves, Error> {
+	let mut unviable_leaves = UnviableLeaves::default();
+
+	let first_block_number = match backend.load_first_block_number()? {
+		None => return Ok(unviable_leaves),
+		Some(n) => n,
+	};
+
+	// Blocks to visit, along with whether a reverted or stagnant block has been
+	// encountered in their ancestry.
+	let mut frontier: Vec<_> = backend
+		.load_blocks_by_number(first_block_number)?
+		.into_iter()
+		.map(|h| (h, false, false))
+		.collect();
+
+	while let Some((block_hash, reverted, stagnant)) = frontier.pop() {
+		let entry = match backend.load_block_entry(&block_hash)? {
+			None => continue,
+			Some(entry) => entry,
+		};
+
+		let reverted = reverted || entry.viability.explicitly_reverted;
+		let stagnant = stagnant || entry.viability.approval.is_stagnant();
+
+		if entry.children.is_empty() {
+			if reverted {
+				unviable_leaves.reverted += 1;
+			} else if stagnant {
+				unviable_leaves.stagnant += 1;
+			}
+		}
+
+		frontier.extend(entry.children.into_iter().map(|h| (h, reverted, stagnant)));
+	}
+
+	Ok(unviable_leaves)
+}
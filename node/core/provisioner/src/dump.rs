@@ -0,0 +1,411 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Dumping of the inherent data assembled by the provisioner, for post-mortem analysis.
+//!
+//! When enabled, a record of everything the provisioner had available when asked for the
+//! inherent data of a block, what made it into the inherent data and why the rest didn't, is
+//! written to a directory. Only the most recent records are kept.
+
+use crate::LOG_TARGET;
+use polkadot_node_subsystem::{messages::ProvisionerInherentData, ActivatedLeaf, LeafStatus};
+use polkadot_primitives::v2::{
+	BlockNumber, CandidateHash, CandidateReceipt, CoreState, DisputeStatement, DisputeStatementSet,
+	SessionIndex, SignedAvailabilityBitfield, ValidatorIndex,
+};
+use serde::Serialize;
+use std::{
+	collections::{HashMap, HashSet},
+	fs, io,
+	path::PathBuf,
+	time::Duration,
+};
+
+/// Configuration of the inherent data dump.
+#[derive(Debug, Clone)]
+pub struct InherentDataDumpConfig {
+	/// The directory the records are written to.
+	pub path: PathBuf,
+	/// The maximum number of records kept. The oldest records are removed first.
+	pub max_records: usize,
+}
+
+impl InherentDataDumpConfig {
+	/// The number of records kept by default.
+	pub const DEFAULT_MAX_RECORDS: usize = 128;
+
+	/// Dump the inherent data to `path`, keeping the default number of records.
+	pub fn new(path: PathBuf) -> Self {
+		InherentDataDumpConfig { path, max_records: Self::DEFAULT_MAX_RECORDS }
+	}
+
+	/// Write `record` on a thread of its own, as writing blocks on the file system.
+	///
+	/// The job doesn't wait for the record to be written, so failures are only logged.
+	pub(crate) fn spawn_write(&self, record: InherentDataRecord) {
+		let config = self.clone();
+		let spawned =
+			std::thread::Builder::new().name("provisioner-dump".into()).spawn(move || {
+				if let Err(err) = config.write(&record) {
+					gum::warn!(
+						target: LOG_TARGET,
+						?err,
+						path = ?config.path,
+						"failed to dump inherent data",
+					);
+				}
+			});
+
+		if let Err(err) = spawned {
+			gum::warn!(target: LOG_TARGET, ?err, "failed to spawn the inherent data dump thread");
+		}
+	}
+
+	/// Write `record` to the dump directory and remove the oldest records beyond
+	/// `max_records`.
+	pub(crate) fn write(&self, record: &InherentDataRecord) -> io::Result<()> {
+		fs::create_dir_all(&self.path)?;
+
+		let file_name = format!("{:010}-{}.json", record.block_number, record.relay_parent);
+		let encoded = serde_json::to_vec_pretty(record)
+			.map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+		fs::write(self.path.join(file_name), encoded)?;
+
+		let mut records = fs::read_dir(&self.path)?
+			.filter_map(|entry| entry.ok().map(|entry| entry.path()))
+			.filter(|path| path.extension().map_or(false, |ext| ext == "json"))
+			.collect::<Vec<_>>();
+		if records.len() > self.max_records {
+			// Records are named by block number first, so they sort oldest first.
+			records.sort();
+			for path in &records[..records.len() - self.max_records] {
+				// Concurrent writes may race to remove the same record.
+				match fs::remove_file(path) {
+					Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+					_ => {},
+				}
+			}
+		}
+
+		Ok(())
+	}
+}
+
+/// Why an item available to the provisioner was excluded from the inherent data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ExclusionReason {
+	/// Bitfields are not included on stale leaves.
+	StaleLeaf,
+	/// The bitfield doesn't have exactly one bit per availability core.
+	BitfieldLengthMismatch,
+	/// The bitfield has a bit set for an unoccupied core.
+	BitfieldUnoccupiedCore,
+	/// A bitfield of the same validator with more bits set was selected.
+	BitfieldSuperseded,
+	/// No core could be occupied by the candidate.
+	NoSuitableCore,
+	/// Candidate backing didn't provide the candidate as backed.
+	NotBacked,
+	/// Another candidate upgrading the validation code was selected.
+	ExtraCodeUpgrade,
+	/// The dispute wasn't forwarded to the runtime, as there were too many recent disputes.
+	TooManyDisputes,
+	/// The dispute coordinator didn't provide votes for the dispute.
+	NoVotes,
+	/// The item didn't fit into the block weight limit.
+	Overweight,
+}
+
+/// The items excluded from the inherent data while assembling it, along with the reasons.
+#[derive(Debug, Default)]
+pub(crate) struct Exclusions {
+	/// Excluded bitfields, by their position in the list of available bitfields.
+	bitfields: HashMap<usize, ExclusionReason>,
+	candidates: HashMap<CandidateHash, ExclusionReason>,
+	disputes: HashMap<(SessionIndex, CandidateHash), ExcludedDispute>,
+}
+
+#[derive(Debug)]
+struct ExcludedDispute {
+	reason: ExclusionReason,
+	votes: Option<Votes>,
+}
+
+impl Exclusions {
+	/// Note the reasons why the `available` bitfields which are not in `selected` were excluded.
+	pub(crate) fn note_bitfields(
+		&mut self,
+		cores: &[CoreState],
+		stale_leaf: bool,
+		available: &[SignedAvailabilityBitfield],
+		selected: &[SignedAvailabilityBitfield],
+	) {
+		let selected = by_validator(selected);
+		for (idx, bitfield) in available.iter().enumerate() {
+			if selected.get(&bitfield.validator_index()) == Some(&bitfield) {
+				continue
+			}
+
+			let payload = &bitfield.payload().0;
+			let reason = if stale_leaf {
+				ExclusionReason::StaleLeaf
+			} else if payload.len() != cores.len() {
+				ExclusionReason::BitfieldLengthMismatch
+			} else if cores
+				.iter()
+				.zip(payload.iter())
+				.any(|(core, bit)| *bit && !core.is_occupied())
+			{
+				ExclusionReason::BitfieldUnoccupiedCore
+			} else {
+				ExclusionReason::BitfieldSuperseded
+			};
+			self.bitfields.insert(idx, reason);
+		}
+	}
+
+	/// Note that the selected bitfields of `validators` were dropped to fit into the block weight
+	/// limit.
+	pub(crate) fn note_overweight_bitfields(
+		&mut self,
+		available: &[SignedAvailabilityBitfield],
+		validators: &HashSet<ValidatorIndex>,
+	) {
+		for (idx, bitfield) in available.iter().enumerate() {
+			if validators.contains(&bitfield.validator_index()) {
+				self.bitfields.entry(idx).or_insert(ExclusionReason::Overweight);
+			}
+		}
+	}
+
+	pub(crate) fn exclude_candidate(
+		&mut self,
+		candidate_hash: CandidateHash,
+		reason: ExclusionReason,
+	) {
+		self.candidates.insert(candidate_hash, reason);
+	}
+
+	pub(crate) fn exclude_dispute(
+		&mut self,
+		session: SessionIndex,
+		candidate_hash: CandidateHash,
+		reason: ExclusionReason,
+	) {
+		self.disputes
+			.insert((session, candidate_hash), ExcludedDispute { reason, votes: None });
+	}
+
+	pub(crate) fn exclude_dispute_statement_set(
+		&mut self,
+		set: &DisputeStatementSet,
+		reason: ExclusionReason,
+	) {
+		self.disputes.insert(
+			(set.session, set.candidate_hash),
+			ExcludedDispute { reason, votes: Some(Votes::of(set)) },
+		);
+	}
+}
+
+/// Index the given bitfields, of which there is at most one per validator, by validator.
+fn by_validator(
+	bitfields: &[SignedAvailabilityBitfield],
+) -> HashMap<ValidatorIndex, &SignedAvailabilityBitfield> {
+	bitfields
+		.iter()
+		.map(|bitfield| (bitfield.validator_index(), bitfield))
+		.collect()
+}
+
+/// Timing of an inherent data request, relative to the activation of the leaf.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct Timing {
+	/// The time after which the provisioner provides the inherent data, at the earliest.
+	pub(crate) pre_propose_timeout_ms: u64,
+	/// When the inherent data was requested.
+	pub(crate) requested_after_ms: u64,
+	/// When the inherent data was provided.
+	pub(crate) provided_after_ms: u64,
+}
+
+impl Timing {
+	pub(crate) fn new(
+		pre_propose_timeout: Duration,
+		requested: Duration,
+		provided: Duration,
+	) -> Self {
+		Timing {
+			pre_propose_timeout_ms: pre_propose_timeout.as_millis() as u64,
+			requested_after_ms: requested.as_millis() as u64,
+			provided_after_ms: provided.as_millis() as u64,
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+struct Votes {
+	valid: usize,
+	invalid: usize,
+}
+
+impl Votes {
+	fn of(set: &DisputeStatementSet) -> Self {
+		let valid = set
+			.statements
+			.iter()
+			.filter(|(statement, _, _)| matches!(statement, DisputeStatement::Valid(_)))
+			.count();
+		Votes { valid, invalid: set.statements.len() - valid }
+	}
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct BitfieldRecord {
+	validator_index: u32,
+	set_bits: usize,
+	included: bool,
+	exclusion: Option<ExclusionReason>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct CandidateRecord {
+	candidate_hash: String,
+	para_id: u32,
+	included: bool,
+	exclusion: Option<ExclusionReason>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct DisputeRecord {
+	session: SessionIndex,
+	candidate_hash: String,
+	votes: Option<Votes>,
+	included: bool,
+	exclusion: Option<ExclusionReason>,
+}
+
+/// A record of an inherent data request.
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct InherentDataRecord {
+	relay_parent: String,
+	block_number: BlockNumber,
+	stale_leaf: bool,
+	timing: Timing,
+	/// Why assembling the inherent data failed, if it did.
+	error: Option<String>,
+	bitfields: Vec<BitfieldRecord>,
+	candidates: Vec<CandidateRecord>,
+	disputes: Vec<DisputeRecord>,
+}
+
+impl InherentDataRecord {
+	/// Record the outcome of an inherent data request, given the bitfields and candidates
+	/// available to the provisioner.
+	pub(crate) fn new<E: std::fmt::Display>(
+		leaf: &ActivatedLeaf,
+		timing: Timing,
+		available_bitfields: &[SignedAvailabilityBitfield],
+		available_candidates: &[CandidateReceipt],
+		outcome: Result<&ProvisionerInherentData, &E>,
+		exclusions: &Exclusions,
+	) -> Self {
+		let (inherent_data, error) = match outcome {
+			Ok(inherent_data) => (Some(inherent_data), None),
+			Err(err) => (None, Some(err.to_string())),
+		};
+
+		let included_bitfields =
+			inherent_data.map(|data| by_validator(&data.bitfields)).unwrap_or_default();
+		let bitfields = available_bitfields
+			.iter()
+			.enumerate()
+			.map(|(idx, bitfield)| {
+				let included =
+					included_bitfields.get(&bitfield.validator_index()) == Some(&bitfield);
+				BitfieldRecord {
+					validator_index: bitfield.validator_index().0,
+					set_bits: bitfield.payload().0.count_ones(),
+					included,
+					exclusion: if included {
+						None
+					} else {
+						exclusions.bitfields.get(&idx).copied()
+					},
+				}
+			})
+			.collect();
+
+		let included_candidates = inherent_data
+			.map(|data| data.backed_candidates.iter().map(|c| c.hash()).collect::<HashSet<_>>())
+			.unwrap_or_default();
+		let candidates = available_candidates
+			.iter()
+			.map(|candidate| {
+				let candidate_hash = candidate.hash();
+				let included = included_candidates.contains(&candidate_hash);
+				CandidateRecord {
+					candidate_hash: format!("{:?}", candidate_hash.0),
+					para_id: candidate.descriptor.para_id.into(),
+					included,
+					exclusion: if included {
+						None
+					} else {
+						exclusions.candidates.get(&candidate_hash).copied()
+					},
+				}
+			})
+			.collect();
+
+		let mut disputes = inherent_data
+			.map(|data| {
+				data.disputes
+					.iter()
+					.map(|set| DisputeRecord {
+						session: set.session,
+						candidate_hash: format!("{:?}", set.candidate_hash.0),
+						votes: Some(Votes::of(set)),
+						included: true,
+						exclusion: None,
+					})
+					.collect::<Vec<_>>()
+			})
+			.unwrap_or_default();
+		disputes.extend(exclusions.disputes.iter().map(|((session, candidate_hash), excluded)| {
+			DisputeRecord {
+				session: *session,
+				candidate_hash: format!("{:?}", candidate_hash.0),
+				votes: excluded.votes,
+				included: false,
+				exclusion: Some(excluded.reason),
+			}
+		}));
+		disputes
+			.sort_by(|a, b| (a.session, &a.candidate_hash).cmp(&(b.session, &b.candidate_hash)));
+
+		InherentDataRecord {
+			relay_parent: format!("{:?}", leaf.hash),
+			block_number: leaf.number,
+			stale_leaf: leaf.status == LeafStatus::Stale,
+			timing,
+			error,
+			bitfields,
+			candidates,
+			disputes,
+		}
+	}
+}
@@ -31,28 +31,32 @@ use polkadot_node_subsystem::{
 	jaeger,
 	messages::{
 		CandidateBackingMessage, ChainApiMessage, DisputeCoordinatorMessage, ProvisionableData,
-		ProvisionerInherentData, ProvisionerMessage,
+		ProvisionerInherentData, ProvisionerMessage, RuntimeApiRequest,
 	},
 	ActivatedLeaf, LeafStatus, PerLeafSpan, SubsystemSender,
 };
 use polkadot_node_subsystem_util::{
-	self as util, request_availability_cores, request_persisted_validation_data, JobSender,
-	JobSubsystem, JobTrait,
+	self as util, has_required_runtime, request_availability_cores, request_inherent_weight_model,
+	request_persisted_validation_data, request_session_info, JobSender, JobSubsystem, JobTrait,
 };
 use polkadot_primitives::v2::{
-	BackedCandidate, BlockNumber, CandidateHash, CandidateReceipt, CoreState, DisputeStatement,
-	DisputeStatementSet, Hash, MultiDisputeStatementSet, OccupiedCoreAssumption, SessionIndex,
-	SignedAvailabilityBitfield, ValidatorIndex,
+	supermajority_threshold, BackedCandidate, BlockNumber, CandidateHash, CandidateReceipt,
+	CoreState, DisputeStatement, DisputeStatementSet, Hash, MultiDisputeStatementSet,
+	OccupiedCoreAssumption, SessionIndex, SignedAvailabilityBitfield, ValidatorIndex,
 };
 use std::{
-	collections::{BTreeMap, HashSet},
+	collections::{BTreeMap, HashMap, HashSet},
 	pin::Pin,
+	time::Instant,
 };
 use thiserror::Error;
 
+mod dump;
 mod metrics;
+mod packing;
 
-pub use self::metrics::*;
+use self::dump::{ExclusionReason, Exclusions, InherentDataRecord, Timing};
+pub use self::{dump::InherentDataDumpConfig, metrics::*};
 
 #[cfg(test)]
 mod tests;
@@ -103,6 +107,11 @@ pub struct ProvisionerJob {
 	metrics: Metrics,
 	inherent_after: InherentAfter,
 	awaiting_inherent: Vec<oneshot::Sender<ProvisionerInherentData>>,
+	inherent_data_dump: Option<InherentDataDumpConfig>,
+	/// When the job was started, i.e. the leaf was activated.
+	started: Instant,
+	/// When the inherent data was first requested, if it is awaiting the pre-propose timeout.
+	awaiting_since: Option<Instant>,
 }
 
 /// Errors in the provisioner.
@@ -149,8 +158,11 @@ pub enum Error {
 }
 
 /// Provisioner run arguments.
-#[derive(Debug, Clone, Copy)]
-pub struct ProvisionerConfig;
+#[derive(Debug, Clone, Default)]
+pub struct ProvisionerConfig {
+	/// Where to dump a record of the inherent data of every authored block, if anywhere.
+	pub inherent_data_dump: Option<InherentDataDumpConfig>,
+}
 
 impl JobTrait for ProvisionerJob {
 	type ToJob = ProvisionerMessage;
@@ -165,14 +177,14 @@ impl JobTrait for ProvisionerJob {
 	// this function is in charge of creating and executing the job's main loop
 	fn run<S: SubsystemSender>(
 		leaf: ActivatedLeaf,
-		_: Self::RunArgs,
+		config: Self::RunArgs,
 		metrics: Self::Metrics,
 		receiver: mpsc::Receiver<ProvisionerMessage>,
 		mut sender: JobSender<S>,
 	) -> Pin<Box<dyn Future<Output = Result<(), Self::Error>> + Send>> {
 		let span = leaf.span.clone();
 		async move {
-			let job = ProvisionerJob::new(leaf, metrics, receiver);
+			let job = ProvisionerJob::new(leaf, config, metrics, receiver);
 
 			job.run_loop(sender.subsystem_sender(), PerLeafSpan::new(span, "provisioner"))
 				.await
@@ -184,6 +196,7 @@ impl JobTrait for ProvisionerJob {
 impl ProvisionerJob {
 	fn new(
 		leaf: ActivatedLeaf,
+		config: ProvisionerConfig,
 		metrics: Metrics,
 		receiver: mpsc::Receiver<ProvisionerMessage>,
 	) -> Self {
@@ -195,6 +208,9 @@ impl ProvisionerJob {
 			metrics,
 			inherent_after: InherentAfter::new_from_now(),
 			awaiting_inherent: Vec::new(),
+			inherent_data_dump: config.inherent_data_dump,
+			started: Instant::now(),
+			awaiting_since: None,
 		}
 	}
 
@@ -211,8 +227,9 @@ impl ProvisionerJob {
 						let _timer = self.metrics.time_request_inherent_data();
 
 						if self.inherent_after.is_ready() {
-							self.send_inherent_data(sender, vec![return_sender]).await;
+							self.send_inherent_data(sender, vec![return_sender], Instant::now()).await;
 						} else {
+							self.awaiting_since.get_or_insert_with(Instant::now);
 							self.awaiting_inherent.push(return_sender);
 						}
 					}
@@ -227,8 +244,8 @@ impl ProvisionerJob {
 				_ = self.inherent_after.ready().fuse() => {
 					let _span = span.child("send-inherent-data");
 					let return_senders = std::mem::take(&mut self.awaiting_inherent);
-					if !return_senders.is_empty() {
-						self.send_inherent_data(sender, return_senders).await;
+					if let Some(requested) = self.awaiting_since.take() {
+						self.send_inherent_data(sender, return_senders, requested).await;
 					}
 				}
 			}
@@ -241,28 +258,52 @@ impl ProvisionerJob {
 		&mut self,
 		sender: &mut impl SubsystemSender,
 		return_senders: Vec<oneshot::Sender<ProvisionerInherentData>>,
+		requested: Instant,
 	) {
-		if let Err(err) = send_inherent_data(
+		let mut exclusions = Exclusions::default();
+		let result = send_inherent_data(
 			&self.leaf,
 			&self.signed_bitfields,
 			&self.backed_candidates,
 			return_senders,
 			sender,
 			&self.metrics,
+			&mut exclusions,
 		)
-		.await
-		{
-			gum::warn!(target: LOG_TARGET, err = ?err, "failed to assemble or send inherent data");
-			self.metrics.on_inherent_data_request(Err(()));
-		} else {
-			self.metrics.on_inherent_data_request(Ok(()));
-			gum::debug!(
-				target: LOG_TARGET,
-				signed_bitfield_count = self.signed_bitfields.len(),
-				backed_candidates_count = self.backed_candidates.len(),
-				leaf_hash = ?self.leaf.hash,
-				"inherent data sent successfully"
+		.await;
+
+		match result {
+			Err(ref err) => {
+				gum::warn!(target: LOG_TARGET, err = ?err, "failed to assemble or send inherent data");
+				self.metrics.on_inherent_data_request(Err(()));
+			},
+			Ok(_) => {
+				self.metrics.on_inherent_data_request(Ok(()));
+				gum::debug!(
+					target: LOG_TARGET,
+					signed_bitfield_count = self.signed_bitfields.len(),
+					backed_candidates_count = self.backed_candidates.len(),
+					leaf_hash = ?self.leaf.hash,
+					"inherent data sent successfully"
+				);
+			},
+		}
+
+		if let Some(ref dump) = self.inherent_data_dump {
+			let timing = Timing::new(
+				PRE_PROPOSE_TIMEOUT,
+				requested.saturating_duration_since(self.started),
+				self.started.elapsed(),
+			);
+			let record = InherentDataRecord::new(
+				&self.leaf,
+				timing,
+				&self.signed_bitfields,
+				&self.backed_candidates,
+				result.as_ref(),
+				&exclusions,
 			);
+			dump.spawn_write(record);
 		}
 	}
 
@@ -319,23 +360,112 @@ async fn send_inherent_data(
 	return_senders: Vec<oneshot::Sender<ProvisionerInherentData>>,
 	from_job: &mut impl SubsystemSender,
 	metrics: &Metrics,
-) -> Result<(), Error> {
+	exclusions: &mut Exclusions,
+) -> Result<ProvisionerInherentData, Error> {
 	let availability_cores = request_availability_cores(leaf.hash, from_job)
 		.await
 		.await
 		.map_err(|err| Error::CanceledAvailabilityCores(err))??;
 
-	let disputes = select_disputes(from_job, metrics).await?;
+	let disputes = select_disputes(from_job, metrics, exclusions).await?;
 
 	// Only include bitfields on fresh leaves. On chain reversions, we want to make sure that
 	// there will be at least one block, which cannot get disputed, so the chain can make progress.
+	let available_bitfields = bitfields;
 	let bitfields = match leaf.status {
 		LeafStatus::Fresh =>
-			select_availability_bitfields(&availability_cores, bitfields, &leaf.hash),
+			select_availability_bitfields(&availability_cores, available_bitfields, &leaf.hash),
 		LeafStatus::Stale => Vec::new(),
 	};
-	let candidates =
-		select_candidates(&availability_cores, &bitfields, candidates, leaf.hash, from_job).await?;
+	exclusions.note_bitfields(
+		&availability_cores,
+		leaf.status == LeafStatus::Stale,
+		available_bitfields,
+		&bitfields,
+	);
+	let candidates = select_candidates(
+		&availability_cores,
+		&bitfields,
+		candidates,
+		leaf.hash,
+		from_job,
+		exclusions,
+	)
+	.await?;
+
+	// The weight model is a staging runtime API, which older runtimes don't implement. Without
+	// it, fitting the inherent data into the block is left to the runtime.
+	let weight_model = if has_required_runtime(
+		leaf.hash,
+		RuntimeApiRequest::INHERENT_WEIGHT_MODEL_RUNTIME_REQUIREMENT,
+		from_job,
+	)
+	.await
+	{
+		match request_inherent_weight_model(leaf.hash, from_job).await.await {
+			Ok(Ok(model)) => Some(model),
+			Ok(Err(err)) => {
+				gum::trace!(
+					target: LOG_TARGET,
+					?err,
+					leaf_hash = ?leaf.hash,
+					"inherent weight model unavailable, leaving the weight limit to the runtime",
+				);
+				None
+			},
+			Err(oneshot::Canceled) => None,
+		}
+	} else {
+		None
+	};
+
+	let (disputes, bitfields, candidates) = match weight_model {
+		Some(model) => {
+			let weight = packing::inherent_data_weight(&model, &disputes, &bitfields, &candidates);
+			if weight > model.max_block_weight {
+				let concluded = concluded_disputes(leaf.hash, &disputes, from_job).await;
+				let (n_disputes, n_bitfields, n_candidates) =
+					(disputes.len(), bitfields.len(), candidates.len());
+				let packed = packing::pack_inherent_data(
+					&model,
+					disputes.clone(),
+					&concluded,
+					bitfields.clone(),
+					candidates.clone(),
+				);
+				note_overweight_exclusions(
+					exclusions,
+					available_bitfields,
+					(&disputes, &bitfields, &candidates),
+					&packed,
+				);
+
+				let dropped_disputes = n_disputes - packed.disputes.len();
+				let dropped_bitfields = n_bitfields - packed.bitfields.len();
+				let dropped_candidates = n_candidates - packed.candidates.len();
+				gum::debug!(
+					target: LOG_TARGET,
+					weight,
+					max_block_weight = model.max_block_weight,
+					dropped_disputes,
+					dropped_bitfields,
+					dropped_candidates,
+					leaf_hash = ?leaf.hash,
+					"inherent data exceeds the block weight limit, packed it",
+				);
+				metrics.on_overweight_inherent_data(
+					dropped_disputes,
+					dropped_bitfields,
+					dropped_candidates,
+				);
+
+				(packed.disputes, packed.bitfields, packed.candidates)
+			} else {
+				(disputes, bitfields, candidates)
+			}
+		},
+		None => (disputes, bitfields, candidates),
+	};
 
 	gum::debug!(
 		target: LOG_TARGET,
@@ -356,7 +486,47 @@ async fn send_inherent_data(
 			.map_err(|_data| Error::InherentDataReturnChannel)?;
 	}
 
-	Ok(())
+	Ok(inherent_data)
+}
+
+/// Note the items which were dropped by packing the inherent data into the block weight limit.
+fn note_overweight_exclusions(
+	exclusions: &mut Exclusions,
+	available_bitfields: &[SignedAvailabilityBitfield],
+	(disputes, bitfields, candidates): (
+		&[DisputeStatementSet],
+		&[SignedAvailabilityBitfield],
+		&[BackedCandidate],
+	),
+	packed: &packing::PackedInherentData,
+) {
+	let packed_disputes = packed
+		.disputes
+		.iter()
+		.map(|d| (d.session, d.candidate_hash))
+		.collect::<HashSet<_>>();
+	for dispute in disputes {
+		if !packed_disputes.contains(&(dispute.session, dispute.candidate_hash)) {
+			exclusions.exclude_dispute_statement_set(dispute, ExclusionReason::Overweight);
+		}
+	}
+
+	let packed_validators =
+		packed.bitfields.iter().map(|b| b.validator_index()).collect::<HashSet<_>>();
+	let dropped_validators = bitfields
+		.iter()
+		.map(|b| b.validator_index())
+		.filter(|v| !packed_validators.contains(v))
+		.collect::<HashSet<_>>();
+	exclusions.note_overweight_bitfields(available_bitfields, &dropped_validators);
+
+	let packed_candidates = packed.candidates.iter().map(|c| c.hash()).collect::<HashSet<_>>();
+	for candidate in candidates {
+		let candidate_hash = candidate.hash();
+		if !packed_candidates.contains(&candidate_hash) {
+			exclusions.exclude_candidate(candidate_hash, ExclusionReason::Overweight);
+		}
+	}
 }
 
 /// In general, we want to pick all the bitfields. However, we have the following constraints:
@@ -437,6 +607,7 @@ async fn select_candidates(
 	candidates: &[CandidateReceipt],
 	relay_parent: Hash,
 	sender: &mut impl SubsystemSender,
+	exclusions: &mut Exclusions,
 ) -> Result<Vec<BackedCandidate>, Error> {
 	let block_number = get_block_number_under_construction(relay_parent, sender).await?;
 
@@ -511,6 +682,13 @@ async fn select_candidates(
 		}
 	}
 
+	for candidate in candidates {
+		let candidate_hash = candidate.hash();
+		if !selected_candidates.contains(&candidate_hash) {
+			exclusions.exclude_candidate(candidate_hash, ExclusionReason::NoSuitableCore);
+		}
+	}
+
 	// now get the backed candidates corresponding to these candidate receipts
 	let (tx, rx) = oneshot::channel();
 	sender
@@ -537,6 +715,8 @@ async fn select_candidates(
 			candidates.get(backed_idx).ok_or(Error::BackedCandidateOrderingProblem)?.hash()
 		{
 			backed_idx += 1;
+		} else {
+			exclusions.exclude_candidate(selected, ExclusionReason::NotBacked);
 		}
 	}
 	if candidates.len() != backed_idx {
@@ -548,6 +728,7 @@ async fn select_candidates(
 	candidates.retain(|c| {
 		if c.candidate.commitments.new_validation_code.is_some() {
 			if with_validation_code {
+				exclusions.exclude_candidate(c.hash(), ExclusionReason::ExtraCodeUpgrade);
 				return false
 			}
 
@@ -703,6 +884,7 @@ fn extend_by_random_subset_without_repetition(
 async fn select_disputes(
 	sender: &mut impl SubsystemSender,
 	metrics: &metrics::Metrics,
+	exclusions: &mut Exclusions,
 ) -> Result<MultiDisputeStatementSet, Error> {
 	const MAX_DISPUTES_FORWARDED_TO_RUNTIME: usize = 1_000;
 
@@ -720,6 +902,7 @@ async fn select_disputes(
 			recent.len(),
 			MAX_DISPUTES_FORWARDED_TO_RUNTIME
 		);
+		let excessive = recent.clone();
 		let mut active = request_disputes(sender, RequestType::Active).await;
 		let n_active = active.len();
 		let active = if active.len() > MAX_DISPUTES_FORWARDED_TO_RUNTIME {
@@ -738,14 +921,29 @@ async fn select_disputes(
 			);
 			active
 		};
+
+		let forwarded = active.iter().collect::<HashSet<_>>();
+		for (session, candidate_hash) in excessive.iter().filter(|d| !forwarded.contains(d)) {
+			exclusions.exclude_dispute(*session, *candidate_hash, ExclusionReason::TooManyDisputes);
+		}
+
 		active
 	} else {
 		recent
 	};
 
 	// Load all votes for all disputes from the coordinator.
+	let queried = disputes.clone();
 	let dispute_candidate_votes = request_votes(sender, disputes).await;
 
+	let answered = dispute_candidate_votes
+		.iter()
+		.map(|(session, candidate_hash, _)| (*session, *candidate_hash))
+		.collect::<HashSet<_>>();
+	for (session, candidate_hash) in queried.into_iter().filter(|d| !answered.contains(d)) {
+		exclusions.exclude_dispute(session, candidate_hash, ExclusionReason::NoVotes);
+	}
+
 	// Transform all `CandidateVotes` into `MultiDisputeStatementSet`.
 	Ok(dispute_candidate_votes
 		.into_iter()
@@ -771,5 +969,49 @@ async fn select_disputes(
 		.collect())
 }
 
+/// Determine which of the given disputes concluded, i.e. gathered a supermajority of votes on
+/// either side, according to the votes known to the dispute coordinator.
+///
+/// Disputes of sessions whose info is unavailable are considered unconcluded.
+async fn concluded_disputes(
+	relay_parent: Hash,
+	disputes: &[DisputeStatementSet],
+	sender: &mut impl SubsystemSender,
+) -> HashSet<(SessionIndex, CandidateHash)> {
+	let mut n_validators = HashMap::<SessionIndex, Option<usize>>::new();
+	let mut concluded = HashSet::new();
+
+	for dispute in disputes {
+		let n = match n_validators.get(&dispute.session) {
+			Some(n) => *n,
+			None => {
+				let n =
+					match request_session_info(relay_parent, dispute.session, sender).await.await {
+						Ok(Ok(Some(info))) => Some(info.validators.len()),
+						_ => None,
+					};
+				n_validators.insert(dispute.session, n);
+				n
+			},
+		};
+		let threshold = match n {
+			Some(n) => supermajority_threshold(n),
+			None => continue,
+		};
+
+		let valid = dispute
+			.statements
+			.iter()
+			.filter(|(statement, _, _)| matches!(statement, DisputeStatement::Valid(_)))
+			.count();
+		let invalid = dispute.statements.len() - valid;
+		if valid >= threshold || invalid >= threshold {
+			concluded.insert((dispute.session, dispute.candidate_hash));
+		}
+	}
+
+	concluded
+}
+
 /// The provisioner subsystem.
 pub type ProvisionerSubsystem<Spawner> = JobSubsystem<ProvisionerJob, Spawner>;
@@ -27,6 +27,9 @@ struct MetricsInner {
 	/// 4 hours on Polkadot. The metrics are updated only when the node authors a block, so values vary across nodes.
 	inherent_data_dispute_statement_sets: prometheus::Counter<prometheus::U64>,
 	inherent_data_dispute_statements: prometheus::CounterVec<prometheus::U64>,
+
+	/// Items dropped from the inherent data to fit into the block weight limit.
+	inherent_data_overweight_dropped: prometheus::CounterVec<prometheus::U64>,
 }
 
 /// Provisioner metrics.
@@ -82,6 +85,24 @@ impl Metrics {
 				.inc_by(disputes.try_into().unwrap_or(0));
 		}
 	}
+
+	pub(crate) fn on_overweight_inherent_data(
+		&self,
+		disputes: usize,
+		bitfields: usize,
+		candidates: usize,
+	) {
+		if let Some(metrics) = &self.0 {
+			for (kind, dropped) in
+				[("dispute", disputes), ("bitfield", bitfields), ("candidate", candidates)]
+			{
+				metrics
+					.inherent_data_overweight_dropped
+					.with_label_values(&[kind])
+					.inc_by(dropped.try_into().unwrap_or(0));
+			}
+		}
+	}
 }
 
 impl metrics::Metrics for Metrics {
@@ -128,6 +149,16 @@ impl metrics::Metrics for Metrics {
 				)?,
 				registry,
 			)?,
+			inherent_data_overweight_dropped: prometheus::register(
+				prometheus::CounterVec::new(
+					prometheus::Opts::new(
+						"polkadot_parachain_inherent_data_overweight_dropped",
+						"Number of items dropped from the inherent data to fit into the block weight limit.",
+					),
+					&["kind"],
+				)?,
+				registry,
+			)?,
 		};
 		Ok(Metrics(Some(metrics)))
 	}
@@ -0,0 +1,124 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Packing of the inherent data into the weight limit of a block.
+//!
+//! The runtime drops a random subset of an overweight inherent. To keep control over what ends
+//! up in the block, the provisioner packs the selected data using the weights reported by the
+//! runtime, in order of priority:
+//!
+//! 1. disputes which concluded according to the local dispute coordinator,
+//! 2. availability bitfields, which make the candidates pending availability available,
+//! 3. backed candidates,
+//! 4. all remaining disputes.
+
+use polkadot_primitives::{
+	v2::{
+		BackedCandidate, CandidateHash, DisputeStatementSet, MultiDisputeStatementSet,
+		SessionIndex, SignedAvailabilityBitfield,
+	},
+	vstaging::InherentWeightModel,
+};
+use std::collections::HashSet;
+
+/// Inherent data fitting into the weight limit of a block.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct PackedInherentData {
+	pub(crate) disputes: MultiDisputeStatementSet,
+	pub(crate) bitfields: Vec<SignedAvailabilityBitfield>,
+	pub(crate) candidates: Vec<BackedCandidate>,
+}
+
+/// The weight charged by the runtime for the given inherent data.
+pub(crate) fn inherent_data_weight(
+	model: &InherentWeightModel,
+	disputes: &[DisputeStatementSet],
+	bitfields: &[SignedAvailabilityBitfield],
+	candidates: &[BackedCandidate],
+) -> u64 {
+	let disputes_weight = disputes
+		.iter()
+		.fold(0u64, |acc, d| acc.saturating_add(model.dispute_statement_set_weight(d)));
+	let candidates_weight = candidates
+		.iter()
+		.fold(0u64, |acc, c| acc.saturating_add(model.backed_candidate_weight(c)));
+
+	disputes_weight
+		.saturating_add(model.bitfields_weight(bitfields.len()))
+		.saturating_add(candidates_weight)
+}
+
+/// Pack the inherent data into `model.max_block_weight`, see the module docs for the priorities.
+///
+/// Within a priority class, items are picked in order, skipping those which don't fit into the
+/// remaining weight. The candidates have been selected assuming all bitfields get included, so
+/// if the bitfields don't fit entirely, no candidates are included.
+///
+/// The relative order of the disputes, bitfields and candidates is preserved.
+pub(crate) fn pack_inherent_data(
+	model: &InherentWeightModel,
+	disputes: MultiDisputeStatementSet,
+	concluded: &HashSet<(SessionIndex, CandidateHash)>,
+	bitfields: Vec<SignedAvailabilityBitfield>,
+	candidates: Vec<BackedCandidate>,
+) -> PackedInherentData {
+	fn consume(remaining: &mut u64, weight: u64) -> bool {
+		match remaining.checked_sub(weight) {
+			Some(left) => {
+				*remaining = left;
+				true
+			},
+			None => false,
+		}
+	}
+
+	let mut remaining = model.max_block_weight;
+
+	let is_concluded = |d: &DisputeStatementSet| concluded.contains(&(d.session, d.candidate_hash));
+	let mut selected_disputes = vec![false; disputes.len()];
+	for (idx, dispute) in disputes.iter().enumerate().filter(|(_, d)| is_concluded(d)) {
+		selected_disputes[idx] =
+			consume(&mut remaining, model.dispute_statement_set_weight(dispute));
+	}
+
+	let (bitfields, candidates) =
+		if consume(&mut remaining, model.bitfields_weight(bitfields.len())) {
+			let candidates = candidates
+				.into_iter()
+				.filter(|c| consume(&mut remaining, model.backed_candidate_weight(c)))
+				.collect();
+			(bitfields, candidates)
+		} else {
+			let bitfields = bitfields
+				.into_iter()
+				.filter(|_| consume(&mut remaining, model.bitfield_weight))
+				.collect();
+			(bitfields, Vec::new())
+		};
+
+	for (idx, dispute) in disputes.iter().enumerate().filter(|(_, d)| !is_concluded(d)) {
+		selected_disputes[idx] =
+			consume(&mut remaining, model.dispute_statement_set_weight(dispute));
+	}
+
+	let disputes = disputes
+		.into_iter()
+		.zip(selected_disputes)
+		.filter_map(|(dispute, selected)| selected.then(|| dispute))
+		.collect();
+
+	PackedInherentData { disputes, bitfields, candidates }
+}
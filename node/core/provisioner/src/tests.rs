@@ -1,7 +1,11 @@
 use super::*;
 use ::test_helpers::{dummy_candidate_descriptor, dummy_hash};
 use bitvec::bitvec;
-use polkadot_primitives::v2::{OccupiedCore, ScheduledCore};
+use polkadot_primitives::v2::{
+	OccupiedCore, ScheduledCore, SigningContext, ValidatorId, ValidatorIndex,
+};
+use sp_application_crypto::AppKey;
+use sp_keystore::{CryptoStore, SyncCryptoStorePtr};
 
 pub fn occupied_core(para_id: u32) -> CoreState {
 	CoreState::Occupied(OccupiedCore {
@@ -38,35 +42,34 @@ pub fn scheduled_core(id: u32) -> ScheduledCore {
 	ScheduledCore { para_id: id.into(), collator: None }
 }
 
+pub async fn signed_bitfield(
+	keystore: &SyncCryptoStorePtr,
+	field: CoreAvailability,
+	validator_idx: ValidatorIndex,
+) -> SignedAvailabilityBitfield {
+	let public = CryptoStore::sr25519_generate_new(&**keystore, ValidatorId::ID, None)
+		.await
+		.expect("generated sr25519 key");
+	SignedAvailabilityBitfield::sign(
+		&keystore,
+		field.into(),
+		&<SigningContext<Hash>>::default(),
+		validator_idx,
+		&public.into(),
+	)
+	.await
+	.ok()
+	.flatten()
+	.expect("Should be signed")
+}
+
 mod select_availability_bitfields {
-	use super::{super::*, default_bitvec, occupied_core};
+	use super::{super::*, default_bitvec, occupied_core, signed_bitfield};
 	use futures::executor::block_on;
-	use polkadot_primitives::v2::{ScheduledCore, SigningContext, ValidatorId, ValidatorIndex};
-	use sp_application_crypto::AppKey;
-	use sp_keystore::{testing::KeyStore, CryptoStore, SyncCryptoStorePtr};
+	use polkadot_primitives::v2::{ScheduledCore, ValidatorIndex};
+	use sp_keystore::{testing::KeyStore, SyncCryptoStorePtr};
 	use std::sync::Arc;
 
-	async fn signed_bitfield(
-		keystore: &SyncCryptoStorePtr,
-		field: CoreAvailability,
-		validator_idx: ValidatorIndex,
-	) -> SignedAvailabilityBitfield {
-		let public = CryptoStore::sr25519_generate_new(&**keystore, ValidatorId::ID, None)
-			.await
-			.expect("generated sr25519 key");
-		SignedAvailabilityBitfield::sign(
-			&keystore,
-			field.into(),
-			&<SigningContext<Hash>>::default(),
-			validator_idx,
-			&public.into(),
-		)
-		.await
-		.ok()
-		.flatten()
-		.expect("Should be signed")
-	}
-
 	#[test]
 	fn not_more_than_one_per_validator() {
 		let keystore: SyncCryptoStorePtr = Arc::new(KeyStore::new());
@@ -339,7 +342,16 @@ mod select_candidates {
 		test_harness(
 			|r| mock_overseer(r, Vec::new()),
 			|mut tx: TestSubsystemSender| async move {
-				select_candidates(&[], &[], &[], Default::default(), &mut tx).await.unwrap();
+				select_candidates(
+					&[],
+					&[],
+					&[],
+					Default::default(),
+					&mut tx,
+					&mut Exclusions::default(),
+				)
+				.await
+				.unwrap();
 			},
 		)
 	}
@@ -406,10 +418,16 @@ mod select_candidates {
 		test_harness(
 			|r| mock_overseer(r, expected_backed),
 			|mut tx: TestSubsystemSender| async move {
-				let result =
-					select_candidates(&mock_cores, &[], &candidates, Default::default(), &mut tx)
-						.await
-						.unwrap();
+				let result = select_candidates(
+					&mock_cores,
+					&[],
+					&candidates,
+					Default::default(),
+					&mut tx,
+					&mut Exclusions::default(),
+				)
+				.await
+				.unwrap();
 
 				result.into_iter().for_each(|c| {
 					assert!(
@@ -470,10 +488,16 @@ mod select_candidates {
 		test_harness(
 			|r| mock_overseer(r, expected_backed),
 			|mut tx: TestSubsystemSender| async move {
-				let result =
-					select_candidates(&mock_cores, &[], &candidates, Default::default(), &mut tx)
-						.await
-						.unwrap();
+				let result = select_candidates(
+					&mock_cores,
+					&[],
+					&candidates,
+					Default::default(),
+					&mut tx,
+					&mut Exclusions::default(),
+				)
+				.await
+				.unwrap();
 
 				result.into_iter().for_each(|c| {
 					assert!(
@@ -486,3 +510,394 @@ mod select_candidates {
 		)
 	}
 }
+
+mod pack_inherent_data {
+	use super::{super::*, default_bitvec, signed_bitfield};
+	use ::test_helpers::{dummy_committed_candidate_receipt, dummy_validator_signature};
+	use futures::executor::block_on;
+	use packing::{inherent_data_weight, pack_inherent_data, PackedInherentData};
+	use polkadot_primitives::{
+		v2::{
+			CandidateCommitments, InvalidDisputeStatementKind, ValidDisputeStatementKind,
+			ValidityAttestation,
+		},
+		vstaging::InherentWeightModel,
+	};
+	use sp_keystore::{testing::KeyStore, SyncCryptoStorePtr};
+	use std::sync::Arc;
+
+	fn weight_model(max_block_weight: u64) -> InherentWeightModel {
+		InherentWeightModel {
+			max_block_weight,
+			bitfield_weight: 10,
+			backed_candidate_base_weight: 100,
+			backed_candidate_per_vote_weight: 10,
+			backed_candidate_code_upgrade_weight: 1_000,
+			dispute_statement_set_base_weight: 100,
+			dispute_statement_weight: 50,
+		}
+	}
+
+	fn dispute(session: SessionIndex, valid: u32, invalid: u32) -> DisputeStatementSet {
+		let valid = (0..valid).map(|i| {
+			(
+				DisputeStatement::Valid(ValidDisputeStatementKind::Explicit),
+				ValidatorIndex(i),
+				dummy_validator_signature(),
+			)
+		});
+		let invalid = (0..invalid).map(|i| {
+			(
+				DisputeStatement::Invalid(InvalidDisputeStatementKind::Explicit),
+				ValidatorIndex(i),
+				dummy_validator_signature(),
+			)
+		});
+
+		DisputeStatementSet {
+			candidate_hash: CandidateHash(Hash::repeat_byte(session as u8)),
+			session,
+			statements: valid.chain(invalid).collect(),
+		}
+	}
+
+	fn backed_candidate(para_id: u32, votes: usize, code_upgrade: bool) -> BackedCandidate {
+		let mut candidate = dummy_committed_candidate_receipt(dummy_hash());
+		candidate.descriptor.para_id = para_id.into();
+		candidate.commitments = CandidateCommitments {
+			new_validation_code: code_upgrade.then(|| vec![1, 2, 3].into()),
+			..Default::default()
+		};
+
+		BackedCandidate {
+			candidate,
+			validity_votes: (0..votes)
+				.map(|_| ValidityAttestation::Explicit(dummy_validator_signature()))
+				.collect(),
+			validator_indices: default_bitvec(votes),
+		}
+	}
+
+	fn bitfields(n: u32) -> Vec<SignedAvailabilityBitfield> {
+		let keystore: SyncCryptoStorePtr = Arc::new(KeyStore::new());
+		(0..n)
+			.map(|i| block_on(signed_bitfield(&keystore, default_bitvec(2), ValidatorIndex(i))))
+			.collect()
+	}
+
+	fn key(dispute: &DisputeStatementSet) -> (SessionIndex, CandidateHash) {
+		(dispute.session, dispute.candidate_hash)
+	}
+
+	#[test]
+	fn weight_follows_model() {
+		let model = weight_model(u64::MAX);
+
+		let disputes = vec![dispute(1, 2, 1)];
+		let bitfields = bitfields(3);
+		let candidates = vec![backed_candidate(1, 2, false), backed_candidate(2, 2, true)];
+
+		assert_eq!(
+			inherent_data_weight(&model, &disputes, &bitfields, &candidates),
+			(100 + 3 * 50) + 3 * 10 + (100 + 2 * 10) + 1_000,
+		);
+	}
+
+	#[test]
+	fn fitting_data_is_kept() {
+		let disputes = vec![dispute(1, 2, 1)];
+		let bitfields = bitfields(3);
+		let candidates = vec![backed_candidate(1, 2, false), backed_candidate(2, 2, true)];
+
+		let model = weight_model(inherent_data_weight(
+			&weight_model(0),
+			&disputes,
+			&bitfields,
+			&candidates,
+		));
+
+		let packed = pack_inherent_data(
+			&model,
+			disputes.clone(),
+			&HashSet::new(),
+			bitfields.clone(),
+			candidates.clone(),
+		);
+
+		assert_eq!(packed, PackedInherentData { disputes, bitfields, candidates });
+	}
+
+	#[test]
+	fn overweight_prefers_concluded_disputes() {
+		let open = dispute(1, 1, 1);
+		let concluded = dispute(2, 3, 0);
+		let disputes = vec![open.clone(), concluded.clone()];
+		let bitfields = bitfields(2);
+		let candidates = vec![backed_candidate(1, 0, false)];
+
+		// Room for the concluded dispute, the bitfields and the candidate, but not for the
+		// open dispute.
+		let model = weight_model(250 + 2 * 10 + 100 + 199);
+
+		let packed = pack_inherent_data(
+			&model,
+			disputes,
+			&[key(&concluded)].into_iter().collect(),
+			bitfields.clone(),
+			candidates.clone(),
+		);
+
+		assert_eq!(packed, PackedInherentData { disputes: vec![concluded], bitfields, candidates });
+	}
+
+	#[test]
+	fn overweight_prefers_candidates_over_open_disputes() {
+		let open = dispute(1, 1, 1);
+		let bitfields = bitfields(2);
+		let candidates = vec![backed_candidate(1, 2, false), backed_candidate(2, 2, false)];
+
+		let model = weight_model(2 * 10 + 2 * 120);
+
+		let packed = pack_inherent_data(
+			&model,
+			vec![open.clone()],
+			&HashSet::new(),
+			bitfields.clone(),
+			candidates.clone(),
+		);
+
+		assert_eq!(packed, PackedInherentData { disputes: Vec::new(), bitfields, candidates });
+
+		// Any weight left after the candidates is filled up with open disputes.
+		let model = weight_model(2 * 10 + 2 * 120 + 200);
+
+		let packed = pack_inherent_data(
+			&model,
+			vec![open.clone()],
+			&HashSet::new(),
+			bitfields.clone(),
+			candidates.clone(),
+		);
+
+		assert_eq!(packed, PackedInherentData { disputes: vec![open], bitfields, candidates });
+	}
+
+	#[test]
+	fn overweight_candidates_are_skipped_in_order() {
+		let bitfields = bitfields(1);
+		let upgrade = backed_candidate(1, 2, true);
+		let light = backed_candidate(2, 2, false);
+		let heavy = backed_candidate(3, 10, false);
+
+		// The code upgrade doesn't fit, but the lighter candidate after it does.
+		let model = weight_model(10 + 120 + 150);
+
+		let packed = pack_inherent_data(
+			&model,
+			Vec::new(),
+			&HashSet::new(),
+			bitfields.clone(),
+			vec![upgrade, light.clone(), heavy],
+		);
+
+		assert_eq!(
+			packed,
+			PackedInherentData { disputes: Vec::new(), bitfields, candidates: vec![light] },
+		);
+	}
+
+	#[test]
+	fn overweight_bitfields_drop_all_candidates() {
+		let concluded = dispute(1, 3, 0);
+		let bitfields = bitfields(4);
+		let candidates = vec![backed_candidate(1, 0, false)];
+
+		// The concluded dispute and only two out of four bitfields fit.
+		let model = weight_model(250 + 2 * 10 + 5);
+
+		let packed = pack_inherent_data(
+			&model,
+			vec![concluded.clone()],
+			&[key(&concluded)].into_iter().collect(),
+			bitfields.clone(),
+			candidates,
+		);
+
+		assert_eq!(
+			packed,
+			PackedInherentData {
+				disputes: vec![concluded],
+				bitfields: bitfields[..2].to_vec(),
+				candidates: Vec::new(),
+			},
+		);
+	}
+
+	#[test]
+	fn dispute_order_is_preserved() {
+		let first = dispute(1, 1, 0);
+		let second = dispute(2, 3, 0);
+		let third = dispute(3, 1, 0);
+
+		let model = weight_model(u64::MAX);
+
+		let packed = pack_inherent_data(
+			&model,
+			vec![first.clone(), second.clone(), third.clone()],
+			&[key(&second)].into_iter().collect(),
+			Vec::new(),
+			Vec::new(),
+		);
+
+		assert_eq!(packed.disputes, vec![first, second, third]);
+	}
+}
+
+mod inherent_data_dump {
+	use super::{super::*, default_bitvec, occupied_core, signed_bitfield};
+	use ::test_helpers::dummy_candidate_receipt;
+	use dump::{ExclusionReason, Exclusions, InherentDataDumpConfig, InherentDataRecord, Timing};
+	use futures::executor::block_on;
+	use polkadot_node_subsystem::{jaeger, ActivatedLeaf, LeafStatus};
+	use polkadot_primitives::v2::ValidatorIndex;
+	use sp_keystore::{testing::KeyStore, SyncCryptoStorePtr};
+	use std::{sync::Arc, time::Duration};
+
+	fn leaf(number: BlockNumber, status: LeafStatus) -> ActivatedLeaf {
+		ActivatedLeaf {
+			hash: Hash::repeat_byte(number as u8),
+			number,
+			status,
+			span: Arc::new(jaeger::Span::Disabled),
+		}
+	}
+
+	fn timing() -> Timing {
+		Timing::new(PRE_PROPOSE_TIMEOUT, Duration::from_millis(1500), Duration::from_millis(2000))
+	}
+
+	fn record(
+		leaf: &ActivatedLeaf,
+		bitfields: &[SignedAvailabilityBitfield],
+		candidates: &[CandidateReceipt],
+		inherent_data: &ProvisionerInherentData,
+		exclusions: &Exclusions,
+	) -> serde_json::Value {
+		let record = InherentDataRecord::new::<Error>(
+			leaf,
+			timing(),
+			bitfields,
+			candidates,
+			Ok(inherent_data),
+			exclusions,
+		);
+		serde_json::to_value(&record).unwrap()
+	}
+
+	fn empty_inherent_data() -> ProvisionerInherentData {
+		ProvisionerInherentData {
+			bitfields: Vec::new(),
+			backed_candidates: Vec::new(),
+			disputes: Vec::new(),
+		}
+	}
+
+	fn exclusion(reason: ExclusionReason) -> serde_json::Value {
+		serde_json::to_value(reason).unwrap()
+	}
+
+	#[test]
+	fn records_why_bitfields_were_excluded() {
+		let keystore: SyncCryptoStorePtr = Arc::new(KeyStore::new());
+		let cores = vec![occupied_core(0), CoreState::Free];
+
+		let mut on_occupied = default_bitvec(2);
+		on_occupied.set(0, true);
+		let mut on_free = default_bitvec(2);
+		on_free.set(1, true);
+
+		let bitfields = vec![
+			block_on(signed_bitfield(&keystore, on_occupied, ValidatorIndex(0))),
+			block_on(signed_bitfield(&keystore, on_free, ValidatorIndex(1))),
+			block_on(signed_bitfield(&keystore, default_bitvec(3), ValidatorIndex(2))),
+			block_on(signed_bitfield(&keystore, default_bitvec(2), ValidatorIndex(0))),
+		];
+
+		let selected = select_availability_bitfields(&cores, &bitfields, &Hash::repeat_byte(0));
+		let mut exclusions = Exclusions::default();
+		exclusions.note_bitfields(&cores, false, &bitfields, &selected);
+
+		let inherent_data =
+			ProvisionerInherentData { bitfields: selected, ..empty_inherent_data() };
+		let record =
+			record(&leaf(1, LeafStatus::Fresh), &bitfields, &[], &inherent_data, &exclusions);
+
+		let bitfields = record["bitfields"].as_array().unwrap();
+		assert_eq!(bitfields[0]["included"], true);
+		assert_eq!(bitfields[0]["exclusion"], serde_json::Value::Null);
+		assert_eq!(bitfields[1]["exclusion"], exclusion(ExclusionReason::BitfieldUnoccupiedCore));
+		assert_eq!(bitfields[2]["exclusion"], exclusion(ExclusionReason::BitfieldLengthMismatch));
+		assert_eq!(bitfields[3]["exclusion"], exclusion(ExclusionReason::BitfieldSuperseded));
+		assert_eq!(record["timing"]["pre_propose_timeout_ms"], 2000);
+		assert_eq!(record["timing"]["requested_after_ms"], 1500);
+	}
+
+	#[test]
+	fn records_stale_leaf_and_candidate_exclusions() {
+		let keystore: SyncCryptoStorePtr = Arc::new(KeyStore::new());
+		let cores = vec![occupied_core(0)];
+
+		let mut bitvec = default_bitvec(1);
+		bitvec.set(0, true);
+		let bitfields = vec![block_on(signed_bitfield(&keystore, bitvec, ValidatorIndex(0)))];
+
+		let candidates = vec![dummy_candidate_receipt(Hash::repeat_byte(1))];
+		let mut exclusions = Exclusions::default();
+		exclusions.note_bitfields(&cores, true, &bitfields, &[]);
+		exclusions.exclude_candidate(candidates[0].hash(), ExclusionReason::NoSuitableCore);
+
+		let record = record(
+			&leaf(2, LeafStatus::Stale),
+			&bitfields,
+			&candidates,
+			&empty_inherent_data(),
+			&exclusions,
+		);
+
+		assert_eq!(record["stale_leaf"], true);
+		assert_eq!(record["bitfields"][0]["exclusion"], exclusion(ExclusionReason::StaleLeaf));
+		assert_eq!(record["candidates"][0]["included"], false);
+		assert_eq!(
+			record["candidates"][0]["exclusion"],
+			exclusion(ExclusionReason::NoSuitableCore)
+		);
+	}
+
+	#[test]
+	fn keeps_only_the_most_recent_records() {
+		let dir = tempfile::tempdir().unwrap();
+		let config = InherentDataDumpConfig { path: dir.path().join("dump"), max_records: 2 };
+
+		for number in 1..=4 {
+			let record = InherentDataRecord::new::<Error>(
+				&leaf(number, LeafStatus::Fresh),
+				timing(),
+				&[],
+				&[],
+				Ok(&empty_inherent_data()),
+				&Exclusions::default(),
+			);
+			config.write(&record).unwrap();
+		}
+
+		let mut written = std::fs::read_dir(&config.path)
+			.unwrap()
+			.map(|entry| entry.unwrap().file_name().into_string().unwrap())
+			.collect::<Vec<_>>();
+		written.sort();
+
+		assert_eq!(written.len(), 2);
+		assert!(written[0].starts_with("0000000003-"));
+		assert!(written[1].starts_with("0000000004-"));
+	}
+}
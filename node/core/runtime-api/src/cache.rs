@@ -20,12 +20,15 @@ use memory_lru::{MemoryLruCache, ResidentSize};
 use parity_util_mem::{MallocSizeOf, MallocSizeOfExt};
 use sp_consensus_babe::Epoch;
 
-use polkadot_primitives::v2::{
-	AuthorityDiscoveryId, BlockNumber, CandidateCommitments, CandidateEvent,
-	CommittedCandidateReceipt, CoreState, GroupRotationInfo, Hash, Id as ParaId,
-	InboundDownwardMessage, InboundHrmpMessage, OccupiedCoreAssumption, PersistedValidationData,
-	PvfCheckStatement, ScrapedOnChainVotes, SessionIndex, SessionInfo, ValidationCode,
-	ValidationCodeHash, ValidatorId, ValidatorIndex, ValidatorSignature,
+use polkadot_primitives::{
+	v2::{
+		AuthorityDiscoveryId, BlockNumber, CandidateCommitments, CandidateEvent,
+		CommittedCandidateReceipt, CoreState, GroupRotationInfo, Hash, Id as ParaId,
+		InboundDownwardMessage, InboundHrmpMessage, OccupiedCoreAssumption,
+		PersistedValidationData, PvfCheckStatement, ScrapedOnChainVotes, SessionIndex, SessionInfo,
+		ValidationCode, ValidationCodeHash, ValidatorId, ValidatorIndex, ValidatorSignature,
+	},
+	vstaging::{BackingMisbehaviorReport, InherentWeightModel},
 };
 
 const AUTHORITIES_CACHE_SIZE: usize = 128 * 1024;
@@ -47,6 +50,8 @@ const ON_CHAIN_VOTES_CACHE_SIZE: usize = 3 * 1024;
 const PVFS_REQUIRE_PRECHECK_SIZE: usize = 1024;
 const VALIDATION_CODE_HASH_CACHE_SIZE: usize = 64 * 1024;
 const VERSION_CACHE_SIZE: usize = 4 * 1024;
+const INHERENT_WEIGHT_MODEL_CACHE_SIZE: usize = 4 * 1024;
+const POV_DICTIONARY_CACHE_SIZE: usize = 1024 * 1024;
 
 struct ResidentSizeOf<T>(T);
 
@@ -115,6 +120,8 @@ pub(crate) struct RequestResultCache {
 		ResidentSizeOf<Option<ValidationCodeHash>>,
 	>,
 	version: MemoryLruCache<Hash, ResidentSizeOf<u32>>,
+	inherent_weight_model: MemoryLruCache<Hash, DoesNotAllocate<InherentWeightModel>>,
+	pov_dictionary: MemoryLruCache<(Hash, ParaId, Hash), ResidentSizeOf<Option<Vec<u8>>>>,
 }
 
 impl Default for RequestResultCache {
@@ -142,6 +149,8 @@ impl Default for RequestResultCache {
 			pvfs_require_precheck: MemoryLruCache::new(PVFS_REQUIRE_PRECHECK_SIZE),
 			validation_code_hash: MemoryLruCache::new(VALIDATION_CODE_HASH_CACHE_SIZE),
 			version: MemoryLruCache::new(VERSION_CACHE_SIZE),
+			inherent_weight_model: MemoryLruCache::new(INHERENT_WEIGHT_MODEL_CACHE_SIZE),
+			pov_dictionary: MemoryLruCache::new(POV_DICTIONARY_CACHE_SIZE),
 		}
 	}
 }
@@ -407,6 +416,33 @@ impl RequestResultCache {
 	pub(crate) fn cache_version(&mut self, key: Hash, value: u32) {
 		self.version.insert(key, ResidentSizeOf(value));
 	}
+
+	pub(crate) fn inherent_weight_model(
+		&mut self,
+		relay_parent: &Hash,
+	) -> Option<&InherentWeightModel> {
+		self.inherent_weight_model.get(relay_parent).map(|v| &v.0)
+	}
+
+	pub(crate) fn cache_inherent_weight_model(
+		&mut self,
+		relay_parent: Hash,
+		model: InherentWeightModel,
+	) {
+		self.inherent_weight_model.insert(relay_parent, DoesNotAllocate(model));
+	}
+
+	pub(crate) fn pov_dictionary(&mut self, key: (Hash, ParaId, Hash)) -> Option<&Option<Vec<u8>>> {
+		self.pov_dictionary.get(&key).map(|v| &v.0)
+	}
+
+	pub(crate) fn cache_pov_dictionary(
+		&mut self,
+		key: (Hash, ParaId, Hash),
+		value: Option<Vec<u8>>,
+	) {
+		self.pov_dictionary.insert(key, ResidentSizeOf(value));
+	}
 }
 
 pub(crate) enum RequestResult {
@@ -442,4 +478,8 @@ pub(crate) enum RequestResult {
 	SubmitPvfCheckStatement(Hash, PvfCheckStatement, ValidatorSignature, ()),
 	ValidationCodeHash(Hash, ParaId, OccupiedCoreAssumption, Option<ValidationCodeHash>),
 	Version(Hash, u32),
+	StagingInherentWeightModel(Hash, InherentWeightModel),
+	// This is a request with side-effects and no result, hence ().
+	StagingSubmitBackingMisbehaviorReport(Hash, BackingMisbehaviorReport, ()),
+	StagingPoVDictionary(Hash, ParaId, Hash, Option<Vec<u8>>),
 }
@@ -167,6 +167,12 @@ where
 				.cache_validation_code_hash((relay_parent, para_id, assumption), hash),
 			Version(relay_parent, version) =>
 				self.requests_cache.cache_version(relay_parent, version),
+			StagingInherentWeightModel(relay_parent, model) =>
+				self.requests_cache.cache_inherent_weight_model(relay_parent, model),
+			StagingSubmitBackingMisbehaviorReport(_, _, ()) => {},
+			StagingPoVDictionary(relay_parent, para_id, hash, dictionary) => self
+				.requests_cache
+				.cache_pov_dictionary((relay_parent, para_id, hash), dictionary),
 		}
 	}
 
@@ -268,6 +274,15 @@ where
 			Request::ValidationCodeHash(para, assumption, sender) =>
 				query!(validation_code_hash(para, assumption), sender)
 					.map(|sender| Request::ValidationCodeHash(para, assumption, sender)),
+			Request::StagingInherentWeightModel(sender) => query!(inherent_weight_model(), sender)
+				.map(|sender| Request::StagingInherentWeightModel(sender)),
+			request @ Request::StagingSubmitBackingMisbehaviorReport(_, _) => {
+				// This request is side-effecting and thus cannot be cached.
+				Some(request)
+			},
+			Request::StagingPoVDictionary(para, hash, sender) =>
+				query!(pov_dictionary(para, hash), sender)
+					.map(|sender| Request::StagingPoVDictionary(para, hash, sender)),
 		}
 	}
 
@@ -369,7 +384,7 @@ where
 	let _timer = metrics.time_make_runtime_api_request();
 
 	macro_rules! query {
-		($req_variant:ident, $api_name:ident ($($param:expr),*), ver = $version:literal, $sender:expr) => {{
+		($req_variant:ident, $api_name:ident ($($param:expr),*), ver = $version:expr, $sender:expr) => {{
 			let sender = $sender;
 			let api = client.runtime_api();
 
@@ -524,6 +539,24 @@ where
 		},
 		Request::ValidationCodeHash(para, assumption, sender) =>
 			query!(ValidationCodeHash, validation_code_hash(para, assumption), ver = 2, sender),
+		Request::StagingInherentWeightModel(sender) => query!(
+			StagingInherentWeightModel,
+			staging_inherent_weight_model(),
+			ver = Request::INHERENT_WEIGHT_MODEL_RUNTIME_REQUIREMENT,
+			sender
+		),
+		Request::StagingSubmitBackingMisbehaviorReport(report, sender) => query!(
+			StagingSubmitBackingMisbehaviorReport,
+			staging_submit_backing_misbehavior_report(report),
+			ver = Request::BACKING_MISBEHAVIOR_REPORT_RUNTIME_REQUIREMENT,
+			sender
+		),
+		Request::StagingPoVDictionary(para, hash, sender) => query!(
+			StagingPoVDictionary,
+			staging_pov_dictionary(para, hash),
+			ver = Request::POV_DICTIONARY_RUNTIME_REQUIREMENT,
+			sender
+		),
 	}
 }
 
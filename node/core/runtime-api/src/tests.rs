@@ -20,12 +20,15 @@ use ::test_helpers::{dummy_committed_candidate_receipt, dummy_validation_code};
 use futures::channel::oneshot;
 use polkadot_node_primitives::{BabeAllowedSlots, BabeEpoch, BabeEpochConfiguration};
 use polkadot_node_subsystem_test_helpers::make_subsystem_context;
-use polkadot_primitives::v2::{
-	AuthorityDiscoveryId, BlockNumber, CandidateEvent, CandidateHash, CommittedCandidateReceipt,
-	CoreState, DisputeState, GroupRotationInfo, Id as ParaId, InboundDownwardMessage,
-	InboundHrmpMessage, OccupiedCoreAssumption, PersistedValidationData, PvfCheckStatement,
-	ScrapedOnChainVotes, SessionIndex, SessionInfo, ValidationCode, ValidationCodeHash,
-	ValidatorId, ValidatorIndex, ValidatorSignature,
+use polkadot_primitives::{
+	v2::{
+		AuthorityDiscoveryId, BlockNumber, CandidateEvent, CandidateHash,
+		CommittedCandidateReceipt, CoreState, DisputeState, GroupRotationInfo, Id as ParaId,
+		InboundDownwardMessage, InboundHrmpMessage, OccupiedCoreAssumption,
+		PersistedValidationData, PvfCheckStatement, ScrapedOnChainVotes, SessionIndex, SessionInfo,
+		ValidationCode, ValidationCodeHash, ValidatorId, ValidatorIndex, ValidatorSignature,
+	},
+	vstaging::{BackingMisbehavior, BackingMisbehaviorReport, InherentWeightModel},
 };
 use sp_core::testing::TaskExecutor;
 use std::{
@@ -55,6 +58,9 @@ struct MockRuntimeApi {
 	submitted_pvf_check_statement: Arc<Mutex<Vec<(PvfCheckStatement, ValidatorSignature)>>>,
 	pvfs_require_precheck: Vec<ValidationCodeHash>,
 	validation_code_hash: HashMap<ParaId, ValidationCodeHash>,
+	inherent_weight_model: InherentWeightModel,
+	submitted_backing_misbehavior_reports: Arc<Mutex<Vec<BackingMisbehaviorReport>>>,
+	pov_dictionaries: HashMap<(ParaId, Hash), Vec<u8>>,
 }
 
 impl ProvideRuntimeApi<Block> for MockRuntimeApi {
@@ -194,6 +200,22 @@ sp_api::mock_impl_runtime_apis! {
 		fn staging_get_disputes() -> Vec<(SessionIndex, CandidateHash, DisputeState<BlockNumber>)> {
 			unimplemented!()
 		}
+
+		fn staging_inherent_weight_model() -> InherentWeightModel {
+			self.inherent_weight_model
+		}
+
+		fn staging_submit_backing_misbehavior_report(report: BackingMisbehaviorReport) {
+			self
+				.submitted_backing_misbehavior_reports
+				.lock()
+				.expect("poisoned mutex")
+				.push(report);
+		}
+
+		fn staging_pov_dictionary(para: ParaId, hash: Hash) -> Option<Vec<u8>> {
+			self.pov_dictionaries.get(&(para, hash)).cloned()
+		}
 	}
 
 	impl BabeApi<Block> for MockRuntimeApi {
@@ -1046,3 +1068,135 @@ fn requests_validation_code_hash() {
 
 	futures::executor::block_on(future::join(subsystem_task, test_task));
 }
+
+#[test]
+fn inherent_weight_model_requires_staging_runtime() {
+	let (ctx, mut ctx_handle) = make_subsystem_context(TaskExecutor::new());
+
+	let relay_parent = [1; 32].into();
+	let spawner = sp_core::testing::TaskExecutor::new();
+	let inherent_weight_model = InherentWeightModel {
+		max_block_weight: 1_000_000,
+		bitfield_weight: 10,
+		backed_candidate_base_weight: 100,
+		backed_candidate_per_vote_weight: 5,
+		backed_candidate_code_upgrade_weight: 500,
+		dispute_statement_set_base_weight: 200,
+		dispute_statement_weight: 20,
+	};
+
+	let mut runtime_api = MockRuntimeApi::default();
+	runtime_api.inherent_weight_model = inherent_weight_model;
+	let runtime_api = Arc::new(runtime_api);
+
+	let subsystem = RuntimeApiSubsystem::new(runtime_api.clone(), Metrics(None), spawner);
+	let subsystem_task = run(ctx, subsystem).map(|x| x.unwrap());
+	let test_task = async move {
+		let (tx, rx) = oneshot::channel();
+
+		ctx_handle
+			.send(FromOverseer::Communication {
+				msg: RuntimeApiMessage::Request(
+					relay_parent,
+					Request::StagingInherentWeightModel(tx),
+				),
+			})
+			.await;
+
+		// The mock runtime reports the current runtime API version, which doesn't include the
+		// staging weight model yet.
+		assert!(matches!(rx.await.unwrap(), Err(RuntimeApiError::NotSupported { .. })));
+
+		ctx_handle.send(FromOverseer::Signal(OverseerSignal::Conclude)).await;
+	};
+
+	futures::executor::block_on(future::join(subsystem_task, test_task));
+}
+
+#[test]
+fn submit_backing_misbehavior_report_requires_staging_runtime() {
+	let (ctx, mut ctx_handle) = make_subsystem_context(TaskExecutor::new());
+	let spawner = sp_core::testing::TaskExecutor::new();
+
+	let runtime_api = Arc::new(MockRuntimeApi::default());
+	let subsystem = RuntimeApiSubsystem::new(runtime_api.clone(), Metrics(None), spawner);
+	let subsystem_task = run(ctx, subsystem).map(|x| x.unwrap());
+
+	let relay_parent = [1; 32].into();
+	let report = BackingMisbehaviorReport {
+		session_index: 1,
+		relay_parent,
+		validator_index: ValidatorIndex(2),
+		misbehavior: BackingMisbehavior::MultipleCandidates {
+			first: (
+				CandidateHash([2; 32].into()),
+				sp_core::crypto::UncheckedFrom::unchecked_from([3; 64]),
+			),
+			second: (
+				CandidateHash([4; 32].into()),
+				sp_core::crypto::UncheckedFrom::unchecked_from([5; 64]),
+			),
+		},
+	};
+
+	let test_task = async move {
+		let (tx, rx) = oneshot::channel();
+		ctx_handle
+			.send(FromOverseer::Communication {
+				msg: RuntimeApiMessage::Request(
+					relay_parent,
+					Request::StagingSubmitBackingMisbehaviorReport(report, tx),
+				),
+			})
+			.await;
+
+		// The mock runtime reports the current runtime API version, which doesn't include the
+		// staging misbehavior reports yet.
+		assert!(matches!(rx.await.unwrap(), Err(RuntimeApiError::NotSupported { .. })));
+		assert!(runtime_api
+			.submitted_backing_misbehavior_reports
+			.lock()
+			.expect("poisoned mutex")
+			.is_empty());
+
+		ctx_handle.send(FromOverseer::Signal(OverseerSignal::Conclude)).await;
+	};
+
+	futures::executor::block_on(future::join(subsystem_task, test_task));
+}
+
+#[test]
+fn pov_dictionary_requires_staging_runtime() {
+	let (ctx, mut ctx_handle) = make_subsystem_context(TaskExecutor::new());
+	let spawner = sp_core::testing::TaskExecutor::new();
+
+	let relay_parent = [1; 32].into();
+	let para_a = ParaId::from(5_u32);
+	let hash = Hash::repeat_byte(2);
+
+	let mut runtime_api = MockRuntimeApi::default();
+	runtime_api.pov_dictionaries.insert((para_a, hash), vec![3; 64]);
+	let runtime_api = Arc::new(runtime_api);
+
+	let subsystem = RuntimeApiSubsystem::new(runtime_api.clone(), Metrics(None), spawner);
+	let subsystem_task = run(ctx, subsystem).map(|x| x.unwrap());
+	let test_task = async move {
+		let (tx, rx) = oneshot::channel();
+		ctx_handle
+			.send(FromOverseer::Communication {
+				msg: RuntimeApiMessage::Request(
+					relay_parent,
+					Request::StagingPoVDictionary(para_a, hash, tx),
+				),
+			})
+			.await;
+
+		// The mock runtime reports the current runtime API version, which doesn't include the
+		// staging PoV dictionaries yet.
+		assert!(matches!(rx.await.unwrap(), Err(RuntimeApiError::NotSupported { .. })));
+
+		ctx_handle.send(FromOverseer::Signal(OverseerSignal::Conclude)).await;
+	};
+
+	futures::executor::block_on(future::join(subsystem_task, test_task));
+}
@@ -0,0 +1,228 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Structured JSON output of `gum` events.
+//!
+//! A [`JsonOutput`] prints events of the `parachain` targets as single lines of JSON to stdout.
+//! It is meant to be installed once, as a handler of a layer of the global subscriber which
+//! only passes on the events of the selected targets, alongside the regular text logger. The
+//! fields identifying what an event is about, i.e. [`FIRST_CLASS_KEYS`], are top level keys of
+//! the JSON object, all remaining fields are collected under `fields`.
+//!
+//! Noisy targets can be tamed by sampling their events or limiting the number of events
+//! printed per second, see [`TargetLimit`].
+
+use std::{
+	collections::HashMap,
+	fmt,
+	io::{self, Write},
+	sync::Mutex,
+	time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use serde_json::Map;
+pub use serde_json::Value;
+use tracing::Level;
+
+/// The keys printed at the top level of every JSON object, if the event carries them.
+pub const FIRST_CLASS_KEYS: &[&str] =
+	&["candidate_hash", "relay_parent", "para_id", "peer_id", "traceID"];
+
+/// The prefix of the targets of all parachain subsystems.
+pub const TARGET_PREFIX: &str = "parachain";
+
+/// Limits on the events of a target printed as JSON.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TargetLimit {
+	/// Only print one in every `sample_one_in` events. `1` prints every event.
+	pub sample_one_in: u32,
+	/// Print at most this many events per second.
+	pub max_per_second: Option<u32>,
+}
+
+impl Default for TargetLimit {
+	fn default() -> Self {
+		Self { sample_one_in: 1, max_per_second: None }
+	}
+}
+
+/// Configuration of the JSON output.
+#[derive(Debug, Clone, Default)]
+pub struct JsonConfig {
+	/// Limits per target. Each applies to all targets it is a prefix of, the longest prefix
+	/// matching a target wins.
+	pub limits: HashMap<String, TargetLimit>,
+}
+
+/// Prints the events of the `parachain` targets as JSON, to stdout by default.
+///
+/// Which events are passed to it is up to the layer it is installed in, events of other
+/// targets are ignored.
+pub struct JsonOutput<W = fn() -> io::Stdout> {
+	limits: HashMap<String, TargetLimit>,
+	state: Mutex<HashMap<String, LimitState>>,
+	make_writer: W,
+}
+
+impl JsonOutput {
+	/// Create the JSON output, printing to stdout.
+	pub fn new(config: JsonConfig) -> Self {
+		Self::with_writer(config, io::stdout)
+	}
+}
+
+impl<W, O> JsonOutput<W>
+where
+	W: Fn() -> O,
+	O: Write,
+{
+	/// Create the JSON output, printing each line to a writer made by `make_writer`.
+	pub fn with_writer(config: JsonConfig, make_writer: W) -> Self {
+		Self { limits: config.limits, state: Mutex::new(HashMap::new()), make_writer }
+	}
+
+	/// Print an event, given all its fields including the `message`.
+	pub fn print(
+		&self,
+		level: &Level,
+		target: &str,
+		fields: impl IntoIterator<Item = (String, Value)>,
+	) {
+		if !target.starts_with(TARGET_PREFIX) {
+			return
+		}
+
+		let suppressed = match self.admit(target) {
+			Some(suppressed) => suppressed,
+			None => return,
+		};
+
+		let mut message = None;
+		let mut fields: Map<String, Value> = fields
+			.into_iter()
+			.filter_map(|(name, value)| {
+				if name == "message" {
+					message = Some(match value {
+						Value::String(s) => s,
+						other => other.to_string(),
+					});
+					None
+				} else {
+					Some((name, value))
+				}
+			})
+			.collect();
+
+		let timestamp =
+			SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64;
+
+		let mut object = Map::new();
+		object.insert("timestamp".into(), timestamp.into());
+		object.insert("level".into(), level.to_string().into());
+		object.insert("target".into(), target.into());
+		if let Some(message) = message {
+			object.insert("message".into(), message.into());
+		}
+		for key in FIRST_CLASS_KEYS {
+			if let Some(value) = fields.remove(*key) {
+				object.insert((*key).into(), value);
+			}
+		}
+		if !fields.is_empty() {
+			object.insert("fields".into(), Value::Object(fields));
+		}
+		if suppressed > 0 {
+			object.insert("suppressed".into(), suppressed.into());
+		}
+
+		let mut line = Value::Object(object).to_string();
+		line.push('\n');
+		// Logging must never take the node down, so failing writes are ignored.
+		let _ = (self.make_writer)().write_all(line.as_bytes());
+	}
+
+	/// The limit of the longest configured prefix of `target`.
+	fn limit_for(&self, target: &str) -> Option<&TargetLimit> {
+		self.limits
+			.iter()
+			.filter(|(prefix, _)| target.starts_with(prefix.as_str()))
+			.max_by_key(|(prefix, _)| prefix.len())
+			.map(|(_, limit)| limit)
+	}
+
+	/// Returns `None` if the event is to be dropped, or the number of events suppressed since
+	/// the last one printed for the target.
+	fn admit(&self, target: &str) -> Option<u64> {
+		let limit = match self.limit_for(target) {
+			Some(limit) => limit,
+			None => return Some(0),
+		};
+
+		let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+		if !state.contains_key(target) {
+			state.insert(target.to_owned(), LimitState::default());
+		}
+		let state = state.get_mut(target).expect("inserted above; qed");
+		if state.admit(limit, Instant::now()) {
+			Some(std::mem::take(&mut state.suppressed))
+		} else {
+			None
+		}
+	}
+}
+
+impl<W> fmt::Debug for JsonOutput<W> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("JsonOutput").field("limits", &self.limits).finish()
+	}
+}
+
+/// Sampling and rate limiting state of a target.
+#[derive(Default)]
+struct LimitState {
+	seen: u64,
+	window_start: Option<Instant>,
+	printed_in_window: u32,
+	suppressed: u64,
+}
+
+impl LimitState {
+	/// Whether to print the next event, given the limit.
+	fn admit(&mut self, limit: &TargetLimit, now: Instant) -> bool {
+		self.seen += 1;
+		if limit.sample_one_in > 1 && (self.seen - 1) % u64::from(limit.sample_one_in) != 0 {
+			self.suppressed += 1;
+			return false
+		}
+
+		if let Some(max_per_second) = limit.max_per_second {
+			match self.window_start {
+				Some(start) if now.saturating_duration_since(start) < Duration::from_secs(1) => {},
+				_ => {
+					self.window_start = Some(now);
+					self.printed_in_window = 0;
+				},
+			}
+			if self.printed_in_window >= max_per_second {
+				self.suppressed += 1;
+				return false
+			}
+			self.printed_in_window += 1;
+		}
+
+		true
+	}
+}
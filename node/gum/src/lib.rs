@@ -31,5 +31,7 @@ pub use polkadot_primitives::v2::{CandidateHash, Hash};
 
 pub use gum_proc_macro::{debug, error, info, trace, warn};
 
+pub mod json;
+
 #[cfg(test)]
 mod tests;
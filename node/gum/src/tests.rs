@@ -102,3 +102,130 @@ fn w_candidate_hash_aliased_unnecessary() {
 		"xxx",
 	);
 }
+
+mod json {
+	use super::*;
+	use crate::json::{JsonConfig, JsonOutput, TargetLimit, Value};
+	use std::{
+		collections::HashMap,
+		io,
+		sync::{Arc, Mutex},
+	};
+
+	#[derive(Clone, Default)]
+	struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+	impl io::Write for Buffer {
+		fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+			self.0.lock().unwrap().extend_from_slice(buf);
+			Ok(buf.len())
+		}
+
+		fn flush(&mut self) -> io::Result<()> {
+			Ok(())
+		}
+	}
+
+	impl Buffer {
+		fn lines(&self) -> Vec<Value> {
+			let raw = self.0.lock().unwrap().clone();
+			String::from_utf8(raw)
+				.unwrap()
+				.lines()
+				.map(|line| serde_json::from_str(line).unwrap())
+				.collect()
+		}
+	}
+
+	fn json_output(
+		limits: HashMap<String, TargetLimit>,
+	) -> (JsonOutput<impl Fn() -> Buffer>, Buffer) {
+		let buffer = Buffer::default();
+		let writer = buffer.clone();
+		(JsonOutput::with_writer(JsonConfig { limits }, move || writer.clone()), buffer)
+	}
+
+	fn message(text: &str) -> (String, Value) {
+		("message".to_owned(), text.into())
+	}
+
+	#[test]
+	fn first_class_keys() {
+		let candidate_hash = CandidateHash(Hash::repeat_byte(0xF1));
+		let trace_id = crate::hash_to_trace_identifier(candidate_hash.0).to_string();
+		let (output, buffer) = json_output(HashMap::new());
+
+		output.print(
+			&Level::WARN,
+			"parachain::foo",
+			vec![
+				message("Something happened"),
+				("candidate_hash".to_owned(), format!("{:?}", candidate_hash).into()),
+				("para_id".to_owned(), 2000_u32.into()),
+				("peer_id".to_owned(), "12D3KooW".into()),
+				("traceID".to_owned(), trace_id.clone().into()),
+				("b".to_owned(), "Y { x: 0 }".into()),
+			],
+		);
+
+		let lines = buffer.lines();
+		assert_eq!(lines.len(), 1);
+		let line = &lines[0];
+		assert_eq!(line["target"], "parachain::foo");
+		assert_eq!(line["level"], "WARN");
+		assert_eq!(line["message"], "Something happened");
+		assert_eq!(line["candidate_hash"], format!("{:?}", candidate_hash));
+		assert!(line.get("relay_parent").is_none());
+		assert_eq!(line["para_id"], 2000);
+		assert_eq!(line["peer_id"], "12D3KooW");
+		assert_eq!(line["traceID"], trace_id);
+		assert_eq!(line["fields"], serde_json::json!({ "b": "Y { x: 0 }" }));
+	}
+
+	#[test]
+	fn only_parachain_targets_are_printed() {
+		let (output, buffer) = json_output(HashMap::new());
+
+		output.print(&Level::INFO, "sync", vec![message("not a subsystem")]);
+		output.print(&Level::INFO, "sc_tracing", vec![message("not a subsystem either")]);
+		output.print(&Level::INFO, "parachain::approval-voting", vec![message("printed")]);
+
+		let lines = buffer.lines();
+		assert_eq!(lines.len(), 1);
+		assert_eq!(lines[0]["target"], "parachain::approval-voting");
+		assert_eq!(lines[0]["message"], "printed");
+	}
+
+	#[test]
+	fn sampling_and_rate_limit() {
+		let limits = [
+			("parachain::noisy".to_owned(), TargetLimit { sample_one_in: 2, max_per_second: None }),
+			(
+				"parachain::noisy::very".to_owned(),
+				TargetLimit { sample_one_in: 1, max_per_second: Some(3) },
+			),
+		]
+		.into_iter()
+		.collect();
+		let (output, buffer) = json_output(limits);
+
+		for i in 0..10_u32 {
+			let fields = || vec![message("event"), ("i".to_owned(), i.into())];
+			output.print(&Level::INFO, "parachain::noisy::a", fields());
+			output.print(&Level::INFO, "parachain::noisy::very::b", fields());
+			output.print(&Level::INFO, "parachain::quiet", fields());
+		}
+
+		let lines = buffer.lines();
+		let count = |target: &str| lines.iter().filter(|l| l["target"] == target).count();
+		assert_eq!(count("parachain::noisy::a"), 5);
+		assert_eq!(count("parachain::noisy::very::b"), 3);
+		assert_eq!(count("parachain::quiet"), 10);
+
+		// The number of suppressed events is reported with the next event printed.
+		let sampled: Vec<_> =
+			lines.iter().filter(|l| l["target"] == "parachain::noisy::a").collect();
+		assert!(sampled[0].get("suppressed").is_none());
+		assert_eq!(sampled[1]["suppressed"], 1);
+	}
+}
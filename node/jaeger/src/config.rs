@@ -16,20 +16,65 @@
 
 //! Polkadot Jaeger configuration.
 
+use std::str::FromStr;
+
+/// The protocol used to export spans to an OpenTelemetry collector.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OtlpProtocol {
+	/// OTLP over gRPC, commonly served on port `4317`.
+	Grpc,
+	/// OTLP over HTTP with protobuf payloads, commonly served on port `4318`.
+	Http,
+}
+
+impl Default for OtlpProtocol {
+	fn default() -> Self {
+		OtlpProtocol::Grpc
+	}
+}
+
+impl FromStr for OtlpProtocol {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s {
+			"grpc" => Ok(OtlpProtocol::Grpc),
+			"http" => Ok(OtlpProtocol::Http),
+			other => Err(format!("Unknown OTLP protocol `{}`, expected `grpc` or `http`", other)),
+		}
+	}
+}
+
+/// The destination of the collected spans.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JaegerBackend {
+	/// A jaeger agent, listening for `UDP` packets on the given address.
+	Agent(std::net::SocketAddr),
+	/// An OpenTelemetry collector, receiving spans via OTLP.
+	Otlp {
+		/// The endpoint of the collector, e.g. `http://127.0.0.1:4317`.
+		endpoint: String,
+		/// The protocol the collector is served with.
+		protocol: OtlpProtocol,
+	},
+}
+
 /// Configuration for the jaeger tracing.
 #[derive(Clone)]
 pub struct JaegerConfig {
 	pub(crate) node_name: String,
-	pub(crate) agent_addr: std::net::SocketAddr,
+	pub(crate) backend: JaegerBackend,
 }
 
 impl std::default::Default for JaegerConfig {
 	fn default() -> Self {
 		Self {
 			node_name: "unknown_".to_owned(),
-			agent_addr: "127.0.0.1:6831"
-				.parse()
-				.expect(r#"Static "127.0.0.1:6831" is a valid socket address string. qed"#),
+			backend: JaegerBackend::Agent(
+				"127.0.0.1:6831"
+					.parse()
+					.expect(r#"Static "127.0.0.1:6831" is a valid socket address string. qed"#),
+			),
 		}
 	}
 }
@@ -62,7 +107,22 @@ impl JaegerConfigBuilder {
 	where
 		U: Into<std::net::SocketAddr>,
 	{
-		self.inner.agent_addr = addr.into();
+		self.inner.backend = JaegerBackend::Agent(addr.into());
+		self
+	}
+
+	/// Send the collected spans to an OpenTelemetry collector instead of a jaeger agent.
+	pub fn otlp<S>(mut self, endpoint: S, protocol: OtlpProtocol) -> Self
+	where
+		S: Into<String>,
+	{
+		self.inner.backend = JaegerBackend::Otlp { endpoint: endpoint.into(), protocol };
+		self
+	}
+
+	/// Set the destination of the collected spans.
+	pub fn backend(mut self, backend: JaegerBackend) -> Self {
+		self.inner.backend = backend;
 		self
 	}
 
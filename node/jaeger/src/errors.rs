@@ -25,4 +25,7 @@ pub enum JaegerError {
 
 	#[error("Missing jaeger configuration")]
 	MissingConfiguration,
+
+	#[error("Failed to set up the OTLP exporter")]
+	Otlp(#[source] opentelemetry::trace::TraceError),
 }
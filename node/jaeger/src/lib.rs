@@ -43,16 +43,21 @@
 //!  -p 9411:9411 \
 //!  docker.io/jaegertracing/all-in-one:1.21
 //! ```
+//!
+//! Alternatively, spans can be exported via OTLP to any OpenTelemetry collector, see
+//! [`JaegerConfigBuilder::otlp`]. Trace identifiers are the same for both backends.
 
 #![forbid(unused_imports)]
 
 mod config;
 mod errors;
+mod otlp;
 mod spans;
 
 pub use self::{
-	config::{JaegerConfig, JaegerConfigBuilder},
+	config::{JaegerBackend, JaegerConfig, JaegerConfigBuilder, OtlpProtocol},
 	errors::JaegerError,
+	otlp::OtlpSpan,
 	spans::{hash_to_trace_identifier, PerLeafSpan, Span, Stage},
 };
 
@@ -67,13 +72,18 @@ lazy_static::lazy_static! {
 	static ref INSTANCE: RwLock<Jaeger> = RwLock::new(Jaeger::None);
 }
 
-/// Stateful convenience wrapper around [`mick_jaeger`].
+/// Stateful convenience wrapper around [`mick_jaeger`] and the OTLP exporter.
 pub enum Jaeger {
 	/// Launched and operational state.
 	Launched {
 		/// [`mick_jaeger`] provided API to record spans to.
 		traces_in: Arc<mick_jaeger::TracesIn>,
 	},
+	/// Launched and operational state, exporting spans via OTLP.
+	LaunchedOtlp {
+		/// The tracer of the installed OTLP pipeline to record spans to.
+		tracer: opentelemetry::sdk::trace::Tracer,
+	},
 	/// Preparation state with the necessary config to launch the collector.
 	Prep(JaegerConfig),
 	/// Uninitialized, suggests wrong API usage if encountered.
@@ -112,11 +122,21 @@ impl Jaeger {
 	pub fn launch<S: SpawnNamed>(self, spawner: S) -> result::Result<(), JaegerError> {
 		let cfg = match self {
 			Self::Prep(cfg) => Ok(cfg),
-			Self::Launched { .. } => return Err(JaegerError::AlreadyLaunched),
+			Self::Launched { .. } | Self::LaunchedOtlp { .. } =>
+				return Err(JaegerError::AlreadyLaunched),
 			Self::None => Err(JaegerError::MissingConfiguration),
 		}?;
 
-		let jaeger_agent = cfg.agent_addr;
+		let jaeger_agent = match cfg.backend {
+			JaegerBackend::Agent(addr) => addr,
+			JaegerBackend::Otlp { endpoint, protocol } => {
+				log::info!("🐹 Exporting spans via OTLP ({:?}) to {}", protocol, &endpoint);
+
+				let tracer = otlp::install(&cfg.node_name, endpoint, protocol)?;
+				*INSTANCE.write() = Self::LaunchedOtlp { tracer };
+				return Ok(())
+			},
+		};
 
 		log::info!("🐹 Collecting jaeger spans for {:?}", &jaeger_agent);
 
@@ -152,16 +172,22 @@ impl Jaeger {
 	///
 	/// The deferral allows to avoid the additional CPU runtime cost in case of
 	/// items that are not a pre-computed hash by themselves.
-	pub(crate) fn span<F>(&self, lazy_hash: F, span_name: &'static str) -> Option<mick_jaeger::Span>
+	pub(crate) fn span<F>(&self, lazy_hash: F, span_name: &'static str) -> Span
 	where
 		F: Fn() -> TraceIdentifier,
 	{
-		if let Self::Launched { traces_in, .. } = self {
-			let ident = lazy_hash();
-			let trace_id = std::num::NonZeroU128::new(ident)?;
-			Some(traces_in.span(trace_id, span_name))
-		} else {
-			None
+		match self {
+			Self::Launched { traces_in, .. } => {
+				let ident = lazy_hash();
+				std::num::NonZeroU128::new(ident)
+					.map(|trace_id| traces_in.span(trace_id, span_name))
+					.into()
+			},
+			Self::LaunchedOtlp { tracer } => match lazy_hash() {
+				0 => Span::Disabled,
+				ident => Span::Otlp(OtlpSpan::new(tracer, ident, span_name)),
+			},
+			_ => Span::Disabled,
 		}
 	}
 }
@@ -0,0 +1,157 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Export of spans to an OpenTelemetry collector via OTLP.
+//!
+//! Spans keep the [`TraceIdentifier`] derived from relay parents and candidate hashes as
+//! their trace id, so spans of the same candidate still correlate across nodes, no matter
+//! which backend each of them exports to.
+
+use opentelemetry::{
+	sdk::{
+		trace::{self as sdk_trace, Tracer},
+		Resource,
+	},
+	trace::{Span as _, TraceContextExt, TraceId, Tracer as _},
+	Context, KeyValue,
+};
+
+use crate::spans::TraceIdentifier;
+#[cfg(not(target_os = "unknown"))]
+use crate::{config::OtlpProtocol, errors::JaegerError};
+
+/// Install a batching OTLP exporter sending spans to the collector at `endpoint`.
+///
+/// Must be called from within a `tokio` runtime, which the exporter spawns its
+/// background task on.
+#[cfg(not(target_os = "unknown"))]
+pub(crate) fn install(
+	node_name: &str,
+	endpoint: String,
+	protocol: OtlpProtocol,
+) -> Result<Tracer, JaegerError> {
+	let exporter: opentelemetry_otlp::SpanExporterBuilder = match protocol {
+		OtlpProtocol::Grpc =>
+			opentelemetry_otlp::new_exporter().tonic().with_endpoint(endpoint).into(),
+		OtlpProtocol::Http =>
+			opentelemetry_otlp::new_exporter().http().with_endpoint(endpoint).into(),
+	};
+
+	opentelemetry_otlp::new_pipeline()
+		.tracing()
+		.with_exporter(exporter)
+		.with_trace_config(sdk_trace::config().with_resource(Resource::new(vec![KeyValue::new(
+			"service.name",
+			format!("polkadot-{}", node_name),
+		)])))
+		.install_batch(opentelemetry::runtime::Tokio)
+		.map_err(JaegerError::Otlp)
+}
+
+/// A span exported via OTLP, ended once dropped.
+pub struct OtlpSpan {
+	inner: sdk_trace::Span,
+	tracer: Tracer,
+	trace_id: TraceIdentifier,
+}
+
+impl OtlpSpan {
+	/// Start a new root span with the given trace identifier.
+	pub(crate) fn new(tracer: &Tracer, trace_id: TraceIdentifier, name: &'static str) -> Self {
+		let mut builder = tracer.span_builder(name);
+		builder.trace_id = Some(to_otlp_trace_id(trace_id));
+		let inner = tracer.build(builder);
+		Self { inner, tracer: tracer.clone(), trace_id }
+	}
+
+	/// Start a child span, which shares the trace identifier.
+	pub(crate) fn child(&self, name: &'static str) -> Self {
+		let parent = Context::new().with_remote_span_context(self.inner.span_context().clone());
+		let inner = self.tracer.start_with_context(name, &parent);
+		Self { inner, tracer: self.tracer.clone(), trace_id: self.trace_id }
+	}
+
+	/// Record that this span follows from `other`.
+	///
+	/// OTLP only allows links to be added when a span is started, so the relationship is
+	/// recorded as an event carrying the identifiers of the other span instead.
+	pub(crate) fn add_follows_from(&mut self, other: &Self) {
+		let other = other.inner.span_context();
+		self.inner.add_event(
+			"follows-from",
+			vec![
+				KeyValue::new("trace-id", other.trace_id().to_string()),
+				KeyValue::new("span-id", other.span_id().to_string()),
+			],
+		);
+	}
+
+	pub(crate) fn add_string_tag(&mut self, tag: &'static str, val: &str) {
+		self.inner.set_attribute(KeyValue::new(tag, val.to_owned()));
+	}
+
+	pub(crate) fn add_int_tag(&mut self, tag: &'static str, value: i64) {
+		self.inner.set_attribute(KeyValue::new(tag, value));
+	}
+
+	/// The trace identifier of the span.
+	pub(crate) fn trace_id(&self) -> TraceIdentifier {
+		self.trace_id
+	}
+}
+
+/// Convert our trace identifier into the OTLP representation.
+///
+/// The big endian bytes are used, so the hex representation of the trace id matches the
+/// leading bytes of the hash it was derived from.
+pub(crate) fn to_otlp_trace_id(trace_id: TraceIdentifier) -> TraceId {
+	TraceId::from_bytes(trace_id.to_be_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::spans::hash_to_trace_identifier;
+	use opentelemetry::trace::TracerProvider as _;
+	use polkadot_primitives::v2::Hash;
+
+	#[test]
+	fn trace_id_is_derived_from_hash() {
+		let provider = sdk_trace::TracerProvider::builder().build();
+		let tracer = provider.tracer("polkadot-jaeger-test");
+
+		// Distinct bytes, such that byte order and truncation bugs show.
+		let mut bytes = [0u8; 32];
+		bytes.iter_mut().enumerate().for_each(|(i, b)| *b = i as u8);
+		let hash = Hash::from(bytes);
+		assert_eq!(hash_to_trace_identifier(hash), 0x000102030405060708090a0b0c0d0e0f);
+
+		let expected = TraceId::from_bytes([
+			0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d,
+			0x0e, 0x0f,
+		]);
+
+		let span = OtlpSpan::new(&tracer, hash_to_trace_identifier(hash), "root");
+		let mut child = span.child("child");
+		child.add_int_tag("tag", 7);
+		child.add_follows_from(&span);
+
+		assert_eq!(span.inner.span_context().trace_id(), expected);
+		assert_eq!(child.inner.span_context().trace_id(), expected);
+		assert_ne!(span.inner.span_context().span_id(), child.inner.span_context().span_id());
+		assert_eq!(child.trace_id(), hash_to_trace_identifier(hash));
+	}
+}
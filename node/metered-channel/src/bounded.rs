@@ -25,14 +25,14 @@ use futures::{
 
 use std::{pin::Pin, result};
 
-use super::{measure_tof_check, CoarseInstant, MaybeTimeOfFlight, Meter};
+use super::{measure_tof_check, CoarseInstant, MaybeTimeOfFlight, MessageLabel, Meter};
 
 /// Create a wrapped `mpsc::channel` pair of `MeteredSender` and `MeteredReceiver`.
 pub fn channel<T>(capacity: usize) -> (MeteredSender<T>, MeteredReceiver<T>) {
 	let (tx, rx) = mpsc::channel::<MaybeTimeOfFlight<T>>(capacity);
 	let shared_meter = Meter::default();
 	let tx = MeteredSender { meter: shared_meter.clone(), inner: tx };
-	let rx = MeteredReceiver { meter: shared_meter, inner: rx };
+	let rx = MeteredReceiver { meter: shared_meter, inner: rx, label: None };
 	(tx, rx)
 }
 
@@ -42,6 +42,8 @@ pub struct MeteredReceiver<T> {
 	// count currently contained messages
 	meter: Meter,
 	inner: mpsc::Receiver<MaybeTimeOfFlight<T>>,
+	// labels time of flight samples by message type
+	label: Option<MessageLabel<T>>,
 }
 
 impl<T> std::ops::Deref for MeteredReceiver<T> {
@@ -82,6 +84,9 @@ impl<T> MeteredReceiver<T> {
 					// `coarsetime` does a saturating sub for all `CoarseInstant` substractions
 					let duration = tof_start.elapsed();
 					self.meter.note_time_of_flight(duration);
+					if let Some(MessageLabel(label)) = self.label {
+						self.meter.note_message_time_of_flight(label(&value), duration);
+					}
 					value
 				},
 				MaybeTimeOfFlight::<T>::Bare(value) => value,
@@ -95,6 +100,12 @@ impl<T> MeteredReceiver<T> {
 		&self.meter
 	}
 
+	/// Additionally sample the time of flight per message type, as labelled by `label`.
+	pub fn with_message_label(mut self, label: fn(&T) -> &'static str) -> Self {
+		self.label = Some(MessageLabel(label));
+		self
+	}
+
 	/// Attempt to receive the next item.
 	pub fn try_next(&mut self) -> Result<Option<T>, mpsc::TryRecvError> {
 		match self.inner.try_next()? {
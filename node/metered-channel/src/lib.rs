@@ -25,9 +25,10 @@ use derive_more::Display;
 
 mod bounded;
 pub mod oneshot;
+mod priority;
 mod unbounded;
 
-pub use self::{bounded::*, unbounded::*};
+pub use self::{bounded::*, priority::*, unbounded::*};
 
 pub use coarsetime::Duration as CoarseDuration;
 use coarsetime::Instant as CoarseInstant;
@@ -44,6 +45,8 @@ pub struct Meter {
 	received: Arc<AtomicUsize>,
 	// Atomic ringbuffer of the last 50 time of flight values
 	tof: Arc<crossbeam_queue::ArrayQueue<CoarseDuration>>,
+	// Atomic ringbuffer of the last time of flight values, labelled by message type
+	tof_by_message: Arc<crossbeam_queue::ArrayQueue<(&'static str, CoarseDuration)>>,
 }
 
 impl std::default::Default for Meter {
@@ -52,6 +55,7 @@ impl std::default::Default for Meter {
 			sent: Arc::new(AtomicUsize::new(0)),
 			received: Arc::new(AtomicUsize::new(0)),
 			tof: Arc::new(crossbeam_queue::ArrayQueue::new(100)),
+			tof_by_message: Arc::new(crossbeam_queue::ArrayQueue::new(100)),
 		}
 	}
 }
@@ -67,6 +71,8 @@ pub struct Readout {
 	pub received: usize,
 	/// Time of flight in micro seconds (us)
 	pub tof: Vec<CoarseDuration>,
+	/// Time of flight labelled by message type, if the receiver labels messages.
+	pub tof_by_message: Vec<(&'static str, CoarseDuration)>,
 }
 
 impl Meter {
@@ -84,6 +90,13 @@ impl Meter {
 				}
 				acc
 			},
+			tof_by_message: {
+				let mut acc = Vec::with_capacity(self.tof_by_message.len());
+				while let Some(value) = self.tof_by_message.pop() {
+					acc.push(value)
+				}
+				acc
+			},
 		}
 	}
 
@@ -102,6 +115,19 @@ impl Meter {
 	fn note_time_of_flight(&self, tof: CoarseDuration) {
 		let _ = self.tof.force_push(tof);
 	}
+
+	fn note_message_time_of_flight(&self, label: &'static str, tof: CoarseDuration) {
+		let _ = self.tof_by_message.force_push((label, tof));
+	}
+}
+
+/// Labels the messages of a receiver by their type.
+struct MessageLabel<T>(fn(&T) -> &'static str);
+
+impl<T> std::fmt::Debug for MessageLabel<T> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str("MessageLabel")
+	}
 }
 
 /// Determine if this instance shall be measured
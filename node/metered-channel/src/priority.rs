@@ -0,0 +1,185 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Metered variant of bounded mpsc channels with a separate lane per priority.
+//!
+//! Messages are received from the high priority lane as long as there are any, so a burst
+//! of normal messages does not delay the urgent ones. Each lane has its own capacity and meter.
+
+use futures::{
+	channel::mpsc,
+	stream::{FusedStream, Stream},
+	task::{Context, Poll},
+};
+
+use std::{pin::Pin, result};
+
+use super::{channel, MaybeTimeOfFlight, Meter, MeteredReceiver, MeteredSender};
+
+/// The lane a message is sent on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Priority {
+	/// The default lane.
+	Normal,
+	/// Received before any message on the normal lane.
+	High,
+}
+
+impl Default for Priority {
+	fn default() -> Self {
+		Self::Normal
+	}
+}
+
+/// Create a pair of `MeteredPrioritySender` and `MeteredPriorityReceiver`.
+///
+/// Each lane can hold `capacity` messages, `prioritize` selects the lane of every message sent.
+pub fn channel_with_priority<T>(
+	capacity: usize,
+	prioritize: fn(&T) -> Priority,
+) -> (MeteredPrioritySender<T>, MeteredPriorityReceiver<T>) {
+	let (high_tx, high_rx) = channel(capacity);
+	let (normal_tx, normal_rx) = channel(capacity);
+	let tx = MeteredPrioritySender { prioritize, high: high_tx, normal: normal_tx };
+	let rx = MeteredPriorityReceiver { high: high_rx, normal: normal_rx };
+	(tx, rx)
+}
+
+/// A receiver of a priority channel, tracking the messages consumed per lane.
+#[derive(Debug)]
+pub struct MeteredPriorityReceiver<T> {
+	high: MeteredReceiver<T>,
+	normal: MeteredReceiver<T>,
+}
+
+impl<T> MeteredPriorityReceiver<T> {
+	/// Get an updated accessor object for the metrics of the given lane.
+	pub fn meter(&self, priority: Priority) -> &Meter {
+		match priority {
+			Priority::High => self.high.meter(),
+			Priority::Normal => self.normal.meter(),
+		}
+	}
+
+	/// Additionally sample the time of flight per message type, as labelled by `label`.
+	pub fn with_message_label(self, label: fn(&T) -> &'static str) -> Self {
+		Self {
+			high: self.high.with_message_label(label),
+			normal: self.normal.with_message_label(label),
+		}
+	}
+
+	/// Attempt to receive the next item, preferring the high priority lane.
+	pub fn try_next(&mut self) -> Result<Option<T>, mpsc::TryRecvError> {
+		if let Ok(Some(value)) = self.high.try_next() {
+			return Ok(Some(value))
+		}
+		self.normal.try_next()
+	}
+}
+
+impl<T> Stream for MeteredPriorityReceiver<T> {
+	type Item = T;
+	fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+		// Terminated lanes are not polled again, as every poll is accounted for by the meter.
+		if !self.high.is_terminated() {
+			if let Poll::Ready(Some(value)) = Pin::new(&mut self.high).poll_next(cx) {
+				return Poll::Ready(Some(value))
+			}
+		}
+		if !self.normal.is_terminated() {
+			if let Poll::Ready(Some(value)) = Pin::new(&mut self.normal).poll_next(cx) {
+				return Poll::Ready(Some(value))
+			}
+		}
+
+		if self.is_terminated() {
+			Poll::Ready(None)
+		} else {
+			Poll::Pending
+		}
+	}
+
+	/// Don't rely on the unreliable size hint.
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		let (high_lower, high_upper) = self.high.size_hint();
+		let (normal_lower, normal_upper) = self.normal.size_hint();
+		let upper =
+			high_upper.zip(normal_upper).and_then(|(high, normal)| high.checked_add(normal));
+		(high_lower.saturating_add(normal_lower), upper)
+	}
+}
+
+impl<T> FusedStream for MeteredPriorityReceiver<T> {
+	fn is_terminated(&self) -> bool {
+		self.high.is_terminated() && self.normal.is_terminated()
+	}
+}
+
+/// The sender of a priority channel, tracking the number of items sent per lane.
+pub struct MeteredPrioritySender<T> {
+	prioritize: fn(&T) -> Priority,
+	high: MeteredSender<T>,
+	normal: MeteredSender<T>,
+}
+
+impl<T> Clone for MeteredPrioritySender<T> {
+	fn clone(&self) -> Self {
+		Self { prioritize: self.prioritize, high: self.high.clone(), normal: self.normal.clone() }
+	}
+}
+
+impl<T: std::fmt::Debug> std::fmt::Debug for MeteredPrioritySender<T> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("MeteredPrioritySender")
+			.field("high", &self.high)
+			.field("normal", &self.normal)
+			.finish()
+	}
+}
+
+impl<T> MeteredPrioritySender<T> {
+	fn lane(&mut self, msg: &T) -> &mut MeteredSender<T> {
+		match (self.prioritize)(msg) {
+			Priority::High => &mut self.high,
+			Priority::Normal => &mut self.normal,
+		}
+	}
+
+	/// Get an updated accessor object for the metrics of the given lane.
+	pub fn meter(&self, priority: Priority) -> &Meter {
+		match priority {
+			Priority::High => self.high.meter(),
+			Priority::Normal => self.normal.meter(),
+		}
+	}
+
+	/// Send message on its lane, wait until capacity is available.
+	pub async fn send(&mut self, msg: T) -> result::Result<(), mpsc::SendError>
+	where
+		Self: Unpin,
+	{
+		self.lane(&msg).send(msg).await
+	}
+
+	/// Attempt to send message on its lane or fail immediately.
+	pub fn try_send(
+		&mut self,
+		msg: T,
+	) -> result::Result<(), mpsc::TrySendError<MaybeTimeOfFlight<T>>> {
+		self.lane(&msg).try_send(msg)
+	}
+}
@@ -39,12 +39,12 @@ fn try_send_try_next() {
 		assert_matches!(rx.meter().read(), Readout { sent: 4, received: 1, .. });
 		rx.try_next().unwrap();
 		rx.try_next().unwrap();
-		assert_matches!(tx.meter().read(), Readout { sent: 4, received: 3, tof } => {
+		assert_matches!(tx.meter().read(), Readout { sent: 4, received: 3, tof, .. } => {
 			// every second in test, consumed before
 			assert_eq!(dbg!(tof).len(), 1);
 		});
 		rx.try_next().unwrap();
-		assert_matches!(rx.meter().read(), Readout { sent: 4, received: 4, tof } => {
+		assert_matches!(rx.meter().read(), Readout { sent: 4, received: 4, tof, .. } => {
 			// every second in test, consumed before
 			assert_eq!(dbg!(tof).len(), 0);
 		});
@@ -127,3 +127,63 @@ fn failed_send_does_not_inc_sent() {
 		assert_matches!(unbounded.meter().read(), Readout { sent: 0, received: 0, .. });
 	});
 }
+
+#[test]
+fn priority_lanes_are_received_first() {
+	use futures::stream::FusedStream;
+
+	let prioritize = |msg: &Msg| if msg.val >= 100 { Priority::High } else { Priority::Normal };
+	let (mut tx, mut rx) = channel_with_priority::<Msg>(5, prioritize);
+
+	block_on(async move {
+		tx.try_send(Msg { val: 1 }).unwrap();
+		tx.try_send(Msg { val: 2 }).unwrap();
+		tx.try_send(Msg { val: 100 }).unwrap();
+		tx.send(Msg { val: 101 }).await.unwrap();
+		tx.try_send(Msg { val: 3 }).unwrap();
+
+		assert_matches!(tx.meter(Priority::High).read(), Readout { sent: 2, received: 0, .. });
+		assert_matches!(tx.meter(Priority::Normal).read(), Readout { sent: 3, received: 0, .. });
+
+		let mut received = Vec::new();
+		received.push(rx.next().await.unwrap().val);
+		received.push(rx.try_next().unwrap().unwrap().val);
+		received.push(rx.next().await.unwrap().val);
+		assert_eq!(received, vec![100, 101, 1]);
+
+		assert_matches!(rx.meter(Priority::High).read(), Readout { sent: 2, received: 2, .. });
+		assert_matches!(rx.meter(Priority::Normal).read(), Readout { sent: 3, received: 1, .. });
+
+		drop(tx);
+		let rest = rx.by_ref().map(|msg| msg.val).collect::<Vec<_>>().await;
+		assert_eq!(rest, vec![2, 3]);
+		assert!(rx.is_terminated());
+	});
+}
+
+#[test]
+fn tof_is_sampled_per_message_type() {
+	let label = |msg: &Msg| if msg.val % 2 == 0 { "even" } else { "odd" };
+	let (mut tx, rx) = channel::<Msg>(5);
+	let mut rx = rx.with_message_label(label);
+	let (unbounded_tx, unbounded_rx) = unbounded::<Msg>();
+	let mut unbounded_rx = unbounded_rx.with_message_label(label);
+
+	block_on(async move {
+		for val in 0..4 {
+			tx.try_send(Msg { val }).unwrap();
+			unbounded_tx.unbounded_send(Msg { val }).unwrap();
+		}
+		for _ in 0..4 {
+			rx.try_next().unwrap();
+			unbounded_rx.try_next().unwrap();
+		}
+
+		// every second in test, starting with the first
+		for readout in [rx.meter().read(), unbounded_rx.meter().read()] {
+			assert_eq!(readout.tof.len(), 2);
+			let labels = readout.tof_by_message.iter().map(|(label, _)| *label).collect::<Vec<_>>();
+			assert_eq!(labels, vec!["even", "even"]);
+		}
+	});
+}
@@ -24,14 +24,14 @@ use futures::{
 
 use std::{pin::Pin, result};
 
-use super::{measure_tof_check, CoarseInstant, MaybeTimeOfFlight, Meter};
+use super::{measure_tof_check, CoarseInstant, MaybeTimeOfFlight, MessageLabel, Meter};
 
 /// Create a wrapped `mpsc::channel` pair of `MeteredSender` and `MeteredReceiver`.
 pub fn unbounded<T>() -> (UnboundedMeteredSender<T>, UnboundedMeteredReceiver<T>) {
 	let (tx, rx) = mpsc::unbounded::<MaybeTimeOfFlight<T>>();
 	let shared_meter = Meter::default();
 	let tx = UnboundedMeteredSender { meter: shared_meter.clone(), inner: tx };
-	let rx = UnboundedMeteredReceiver { meter: shared_meter, inner: rx };
+	let rx = UnboundedMeteredReceiver { meter: shared_meter, inner: rx, label: None };
 	(tx, rx)
 }
 
@@ -41,6 +41,8 @@ pub struct UnboundedMeteredReceiver<T> {
 	// count currently contained messages
 	meter: Meter,
 	inner: mpsc::UnboundedReceiver<MaybeTimeOfFlight<T>>,
+	// labels time of flight samples by message type
+	label: Option<MessageLabel<T>>,
 }
 
 impl<T> std::ops::Deref for UnboundedMeteredReceiver<T> {
@@ -81,6 +83,9 @@ impl<T> UnboundedMeteredReceiver<T> {
 					// `coarsetime` does a saturating substractio for all `CoarseInstant`s
 					let duration = tof_start.elapsed();
 					self.meter.note_time_of_flight(duration);
+					if let Some(MessageLabel(label)) = self.label {
+						self.meter.note_message_time_of_flight(label(&value), duration);
+					}
 					value
 				},
 				MaybeTimeOfFlight::<T>::Bare(value) => value,
@@ -94,6 +99,12 @@ impl<T> UnboundedMeteredReceiver<T> {
 		&self.meter
 	}
 
+	/// Additionally sample the time of flight per message type, as labelled by `label`.
+	pub fn with_message_label(mut self, label: fn(&T) -> &'static str) -> Self {
+		self.label = Some(MessageLabel(label));
+		self
+	}
+
 	/// Attempt to receive the next item.
 	pub fn try_next(&mut self) -> Result<Option<T>, mpsc::TryRecvError> {
 		match self.inner.try_next()? {
@@ -79,6 +79,34 @@ pub mod metrics {
 			Ok(())
 		}
 	}
+
+	/// Time of flight of channel messages, labelled by the receiving subsystem and message type.
+	///
+	/// Only channels whose receiver labels messages, see `with_message_label`, are sampled.
+	#[derive(Clone)]
+	pub struct MessageTofHistogram(prometheus::HistogramVec);
+
+	impl MessageTofHistogram {
+		/// Register the histogram with the given name and help text.
+		pub fn try_register(
+			name: &str,
+			help: &str,
+			registry: &prometheus::Registry,
+		) -> Result<Self, prometheus::PrometheusError> {
+			let histogram = prometheus::HistogramVec::new(
+				prometheus::HistogramOpts::new(name, help),
+				&["subsystem_name", "message_type"],
+			)?;
+			Ok(Self(prometheus::register(histogram, registry)?))
+		}
+
+		/// Observe the time of flight samples of a channel readout.
+		pub fn observe(&self, subsystem_name: &str, readout: &crate::metered::Readout) {
+			for (message_type, tof) in &readout.tof_by_message {
+				self.0.with_label_values(&[subsystem_name, message_type]).observe(tof.as_f64());
+			}
+		}
+	}
 }
 
 #[cfg(all(feature = "runtime-metrics", not(feature = "runtime-benchmarks"), test))]
@@ -22,10 +22,12 @@
 
 use futures::{channel::oneshot, FutureExt as _};
 use polkadot_node_network_protocol::{
-	v1 as protocol_v1, PeerId, UnifiedReputationChange as Rep, View,
+	peer_set::ValidationVersion, v1 as protocol_v1, v2 as protocol_v2, PeerId, ProtocolVersion,
+	UnifiedReputationChange as Rep, ValidationProtocols, View,
 };
 use polkadot_node_primitives::approval::{
-	AssignmentCert, BlockApprovalMeta, IndirectAssignmentCert, IndirectSignedApprovalVote,
+	AssignmentCert, AssignmentCertV2, BlockApprovalMeta, CandidateBitfield,
+	IndirectAssignmentCertV2, IndirectSignedApprovalVote, IndirectSignedApprovalVoteV2,
 };
 use polkadot_node_subsystem::{
 	messages::{
@@ -233,6 +235,13 @@ enum Resend {
 ///
 /// It tracks metadata about our view of the unfinalized chain,
 /// which assignments and approvals we have seen, and our peers' views.
+/// The view of a connected peer and the version of the protocol it speaks.
+#[derive(Debug, Clone, PartialEq)]
+struct PeerEntry {
+	view: View,
+	version: ProtocolVersion,
+}
+
 #[derive(Default)]
 struct State {
 	/// These two fields are used in conjunction to construct a view over the unfinalized chain.
@@ -248,7 +257,7 @@ struct State {
 	pending_known: HashMap<Hash, Vec<(PeerId, PendingMessage)>>,
 
 	/// Peer data is partially stored here, and partially inline within the [`BlockEntry`]s
-	peer_views: HashMap<PeerId, View>,
+	peer_views: HashMap<PeerId, PeerEntry>,
 
 	/// Keeps a topology for various different sessions.
 	topologies: SessionTopologies,
@@ -267,7 +276,16 @@ enum MessageKind {
 }
 
 #[derive(Debug, Clone, Hash, PartialEq, Eq)]
-struct MessageSubject(Hash, CandidateIndex, ValidatorIndex);
+struct MessageSubject(Hash, CandidateBitfield, ValidatorIndex);
+
+impl MessageSubject {
+	// The subjects for each of the candidates covered by this subject individually.
+	fn per_candidate(&self) -> impl Iterator<Item = MessageSubject> + '_ {
+		self.1
+			.iter_ones()
+			.map(move |i| MessageSubject(self.0, (i as CandidateIndex).into(), self.2))
+	}
+}
 
 #[derive(Debug, Clone, Default)]
 struct Knowledge {
@@ -288,7 +306,7 @@ impl Knowledge {
 	}
 
 	fn insert(&mut self, message: MessageSubject, kind: MessageKind) -> bool {
-		match self.known_messages.entry(message) {
+		let mut success = match self.known_messages.entry(message.clone()) {
 			hash_map::Entry::Vacant(vacant) => {
 				vacant.insert(kind);
 				true
@@ -302,7 +320,17 @@ impl Knowledge {
 					true
 				},
 			},
+		};
+
+		// A message covering multiple candidates is also known for each of the candidates
+		// individually, as assignments and approvals don't necessarily cover the same candidates.
+		if success && message.1.count_ones() > 1 {
+			for single in message.per_candidate() {
+				success = self.insert(single, kind) && success;
+			}
 		}
+
+		success
 	}
 }
 
@@ -340,22 +368,50 @@ struct BlockEntry {
 
 #[derive(Debug)]
 enum ApprovalState {
-	Assigned(AssignmentCert),
-	Approved(AssignmentCert, ValidatorSignature),
+	Assigned(AssignmentCertV2, CandidateBitfield),
+	/// The assignment and claimed candidates, along with the approval signature and the
+	/// candidates it covers.
+	Approved(AssignmentCertV2, CandidateBitfield, ValidatorSignature, CandidateBitfield),
 }
 
 impl ApprovalState {
-	fn assignment_cert(&self) -> &AssignmentCert {
+	fn assignment_cert(&self) -> &AssignmentCertV2 {
 		match *self {
-			ApprovalState::Assigned(ref cert) => cert,
-			ApprovalState::Approved(ref cert, _) => cert,
+			ApprovalState::Assigned(ref cert, _) => cert,
+			ApprovalState::Approved(ref cert, _, _, _) => cert,
 		}
 	}
 
-	fn approval_signature(&self) -> Option<ValidatorSignature> {
+	// The candidates claimed by the assignment, which may be more than the candidate
+	// this state is stored for.
+	fn claimed_candidates(&self) -> &CandidateBitfield {
 		match *self {
-			ApprovalState::Assigned(_) => None,
-			ApprovalState::Approved(_, ref sig) => Some(sig.clone()),
+			ApprovalState::Assigned(_, ref claimed) => claimed,
+			ApprovalState::Approved(_, ref claimed, _, _) => claimed,
+		}
+	}
+
+	// The approval signature along with the candidates it approves, which may be more than
+	// the candidate this state is stored for.
+	fn approval(&self) -> Option<(ValidatorSignature, CandidateBitfield)> {
+		match *self {
+			ApprovalState::Assigned(_, _) => None,
+			ApprovalState::Approved(_, _, ref sig, ref approved) =>
+				Some((sig.clone(), approved.clone())),
+		}
+	}
+
+	// Record an approval vote, unless one is already known. Only one approval vote is
+	// circulated per assignment.
+	fn approve(&mut self, signature: ValidatorSignature, approved_candidates: CandidateBitfield) {
+		if let ApprovalState::Assigned(cert, claimed) = self {
+			let approved = ApprovalState::Approved(
+				cert.clone(),
+				claimed.clone(),
+				signature,
+				approved_candidates,
+			);
+			*self = approved;
 		}
 	}
 }
@@ -447,8 +503,8 @@ impl MessageSource {
 }
 
 enum PendingMessage {
-	Assignment(IndirectAssignmentCert, CandidateIndex),
-	Approval(IndirectSignedApprovalVote),
+	Assignment(IndirectAssignmentCertV2, CandidateBitfield),
+	Approval(IndirectSignedApprovalVoteV2),
 }
 
 impl State {
@@ -457,14 +513,16 @@ impl State {
 		ctx: &mut (impl SubsystemContext<Message = ApprovalDistributionMessage>
 		          + overseer::SubsystemContext<Message = ApprovalDistributionMessage>),
 		metrics: &Metrics,
-		event: NetworkBridgeEvent<protocol_v1::ApprovalDistributionMessage>,
+		event: NetworkBridgeEvent<protocol_v2::ApprovalDistributionMessage>,
 		rng: &mut (impl CryptoRng + Rng),
 	) {
 		match event {
-			NetworkBridgeEvent::PeerConnected(peer_id, role, _) => {
+			NetworkBridgeEvent::PeerConnected(peer_id, role, version, _) => {
 				// insert a blank view if none already present
-				gum::trace!(target: LOG_TARGET, ?peer_id, ?role, "Peer connected");
-				self.peer_views.entry(peer_id).or_default();
+				gum::trace!(target: LOG_TARGET, ?peer_id, ?role, version, "Peer connected");
+				self.peer_views
+					.entry(peer_id)
+					.or_insert(PeerEntry { view: Default::default(), version });
 			},
 			NetworkBridgeEvent::PeerDisconnected(peer_id) => {
 				gum::trace!(target: LOG_TARGET, ?peer_id, "Peer disconnected");
@@ -551,7 +609,7 @@ impl State {
 		);
 
 		{
-			for (peer_id, view) in self.peer_views.iter() {
+			for (peer_id, PeerEntry { view, version }) in self.peer_views.iter() {
 				let intersection = view.iter().filter(|h| new_hashes.contains(h));
 				let view_intersection = View::new(intersection.cloned(), view.finalized_number);
 				Self::unify_with_peer(
@@ -561,6 +619,7 @@ impl State {
 					&self.topologies,
 					self.peer_views.len(),
 					peer_id.clone(),
+					*version,
 					view_intersection,
 					rng,
 				)
@@ -598,13 +657,13 @@ impl State {
 
 				for (peer_id, message) in to_import {
 					match message {
-						PendingMessage::Assignment(assignment, claimed_index) => {
+						PendingMessage::Assignment(assignment, claimed_indices) => {
 							self.import_and_circulate_assignment(
 								ctx,
 								metrics,
 								MessageSource::Peer(peer_id),
 								assignment,
-								claimed_index,
+								claimed_indices,
 								rng,
 							)
 							.await;
@@ -640,6 +699,7 @@ impl State {
 			ctx,
 			&mut self.blocks,
 			&self.topologies,
+			&self.peer_views,
 			|block_entry| block_entry.session == session,
 			|required_routing, local, validator_index| {
 				if *required_routing == RequiredRouting::PendingTopology {
@@ -656,87 +716,100 @@ impl State {
 		          + overseer::SubsystemContext<Message = ApprovalDistributionMessage>),
 		metrics: &Metrics,
 		peer_id: PeerId,
-		msg: protocol_v1::ApprovalDistributionMessage,
+		msg: protocol_v2::ApprovalDistributionMessage,
 		rng: &mut (impl CryptoRng + Rng),
 	) {
 		match msg {
-			protocol_v1::ApprovalDistributionMessage::Assignments(assignments) => {
-				gum::trace!(
-					target: LOG_TARGET,
-					peer_id = %peer_id,
-					num = assignments.len(),
-					"Processing assignments from a peer",
+			protocol_v2::ApprovalDistributionMessage::Assignments(assignments) => {
+				self.process_incoming_assignments(ctx, metrics, peer_id, assignments, rng).await;
+			},
+			protocol_v2::ApprovalDistributionMessage::Approvals(approvals) => {
+				self.process_incoming_approvals(ctx, metrics, peer_id, approvals).await;
+			},
+		}
+	}
+
+	async fn process_incoming_approvals(
+		&mut self,
+		ctx: &mut (impl SubsystemContext<Message = ApprovalDistributionMessage>
+		          + overseer::SubsystemContext<Message = ApprovalDistributionMessage>),
+		metrics: &Metrics,
+		peer_id: PeerId,
+		approvals: Vec<IndirectSignedApprovalVoteV2>,
+	) {
+		gum::trace!(
+			target: LOG_TARGET,
+			peer_id = %peer_id,
+			num = approvals.len(),
+			"Processing approvals from a peer",
+		);
+		for approval_vote in approvals.into_iter() {
+			if let Some(pending) = self.pending_known.get_mut(&approval_vote.block_hash) {
+				let message_subject = MessageSubject(
+					approval_vote.block_hash,
+					approval_vote.candidate_indices.clone(),
+					approval_vote.validator,
 				);
-				for (assignment, claimed_index) in assignments.into_iter() {
-					if let Some(pending) = self.pending_known.get_mut(&assignment.block_hash) {
-						let message_subject = MessageSubject(
-							assignment.block_hash,
-							claimed_index,
-							assignment.validator,
-						);
 
-						gum::trace!(
-							target: LOG_TARGET,
-							%peer_id,
-							?message_subject,
-							"Pending assignment",
-						);
+				gum::trace!(target: LOG_TARGET, %peer_id, ?message_subject, "Pending approval",);
 
-						pending.push((
-							peer_id.clone(),
-							PendingMessage::Assignment(assignment, claimed_index),
-						));
+				pending.push((peer_id.clone(), PendingMessage::Approval(approval_vote)));
 
-						continue
-					}
+				continue
+			}
 
-					self.import_and_circulate_assignment(
-						ctx,
-						metrics,
-						MessageSource::Peer(peer_id.clone()),
-						assignment,
-						claimed_index,
-						rng,
-					)
-					.await;
-				}
-			},
-			protocol_v1::ApprovalDistributionMessage::Approvals(approvals) => {
-				gum::trace!(
-					target: LOG_TARGET,
-					peer_id = %peer_id,
-					num = approvals.len(),
-					"Processing approvals from a peer",
+			self.import_and_circulate_approval(
+				ctx,
+				metrics,
+				MessageSource::Peer(peer_id.clone()),
+				approval_vote,
+			)
+			.await;
+		}
+	}
+
+	async fn process_incoming_assignments(
+		&mut self,
+		ctx: &mut (impl SubsystemContext<Message = ApprovalDistributionMessage>
+		          + overseer::SubsystemContext<Message = ApprovalDistributionMessage>),
+		metrics: &Metrics,
+		peer_id: PeerId,
+		assignments: Vec<(IndirectAssignmentCertV2, CandidateBitfield)>,
+		rng: &mut (impl CryptoRng + Rng),
+	) {
+		gum::trace!(
+			target: LOG_TARGET,
+			peer_id = %peer_id,
+			num = assignments.len(),
+			"Processing assignments from a peer",
+		);
+		for (assignment, claimed_indices) in assignments.into_iter() {
+			if let Some(pending) = self.pending_known.get_mut(&assignment.block_hash) {
+				let message_subject = MessageSubject(
+					assignment.block_hash,
+					claimed_indices.clone(),
+					assignment.validator,
 				);
-				for approval_vote in approvals.into_iter() {
-					if let Some(pending) = self.pending_known.get_mut(&approval_vote.block_hash) {
-						let message_subject = MessageSubject(
-							approval_vote.block_hash,
-							approval_vote.candidate_index,
-							approval_vote.validator,
-						);
 
-						gum::trace!(
-							target: LOG_TARGET,
-							%peer_id,
-							?message_subject,
-							"Pending approval",
-						);
+				gum::trace!(target: LOG_TARGET, %peer_id, ?message_subject, "Pending assignment",);
 
-						pending.push((peer_id.clone(), PendingMessage::Approval(approval_vote)));
+				pending.push((
+					peer_id.clone(),
+					PendingMessage::Assignment(assignment, claimed_indices),
+				));
 
-						continue
-					}
+				continue
+			}
 
-					self.import_and_circulate_approval(
-						ctx,
-						metrics,
-						MessageSource::Peer(peer_id.clone()),
-						approval_vote,
-					)
-					.await;
-				}
-			},
+			self.import_and_circulate_assignment(
+				ctx,
+				metrics,
+				MessageSource::Peer(peer_id.clone()),
+				assignment,
+				claimed_indices,
+				rng,
+			)
+			.await;
 		}
 	}
 
@@ -753,8 +826,10 @@ impl State {
 	) {
 		gum::trace!(target: LOG_TARGET, ?view, "Peer view change");
 		let finalized_number = view.finalized_number;
-		let old_view =
-			self.peer_views.get_mut(&peer_id).map(|d| std::mem::replace(d, view.clone()));
+		let (old_view, version) = match self.peer_views.get_mut(&peer_id) {
+			Some(entry) => (Some(std::mem::replace(&mut entry.view, view.clone())), entry.version),
+			None => (None, ValidationVersion::V1.into()),
+		};
 		let old_finalized_number = old_view.map(|v| v.finalized_number).unwrap_or(0);
 
 		// we want to prune every block known_by peer up to (including) view.finalized_number
@@ -782,6 +857,7 @@ impl State {
 			&self.topologies,
 			self.peer_views.len(),
 			peer_id.clone(),
+			version,
 			view,
 			rng,
 		)
@@ -823,8 +899,8 @@ impl State {
 		          + overseer::SubsystemContext<Message = ApprovalDistributionMessage>),
 		metrics: &Metrics,
 		source: MessageSource,
-		assignment: IndirectAssignmentCert,
-		claimed_candidate_index: CandidateIndex,
+		assignment: IndirectAssignmentCertV2,
+		claimed_candidate_indices: CandidateBitfield,
 		rng: &mut (impl CryptoRng + Rng),
 	) {
 		let block_hash = assignment.block_hash.clone();
@@ -850,9 +926,32 @@ impl State {
 		};
 
 		// compute metadata on the assignment.
-		let message_subject = MessageSubject(block_hash, claimed_candidate_index, validator_index);
+		let message_subject =
+			MessageSubject(block_hash, claimed_candidate_indices.clone(), validator_index);
 		let message_kind = MessageKind::Assignment;
 
+		// All claimed candidates must be part of the block.
+		if claimed_candidate_indices.count_ones() == 0 ||
+			claimed_candidate_indices.iter_ones().any(|i| i >= entry.candidates.len())
+		{
+			if let Some(peer_id) = source.peer_id() {
+				gum::debug!(
+					target: LOG_TARGET,
+					?peer_id,
+					?message_subject,
+					"Assignment claims unknown candidates",
+				);
+				modify_reputation(ctx, peer_id, COST_INVALID_MESSAGE).await;
+			} else {
+				gum::warn!(
+					target: LOG_TARGET,
+					?message_subject,
+					"Local assignment claims unknown candidates",
+				);
+			}
+			return
+		}
+
 		if let Some(peer_id) = source.peer_id() {
 			// check if our knowledge of the peer already contains this assignment
 			match entry.known_by.entry(peer_id.clone()) {
@@ -897,7 +996,7 @@ impl State {
 
 			ctx.send_message(ApprovalVotingMessage::CheckAndImportAssignment(
 				assignment.clone(),
-				claimed_candidate_index,
+				claimed_candidate_indices.clone(),
 				tx,
 			))
 			.await;
@@ -922,7 +1021,7 @@ impl State {
 			match result {
 				AssignmentCheckResult::Accepted => {
 					modify_reputation(ctx, peer_id.clone(), BENEFIT_VALID_MESSAGE_FIRST).await;
-					entry.knowledge.known_messages.insert(message_subject.clone(), message_kind);
+					entry.knowledge.insert(message_subject.clone(), message_kind);
 					if let Some(peer_knowledge) = entry.known_by.get_mut(&peer_id) {
 						peer_knowledge.received.insert(message_subject.clone(), message_kind);
 					}
@@ -992,28 +1091,32 @@ impl State {
 			t.required_routing_for(validator_index, local)
 		});
 
-		let message_state = match entry.candidates.get_mut(claimed_candidate_index as usize) {
-			Some(candidate_entry) => {
-				// set the approval state for validator_index to Assigned
-				// unless the approval state is set already
-				candidate_entry.messages.entry(validator_index).or_insert_with(|| MessageState {
+		// Store the assignment for every claimed candidate, so that follow-up approvals for
+		// any of them can be routed the same way.
+		for candidate_index in claimed_candidate_indices.iter_ones() {
+			// set the approval state for validator_index to Assigned
+			// unless the approval state is set already
+			entry.candidates[candidate_index]
+				.messages
+				.entry(validator_index)
+				.or_insert_with(|| MessageState {
 					required_routing,
 					local,
 					random_routing: RandomRouting { target: RANDOM_CIRCULATION, sent: 0 },
-					approval_state: ApprovalState::Assigned(assignment.cert.clone()),
-				})
-			},
-			None => {
-				gum::warn!(
-					target: LOG_TARGET,
-					hash = ?block_hash,
-					?claimed_candidate_index,
-					"Expected a candidate entry on import_and_circulate_assignment",
-				);
+					approval_state: ApprovalState::Assigned(
+						assignment.cert.clone(),
+						claimed_candidate_indices.clone(),
+					),
+				});
+		}
 
-				return
-			},
-		};
+		let first_candidate_index = claimed_candidate_indices
+			.first_one()
+			.expect("claimed candidates were checked to be non-empty above; qed");
+		let message_state = entry.candidates[first_candidate_index]
+			.messages
+			.get_mut(&validator_index)
+			.expect("inserted above if not present; qed");
 
 		// Dispatch the message to all peers in the routing set which
 		// know the block.
@@ -1021,15 +1124,23 @@ impl State {
 		// If the topology isn't known yet (race with networking subsystems)
 		// then messages will be sent when we get it.
 
-		let assignments = vec![(assignment, claimed_candidate_index)];
 		let n_peers_total = self.peer_views.len();
 		let source_peer = source.peer_id();
+		let peer_views = &self.peer_views;
+		let cert = &assignment.cert;
+		let claimed_candidates = &claimed_candidate_indices;
 
 		let mut peer_filter = move |peer| {
 			if Some(peer) == source_peer.as_ref() {
 				return false
 			}
 
+			if !peer_views.get(peer).map_or(false, |entry| {
+				assignment_supported_by(entry.version, cert, claimed_candidates)
+			}) {
+				return false
+			}
+
 			if let Some(true) = topology.as_ref().map(|t| t.route_to_peer(required_routing, peer)) {
 				return true
 			}
@@ -1060,19 +1171,15 @@ impl State {
 			gum::trace!(
 				target: LOG_TARGET,
 				?block_hash,
-				?claimed_candidate_index,
+				?claimed_candidate_indices,
 				local = source.peer_id().is_none(),
 				num_peers = peers.len(),
 				"Sending an assignment to peers",
 			);
 
-			ctx.send_message(NetworkBridgeMessage::SendValidationMessage(
-				peers,
-				protocol_v1::ValidationProtocol::ApprovalDistribution(
-					protocol_v1::ApprovalDistributionMessage::Assignments(assignments),
-				),
-			))
-			.await;
+			let peers = with_versions(&self.peer_views, peers);
+			let assignments = vec![(assignment, claimed_candidate_indices)];
+			send_assignments_batched(ctx, peers, assignments).await;
 		}
 	}
 
@@ -1082,14 +1189,17 @@ impl State {
 		          + overseer::SubsystemContext<Message = ApprovalDistributionMessage>),
 		metrics: &Metrics,
 		source: MessageSource,
-		vote: IndirectSignedApprovalVote,
+		vote: IndirectSignedApprovalVoteV2,
 	) {
 		let block_hash = vote.block_hash.clone();
 		let validator_index = vote.validator;
-		let candidate_index = vote.candidate_index;
+		let candidate_indices = vote.candidate_indices.clone();
 
 		let entry = match self.blocks.get_mut(&block_hash) {
-			Some(entry) if entry.candidates.get(candidate_index as usize).is_some() => entry,
+			Some(entry)
+				if candidate_indices.count_ones() > 0 &&
+					candidate_indices.iter_ones().all(|i| entry.candidates.get(i).is_some()) =>
+				entry,
 			_ => {
 				if let Some(peer_id) = source.peer_id() {
 					if !self.recent_outdated_blocks.is_recent_outdated(&block_hash) {
@@ -1101,11 +1211,16 @@ impl State {
 		};
 
 		// compute metadata on the assignment.
-		let message_subject = MessageSubject(block_hash, candidate_index, validator_index);
+		let message_subject =
+			MessageSubject(block_hash, candidate_indices.clone(), validator_index);
 		let message_kind = MessageKind::Approval;
 
 		if let Some(peer_id) = source.peer_id() {
-			if !entry.knowledge.contains(&message_subject, MessageKind::Assignment) {
+			// The assignments for all approved candidates must be known.
+			if !message_subject
+				.per_candidate()
+				.all(|subject| entry.knowledge.contains(&subject, MessageKind::Assignment))
+			{
 				gum::debug!(
 					target: LOG_TARGET,
 					?peer_id,
@@ -1218,62 +1333,51 @@ impl State {
 		// Invariant: to our knowledge, none of the peers except for the `source` know about the approval.
 		metrics.on_approval_imported();
 
-		let required_routing = match entry.candidates.get_mut(candidate_index as usize) {
-			Some(candidate_entry) => {
-				// set the approval state for validator_index to Approved
-				// it should be in assigned state already
-				match candidate_entry.messages.remove(&validator_index) {
-					Some(MessageState {
-						approval_state: ApprovalState::Assigned(cert),
-						required_routing,
-						local,
-						random_routing,
-					}) => {
-						candidate_entry.messages.insert(
-							validator_index,
-							MessageState {
-								approval_state: ApprovalState::Approved(
-									cert,
-									vote.signature.clone(),
-								),
-								required_routing,
-								local,
-								random_routing,
-							},
-						);
+		let mut required_routing = None;
+		for candidate_index in candidate_indices.iter_ones() {
+			let candidate_entry = match entry.candidates.get_mut(candidate_index) {
+				Some(candidate_entry) => candidate_entry,
+				None => {
+					gum::warn!(
+						target: LOG_TARGET,
+						hash = ?block_hash,
+						?candidate_index,
+						?validator_index,
+						"Expected a candidate entry on import_and_circulate_approval",
+					);
 
-						required_routing
-					},
-					Some(_) => {
-						unreachable!(
-							"we only insert it after the metadata, checked the metadata above; qed"
-						);
-					},
-					None => {
-						// this would indicate a bug in approval-voting
-						gum::warn!(
-							target: LOG_TARGET,
-							hash = ?block_hash,
-							?candidate_index,
-							?validator_index,
-							"Importing an approval we don't have an assignment for",
-						);
+					return
+				},
+			};
 
-						return
-					},
-				}
-			},
-			None => {
-				gum::warn!(
-					target: LOG_TARGET,
-					hash = ?block_hash,
-					?candidate_index,
-					?validator_index,
-					"Expected a candidate entry on import_and_circulate_approval",
-				);
+			// set the approval state for validator_index to Approved
+			// it should be in assigned state already
+			match candidate_entry.messages.get_mut(&validator_index) {
+				Some(message_state) => {
+					message_state
+						.approval_state
+						.approve(vote.signature.clone(), candidate_indices.clone());
 
-				return
-			},
+					required_routing.get_or_insert(message_state.required_routing);
+				},
+				None => {
+					// this would indicate a bug in approval-voting
+					gum::warn!(
+						target: LOG_TARGET,
+						hash = ?block_hash,
+						?candidate_index,
+						?validator_index,
+						"Importing an approval we don't have an assignment for",
+					);
+
+					return
+				},
+			}
+		}
+
+		let required_routing = match required_routing {
+			Some(required_routing) => required_routing,
+			None => return,
 		};
 
 		// Dispatch a ApprovalDistributionV1Message::Approval(vote)
@@ -1283,11 +1387,28 @@ impl State {
 		let source_peer = source.peer_id();
 
 		let message_subject = &message_subject;
+		let peer_views = &self.peer_views;
 		let peer_filter = move |peer, knowledge: &PeerKnowledge| {
 			if Some(peer) == source_peer.as_ref() {
 				return false
 			}
 
+			let version = match peer_views.get(peer) {
+				Some(entry) => entry.version,
+				None => return false,
+			};
+
+			// Peers speaking the first version of the protocol aren't sent multi-candidate
+			// assignments, so the topology doesn't guarantee that they know the assignment.
+			if !approval_supported_by(version, &message_subject.1) ||
+				(version == ProtocolVersion::from(ValidationVersion::V1) &&
+					!message_subject
+						.per_candidate()
+						.all(|subject| knowledge.contains(&subject, MessageKind::Assignment)))
+			{
+				return false
+			}
+
 			// Here we're leaning on a few behaviors of assignment propagation:
 			//   1. At this point, the only peer we're aware of which has the approval
 			//      message is the source peer.
@@ -1298,7 +1419,10 @@ impl State {
 			//      source of the assignment. Hence the `in_topology_check`.
 			//   3. Any randomly selected peers have been sent the assignment already.
 			let in_topology = topology.map_or(false, |t| t.route_to_peer(required_routing, peer));
-			in_topology || knowledge.sent.contains(message_subject, MessageKind::Assignment)
+			in_topology ||
+				message_subject
+					.per_candidate()
+					.all(|subject| knowledge.sent.contains(&subject, MessageKind::Assignment))
 		};
 
 		let peers = entry
@@ -1318,23 +1442,17 @@ impl State {
 		}
 
 		if !peers.is_empty() {
-			let approvals = vec![vote];
 			gum::trace!(
 				target: LOG_TARGET,
 				?block_hash,
-				?candidate_index,
+				?candidate_indices,
 				local = source.peer_id().is_none(),
 				num_peers = peers.len(),
 				"Sending an approval to peers",
 			);
 
-			ctx.send_message(NetworkBridgeMessage::SendValidationMessage(
-				peers,
-				protocol_v1::ValidationProtocol::ApprovalDistribution(
-					protocol_v1::ApprovalDistributionMessage::Approvals(approvals),
-				),
-			))
-			.await;
+			let peers = with_versions(&self.peer_views, peers);
+			send_approvals_batched(ctx, peers, vec![vote]).await;
 		}
 	}
 
@@ -1346,6 +1464,7 @@ impl State {
 		topologies: &SessionTopologies,
 		total_peers: usize,
 		peer_id: PeerId,
+		peer_version: ProtocolVersion,
 		view: View,
 		rng: &mut (impl CryptoRng + Rng),
 	) {
@@ -1376,10 +1495,17 @@ impl State {
 				let topology = topologies.get_topology(entry.session);
 
 				// Iterate all messages in all candidates.
-				for (candidate_index, validator, message_state) in
-					entry.candidates.iter_mut().enumerate().flat_map(|(c_i, c)| {
-						c.messages.iter_mut().map(move |(k, v)| (c_i as _, k, v))
-					}) {
+				for (validator, message_state) in
+					entry.candidates.iter_mut().flat_map(|c| c.messages.iter_mut())
+				{
+					if !assignment_supported_by(
+						peer_version,
+						message_state.approval_state.assignment_cert(),
+						message_state.approval_state.claimed_candidates(),
+					) {
+						continue
+					}
+
 					// Propagate the message to all peers in the required routing set OR
 					// randomly sample peers.
 					{
@@ -1405,41 +1531,47 @@ impl State {
 						}
 					}
 
-					let message_subject =
-						MessageSubject(block.clone(), candidate_index, validator.clone());
+					let claimed_candidates = message_state.approval_state.claimed_candidates();
+					let assignment_subject = MessageSubject(
+						block.clone(),
+						claimed_candidates.clone(),
+						validator.clone(),
+					);
 
 					let assignment_message = (
-						IndirectAssignmentCert {
+						IndirectAssignmentCertV2 {
 							block_hash: block.clone(),
 							validator: validator.clone(),
 							cert: message_state.approval_state.assignment_cert().clone(),
 						},
-						candidate_index,
+						claimed_candidates.clone(),
 					);
 
-					let approval_message =
-						message_state.approval_state.approval_signature().map(|signature| {
-							IndirectSignedApprovalVote {
-								block_hash: block.clone(),
-								validator: validator.clone(),
-								candidate_index,
-								signature,
-							}
-						});
-
-					if !peer_knowledge.contains(&message_subject, MessageKind::Assignment) {
-						peer_knowledge
-							.sent
-							.insert(message_subject.clone(), MessageKind::Assignment);
+					if !peer_knowledge.contains(&assignment_subject, MessageKind::Assignment) {
+						peer_knowledge.sent.insert(assignment_subject, MessageKind::Assignment);
 						assignments_to_send.push(assignment_message);
 					}
 
-					if let Some(approval_message) = approval_message {
-						if !peer_knowledge.contains(&message_subject, MessageKind::Approval) {
-							peer_knowledge
-								.sent
-								.insert(message_subject.clone(), MessageKind::Approval);
-							approvals_to_send.push(approval_message);
+					if let Some((signature, approved_candidates)) =
+						message_state.approval_state.approval()
+					{
+						let approval_subject =
+							MessageSubject(block.clone(), approved_candidates, validator.clone());
+
+						// An approval covering multiple candidates is sent along with the
+						// assignment of the last of them.
+						if approval_supported_by(peer_version, &approval_subject.1) &&
+							!peer_knowledge.contains(&approval_subject, MessageKind::Approval) &&
+							approval_subject.per_candidate().all(|subject| {
+								peer_knowledge.contains(&subject, MessageKind::Assignment)
+							}) {
+							approvals_to_send.push(IndirectSignedApprovalVoteV2 {
+								block_hash: block.clone(),
+								candidate_indices: approval_subject.1.clone(),
+								validator: validator.clone(),
+								signature,
+							});
+							peer_knowledge.sent.insert(approval_subject, MessageKind::Approval);
 						}
 					}
 				}
@@ -1456,12 +1588,11 @@ impl State {
 				"Sending assignments to unified peer",
 			);
 
-			ctx.send_message(NetworkBridgeMessage::SendValidationMessage(
-				vec![peer_id.clone()],
-				protocol_v1::ValidationProtocol::ApprovalDistribution(
-					protocol_v1::ApprovalDistributionMessage::Assignments(assignments_to_send),
-				),
-			))
+			send_assignments_batched(
+				ctx,
+				vec![(peer_id.clone(), peer_version)],
+				assignments_to_send,
+			)
 			.await;
 		}
 
@@ -1473,13 +1604,8 @@ impl State {
 				"Sending approvals to unified peer",
 			);
 
-			ctx.send_message(NetworkBridgeMessage::SendValidationMessage(
-				vec![peer_id.clone()],
-				protocol_v1::ValidationProtocol::ApprovalDistribution(
-					protocol_v1::ApprovalDistributionMessage::Approvals(approvals_to_send),
-				),
-			))
-			.await;
+			send_approvals_batched(ctx, vec![(peer_id.clone(), peer_version)], approvals_to_send)
+				.await;
 		}
 	}
 
@@ -1508,6 +1634,7 @@ impl State {
 			ctx,
 			&mut self.blocks,
 			&self.topologies,
+			&self.peer_views,
 			|block_entry| {
 				let block_age = max_age - block_entry.number;
 
@@ -1535,6 +1662,7 @@ impl State {
 			ctx,
 			&mut self.blocks,
 			&self.topologies,
+			&self.peer_views,
 			|block_entry| {
 				// Ramp up aggression only for the very oldest block(s).
 				// Approval voting can get stuck on a single block preventing
@@ -1592,6 +1720,7 @@ async fn adjust_required_routing_and_propagate(
 	          + overseer::SubsystemContext<Message = ApprovalDistributionMessage>),
 	blocks: &mut HashMap<Hash, BlockEntry>,
 	topologies: &SessionTopologies,
+	peer_views: &HashMap<PeerId, PeerEntry>,
 	block_filter: impl Fn(&mut BlockEntry) -> bool,
 	routing_modifier: impl Fn(&mut RequiredRouting, bool, &ValidatorIndex),
 ) {
@@ -1606,11 +1735,8 @@ async fn adjust_required_routing_and_propagate(
 		}
 
 		// Iterate all messages in all candidates.
-		for (candidate_index, validator, message_state) in block_entry
-			.candidates
-			.iter_mut()
-			.enumerate()
-			.flat_map(|(c_i, c)| c.messages.iter_mut().map(move |(k, v)| (c_i as _, k, v)))
+		for (validator, message_state) in
+			block_entry.candidates.iter_mut().flat_map(|c| c.messages.iter_mut())
 		{
 			routing_modifier(&mut message_state.required_routing, message_state.local, validator);
 
@@ -1624,23 +1750,24 @@ async fn adjust_required_routing_and_propagate(
 			};
 
 			// Propagate the message to all peers in the required routing set.
-			let message_subject =
-				MessageSubject(block_hash.clone(), candidate_index, validator.clone());
+			let claimed_candidates = message_state.approval_state.claimed_candidates();
+			let assignment_subject =
+				MessageSubject(block_hash.clone(), claimed_candidates.clone(), validator.clone());
 
 			let assignment_message = (
-				IndirectAssignmentCert {
+				IndirectAssignmentCertV2 {
 					block_hash: block_hash.clone(),
 					validator: validator.clone(),
 					cert: message_state.approval_state.assignment_cert().clone(),
 				},
-				candidate_index,
+				claimed_candidates.clone(),
 			);
 			let approval_message =
-				message_state.approval_state.approval_signature().map(|signature| {
-					IndirectSignedApprovalVote {
+				message_state.approval_state.approval().map(|(signature, approved_candidates)| {
+					IndirectSignedApprovalVoteV2 {
 						block_hash: block_hash.clone(),
+						candidate_indices: approved_candidates,
 						validator: validator.clone(),
-						candidate_index,
 						signature,
 					}
 				});
@@ -1650,8 +1777,18 @@ async fn adjust_required_routing_and_propagate(
 					continue
 				}
 
-				if !peer_knowledge.contains(&message_subject, MessageKind::Assignment) {
-					peer_knowledge.sent.insert(message_subject.clone(), MessageKind::Assignment);
+				let version = match peer_views.get(peer) {
+					Some(entry) => entry.version,
+					None => continue,
+				};
+
+				if !assignment_supported_by(version, &assignment_message.0.cert, claimed_candidates)
+				{
+					continue
+				}
+
+				if !peer_knowledge.contains(&assignment_subject, MessageKind::Assignment) {
+					peer_knowledge.sent.insert(assignment_subject.clone(), MessageKind::Assignment);
 					peer_assignments
 						.entry(peer.clone())
 						.or_insert_with(Vec::new)
@@ -1659,8 +1796,20 @@ async fn adjust_required_routing_and_propagate(
 				}
 
 				if let Some(approval_message) = approval_message.as_ref() {
-					if !peer_knowledge.contains(&message_subject, MessageKind::Approval) {
-						peer_knowledge.sent.insert(message_subject.clone(), MessageKind::Approval);
+					let approval_subject = MessageSubject(
+						block_hash.clone(),
+						approval_message.candidate_indices.clone(),
+						validator.clone(),
+					);
+
+					// An approval covering multiple candidates is sent along with the
+					// assignment of the last of them.
+					if approval_supported_by(version, &approval_subject.1) &&
+						!peer_knowledge.contains(&approval_subject, MessageKind::Approval) &&
+						approval_subject.per_candidate().all(|subject| {
+							peer_knowledge.contains(&subject, MessageKind::Assignment)
+						}) {
+						peer_knowledge.sent.insert(approval_subject, MessageKind::Approval);
 						peer_approvals
 							.entry(peer.clone())
 							.or_insert_with(Vec::new)
@@ -1674,21 +1823,138 @@ async fn adjust_required_routing_and_propagate(
 	// Send messages in accumulated packets, assignments preceding approvals.
 
 	for (peer, assignments_packet) in peer_assignments {
+		send_assignments_batched(ctx, with_versions(peer_views, vec![peer]), assignments_packet)
+			.await;
+	}
+
+	for (peer, approvals_packet) in peer_approvals {
+		send_approvals_batched(ctx, with_versions(peer_views, vec![peer]), approvals_packet).await;
+	}
+}
+
+// Attaches the protocol version spoken by each of the given peers. Peers which are not connected
+// anymore are left out.
+fn with_versions(
+	peer_views: &HashMap<PeerId, PeerEntry>,
+	peers: Vec<PeerId>,
+) -> Vec<(PeerId, ProtocolVersion)> {
+	peers
+		.into_iter()
+		.filter_map(|peer| peer_views.get(&peer).map(|entry| (peer, entry.version)))
+		.collect()
+}
+
+// Splits the given peers into those speaking the first version of the protocol and all others.
+fn split_by_version(peers: Vec<(PeerId, ProtocolVersion)>) -> (Vec<PeerId>, Vec<PeerId>) {
+	let mut v1_peers = Vec::new();
+	let mut v2_peers = Vec::new();
+
+	for (peer, version) in peers {
+		if version == ProtocolVersion::from(ValidationVersion::V1) {
+			v1_peers.push(peer);
+		} else {
+			v2_peers.push(peer);
+		}
+	}
+
+	(v1_peers, v2_peers)
+}
+
+// Whether an assignment can be sent to a peer speaking the given version of the protocol.
+// Multi-candidate certificates are unknown to the first version.
+fn assignment_supported_by(
+	version: ProtocolVersion,
+	cert: &AssignmentCertV2,
+	claimed_candidates: &CandidateBitfield,
+) -> bool {
+	version != ProtocolVersion::from(ValidationVersion::V1) ||
+		(claimed_candidates.count_ones() == 1 && AssignmentCert::try_from(cert.clone()).is_ok())
+}
+
+// Whether an approval can be sent to a peer speaking the given version of the protocol.
+// Coalesced approvals are unknown to the first version.
+fn approval_supported_by(
+	version: ProtocolVersion,
+	approved_candidates: &CandidateBitfield,
+) -> bool {
+	version != ProtocolVersion::from(ValidationVersion::V1) || approved_candidates.count_ones() == 1
+}
+
+// Sends assignments to the given peers in the version of the protocol each of them speaks.
+// Peers speaking the first version only receive the assignments which can be expressed in it,
+// multi-candidate certificates are sent to peers speaking the second version only.
+async fn send_assignments_batched(
+	ctx: &mut (impl SubsystemContext<Message = ApprovalDistributionMessage>
+	          + overseer::SubsystemContext<Message = ApprovalDistributionMessage>),
+	peers: Vec<(PeerId, ProtocolVersion)>,
+	assignments: Vec<(IndirectAssignmentCertV2, CandidateBitfield)>,
+) {
+	let (v1_peers, v2_peers) = split_by_version(peers);
+
+	if !v1_peers.is_empty() {
+		let v1_assignments = assignments
+			.iter()
+			.filter_map(|(assignment, claimed_candidates)| {
+				protocol_v2::assignment_into_v1(assignment.clone(), claimed_candidates)
+			})
+			.collect::<Vec<_>>();
+
+		if !v1_assignments.is_empty() {
+			ctx.send_message(NetworkBridgeMessage::SendValidationMessage(
+				v1_peers,
+				ValidationProtocols::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
+					protocol_v1::ApprovalDistributionMessage::Assignments(v1_assignments),
+				)),
+			))
+			.await;
+		}
+	}
+
+	if !v2_peers.is_empty() {
 		ctx.send_message(NetworkBridgeMessage::SendValidationMessage(
-			vec![peer],
-			protocol_v1::ValidationProtocol::ApprovalDistribution(
-				protocol_v1::ApprovalDistributionMessage::Assignments(assignments_packet),
-			),
+			v2_peers,
+			ValidationProtocols::V2(protocol_v2::ValidationProtocol::ApprovalDistribution(
+				protocol_v2::ApprovalDistributionMessage::Assignments(assignments),
+			)),
 		))
 		.await;
 	}
+}
 
-	for (peer, approvals_packet) in peer_approvals {
+// Sends approvals to the given peers in the version of the protocol each of them speaks.
+// Peers speaking the first version only receive the approvals covering a single candidate,
+// coalesced approvals are sent to peers speaking the second version only.
+async fn send_approvals_batched(
+	ctx: &mut (impl SubsystemContext<Message = ApprovalDistributionMessage>
+	          + overseer::SubsystemContext<Message = ApprovalDistributionMessage>),
+	peers: Vec<(PeerId, ProtocolVersion)>,
+	approvals: Vec<IndirectSignedApprovalVoteV2>,
+) {
+	let (v1_peers, v2_peers) = split_by_version(peers);
+
+	if !v1_peers.is_empty() {
+		let v1_approvals = approvals
+			.iter()
+			.filter_map(|approval| IndirectSignedApprovalVote::try_from(approval.clone()).ok())
+			.collect::<Vec<_>>();
+
+		if !v1_approvals.is_empty() {
+			ctx.send_message(NetworkBridgeMessage::SendValidationMessage(
+				v1_peers,
+				ValidationProtocols::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
+					protocol_v1::ApprovalDistributionMessage::Approvals(v1_approvals),
+				)),
+			))
+			.await;
+		}
+	}
+
+	if !v2_peers.is_empty() {
 		ctx.send_message(NetworkBridgeMessage::SendValidationMessage(
-			vec![peer],
-			protocol_v1::ValidationProtocol::ApprovalDistribution(
-				protocol_v1::ApprovalDistributionMessage::Approvals(approvals_packet),
-			),
+			v2_peers,
+			ValidationProtocols::V2(protocol_v2::ValidationProtocol::ApprovalDistribution(
+				protocol_v2::ApprovalDistributionMessage::Approvals(approvals),
+			)),
 		))
 		.await;
 	}
@@ -1779,18 +2045,18 @@ impl ApprovalDistribution {
 		Context: overseer::SubsystemContext<Message = ApprovalDistributionMessage>,
 	{
 		match msg {
-			ApprovalDistributionMessage::NetworkBridgeUpdateV1(event) => {
+			ApprovalDistributionMessage::NetworkBridgeUpdate(event) => {
 				state.handle_network_msg(ctx, metrics, event, rng).await;
 			},
 			ApprovalDistributionMessage::NewBlocks(metas) => {
 				state.handle_new_blocks(ctx, metrics, metas, rng).await;
 			},
-			ApprovalDistributionMessage::DistributeAssignment(cert, candidate_index) => {
+			ApprovalDistributionMessage::DistributeAssignment(cert, candidate_indices) => {
 				gum::debug!(
 					target: LOG_TARGET,
-					"Distributing our assignment on candidate (block={}, index={})",
+					"Distributing our assignment on candidates (block={}, indices={:?})",
 					cert.block_hash,
-					candidate_index,
+					candidate_indices,
 				);
 
 				state
@@ -1799,7 +2065,7 @@ impl ApprovalDistribution {
 						&metrics,
 						MessageSource::Local,
 						cert,
-						candidate_index,
+						candidate_indices,
 						rng,
 					)
 					.await;
@@ -1807,9 +2073,9 @@ impl ApprovalDistribution {
 			ApprovalDistributionMessage::DistributeApproval(vote) => {
 				gum::debug!(
 					target: LOG_TARGET,
-					"Distributing our approval vote on candidate (block={}, index={})",
+					"Distributing our approval vote on candidates (block={}, indices={:?})",
 					vote.block_hash,
-					vote.candidate_index,
+					vote.candidate_indices,
 				);
 
 				state
@@ -17,14 +17,15 @@
 use super::*;
 use assert_matches::assert_matches;
 use futures::{executor, future, Future};
-use polkadot_node_network_protocol::{our_view, view, ObservedRole};
+use polkadot_node_network_protocol::{our_view, peer_set::ValidationVersion, view, ObservedRole};
 use polkadot_node_primitives::approval::{
-	AssignmentCertKind, VRFOutput, VRFProof, RELAY_VRF_MODULO_CONTEXT,
+	AssignmentCert, AssignmentCertKind, AssignmentCertKindV2, CoreBitfield, VRFOutput, VRFProof,
+	RELAY_VRF_MODULO_CONTEXT,
 };
 use polkadot_node_subsystem::messages::{AllMessages, ApprovalCheckError};
 use polkadot_node_subsystem_test_helpers as test_helpers;
 use polkadot_node_subsystem_util::TimeoutExt as _;
-use polkadot_primitives::v2::{AuthorityDiscoveryId, BlakeTwo256, HashT};
+use polkadot_primitives::v2::{AuthorityDiscoveryId, BlakeTwo256, CoreIndex, HashT};
 use rand::SeedableRng;
 use sp_authority_discovery::AuthorityPair as AuthorityDiscoveryPair;
 use sp_core::crypto::Pair as PairT;
@@ -157,7 +158,7 @@ async fn setup_gossip_topology(
 ) {
 	overseer_send(
 		virtual_overseer,
-		ApprovalDistributionMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::NewGossipTopology(
+		ApprovalDistributionMessage::NetworkBridgeUpdate(NetworkBridgeEvent::NewGossipTopology(
 			gossip_topology,
 		)),
 	)
@@ -168,19 +169,21 @@ async fn setup_peer_with_view(
 	virtual_overseer: &mut VirtualOverseer,
 	peer_id: &PeerId,
 	view: View,
+	version: ValidationVersion,
 ) {
 	overseer_send(
 		virtual_overseer,
-		ApprovalDistributionMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::PeerConnected(
+		ApprovalDistributionMessage::NetworkBridgeUpdate(NetworkBridgeEvent::PeerConnected(
 			peer_id.clone(),
 			ObservedRole::Full,
+			version.into(),
 			None,
 		)),
 	)
 	.await;
 	overseer_send(
 		virtual_overseer,
-		ApprovalDistributionMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::PeerViewChange(
+		ApprovalDistributionMessage::NetworkBridgeUpdate(NetworkBridgeEvent::PeerViewChange(
 			peer_id.clone(),
 			view,
 		)),
@@ -191,13 +194,13 @@ async fn setup_peer_with_view(
 async fn send_message_from_peer(
 	virtual_overseer: &mut VirtualOverseer,
 	peer_id: &PeerId,
-	msg: protocol_v1::ApprovalDistributionMessage,
+	msg: impl Into<protocol_v2::ApprovalDistributionMessage>,
 ) {
 	overseer_send(
 		virtual_overseer,
-		ApprovalDistributionMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::PeerMessage(
+		ApprovalDistributionMessage::NetworkBridgeUpdate(NetworkBridgeEvent::PeerMessage(
 			peer_id.clone(),
-			msg,
+			msg.into(),
 		)),
 	)
 	.await;
@@ -221,6 +224,28 @@ fn fake_assignment_cert(block_hash: Hash, validator: ValidatorIndex) -> Indirect
 	}
 }
 
+fn fake_assignment_cert_v2(
+	block_hash: Hash,
+	validator: ValidatorIndex,
+	core_bitfield: CoreBitfield,
+) -> IndirectAssignmentCertV2 {
+	let ctx = schnorrkel::signing_context(RELAY_VRF_MODULO_CONTEXT);
+	let msg = b"WhenParachains?";
+	let mut prng = rand_core::OsRng;
+	let keypair = schnorrkel::Keypair::generate_with(&mut prng);
+	let (inout, proof, _) = keypair.vrf_sign(ctx.bytes(msg));
+	let out = inout.to_output();
+
+	IndirectAssignmentCertV2 {
+		block_hash,
+		validator,
+		cert: AssignmentCertV2 {
+			kind: AssignmentCertKindV2::RelayVRFModuloCompact { core_bitfield },
+			vrf: (VRFOutput(out), VRFProof(proof)),
+		},
+	}
+}
+
 async fn expect_reputation_change(
 	virtual_overseer: &mut VirtualOverseer,
 	peer_id: &PeerId,
@@ -255,9 +280,9 @@ fn try_import_the_same_assignment() {
 	let _ = test_harness(State::default(), |mut virtual_overseer| async move {
 		let overseer = &mut virtual_overseer;
 		// setup peers
-		setup_peer_with_view(overseer, &peer_a, view![]).await;
-		setup_peer_with_view(overseer, &peer_b, view![hash]).await;
-		setup_peer_with_view(overseer, &peer_c, view![hash]).await;
+		setup_peer_with_view(overseer, &peer_a, view![], ValidationVersion::V1).await;
+		setup_peer_with_view(overseer, &peer_b, view![hash], ValidationVersion::V1).await;
+		setup_peer_with_view(overseer, &peer_c, view![hash], ValidationVersion::V1).await;
 
 		// new block `hash_a` with 1 candidates
 		let meta = BlockApprovalMeta {
@@ -286,10 +311,11 @@ fn try_import_the_same_assignment() {
 			overseer_recv(overseer).await,
 			AllMessages::ApprovalVoting(ApprovalVotingMessage::CheckAndImportAssignment(
 				assignment,
-				0u32,
+				claimed_indices,
 				tx,
 			)) => {
-				assert_eq!(assignment, cert);
+				assert_eq!(assignment, cert.clone().into());
+				assert_eq!(claimed_indices, 0u32.into());
 				tx.send(AssignmentCheckResult::Accepted).unwrap();
 			}
 		);
@@ -300,9 +326,9 @@ fn try_import_the_same_assignment() {
 			overseer_recv(overseer).await,
 			AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
 				peers,
-				protocol_v1::ValidationProtocol::ApprovalDistribution(
+				ValidationProtocols::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
 					protocol_v1::ApprovalDistributionMessage::Assignments(assignments)
-				)
+				))
 			)) => {
 				assert_eq!(peers.len(), 2);
 				assert_eq!(assignments.len(), 1);
@@ -310,7 +336,7 @@ fn try_import_the_same_assignment() {
 		);
 
 		// setup new peer
-		setup_peer_with_view(overseer, &peer_d, view![]).await;
+		setup_peer_with_view(overseer, &peer_d, view![], ValidationVersion::V1).await;
 
 		// send the same assignment from peer_d
 		let msg = protocol_v1::ApprovalDistributionMessage::Assignments(assignments);
@@ -324,6 +350,434 @@ fn try_import_the_same_assignment() {
 	});
 }
 
+/// import an assignment claiming multiple candidates
+/// circulate it as a v2 assignment
+/// import an approval for one of the claimed candidates
+#[test]
+fn import_and_circulate_multiple_candidate_assignment() {
+	let peer_a = PeerId::random();
+	let peer_b = PeerId::random();
+	let peer_c = PeerId::random();
+	let peer_d = PeerId::random();
+	let parent_hash = Hash::repeat_byte(0xFF);
+	let hash = Hash::repeat_byte(0xAA);
+
+	let _ = test_harness(State::default(), |mut virtual_overseer| async move {
+		let overseer = &mut virtual_overseer;
+		// setup peers, `peer_d` only speaks the first version of the protocol
+		setup_peer_with_view(overseer, &peer_a, view![hash], ValidationVersion::V2).await;
+		setup_peer_with_view(overseer, &peer_b, view![hash], ValidationVersion::V2).await;
+		setup_peer_with_view(overseer, &peer_c, view![hash], ValidationVersion::V2).await;
+		setup_peer_with_view(overseer, &peer_d, view![hash], ValidationVersion::V1).await;
+
+		// new block `hash` with 2 candidates
+		let meta = BlockApprovalMeta {
+			hash,
+			parent_hash,
+			number: 2,
+			candidates: vec![Default::default(); 2],
+			slot: 1.into(),
+			session: 1,
+		};
+		let msg = ApprovalDistributionMessage::NewBlocks(vec![meta]);
+		overseer_send(overseer, msg).await;
+
+		// send the assignment claiming both candidates of `hash`
+		let validator_index = ValidatorIndex(0);
+		let cert = fake_assignment_cert_v2(
+			hash,
+			validator_index,
+			vec![CoreIndex(0), CoreIndex(1)].try_into().unwrap(),
+		);
+		let claimed_candidates: CandidateBitfield = vec![0u32, 1u32].try_into().unwrap();
+		let assignments = vec![(cert.clone(), claimed_candidates.clone())];
+
+		let msg = protocol_v2::ApprovalDistributionMessage::Assignments(assignments);
+		send_message_from_peer(overseer, &peer_a, msg).await;
+
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::ApprovalVoting(ApprovalVotingMessage::CheckAndImportAssignment(
+				assignment,
+				claimed_indices,
+				tx,
+			)) => {
+				assert_eq!(assignment, cert);
+				assert_eq!(claimed_indices, claimed_candidates);
+				tx.send(AssignmentCheckResult::Accepted).unwrap();
+			}
+		);
+
+		expect_reputation_change(overseer, &peer_a, BENEFIT_VALID_MESSAGE_FIRST).await;
+
+		// the assignment can't be expressed as a v1 assignment and isn't sent to `peer_d`.
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
+				peers,
+				ValidationProtocols::V2(protocol_v2::ValidationProtocol::ApprovalDistribution(
+					protocol_v2::ApprovalDistributionMessage::Assignments(assignments)
+				))
+			)) => {
+				assert_eq!(peers.len(), 2);
+				assert!(!peers.contains(&peer_d));
+				assert_eq!(assignments, vec![(cert.clone(), claimed_candidates.clone())]);
+			}
+		);
+
+		// an approval for the second candidate is accepted.
+		let approval = IndirectSignedApprovalVote {
+			block_hash: hash,
+			candidate_index: 1,
+			validator: validator_index,
+			signature: dummy_signature(),
+		};
+		let msg = protocol_v1::ApprovalDistributionMessage::Approvals(vec![approval.clone()]);
+		send_message_from_peer(overseer, &peer_b, msg).await;
+
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::ApprovalVoting(ApprovalVotingMessage::CheckAndImportApproval(
+				vote,
+				tx,
+			)) => {
+				assert_eq!(vote, IndirectSignedApprovalVoteV2::from(approval.clone()));
+				tx.send(ApprovalCheckResult::Accepted).unwrap();
+			}
+		);
+
+		expect_reputation_change(overseer, &peer_b, BENEFIT_VALID_MESSAGE_FIRST).await;
+
+		// `peer_d` doesn't know the assignment, so it isn't sent the approval either.
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
+				peers,
+				ValidationProtocols::V2(protocol_v2::ValidationProtocol::ApprovalDistribution(
+					protocol_v2::ApprovalDistributionMessage::Approvals(approvals)
+				))
+			)) => {
+				assert_eq!(peers, vec![peer_c.clone()]);
+				assert_eq!(approvals, vec![approval.into()]);
+			}
+		);
+
+		assert!(overseer.recv().timeout(TIMEOUT).await.is_none(), "no message should be sent");
+		virtual_overseer
+	});
+}
+
+/// an assignment claiming a candidate which is not part of the block is rejected
+#[test]
+fn assignment_claiming_unknown_candidate_is_rejected() {
+	let peer_a = PeerId::random();
+	let parent_hash = Hash::repeat_byte(0xFF);
+	let hash = Hash::repeat_byte(0xAA);
+
+	let _ = test_harness(State::default(), |mut virtual_overseer| async move {
+		let overseer = &mut virtual_overseer;
+		setup_peer_with_view(overseer, &peer_a, view![hash], ValidationVersion::V1).await;
+
+		// new block `hash` with 1 candidate
+		let meta = BlockApprovalMeta {
+			hash,
+			parent_hash,
+			number: 2,
+			candidates: vec![Default::default(); 1],
+			slot: 1.into(),
+			session: 1,
+		};
+		let msg = ApprovalDistributionMessage::NewBlocks(vec![meta]);
+		overseer_send(overseer, msg).await;
+
+		let cert = fake_assignment_cert_v2(
+			hash,
+			ValidatorIndex(0),
+			vec![CoreIndex(0), CoreIndex(1)].try_into().unwrap(),
+		);
+		let claimed_candidates: CandidateBitfield = vec![0u32, 1u32].try_into().unwrap();
+
+		let msg =
+			protocol_v2::ApprovalDistributionMessage::Assignments(vec![(cert, claimed_candidates)]);
+		send_message_from_peer(overseer, &peer_a, msg).await;
+
+		expect_reputation_change(overseer, &peer_a, COST_INVALID_MESSAGE).await;
+
+		assert!(overseer.recv().timeout(TIMEOUT).await.is_none(), "no message should be sent");
+		virtual_overseer
+	});
+}
+
+/// import an assignment claiming multiple candidates
+/// import an approval covering all of them with a single signature
+/// circulate it as a v2 approval
+#[test]
+fn import_and_circulate_coalesced_approval() {
+	let peer_a = PeerId::random();
+	let peer_b = PeerId::random();
+	let peer_c = PeerId::random();
+	let peer_d = PeerId::random();
+	let parent_hash = Hash::repeat_byte(0xFF);
+	let hash = Hash::repeat_byte(0xAA);
+
+	let _ = test_harness(State::default(), |mut virtual_overseer| async move {
+		let overseer = &mut virtual_overseer;
+		// setup peers, `peer_d` only speaks the first version of the protocol
+		setup_peer_with_view(overseer, &peer_a, view![hash], ValidationVersion::V2).await;
+		setup_peer_with_view(overseer, &peer_b, view![hash], ValidationVersion::V2).await;
+		setup_peer_with_view(overseer, &peer_c, view![hash], ValidationVersion::V2).await;
+		setup_peer_with_view(overseer, &peer_d, view![hash], ValidationVersion::V1).await;
+
+		// new block `hash` with 2 candidates
+		let meta = BlockApprovalMeta {
+			hash,
+			parent_hash,
+			number: 2,
+			candidates: vec![Default::default(); 2],
+			slot: 1.into(),
+			session: 1,
+		};
+		let msg = ApprovalDistributionMessage::NewBlocks(vec![meta]);
+		overseer_send(overseer, msg).await;
+
+		// send the assignment claiming both candidates of `hash`
+		let validator_index = ValidatorIndex(0);
+		let cert = fake_assignment_cert_v2(
+			hash,
+			validator_index,
+			vec![CoreIndex(0), CoreIndex(1)].try_into().unwrap(),
+		);
+		let candidates: CandidateBitfield = vec![0u32, 1u32].try_into().unwrap();
+
+		let msg = protocol_v2::ApprovalDistributionMessage::Assignments(vec![(
+			cert.clone(),
+			candidates.clone(),
+		)]);
+		send_message_from_peer(overseer, &peer_a, msg).await;
+
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::ApprovalVoting(ApprovalVotingMessage::CheckAndImportAssignment(
+				_,
+				_,
+				tx,
+			)) => {
+				tx.send(AssignmentCheckResult::Accepted).unwrap();
+			}
+		);
+
+		expect_reputation_change(overseer, &peer_a, BENEFIT_VALID_MESSAGE_FIRST).await;
+
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
+				_,
+				ValidationProtocols::V2(protocol_v2::ValidationProtocol::ApprovalDistribution(
+					protocol_v2::ApprovalDistributionMessage::Assignments(_)
+				))
+			))
+		);
+
+		// a single approval covers both candidates.
+		let approval = IndirectSignedApprovalVoteV2 {
+			block_hash: hash,
+			candidate_indices: candidates.clone(),
+			validator: validator_index,
+			signature: dummy_signature(),
+		};
+		let msg = protocol_v2::ApprovalDistributionMessage::Approvals(vec![approval.clone()]);
+		send_message_from_peer(overseer, &peer_b, msg).await;
+
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::ApprovalVoting(ApprovalVotingMessage::CheckAndImportApproval(
+				vote,
+				tx,
+			)) => {
+				assert_eq!(vote, approval);
+				tx.send(ApprovalCheckResult::Accepted).unwrap();
+			}
+		);
+
+		expect_reputation_change(overseer, &peer_b, BENEFIT_VALID_MESSAGE_FIRST).await;
+
+		// the approval can't be expressed as a v1 approval and isn't sent to `peer_d`.
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
+				peers,
+				ValidationProtocols::V2(protocol_v2::ValidationProtocol::ApprovalDistribution(
+					protocol_v2::ApprovalDistributionMessage::Approvals(approvals)
+				))
+			)) => {
+				assert_eq!(peers, vec![peer_c.clone()]);
+				assert_eq!(approvals, vec![approval.clone()]);
+			}
+		);
+
+		// the same approval for a single one of the candidates is already known.
+		let single_approval = IndirectSignedApprovalVote {
+			block_hash: hash,
+			candidate_index: 1,
+			validator: validator_index,
+			signature: dummy_signature(),
+		};
+		let msg = protocol_v1::ApprovalDistributionMessage::Approvals(vec![single_approval]);
+		send_message_from_peer(overseer, &peer_a, msg).await;
+
+		expect_reputation_change(overseer, &peer_a, BENEFIT_VALID_MESSAGE).await;
+
+		assert!(overseer.recv().timeout(TIMEOUT).await.is_none(), "no message should be sent");
+		virtual_overseer
+	});
+}
+
+/// an approval covering a candidate without a known assignment is rejected
+#[test]
+fn coalesced_approval_without_assignment_for_all_candidates_is_rejected() {
+	let peer_a = PeerId::random();
+	let peer_b = PeerId::random();
+	let parent_hash = Hash::repeat_byte(0xFF);
+	let hash = Hash::repeat_byte(0xAA);
+
+	let _ = test_harness(State::default(), |mut virtual_overseer| async move {
+		let overseer = &mut virtual_overseer;
+		setup_peer_with_view(overseer, &peer_a, view![hash], ValidationVersion::V1).await;
+		setup_peer_with_view(overseer, &peer_b, view![hash], ValidationVersion::V1).await;
+
+		// new block `hash` with 2 candidates
+		let meta = BlockApprovalMeta {
+			hash,
+			parent_hash,
+			number: 2,
+			candidates: vec![Default::default(); 2],
+			slot: 1.into(),
+			session: 1,
+		};
+		let msg = ApprovalDistributionMessage::NewBlocks(vec![meta]);
+		overseer_send(overseer, msg).await;
+
+		// the assignment only claims the first candidate.
+		let validator_index = ValidatorIndex(0);
+		let cert = fake_assignment_cert(hash, validator_index);
+		let msg = protocol_v1::ApprovalDistributionMessage::Assignments(vec![(cert, 0u32)]);
+		send_message_from_peer(overseer, &peer_a, msg).await;
+
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::ApprovalVoting(ApprovalVotingMessage::CheckAndImportAssignment(
+				_,
+				_,
+				tx,
+			)) => {
+				tx.send(AssignmentCheckResult::Accepted).unwrap();
+			}
+		);
+
+		expect_reputation_change(overseer, &peer_a, BENEFIT_VALID_MESSAGE_FIRST).await;
+
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
+				_,
+				ValidationProtocols::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
+					protocol_v1::ApprovalDistributionMessage::Assignments(_)
+				))
+			))
+		);
+
+		let approval = IndirectSignedApprovalVoteV2 {
+			block_hash: hash,
+			candidate_indices: vec![0u32, 1u32].try_into().unwrap(),
+			validator: validator_index,
+			signature: dummy_signature(),
+		};
+		let msg = protocol_v2::ApprovalDistributionMessage::Approvals(vec![approval]);
+		send_message_from_peer(overseer, &peer_b, msg).await;
+
+		expect_reputation_change(overseer, &peer_b, COST_UNEXPECTED_MESSAGE).await;
+
+		assert!(overseer.recv().timeout(TIMEOUT).await.is_none(), "no message should be sent");
+		virtual_overseer
+	});
+}
+
+/// a single-candidate assignment is sent to each peer in the version of the protocol it speaks
+#[test]
+fn assignments_are_sent_in_the_version_of_each_peer() {
+	let peer_a = PeerId::random();
+	let peer_b = PeerId::random();
+	let peer_c = PeerId::random();
+	let parent_hash = Hash::repeat_byte(0xFF);
+	let hash = Hash::repeat_byte(0xAA);
+
+	let _ = test_harness(State::default(), |mut virtual_overseer| async move {
+		let overseer = &mut virtual_overseer;
+		setup_peer_with_view(overseer, &peer_a, view![hash], ValidationVersion::V1).await;
+		setup_peer_with_view(overseer, &peer_b, view![hash], ValidationVersion::V1).await;
+		setup_peer_with_view(overseer, &peer_c, view![hash], ValidationVersion::V2).await;
+
+		// new block `hash` with 1 candidate
+		let meta = BlockApprovalMeta {
+			hash,
+			parent_hash,
+			number: 2,
+			candidates: vec![Default::default(); 1],
+			slot: 1.into(),
+			session: 1,
+		};
+		let msg = ApprovalDistributionMessage::NewBlocks(vec![meta]);
+		overseer_send(overseer, msg).await;
+
+		let cert = fake_assignment_cert(hash, ValidatorIndex(0));
+		let assignments = vec![(cert.clone(), 0u32)];
+		let msg = protocol_v1::ApprovalDistributionMessage::Assignments(assignments.clone());
+		send_message_from_peer(overseer, &peer_a, msg).await;
+
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::ApprovalVoting(ApprovalVotingMessage::CheckAndImportAssignment(
+				_,
+				_,
+				tx,
+			)) => {
+				tx.send(AssignmentCheckResult::Accepted).unwrap();
+			}
+		);
+
+		expect_reputation_change(overseer, &peer_a, BENEFIT_VALID_MESSAGE_FIRST).await;
+
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
+				peers,
+				ValidationProtocols::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
+					protocol_v1::ApprovalDistributionMessage::Assignments(sent_assignments)
+				))
+			)) => {
+				assert_eq!(peers, vec![peer_b.clone()]);
+				assert_eq!(sent_assignments, assignments);
+			}
+		);
+
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
+				peers,
+				ValidationProtocols::V2(protocol_v2::ValidationProtocol::ApprovalDistribution(
+					protocol_v2::ApprovalDistributionMessage::Assignments(sent_assignments)
+				))
+			)) => {
+				assert_eq!(peers, vec![peer_c.clone()]);
+				assert_eq!(sent_assignments, vec![(cert.into(), 0u32.into())]);
+			}
+		);
+
+		assert!(overseer.recv().timeout(TIMEOUT).await.is_none(), "no message should be sent");
+		virtual_overseer
+	});
+}
+
 /// <https://github.com/paritytech/polkadot/pull/2160#discussion_r547594835>
 ///
 /// 1. Send a view update that removes block B from their view.
@@ -339,7 +793,7 @@ fn spam_attack_results_in_negative_reputation_change() {
 	let _ = test_harness(State::default(), |mut virtual_overseer| async move {
 		let overseer = &mut virtual_overseer;
 		let peer = &peer_a;
-		setup_peer_with_view(overseer, peer, view![]).await;
+		setup_peer_with_view(overseer, peer, view![], ValidationVersion::V1).await;
 
 		// new block `hash_b` with 20 candidates
 		let candidates_count = 20;
@@ -378,8 +832,8 @@ fn spam_attack_results_in_negative_reputation_change() {
 					claimed_candidate_index,
 					tx,
 				)) => {
-					assert_eq!(assignment, assignments[i].0);
-					assert_eq!(claimed_candidate_index, assignments[i].1);
+					assert_eq!(assignment, assignments[i].0.clone().into());
+					assert_eq!(claimed_candidate_index, assignments[i].1.into());
 					tx.send(AssignmentCheckResult::Accepted).unwrap();
 				}
 			);
@@ -390,7 +844,7 @@ fn spam_attack_results_in_negative_reputation_change() {
 		// send a view update that removes block B from peer's view by bumping the finalized_number
 		overseer_send(
 			overseer,
-			ApprovalDistributionMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::PeerViewChange(
+			ApprovalDistributionMessage::NetworkBridgeUpdate(NetworkBridgeEvent::PeerViewChange(
 				peer.clone(),
 				View::with_finalized(2),
 			)),
@@ -423,7 +877,7 @@ fn peer_sending_us_the_same_we_just_sent_them_is_ok() {
 	let _ = test_harness(State::default(), |mut virtual_overseer| async move {
 		let overseer = &mut virtual_overseer;
 		let peer = &peer_a;
-		setup_peer_with_view(overseer, peer, view![]).await;
+		setup_peer_with_view(overseer, peer, view![], ValidationVersion::V1).await;
 
 		// new block `hash` with 1 candidates
 		let meta = BlockApprovalMeta {
@@ -443,14 +897,17 @@ fn peer_sending_us_the_same_we_just_sent_them_is_ok() {
 		let cert = fake_assignment_cert(hash, validator_index);
 		overseer_send(
 			overseer,
-			ApprovalDistributionMessage::DistributeAssignment(cert.clone(), candidate_index),
+			ApprovalDistributionMessage::DistributeAssignment(
+				cert.clone().into(),
+				candidate_index.into(),
+			),
 		)
 		.await;
 
 		// update peer view to include the hash
 		overseer_send(
 			overseer,
-			ApprovalDistributionMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::PeerViewChange(
+			ApprovalDistributionMessage::NetworkBridgeUpdate(NetworkBridgeEvent::PeerViewChange(
 				peer.clone(),
 				view![hash],
 			)),
@@ -462,9 +919,9 @@ fn peer_sending_us_the_same_we_just_sent_them_is_ok() {
 			overseer_recv(overseer).await,
 			AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
 				peers,
-				protocol_v1::ValidationProtocol::ApprovalDistribution(
+				ValidationProtocols::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
 					protocol_v1::ApprovalDistributionMessage::Assignments(assignments)
-				)
+				))
 			)) => {
 				assert_eq!(peers.len(), 1);
 				assert_eq!(assignments.len(), 1);
@@ -499,9 +956,9 @@ fn import_approval_happy_path() {
 	let _ = test_harness(State::default(), |mut virtual_overseer| async move {
 		let overseer = &mut virtual_overseer;
 		// setup peers
-		setup_peer_with_view(overseer, &peer_a, view![]).await;
-		setup_peer_with_view(overseer, &peer_b, view![hash]).await;
-		setup_peer_with_view(overseer, &peer_c, view![hash]).await;
+		setup_peer_with_view(overseer, &peer_a, view![], ValidationVersion::V1).await;
+		setup_peer_with_view(overseer, &peer_b, view![hash], ValidationVersion::V1).await;
+		setup_peer_with_view(overseer, &peer_c, view![hash], ValidationVersion::V1).await;
 
 		// new block `hash_a` with 1 candidates
 		let meta = BlockApprovalMeta {
@@ -521,7 +978,7 @@ fn import_approval_happy_path() {
 		let cert = fake_assignment_cert(hash, validator_index);
 		overseer_send(
 			overseer,
-			ApprovalDistributionMessage::DistributeAssignment(cert, candidate_index),
+			ApprovalDistributionMessage::DistributeAssignment(cert.into(), candidate_index.into()),
 		)
 		.await;
 
@@ -529,9 +986,9 @@ fn import_approval_happy_path() {
 			overseer_recv(overseer).await,
 			AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
 				peers,
-				protocol_v1::ValidationProtocol::ApprovalDistribution(
+				ValidationProtocols::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
 					protocol_v1::ApprovalDistributionMessage::Assignments(assignments)
-				)
+				))
 			)) => {
 				assert_eq!(peers.len(), 2);
 				assert_eq!(assignments.len(), 1);
@@ -554,7 +1011,7 @@ fn import_approval_happy_path() {
 				vote,
 				tx,
 			)) => {
-				assert_eq!(vote, approval);
+				assert_eq!(vote, IndirectSignedApprovalVoteV2::from(approval.clone()));
 				tx.send(ApprovalCheckResult::Accepted).unwrap();
 			}
 		);
@@ -565,9 +1022,9 @@ fn import_approval_happy_path() {
 			overseer_recv(overseer).await,
 			AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
 				peers,
-				protocol_v1::ValidationProtocol::ApprovalDistribution(
+				ValidationProtocols::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
 					protocol_v1::ApprovalDistributionMessage::Approvals(approvals)
-				)
+				))
 			)) => {
 				assert_eq!(peers.len(), 1);
 				assert_eq!(approvals.len(), 1);
@@ -587,8 +1044,8 @@ fn import_approval_bad() {
 	let _ = test_harness(State::default(), |mut virtual_overseer| async move {
 		let overseer = &mut virtual_overseer;
 		// setup peers
-		setup_peer_with_view(overseer, &peer_a, view![]).await;
-		setup_peer_with_view(overseer, &peer_b, view![hash]).await;
+		setup_peer_with_view(overseer, &peer_a, view![], ValidationVersion::V1).await;
+		setup_peer_with_view(overseer, &peer_b, view![hash], ValidationVersion::V1).await;
 
 		// new block `hash_a` with 1 candidates
 		let meta = BlockApprovalMeta {
@@ -630,8 +1087,8 @@ fn import_approval_bad() {
 				i,
 				tx,
 			)) => {
-				assert_eq!(assignment, cert);
-				assert_eq!(i, candidate_index);
+				assert_eq!(assignment, cert.clone().into());
+				assert_eq!(i, candidate_index.into());
 				tx.send(AssignmentCheckResult::Accepted).unwrap();
 			}
 		);
@@ -648,7 +1105,7 @@ fn import_approval_bad() {
 				vote,
 				tx,
 			)) => {
-				assert_eq!(vote, approval);
+				assert_eq!(vote, IndirectSignedApprovalVoteV2::from(approval.clone()));
 				tx.send(ApprovalCheckResult::Bad(ApprovalCheckError::UnknownBlock(hash))).unwrap();
 			}
 		);
@@ -776,21 +1233,29 @@ fn update_peer_view() {
 		let cert_a = fake_assignment_cert(hash_a, ValidatorIndex(0));
 		let cert_b = fake_assignment_cert(hash_b, ValidatorIndex(0));
 
-		overseer_send(overseer, ApprovalDistributionMessage::DistributeAssignment(cert_a, 0)).await;
+		overseer_send(
+			overseer,
+			ApprovalDistributionMessage::DistributeAssignment(cert_a.into(), 0u32.into()),
+		)
+		.await;
 
-		overseer_send(overseer, ApprovalDistributionMessage::DistributeAssignment(cert_b, 0)).await;
+		overseer_send(
+			overseer,
+			ApprovalDistributionMessage::DistributeAssignment(cert_b.into(), 0u32.into()),
+		)
+		.await;
 
 		// connect a peer
-		setup_peer_with_view(overseer, peer, view![hash_a]).await;
+		setup_peer_with_view(overseer, peer, view![hash_a], ValidationVersion::V1).await;
 
 		// we should send relevant assignments to the peer
 		assert_matches!(
 			overseer_recv(overseer).await,
 			AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
 				peers,
-				protocol_v1::ValidationProtocol::ApprovalDistribution(
+				ValidationProtocols::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
 					protocol_v1::ApprovalDistributionMessage::Assignments(assignments)
-				)
+				))
 			)) => {
 				assert_eq!(peers.len(), 1);
 				assert_eq!(assignments.len(), 1);
@@ -799,7 +1264,7 @@ fn update_peer_view() {
 		virtual_overseer
 	});
 
-	assert_eq!(state.peer_views.get(peer).map(|v| v.finalized_number), Some(0));
+	assert_eq!(state.peer_views.get(peer).map(|entry| entry.view.finalized_number), Some(0));
 	assert_eq!(
 		state
 			.blocks
@@ -819,7 +1284,7 @@ fn update_peer_view() {
 		// update peer's view
 		overseer_send(
 			overseer,
-			ApprovalDistributionMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::PeerViewChange(
+			ApprovalDistributionMessage::NetworkBridgeUpdate(NetworkBridgeEvent::PeerViewChange(
 				peer.clone(),
 				View::new(vec![hash_b, hash_c, hash_d], 2),
 			)),
@@ -830,7 +1295,7 @@ fn update_peer_view() {
 
 		overseer_send(
 			overseer,
-			ApprovalDistributionMessage::DistributeAssignment(cert_c.clone(), 0),
+			ApprovalDistributionMessage::DistributeAssignment(cert_c.clone().into(), 0u32.into()),
 		)
 		.await;
 
@@ -839,9 +1304,9 @@ fn update_peer_view() {
 			overseer_recv(overseer).await,
 			AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
 				peers,
-				protocol_v1::ValidationProtocol::ApprovalDistribution(
+				ValidationProtocols::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
 					protocol_v1::ApprovalDistributionMessage::Assignments(assignments)
-				)
+				))
 			)) => {
 				assert_eq!(peers.len(), 1);
 				assert_eq!(assignments.len(), 1);
@@ -851,7 +1316,7 @@ fn update_peer_view() {
 		virtual_overseer
 	});
 
-	assert_eq!(state.peer_views.get(peer).map(|v| v.finalized_number), Some(2));
+	assert_eq!(state.peer_views.get(peer).map(|entry| entry.view.finalized_number), Some(2));
 	assert_eq!(
 		state
 			.blocks
@@ -872,7 +1337,7 @@ fn update_peer_view() {
 		// update peer's view
 		overseer_send(
 			overseer,
-			ApprovalDistributionMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::PeerViewChange(
+			ApprovalDistributionMessage::NetworkBridgeUpdate(NetworkBridgeEvent::PeerViewChange(
 				peer.clone(),
 				View::with_finalized(finalized_number),
 			)),
@@ -881,7 +1346,10 @@ fn update_peer_view() {
 		virtual_overseer
 	});
 
-	assert_eq!(state.peer_views.get(peer).map(|v| v.finalized_number), Some(finalized_number));
+	assert_eq!(
+		state.peer_views.get(peer).map(|entry| entry.view.finalized_number),
+		Some(finalized_number)
+	);
 	assert!(state.blocks.get(&hash_c).unwrap().known_by.get(peer).is_none());
 }
 
@@ -896,7 +1364,7 @@ fn import_remotely_then_locally() {
 	let _ = test_harness(State::default(), |mut virtual_overseer| async move {
 		let overseer = &mut virtual_overseer;
 		// setup the peer
-		setup_peer_with_view(overseer, peer, view![hash]).await;
+		setup_peer_with_view(overseer, peer, view![hash], ValidationVersion::V1).await;
 
 		// new block `hash_a` with 1 candidates
 		let meta = BlockApprovalMeta {
@@ -926,8 +1394,8 @@ fn import_remotely_then_locally() {
 				i,
 				tx,
 			)) => {
-				assert_eq!(assignment, cert);
-				assert_eq!(i, candidate_index);
+				assert_eq!(assignment, cert.clone().into());
+				assert_eq!(i, candidate_index.into());
 				tx.send(AssignmentCheckResult::Accepted).unwrap();
 			}
 		);
@@ -937,7 +1405,7 @@ fn import_remotely_then_locally() {
 		// import the same assignment locally
 		overseer_send(
 			overseer,
-			ApprovalDistributionMessage::DistributeAssignment(cert, candidate_index),
+			ApprovalDistributionMessage::DistributeAssignment(cert.into(), candidate_index.into()),
 		)
 		.await;
 
@@ -959,14 +1427,15 @@ fn import_remotely_then_locally() {
 				vote,
 				tx,
 			)) => {
-				assert_eq!(vote, approval);
+				assert_eq!(vote, IndirectSignedApprovalVoteV2::from(approval.clone()));
 				tx.send(ApprovalCheckResult::Accepted).unwrap();
 			}
 		);
 		expect_reputation_change(overseer, peer, BENEFIT_VALID_MESSAGE_FIRST).await;
 
 		// import the same approval locally
-		overseer_send(overseer, ApprovalDistributionMessage::DistributeApproval(approval)).await;
+		overseer_send(overseer, ApprovalDistributionMessage::DistributeApproval(approval.into()))
+			.await;
 
 		assert!(overseer.recv().timeout(TIMEOUT).await.is_none(), "no message should be sent");
 		virtual_overseer
@@ -1009,15 +1478,21 @@ fn sends_assignments_even_when_state_is_approved() {
 
 		overseer_send(
 			overseer,
-			ApprovalDistributionMessage::DistributeAssignment(cert.clone(), candidate_index),
+			ApprovalDistributionMessage::DistributeAssignment(
+				cert.clone().into(),
+				candidate_index.into(),
+			),
 		)
 		.await;
 
-		overseer_send(overseer, ApprovalDistributionMessage::DistributeApproval(approval.clone()))
-			.await;
+		overseer_send(
+			overseer,
+			ApprovalDistributionMessage::DistributeApproval(approval.clone().into()),
+		)
+		.await;
 
 		// connect the peer.
-		setup_peer_with_view(overseer, peer, view![hash]).await;
+		setup_peer_with_view(overseer, peer, view![hash], ValidationVersion::V1).await;
 
 		let assignments = vec![(cert.clone(), candidate_index)];
 		let approvals = vec![approval.clone()];
@@ -1026,9 +1501,9 @@ fn sends_assignments_even_when_state_is_approved() {
 			overseer_recv(overseer).await,
 			AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
 				peers,
-				protocol_v1::ValidationProtocol::ApprovalDistribution(
+				ValidationProtocols::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
 					protocol_v1::ApprovalDistributionMessage::Assignments(sent_assignments)
-				)
+				))
 			)) => {
 				assert_eq!(peers, vec![peer.clone()]);
 				assert_eq!(sent_assignments, assignments);
@@ -1039,9 +1514,9 @@ fn sends_assignments_even_when_state_is_approved() {
 			overseer_recv(overseer).await,
 			AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
 				peers,
-				protocol_v1::ValidationProtocol::ApprovalDistribution(
+				ValidationProtocols::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
 					protocol_v1::ApprovalDistributionMessage::Approvals(sent_approvals)
-				)
+				))
 			)) => {
 				assert_eq!(peers, vec![peer.clone()]);
 				assert_eq!(sent_approvals, approvals);
@@ -1081,12 +1556,12 @@ fn race_condition_in_local_vs_remote_view_update() {
 		};
 
 		// This will send a peer view that is ahead of our view
-		setup_peer_with_view(overseer, peer, view![hash_b]).await;
+		setup_peer_with_view(overseer, peer, view![hash_b], ValidationVersion::V1).await;
 
 		// Send our view update to include a new head
 		overseer_send(
 			overseer,
-			ApprovalDistributionMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::OurViewChange(
+			ApprovalDistributionMessage::NetworkBridgeUpdate(NetworkBridgeEvent::OurViewChange(
 				our_view![hash_b],
 			)),
 		)
@@ -1119,8 +1594,8 @@ fn race_condition_in_local_vs_remote_view_update() {
 					claimed_candidate_index,
 					tx,
 				)) => {
-					assert_eq!(assignment, assignments[i].0);
-					assert_eq!(claimed_candidate_index, assignments[i].1);
+					assert_eq!(assignment, assignments[i].0.clone().into());
+					assert_eq!(claimed_candidate_index, assignments[i].1.into());
 					tx.send(AssignmentCheckResult::Accepted).unwrap();
 				}
 			);
@@ -1145,7 +1620,7 @@ fn propagates_locally_generated_assignment_to_both_dimensions() {
 
 		// Connect all peers.
 		for (peer, _) in &peers {
-			setup_peer_with_view(overseer, peer, view![hash]).await;
+			setup_peer_with_view(overseer, peer, view![hash], ValidationVersion::V1).await;
 		}
 
 		// Set up a gossip topology.
@@ -1187,12 +1662,18 @@ fn propagates_locally_generated_assignment_to_both_dimensions() {
 
 		overseer_send(
 			overseer,
-			ApprovalDistributionMessage::DistributeAssignment(cert.clone(), candidate_index),
+			ApprovalDistributionMessage::DistributeAssignment(
+				cert.clone().into(),
+				candidate_index.into(),
+			),
 		)
 		.await;
 
-		overseer_send(overseer, ApprovalDistributionMessage::DistributeApproval(approval.clone()))
-			.await;
+		overseer_send(
+			overseer,
+			ApprovalDistributionMessage::DistributeApproval(approval.clone().into()),
+		)
+		.await;
 
 		let assignments = vec![(cert.clone(), candidate_index)];
 		let approvals = vec![approval.clone()];
@@ -1201,9 +1682,9 @@ fn propagates_locally_generated_assignment_to_both_dimensions() {
 			overseer_recv(overseer).await,
 			AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
 				sent_peers,
-				protocol_v1::ValidationProtocol::ApprovalDistribution(
+				ValidationProtocols::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
 					protocol_v1::ApprovalDistributionMessage::Assignments(sent_assignments)
-				)
+				))
 			)) => {
 				assert_eq!(sent_peers.len(), expected_indices.len() + 4);
 				for &i in &expected_indices {
@@ -1222,9 +1703,9 @@ fn propagates_locally_generated_assignment_to_both_dimensions() {
 			overseer_recv(overseer).await,
 			AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
 				sent_peers,
-				protocol_v1::ValidationProtocol::ApprovalDistribution(
+				ValidationProtocols::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
 					protocol_v1::ApprovalDistributionMessage::Approvals(sent_approvals)
-				)
+				))
 			)) => {
 				// Random sampling is reused from the assignment.
 				assert_eq!(sent_peers, assignment_sent_peers);
@@ -1250,7 +1731,7 @@ fn propagates_assignments_along_unshared_dimension() {
 
 		// Connect all peers.
 		for (peer, _) in &peers {
-			setup_peer_with_view(overseer, peer, view![hash]).await;
+			setup_peer_with_view(overseer, peer, view![hash], ValidationVersion::V1).await;
 		}
 
 		// Set up a gossip topology.
@@ -1305,9 +1786,9 @@ fn propagates_assignments_along_unshared_dimension() {
 				overseer_recv(overseer).await,
 				AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
 					sent_peers,
-					protocol_v1::ValidationProtocol::ApprovalDistribution(
+					ValidationProtocols::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
 						protocol_v1::ApprovalDistributionMessage::Assignments(sent_assignments)
-					)
+					))
 				)) => {
 					assert_eq!(sent_peers.len(), expected_y.len() + 4);
 					for &i in &expected_y {
@@ -1354,9 +1835,9 @@ fn propagates_assignments_along_unshared_dimension() {
 				overseer_recv(overseer).await,
 				AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
 					sent_peers,
-					protocol_v1::ValidationProtocol::ApprovalDistribution(
+					ValidationProtocols::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
 						protocol_v1::ApprovalDistributionMessage::Assignments(sent_assignments)
-					)
+					))
 				)) => {
 					assert_eq!(sent_peers.len(), expected_x.len() + 4);
 					for &i in &expected_x {
@@ -1392,7 +1873,7 @@ fn propagates_to_required_after_connect() {
 		// Connect all peers except omitted.
 		for (i, (peer, _)) in peers.iter().enumerate() {
 			if !omitted.contains(&i) {
-				setup_peer_with_view(overseer, peer, view![hash]).await;
+				setup_peer_with_view(overseer, peer, view![hash], ValidationVersion::V1).await;
 			}
 		}
 
@@ -1435,12 +1916,18 @@ fn propagates_to_required_after_connect() {
 
 		overseer_send(
 			overseer,
-			ApprovalDistributionMessage::DistributeAssignment(cert.clone(), candidate_index),
+			ApprovalDistributionMessage::DistributeAssignment(
+				cert.clone().into(),
+				candidate_index.into(),
+			),
 		)
 		.await;
 
-		overseer_send(overseer, ApprovalDistributionMessage::DistributeApproval(approval.clone()))
-			.await;
+		overseer_send(
+			overseer,
+			ApprovalDistributionMessage::DistributeApproval(approval.clone().into()),
+		)
+		.await;
 
 		let assignments = vec![(cert.clone(), candidate_index)];
 		let approvals = vec![approval.clone()];
@@ -1449,9 +1936,9 @@ fn propagates_to_required_after_connect() {
 			overseer_recv(overseer).await,
 			AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
 				sent_peers,
-				protocol_v1::ValidationProtocol::ApprovalDistribution(
+				ValidationProtocols::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
 					protocol_v1::ApprovalDistributionMessage::Assignments(sent_assignments)
-				)
+				))
 			)) => {
 				assert_eq!(sent_peers.len(), expected_indices.len() + 4);
 				for &i in &expected_indices {
@@ -1470,9 +1957,9 @@ fn propagates_to_required_after_connect() {
 			overseer_recv(overseer).await,
 			AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
 				sent_peers,
-				protocol_v1::ValidationProtocol::ApprovalDistribution(
+				ValidationProtocols::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
 					protocol_v1::ApprovalDistributionMessage::Approvals(sent_approvals)
-				)
+				))
 			)) => {
 				// Random sampling is reused from the assignment.
 				assert_eq!(sent_peers, assignment_sent_peers);
@@ -1481,15 +1968,15 @@ fn propagates_to_required_after_connect() {
 		);
 
 		for i in omitted.iter().copied() {
-			setup_peer_with_view(overseer, &peers[i].0, view![hash]).await;
+			setup_peer_with_view(overseer, &peers[i].0, view![hash], ValidationVersion::V1).await;
 
 			assert_matches!(
 				overseer_recv(overseer).await,
 				AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
 					sent_peers,
-					protocol_v1::ValidationProtocol::ApprovalDistribution(
+					ValidationProtocols::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
 						protocol_v1::ApprovalDistributionMessage::Assignments(sent_assignments)
-					)
+					))
 				)) => {
 					assert_eq!(sent_peers.len(), 1);
 					assert_eq!(&sent_peers[0], &peers[i].0);
@@ -1501,9 +1988,9 @@ fn propagates_to_required_after_connect() {
 				overseer_recv(overseer).await,
 				AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
 					sent_peers,
-					protocol_v1::ValidationProtocol::ApprovalDistribution(
+					ValidationProtocols::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
 						protocol_v1::ApprovalDistributionMessage::Approvals(sent_approvals)
-					)
+					))
 				)) => {
 					assert_eq!(sent_peers.len(), 1);
 					assert_eq!(&sent_peers[0], &peers[i].0);
@@ -1530,7 +2017,7 @@ fn sends_to_more_peers_after_getting_topology() {
 
 		// Connect all peers except omitted.
 		for (peer, _) in &peers {
-			setup_peer_with_view(overseer, peer, view![hash]).await;
+			setup_peer_with_view(overseer, peer, view![hash], ValidationVersion::V1).await;
 		}
 
 		// new block `hash_a` with 1 candidates
@@ -1560,12 +2047,18 @@ fn sends_to_more_peers_after_getting_topology() {
 
 		overseer_send(
 			overseer,
-			ApprovalDistributionMessage::DistributeAssignment(cert.clone(), candidate_index),
+			ApprovalDistributionMessage::DistributeAssignment(
+				cert.clone().into(),
+				candidate_index.into(),
+			),
 		)
 		.await;
 
-		overseer_send(overseer, ApprovalDistributionMessage::DistributeApproval(approval.clone()))
-			.await;
+		overseer_send(
+			overseer,
+			ApprovalDistributionMessage::DistributeApproval(approval.clone().into()),
+		)
+		.await;
 
 		let assignments = vec![(cert.clone(), candidate_index)];
 		let approvals = vec![approval.clone()];
@@ -1575,9 +2068,9 @@ fn sends_to_more_peers_after_getting_topology() {
 			overseer_recv(overseer).await,
 			AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
 				sent_peers,
-				protocol_v1::ValidationProtocol::ApprovalDistribution(
+				ValidationProtocols::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
 					protocol_v1::ApprovalDistributionMessage::Assignments(sent_assignments)
-				)
+				))
 			)) => {
 				// Only sends to random peers.
 				assert_eq!(sent_peers.len(), 4);
@@ -1597,9 +2090,9 @@ fn sends_to_more_peers_after_getting_topology() {
 			overseer_recv(overseer).await,
 			AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
 				sent_peers,
-				protocol_v1::ValidationProtocol::ApprovalDistribution(
+				ValidationProtocols::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
 					protocol_v1::ApprovalDistributionMessage::Approvals(sent_approvals)
-				)
+				))
 			)) => {
 				// Random sampling is reused from the assignment.
 				assert_eq!(sent_peers, assignment_sent_peers);
@@ -1622,9 +2115,9 @@ fn sends_to_more_peers_after_getting_topology() {
 				overseer_recv(overseer).await,
 				AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
 					sent_peers,
-					protocol_v1::ValidationProtocol::ApprovalDistribution(
+					ValidationProtocols::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
 						protocol_v1::ApprovalDistributionMessage::Assignments(sent_assignments)
-					)
+					))
 				)) => {
 					// Sends to all expected peers.
 					assert_eq!(sent_peers.len(), 1);
@@ -1643,9 +2136,9 @@ fn sends_to_more_peers_after_getting_topology() {
 				overseer_recv(overseer).await,
 				AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
 					sent_peers,
-					protocol_v1::ValidationProtocol::ApprovalDistribution(
+					ValidationProtocols::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
 						protocol_v1::ApprovalDistributionMessage::Approvals(sent_approvals)
-					)
+					))
 				)) => {
 					// Sends to all expected peers.
 					assert_eq!(sent_peers.len(), 1);
@@ -1682,7 +2175,7 @@ fn originator_aggression_l1() {
 
 		// Connect all peers except omitted.
 		for (peer, _) in &peers {
-			setup_peer_with_view(overseer, peer, view![hash]).await;
+			setup_peer_with_view(overseer, peer, view![hash], ValidationVersion::V1).await;
 		}
 
 		// new block `hash_a` with 1 candidates
@@ -1719,12 +2212,18 @@ fn originator_aggression_l1() {
 
 		overseer_send(
 			overseer,
-			ApprovalDistributionMessage::DistributeAssignment(cert.clone(), candidate_index),
+			ApprovalDistributionMessage::DistributeAssignment(
+				cert.clone().into(),
+				candidate_index.into(),
+			),
 		)
 		.await;
 
-		overseer_send(overseer, ApprovalDistributionMessage::DistributeApproval(approval.clone()))
-			.await;
+		overseer_send(
+			overseer,
+			ApprovalDistributionMessage::DistributeApproval(approval.clone().into()),
+		)
+		.await;
 
 		let assignments = vec![(cert.clone(), candidate_index)];
 		let approvals = vec![approval.clone()];
@@ -1733,9 +2232,9 @@ fn originator_aggression_l1() {
 			overseer_recv(overseer).await,
 			AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
 				sent_peers,
-				protocol_v1::ValidationProtocol::ApprovalDistribution(
+				ValidationProtocols::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
 					protocol_v1::ApprovalDistributionMessage::Assignments(_)
-				)
+				))
 			)) => {
 				sent_peers.into_iter()
 					.filter_map(|sp| peers.iter().position(|p| &p.0 == &sp))
@@ -1747,9 +2246,9 @@ fn originator_aggression_l1() {
 			overseer_recv(overseer).await,
 			AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
 				_,
-				protocol_v1::ValidationProtocol::ApprovalDistribution(
+				ValidationProtocols::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
 					protocol_v1::ApprovalDistributionMessage::Approvals(_)
-				)
+				))
 			)) => { }
 		);
 
@@ -1783,9 +2282,9 @@ fn originator_aggression_l1() {
 				overseer_recv(overseer).await,
 				AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
 					sent_peers,
-					protocol_v1::ValidationProtocol::ApprovalDistribution(
+					ValidationProtocols::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
 						protocol_v1::ApprovalDistributionMessage::Assignments(sent_assignments)
-					)
+					))
 				)) => {
 					// Sends to all expected peers.
 					assert_eq!(sent_peers.len(), 1);
@@ -1803,9 +2302,9 @@ fn originator_aggression_l1() {
 				overseer_recv(overseer).await,
 				AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
 					sent_peers,
-					protocol_v1::ValidationProtocol::ApprovalDistribution(
+					ValidationProtocols::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
 						protocol_v1::ApprovalDistributionMessage::Approvals(sent_approvals)
-					)
+					))
 				)) => {
 					// Sends to all expected peers.
 					assert_eq!(sent_peers.len(), 1);
@@ -1840,7 +2339,7 @@ fn non_originator_aggression_l1() {
 
 		// Connect all peers except omitted.
 		for (peer, _) in &peers {
-			setup_peer_with_view(overseer, peer, view![hash]).await;
+			setup_peer_with_view(overseer, peer, view![hash], ValidationVersion::V1).await;
 		}
 
 		// new block `hash_a` with 1 candidates
@@ -1892,9 +2391,9 @@ fn non_originator_aggression_l1() {
 			overseer_recv(overseer).await,
 			AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
 				_,
-				protocol_v1::ValidationProtocol::ApprovalDistribution(
+				ValidationProtocols::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
 					protocol_v1::ApprovalDistributionMessage::Assignments(_)
-				)
+				))
 			)) => { }
 		);
 
@@ -1945,7 +2444,7 @@ fn non_originator_aggression_l2() {
 
 		// Connect all peers except omitted.
 		for (peer, _) in &peers {
-			setup_peer_with_view(overseer, peer, view![hash]).await;
+			setup_peer_with_view(overseer, peer, view![hash], ValidationVersion::V1).await;
 		}
 
 		// new block `hash_a` with 1 candidates
@@ -1997,9 +2496,9 @@ fn non_originator_aggression_l2() {
 			overseer_recv(overseer).await,
 			AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
 				sent_peers,
-				protocol_v1::ValidationProtocol::ApprovalDistribution(
+				ValidationProtocols::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
 					protocol_v1::ApprovalDistributionMessage::Assignments(_)
-				)
+				))
 			)) => {
 				sent_peers.into_iter()
 					.filter_map(|sp| peers.iter().position(|p| &p.0 == &sp))
@@ -2067,9 +2566,9 @@ fn non_originator_aggression_l2() {
 				overseer_recv(overseer).await,
 				AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
 					sent_peers,
-					protocol_v1::ValidationProtocol::ApprovalDistribution(
+					ValidationProtocols::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
 						protocol_v1::ApprovalDistributionMessage::Assignments(sent_assignments)
-					)
+					))
 				)) => {
 					// Sends to all expected peers.
 					assert_eq!(sent_peers.len(), 1);
@@ -2104,7 +2603,7 @@ fn resends_messages_periodically() {
 
 		// Connect all peers.
 		for (peer, _) in &peers {
-			setup_peer_with_view(overseer, peer, view![hash]).await;
+			setup_peer_with_view(overseer, peer, view![hash], ValidationVersion::V1).await;
 		}
 
 		// Set up a gossip topology.
@@ -2158,9 +2657,9 @@ fn resends_messages_periodically() {
 				overseer_recv(overseer).await,
 				AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
 					sent_peers,
-					protocol_v1::ValidationProtocol::ApprovalDistribution(
+					ValidationProtocols::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
 						protocol_v1::ApprovalDistributionMessage::Assignments(sent_assignments)
-					)
+					))
 				)) => {
 					assert_eq!(sent_peers.len(), expected_y.len() + 4);
 					for &i in &expected_y {
@@ -2207,9 +2706,9 @@ fn resends_messages_periodically() {
 					overseer_recv(overseer).await,
 					AllMessages::NetworkBridge(NetworkBridgeMessage::SendValidationMessage(
 						sent_peers,
-						protocol_v1::ValidationProtocol::ApprovalDistribution(
+						ValidationProtocols::V1(protocol_v1::ValidationProtocol::ApprovalDistribution(
 							protocol_v1::ApprovalDistributionMessage::Assignments(sent_assignments)
-						)
+						))
 					)) => {
 						assert_eq!(sent_peers.len(), 1);
 						let expected_pos = expected_y.iter()
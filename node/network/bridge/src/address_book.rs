@@ -0,0 +1,503 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A persistent cache of the addresses resolved by the authority discovery.
+//!
+//! After a restart the authority discovery has to look up all authorities in the DHT again,
+//! which takes a while. The addresses resolved before are kept in the DB, such that connection
+//! requests can be served right away. Cached addresses are only used while the authority
+//! discovery can't resolve an authority, and are dropped once they haven't been resolved for
+//! [`MAX_ADDRESS_AGE`].
+
+use std::{
+	collections::{HashMap, HashSet},
+	fmt,
+	sync::Arc,
+	time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use async_trait::async_trait;
+use futures_timer::Delay;
+use parity_scale_codec::{Decode, Encode};
+use parking_lot::Mutex;
+
+use sc_network::multiaddr::{Multiaddr, Protocol};
+
+use polkadot_node_network_protocol::{authority_discovery::AuthorityDiscovery, PeerId};
+use polkadot_node_subsystem_util::database::{DBTransaction, Database};
+use polkadot_primitives::v2::AuthorityDiscoveryId;
+
+const LOG_TARGET: &str = "parachain::address-book";
+
+const ADDRESS_BOOK_KEY: &[u8; 11] = b"AddressBook";
+
+/// Addresses which haven't been resolved by the authority discovery for this long are dropped.
+const MAX_ADDRESS_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// The interval at which the cached addresses are refreshed and written to the DB.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// The interval at which changed addresses are written to the DB between refreshes.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Configuration of the address book.
+#[derive(Debug, Clone)]
+pub struct AddressBookConfig {
+	/// The column of the DB the addresses are stored in.
+	pub col_data: u32,
+}
+
+/// The addresses of an authority, as last resolved by the authority discovery.
+#[derive(Debug, Clone, PartialEq)]
+struct Entry {
+	addresses: HashSet<Multiaddr>,
+	/// Seconds since the unix epoch.
+	resolved_at: u64,
+}
+
+impl Entry {
+	fn is_stale(&self, now: u64) -> bool {
+		now.saturating_sub(self.resolved_at) > MAX_ADDRESS_AGE.as_secs()
+	}
+}
+
+/// Addresses are stored by their byte representation.
+#[derive(Encode, Decode)]
+struct StoredEntry {
+	authority: AuthorityDiscoveryId,
+	addresses: Vec<Vec<u8>>,
+	resolved_at: u64,
+}
+
+#[derive(Default)]
+struct State {
+	db: Option<(Arc<dyn Database>, AddressBookConfig)>,
+	entries: HashMap<AuthorityDiscoveryId, Entry>,
+	/// Whether addresses changed since the address book was last written.
+	dirty: bool,
+}
+
+impl State {
+	fn load(db: Arc<dyn Database>, config: AddressBookConfig) -> Self {
+		let entries = load_entries(&*db, &config);
+		State { db: Some((db, config)), entries, dirty: false }
+	}
+
+	/// Note the addresses resolved for an authority, marking the address book dirty if they
+	/// changed.
+	fn note_resolved(
+		&mut self,
+		authority: AuthorityDiscoveryId,
+		addresses: HashSet<Multiaddr>,
+		now: u64,
+	) {
+		let entry = Entry { addresses, resolved_at: now };
+		let changed = match self.entries.insert(authority, entry.clone()) {
+			Some(previous) => previous.addresses != entry.addresses,
+			None => true,
+		};
+		self.dirty |= changed;
+	}
+
+	fn cached_addresses(
+		&self,
+		authority: &AuthorityDiscoveryId,
+		now: u64,
+	) -> Option<HashSet<Multiaddr>> {
+		self.entries
+			.get(authority)
+			.filter(|entry| !entry.is_stale(now))
+			.map(|entry| entry.addresses.clone())
+	}
+
+	fn cached_authorities(
+		&self,
+		peer_id: PeerId,
+		now: u64,
+	) -> Option<HashSet<AuthorityDiscoveryId>> {
+		let protocol = Protocol::P2p(peer_id.into());
+		let authorities: HashSet<_> = self
+			.entries
+			.iter()
+			.filter(|(_, entry)| !entry.is_stale(now))
+			.filter(|(_, entry)| entry.addresses.iter().any(|a| a.iter().any(|p| p == protocol)))
+			.map(|(authority, _)| authority.clone())
+			.collect();
+		if authorities.is_empty() {
+			None
+		} else {
+			Some(authorities)
+		}
+	}
+
+	/// Drop the stale entries, returns how many were dropped.
+	fn prune(&mut self, now: u64) -> usize {
+		let before = self.entries.len();
+		self.entries.retain(|_, entry| !entry.is_stale(now));
+		before - self.entries.len()
+	}
+
+	/// The transaction writing the whole address book, clears the dirty flag.
+	///
+	/// The transaction is meant to be written once the lock on the state is released.
+	fn transaction(&mut self) -> Option<(Arc<dyn Database>, DBTransaction)> {
+		let (db, config) = self.db.as_ref()?;
+		self.dirty = false;
+
+		let stored: Vec<_> = self
+			.entries
+			.iter()
+			.map(|(authority, entry)| StoredEntry {
+				authority: authority.clone(),
+				addresses: entry.addresses.iter().map(|a| a.to_vec()).collect(),
+				resolved_at: entry.resolved_at,
+			})
+			.collect();
+
+		let mut tx = DBTransaction::new();
+		tx.put_vec(config.col_data, ADDRESS_BOOK_KEY, stored.encode());
+		Some((db.clone(), tx))
+	}
+
+	/// The transaction writing the address book, if it changed since it was last written.
+	fn dirty_transaction(&mut self) -> Option<(Arc<dyn Database>, DBTransaction)> {
+		if self.dirty {
+			self.transaction()
+		} else {
+			None
+		}
+	}
+}
+
+fn write_transaction(db: Arc<dyn Database>, tx: DBTransaction) {
+	if let Err(err) = db.write(tx) {
+		gum::warn!(target: LOG_TARGET, ?err, "Failed to write address book");
+	}
+}
+
+fn load_entries(
+	db: &dyn Database,
+	config: &AddressBookConfig,
+) -> HashMap<AuthorityDiscoveryId, Entry> {
+	let raw = match db.get(config.col_data, ADDRESS_BOOK_KEY) {
+		Ok(raw) => raw,
+		Err(err) => {
+			gum::warn!(target: LOG_TARGET, ?err, "Failed to load address book");
+			return HashMap::new()
+		},
+	};
+
+	let stored = match raw.map(|raw| Vec::<StoredEntry>::decode(&mut &raw[..])).transpose() {
+		Ok(stored) => stored.unwrap_or_default(),
+		Err(err) => {
+			gum::warn!(target: LOG_TARGET, ?err, "Failed to decode address book");
+			return HashMap::new()
+		},
+	};
+
+	stored
+		.into_iter()
+		.map(|stored| {
+			let addresses = stored
+				.addresses
+				.into_iter()
+				.filter_map(|a| Multiaddr::try_from(a).ok())
+				.collect();
+			(stored.authority, Entry { addresses, resolved_at: stored.resolved_at })
+		})
+		.collect()
+}
+
+fn now() -> u64 {
+	SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+/// An [`AuthorityDiscovery`] falling back to the addresses it resolved before, possibly
+/// before a restart.
+///
+/// Clones share the same address book.
+#[derive(Clone)]
+pub struct AddressBook<AD> {
+	authority_discovery: AD,
+	state: Arc<Mutex<State>>,
+}
+
+impl<AD> AddressBook<AD> {
+	/// Create an address book kept in memory only.
+	pub fn new(authority_discovery: AD) -> Self {
+		AddressBook { authority_discovery, state: Default::default() }
+	}
+
+	/// Create an address book persisted in the given DB, loading the addresses stored before.
+	pub fn with_db(
+		authority_discovery: AD,
+		db: Arc<dyn Database>,
+		config: AddressBookConfig,
+	) -> Self {
+		let state = State::load(db, config);
+		gum::debug!(target: LOG_TARGET, entries = state.entries.len(), "Loaded address book");
+		AddressBook { authority_discovery, state: Arc::new(Mutex::new(state)) }
+	}
+
+	/// The number of authorities with known addresses, including stale ones.
+	pub fn num_entries(&self) -> usize {
+		self.state.lock().entries.len()
+	}
+}
+
+impl<AD: AuthorityDiscovery> AddressBook<AD> {
+	/// Resolve all authorities in the address book again, drop the stale ones and write the
+	/// address book to the DB.
+	pub async fn refresh(&mut self) {
+		let authorities: Vec<_> = self.state.lock().entries.keys().cloned().collect();
+
+		let mut resolved = Vec::with_capacity(authorities.len());
+		for authority in authorities {
+			if let Some(addresses) =
+				self.authority_discovery.get_addresses_by_authority_id(authority.clone()).await
+			{
+				resolved.push((authority, addresses));
+			}
+		}
+
+		let now = now();
+		let num_resolved = resolved.len();
+		let (entries, stale, write) = {
+			let mut state = self.state.lock();
+			for (authority, addresses) in resolved {
+				state.note_resolved(authority, addresses, now);
+			}
+			let stale = state.prune(now);
+			(state.entries.len(), stale, state.transaction())
+		};
+		if let Some((db, tx)) = write {
+			write_transaction(db, tx);
+		}
+
+		gum::debug!(
+			target: LOG_TARGET,
+			entries,
+			resolved = num_resolved,
+			stale,
+			"Refreshed address book",
+		);
+	}
+
+	/// Write the address book to the DB if addresses changed since it was last written.
+	pub fn flush(&self) {
+		let write = self.state.lock().dirty_transaction();
+		if let Some((db, tx)) = write {
+			write_transaction(db, tx);
+		}
+	}
+
+	/// Refresh the address book every ten minutes and write changed addresses in between,
+	/// meant to be spawned as a task.
+	pub async fn run_refresh(mut self) {
+		let mut last_refresh = Instant::now();
+		loop {
+			Delay::new(FLUSH_INTERVAL).await;
+			if last_refresh.elapsed() >= REFRESH_INTERVAL {
+				self.refresh().await;
+				last_refresh = Instant::now();
+			} else {
+				self.flush();
+			}
+		}
+	}
+}
+
+impl<AD> fmt::Debug for AddressBook<AD> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("AddressBook").field("entries", &self.num_entries()).finish()
+	}
+}
+
+#[async_trait]
+impl<AD: AuthorityDiscovery> AuthorityDiscovery for AddressBook<AD> {
+	async fn get_addresses_by_authority_id(
+		&mut self,
+		authority: AuthorityDiscoveryId,
+	) -> Option<HashSet<Multiaddr>> {
+		let resolved =
+			self.authority_discovery.get_addresses_by_authority_id(authority.clone()).await;
+
+		let mut state = self.state.lock();
+		match resolved {
+			Some(addresses) => {
+				// Changes are written by `run_refresh`, so resolving stays cheap.
+				state.note_resolved(authority, addresses.clone(), now());
+				Some(addresses)
+			},
+			None => {
+				let cached = state.cached_addresses(&authority, now());
+				if cached.is_some() {
+					gum::trace!(target: LOG_TARGET, ?authority, "Using cached addresses");
+				}
+				cached
+			},
+		}
+	}
+
+	async fn get_authority_ids_by_peer_id(
+		&mut self,
+		peer_id: PeerId,
+	) -> Option<HashSet<AuthorityDiscoveryId>> {
+		match self.authority_discovery.get_authority_ids_by_peer_id(peer_id).await {
+			Some(authorities) => Some(authorities),
+			None => self.state.lock().cached_authorities(peer_id, now()),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use polkadot_node_subsystem_util::database::kvdb_impl::DbAdapter;
+	use sp_keyring::Sr25519Keyring;
+
+	#[derive(Default, Clone, Debug)]
+	struct TestAuthorityDiscovery {
+		by_authority_id: HashMap<AuthorityDiscoveryId, HashSet<Multiaddr>>,
+	}
+
+	#[async_trait]
+	impl AuthorityDiscovery for TestAuthorityDiscovery {
+		async fn get_addresses_by_authority_id(
+			&mut self,
+			authority: AuthorityDiscoveryId,
+		) -> Option<HashSet<Multiaddr>> {
+			self.by_authority_id.get(&authority).cloned()
+		}
+
+		async fn get_authority_ids_by_peer_id(
+			&mut self,
+			peer_id: PeerId,
+		) -> Option<HashSet<AuthorityDiscoveryId>> {
+			let protocol = Protocol::P2p(peer_id.into());
+			let authorities: HashSet<_> = self
+				.by_authority_id
+				.iter()
+				.filter(|(_, addrs)| addrs.iter().any(|a| a.iter().any(|p| p == protocol)))
+				.map(|(authority, _)| authority.clone())
+				.collect();
+			if authorities.is_empty() {
+				None
+			} else {
+				Some(authorities)
+			}
+		}
+	}
+
+	fn make_db() -> Arc<dyn Database> {
+		Arc::new(DbAdapter::new(kvdb_memorydb::create(1), &[]))
+	}
+
+	fn config() -> AddressBookConfig {
+		AddressBookConfig { col_data: 0 }
+	}
+
+	fn address_of(peer_id: PeerId) -> HashSet<Multiaddr> {
+		let addr: Multiaddr = "/ip4/127.0.0.1/tcp/30333".parse().unwrap();
+		HashSet::from([addr.with(Protocol::P2p(peer_id.into()))])
+	}
+
+	#[test]
+	fn resolved_addresses_survive_restart() {
+		let db = make_db();
+		let authority: AuthorityDiscoveryId = Sr25519Keyring::Alice.public().into();
+		let peer_id = PeerId::random();
+
+		let mut live = TestAuthorityDiscovery::default();
+		live.by_authority_id.insert(authority.clone(), address_of(peer_id));
+
+		let mut book = AddressBook::with_db(live, db.clone(), config());
+		let resolved =
+			futures::executor::block_on(book.get_addresses_by_authority_id(authority.clone()));
+		assert_eq!(resolved, Some(address_of(peer_id)));
+
+		// Resolved addresses are only written on the next flush.
+		assert!(load_entries(&*db, &config()).is_empty());
+		book.flush();
+
+		// After a restart, the DHT doesn't know the authority yet.
+		let mut book = AddressBook::with_db(TestAuthorityDiscovery::default(), db, config());
+		assert_eq!(book.num_entries(), 1);
+		futures::executor::block_on(async {
+			assert_eq!(
+				book.get_addresses_by_authority_id(authority.clone()).await,
+				Some(address_of(peer_id)),
+			);
+			assert_eq!(
+				book.get_authority_ids_by_peer_id(peer_id).await,
+				Some(HashSet::from([authority])),
+			);
+			assert_eq!(book.get_authority_ids_by_peer_id(PeerId::random()).await, None);
+		});
+	}
+
+	#[test]
+	fn stale_addresses_are_dropped() {
+		let db = make_db();
+		let fresh: AuthorityDiscoveryId = Sr25519Keyring::Alice.public().into();
+		let stale: AuthorityDiscoveryId = Sr25519Keyring::Bob.public().into();
+
+		let now = now();
+		let mut state = State::load(db.clone(), config());
+		state.note_resolved(fresh.clone(), address_of(PeerId::random()), now);
+		state.note_resolved(
+			stale.clone(),
+			address_of(PeerId::random()),
+			now - MAX_ADDRESS_AGE.as_secs() - 1,
+		);
+		let (db, tx) = state.transaction().unwrap();
+		write_transaction(db, tx);
+
+		let mut book =
+			AddressBook::with_db(TestAuthorityDiscovery::default(), db.clone(), config());
+		futures::executor::block_on(async {
+			assert!(book.get_addresses_by_authority_id(fresh.clone()).await.is_some());
+			assert!(book.get_addresses_by_authority_id(stale.clone()).await.is_none());
+			book.refresh().await;
+		});
+
+		let entries = load_entries(&*db, &config());
+		assert!(entries.contains_key(&fresh));
+		assert!(!entries.contains_key(&stale));
+	}
+
+	#[test]
+	fn refresh_updates_resolution_time() {
+		let db = make_db();
+		let authority: AuthorityDiscoveryId = Sr25519Keyring::Alice.public().into();
+		let addresses = address_of(PeerId::random());
+
+		let mut state = State::load(db.clone(), config());
+		state.note_resolved(authority.clone(), addresses.clone(), 0);
+		let (db, tx) = state.transaction().unwrap();
+		write_transaction(db, tx);
+
+		let mut live = TestAuthorityDiscovery::default();
+		live.by_authority_id.insert(authority.clone(), addresses.clone());
+		let mut book = AddressBook::with_db(live, db.clone(), config());
+		futures::executor::block_on(book.refresh());
+
+		let entries = load_entries(&*db, &config());
+		let entry = entries.get(&authority).expect("authority was resolved");
+		assert_eq!(entry.addresses, addresses);
+		assert!(!entry.is_stale(now()));
+	}
+}
@@ -26,8 +26,9 @@ use sc_network::Event as NetworkEvent;
 use sp_consensus::SyncOracle;
 
 use polkadot_node_network_protocol::{
-	peer_set::PeerSet, v1 as protocol_v1, ObservedRole, OurView, PeerId,
-	UnifiedReputationChange as Rep, View,
+	peer_set::PeerSet, v2 as protocol_v2, CollationProtocols, ObservedRole, OurView, PeerId,
+	ProtocolVersion, UnifiedReputationChange as Rep, ValidationProtocols,
+	VersionedCollatorProtocolMessage, VersionedProtocol, View,
 };
 use polkadot_node_subsystem_util::metrics::{self, prometheus};
 use polkadot_overseer::gen::{OverseerError, Subsystem};
@@ -55,6 +56,10 @@ use std::{
 
 mod validator_discovery;
 
+/// A persistent cache of the addresses resolved by the authority discovery.
+mod address_book;
+pub use address_book::{AddressBook, AddressBookConfig};
+
 /// Actual interfacing to the network based on the `Network` trait.
 ///
 /// Defines the `Network` trait with an implementation for an `Arc<NetworkService>`.
@@ -148,6 +153,16 @@ impl Metrics {
 		});
 	}
 
+	/// Time spent waiting for subsystems to accept the messages received on the peer-set.
+	fn time_dispatch(&self, peer_set: PeerSet) -> Option<prometheus::prometheus::HistogramTimer> {
+		self.0.as_ref().map(|metrics| {
+			metrics
+				.dispatch_blocked
+				.with_label_values(&[peer_set.get_protocol_name_static()])
+				.start_timer()
+		})
+	}
+
 	fn on_report_event(&self) {
 		if let Some(metrics) = self.0.as_ref() {
 			metrics.report_events.inc()
@@ -168,6 +183,8 @@ struct MetricsInner {
 
 	bytes_received: prometheus::CounterVec<prometheus::U64>,
 	bytes_sent: prometheus::CounterVec<prometheus::U64>,
+
+	dispatch_blocked: prometheus::HistogramVec,
 }
 
 impl metrics::Metrics for Metrics {
@@ -262,6 +279,16 @@ impl metrics::Metrics for Metrics {
 				)?,
 				registry,
 			)?,
+			dispatch_blocked: prometheus::register(
+				prometheus::HistogramVec::new(
+					prometheus::HistogramOpts::new(
+						"polkadot_parachain_notification_dispatch_blocked_seconds",
+						"Time spent waiting for subsystems to accept the messages received on a parachain notification protocol",
+					),
+					&["protocol"]
+				)?,
+				registry,
+			)?,
 		};
 
 		Ok(Metrics(Some(metrics)))
@@ -281,6 +308,33 @@ pub enum WireMessage<M> {
 	ViewUpdate(View),
 }
 
+impl<M> WireMessage<M> {
+	/// Convert the protocol message, leaving view updates as they are.
+	fn map_protocol_message<N>(self, f: impl FnOnce(M) -> N) -> WireMessage<N> {
+		match self {
+			WireMessage::ProtocolMessage(message) => WireMessage::ProtocolMessage(f(message)),
+			WireMessage::ViewUpdate(view) => WireMessage::ViewUpdate(view),
+		}
+	}
+}
+
+impl<M: VersionedProtocol> WireMessage<M> {
+	/// Decode a message sent by a peer speaking the given protocol version of the peer-set.
+	///
+	/// View updates are the same in all versions, only protocol messages differ.
+	fn decode_versioned(
+		version: ProtocolVersion,
+		mut bytes: &[u8],
+	) -> Result<Self, parity_scale_codec::Error> {
+		// The variant indices must match the `codec` indices above.
+		match u8::decode(&mut bytes)? {
+			1 => M::decode_versioned(version, &mut bytes).map(WireMessage::ProtocolMessage),
+			2 => View::decode(&mut bytes).map(WireMessage::ViewUpdate),
+			_ => Err("Invalid wire message variant".into()),
+		}
+	}
+}
+
 /// The network bridge subsystem.
 pub struct NetworkBridge<N, AD> {
 	/// `Network` trait implementing type.
@@ -329,6 +383,8 @@ where
 struct PeerData {
 	/// The Latest view sent by the peer.
 	view: View,
+	/// The protocol version negotiated with the peer.
+	version: ProtocolVersion,
 }
 
 #[derive(Debug)]
@@ -359,6 +415,33 @@ struct SharedInner {
 	local_view: Option<View>,
 	validation_peers: HashMap<PeerId, PeerData>,
 	collation_peers: HashMap<PeerId, PeerData>,
+	approval_peers: HashMap<PeerId, PeerData>,
+}
+
+impl SharedInner {
+	fn peers(&self, peer_set: PeerSet) -> &HashMap<PeerId, PeerData> {
+		match peer_set {
+			PeerSet::Validation => &self.validation_peers,
+			PeerSet::Collation => &self.collation_peers,
+			PeerSet::Approval => &self.approval_peers,
+		}
+	}
+
+	fn peers_mut(&mut self, peer_set: PeerSet) -> &mut HashMap<PeerId, PeerData> {
+		match peer_set {
+			PeerSet::Validation => &mut self.validation_peers,
+			PeerSet::Collation => &mut self.collation_peers,
+			PeerSet::Approval => &mut self.approval_peers,
+		}
+	}
+
+	/// The protocol version negotiated with the peer on the peer-set, or the main version of the
+	/// peer-set if the peer is not connected on it.
+	fn version(&self, peer: &PeerId, peer_set: PeerSet) -> ProtocolVersion {
+		self.peers(peer_set)
+			.get(peer)
+			.map_or(peer_set.get_main_version(), |p| p.version)
+	}
 }
 
 enum Mode {
@@ -476,11 +559,11 @@ where
 							num_messages = 1,
 						);
 
-						send_message(
+						send_validation_protocol_message(
 							&mut network_service,
+							&shared,
 							peers,
-							PeerSet::Validation,
-							WireMessage::ProtocolMessage(msg),
+							msg,
 							&metrics,
 						);
 					}
@@ -492,11 +575,11 @@ where
 						);
 
 						for (peers, msg) in msgs {
-							send_message(
+							send_validation_protocol_message(
 								&mut network_service,
+								&shared,
 								peers,
-								PeerSet::Validation,
-								WireMessage::ProtocolMessage(msg),
+								msg,
 								&metrics,
 							);
 						}
@@ -508,11 +591,12 @@ where
 							num_messages = 1,
 						);
 
-						send_message(
+						send_versioned_message(
 							&mut network_service,
-							peers,
+							&shared,
 							PeerSet::Collation,
-							WireMessage::ProtocolMessage(msg),
+							peers,
+							CollationProtocols::V2(msg),
 							&metrics,
 						);
 					}
@@ -524,11 +608,12 @@ where
 						);
 
 						for (peers, msg) in msgs {
-							send_message(
+							send_versioned_message(
 								&mut network_service,
-								peers,
+								&shared,
 								PeerSet::Collation,
-								WireMessage::ProtocolMessage(msg),
+								peers,
+								CollationProtocols::V2(msg),
 								&metrics,
 							);
 						}
@@ -672,33 +757,47 @@ async fn handle_network_messages<AD: validator_discovery::AuthorityDiscovery>(
 			Some(NetworkEvent::SyncConnected { .. }) |
 			Some(NetworkEvent::SyncDisconnected { .. }) => {},
 			Some(NetworkEvent::NotificationStreamOpened {
-				remote: peer, protocol, role, ..
+				remote: peer,
+				protocol,
+				negotiated_fallback,
+				role,
 			}) => {
 				let role = ObservedRole::from(role);
 				let peer_set = match PeerSet::try_from_protocol_name(&protocol) {
 					None => continue,
 					Some(peer_set) => peer_set,
 				};
+				let version = match peer_set.get_negotiated_version(negotiated_fallback.as_ref()) {
+					None => {
+						gum::debug!(
+							target: LOG_TARGET,
+							peer_set = ?peer_set,
+							peer = ?peer,
+							?negotiated_fallback,
+							"Unknown protocol version negotiated",
+						);
+						continue
+					},
+					Some(version) => version,
+				};
 
 				gum::debug!(
 					target: LOG_TARGET,
 					action = "PeerConnected",
 					peer_set = ?peer_set,
+					version,
 					peer = ?peer,
 					role = ?role
 				);
 
 				let local_view = {
 					let mut shared = shared.0.lock();
-					let peer_map = match peer_set {
-						PeerSet::Validation => &mut shared.validation_peers,
-						PeerSet::Collation => &mut shared.collation_peers,
-					};
+					let peer_map = shared.peers_mut(peer_set);
 
 					match peer_map.entry(peer.clone()) {
 						hash_map::Entry::Occupied(_) => continue,
 						hash_map::Entry::Vacant(vacant) => {
-							vacant.insert(PeerData { view: View::default() });
+							vacant.insert(PeerData { view: View::default(), version });
 						},
 					}
 
@@ -718,6 +817,7 @@ async fn handle_network_messages<AD: validator_discovery::AuthorityDiscovery>(
 								NetworkBridgeEvent::PeerConnected(
 									peer.clone(),
 									role,
+									version,
 									maybe_authority,
 								),
 								NetworkBridgeEvent::PeerViewChange(peer.clone(), View::default()),
@@ -730,7 +830,7 @@ async fn handle_network_messages<AD: validator_discovery::AuthorityDiscovery>(
 							&mut network_service,
 							vec![peer],
 							PeerSet::Validation,
-							WireMessage::<protocol_v1::ValidationProtocol>::ViewUpdate(local_view),
+							WireMessage::<ValidationProtocols>::ViewUpdate(local_view),
 							&metrics,
 						);
 					},
@@ -740,6 +840,7 @@ async fn handle_network_messages<AD: validator_discovery::AuthorityDiscovery>(
 								NetworkBridgeEvent::PeerConnected(
 									peer.clone(),
 									role,
+									version,
 									maybe_authority,
 								),
 								NetworkBridgeEvent::PeerViewChange(peer.clone(), View::default()),
@@ -752,10 +853,13 @@ async fn handle_network_messages<AD: validator_discovery::AuthorityDiscovery>(
 							&mut network_service,
 							vec![peer],
 							PeerSet::Collation,
-							WireMessage::<protocol_v1::CollationProtocol>::ViewUpdate(local_view),
+							WireMessage::<CollationProtocols>::ViewUpdate(local_view),
 							&metrics,
 						);
 					},
+					// Subsystems learn about peers and their views through the validation
+					// peer-set, the approval peer-set only carries messages.
+					PeerSet::Approval => {},
 				}
 			},
 			Some(NetworkEvent::NotificationStreamClosed { remote: peer, protocol }) => {
@@ -773,10 +877,7 @@ async fn handle_network_messages<AD: validator_discovery::AuthorityDiscovery>(
 
 				let was_connected = {
 					let mut shared = shared.0.lock();
-					let peer_map = match peer_set {
-						PeerSet::Validation => &mut shared.validation_peers,
-						PeerSet::Collation => &mut shared.collation_peers,
-					};
+					let peer_map = shared.peers_mut(peer_set);
 
 					let w = peer_map.remove(&peer).is_some();
 
@@ -800,87 +901,125 @@ async fn handle_network_messages<AD: validator_discovery::AuthorityDiscovery>(
 								&mut sender,
 							)
 							.await,
+						PeerSet::Approval => {},
 					}
 				}
 			},
 			Some(NetworkEvent::NotificationsReceived { remote, messages }) => {
+				// Messages are decoded according to the protocol version negotiated with the peer.
+				let (validation_version, collation_version, approval_version) = {
+					let shared = shared.0.lock();
+					(
+						shared.version(&remote, PeerSet::Validation),
+						shared.version(&remote, PeerSet::Collation),
+						shared.version(&remote, PeerSet::Approval),
+					)
+				};
+
 				let v_messages: Result<Vec<_>, _> = messages
 					.iter()
 					.filter(|(protocol, _)| protocol == &PeerSet::Validation.into_protocol_name())
 					.map(|(_, msg_bytes)| {
-						WireMessage::decode(&mut msg_bytes.as_ref()).map(|m| (m, msg_bytes.len()))
+						decode_validation_message(
+							PeerSet::Validation,
+							validation_version,
+							msg_bytes.as_ref(),
+						)
 					})
 					.collect();
 
-				let v_messages = match v_messages {
-					Err(_) => {
-						gum::debug!(target: LOG_TARGET, action = "ReportPeer");
-
-						network_service.report_peer(remote, MALFORMED_MESSAGE_COST);
-						continue
-					},
-					Ok(v) => v,
-				};
+				let a_messages: Result<Vec<_>, _> = messages
+					.iter()
+					.filter(|(protocol, _)| protocol == &PeerSet::Approval.into_protocol_name())
+					.map(|(_, msg_bytes)| {
+						decode_validation_message(
+							PeerSet::Approval,
+							approval_version,
+							msg_bytes.as_ref(),
+						)
+					})
+					.collect();
 
 				let c_messages: Result<Vec<_>, _> = messages
 					.iter()
 					.filter(|(protocol, _)| protocol == &PeerSet::Collation.into_protocol_name())
 					.map(|(_, msg_bytes)| {
-						WireMessage::decode(&mut msg_bytes.as_ref()).map(|m| (m, msg_bytes.len()))
+						WireMessage::<CollationProtocols>::decode_versioned(
+							collation_version,
+							msg_bytes.as_ref(),
+						)
+						.map(|m| (m.map_protocol_message(Into::into), msg_bytes.len()))
 					})
 					.collect();
 
-				match c_messages {
-					Err(_) => {
-						gum::debug!(target: LOG_TARGET, action = "ReportPeer");
+				let (v_messages, a_messages, c_messages) =
+					match (v_messages, a_messages, c_messages) {
+						(Ok(v), Ok(a), Ok(c)) => (v, a, c),
+						_ => {
+							gum::debug!(target: LOG_TARGET, action = "ReportPeer");
 
-						network_service.report_peer(remote, MALFORMED_MESSAGE_COST);
-						continue
-					},
-					Ok(c_messages) =>
-						if v_messages.is_empty() && c_messages.is_empty() {
+							network_service.report_peer(remote, MALFORMED_MESSAGE_COST);
 							continue
-						} else {
-							gum::trace!(
-								target: LOG_TARGET,
-								action = "PeerMessages",
-								peer = ?remote,
-								num_validation_messages = %v_messages.len(),
-								num_collation_messages = %c_messages.len()
-							);
+						},
+					};
 
-							if !v_messages.is_empty() {
-								let (events, reports) = handle_peer_messages(
-									remote.clone(),
-									PeerSet::Validation,
-									&mut shared.0.lock().validation_peers,
-									v_messages,
-									&metrics,
-								);
-
-								for report in reports {
-									network_service.report_peer(remote.clone(), report);
-								}
+				if v_messages.is_empty() && a_messages.is_empty() && c_messages.is_empty() {
+					continue
+				}
 
-								dispatch_validation_events_to_all(events, &mut sender).await;
-							}
+				gum::trace!(
+					target: LOG_TARGET,
+					action = "PeerMessages",
+					peer = ?remote,
+					num_validation_messages = %v_messages.len(),
+					num_approval_messages = %a_messages.len(),
+					num_collation_messages = %c_messages.len()
+				);
 
-							if !c_messages.is_empty() {
-								let (events, reports) = handle_peer_messages(
-									remote.clone(),
-									PeerSet::Collation,
-									&mut shared.0.lock().collation_peers,
-									c_messages,
-									&metrics,
-								);
+				for (peer_set, messages) in
+					[(PeerSet::Validation, v_messages), (PeerSet::Approval, a_messages)]
+				{
+					if messages.is_empty() {
+						continue
+					}
 
-								for report in reports {
-									network_service.report_peer(remote.clone(), report);
-								}
+					let (events, reports) = handle_peer_messages(
+						remote.clone(),
+						peer_set,
+						shared.0.lock().peers_mut(peer_set),
+						messages,
+						&metrics,
+					);
 
-								dispatch_collation_events_to_all(events, &mut sender).await;
-							}
-						},
+					for report in reports {
+						network_service.report_peer(remote.clone(), report);
+					}
+
+					// Subsystems learn about the views of peers through the validation peer-set.
+					let events = events.into_iter().filter(|event| {
+						peer_set == PeerSet::Validation ||
+							matches!(event, NetworkBridgeEvent::PeerMessage(..))
+					});
+
+					let _timer = metrics.time_dispatch(peer_set);
+					dispatch_validation_events_to_all(events, &mut sender).await;
+				}
+
+				if !c_messages.is_empty() {
+					let (events, reports) = handle_peer_messages(
+						remote.clone(),
+						PeerSet::Collation,
+						&mut shared.0.lock().collation_peers,
+						c_messages,
+						&metrics,
+					);
+
+					for report in reports {
+						network_service.report_peer(remote.clone(), report);
+					}
+
+					let _timer = metrics.time_dispatch(PeerSet::Collation);
+					dispatch_collation_events_to_all(events, &mut sender).await;
 				}
 			},
 		}
@@ -1081,7 +1220,7 @@ fn handle_peer_messages<M>(
 fn send_validation_message(
 	net: &mut impl Network,
 	peers: Vec<PeerId>,
-	message: WireMessage<protocol_v1::ValidationProtocol>,
+	message: WireMessage<ValidationProtocols>,
 	metrics: &Metrics,
 ) {
 	send_message(net, peers, PeerSet::Validation, message, metrics);
@@ -1090,28 +1229,109 @@ fn send_validation_message(
 fn send_collation_message(
 	net: &mut impl Network,
 	peers: Vec<PeerId>,
-	message: WireMessage<protocol_v1::CollationProtocol>,
+	message: WireMessage<CollationProtocols>,
 	metrics: &Metrics,
 ) {
 	send_message(net, peers, PeerSet::Collation, message, metrics)
 }
 
 async fn dispatch_validation_event_to_all(
-	event: NetworkBridgeEvent<protocol_v1::ValidationProtocol>,
+	event: NetworkBridgeEvent<protocol_v2::ValidationProtocol>,
 	ctx: &mut impl SubsystemSender,
 ) {
 	dispatch_validation_events_to_all(std::iter::once(event), ctx).await
 }
 
+/// Send a message on a peer-set, converted to the protocol version each peer speaks.
+///
+/// Peers whose version can not express the message are skipped.
+fn send_versioned_message<M: VersionedProtocol>(
+	net: &mut impl Network,
+	shared: &Shared,
+	peer_set: PeerSet,
+	peers: Vec<PeerId>,
+	message: M,
+	metrics: &Metrics,
+) {
+	let mut peers_by_version: HashMap<ProtocolVersion, Vec<PeerId>> = HashMap::new();
+	{
+		let shared = shared.0.lock();
+		for peer in peers {
+			peers_by_version.entry(shared.version(&peer, peer_set)).or_default().push(peer);
+		}
+	}
+
+	for (version, peers) in peers_by_version {
+		match message.clone().into_version(version) {
+			Some(message) =>
+				send_message(net, peers, peer_set, WireMessage::ProtocolMessage(message), metrics),
+			None => gum::debug!(
+				target: LOG_TARGET,
+				?peer_set,
+				version,
+				num_peers = peers.len(),
+				"Message can not be sent in the protocol version of the peers",
+			),
+		}
+	}
+}
+
+/// Send a message of the validation protocol, on the peer-set dedicated to its kind of traffic for
+/// the peers connected on that one, and on the validation peer-set for all others.
+fn send_validation_protocol_message(
+	net: &mut impl Network,
+	shared: &Shared,
+	peers: Vec<PeerId>,
+	message: ValidationProtocols,
+	metrics: &Metrics,
+) {
+	let peer_set = message.peer_set();
+	let (dedicated_peers, peers): (Vec<_>, Vec<_>) = if peer_set == PeerSet::Validation {
+		(Vec::new(), peers)
+	} else {
+		let shared = shared.0.lock();
+		peers.into_iter().partition(|peer| shared.peers(peer_set).contains_key(peer))
+	};
+
+	send_versioned_message(net, shared, peer_set, dedicated_peers, message.clone(), metrics);
+	send_versioned_message(net, shared, PeerSet::Validation, peers, message, metrics);
+}
+
+/// Decode a message received on a peer-set carrying validation protocol messages.
+///
+/// Peer-sets dedicated to a kind of traffic must not be used for anything else.
+fn decode_validation_message(
+	peer_set: PeerSet,
+	version: ProtocolVersion,
+	bytes: &[u8],
+) -> Result<(WireMessage<protocol_v2::ValidationProtocol>, usize), parity_scale_codec::Error> {
+	match WireMessage::<ValidationProtocols>::decode_versioned(version, bytes)? {
+		WireMessage::ProtocolMessage(message)
+			if peer_set != PeerSet::Validation && message.peer_set() != peer_set =>
+			Err("Message not allowed on the peer-set".into()),
+		message => Ok((message.map_protocol_message(route_validation_message), bytes.len())),
+	}
+}
+
+/// Route a message on the validation peer-set to the message type the subsystems handle.
+///
+/// Messages of the first version are upgraded, such that subsystems only handle the latest one.
+fn route_validation_message(message: ValidationProtocols) -> protocol_v2::ValidationProtocol {
+	match message {
+		ValidationProtocols::V1(message) => message.into(),
+		ValidationProtocols::V2(message) => message,
+	}
+}
+
 async fn dispatch_collation_event_to_all(
-	event: NetworkBridgeEvent<protocol_v1::CollationProtocol>,
+	event: NetworkBridgeEvent<VersionedCollatorProtocolMessage>,
 	ctx: &mut impl SubsystemSender,
 ) {
 	dispatch_collation_events_to_all(std::iter::once(event), ctx).await
 }
 
 fn dispatch_validation_event_to_all_unbounded(
-	event: NetworkBridgeEvent<protocol_v1::ValidationProtocol>,
+	event: NetworkBridgeEvent<protocol_v2::ValidationProtocol>,
 	ctx: &mut impl SubsystemSender,
 ) {
 	for msg in AllMessages::dispatch_iter(event) {
@@ -1120,17 +1340,15 @@ fn dispatch_validation_event_to_all_unbounded(
 }
 
 fn dispatch_collation_event_to_all_unbounded(
-	event: NetworkBridgeEvent<protocol_v1::CollationProtocol>,
+	event: NetworkBridgeEvent<VersionedCollatorProtocolMessage>,
 	ctx: &mut impl SubsystemSender,
 ) {
-	if let Some(msg) = event.focus().ok().map(CollatorProtocolMessage::NetworkBridgeUpdateV1) {
-		ctx.send_unbounded_message(msg.into());
-	}
+	ctx.send_unbounded_message(CollatorProtocolMessage::NetworkBridgeUpdate(event).into());
 }
 
 async fn dispatch_validation_events_to_all<I>(events: I, ctx: &mut impl SubsystemSender)
 where
-	I: IntoIterator<Item = NetworkBridgeEvent<protocol_v1::ValidationProtocol>>,
+	I: IntoIterator<Item = NetworkBridgeEvent<protocol_v2::ValidationProtocol>>,
 	I::IntoIter: Send,
 {
 	ctx.send_messages(events.into_iter().flat_map(AllMessages::dispatch_iter)).await
@@ -1138,14 +1356,12 @@ where
 
 async fn dispatch_collation_events_to_all<I>(events: I, ctx: &mut impl SubsystemSender)
 where
-	I: IntoIterator<Item = NetworkBridgeEvent<protocol_v1::CollationProtocol>>,
+	I: IntoIterator<Item = NetworkBridgeEvent<VersionedCollatorProtocolMessage>>,
 	I::IntoIter: Send,
 {
-	let messages_for = |event: NetworkBridgeEvent<protocol_v1::CollationProtocol>| {
-		event.focus().ok().map(|m| {
-			AllMessages::CollatorProtocol(CollatorProtocolMessage::NetworkBridgeUpdateV1(m))
-		})
+	let messages_for = |event: NetworkBridgeEvent<VersionedCollatorProtocolMessage>| {
+		AllMessages::CollatorProtocol(CollatorProtocolMessage::NetworkBridgeUpdate(event))
 	};
 
-	ctx.send_messages(events.into_iter().flat_map(messages_for)).await
+	ctx.send_messages(events.into_iter().map(messages_for)).await
 }
@@ -28,13 +28,17 @@ use std::{
 
 use sc_network::{Event as NetworkEvent, IfDisconnected};
 
-use polkadot_node_network_protocol::{request_response::outgoing::Requests, view, ObservedRole};
+use polkadot_node_network_protocol::{
+	peer_set::{CollationVersion, ValidationVersion},
+	request_response::outgoing::Requests,
+	v1 as protocol_v1, v2 as protocol_v2, view, ObservedRole,
+};
 use polkadot_node_subsystem_test_helpers::{
 	SingleItemSink, SingleItemStream, TestSubsystemContextHandle,
 };
 use polkadot_node_subsystem_util::metered;
-use polkadot_primitives::v2::AuthorityDiscoveryId;
-use polkadot_primitives_test_helpers::dummy_collator_signature;
+use polkadot_primitives::v2::{AuthorityDiscoveryId, CandidateHash};
+use polkadot_primitives_test_helpers::{dummy_collator_signature, dummy_validator_signature};
 use polkadot_subsystem::{
 	jaeger,
 	messages::{
@@ -173,10 +177,20 @@ impl TestNetworkHandle {
 	}
 
 	async fn connect_peer(&mut self, peer: PeerId, peer_set: PeerSet, role: ObservedRole) {
+		self.connect_peer_with_fallback(peer, peer_set, role, None).await;
+	}
+
+	async fn connect_peer_with_fallback(
+		&mut self,
+		peer: PeerId,
+		peer_set: PeerSet,
+		role: ObservedRole,
+		negotiated_fallback: Option<&'static str>,
+	) {
 		self.send_network_event(NetworkEvent::NotificationStreamOpened {
 			remote: peer,
 			protocol: peer_set.into_protocol_name(),
-			negotiated_fallback: None,
+			negotiated_fallback: negotiated_fallback.map(Cow::Borrowed),
 			role: role.into(),
 		})
 		.await;
@@ -308,7 +322,7 @@ fn test_harness<T: Future<Output = VirtualOverseer>>(
 }
 
 async fn assert_sends_validation_event_to_all(
-	event: NetworkBridgeEvent<protocol_v1::ValidationProtocol>,
+	event: NetworkBridgeEvent<protocol_v2::ValidationProtocol>,
 	virtual_overseer: &mut TestSubsystemContextHandle<NetworkBridgeMessage>,
 ) {
 	// Ordering must match the enum variant order
@@ -330,7 +344,7 @@ async fn assert_sends_validation_event_to_all(
 	assert_matches!(
 		virtual_overseer.recv().await,
 		AllMessages::ApprovalDistribution(
-			ApprovalDistributionMessage::NetworkBridgeUpdateV1(e)
+			ApprovalDistributionMessage::NetworkBridgeUpdate(e)
 		) if e == event.focus().expect("could not focus message")
 	);
 
@@ -343,14 +357,14 @@ async fn assert_sends_validation_event_to_all(
 }
 
 async fn assert_sends_collation_event_to_all(
-	event: NetworkBridgeEvent<protocol_v1::CollationProtocol>,
+	event: NetworkBridgeEvent<VersionedCollatorProtocolMessage>,
 	virtual_overseer: &mut TestSubsystemContextHandle<NetworkBridgeMessage>,
 ) {
 	assert_matches!(
 		virtual_overseer.recv().await,
 		AllMessages::CollatorProtocol(
-			CollatorProtocolMessage::NetworkBridgeUpdateV1(e)
-		) if e == event.focus().expect("could not focus message")
+			CollatorProtocolMessage::NetworkBridgeUpdate(e)
+		) if e == event
 	)
 }
 
@@ -642,7 +656,12 @@ fn peer_view_updates_sent_via_overseer() {
 		// bridge will inform about all connected peers.
 		{
 			assert_sends_validation_event_to_all(
-				NetworkBridgeEvent::PeerConnected(peer.clone(), ObservedRole::Full, None),
+				NetworkBridgeEvent::PeerConnected(
+					peer.clone(),
+					ObservedRole::Full,
+					ValidationVersion::V2.into(),
+					None,
+				),
 				&mut virtual_overseer,
 			)
 			.await;
@@ -685,7 +704,12 @@ fn peer_messages_sent_via_overseer() {
 		// bridge will inform about all connected peers.
 		{
 			assert_sends_validation_event_to_all(
-				NetworkBridgeEvent::PeerConnected(peer.clone(), ObservedRole::Full, None),
+				NetworkBridgeEvent::PeerConnected(
+					peer.clone(),
+					ObservedRole::Full,
+					ValidationVersion::V2.into(),
+					None,
+				),
 				&mut virtual_overseer,
 			)
 			.await;
@@ -698,9 +722,9 @@ fn peer_messages_sent_via_overseer() {
 		}
 
 		let approval_distribution_message =
-			protocol_v1::ApprovalDistributionMessage::Approvals(Vec::new());
+			protocol_v2::ApprovalDistributionMessage::Approvals(Vec::new());
 
-		let message = protocol_v1::ValidationProtocol::ApprovalDistribution(
+		let message = protocol_v2::ValidationProtocol::ApprovalDistribution(
 			approval_distribution_message.clone(),
 		);
 
@@ -720,7 +744,7 @@ fn peer_messages_sent_via_overseer() {
 		assert_matches!(
 			virtual_overseer.recv().await,
 			AllMessages::ApprovalDistribution(
-				ApprovalDistributionMessage::NetworkBridgeUpdateV1(
+				ApprovalDistributionMessage::NetworkBridgeUpdate(
 					NetworkBridgeEvent::PeerMessage(p, m)
 				)
 			) => {
@@ -738,6 +762,150 @@ fn peer_messages_sent_via_overseer() {
 	});
 }
 
+#[test]
+fn approval_traffic_uses_approval_peer_set() {
+	test_harness(done_syncing_oracle(), |test_harness| async move {
+		let TestHarness { mut network_handle, mut virtual_overseer } = test_harness;
+
+		let peer = PeerId::random();
+		let validation_only_peer = PeerId::random();
+
+		network_handle
+			.connect_peer(peer.clone(), PeerSet::Validation, ObservedRole::Authority)
+			.await;
+		network_handle
+			.connect_peer(
+				validation_only_peer.clone(),
+				PeerSet::Validation,
+				ObservedRole::Authority,
+			)
+			.await;
+		network_handle
+			.connect_peer(peer.clone(), PeerSet::Approval, ObservedRole::Authority)
+			.await;
+
+		// subsystems only learn about connections on the validation peer-set.
+		for p in [&peer, &validation_only_peer] {
+			assert_sends_validation_event_to_all(
+				NetworkBridgeEvent::PeerConnected(
+					p.clone(),
+					ObservedRole::Authority,
+					ValidationVersion::V2.into(),
+					None,
+				),
+				&mut virtual_overseer,
+			)
+			.await;
+
+			assert_sends_validation_event_to_all(
+				NetworkBridgeEvent::PeerViewChange(p.clone(), View::default()),
+				&mut virtual_overseer,
+			)
+			.await;
+		}
+
+		// consume our view updates, which are only sent on the validation peer-set.
+		let _ = network_handle.next_network_actions(2).await;
+
+		let approval_message = protocol_v2::ValidationProtocol::ApprovalDistribution(
+			protocol_v2::ApprovalDistributionMessage::Approvals(Vec::new()),
+		);
+
+		virtual_overseer
+			.send(FromOverseer::Communication {
+				msg: NetworkBridgeMessage::SendValidationMessage(
+					vec![peer.clone(), validation_only_peer.clone()],
+					ValidationProtocols::V2(approval_message.clone()),
+				),
+			})
+			.await;
+
+		// peers not connected on the approval peer-set get approvals on the validation one.
+		let actions = network_handle.next_network_actions(2).await;
+		assert_network_actions_contains(
+			&actions,
+			&NetworkAction::WriteNotification(
+				peer.clone(),
+				PeerSet::Approval,
+				WireMessage::ProtocolMessage(approval_message.clone()).encode(),
+			),
+		);
+		assert_network_actions_contains(
+			&actions,
+			&NetworkAction::WriteNotification(
+				validation_only_peer,
+				PeerSet::Validation,
+				WireMessage::ProtocolMessage(approval_message.clone()).encode(),
+			),
+		);
+
+		// other validation traffic stays on the validation peer-set.
+		let statement_message = protocol_v2::ValidationProtocol::StatementDistribution(
+			protocol_v2::StatementDistributionMessage::LargeStatement(
+				protocol_v1::StatementMetadata {
+					relay_parent: Hash::repeat_byte(1),
+					candidate_hash: CandidateHash(Hash::repeat_byte(2)),
+					signed_by: ValidatorIndex(0),
+					signature: dummy_validator_signature(),
+				},
+			),
+		);
+
+		virtual_overseer
+			.send(FromOverseer::Communication {
+				msg: NetworkBridgeMessage::SendValidationMessage(
+					vec![peer.clone()],
+					ValidationProtocols::V2(statement_message.clone()),
+				),
+			})
+			.await;
+
+		assert_eq!(
+			network_handle.next_network_action().await,
+			NetworkAction::WriteNotification(
+				peer.clone(),
+				PeerSet::Validation,
+				WireMessage::ProtocolMessage(statement_message.clone()).encode(),
+			)
+		);
+
+		// approvals received on the approval peer-set reach approval distribution.
+		network_handle
+			.peer_message(
+				peer.clone(),
+				PeerSet::Approval,
+				WireMessage::ProtocolMessage(approval_message).encode(),
+			)
+			.await;
+
+		assert_matches!(
+			virtual_overseer.recv().await,
+			AllMessages::ApprovalDistribution(
+				ApprovalDistributionMessage::NetworkBridgeUpdate(
+					NetworkBridgeEvent::PeerMessage(p, _)
+				)
+			) => {
+				assert_eq!(p, peer);
+			}
+		);
+
+		// but nothing else is accepted on it.
+		network_handle
+			.peer_message(
+				peer.clone(),
+				PeerSet::Approval,
+				WireMessage::ProtocolMessage(statement_message).encode(),
+			)
+			.await;
+
+		assert_eq!(
+			network_handle.next_network_action().await,
+			NetworkAction::ReputationChange(peer, MALFORMED_MESSAGE_COST),
+		);
+		virtual_overseer
+	});
+}
+
 #[test]
 fn peer_disconnect_from_just_one_peerset() {
 	test_harness(done_syncing_oracle(), |test_harness| async move {
@@ -755,7 +923,12 @@ fn peer_disconnect_from_just_one_peerset() {
 		// bridge will inform about all connected peers.
 		{
 			assert_sends_validation_event_to_all(
-				NetworkBridgeEvent::PeerConnected(peer.clone(), ObservedRole::Full, None),
+				NetworkBridgeEvent::PeerConnected(
+					peer.clone(),
+					ObservedRole::Full,
+					ValidationVersion::V2.into(),
+					None,
+				),
 				&mut virtual_overseer,
 			)
 			.await;
@@ -769,7 +942,12 @@ fn peer_disconnect_from_just_one_peerset() {
 
 		{
 			assert_sends_collation_event_to_all(
-				NetworkBridgeEvent::PeerConnected(peer.clone(), ObservedRole::Full, None),
+				NetworkBridgeEvent::PeerConnected(
+					peer.clone(),
+					ObservedRole::Full,
+					CollationVersion::V2.into(),
+					None,
+				),
 				&mut virtual_overseer,
 			)
 			.await;
@@ -838,7 +1016,12 @@ fn relays_collation_protocol_messages() {
 		// bridge will inform about all connected peers.
 		{
 			assert_sends_validation_event_to_all(
-				NetworkBridgeEvent::PeerConnected(peer_a.clone(), ObservedRole::Full, None),
+				NetworkBridgeEvent::PeerConnected(
+					peer_a.clone(),
+					ObservedRole::Full,
+					ValidationVersion::V2.into(),
+					None,
+				),
 				&mut virtual_overseer,
 			)
 			.await;
@@ -852,7 +1035,12 @@ fn relays_collation_protocol_messages() {
 
 		{
 			assert_sends_collation_event_to_all(
-				NetworkBridgeEvent::PeerConnected(peer_b.clone(), ObservedRole::Full, None),
+				NetworkBridgeEvent::PeerConnected(
+					peer_b.clone(),
+					ObservedRole::Full,
+					CollationVersion::V2.into(),
+					None,
+				),
 				&mut virtual_overseer,
 			)
 			.await;
@@ -866,14 +1054,14 @@ fn relays_collation_protocol_messages() {
 
 		// peer A gets reported for sending a collation message.
 
-		let collator_protocol_message = protocol_v1::CollatorProtocolMessage::Declare(
+		let collator_protocol_message = protocol_v2::CollatorProtocolMessage::Declare(
 			Sr25519Keyring::Alice.public().into(),
 			Default::default(),
 			sp_core::crypto::UncheckedFrom::unchecked_from([1u8; 64]),
 		);
 
 		let message =
-			protocol_v1::CollationProtocol::CollatorProtocol(collator_protocol_message.clone());
+			protocol_v2::CollationProtocol::CollatorProtocol(collator_protocol_message.clone());
 
 		network_handle
 			.peer_message(
@@ -902,18 +1090,336 @@ fn relays_collation_protocol_messages() {
 		assert_matches!(
 			virtual_overseer.recv().await,
 			AllMessages::CollatorProtocol(
-				CollatorProtocolMessage::NetworkBridgeUpdateV1(
+				CollatorProtocolMessage::NetworkBridgeUpdate(
 					NetworkBridgeEvent::PeerMessage(p, m)
 				)
 			) => {
 				assert_eq!(p, peer_b);
-				assert_eq!(m, collator_protocol_message);
+				assert_eq!(m, VersionedCollatorProtocolMessage::V2(collator_protocol_message));
 			}
 		);
 		virtual_overseer
 	});
 }
 
+#[test]
+fn collation_messages_follow_negotiated_version() {
+	test_harness(done_syncing_oracle(), |test_harness| async move {
+		let TestHarness { mut network_handle, mut virtual_overseer } = test_harness;
+
+		let peer = PeerId::random();
+
+		network_handle
+			.connect_peer_with_fallback(
+				peer.clone(),
+				PeerSet::Collation,
+				ObservedRole::Full,
+				Some("/polkadot/collation/1"),
+			)
+			.await;
+
+		assert_sends_collation_event_to_all(
+			NetworkBridgeEvent::PeerConnected(
+				peer.clone(),
+				ObservedRole::Full,
+				CollationVersion::V1.into(),
+				None,
+			),
+			&mut virtual_overseer,
+		)
+		.await;
+
+		assert_sends_collation_event_to_all(
+			NetworkBridgeEvent::PeerViewChange(peer.clone(), View::default()),
+			&mut virtual_overseer,
+		)
+		.await;
+
+		// consume our view update.
+		let _ = network_handle.next_network_action().await;
+
+		let relay_parent = Hash::repeat_byte(1);
+
+		// v2 messages are downgraded for a peer which negotiated v1.
+		virtual_overseer
+			.send(FromOverseer::Communication {
+				msg: NetworkBridgeMessage::SendCollationMessage(
+					vec![peer.clone()],
+					protocol_v2::CollationProtocol::CollatorProtocol(
+						protocol_v2::CollatorProtocolMessage::AdvertiseCollation {
+							relay_parent,
+							candidate_hash: CandidateHash(Hash::repeat_byte(2)),
+							parent_head_data_hash: Hash::repeat_byte(3),
+						},
+					),
+				),
+			})
+			.await;
+
+		let v1_message = protocol_v1::CollationProtocol::CollatorProtocol(
+			protocol_v1::CollatorProtocolMessage::AdvertiseCollation(relay_parent),
+		);
+
+		assert_eq!(
+			network_handle.next_network_action().await,
+			NetworkAction::WriteNotification(
+				peer.clone(),
+				PeerSet::Collation,
+				WireMessage::ProtocolMessage(v1_message.clone()).encode(),
+			)
+		);
+
+		// and messages received from it are decoded as v1.
+		network_handle
+			.peer_message(
+				peer.clone(),
+				PeerSet::Collation,
+				WireMessage::ProtocolMessage(v1_message).encode(),
+			)
+			.await;
+
+		assert_matches!(
+			virtual_overseer.recv().await,
+			AllMessages::CollatorProtocol(
+				CollatorProtocolMessage::NetworkBridgeUpdate(
+					NetworkBridgeEvent::PeerMessage(p, m)
+				)
+			) => {
+				assert_eq!(p, peer);
+				assert_eq!(
+					m,
+					VersionedCollatorProtocolMessage::V1(
+						protocol_v1::CollatorProtocolMessage::AdvertiseCollation(relay_parent),
+					),
+				);
+			}
+		);
+		virtual_overseer
+	});
+}
+
+#[test]
+fn validation_messages_follow_negotiated_version() {
+	test_harness(done_syncing_oracle(), |test_harness| async move {
+		let TestHarness { mut network_handle, mut virtual_overseer } = test_harness;
+
+		let peer = PeerId::random();
+
+		network_handle
+			.connect_peer_with_fallback(
+				peer.clone(),
+				PeerSet::Validation,
+				ObservedRole::Full,
+				Some("/polkadot/validation/1"),
+			)
+			.await;
+
+		assert_sends_validation_event_to_all(
+			NetworkBridgeEvent::PeerConnected(
+				peer.clone(),
+				ObservedRole::Full,
+				ValidationVersion::V1.into(),
+				None,
+			),
+			&mut virtual_overseer,
+		)
+		.await;
+
+		assert_sends_validation_event_to_all(
+			NetworkBridgeEvent::PeerViewChange(peer.clone(), View::default()),
+			&mut virtual_overseer,
+		)
+		.await;
+
+		// consume our view update.
+		let _ = network_handle.next_network_action().await;
+
+		// v2 messages which can be expressed in v1 are downgraded for a peer which negotiated v1.
+		virtual_overseer
+			.send(FromOverseer::Communication {
+				msg: NetworkBridgeMessage::SendValidationMessage(
+					vec![peer.clone()],
+					ValidationProtocols::V2(protocol_v2::ValidationProtocol::ApprovalDistribution(
+						protocol_v2::ApprovalDistributionMessage::Approvals(Vec::new()),
+					)),
+				),
+			})
+			.await;
+
+		let v1_message = protocol_v1::ValidationProtocol::ApprovalDistribution(
+			protocol_v1::ApprovalDistributionMessage::Approvals(Vec::new()),
+		);
+
+		assert_eq!(
+			network_handle.next_network_action().await,
+			NetworkAction::WriteNotification(
+				peer.clone(),
+				PeerSet::Validation,
+				WireMessage::ProtocolMessage(v1_message.clone()).encode(),
+			)
+		);
+
+		// and messages received from it are decoded as v1 and upgraded for the subsystems.
+		network_handle
+			.peer_message(
+				peer.clone(),
+				PeerSet::Validation,
+				WireMessage::ProtocolMessage(v1_message).encode(),
+			)
+			.await;
+
+		assert_matches!(
+			virtual_overseer.recv().await,
+			AllMessages::ApprovalDistribution(
+				ApprovalDistributionMessage::NetworkBridgeUpdate(
+					NetworkBridgeEvent::PeerMessage(p, m)
+				)
+			) => {
+				assert_eq!(p, peer);
+				assert_eq!(m, protocol_v2::ApprovalDistributionMessage::Approvals(Vec::new()));
+			}
+		);
+		virtual_overseer
+	});
+}
+
+/// A message of a protocol with two versions, the second one adding a field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum DummyProtocols {
+	V1(u8),
+	V2(u8, u8),
+}
+
+impl Encode for DummyProtocols {
+	fn encode_to<T: parity_scale_codec::Output + ?Sized>(&self, dest: &mut T) {
+		match self {
+			DummyProtocols::V1(a) => a.encode_to(dest),
+			DummyProtocols::V2(a, b) => (a, b).encode_to(dest),
+		}
+	}
+}
+
+impl VersionedProtocol for DummyProtocols {
+	fn decode_versioned(
+		version: ProtocolVersion,
+		input: &mut &[u8],
+	) -> Result<Self, parity_scale_codec::Error> {
+		match version {
+			1 => u8::decode(input).map(DummyProtocols::V1),
+			2 => <(u8, u8)>::decode(input).map(|(a, b)| DummyProtocols::V2(a, b)),
+			_ => Err("Unsupported version".into()),
+		}
+	}
+
+	fn version(&self) -> ProtocolVersion {
+		match self {
+			DummyProtocols::V1(_) => 1,
+			DummyProtocols::V2(..) => 2,
+		}
+	}
+
+	fn into_version(self, version: ProtocolVersion) -> Option<Self> {
+		match (self, version) {
+			(DummyProtocols::V2(a, _), 1) => Some(DummyProtocols::V1(a)),
+			(message, version) if message.version() == version => Some(message),
+			_ => None,
+		}
+	}
+}
+
+#[test]
+fn versioned_messages_are_decoded_by_negotiated_version() {
+	let v1_bytes = WireMessage::ProtocolMessage(DummyProtocols::V1(1)).encode();
+	let v2_bytes = WireMessage::ProtocolMessage(DummyProtocols::V2(1, 2)).encode();
+
+	assert_matches!(
+		WireMessage::<DummyProtocols>::decode_versioned(1, &v1_bytes),
+		Ok(WireMessage::ProtocolMessage(DummyProtocols::V1(1)))
+	);
+	assert_matches!(
+		WireMessage::<DummyProtocols>::decode_versioned(2, &v2_bytes),
+		Ok(WireMessage::ProtocolMessage(DummyProtocols::V2(1, 2)))
+	);
+	// A v1 message is too short for a peer speaking v2.
+	assert!(WireMessage::<DummyProtocols>::decode_versioned(2, &v1_bytes).is_err());
+	assert!(WireMessage::<DummyProtocols>::decode_versioned(3, &v1_bytes).is_err());
+
+	// View updates are the same in all versions.
+	let view = view![Hash::repeat_byte(1)];
+	let view_bytes = WireMessage::<DummyProtocols>::ViewUpdate(view.clone()).encode();
+	for version in [1, 2] {
+		assert_matches!(
+			WireMessage::<DummyProtocols>::decode_versioned(version, &view_bytes),
+			Ok(WireMessage::ViewUpdate(v)) if v == view
+		);
+	}
+}
+
+#[test]
+fn versioned_messages_are_sent_in_negotiated_version() {
+	let (mut network, mut network_handle, _) = new_test_network();
+	let shared = Shared::default();
+
+	let peer_v1 = PeerId::random();
+	let peer_v2 = PeerId::random();
+	{
+		let mut shared = shared.0.lock();
+		shared
+			.validation_peers
+			.insert(peer_v1.clone(), PeerData { view: View::default(), version: 1 });
+		shared
+			.validation_peers
+			.insert(peer_v2.clone(), PeerData { view: View::default(), version: 2 });
+	}
+
+	send_versioned_message(
+		&mut network,
+		&shared,
+		PeerSet::Validation,
+		vec![peer_v1.clone(), peer_v2.clone()],
+		DummyProtocols::V2(1, 2),
+		&Metrics(None),
+	);
+
+	let actions = executor::block_on(network_handle.next_network_actions(2));
+	assert_network_actions_contains(
+		&actions,
+		&NetworkAction::WriteNotification(
+			peer_v1.clone(),
+			PeerSet::Validation,
+			WireMessage::ProtocolMessage(DummyProtocols::V1(1)).encode(),
+		),
+	);
+	assert_network_actions_contains(
+		&actions,
+		&NetworkAction::WriteNotification(
+			peer_v2.clone(),
+			PeerSet::Validation,
+			WireMessage::ProtocolMessage(DummyProtocols::V2(1, 2)).encode(),
+		),
+	);
+
+	// A v1 message can not be upgraded, so it is only sent to the v1 peer.
+	send_versioned_message(
+		&mut network,
+		&shared,
+		PeerSet::Validation,
+		vec![peer_v1.clone(), peer_v2],
+		DummyProtocols::V1(3),
+		&Metrics(None),
+	);
+
+	assert_eq!(
+		executor::block_on(network_handle.next_network_action()),
+		NetworkAction::WriteNotification(
+			peer_v1,
+			PeerSet::Validation,
+			WireMessage::ProtocolMessage(DummyProtocols::V1(3)).encode(),
+		),
+	);
+	assert!(network_handle.action_rx.try_next().is_err());
+}
+
 #[test]
 fn different_views_on_different_peer_sets() {
 	test_harness(done_syncing_oracle(), |test_harness| async move {
@@ -931,7 +1437,12 @@ fn different_views_on_different_peer_sets() {
 		// bridge will inform about all connected peers.
 		{
 			assert_sends_validation_event_to_all(
-				NetworkBridgeEvent::PeerConnected(peer.clone(), ObservedRole::Full, None),
+				NetworkBridgeEvent::PeerConnected(
+					peer.clone(),
+					ObservedRole::Full,
+					ValidationVersion::V2.into(),
+					None,
+				),
 				&mut virtual_overseer,
 			)
 			.await;
@@ -945,7 +1456,12 @@ fn different_views_on_different_peer_sets() {
 
 		{
 			assert_sends_collation_event_to_all(
-				NetworkBridgeEvent::PeerConnected(peer.clone(), ObservedRole::Full, None),
+				NetworkBridgeEvent::PeerConnected(
+					peer.clone(),
+					ObservedRole::Full,
+					CollationVersion::V2.into(),
+					None,
+				),
 				&mut virtual_overseer,
 			)
 			.await;
@@ -1094,7 +1610,12 @@ fn send_messages_to_peers() {
 		// bridge will inform about all connected peers.
 		{
 			assert_sends_validation_event_to_all(
-				NetworkBridgeEvent::PeerConnected(peer.clone(), ObservedRole::Full, None),
+				NetworkBridgeEvent::PeerConnected(
+					peer.clone(),
+					ObservedRole::Full,
+					ValidationVersion::V2.into(),
+					None,
+				),
 				&mut virtual_overseer,
 			)
 			.await;
@@ -1108,7 +1629,12 @@ fn send_messages_to_peers() {
 
 		{
 			assert_sends_collation_event_to_all(
-				NetworkBridgeEvent::PeerConnected(peer.clone(), ObservedRole::Full, None),
+				NetworkBridgeEvent::PeerConnected(
+					peer.clone(),
+					ObservedRole::Full,
+					CollationVersion::V2.into(),
+					None,
+				),
 				&mut virtual_overseer,
 			)
 			.await;
@@ -1139,17 +1665,19 @@ fn send_messages_to_peers() {
 				.send(FromOverseer::Communication {
 					msg: NetworkBridgeMessage::SendValidationMessage(
 						vec![peer.clone()],
-						message.clone(),
+						ValidationProtocols::V1(message.clone()),
 					),
 				})
 				.await;
 
+			// The message is upgraded for the peer, which speaks the second version.
 			assert_eq!(
 				network_handle.next_network_action().await,
 				NetworkAction::WriteNotification(
 					peer.clone(),
 					PeerSet::Validation,
-					WireMessage::ProtocolMessage(message).encode(),
+					WireMessage::ProtocolMessage(protocol_v2::ValidationProtocol::from(message))
+						.encode(),
 				)
 			);
 		}
@@ -1157,14 +1685,14 @@ fn send_messages_to_peers() {
 		// send a collation protocol message.
 
 		{
-			let collator_protocol_message = protocol_v1::CollatorProtocolMessage::Declare(
+			let collator_protocol_message = protocol_v2::CollatorProtocolMessage::Declare(
 				Sr25519Keyring::Alice.public().into(),
 				0_u32.into(),
 				dummy_collator_signature(),
 			);
 
 			let message =
-				protocol_v1::CollationProtocol::CollatorProtocol(collator_protocol_message.clone());
+				protocol_v2::CollationProtocol::CollatorProtocol(collator_protocol_message.clone());
 
 			virtual_overseer
 				.send(FromOverseer::Communication {
@@ -51,11 +51,27 @@ impl<N: Network, AD: AuthorityDiscovery> Service<N, AD> {
 	}
 
 	/// Connect to already resolved addresses.
+	///
+	/// The peer-sets dedicated to part of the traffic of `peer_set` connect to the same addresses.
 	pub async fn on_resolved_request(
 		&mut self,
 		newly_requested: HashSet<Multiaddr>,
 		peer_set: PeerSet,
 		mut network_service: N,
+	) -> N {
+		for dedicated in peer_set.get_dedicated_peer_sets() {
+			network_service = self
+				.update_reserved_peers(newly_requested.clone(), *dedicated, network_service)
+				.await;
+		}
+		self.update_reserved_peers(newly_requested, peer_set, network_service).await
+	}
+
+	async fn update_reserved_peers(
+		&mut self,
+		newly_requested: HashSet<Multiaddr>,
+		peer_set: PeerSet,
+		mut network_service: N,
 	) -> N {
 		let state = &mut self.state[peer_set];
 		let new_peer_ids: HashSet<PeerId> = extract_peer_ids(newly_requested.iter().cloned());
@@ -353,4 +369,26 @@ mod tests {
 			assert_eq!(failed, 1);
 		});
 	}
+
+	#[test]
+	fn dedicated_peer_sets_connect_to_the_same_validators() {
+		let mut service = new_service();
+
+		let (ns, ads) = new_network();
+
+		let authority_ids: Vec<_> =
+			ads.by_peer_id.values().map(|v| v.iter()).flatten().cloned().collect();
+
+		futures::executor::block_on(async move {
+			let (failed, _) = oneshot::channel();
+			let _ = service
+				.on_request(authority_ids[..2].to_vec(), PeerSet::Validation, failed, ns, ads)
+				.await;
+
+			let validation = &service.state[PeerSet::Validation].previously_requested;
+			assert_eq!(validation.len(), 2);
+			assert_eq!(&service.state[PeerSet::Approval].previously_requested, validation);
+			assert!(service.state[PeerSet::Collation].previously_requested.is_empty());
+		});
+	}
 }
@@ -17,24 +17,33 @@
 use std::{
 	collections::{HashMap, HashSet, VecDeque},
 	pin::Pin,
-	time::Duration,
+	time::{Duration, Instant},
 };
 
 use futures::{
-	channel::oneshot, pin_mut, select, stream::FuturesUnordered, Future, FutureExt, StreamExt,
+	channel::{mpsc, oneshot},
+	pin_mut, select,
+	stream::FuturesUnordered,
+	Future, FutureExt, StreamExt,
 };
+use parity_scale_codec::Encode;
 use sp_core::Pair;
 
 use polkadot_node_network_protocol::{
 	peer_set::PeerSet,
 	request_response::{
 		incoming::{self, OutgoingResponse},
-		v1::{self as request_v1, CollationFetchingRequest, CollationFetchingResponse},
-		IncomingRequest, IncomingRequestReceiver,
+		outgoing::Recipient,
+		v1::{
+			self as request_v1, CollationFetchingRequest, CollationFetchingResponse,
+			CollationPushingRequest,
+		},
+		IncomingRequest, IncomingRequestReceiver, OutgoingRequest, Requests,
 	},
-	v1 as protocol_v1, OurView, PeerId, UnifiedReputationChange as Rep, View,
+	v1 as protocol_v1, v2 as protocol_v2, OurView, PeerId, UnifiedReputationChange as Rep,
+	VersionedCollatorProtocolMessage, View,
 };
-use polkadot_node_primitives::{CollationSecondedSignal, PoV, Statement};
+use polkadot_node_primitives::{CollationSecondedSignal, PoV, SignedFullStatement, Statement};
 use polkadot_node_subsystem_util::{
 	metrics::{self, prometheus},
 	runtime::{get_availability_cores, get_group_rotation_info, RuntimeInfo},
@@ -42,11 +51,11 @@ use polkadot_node_subsystem_util::{
 };
 use polkadot_primitives::v2::{
 	AuthorityDiscoveryId, CandidateHash, CandidateReceipt, CollatorPair, CoreIndex, CoreState,
-	Hash, Id as ParaId,
+	Hash, Id as ParaId, ValidatorIndex,
 };
 use polkadot_subsystem::{
 	jaeger,
-	messages::{CollatorProtocolMessage, NetworkBridgeEvent, NetworkBridgeMessage},
+	messages::{CollatorProtocolMessage, IfDisconnected, NetworkBridgeEvent, NetworkBridgeMessage},
 	overseer, FromOverseer, OverseerSignal, PerLeafSpan, SubsystemContext,
 };
 
@@ -70,6 +79,18 @@ const COST_APPARENT_FLOOD: Rep =
 /// For considerations on this value, see: https://github.com/paritytech/polkadot/issues/4386
 const MAX_UNSHARED_UPLOAD_TIME: Duration = Duration::from_millis(150);
 
+/// The maximum number of validators we upload a collation to in parallel.
+///
+/// The number of parallel uploads is further limited by our estimated upload bandwidth, see
+/// [`UploadBandwidth`].
+const MAX_PARALLEL_COLLATION_UPLOADS: usize = 3;
+
+/// The upload bandwidth in bytes per second we assume before any collation upload finished.
+const INITIAL_UPLOAD_BANDWIDTH: f64 = 10.0 * 1024.0 * 1024.0;
+
+/// The weight of a new sample in the moving average of the upload bandwidth.
+const UPLOAD_BANDWIDTH_SMOOTHING: f64 = 0.2;
+
 #[derive(Clone, Default)]
 pub struct Metrics(Option<MetricsInner>);
 
@@ -92,6 +113,24 @@ impl Metrics {
 		}
 	}
 
+	fn on_collation_pushed(&self) {
+		if let Some(metrics) = &self.0 {
+			metrics.collations_pushed.inc();
+		}
+	}
+
+	fn on_collation_seconded(&self) {
+		if let Some(metrics) = &self.0 {
+			metrics.collations_seconded.inc();
+		}
+	}
+
+	fn on_upload_bandwidth(&self, bytes_per_sec: f64) {
+		if let Some(metrics) = &self.0 {
+			metrics.upload_bandwidth.set(bytes_per_sec as u64);
+		}
+	}
+
 	/// Provide a timer for `process_msg` which observes on drop.
 	fn time_process_msg(&self) -> Option<prometheus::prometheus::HistogramTimer> {
 		self.0.as_ref().map(|metrics| metrics.process_msg.start_timer())
@@ -113,6 +152,9 @@ struct MetricsInner {
 	advertisements_made: prometheus::Counter<prometheus::U64>,
 	collations_sent: prometheus::Counter<prometheus::U64>,
 	collations_send_requested: prometheus::Counter<prometheus::U64>,
+	collations_pushed: prometheus::Counter<prometheus::U64>,
+	collations_seconded: prometheus::Counter<prometheus::U64>,
+	upload_bandwidth: prometheus::Gauge<prometheus::U64>,
 	process_msg: prometheus::Histogram,
 	collation_distribution_time: prometheus::HistogramVec,
 }
@@ -143,6 +185,27 @@ impl metrics::Metrics for Metrics {
 				)?,
 				registry,
 			)?,
+			collations_pushed: prometheus::register(
+				prometheus::Counter::new(
+					"polkadot_parachain_collations_pushed_total",
+					"A number of collations pushed to validators without being requested.",
+				)?,
+				registry,
+			)?,
+			collations_seconded: prometheus::register(
+				prometheus::Counter::new(
+					"polkadot_parachain_collations_seconded_total",
+					"A number of `CollationSeconded` messages received from validators.",
+				)?,
+				registry,
+			)?,
+			upload_bandwidth: prometheus::register(
+				prometheus::Gauge::new(
+					"polkadot_parachain_collator_protocol_collator_upload_bandwidth",
+					"Estimated upload bandwidth for collations in bytes per second.",
+				)?,
+				registry,
+			)?,
 			process_msg: prometheus::register(
 				prometheus::Histogram::with_opts(
 					prometheus::HistogramOpts::new(
@@ -181,16 +244,30 @@ impl metrics::Metrics for Metrics {
 /// It keeps track to which validators we advertised our collation.
 #[derive(Debug)]
 struct ValidatorGroup {
+	/// All [`AuthorityDiscoveryId`]'s of the validators in the group.
+	validators: HashSet<AuthorityDiscoveryId>,
 	/// All [`ValidatorId`]'s of the current group to that we advertised our collation.
 	advertised_to: HashSet<AuthorityDiscoveryId>,
 }
 
 impl ValidatorGroup {
-	/// Create a new `ValidatorGroup`
+	/// Create a new `ValidatorGroup` of the given validators
 	///
 	/// without any advertisements.
-	fn new() -> Self {
-		Self { advertised_to: HashSet::new() }
+	fn new(validators: HashSet<AuthorityDiscoveryId>) -> Self {
+		Self { validators, advertised_to: HashSet::new() }
+	}
+
+	/// Returns `true` if the given peer is a validator of this group.
+	fn contains_peer(
+		&self,
+		peer_ids: &HashMap<PeerId, HashSet<AuthorityDiscoveryId>>,
+		peer: &PeerId,
+	) -> bool {
+		match peer_ids.get(peer) {
+			Some(discovery_ids) => discovery_ids.iter().any(|d| self.validators.contains(d)),
+			None => false,
+		}
 	}
 
 	/// Returns `true` if we should advertise our collation to the given peer.
@@ -200,7 +277,9 @@ impl ValidatorGroup {
 		peer: &PeerId,
 	) -> bool {
 		match peer_ids.get(peer) {
-			Some(discovery_ids) => !discovery_ids.iter().any(|d| self.advertised_to.contains(d)),
+			Some(discovery_ids) =>
+				self.contains_peer(peer_ids, peer) &&
+					!discovery_ids.iter().any(|d| self.advertised_to.contains(d)),
 			None => false,
 		}
 	}
@@ -248,15 +327,95 @@ impl CollationStatus {
 /// A collation built by the collator.
 struct Collation {
 	receipt: CandidateReceipt,
+	/// The hash of the head data of the parent of the candidate.
+	parent_head_data_hash: Hash,
 	pov: PoV,
 	status: CollationStatus,
+	/// The validators we started uploading the collation to.
+	uploaded_to: HashSet<PeerId>,
+}
+
+/// The result sender of a collation.
+///
+/// The [`CollationSecondedSignal`] is sent as soon as the first validator seconded the
+/// collation, the validators seconding it later are reported on the stream of the signal.
+/// Dropping the result sender ends that stream.
+struct CollationResultSender {
+	/// The sender of the signal, until the first validator seconded the collation.
+	signal_sender: Option<oneshot::Sender<CollationSecondedSignal>>,
+	/// The sender of the validators seconding the collation after the first one.
+	further_seconded_by: Option<mpsc::UnboundedSender<ValidatorIndex>>,
+	/// The peers we received a `CollationSeconded` message from.
+	seconded_peers: HashSet<PeerId>,
+}
+
+impl CollationResultSender {
+	fn new(sender: oneshot::Sender<CollationSecondedSignal>) -> Self {
+		Self {
+			signal_sender: Some(sender),
+			further_seconded_by: None,
+			seconded_peers: HashSet::new(),
+		}
+	}
+
+	/// Report a validator seconding the collation, the first one with the
+	/// [`CollationSecondedSignal`].
+	fn note_seconded(&mut self, relay_parent: Hash, statement: SignedFullStatement) {
+		if let Some(signal_sender) = self.signal_sender.take() {
+			let (tx, rx) = mpsc::unbounded();
+			self.further_seconded_by = Some(tx);
+			let _ = signal_sender.send(CollationSecondedSignal {
+				relay_parent,
+				statement,
+				further_seconded_by: rx,
+			});
+		} else if let Some(ref further_seconded_by) = self.further_seconded_by {
+			let _ = further_seconded_by.unbounded_send(statement.validator_index());
+		}
+	}
+}
+
+/// Estimate of our upload bandwidth, based on the collation uploads we did so far.
+///
+/// It is used to pace uploads: we only upload a collation to as many validators in parallel as
+/// we can serve within [`MAX_UNSHARED_UPLOAD_TIME`].
+struct UploadBandwidth {
+	/// Moving average of the bandwidth in bytes per second.
+	bytes_per_sec: f64,
+}
+
+impl Default for UploadBandwidth {
+	fn default() -> Self {
+		Self { bytes_per_sec: INITIAL_UPLOAD_BANDWIDTH }
+	}
+}
+
+impl UploadBandwidth {
+	/// Note an upload of `bytes`, which was started with `parallel - 1` other uploads in flight
+	/// and took `elapsed`.
+	fn note_upload(&mut self, bytes: usize, parallel: usize, elapsed: Duration) {
+		let elapsed = elapsed.as_secs_f64();
+		if elapsed <= 0.0 {
+			return
+		}
+
+		let sample = (bytes * parallel) as f64 / elapsed;
+		self.bytes_per_sec = self.bytes_per_sec * (1.0 - UPLOAD_BANDWIDTH_SMOOTHING) +
+			sample * UPLOAD_BANDWIDTH_SMOOTHING;
+	}
+
+	/// The number of validators to upload a collation of the given size to in parallel.
+	fn max_parallel_uploads(&self, collation_size: usize) -> usize {
+		let budget = self.bytes_per_sec * MAX_UNSHARED_UPLOAD_TIME.as_secs_f64();
+		((budget / collation_size.max(1) as f64) as usize).clamp(1, MAX_PARALLEL_COLLATION_UPLOADS)
+	}
 }
 
 /// Stores the state for waiting collation fetches.
 #[derive(Default)]
 struct WaitingCollationFetches {
-	/// Is there currently a collation getting fetched?
-	collation_fetch_active: bool,
+	/// The number of collation uploads currently active, including pushed ones.
+	active_uploads: usize,
 	/// The collation fetches waiting to be fulfilled.
 	waiting: VecDeque<IncomingRequest<CollationFetchingRequest>>,
 	/// All peers that are waiting or actively uploading.
@@ -266,8 +425,22 @@ struct WaitingCollationFetches {
 	waiting_peers: HashSet<PeerId>,
 }
 
+/// A requested or pushed collation upload to a validator, which either finished or timed out.
+struct FinishedUpload {
+	relay_parent: Hash,
+	para_id: ParaId,
+	peer_id: PeerId,
+	/// The size of the uploaded PoV.
+	bytes: usize,
+	/// The number of uploads of the collation active when this one was started, including itself.
+	parallel: usize,
+	/// The time the upload took. `None` if the upload failed or didn't finish within
+	/// [`MAX_UNSHARED_UPLOAD_TIME`], so we don't learn anything about our bandwidth from it.
+	elapsed: Option<Duration>,
+}
+
 type ActiveCollationFetches =
-	FuturesUnordered<Pin<Box<dyn Future<Output = (Hash, PeerId)> + Send + 'static>>>;
+	FuturesUnordered<Pin<Box<dyn Future<Output = FinishedUpload> + Send + 'static>>>;
 
 struct State {
 	/// Our network peer id.
@@ -276,9 +449,15 @@ struct State {
 	/// Our collator pair.
 	collator_pair: CollatorPair,
 
-	/// The para this collator is collating on.
-	/// Starts as `None` and is updated with every `CollateOn` message.
-	collating_on: Option<ParaId>,
+	/// The paras this collator is collating on.
+	/// Starts empty and is extended with every `CollateOn` message.
+	collating_on: HashSet<ParaId>,
+
+	/// The para we declared to each connected peer.
+	///
+	/// Validators only accept a single `Declare` per connection, so a peer only gets advertised
+	/// the collations of the para we declared to it.
+	declared_to: HashMap<PeerId, ParaId>,
 
 	/// Track all active peers and their views
 	/// to determine what is relevant to them.
@@ -292,14 +471,14 @@ struct State {
 
 	/// Possessed collations.
 	///
-	/// We will keep up to one local collation per relay-parent.
-	collations: HashMap<Hash, Collation>,
+	/// We will keep up to one local collation per relay-parent and para.
+	collations: HashMap<(Hash, ParaId), Collation>,
 
 	/// The result senders per collation.
-	collation_result_senders: HashMap<CandidateHash, oneshot::Sender<CollationSecondedSignal>>,
+	collation_result_senders: HashMap<CandidateHash, CollationResultSender>,
 
-	/// Our validator groups per active leaf.
-	our_validators_groups: HashMap<Hash, ValidatorGroup>,
+	/// Our validator groups per active leaf and para.
+	our_validators_groups: HashMap<(Hash, ParaId), ValidatorGroup>,
 
 	/// The mapping from [`PeerId`] to [`HashSet<AuthorityDiscoveryId>`]. This is filled over time as we learn the [`PeerId`]'s
 	/// by `PeerConnected` events.
@@ -310,14 +489,17 @@ struct State {
 
 	/// All collation fetching requests that are still waiting to be answered.
 	///
-	/// They are stored per relay parent, when our view changes and the relay parent moves out, we will cancel the fetch
-	/// request.
-	waiting_collation_fetches: HashMap<Hash, WaitingCollationFetches>,
+	/// They are stored per relay parent and para, when our view changes and the relay parent moves out, we will cancel
+	/// the fetch request.
+	waiting_collation_fetches: HashMap<(Hash, ParaId), WaitingCollationFetches>,
 
-	/// Active collation fetches.
+	/// Active collation fetches and pushes.
 	///
-	/// Each future returns the relay parent of the finished collation fetch.
+	/// Each future returns the finished upload.
 	active_collation_fetches: ActiveCollationFetches,
+
+	/// Our estimated upload bandwidth.
+	upload_bandwidth: UploadBandwidth,
 }
 
 impl State {
@@ -329,6 +511,7 @@ impl State {
 			collator_pair,
 			metrics,
 			collating_on: Default::default(),
+			declared_to: Default::default(),
 			peer_views: Default::default(),
 			view: Default::default(),
 			span_per_relay_parent: Default::default(),
@@ -338,6 +521,7 @@ impl State {
 			peer_ids: Default::default(),
 			waiting_collation_fetches: Default::default(),
 			active_collation_fetches: Default::default(),
+			upload_bandwidth: Default::default(),
 		}
 	}
 
@@ -349,6 +533,21 @@ impl State {
 			.map(|(peer, _)| *peer)
 			.collect()
 	}
+
+	/// The para to declare to the given peer, if any.
+	///
+	/// This is the para of a validator group the peer is part of. If the peer isn't part of any
+	/// of our groups, we only declare to it while collating on a single para.
+	fn para_to_declare(&self, peer: &PeerId) -> Option<ParaId> {
+		self.our_validators_groups
+			.iter()
+			.find(|(_, group)| group.contains_peer(&self.peer_ids, peer))
+			.map(|((_, para_id), _)| *para_id)
+			.or_else(|| match self.collating_on.len() {
+				1 => self.collating_on.iter().next().copied(),
+				_ => None,
+			})
+	}
 }
 
 /// Distribute a collation.
@@ -365,6 +564,7 @@ async fn distribute_collation<Context>(
 	state: &mut State,
 	id: ParaId,
 	receipt: CandidateReceipt,
+	parent_head_data_hash: Hash,
 	pov: PoV,
 	result_sender: Option<oneshot::Sender<CollationSecondedSignal>>,
 ) -> Result<()>
@@ -385,12 +585,13 @@ where
 		return Ok(())
 	}
 
-	// We have already seen collation for this relay parent.
-	if state.collations.contains_key(&relay_parent) {
+	// We have already seen collation for this relay parent and para.
+	if state.collations.contains_key(&(relay_parent, id)) {
 		gum::debug!(
 			target: LOG_TARGET,
 			?relay_parent,
-			"Already seen collation for this relay parent",
+			para_id = %id,
+			"Already seen collation for this relay parent and para",
 		);
 		return Ok(())
 	}
@@ -436,18 +637,47 @@ where
 		"Accepted collation, connecting to validators."
 	);
 
-	// Issue a discovery request for the validators of the current group:
-	connect_to_validators(ctx, current_validators.validators.into_iter().collect()).await;
+	state.our_validators_groups.insert(
+		(relay_parent, id),
+		ValidatorGroup::new(current_validators.validators.into_iter().collect()),
+	);
 
-	state.our_validators_groups.insert(relay_parent, ValidatorGroup::new());
+	// Issue a discovery request for the validators of the current group. Every request revokes the
+	// previous one, so keep the validators of our other groups connected as well:
+	let validator_ids = state
+		.our_validators_groups
+		.values()
+		.flat_map(|group| group.validators.iter().cloned())
+		.collect::<HashSet<_>>();
+	connect_to_validators(ctx, validator_ids.into_iter().collect()).await;
 
 	if let Some(result_sender) = result_sender {
-		state.collation_result_senders.insert(receipt.hash(), result_sender);
+		state
+			.collation_result_senders
+			.insert(receipt.hash(), CollationResultSender::new(result_sender));
 	}
 
-	state
-		.collations
-		.insert(relay_parent, Collation { receipt, pov, status: CollationStatus::Created });
+	state.collations.insert(
+		(relay_parent, id),
+		Collation {
+			receipt,
+			parent_head_data_hash,
+			pov,
+			status: CollationStatus::Created,
+			uploaded_to: HashSet::new(),
+		},
+	);
+
+	// Declare to connected validators of the group we had no para to declare to before:
+	let undeclared = state
+		.peer_ids
+		.keys()
+		.filter(|peer_id| !state.declared_to.contains_key(peer_id))
+		.copied()
+		.collect::<Vec<_>>();
+	for peer_id in undeclared {
+		declare(ctx, state, peer_id).await;
+	}
 
 	let interested = state.peers_interested_in_leaf(&relay_parent);
 	// Make sure already connected peers get collations:
@@ -528,16 +758,22 @@ where
 	Ok(current_validators)
 }
 
-/// Issue a `Declare` collation message to the given `peer`.
+/// Issue a `Declare` collation message to the given `peer`, unless we already declared to it.
+///
+/// The declared para is determined by [`State::para_to_declare`].
 async fn declare<Context>(ctx: &mut Context, state: &mut State, peer: PeerId)
 where
 	Context: SubsystemContext<Message = CollatorProtocolMessage>,
 	Context: overseer::SubsystemContext<Message = CollatorProtocolMessage>,
 {
-	let declare_signature_payload = protocol_v1::declare_signature_payload(&state.local_peer_id);
+	if state.declared_to.contains_key(&peer) {
+		return
+	}
 
-	if let Some(para_id) = state.collating_on {
-		let wire_message = protocol_v1::CollatorProtocolMessage::Declare(
+	let declare_signature_payload = protocol_v2::declare_signature_payload(&state.local_peer_id);
+
+	if let Some(para_id) = state.para_to_declare(&peer) {
+		let wire_message = protocol_v2::CollatorProtocolMessage::Declare(
 			state.collator_pair.public(),
 			para_id,
 			state.collator_pair.sign(&declare_signature_payload),
@@ -545,9 +781,11 @@ where
 
 		ctx.send_message(NetworkBridgeMessage::SendCollationMessage(
 			vec![peer],
-			protocol_v1::CollationProtocol::CollatorProtocol(wire_message),
+			protocol_v2::CollationProtocol::CollatorProtocol(wire_message),
 		))
 		.await;
+
+		state.declared_to.insert(peer, para_id);
 	}
 }
 
@@ -571,8 +809,8 @@ where
 
 /// Advertise collation to the given `peer`.
 ///
-/// This will only advertise a collation if there exists one for the given `relay_parent` and the given `peer` is
-/// set as validator for our para at the given `relay_parent`.
+/// This will only advertise a collation if there exists one for the given `relay_parent` and the para we declared to
+/// the given `peer`, and the `peer` is set as validator for that para at the given `relay_parent`.
 async fn advertise_collation<Context>(
 	ctx: &mut Context,
 	state: &mut State,
@@ -582,13 +820,27 @@ async fn advertise_collation<Context>(
 	Context: SubsystemContext<Message = CollatorProtocolMessage>,
 	Context: overseer::SubsystemContext<Message = CollatorProtocolMessage>,
 {
+	let para_id = match state.declared_to.get(&peer) {
+		Some(para_id) => *para_id,
+		None => {
+			gum::trace!(
+				target: LOG_TARGET,
+				?relay_parent,
+				peer_id = %peer,
+				"Not advertising collation as we didn't declare to this peer.",
+			);
+			return
+		},
+	};
+	let key = (relay_parent, para_id);
+
 	let should_advertise = state
 		.our_validators_groups
-		.get(&relay_parent)
+		.get(&key)
 		.map(|g| g.should_advertise_to(&state.peer_ids, &peer))
 		.unwrap_or(false);
 
-	match (state.collations.get_mut(&relay_parent), should_advertise) {
+	let wire_message = match (state.collations.get_mut(&key), should_advertise) {
 		(None, _) => {
 			gum::trace!(
 				target: LOG_TARGET,
@@ -603,7 +855,7 @@ async fn advertise_collation<Context>(
 				target: LOG_TARGET,
 				?relay_parent,
 				peer_id = %peer,
-				"Not advertising collation as we already advertised it to this validator.",
+				"Not advertising collation as the validator isn't in the group or we already advertised it.",
 			);
 			return
 		},
@@ -614,23 +866,113 @@ async fn advertise_collation<Context>(
 				peer_id = %peer,
 				"Advertising collation.",
 			);
-			collation.status.advance_to_advertised()
-		},
-	}
+			collation.status.advance_to_advertised();
 
-	let wire_message = protocol_v1::CollatorProtocolMessage::AdvertiseCollation(relay_parent);
+			protocol_v2::CollatorProtocolMessage::AdvertiseCollation {
+				relay_parent,
+				candidate_hash: collation.receipt.hash(),
+				parent_head_data_hash: collation.parent_head_data_hash,
+			}
+		},
+	};
 
 	ctx.send_message(NetworkBridgeMessage::SendCollationMessage(
 		vec![peer.clone()],
-		protocol_v1::CollationProtocol::CollatorProtocol(wire_message),
+		protocol_v2::CollationProtocol::CollatorProtocol(wire_message),
 	))
 	.await;
 
-	if let Some(validators) = state.our_validators_groups.get_mut(&relay_parent) {
+	if let Some(validators) = state.our_validators_groups.get_mut(&key) {
 		validators.advertised_to_peer(&state.peer_ids, &peer);
 	}
 
 	state.metrics.on_advertisment_made();
+
+	push_collation(ctx, state, relay_parent, para_id, peer).await;
+}
+
+/// Push the collation for `relay_parent` and `para_id` to the given validator, without waiting
+/// for it to request the collation.
+///
+/// We only push the collation to as many validators as our upload bandwidth allows to upload to
+/// in parallel. Pushes count as active uploads, so requested uploads wait for them. Validators we
+/// didn't push the collation to can still fetch it after our advertisement.
+async fn push_collation<Context>(
+	ctx: &mut Context,
+	state: &mut State,
+	relay_parent: Hash,
+	para_id: ParaId,
+	peer: PeerId,
+) where
+	Context: SubsystemContext<Message = CollatorProtocolMessage>,
+	Context: overseer::SubsystemContext<Message = CollatorProtocolMessage>,
+{
+	let key = (relay_parent, para_id);
+
+	let collation = match state.collations.get_mut(&key) {
+		Some(collation) if !collation.uploaded_to.contains(&peer) => collation,
+		_ => return,
+	};
+	let bytes = collation.pov.encoded_size();
+
+	if collation.uploaded_to.len() >= state.upload_bandwidth.max_parallel_uploads(bytes) {
+		gum::trace!(
+			target: LOG_TARGET,
+			?relay_parent,
+			peer_id = %peer,
+			"Not pushing collation, as we upload it to enough validators already.",
+		);
+		return
+	}
+	collation.uploaded_to.insert(peer);
+
+	let waiting = state.waiting_collation_fetches.entry(key).or_default();
+	waiting.active_uploads += 1;
+	let parallel = waiting.active_uploads;
+
+	gum::debug!(target: LOG_TARGET, ?relay_parent, peer_id = %peer, "Pushing collation.");
+
+	let (request, response) = OutgoingRequest::new(
+		Recipient::Peer(peer),
+		CollationPushingRequest { receipt: collation.receipt.clone(), pov: collation.pov.clone() },
+	);
+	ctx.send_message(NetworkBridgeMessage::SendRequests(
+		vec![Requests::CollationPushing(request)],
+		IfDisconnected::ImmediateError,
+	))
+	.await;
+
+	let started = Instant::now();
+	state.active_collation_fetches.push(
+		async move {
+			let elapsed = match response.timeout(MAX_UNSHARED_UPLOAD_TIME).await {
+				Some(Ok(_)) => Some(started.elapsed()),
+				Some(Err(err)) => {
+					gum::debug!(
+						target: LOG_TARGET,
+						?relay_parent,
+						peer_id = %peer,
+						?err,
+						"Pushing collation to validator failed.",
+					);
+					None
+				},
+				None => {
+					gum::debug!(
+						target: LOG_TARGET,
+						?relay_parent,
+						peer_id = %peer,
+						"Pushing collation to validator timed out, carrying on with next validator."
+					);
+					None
+				},
+			};
+			FinishedUpload { relay_parent, para_id, peer_id: peer, bytes, parallel, elapsed }
+		}
+		.boxed(),
+	);
+
+	state.metrics.on_collation_pushed();
 }
 
 /// The main incoming message dispatching switch.
@@ -648,37 +990,43 @@ where
 
 	match msg {
 		CollateOn(id) => {
-			state.collating_on = Some(id);
+			state.collating_on.insert(id);
 		},
-		DistributeCollation(receipt, pov, result_sender) => {
+		DistributeCollation(receipt, parent_head_data_hash, pov, result_sender) => {
 			let _span1 = state
 				.span_per_relay_parent
 				.get(&receipt.descriptor.relay_parent)
 				.map(|s| s.child("distributing-collation"));
 			let _span2 = jaeger::Span::new(&pov, "distributing-collation");
-			match state.collating_on {
-				Some(id) if receipt.descriptor.para_id != id => {
-					// If the ParaId of a collation requested to be distributed does not match
-					// the one we expect, we ignore the message.
-					gum::warn!(
-						target: LOG_TARGET,
-						para_id = %receipt.descriptor.para_id,
-						collating_on = %id,
-						"DistributeCollation for unexpected para_id",
-					);
-				},
-				Some(id) => {
-					let _ = state.metrics.time_collation_distribution("distribute");
-					distribute_collation(ctx, runtime, state, id, receipt, pov, result_sender)
-						.await?;
-				},
-				None => {
-					gum::warn!(
-						target: LOG_TARGET,
-						para_id = %receipt.descriptor.para_id,
-						"DistributeCollation message while not collating on any",
-					);
-				},
+			let id = receipt.descriptor.para_id;
+			if state.collating_on.contains(&id) {
+				let _ = state.metrics.time_collation_distribution("distribute");
+				distribute_collation(
+					ctx,
+					runtime,
+					state,
+					id,
+					receipt,
+					parent_head_data_hash,
+					pov,
+					result_sender,
+				)
+				.await?;
+			} else if state.collating_on.is_empty() {
+				gum::warn!(
+					target: LOG_TARGET,
+					para_id = %id,
+					"DistributeCollation message while not collating on any",
+				);
+			} else {
+				// If the ParaId of a collation requested to be distributed is not one of
+				// the ones we expect, we ignore the message.
+				gum::warn!(
+					target: LOG_TARGET,
+					para_id = %id,
+					collating_on = ?state.collating_on,
+					"DistributeCollation for unexpected para_id",
+				);
 			}
 		},
 		ReportCollator(_) => {
@@ -687,7 +1035,7 @@ where
 				"ReportCollator message is not expected on the collator side of the protocol",
 			);
 		},
-		NetworkBridgeUpdateV1(event) => {
+		NetworkBridgeUpdate(event) => {
 			// We should count only this shoulder in the histogram, as other shoulders are just introducing noise
 			let _ = state.metrics.time_process_msg();
 			if let Err(e) = handle_network_msg(ctx, runtime, state, event).await {
@@ -704,17 +1052,76 @@ where
 	Ok(())
 }
 
+/// Start uploading the collation for `relay_parent` and `para_id` to waiting validators, as long
+/// as our upload bandwidth allows for more parallel uploads.
+async fn start_waiting_uploads(state: &mut State, relay_parent: Hash, para_id: ParaId) {
+	loop {
+		let max_parallel = match state.collations.get(&(relay_parent, para_id)) {
+			Some(collation) =>
+				state.upload_bandwidth.max_parallel_uploads(collation.pov.encoded_size()),
+			None => return,
+		};
+
+		let waiting = match state.waiting_collation_fetches.get_mut(&(relay_parent, para_id)) {
+			Some(waiting) if waiting.active_uploads < max_parallel => waiting,
+			_ => return,
+		};
+
+		let request = match waiting.waiting.pop_front() {
+			Some(request) => request,
+			None => return,
+		};
+
+		waiting.active_uploads += 1;
+		let parallel = waiting.active_uploads;
+
+		let (receipt, pov) = match state.collations.get_mut(&(relay_parent, para_id)) {
+			Some(collation) => {
+				collation.uploaded_to.insert(request.peer);
+				(collation.receipt.clone(), collation.pov.clone())
+			},
+			None => return,
+		};
+
+		send_collation(state, request, receipt, pov, parallel).await;
+	}
+}
+
+/// Account for a finished collation upload and start the uploads it was holding back.
+async fn handle_finished_upload(state: &mut State, upload: FinishedUpload) {
+	if let Some(elapsed) = upload.elapsed {
+		state.upload_bandwidth.note_upload(upload.bytes, upload.parallel, elapsed);
+		state.metrics.on_upload_bandwidth(state.upload_bandwidth.bytes_per_sec);
+	}
+
+	match state.waiting_collation_fetches.get_mut(&(upload.relay_parent, upload.para_id)) {
+		Some(waiting) => {
+			waiting.waiting_peers.remove(&upload.peer_id);
+			waiting.active_uploads = waiting.active_uploads.saturating_sub(1);
+		},
+		// No waiting collation fetches means we already removed the relay parent from our view.
+		None => return,
+	}
+
+	start_waiting_uploads(state, upload.relay_parent, upload.para_id).await;
+}
+
 /// Issue a response to a previously requested collation.
+///
+/// `parallel` is the number of uploads of the collation active, including this one.
 async fn send_collation(
 	state: &mut State,
 	request: IncomingRequest<CollationFetchingRequest>,
 	receipt: CandidateReceipt,
 	pov: PoV,
+	parallel: usize,
 ) {
 	let (tx, rx) = oneshot::channel();
 
 	let relay_parent = request.payload.relay_parent;
+	let para_id = request.payload.para_id;
 	let peer_id = request.peer;
+	let bytes = pov.encoded_size();
 
 	let response = OutgoingResponse {
 		result: Ok(CollationFetchingResponse::Collation(receipt, pov)),
@@ -726,18 +1133,23 @@ async fn send_collation(
 		gum::warn!(target: LOG_TARGET, "Sending collation response failed");
 	}
 
+	let started = Instant::now();
 	state.active_collation_fetches.push(
 		async move {
-			let r = rx.timeout(MAX_UNSHARED_UPLOAD_TIME).await;
-			if r.is_none() {
-				gum::debug!(
-					target: LOG_TARGET,
-					?relay_parent,
-					?peer_id,
-					"Sending collation to validator timed out, carrying on with next validator."
-				);
-			}
-			(relay_parent, peer_id)
+			let elapsed = match rx.timeout(MAX_UNSHARED_UPLOAD_TIME).await {
+				Some(Ok(())) => Some(started.elapsed()),
+				Some(Err(_)) => None,
+				None => {
+					gum::debug!(
+						target: LOG_TARGET,
+						?relay_parent,
+						?peer_id,
+						"Sending collation to validator timed out, carrying on with next validator."
+					);
+					None
+				},
+			};
+			FinishedUpload { relay_parent, para_id, peer_id, bytes, parallel, elapsed }
 		}
 		.boxed(),
 	);
@@ -751,16 +1163,17 @@ async fn handle_incoming_peer_message<Context>(
 	runtime: &mut RuntimeInfo,
 	state: &mut State,
 	origin: PeerId,
-	msg: protocol_v1::CollatorProtocolMessage,
+	msg: VersionedCollatorProtocolMessage,
 ) -> Result<()>
 where
 	Context: SubsystemContext<Message = CollatorProtocolMessage>,
 	Context: overseer::SubsystemContext<Message = CollatorProtocolMessage>,
 {
-	use protocol_v1::CollatorProtocolMessage::*;
+	use VersionedCollatorProtocolMessage::{V1, V2};
 
 	match msg {
-		Declare(_, _, _) => {
+		V1(protocol_v1::CollatorProtocolMessage::Declare(_, _, _)) |
+		V2(protocol_v2::CollatorProtocolMessage::Declare(_, _, _)) => {
 			gum::trace!(
 				target: LOG_TARGET,
 				?origin,
@@ -771,7 +1184,8 @@ where
 			ctx.send_message(NetworkBridgeMessage::DisconnectPeer(origin, PeerSet::Collation))
 				.await;
 		},
-		AdvertiseCollation(_) => {
+		V1(protocol_v1::CollatorProtocolMessage::AdvertiseCollation(_)) |
+		V2(protocol_v2::CollatorProtocolMessage::AdvertiseCollation { .. }) => {
 			gum::trace!(
 				target: LOG_TARGET,
 				?origin,
@@ -788,7 +1202,8 @@ where
 			ctx.send_message(NetworkBridgeMessage::DisconnectPeer(origin, PeerSet::Collation))
 				.await;
 		},
-		CollationSeconded(relay_parent, statement) => {
+		V1(protocol_v1::CollatorProtocolMessage::CollationSeconded(relay_parent, statement)) |
+		V2(protocol_v2::CollatorProtocolMessage::CollationSeconded(relay_parent, statement)) => {
 			if !matches!(statement.unchecked_payload(), Statement::Seconded(_)) {
 				gum::warn!(
 					target: LOG_TARGET,
@@ -802,17 +1217,44 @@ where
 					.await?
 					.map_err(Error::InvalidStatementSignature)?;
 
-				let removed =
-					state.collation_result_senders.remove(&statement.payload().candidate_hash());
+				let candidate_hash = statement.payload().candidate_hash();
+				let para_id = match statement.payload() {
+					Statement::Seconded(receipt) => receipt.descriptor.para_id,
+					// Checked above, before the signature.
+					Statement::Valid(_) => return Ok(()),
+				};
+
+				if let Some(result_sender) = state.collation_result_senders.get_mut(&candidate_hash)
+				{
+					if !result_sender.seconded_peers.insert(origin) {
+						gum::debug!(
+							target: LOG_TARGET,
+							?statement,
+							?origin,
+							"received a duplicate `CollationSeconded`",
+						);
+						return Ok(())
+					}
 
-				if let Some(sender) = removed {
 					gum::trace!(
 						target: LOG_TARGET,
 						?statement,
 						?origin,
 						"received a valid `CollationSeconded`",
 					);
-					let _ = sender.send(CollationSecondedSignal { statement, relay_parent });
+					state.metrics.on_collation_seconded();
+
+					result_sender.note_seconded(relay_parent, statement);
+
+					// Once every validator we uploaded the collation to seconded it, there is
+					// nothing left to report.
+					let all_seconded = state
+						.collations
+						.get(&(relay_parent, para_id))
+						.map_or(true, |c| c.uploaded_to.is_subset(&result_sender.seconded_peers));
+					if all_seconded {
+						state.collation_result_senders.remove(&candidate_hash);
+					}
 				} else {
 					gum::warn!(
 						target: LOG_TARGET,
@@ -843,63 +1285,58 @@ where
 		.get(&req.payload.relay_parent)
 		.map(|s| s.child("request-collation"));
 
-	match state.collating_on {
-		Some(our_para_id) if our_para_id == req.payload.para_id => {
-			let (receipt, pov) =
-				if let Some(collation) = state.collations.get_mut(&req.payload.relay_parent) {
-					collation.status.advance_to_requested();
-					(collation.receipt.clone(), collation.pov.clone())
-				} else {
-					gum::warn!(
-						target: LOG_TARGET,
-						relay_parent = %req.payload.relay_parent,
-						"received a `RequestCollation` for a relay parent we don't have collation stored.",
-					);
+	let para_id = req.payload.para_id;
 
-					return Ok(())
-				};
+	if state.collating_on.contains(&para_id) {
+		let relay_parent = req.payload.relay_parent;
 
-			state.metrics.on_collation_sent_requested();
+		if let Some(collation) = state.collations.get_mut(&(relay_parent, para_id)) {
+			collation.status.advance_to_requested();
+		} else {
+			gum::warn!(
+				target: LOG_TARGET,
+				%relay_parent,
+				%para_id,
+				"received a `RequestCollation` for a relay parent we don't have collation stored.",
+			);
 
-			let _span = _span.as_ref().map(|s| s.child("sending"));
+			return Ok(())
+		}
 
-			let waiting =
-				state.waiting_collation_fetches.entry(req.payload.relay_parent).or_default();
+		state.metrics.on_collation_sent_requested();
 
-			if !waiting.waiting_peers.insert(req.peer) {
-				gum::debug!(
-					target: LOG_TARGET,
-					"Dropping incoming request as peer has a request in flight already."
-				);
-				ctx.send_message(NetworkBridgeMessage::ReportPeer(req.peer, COST_APPARENT_FLOOD))
-					.await;
-				return Ok(())
-			}
+		let _span = _span.as_ref().map(|s| s.child("sending"));
 
-			if waiting.collation_fetch_active {
-				waiting.waiting.push_back(req);
-			} else {
-				waiting.collation_fetch_active = true;
-				// Obtain a timer for sending collation
-				let _ = state.metrics.time_collation_distribution("send");
-				send_collation(state, req, receipt, pov).await;
-			}
-		},
-		Some(our_para_id) => {
-			gum::warn!(
-				target: LOG_TARGET,
-				for_para_id = %req.payload.para_id,
-				our_para_id = %our_para_id,
-				"received a `CollationFetchingRequest` for unexpected para_id",
-			);
-		},
-		None => {
-			gum::warn!(
+		let waiting = state.waiting_collation_fetches.entry((relay_parent, para_id)).or_default();
+
+		if !waiting.waiting_peers.insert(req.peer) {
+			gum::debug!(
 				target: LOG_TARGET,
-				for_para_id = %req.payload.para_id,
-				"received a `RequestCollation` while not collating on any para",
+				"Dropping incoming request as peer has a request in flight already."
 			);
-		},
+			ctx.send_message(NetworkBridgeMessage::ReportPeer(req.peer, COST_APPARENT_FLOOD))
+				.await;
+			return Ok(())
+		}
+
+		waiting.waiting.push_back(req);
+
+		// Obtain a timer for sending collation
+		let _ = state.metrics.time_collation_distribution("send");
+		start_waiting_uploads(state, relay_parent, para_id).await;
+	} else if state.collating_on.is_empty() {
+		gum::warn!(
+			target: LOG_TARGET,
+			for_para_id = %para_id,
+			"received a `RequestCollation` while not collating on any para",
+		);
+	} else {
+		gum::warn!(
+			target: LOG_TARGET,
+			for_para_id = %para_id,
+			collating_on = ?state.collating_on,
+			"received a `CollationFetchingRequest` for unexpected para_id",
+		);
 	}
 	Ok(())
 }
@@ -930,7 +1367,7 @@ async fn handle_network_msg<Context>(
 	ctx: &mut Context,
 	runtime: &mut RuntimeInfo,
 	state: &mut State,
-	bridge_message: NetworkBridgeEvent<protocol_v1::CollatorProtocolMessage>,
+	bridge_message: NetworkBridgeEvent<VersionedCollatorProtocolMessage>,
 ) -> Result<()>
 where
 	Context: SubsystemContext<Message = CollatorProtocolMessage>,
@@ -939,7 +1376,7 @@ where
 	use NetworkBridgeEvent::*;
 
 	match bridge_message {
-		PeerConnected(peer_id, observed_role, maybe_authority) => {
+		PeerConnected(peer_id, observed_role, _, maybe_authority) => {
 			// If it is possible that a disconnected validator would attempt a reconnect
 			// it should be handled here.
 			gum::trace!(target: LOG_TARGET, ?peer_id, ?observed_role, "Peer connected");
@@ -963,6 +1400,7 @@ where
 			gum::trace!(target: LOG_TARGET, ?peer_id, "Peer disconnected");
 			state.peer_views.remove(&peer_id);
 			state.peer_ids.remove(&peer_id);
+			state.declared_to.remove(&peer_id);
 		},
 		OurViewChange(view) => {
 			gum::trace!(target: LOG_TARGET, ?view, "Own view change");
@@ -984,7 +1422,13 @@ async fn handle_our_view_change(state: &mut State, view: OurView) -> Result<()>
 	for removed in state.view.difference(&view) {
 		gum::debug!(target: LOG_TARGET, relay_parent = ?removed, "Removing relay parent because our view changed.");
 
-		if let Some(collation) = state.collations.remove(removed) {
+		let removed_collations = state
+			.collations
+			.keys()
+			.filter(|(relay_parent, _)| relay_parent == removed)
+			.copied()
+			.collect::<Vec<_>>();
+		for collation in removed_collations.iter().filter_map(|key| state.collations.remove(key)) {
 			state.collation_result_senders.remove(&collation.receipt.hash());
 
 			match collation.status {
@@ -1008,9 +1452,13 @@ async fn handle_our_view_change(state: &mut State, view: OurView) -> Result<()>
 				),
 			}
 		}
-		state.our_validators_groups.remove(removed);
+		state
+			.our_validators_groups
+			.retain(|(relay_parent, _), _| relay_parent != removed);
 		state.span_per_relay_parent.remove(removed);
-		state.waiting_collation_fetches.remove(removed);
+		state
+			.waiting_collation_fetches
+			.retain(|(relay_parent, _), _| relay_parent != removed);
 	}
 
 	state.view = view;
@@ -1050,26 +1498,8 @@ where
 				FromOverseer::Signal(BlockFinalized(..)) => {}
 				FromOverseer::Signal(Conclude) => return Ok(()),
 			},
-			(relay_parent, peer_id) = state.active_collation_fetches.select_next_some() => {
-				let next = if let Some(waiting) = state.waiting_collation_fetches.get_mut(&relay_parent) {
-					waiting.waiting_peers.remove(&peer_id);
-					if let Some(next) = waiting.waiting.pop_front() {
-						next
-					} else {
-						waiting.collation_fetch_active = false;
-						continue
-					}
-				} else {
-					// No waiting collation fetches means we already removed the relay parent from our view.
-					continue
-				};
-
-				if let Some(collation) = state.collations.get(&relay_parent) {
-					let receipt = collation.receipt.clone();
-					let pov = collation.pov.clone();
-
-					send_collation(&mut state, next, receipt, pov).await;
-				}
+			upload = state.active_collation_fetches.select_next_some() => {
+				handle_finished_upload(&mut state, upload).await;
 			}
 			in_req = recv_req => {
 				match in_req {
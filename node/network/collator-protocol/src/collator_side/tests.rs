@@ -27,14 +27,20 @@ use parity_scale_codec::{Decode, Encode};
 use sc_network::config::IncomingRequest as RawIncomingRequest;
 use sp_core::crypto::Pair;
 use sp_keyring::Sr25519Keyring;
+use sp_keystore::{testing::KeyStore as TestKeyStore, SyncCryptoStore, SyncCryptoStorePtr};
 use sp_runtime::traits::AppVerify;
 
-use polkadot_node_network_protocol::{our_view, request_response::IncomingRequest, view};
+use polkadot_node_network_protocol::{
+	our_view,
+	peer_set::CollationVersion,
+	request_response::{v1::CollationPushingResponse, IncomingRequest, ResponseSender},
+	view,
+};
 use polkadot_node_primitives::BlockData;
 use polkadot_node_subsystem_util::TimeoutExt;
 use polkadot_primitives::v2::{
-	AuthorityDiscoveryId, CollatorPair, GroupRotationInfo, ScheduledCore, SessionIndex,
-	SessionInfo, ValidatorId, ValidatorIndex,
+	AuthorityDiscoveryId, CollatorPair, CommittedCandidateReceipt, GroupRotationInfo,
+	ScheduledCore, SessionIndex, SessionInfo, SigningContext, ValidatorId, ValidatorIndex,
 };
 use polkadot_primitives_test_helpers::TestCandidateBuilder;
 use polkadot_subsystem::{
@@ -51,7 +57,7 @@ struct TestState {
 	group_rotation_info: GroupRotationInfo,
 	validator_peer_id: Vec<PeerId>,
 	relay_parent: Hash,
-	availability_core: CoreState,
+	availability_cores: Vec<CoreState>,
 	local_peer_id: PeerId,
 	collator_pair: CollatorPair,
 	session_index: SessionIndex,
@@ -90,7 +96,8 @@ impl Default for TestState {
 		let group_rotation_info =
 			GroupRotationInfo { session_start_block: 0, group_rotation_frequency: 100, now: 1 };
 
-		let availability_core = CoreState::Scheduled(ScheduledCore { para_id, collator: None });
+		let availability_cores =
+			vec![CoreState::Scheduled(ScheduledCore { para_id, collator: None })];
 
 		let relay_parent = Hash::random();
 
@@ -117,7 +124,7 @@ impl Default for TestState {
 			group_rotation_info,
 			validator_peer_id,
 			relay_parent,
-			availability_core,
+			availability_cores,
 			local_peer_id,
 			collator_pair,
 			session_index: 1,
@@ -171,7 +178,7 @@ impl TestState {
 
 		overseer_send(
 			virtual_overseer,
-			CollatorProtocolMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::OurViewChange(
+			CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::OurViewChange(
 				our_view,
 			)),
 		)
@@ -225,6 +232,9 @@ fn test_harness<T: Future<Output = TestHarness>>(
 
 const TIMEOUT: Duration = Duration::from_millis(100);
 
+/// Size of a PoV that exceeds the upload budget of [`INITIAL_UPLOAD_BANDWIDTH`].
+const LARGE_POV_SIZE: usize = 2 * 1024 * 1024;
+
 async fn overseer_send(overseer: &mut VirtualOverseer, msg: CollatorProtocolMessage) {
 	gum::trace!(?msg, "sending message");
 	overseer
@@ -277,19 +287,33 @@ async fn setup_system(virtual_overseer: &mut VirtualOverseer, test_state: &TestS
 
 	overseer_send(
 		virtual_overseer,
-		CollatorProtocolMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::OurViewChange(
-			our_view![test_state.relay_parent],
-		)),
+		CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::OurViewChange(our_view![
+			test_state.relay_parent
+		])),
 	)
 	.await;
 }
 
+/// The parent head data hash of the collations distributed in the tests.
+const PARENT_HEAD_DATA_HASH: Hash = Hash::repeat_byte(0xAA);
+
 /// Result of [`distribute_collation`]
 struct DistributeCollation {
 	candidate: CandidateReceipt,
 	pov_block: PoV,
 }
 
+/// Create a candidate for the given PoV at the current relay parent.
+fn make_candidate(test_state: &TestState, pov_block: &PoV) -> CandidateReceipt {
+	TestCandidateBuilder {
+		para_id: test_state.para_id,
+		relay_parent: test_state.relay_parent,
+		pov_hash: pov_block.hash(),
+		..Default::default()
+	}
+	.build()
+}
+
 /// Create some PoV and distribute it.
 async fn distribute_collation(
 	virtual_overseer: &mut VirtualOverseer,
@@ -299,20 +323,37 @@ async fn distribute_collation(
 ) -> DistributeCollation {
 	// Now we want to distribute a `PoVBlock`
 	let pov_block = PoV { block_data: BlockData(vec![42, 43, 44]) };
+	let candidate = make_candidate(test_state, &pov_block);
 
-	let pov_hash = pov_block.hash();
-
-	let candidate = TestCandidateBuilder {
-		para_id: test_state.para_id,
-		relay_parent: test_state.relay_parent,
-		pov_hash,
-		..Default::default()
-	}
-	.build();
+	distribute_collation_with(
+		virtual_overseer,
+		test_state,
+		should_connect,
+		candidate,
+		pov_block,
+		None,
+	)
+	.await
+}
 
+/// Distribute the given collation.
+async fn distribute_collation_with(
+	virtual_overseer: &mut VirtualOverseer,
+	test_state: &TestState,
+	// whether or not we expect a connection request or not.
+	should_connect: bool,
+	candidate: CandidateReceipt,
+	pov_block: PoV,
+	result_sender: Option<oneshot::Sender<CollationSecondedSignal>>,
+) -> DistributeCollation {
 	overseer_send(
 		virtual_overseer,
-		CollatorProtocolMessage::DistributeCollation(candidate.clone(), pov_block.clone(), None),
+		CollatorProtocolMessage::DistributeCollation(
+			candidate.clone(),
+			PARENT_HEAD_DATA_HASH,
+			pov_block.clone(),
+			result_sender,
+		),
 	)
 	.await;
 
@@ -324,7 +365,7 @@ async fn distribute_collation(
 			RuntimeApiRequest::AvailabilityCores(tx)
 		)) => {
 			assert_eq!(relay_parent, test_state.relay_parent);
-			tx.send(Ok(vec![test_state.availability_core.clone()])).unwrap();
+			tx.send(Ok(test_state.availability_cores.clone())).unwrap();
 		}
 	);
 
@@ -388,9 +429,10 @@ async fn connect_peer(
 ) {
 	overseer_send(
 		virtual_overseer,
-		CollatorProtocolMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::PeerConnected(
+		CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::PeerConnected(
 			peer.clone(),
 			polkadot_node_network_protocol::ObservedRole::Authority,
+			CollationVersion::V2.into(),
 			authority_id.map(|v| HashSet::from([v])),
 		)),
 	)
@@ -398,7 +440,7 @@ async fn connect_peer(
 
 	overseer_send(
 		virtual_overseer,
-		CollatorProtocolMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::PeerViewChange(
+		CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::PeerViewChange(
 			peer,
 			view![],
 		)),
@@ -410,7 +452,7 @@ async fn connect_peer(
 async fn disconnect_peer(virtual_overseer: &mut VirtualOverseer, peer: PeerId) {
 	overseer_send(
 		virtual_overseer,
-		CollatorProtocolMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::PeerDisconnected(peer)),
+		CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::PeerDisconnected(peer)),
 	)
 	.await;
 }
@@ -426,19 +468,19 @@ async fn expect_declare_msg(
 		AllMessages::NetworkBridge(
 			NetworkBridgeMessage::SendCollationMessage(
 				to,
-				protocol_v1::CollationProtocol::CollatorProtocol(wire_message),
+				protocol_v2::CollationProtocol::CollatorProtocol(wire_message),
 			)
 		) => {
 			assert_eq!(to[0], *peer);
 			assert_matches!(
 				wire_message,
-				protocol_v1::CollatorProtocolMessage::Declare(
+				protocol_v2::CollatorProtocolMessage::Declare(
 					collator_id,
 					para_id,
 					signature,
 				) => {
 					assert!(signature.verify(
-						&*protocol_v1::declare_signature_payload(&test_state.local_peer_id),
+						&*protocol_v2::declare_signature_payload(&test_state.local_peer_id),
 						&collator_id),
 					);
 					assert_eq!(collator_id, test_state.collator_pair.public());
@@ -449,31 +491,59 @@ async fn expect_declare_msg(
 	);
 }
 
-/// Check that the next received message is a collation advertisement message.
+/// Check that the next received message is a collation advertisement message and return the
+/// advertised candidate hash.
 async fn expect_advertise_collation_msg(
 	virtual_overseer: &mut VirtualOverseer,
 	peer: &PeerId,
 	expected_relay_parent: Hash,
-) {
+) -> CandidateHash {
 	assert_matches!(
 		overseer_recv(virtual_overseer).await,
 		AllMessages::NetworkBridge(
 			NetworkBridgeMessage::SendCollationMessage(
 				to,
-				protocol_v1::CollationProtocol::CollatorProtocol(wire_message),
+				protocol_v2::CollationProtocol::CollatorProtocol(wire_message),
 			)
 		) => {
 			assert_eq!(to[0], *peer);
 			assert_matches!(
 				wire_message,
-				protocol_v1::CollatorProtocolMessage::AdvertiseCollation(
+				protocol_v2::CollatorProtocolMessage::AdvertiseCollation {
 					relay_parent,
-				) => {
+					candidate_hash,
+					parent_head_data_hash,
+				} => {
 					assert_eq!(relay_parent, expected_relay_parent);
+					assert_eq!(parent_head_data_hash, PARENT_HEAD_DATA_HASH);
+					candidate_hash
 				}
-			);
+			)
 		}
-	);
+	)
+}
+
+/// Check that the next received message pushes the collation to the given peer and return the
+/// pushed collation, along with the sender for the response.
+async fn expect_collation_push(
+	virtual_overseer: &mut VirtualOverseer,
+	peer: &PeerId,
+) -> (CollationPushingRequest, ResponseSender) {
+	assert_matches!(
+		overseer_recv(virtual_overseer).await,
+		AllMessages::NetworkBridge(
+			NetworkBridgeMessage::SendRequests(mut requests, IfDisconnected::ImmediateError)
+		) => {
+			assert_eq!(requests.len(), 1);
+			assert_matches!(
+				requests.pop().unwrap(),
+				Requests::CollationPushing(request) => {
+					assert_eq!(request.peer, Recipient::Peer(*peer));
+					(request.payload, request.pending_response)
+				}
+			)
+		}
+	)
 }
 
 /// Send a message that the given peer's view changed.
@@ -484,7 +554,7 @@ async fn send_peer_view_change(
 ) {
 	overseer_send(
 		virtual_overseer,
-		CollatorProtocolMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::PeerViewChange(
+		CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::PeerViewChange(
 			peer.clone(),
 			View::new(hashes, 0),
 		)),
@@ -529,9 +599,17 @@ fn advertise_and_send_collation() {
 
 		// The peer is interested in a leaf that we have a collation for;
 		// advertise it.
-		expect_advertise_collation_msg(&mut virtual_overseer, &peer, test_state.relay_parent).await;
+		let advertised =
+			expect_advertise_collation_msg(&mut virtual_overseer, &peer, test_state.relay_parent)
+				.await;
+		assert_eq!(advertised, candidate.hash());
+
+		// We push the collation right away.
+		let (push, _) = expect_collation_push(&mut virtual_overseer, &peer).await;
+		assert_eq!(push.receipt, candidate);
+		assert_eq!(push.pov, pov_block);
 
-		// Request a collation.
+		// The validator failed to receive it, so it requests the collation.
 		let (pending_response, rx) = oneshot::channel();
 		req_cfg
 			.inbound_queue
@@ -627,7 +705,7 @@ fn advertise_and_send_collation() {
 		// Send info about peer's view.
 		overseer_send(
 			&mut virtual_overseer,
-			CollatorProtocolMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::PeerViewChange(
+			CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::PeerViewChange(
 				peer.clone(),
 				view![test_state.relay_parent],
 			)),
@@ -713,6 +791,7 @@ fn collations_are_only_advertised_to_validators_with_correct_view() {
 		distribute_collation(virtual_overseer, &test_state, true).await;
 
 		expect_advertise_collation_msg(virtual_overseer, &peer2, test_state.relay_parent).await;
+		expect_collation_push(virtual_overseer, &peer2).await;
 
 		// The other validator announces that it changed its view.
 		send_peer_view_change(virtual_overseer, &peer, vec![test_state.relay_parent]).await;
@@ -760,6 +839,7 @@ fn collate_on_two_different_relay_chain_blocks() {
 
 		send_peer_view_change(virtual_overseer, &peer, vec![old_relay_parent]).await;
 		expect_advertise_collation_msg(virtual_overseer, &peer, old_relay_parent).await;
+		expect_collation_push(virtual_overseer, &peer).await;
 
 		send_peer_view_change(virtual_overseer, &peer2, vec![test_state.relay_parent]).await;
 
@@ -768,6 +848,117 @@ fn collate_on_two_different_relay_chain_blocks() {
 	})
 }
 
+#[test]
+fn collate_on_two_paras() {
+	let mut test_state = TestState::default();
+	let local_peer_id = test_state.local_peer_id.clone();
+	let collator_pair = test_state.collator_pair.clone();
+
+	let para_b = ParaId::from(2);
+	test_state
+		.availability_cores
+		.push(CoreState::Scheduled(ScheduledCore { para_id: para_b, collator: None }));
+	let test_state_b = TestState { para_id: para_b, ..test_state.clone() };
+
+	test_harness(local_peer_id, collator_pair, |mut test_harness| async move {
+		let virtual_overseer = &mut test_harness.virtual_overseer;
+		let req_cfg = &mut test_harness.req_cfg;
+
+		// Validator 0 is only in the group of the first core, validator 3 only in the group of the
+		// second one.
+		let peer_a = test_state.validator_peer_id[0].clone();
+		let validator_id_a = test_state.session_info.discovery_keys[0].clone();
+		let peer_b = test_state.validator_peer_id[3].clone();
+		let validator_id_b = test_state.session_info.discovery_keys[3].clone();
+
+		setup_system(virtual_overseer, &test_state).await;
+		overseer_send(virtual_overseer, CollatorProtocolMessage::CollateOn(para_b)).await;
+
+		let DistributeCollation { candidate: candidate_a, .. } =
+			distribute_collation(virtual_overseer, &test_state, true).await;
+
+		connect_peer(virtual_overseer, peer_a.clone(), Some(validator_id_a)).await;
+		expect_declare_msg(virtual_overseer, &test_state, &peer_a).await;
+
+		// We don't know which para to declare to a validator outside of our groups.
+		connect_peer(virtual_overseer, peer_b.clone(), Some(validator_id_b)).await;
+		assert!(overseer_recv_with_timeout(virtual_overseer, TIMEOUT).await.is_none());
+
+		let DistributeCollation { candidate: candidate_b, pov_block: pov_block_b } =
+			distribute_collation(virtual_overseer, &test_state_b, false).await;
+
+		// We stay connected to the validators of both groups.
+		assert_matches!(
+			overseer_recv(virtual_overseer).await,
+			AllMessages::NetworkBridge(NetworkBridgeMessage::ConnectToValidators {
+				validator_ids,
+				..
+			}) => {
+				let expected = [0, 2, 3, 4]
+					.iter()
+					.map(|i| test_state.session_info.discovery_keys[*i].clone())
+					.collect::<HashSet<_>>();
+				assert_eq!(validator_ids.into_iter().collect::<HashSet<_>>(), expected);
+			}
+		);
+
+		// Now the validator of the second group learns about our second para.
+		expect_declare_msg(virtual_overseer, &test_state_b, &peer_b).await;
+
+		// Every validator only gets advertised the collation of its para.
+		send_peer_view_change(virtual_overseer, &peer_a, vec![test_state.relay_parent]).await;
+		let advertised =
+			expect_advertise_collation_msg(virtual_overseer, &peer_a, test_state.relay_parent)
+				.await;
+		assert_eq!(advertised, candidate_a.hash());
+		let (push, _) = expect_collation_push(virtual_overseer, &peer_a).await;
+		assert_eq!(push.receipt, candidate_a);
+
+		send_peer_view_change(virtual_overseer, &peer_b, vec![test_state.relay_parent]).await;
+		let advertised =
+			expect_advertise_collation_msg(virtual_overseer, &peer_b, test_state.relay_parent)
+				.await;
+		assert_eq!(advertised, candidate_b.hash());
+		let (push, _) = expect_collation_push(virtual_overseer, &peer_b).await;
+		assert_eq!(push.receipt, candidate_b);
+
+		assert!(overseer_recv_with_timeout(virtual_overseer, TIMEOUT).await.is_none());
+
+		let (pending_response, rx) = oneshot::channel();
+		req_cfg
+			.inbound_queue
+			.as_mut()
+			.unwrap()
+			.send(RawIncomingRequest {
+				peer: peer_b,
+				payload: CollationFetchingRequest {
+					relay_parent: test_state.relay_parent,
+					para_id: para_b,
+				}
+				.encode(),
+				pending_response,
+			})
+			.await
+			.unwrap();
+
+		assert_matches!(
+			rx.await,
+			Ok(full_response) => {
+				let CollationFetchingResponse::Collation(receipt, pov): CollationFetchingResponse
+					= CollationFetchingResponse::decode(
+						&mut full_response.result
+						.expect("We should have a proper answer").as_ref()
+				)
+				.expect("Decoding should work");
+				assert_eq!(receipt, candidate_b);
+				assert_eq!(pov, pov_block_b);
+			}
+		);
+
+		test_harness
+	})
+}
+
 #[test]
 fn validator_reconnect_does_not_advertise_a_second_time() {
 	let test_state = TestState::default();
@@ -790,6 +981,7 @@ fn validator_reconnect_does_not_advertise_a_second_time() {
 
 		send_peer_view_change(virtual_overseer, &peer, vec![test_state.relay_parent]).await;
 		expect_advertise_collation_msg(virtual_overseer, &peer, test_state.relay_parent).await;
+		expect_collation_push(virtual_overseer, &peer).await;
 
 		// Disconnect and reconnect directly
 		disconnect_peer(virtual_overseer, peer.clone()).await;
@@ -824,12 +1016,14 @@ fn collators_reject_declare_messages() {
 
 		overseer_send(
 			virtual_overseer,
-			CollatorProtocolMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::PeerMessage(
+			CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::PeerMessage(
 				peer.clone(),
-				protocol_v1::CollatorProtocolMessage::Declare(
-					collator_pair2.public(),
-					ParaId::from(5),
-					collator_pair2.sign(b"garbage"),
+				VersionedCollatorProtocolMessage::V1(
+					protocol_v1::CollatorProtocolMessage::Declare(
+						collator_pair2.public(),
+						ParaId::from(5),
+						collator_pair2.sign(b"garbage"),
+					),
 				),
 			)),
 		)
@@ -848,10 +1042,12 @@ fn collators_reject_declare_messages() {
 
 /// Run tests on validator response sequence.
 ///
-/// After the first response is done, the passed in lambda will be called with the receiver for the
-/// next response and a sender for giving feedback on the response of the first transmission. After
-/// the lambda has passed it is assumed that the second response is sent, which is checked by this
-/// function.
+/// The distributed collation is too large to be uploaded to both validators in parallel, so it
+/// is only pushed to the first validator and the second one has to request it.
+///
+/// After the push was sent, the passed in lambda will be called with the receiver for the
+/// response to the second validator and a sender for finishing the push. After the lambda has
+/// passed it is assumed that the second response is sent, which is checked by this function.
 ///
 /// The lambda can trigger occasions on which the second response should be sent, like timeouts,
 /// successful completion.
@@ -870,8 +1066,18 @@ where
 
 		setup_system(virtual_overseer, &test_state).await;
 
-		let DistributeCollation { candidate, pov_block } =
-			distribute_collation(virtual_overseer, &test_state, true).await;
+		// The PoV is too large to be uploaded to more than one validator at a time.
+		let pov_block = PoV { block_data: BlockData(vec![42; LARGE_POV_SIZE]) };
+		let candidate = make_candidate(&test_state, &pov_block);
+		let DistributeCollation { candidate, pov_block } = distribute_collation_with(
+			virtual_overseer,
+			&test_state,
+			true,
+			candidate,
+			pov_block,
+			None,
+		)
+		.await;
 
 		for (val, peer) in test_state
 			.current_group_validator_authority_ids()
@@ -896,20 +1102,23 @@ where
 		send_peer_view_change(virtual_overseer, &validator_1, vec![test_state.relay_parent]).await;
 
 		// The peer is interested in a leaf that we have a collation for;
-		// advertise it.
+		// advertise it and push it to the first validator only.
 		expect_advertise_collation_msg(virtual_overseer, &validator_0, test_state.relay_parent)
 			.await;
+		let (push, push_response) = expect_collation_push(virtual_overseer, &validator_0).await;
+		assert_eq!(push.receipt, candidate);
+		assert_eq!(push.pov, pov_block);
 		expect_advertise_collation_msg(virtual_overseer, &validator_1, test_state.relay_parent)
 			.await;
 
-		// Request a collation.
+		// Let the second validator request the collation.
 		let (pending_response, rx) = oneshot::channel();
 		req_cfg
 			.inbound_queue
 			.as_mut()
 			.unwrap()
 			.send(RawIncomingRequest {
-				peer: validator_0,
+				peer: validator_1,
 				payload: CollationFetchingRequest {
 					relay_parent: test_state.relay_parent,
 					para_id: test_state.para_id,
@@ -920,8 +1129,22 @@ where
 			.await
 			.unwrap();
 
-		// Keep the feedback channel alive because we need to use it to inform about the finished transfer.
-		let feedback_tx = assert_matches!(
+		// Answer the push once the lambda finishes it, keep it pending otherwise.
+		let (feedback_tx, feedback_rx) = oneshot::channel();
+		let finish_push = async move {
+			match feedback_rx.await {
+				Ok(()) => {
+					let _ = push_response.send(Ok(CollationPushingResponse::Received.encode()));
+					None
+				},
+				Err(_) => Some(push_response),
+			}
+		};
+		let (rx, _push_response) =
+			future::join(handle_first_response(rx, feedback_tx), finish_push).await;
+
+		// Now we should send it to the second validator
+		assert_matches!(
 			rx.await,
 			Ok(full_response) => {
 				let CollationFetchingResponse::Collation(receipt, pov): CollationFetchingResponse
@@ -937,14 +1160,45 @@ where
 			}
 		);
 
-		// Let the second validator request the collation.
+		test_harness
+	});
+}
+
+/// Connect the validators of the current group, fail the pushes to the first two and let them
+/// request the collation.
+///
+/// Returns the receivers for the responses of both validators.
+async fn connect_and_request_from_two_validators(
+	virtual_overseer: &mut VirtualOverseer,
+	req_cfg: &mut sc_network::config::RequestResponseConfig,
+	test_state: &TestState,
+) -> Vec<oneshot::Receiver<sc_network::config::OutgoingResponse>> {
+	for (val, peer) in test_state
+		.current_group_validator_authority_ids()
+		.into_iter()
+		.zip(test_state.current_group_validator_peer_ids())
+	{
+		connect_peer(virtual_overseer, peer.clone(), Some(val.clone())).await;
+	}
+
+	for peer_id in test_state.current_group_validator_peer_ids() {
+		expect_declare_msg(virtual_overseer, test_state, &peer_id).await;
+	}
+
+	let mut receivers = Vec::new();
+	for peer in test_state.current_group_validator_peer_ids().into_iter().take(2) {
+		send_peer_view_change(virtual_overseer, &peer, vec![test_state.relay_parent]).await;
+		expect_advertise_collation_msg(virtual_overseer, &peer, test_state.relay_parent).await;
+		// Dropping the response sender fails the push.
+		expect_collation_push(virtual_overseer, &peer).await;
+
 		let (pending_response, rx) = oneshot::channel();
 		req_cfg
 			.inbound_queue
 			.as_mut()
 			.unwrap()
 			.send(RawIncomingRequest {
-				peer: validator_1,
+				peer,
 				payload: CollationFetchingRequest {
 					relay_parent: test_state.relay_parent,
 					para_id: test_state.para_id,
@@ -954,25 +1208,150 @@ where
 			})
 			.await
 			.unwrap();
+		receivers.push(rx);
+	}
 
-		let rx = handle_first_response(rx, feedback_tx).await;
+	receivers
+}
 
-		// Now we should send it to the second validator
-		assert_matches!(
-			rx.await,
-			Ok(full_response) => {
-				let CollationFetchingResponse::Collation(receipt, pov): CollationFetchingResponse
-					= CollationFetchingResponse::decode(
-						&mut full_response.result
-						.expect("We should have a proper answer").as_ref()
-				)
-				.expect("Decoding should work");
-				assert_eq!(receipt, candidate);
-				assert_eq!(pov, pov_block);
+#[test]
+fn small_collations_are_sent_to_multiple_validators_in_parallel() {
+	let test_state = TestState::default();
+	let local_peer_id = test_state.local_peer_id.clone();
+	let collator_pair = test_state.collator_pair.clone();
 
-				full_response.sent_feedback.expect("Feedback channel is always set")
+	test_harness(local_peer_id, collator_pair, |mut test_harness| async move {
+		let virtual_overseer = &mut test_harness.virtual_overseer;
+		let req_cfg = &mut test_harness.req_cfg;
+
+		setup_system(virtual_overseer, &test_state).await;
+
+		distribute_collation(virtual_overseer, &test_state, true).await;
+
+		let mut receivers =
+			connect_and_request_from_two_validators(virtual_overseer, req_cfg, &test_state).await;
+
+		// Keep the feedback channels alive, so neither upload is considered finished.
+		let mut feedback = Vec::new();
+		for rx in receivers.drain(..) {
+			let response = rx
+				.timeout(MAX_UNSHARED_UPLOAD_TIME / 2)
+				.await
+				.expect("Collation is sent without waiting for the other upload")
+				.unwrap();
+			feedback.push(response.sent_feedback.expect("Feedback channel is always set"));
+		}
+
+		test_harness
+	});
+}
+
+#[test]
+fn collation_seconded_signal_is_sent_on_first_seconding() {
+	let test_state = TestState::default();
+	let local_peer_id = test_state.local_peer_id.clone();
+	let collator_pair = test_state.collator_pair.clone();
+
+	test_harness(local_peer_id, collator_pair, |mut test_harness| async move {
+		let virtual_overseer = &mut test_harness.virtual_overseer;
+		let req_cfg = &mut test_harness.req_cfg;
+
+		setup_system(virtual_overseer, &test_state).await;
+
+		let pov_block = PoV { block_data: BlockData(vec![42, 43, 44]) };
+		let committed = CommittedCandidateReceipt {
+			descriptor: make_candidate(&test_state, &pov_block).descriptor,
+			commitments: Default::default(),
+		};
+		let (result_sender, result_receiver) = oneshot::channel();
+		distribute_collation_with(
+			virtual_overseer,
+			&test_state,
+			true,
+			committed.to_plain(),
+			pov_block,
+			Some(result_sender),
+		)
+		.await;
+
+		let receivers =
+			connect_and_request_from_two_validators(virtual_overseer, req_cfg, &test_state).await;
+		for rx in receivers {
+			let response = rx.await.unwrap();
+			let _ = response.sent_feedback.expect("Feedback channel is always set").send(());
+		}
+
+		let keystore: SyncCryptoStorePtr = Arc::new(TestKeyStore::new());
+		let signing_context = SigningContext {
+			session_index: test_state.current_session_index(),
+			parent_hash: test_state.relay_parent,
+		};
+		let seconding = [
+			(Sr25519Keyring::Charlie, ValidatorIndex(2)),
+			(Sr25519Keyring::Alice, ValidatorIndex(0)),
+		];
+		let peers = test_state.current_group_validator_peer_ids();
+
+		let mut result_receiver = Some(result_receiver);
+		let mut signal = None;
+		for ((keyring, validator_index), peer) in seconding.iter().zip(peers) {
+			let public = SyncCryptoStore::sr25519_generate_new(
+				&*keystore,
+				polkadot_primitives::v2::PARACHAIN_KEY_TYPE_ID,
+				Some(&keyring.to_seed()),
+			)
+			.unwrap();
+			let statement = SignedFullStatement::sign(
+				&keystore,
+				Statement::Seconded(committed.clone()),
+				&signing_context,
+				*validator_index,
+				&public.into(),
+			)
+			.await
+			.ok()
+			.flatten()
+			.expect("should be signed");
+
+			overseer_send(
+				virtual_overseer,
+				CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::PeerMessage(
+					peer,
+					VersionedCollatorProtocolMessage::V2(
+						protocol_v2::CollatorProtocolMessage::CollationSeconded(
+							test_state.relay_parent,
+							statement.into_unchecked(),
+						),
+					),
+				)),
+			)
+			.await;
+
+			// The signal is sent right away, without waiting for the other validator.
+			if let Some(result_receiver) = result_receiver.take() {
+				signal = Some(
+					result_receiver
+						.timeout(TIMEOUT)
+						.await
+						.expect("Signal is sent on the first seconding")
+						.unwrap(),
+				);
 			}
-		);
+		}
+
+		let signal = signal.unwrap();
+		assert_eq!(signal.relay_parent, test_state.relay_parent);
+		assert_eq!(signal.statement.validator_index(), ValidatorIndex(2));
+
+		// The stream of later validators ends once both validators we uploaded the collation to
+		// seconded it.
+		let further_seconded_by = signal
+			.further_seconded_by
+			.collect::<Vec<_>>()
+			.timeout(TIMEOUT)
+			.await
+			.expect("Stream ends once all validators seconded");
+		assert_eq!(further_seconded_by, vec![ValidatorIndex(0)]);
 
 		test_harness
 	});
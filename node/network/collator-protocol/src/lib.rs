@@ -21,7 +21,7 @@
 #![deny(unused_crate_dependencies)]
 #![recursion_limit = "256"]
 
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 
 use futures::{FutureExt, TryFutureExt};
 
@@ -31,6 +31,7 @@ use polkadot_node_network_protocol::{
 	request_response::{v1 as request_v1, IncomingRequestReceiver},
 	PeerId, UnifiedReputationChange as Rep,
 };
+use polkadot_node_subsystem_util::database::Database;
 use polkadot_primitives::v2::CollatorPair;
 
 use polkadot_subsystem::{
@@ -44,6 +45,8 @@ mod error;
 mod collator_side;
 mod validator_side;
 
+pub use validator_side::{CollationFetchLimits, CollationFetchPolicy, CollatorReputationConfig};
+
 const LOG_TARGET: &'static str = "parachain::collator-protocol";
 
 /// A collator eviction policy - how fast to evict collators which are inactive.
@@ -72,6 +75,14 @@ pub enum ProtocolSide {
 		keystore: SyncCryptoStorePtr,
 		/// An eviction policy for inactive peers or validators.
 		eviction_policy: CollatorEvictionPolicy,
+		/// The database the scores of collators are persisted in.
+		db: Arc<dyn Database>,
+		/// Configuration of the persistent scores of collators.
+		reputation_config: CollatorReputationConfig,
+		/// The policy limiting the collations fetched.
+		fetch_policy: Box<dyn CollationFetchPolicy>,
+		/// Receiver for collations pushed by collators.
+		push_receiver: IncomingRequestReceiver<request_v1::CollationPushingRequest>,
 		/// Prometheus metrics for validators.
 		metrics: validator_side::Metrics,
 	},
@@ -104,8 +115,25 @@ impl CollatorProtocolSubsystem {
 		Context: SubsystemContext<Message = CollatorProtocolMessage>,
 	{
 		match self.protocol_side {
-			ProtocolSide::Validator { keystore, eviction_policy, metrics } =>
-				validator_side::run(ctx, keystore, eviction_policy, metrics).await,
+			ProtocolSide::Validator {
+				keystore,
+				eviction_policy,
+				db,
+				reputation_config,
+				fetch_policy,
+				push_receiver,
+				metrics,
+			} =>
+				validator_side::run(
+					ctx,
+					keystore,
+					eviction_policy,
+					validator_side::ReputationStore::new(db, reputation_config),
+					fetch_policy,
+					push_receiver,
+					metrics,
+				)
+				.await,
 			ProtocolSide::Collator(local_peer_id, collator_pair, req_receiver, metrics) =>
 				collator_side::run(ctx, local_peer_id, collator_pair, req_receiver, metrics).await,
 		}
@@ -0,0 +1,72 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Policies deciding which collations a validator fetches.
+//!
+//! Fetching collations is the most bandwidth intensive part of the validator side of the
+//! protocol. The policy is consulted before every fetch, so the collators of a single para
+//! can't saturate the bandwidth of a validator, and once a collation has been fetched, to
+//! reject oversized PoVs.
+
+use polkadot_primitives::v2::{Id as ParaId, MAX_POV_SIZE};
+
+/// A policy limiting the collations fetched by a validator.
+pub trait CollationFetchPolicy: Send {
+	/// Whether a collation of `para_id` may be fetched, given the number of fetches in flight
+	/// for that para and in total.
+	fn can_fetch(&self, para_id: ParaId, para_fetches: usize, total_fetches: usize) -> bool;
+
+	/// The maximum encoded size of a PoV fetched for `para_id`.
+	fn max_pov_size(&self, para_id: ParaId) -> usize;
+}
+
+/// The default [`CollationFetchPolicy`], limiting the number of concurrent fetches per para
+/// and in total, as well as the size of fetched PoVs.
+#[derive(Debug, Clone)]
+pub struct CollationFetchLimits {
+	/// The maximum number of concurrent fetches for a single para.
+	pub max_fetches_per_para: usize,
+	/// The maximum number of concurrent fetches across all paras.
+	pub max_fetches: usize,
+	/// The maximum encoded size of a fetched PoV.
+	pub max_pov_size: usize,
+}
+
+impl Default for CollationFetchLimits {
+	fn default() -> Self {
+		CollationFetchLimits {
+			max_fetches_per_para: 3,
+			max_fetches: 10,
+			max_pov_size: MAX_POV_SIZE as usize,
+		}
+	}
+}
+
+impl CollationFetchPolicy for CollationFetchLimits {
+	fn can_fetch(&self, _para_id: ParaId, para_fetches: usize, total_fetches: usize) -> bool {
+		para_fetches < self.max_fetches_per_para && total_fetches < self.max_fetches
+	}
+
+	fn max_pov_size(&self, _para_id: ParaId) -> usize {
+		self.max_pov_size
+	}
+}
+
+impl Default for Box<dyn CollationFetchPolicy> {
+	fn default() -> Self {
+		Box::new(CollationFetchLimits::default())
+	}
+}
@@ -15,14 +15,16 @@
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
 use always_assert::never;
+use fatality::Split;
 use futures::{
 	channel::oneshot,
 	future::{BoxFuture, Fuse, FusedFuture},
-	select,
+	pin_mut, select,
 	stream::{FusedStream, FuturesUnordered},
 	FutureExt, StreamExt,
 };
 use futures_timer::Delay;
+use parity_scale_codec::Encode;
 use std::{
 	collections::{hash_map::Entry, HashMap, HashSet},
 	sync::Arc,
@@ -36,15 +38,22 @@ use polkadot_node_network_protocol::{
 	peer_set::PeerSet,
 	request_response as req_res,
 	request_response::{
+		incoming::{self, OutgoingResponse},
 		outgoing::{Recipient, RequestError},
-		v1::{CollationFetchingRequest, CollationFetchingResponse},
-		OutgoingRequest, Requests,
+		v1::{
+			CollationFetchingRequest, CollationFetchingResponse, CollationPushingRequest,
+			CollationPushingResponse,
+		},
+		IncomingRequest, IncomingRequestReceiver, OutgoingRequest, Requests,
 	},
-	v1 as protocol_v1, OurView, PeerId, UnifiedReputationChange as Rep, View,
+	v1 as protocol_v1, v2 as protocol_v2, OurView, PeerId, UnifiedReputationChange as Rep,
+	VersionedCollatorProtocolMessage, View,
 };
 use polkadot_node_primitives::{PoV, SignedFullStatement};
 use polkadot_node_subsystem_util::metrics::{self, prometheus};
-use polkadot_primitives::v2::{CandidateReceipt, CollatorId, Hash, Id as ParaId};
+use polkadot_primitives::v2::{
+	CandidateHash, CandidateReceipt, CollatorId, Hash, Id as ParaId, OccupiedCoreAssumption,
+};
 use polkadot_subsystem::{
 	jaeger,
 	messages::{
@@ -58,10 +67,19 @@ use crate::error::Result;
 
 use super::{modify_reputation, LOG_TARGET};
 
+mod fetch_policy;
+mod reputation;
+
+pub use fetch_policy::{CollationFetchLimits, CollationFetchPolicy};
+use reputation::CollationOutcome;
+pub use reputation::CollatorReputationConfig;
+pub(crate) use reputation::ReputationStore;
+
 #[cfg(test)]
 mod tests;
 
 const COST_UNEXPECTED_MESSAGE: Rep = Rep::CostMinor("An unexpected message");
+const COST_INVALID_REQUEST: Rep = Rep::CostMajor("Peer sent unparsable request");
 /// Message could not be decoded properly.
 const COST_CORRUPTED_MESSAGE: Rep = Rep::CostMinor("Message was corrupt");
 /// Network errors that originated at the remote host should have same cost as timeout.
@@ -70,6 +88,11 @@ const COST_INVALID_SIGNATURE: Rep = Rep::Malicious("Invalid network message sign
 const COST_REPORT_BAD: Rep = Rep::Malicious("A collator was reported by another subsystem");
 const COST_WRONG_PARA: Rep = Rep::Malicious("A collator provided a collation for the wrong para");
 const COST_UNNEEDED_COLLATOR: Rep = Rep::CostMinor("An unneeded collator connected");
+const COST_OVERSIZED_POV: Rep = Rep::CostMinor("A collator provided an oversized PoV");
+const COST_WRONG_CANDIDATE: Rep =
+	Rep::Malicious("A collator provided a collation not matching its advertisement");
+const COST_STALE_ADVERTISEMENT: Rep =
+	Rep::CostMinor("A collator advertised a collation not building on the para head");
 const BENEFIT_NOTIFY_GOOD: Rep =
 	Rep::BenefitMinor("A collator was noted good by another subsystem");
 
@@ -86,6 +109,9 @@ const BENEFIT_NOTIFY_GOOD: Rep =
 /// There is debug logging output, so we can adjust this value based on production results.
 const MAX_UNSHARED_DOWNLOAD_TIME: Duration = Duration::from_millis(400);
 
+/// Allowance for the candidate receipt in a collation response, on top of the maximum PoV size.
+const MAX_CANDIDATE_RECEIPT_OVERHEAD: usize = 10_000;
+
 // How often to check all peers with activity.
 #[cfg(not(test))]
 const ACTIVITY_POLL: Duration = Duration::from_secs(1);
@@ -98,6 +124,9 @@ const ACTIVITY_POLL: Duration = Duration::from_millis(10);
 // See https://github.com/paritytech/polkadot/issues/4182
 const CHECK_COLLATIONS_POLL: Duration = Duration::from_millis(5);
 
+/// How often updated collator scores are written to the DB.
+const REPUTATION_FLUSH_INTERVAL: Duration = Duration::from_secs(30);
+
 #[derive(Clone, Default)]
 pub struct Metrics(Option<MetricsInner>);
 
@@ -138,6 +167,15 @@ impl Metrics {
 	) -> Option<metrics::prometheus::prometheus::HistogramTimer> {
 		self.0.as_ref().map(|metrics| metrics.collation_request_duration.start_timer())
 	}
+
+	/// Note the outcome of a collation fetched from a collator, along with the number of
+	/// collators scored.
+	fn on_collation_outcome(&self, outcome: CollationOutcome, scored_collators: usize) {
+		if let Some(metrics) = &self.0 {
+			metrics.collation_outcomes.with_label_values(&[outcome.as_str()]).inc();
+			metrics.scored_collators.set(scored_collators as u64);
+		}
+	}
 }
 
 #[derive(Clone)]
@@ -147,6 +185,8 @@ struct MetricsInner {
 	handle_collation_request_result: prometheus::Histogram,
 	collator_peer_count: prometheus::Gauge<prometheus::U64>,
 	collation_request_duration: prometheus::Histogram,
+	collation_outcomes: prometheus::CounterVec<prometheus::U64>,
+	scored_collators: prometheus::Gauge<prometheus::U64>,
 }
 
 impl metrics::Metrics for Metrics {
@@ -198,6 +238,23 @@ impl metrics::Metrics for Metrics {
 				)?,
 				registry,
 			)?,
+			collation_outcomes: prometheus::register(
+				prometheus::CounterVec::new(
+					prometheus::Opts::new(
+						"polkadot_parachain_collator_protocol_validator_collation_outcomes_total",
+						"Number of outcomes of fetched collations recorded in the scores of collators.",
+					),
+					&["outcome"],
+				)?,
+				registry,
+			)?,
+			scored_collators: prometheus::register(
+				prometheus::Gauge::new(
+					"polkadot_parachain_collator_protocol_validator_scored_collators",
+					"Number of collators with a persisted score for the paras we are assigned to.",
+				)?,
+				registry,
+			)?,
 		};
 
 		Ok(Metrics(Some(metrics)))
@@ -350,6 +407,36 @@ impl Default for PeerData {
 
 struct GroupAssignments {
 	current: Option<ParaId>,
+	/// The hashes of the head data a collation for the current para may build on, `None` if
+	/// they could not be obtained.
+	parent_head_data_hashes: Option<Vec<Hash>>,
+}
+
+/// The hashes of the head data a collation for `para_id` at `relay_parent` may build on.
+///
+/// These are the para heads assuming the candidate pending availability, if any, is included
+/// or timed out, just like candidate validation does.
+async fn parent_head_data_hashes(
+	sender: &mut impl SubsystemSender,
+	relay_parent: Hash,
+	para_id: ParaId,
+) -> Option<Vec<Hash>> {
+	let mut hashes = Vec::new();
+	for assumption in [OccupiedCoreAssumption::Included, OccupiedCoreAssumption::TimedOut] {
+		let validation_data = polkadot_node_subsystem_util::request_persisted_validation_data(
+			relay_parent,
+			para_id,
+			assumption,
+			sender,
+		)
+		.await
+		.await
+		.ok()
+		.and_then(|x| x.ok())
+		.flatten()?;
+		hashes.push(validation_data.parent_head.hash());
+	}
+	Some(hashes)
 }
 
 #[derive(Default)]
@@ -426,6 +513,22 @@ impl ActiveParas {
 			//
 			// However, this'll work fine for parachains, as each parachain gets a dedicated
 			// core.
+			let parent_head_data_hashes = match para_now {
+				Some(para_now) => {
+					let hashes = parent_head_data_hashes(sender, relay_parent, para_now).await;
+					if hashes.is_none() {
+						gum::debug!(
+							target: LOG_TARGET,
+							?relay_parent,
+							para_id = ?para_now,
+							"Failed to query the persisted validation data",
+						);
+					}
+					hashes
+				},
+				None => None,
+			};
+
 			if let Some(para_now) = para_now {
 				let entry = self.current_assignments.entry(para_now).or_default();
 				*entry += 1;
@@ -439,15 +542,17 @@ impl ActiveParas {
 				}
 			}
 
-			self.relay_parent_assignments
-				.insert(relay_parent, GroupAssignments { current: para_now });
+			self.relay_parent_assignments.insert(
+				relay_parent,
+				GroupAssignments { current: para_now, parent_head_data_hashes },
+			);
 		}
 	}
 
 	fn remove_outgoing(&mut self, old_relay_parents: impl IntoIterator<Item = Hash>) {
 		for old_relay_parent in old_relay_parents {
 			if let Some(assignments) = self.relay_parent_assignments.remove(&old_relay_parent) {
-				let GroupAssignments { current } = assignments;
+				let GroupAssignments { current, .. } = assignments;
 
 				if let Some(cur) = current {
 					if let Entry::Occupied(mut occupied) = self.current_assignments.entry(cur) {
@@ -469,6 +574,24 @@ impl ActiveParas {
 	fn is_current(&self, id: &ParaId) -> bool {
 		self.current_assignments.contains_key(id)
 	}
+
+	/// Whether a collation for `para_id` at `relay_parent` building on the head data with the
+	/// given hash builds on the para head. `None` if the heads of the para are not known there.
+	fn builds_on_para_head(
+		&self,
+		relay_parent: &Hash,
+		para_id: ParaId,
+		parent_head_data_hash: &Hash,
+	) -> Option<bool> {
+		let assignments = self.relay_parent_assignments.get(relay_parent)?;
+		if assignments.current != Some(para_id) {
+			return None
+		}
+		assignments
+			.parent_head_data_hashes
+			.as_ref()
+			.map(|hashes| hashes.contains(parent_head_data_hash))
+	}
 }
 
 #[derive(Debug, Clone, Hash, Eq, PartialEq)]
@@ -476,15 +599,23 @@ struct PendingCollation {
 	relay_parent: Hash,
 	para_id: ParaId,
 	peer_id: PeerId,
+	/// The candidate hash of the advertisement, if the collator advertised it.
+	candidate_hash: Option<CandidateHash>,
 	commitments_hash: Option<Hash>,
 }
 
 impl PendingCollation {
-	fn new(relay_parent: Hash, para_id: &ParaId, peer_id: &PeerId) -> Self {
+	fn new(
+		relay_parent: Hash,
+		para_id: &ParaId,
+		peer_id: &PeerId,
+		candidate_hash: Option<CandidateHash>,
+	) -> Self {
 		Self {
 			relay_parent,
 			para_id: para_id.clone(),
 			peer_id: peer_id.clone(),
+			candidate_hash,
 			commitments_hash: None,
 		}
 	}
@@ -536,18 +667,25 @@ struct CollationsPerRelayParent {
 	waiting_collation: Option<CollatorId>,
 	/// Collation that were advertised to us, but we did not yet fetch.
 	unfetched_collations: Vec<(PendingCollation, CollatorId)>,
+	/// Candidates we fetched at this relay parent, to not fetch them again from other `v2`
+	/// collators advertising them.
+	fetched_candidates: HashSet<CandidateHash>,
 }
 
 impl CollationsPerRelayParent {
-	/// Returns the next collation to fetch from the `unfetched_collations`.
+	/// Returns the next collation to fetch from the `unfetched_collations`, which is the one
+	/// advertised by the collator with the highest score. Ties are broken in favor of the
+	/// latest advertisement.
 	///
 	/// This will reset the status back to `Waiting` using [`CollationStatus::back_to_waiting`].
 	///
-	/// Returns `Some(_)` if there is any collation to fetch, the `status` is not `Seconded` and
-	/// the passed in `finished_one` is the currently `waiting_collation`.
-	pub fn get_next_collation_to_fetch(
+	/// Returns `Some(_)` if there is any collation `may_fetch` allows to fetch, the `status` is
+	/// not `Seconded` and the passed in `finished_one` is the currently `waiting_collation`.
+	fn get_next_collation_to_fetch(
 		&mut self,
 		finished_one: Option<&CollatorId>,
+		reputation: &mut ReputationStore,
+		may_fetch: impl Fn(ParaId) -> bool,
 	) -> Option<(PendingCollation, CollatorId)> {
 		// If finished one does not match waiting_collation, then we already dequeued another fetch
 		// to replace it.
@@ -566,7 +704,14 @@ impl CollationsPerRelayParent {
 			// We don't need to fetch any other collation when we already have seconded one.
 			CollationStatus::Seconded => None,
 			CollationStatus::Waiting => {
-				let next = self.unfetched_collations.pop();
+				let next = self
+					.unfetched_collations
+					.iter()
+					.enumerate()
+					.filter(|(_, (pc, _))| may_fetch(pc.para_id))
+					.max_by_key(|(_, (pc, collator_id))| reputation.score(pc.para_id, collator_id))
+					.map(|(i, _)| i)
+					.map(|i| self.unfetched_collations.remove(i));
 				self.waiting_collation = next.as_ref().map(|(_, collator_id)| collator_id.clone());
 				next
 			},
@@ -616,6 +761,40 @@ struct State {
 
 	/// Keep track of all pending candidate collations
 	pending_candidates: HashMap<Hash, CollationEvent>,
+
+	/// Persistent scores of collators, used to prioritize their advertisements.
+	reputation: ReputationStore,
+
+	/// The policy limiting the collations we fetch.
+	fetch_policy: Box<dyn CollationFetchPolicy>,
+
+	/// Relay parents with collations to fetch, which the fetch policy didn't allow to fetch
+	/// yet. Retried once fetches finish.
+	deferred_fetches: HashSet<Hash>,
+}
+
+/// Whether the fetch policy allows fetching another collation for `para_id`, given the
+/// collations currently requested.
+fn may_fetch(
+	fetch_policy: &dyn CollationFetchPolicy,
+	requested_collations: &HashMap<PendingCollation, PerRequest>,
+	para_id: ParaId,
+) -> bool {
+	let para_fetches = requested_collations.keys().filter(|pc| pc.para_id == para_id).count();
+	fetch_policy.can_fetch(para_id, para_fetches, requested_collations.len())
+}
+
+/// Record the outcome of a collation in the score of the collator.
+fn note_collation_outcome(
+	state: &mut State,
+	para_id: ParaId,
+	collator_id: CollatorId,
+	outcome: CollationOutcome,
+) {
+	gum::trace!(target: LOG_TARGET, ?para_id, ?collator_id, ?outcome, "Noting collation outcome");
+
+	state.reputation.note_outcome(para_id, collator_id, outcome);
+	state.metrics.on_collation_outcome(outcome, state.reputation.len());
 }
 
 // O(n) search for collator ID by iterating through the peers map. This should be fast enough
@@ -650,7 +829,7 @@ async fn fetch_collation<Context>(
 {
 	let (tx, rx) = oneshot::channel();
 
-	let PendingCollation { relay_parent, para_id, peer_id, .. } = pc;
+	let PendingCollation { relay_parent, para_id, peer_id, candidate_hash, .. } = pc;
 
 	let timeout = |collator_id, relay_parent| async move {
 		Delay::new(MAX_UNSHARED_DOWNLOAD_TIME).await;
@@ -662,7 +841,7 @@ async fn fetch_collation<Context>(
 
 	if let Some(peer_data) = state.peer_data.get(&peer_id) {
 		if peer_data.has_advertised(&relay_parent) {
-			request_collation(ctx, state, relay_parent, para_id, peer_id, tx).await;
+			request_collation(ctx, state, relay_parent, para_id, peer_id, candidate_hash, tx).await;
 		} else {
 			gum::debug!(
 				target: LOG_TARGET,
@@ -723,10 +902,10 @@ async fn notify_collation_seconded<Context>(
 	Context: SubsystemContext<Message = CollatorProtocolMessage>,
 {
 	let wire_message =
-		protocol_v1::CollatorProtocolMessage::CollationSeconded(relay_parent, statement.into());
+		protocol_v2::CollatorProtocolMessage::CollationSeconded(relay_parent, statement.into());
 	ctx.send_message(NetworkBridgeMessage::SendCollationMessage(
 		vec![peer_id],
-		protocol_v1::CollationProtocol::CollatorProtocol(wire_message),
+		protocol_v2::CollationProtocol::CollatorProtocol(wire_message),
 	))
 	.await;
 
@@ -759,6 +938,7 @@ async fn request_collation<Context>(
 	relay_parent: Hash,
 	para_id: ParaId,
 	peer_id: PeerId,
+	candidate_hash: Option<CandidateHash>,
 	result: oneshot::Sender<(CandidateReceipt, PoV)>,
 ) where
 	Context: overseer::SubsystemContext<Message = CollatorProtocolMessage>,
@@ -774,7 +954,7 @@ async fn request_collation<Context>(
 		);
 		return
 	}
-	let pending_collation = PendingCollation::new(relay_parent, &para_id, &peer_id);
+	let pending_collation = PendingCollation::new(relay_parent, &para_id, &peer_id, candidate_hash);
 	if state.requested_collations.contains_key(&pending_collation) {
 		gum::warn!(
 			target: LOG_TARGET,
@@ -786,9 +966,10 @@ async fn request_collation<Context>(
 		return
 	}
 
-	let (full_request, response_recv) = OutgoingRequest::new(
+	let (full_request, response_recv) = OutgoingRequest::new_with_max_response_size(
 		Recipient::Peer(peer_id),
 		CollationFetchingRequest { relay_parent, para_id },
+		state.fetch_policy.max_pov_size(para_id) + MAX_CANDIDATE_RECEIPT_OVERHEAD,
 	);
 	let requests = Requests::CollationFetching(full_request);
 
@@ -802,9 +983,7 @@ async fn request_collation<Context>(
 		_lifetime_timer: state.metrics.time_collation_request_duration(),
 	};
 
-	state
-		.requested_collations
-		.insert(PendingCollation::new(relay_parent, &para_id, &peer_id), per_request);
+	state.requested_collations.insert(pending_collation, per_request);
 
 	gum::debug!(
 		target: LOG_TARGET,
@@ -826,15 +1005,16 @@ async fn process_incoming_peer_message<Context>(
 	ctx: &mut Context,
 	state: &mut State,
 	origin: PeerId,
-	msg: protocol_v1::CollatorProtocolMessage,
+	msg: VersionedCollatorProtocolMessage,
 ) where
 	Context: overseer::SubsystemContext<Message = CollatorProtocolMessage>,
 	Context: SubsystemContext<Message = CollatorProtocolMessage>,
 {
-	use protocol_v1::CollatorProtocolMessage::*;
 	use sp_runtime::traits::AppVerify;
+	use VersionedCollatorProtocolMessage::{V1, V2};
 	match msg {
-		Declare(collator_id, para_id, signature) => {
+		V1(protocol_v1::CollatorProtocolMessage::Declare(collator_id, para_id, signature)) |
+		V2(protocol_v2::CollatorProtocolMessage::Declare(collator_id, para_id, signature)) => {
 			if collator_peer_id(&state.peer_data, &collator_id).is_some() {
 				modify_reputation(ctx, origin, COST_UNEXPECTED_MESSAGE).await;
 				return
@@ -900,101 +1080,336 @@ async fn process_incoming_peer_message<Context>(
 				disconnect_peer(ctx, origin).await;
 			}
 		},
-		AdvertiseCollation(relay_parent) => {
-			let _span = state
-				.span_per_relay_parent
-				.get(&relay_parent)
-				.map(|s| s.child("advertise-collation"));
-			if !state.view.contains(&relay_parent) {
+		V1(protocol_v1::CollatorProtocolMessage::AdvertiseCollation(relay_parent)) =>
+			handle_advertisement(ctx, state, origin, relay_parent, None).await,
+		V2(protocol_v2::CollatorProtocolMessage::AdvertiseCollation {
+			relay_parent,
+			candidate_hash,
+			parent_head_data_hash,
+		}) =>
+			handle_advertisement(
+				ctx,
+				state,
+				origin,
+				relay_parent,
+				Some((candidate_hash, parent_head_data_hash)),
+			)
+			.await,
+		V1(protocol_v1::CollatorProtocolMessage::CollationSeconded(_, _)) |
+		V2(protocol_v2::CollatorProtocolMessage::CollationSeconded(_, _)) => {
+			gum::warn!(
+				target: LOG_TARGET,
+				peer_id = ?origin,
+				"Unexpected `CollationSeconded` message, decreasing reputation",
+			);
+		},
+	}
+}
+
+/// A collation has been advertised to us.
+///
+/// Advertisements of `v2` collators carry the candidate hash, which allows skipping candidates
+/// we already fetched. The fetched collation has to match the advertised candidate hash.
+///
+/// They also carry the hash of the head data the collation builds on. Advertisements of
+/// collations not building on the para head at the relay parent are stale and not fetched.
+async fn handle_advertisement<Context>(
+	ctx: &mut Context,
+	state: &mut State,
+	origin: PeerId,
+	relay_parent: Hash,
+	v2_advertisement: Option<(CandidateHash, Hash)>,
+) where
+	Context: overseer::SubsystemContext<Message = CollatorProtocolMessage>,
+	Context: SubsystemContext<Message = CollatorProtocolMessage>,
+{
+	let _span = state
+		.span_per_relay_parent
+		.get(&relay_parent)
+		.map(|s| s.child("advertise-collation"));
+	if !state.view.contains(&relay_parent) {
+		gum::debug!(
+			target: LOG_TARGET,
+			peer_id = ?origin,
+			?relay_parent,
+			"Advertise collation out of view",
+		);
+
+		modify_reputation(ctx, origin, COST_UNEXPECTED_MESSAGE).await;
+		return
+	}
+
+	let peer_data = match state.peer_data.get_mut(&origin) {
+		None => {
+			gum::debug!(
+				target: LOG_TARGET,
+				peer_id = ?origin,
+				?relay_parent,
+				"Advertise collation message has been received from an unknown peer",
+			);
+			modify_reputation(ctx, origin, COST_UNEXPECTED_MESSAGE).await;
+			return
+		},
+		Some(p) => p,
+	};
+
+	let candidate_hash = v2_advertisement.map(|(candidate_hash, _)| candidate_hash);
+	if let (Some(para_id), Some((_, parent_head_data_hash))) =
+		(peer_data.collating_para(), v2_advertisement)
+	{
+		let builds_on_para_head =
+			state
+				.active_paras
+				.builds_on_para_head(&relay_parent, para_id, &parent_head_data_hash);
+		if builds_on_para_head == Some(false) {
+			gum::debug!(
+				target: LOG_TARGET,
+				peer_id = ?origin,
+				%para_id,
+				?relay_parent,
+				?parent_head_data_hash,
+				"Advertised collation does not build on the para head",
+			);
+			modify_reputation(ctx, origin, COST_STALE_ADVERTISEMENT).await;
+			return
+		}
+	}
+
+	match peer_data.insert_advertisement(relay_parent, &state.view) {
+		Ok((id, para_id)) => {
+			gum::debug!(
+				target: LOG_TARGET,
+				peer_id = ?origin,
+				%para_id,
+				?relay_parent,
+				"Received advertise collation",
+			);
+
+			let collations = state.collations_per_relay_parent.entry(relay_parent).or_default();
+			if let Some(candidate_hash) =
+				candidate_hash.filter(|hash| collations.fetched_candidates.contains(hash))
+			{
 				gum::debug!(
 					target: LOG_TARGET,
 					peer_id = ?origin,
+					%para_id,
 					?relay_parent,
-					"Advertise collation out of view",
+					?candidate_hash,
+					"Candidate was already fetched from another collator",
 				);
-
-				modify_reputation(ctx, origin, COST_UNEXPECTED_MESSAGE).await;
 				return
 			}
 
-			let peer_data = match state.peer_data.get_mut(&origin) {
-				None => {
-					gum::debug!(
+			let pending_collation =
+				PendingCollation::new(relay_parent, &para_id, &origin, candidate_hash);
+			let may_fetch = may_fetch(&*state.fetch_policy, &state.requested_collations, para_id);
+
+			let collations = state.collations_per_relay_parent.entry(relay_parent).or_default();
+
+			match collations.status {
+				CollationStatus::Fetching | CollationStatus::WaitingOnValidation => {
+					gum::trace!(
 						target: LOG_TARGET,
 						peer_id = ?origin,
+						%para_id,
 						?relay_parent,
-						"Advertise collation message has been received from an unknown peer",
+						"Added collation to the pending list"
 					);
-					modify_reputation(ctx, origin, COST_UNEXPECTED_MESSAGE).await;
-					return
+					collations.unfetched_collations.push((pending_collation, id));
 				},
-				Some(p) => p,
-			};
-
-			match peer_data.insert_advertisement(relay_parent, &state.view) {
-				Ok((id, para_id)) => {
+				CollationStatus::Waiting if !may_fetch => {
 					gum::debug!(
 						target: LOG_TARGET,
 						peer_id = ?origin,
 						%para_id,
 						?relay_parent,
-						"Received advertise collation",
+						"Fetch limit reached, deferring the fetch of the collation"
 					);
+					collations.unfetched_collations.push((pending_collation, id));
+					state.deferred_fetches.insert(relay_parent);
+				},
+				CollationStatus::Waiting => {
+					collations.status = CollationStatus::Fetching;
+					collations.waiting_collation = Some(id.clone());
 
-					let pending_collation = PendingCollation::new(relay_parent, &para_id, &origin);
-
-					let collations =
-						state.collations_per_relay_parent.entry(relay_parent).or_default();
-
-					match collations.status {
-						CollationStatus::Fetching | CollationStatus::WaitingOnValidation => {
-							gum::trace!(
-								target: LOG_TARGET,
-								peer_id = ?origin,
-								%para_id,
-								?relay_parent,
-								"Added collation to the pending list"
-							);
-							collations.unfetched_collations.push((pending_collation, id));
-						},
-						CollationStatus::Waiting => {
-							collations.status = CollationStatus::Fetching;
-							collations.waiting_collation = Some(id.clone());
-
-							fetch_collation(ctx, state, pending_collation.clone(), id).await;
-						},
-						CollationStatus::Seconded => {
-							gum::trace!(
-								target: LOG_TARGET,
-								peer_id = ?origin,
-								%para_id,
-								?relay_parent,
-								"Valid seconded collation"
-							);
-						},
-					}
+					fetch_collation(ctx, state, pending_collation.clone(), id).await;
 				},
-				Err(error) => {
-					gum::debug!(
+				CollationStatus::Seconded => {
+					gum::trace!(
 						target: LOG_TARGET,
 						peer_id = ?origin,
+						%para_id,
 						?relay_parent,
-						?error,
-						"Invalid advertisement",
+						"Valid seconded collation"
 					);
-
-					modify_reputation(ctx, origin, COST_UNEXPECTED_MESSAGE).await;
 				},
 			}
 		},
-		CollationSeconded(_, _) => {
-			gum::warn!(
+		Err(error) => {
+			gum::debug!(
 				target: LOG_TARGET,
 				peer_id = ?origin,
-				"Unexpected `CollationSeconded` message, decreasing reputation",
+				?relay_parent,
+				?error,
+				"Invalid advertisement",
+			);
+
+			modify_reputation(ctx, origin, COST_UNEXPECTED_MESSAGE).await;
+		},
+	}
+}
+
+/// Check a collation pushed to us, returning the collator which pushed it if we accept it or
+/// the reputation change to apply to the peer otherwise.
+///
+/// Pushed collations are only accepted from collators declared for the para of the collation.
+fn check_pushed_collation(
+	state: &State,
+	origin: &PeerId,
+	receipt: &CandidateReceipt,
+	pov: &PoV,
+) -> std::result::Result<CollatorId, Option<Rep>> {
+	let relay_parent = receipt.descriptor.relay_parent;
+	let para_id = receipt.descriptor.para_id;
+
+	if !state.view.contains(&relay_parent) {
+		gum::debug!(
+			target: LOG_TARGET,
+			peer_id = ?origin,
+			?relay_parent,
+			"Pushed collation out of view",
+		);
+		return Err(Some(COST_UNEXPECTED_MESSAGE))
+	}
+
+	let collator = state
+		.peer_data
+		.get(origin)
+		.and_then(|peer_data| peer_data.collator_id().cloned().zip(peer_data.collating_para()));
+	let collator_id = match collator {
+		Some((collator_id, collating_para)) if collating_para == para_id => collator_id,
+		Some(_) => {
+			gum::debug!(
+				target: LOG_TARGET,
+				peer_id = ?origin,
+				%para_id,
+				"Collator pushed a collation for the wrong para",
 			);
+			return Err(Some(COST_WRONG_PARA))
 		},
+		// The push might have overtaken the declaration of the collator, which can still let us
+		// fetch the collation after advertising it.
+		None => {
+			gum::debug!(
+				target: LOG_TARGET,
+				peer_id = ?origin,
+				%para_id,
+				"Collation pushed by an undeclared collator",
+			);
+			return Err(None)
+		},
+	};
+
+	let max_pov_size = state.fetch_policy.max_pov_size(para_id);
+	if pov.encoded_size() > max_pov_size {
+		gum::debug!(
+			target: LOG_TARGET,
+			peer_id = ?origin,
+			%para_id,
+			pov_size = pov.encoded_size(),
+			max_pov_size,
+			"Collator pushed an oversized PoV",
+		);
+		return Err(Some(COST_OVERSIZED_POV))
+	}
+
+	// Pushed collations are subject to the same limits as fetched ones, the collator can still
+	// advertise the collation, which we fetch once the fetch policy allows for it.
+	if !may_fetch(&*state.fetch_policy, &state.requested_collations, para_id) {
+		gum::debug!(
+			target: LOG_TARGET,
+			peer_id = ?origin,
+			%para_id,
+			?relay_parent,
+			"Fetch limit reached, declining the pushed collation",
+		);
+		return Err(None)
 	}
+
+	Ok(collator_id)
+}
+
+/// A collator pushed a collation to us, without us requesting it.
+///
+/// Accepted collations are only used while we are still waiting for a collation at their relay
+/// parent. They are then handled like fetched collations, so we don't fetch the same candidate
+/// after its advertisement.
+async fn handle_pushed_collation<Context>(
+	ctx: &mut Context,
+	state: &mut State,
+	req: IncomingRequest<CollationPushingRequest>,
+) where
+	Context: overseer::SubsystemContext<Message = CollatorProtocolMessage>,
+	Context: SubsystemContext<Message = CollatorProtocolMessage>,
+{
+	let IncomingRequest { peer: origin, payload, pending_response } = req;
+	let CollationPushingRequest { receipt, pov } = payload;
+
+	let collator_id = match check_pushed_collation(state, &origin, &receipt, &pov) {
+		Ok(collator_id) => {
+			let _ = pending_response.send_response(CollationPushingResponse::Received);
+			collator_id
+		},
+		Err(rep) => {
+			let _ = pending_response.send_outgoing_response(OutgoingResponse {
+				result: Err(()),
+				reputation_changes: rep.into_iter().collect(),
+				sent_feedback: None,
+			});
+			return
+		},
+	};
+
+	let relay_parent = receipt.descriptor.relay_parent;
+	let para_id = receipt.descriptor.para_id;
+	let candidate_hash = receipt.hash();
+
+	let collations = state.collations_per_relay_parent.entry(relay_parent).or_default();
+	if !matches!(collations.status, CollationStatus::Waiting) ||
+		collations.fetched_candidates.contains(&candidate_hash)
+	{
+		gum::debug!(
+			target: LOG_TARGET,
+			peer_id = ?origin,
+			%para_id,
+			?relay_parent,
+			?candidate_hash,
+			status = ?collations.status,
+			"Ignoring pushed collation",
+		);
+		return
+	}
+
+	gum::debug!(
+		target: LOG_TARGET,
+		peer_id = ?origin,
+		%para_id,
+		?relay_parent,
+		?candidate_hash,
+		"Received pushed collation",
+	);
+
+	collations.status = CollationStatus::Fetching;
+	collations.waiting_collation = Some(collator_id.clone());
+
+	let pending_collation =
+		PendingCollation::new(relay_parent, &para_id, &origin, Some(candidate_hash));
+	handle_collation_fetched_result(
+		ctx,
+		state,
+		((collator_id, pending_collation), Ok((receipt, pov))),
+	)
+	.await;
 }
 
 /// A leaf has become inactive so we want to
@@ -1073,7 +1488,7 @@ async fn handle_network_msg<Context>(
 	ctx: &mut Context,
 	state: &mut State,
 	keystore: &SyncCryptoStorePtr,
-	bridge_message: NetworkBridgeEvent<protocol_v1::CollatorProtocolMessage>,
+	bridge_message: NetworkBridgeEvent<VersionedCollatorProtocolMessage>,
 ) -> Result<()>
 where
 	Context: overseer::SubsystemContext<Message = CollatorProtocolMessage>,
@@ -1082,7 +1497,7 @@ where
 	use NetworkBridgeEvent::*;
 
 	match bridge_message {
-		PeerConnected(peer_id, _role, _) => {
+		PeerConnected(peer_id, _role, _, _) => {
 			state.peer_data.entry(peer_id).or_default();
 			state.metrics.note_collator_peer_count(state.peer_data.len());
 		},
@@ -1129,7 +1544,7 @@ async fn process_msg<Context>(
 				"CollateOn message is not expected on the validator side of the protocol",
 			);
 		},
-		DistributeCollation(_, _, _) => {
+		DistributeCollation(_, _, _, _) => {
 			gum::warn!(
 				target: LOG_TARGET,
 				"DistributeCollation message is not expected on the validator side of the protocol",
@@ -1138,7 +1553,7 @@ async fn process_msg<Context>(
 		ReportCollator(id) => {
 			report_collator(ctx, &state.peer_data, id).await;
 		},
-		NetworkBridgeUpdateV1(event) => {
+		NetworkBridgeUpdate(event) => {
 			if let Err(e) = handle_network_msg(ctx, state, keystore, event).await {
 				gum::warn!(
 					target: LOG_TARGET,
@@ -1150,7 +1565,13 @@ async fn process_msg<Context>(
 		Seconded(parent, stmt) => {
 			if let Some(collation_event) = state.pending_candidates.remove(&parent) {
 				let (collator_id, pending_collation) = collation_event;
-				let PendingCollation { relay_parent, peer_id, .. } = pending_collation;
+				let PendingCollation { relay_parent, peer_id, para_id, .. } = pending_collation;
+				note_collation_outcome(
+					state,
+					para_id,
+					collator_id.clone(),
+					CollationOutcome::Seconded,
+				);
 				note_good_collation(ctx, &state.peer_data, collator_id).await;
 				notify_collation_seconded(ctx, peer_id, relay_parent, stmt).await;
 
@@ -1166,11 +1587,11 @@ async fn process_msg<Context>(
 			}
 		},
 		Invalid(parent, candidate_receipt) => {
-			let id = match state.pending_candidates.entry(parent) {
+			let (id, pending_collation) = match state.pending_candidates.entry(parent) {
 				Entry::Occupied(entry)
 					if entry.get().1.commitments_hash ==
 						Some(candidate_receipt.commitments_hash) =>
-					entry.remove().0,
+					entry.remove(),
 				Entry::Occupied(_) => {
 					gum::error!(
 						target: LOG_TARGET,
@@ -1183,9 +1604,15 @@ async fn process_msg<Context>(
 				Entry::Vacant(_) => return,
 			};
 
+			note_collation_outcome(
+				state,
+				pending_collation.para_id,
+				id.clone(),
+				CollationOutcome::Invalid,
+			);
 			report_collator(ctx, &state.peer_data, id.clone()).await;
 
-			dequeue_next_collation_and_fetch(ctx, state, parent, id).await;
+			dequeue_next_collation_and_fetch(ctx, state, parent, Some(id)).await;
 		},
 	}
 }
@@ -1214,13 +1641,16 @@ pub(crate) async fn run<Context>(
 	mut ctx: Context,
 	keystore: SyncCryptoStorePtr,
 	eviction_policy: crate::CollatorEvictionPolicy,
+	reputation: ReputationStore,
+	fetch_policy: Box<dyn CollationFetchPolicy>,
+	mut push_receiver: IncomingRequestReceiver<CollationPushingRequest>,
 	metrics: Metrics,
 ) -> std::result::Result<(), crate::error::FatalError>
 where
 	Context: overseer::SubsystemContext<Message = CollatorProtocolMessage>,
 	Context: SubsystemContext<Message = CollatorProtocolMessage>,
 {
-	let mut state = State { metrics, ..Default::default() };
+	let mut state = State { metrics, reputation, fetch_policy, ..Default::default() };
 
 	let next_inactivity_stream = infinite_stream(ACTIVITY_POLL);
 	futures::pin_mut!(next_inactivity_stream);
@@ -1228,7 +1658,12 @@ where
 	let check_collations_stream = infinite_stream(CHECK_COLLATIONS_POLL);
 	futures::pin_mut!(check_collations_stream);
 
+	let reputation_flush_stream = infinite_stream(REPUTATION_FLUSH_INTERVAL);
+	futures::pin_mut!(reputation_flush_stream);
+
 	loop {
+		let recv_push = push_receiver.recv(|| vec![COST_INVALID_REQUEST]).fuse();
+		pin_mut!(recv_push);
 		select! {
 			res = ctx.recv().fuse() => {
 				match res {
@@ -1248,6 +1683,20 @@ where
 			_ = next_inactivity_stream.next() => {
 				disconnect_inactive_peers(&mut ctx, &eviction_policy, &state.peer_data).await;
 			}
+			in_push = recv_push => {
+				match in_push {
+					Ok(req) => handle_pushed_collation(&mut ctx, &mut state, req).await,
+					Err(error) => {
+						let jfyi = error.split().map_err(incoming::Error::from)?;
+						gum::debug!(
+							target: LOG_TARGET,
+							error = ?jfyi,
+							"Decoding pushed collation failed"
+						);
+						continue
+					}
+				}
+			}
 			res = state.collation_fetches.select_next_some() => {
 				handle_collation_fetched_result(&mut ctx, &mut state, res).await;
 			}
@@ -1259,47 +1708,93 @@ where
 					?collator_id,
 					"Timeout hit - already seconded?"
 				);
-				dequeue_next_collation_and_fetch(&mut ctx, &mut state, relay_parent, collator_id).await;
+				dequeue_next_collation_and_fetch(&mut ctx, &mut state, relay_parent, Some(collator_id)).await;
 			}
 			_ = check_collations_stream.next() => {
-				let reputation_changes = poll_requests(
+				let (reputation_changes, timed_out) = poll_requests(
 					&mut state.requested_collations,
 					&state.metrics,
 					&state.span_per_relay_parent,
+					&*state.fetch_policy,
 				).await;
 
 				for (peer_id, rep) in reputation_changes {
 					modify_reputation(&mut ctx, peer_id, rep).await;
 				}
+
+				for pending_collation in timed_out {
+					let collator_id = state
+						.peer_data
+						.get(&pending_collation.peer_id)
+						.and_then(|p| p.collator_id())
+						.cloned();
+
+					if let Some(collator_id) = collator_id {
+						note_collation_outcome(
+							&mut state,
+							pending_collation.para_id,
+							collator_id,
+							CollationOutcome::TimedOut,
+						);
+					}
+				}
+
+				fetch_deferred_collations(&mut ctx, &mut state).await;
+			},
+			_ = reputation_flush_stream.next() => {
+				if let Some((db, tx)) = state.reputation.dirty_transaction() {
+					let write = async move { reputation::write_transaction(&*db, tx) }.boxed();
+					if let Err(err) = ctx.spawn_blocking("collator-scores-write", write) {
+						gum::warn!(
+							target: LOG_TARGET,
+							?err,
+							"Failed to spawn the write of collator scores",
+						);
+					}
+				}
 			},
 		}
 	}
 
+	state.reputation.flush();
+
 	Ok(())
 }
 
+/// Poll all requested collations, returning the reputation changes to apply to peers and
+/// the requests which timed out.
 async fn poll_requests(
 	requested_collations: &mut HashMap<PendingCollation, PerRequest>,
 	metrics: &Metrics,
 	span_per_relay_parent: &HashMap<Hash, PerLeafSpan>,
-) -> Vec<(PeerId, Rep)> {
+	fetch_policy: &dyn CollationFetchPolicy,
+) -> (Vec<(PeerId, Rep)>, Vec<PendingCollation>) {
 	let mut retained_requested = HashSet::new();
 	let mut reputation_changes = Vec::new();
+	let mut timed_out = Vec::new();
 	for (pending_collation, per_req) in requested_collations.iter_mut() {
 		// Despite the await, this won't block on the response itself.
-		let result =
-			poll_collation_response(metrics, span_per_relay_parent, pending_collation, per_req)
-				.await;
+		let result = poll_collation_response(
+			metrics,
+			span_per_relay_parent,
+			fetch_policy,
+			pending_collation,
+			per_req,
+		)
+		.await;
 
 		if !result.is_ready() {
 			retained_requested.insert(pending_collation.clone());
 		}
-		if let CollationFetchResult::Error(Some(rep)) = result {
-			reputation_changes.push((pending_collation.peer_id.clone(), rep));
+		match result {
+			CollationFetchResult::Error(Some(rep)) =>
+				reputation_changes.push((pending_collation.peer_id.clone(), rep)),
+			CollationFetchResult::TimedOut => timed_out.push(pending_collation.clone()),
+			_ => {},
 		}
 	}
 	requested_collations.retain(|k, _| retained_requested.contains(k));
-	reputation_changes
+	(reputation_changes, timed_out)
 }
 
 /// Dequeue another collation and fetch.
@@ -1309,13 +1804,35 @@ async fn dequeue_next_collation_and_fetch(
 	state: &mut State,
 	relay_parent: Hash,
 	// The collator we tried to fetch from last.
-	previous_fetch: CollatorId,
+	previous_fetch: Option<CollatorId>,
 ) {
-	if let Some((next, id)) = state
-		.collations_per_relay_parent
-		.get_mut(&relay_parent)
-		.and_then(|c| c.get_next_collation_to_fetch(Some(&previous_fetch)))
-	{
+	let collations = match state.collations_per_relay_parent.get_mut(&relay_parent) {
+		Some(collations) => collations,
+		None => return,
+	};
+	let may_fetch = |para_id| may_fetch(&*state.fetch_policy, &state.requested_collations, para_id);
+
+	let limit_reached = collations.waiting_collation == previous_fetch &&
+		!matches!(collations.status, CollationStatus::Seconded) &&
+		!collations.unfetched_collations.is_empty() &&
+		!collations.unfetched_collations.iter().any(|(pc, _)| may_fetch(pc.para_id));
+	if limit_reached {
+		gum::debug!(
+			target: LOG_TARGET,
+			?relay_parent,
+			previous_collator = ?previous_fetch,
+			"Fetch limit reached, deferring the fetch of the next collation"
+		);
+		state.deferred_fetches.insert(relay_parent);
+		return
+	}
+
+	let next = collations.get_next_collation_to_fetch(
+		previous_fetch.as_ref(),
+		&mut state.reputation,
+		may_fetch,
+	);
+	if let Some((next, id)) = next {
 		gum::debug!(
 			target: LOG_TARGET,
 			?relay_parent,
@@ -1333,6 +1850,27 @@ async fn dequeue_next_collation_and_fetch(
 	}
 }
 
+/// Retry the fetches the fetch policy deferred, unless a collation for the relay parent is
+/// already being validated or was seconded.
+async fn fetch_deferred_collations(
+	ctx: &mut (impl SubsystemContext<Message = CollatorProtocolMessage>
+	          + overseer::SubsystemContext<Message = CollatorProtocolMessage>),
+	state: &mut State,
+) {
+	for relay_parent in std::mem::take(&mut state.deferred_fetches) {
+		let previous_fetch = match state.collations_per_relay_parent.get(&relay_parent) {
+			Some(collations) => match collations.status {
+				CollationStatus::Waiting | CollationStatus::Fetching =>
+					collations.waiting_collation.clone(),
+				CollationStatus::WaitingOnValidation | CollationStatus::Seconded => continue,
+			},
+			None => continue,
+		};
+
+		dequeue_next_collation_and_fetch(ctx, state, relay_parent, previous_fetch).await;
+	}
+}
+
 /// Handle a fetched collation result.
 async fn handle_collation_fetched_result<Context>(
 	ctx: &mut Context,
@@ -1360,12 +1898,20 @@ async fn handle_collation_fetched_result<Context>(
 				"Failed to fetch collation.",
 			);
 
-			dequeue_next_collation_and_fetch(ctx, state, relay_parent, collation_event.0).await;
+			dequeue_next_collation_and_fetch(ctx, state, relay_parent, Some(collation_event.0))
+				.await;
 			return
 		},
 	};
 
 	if let Some(collations) = state.collations_per_relay_parent.get_mut(&relay_parent) {
+		// Other collators advertising the same candidate don't need to be fetched from anymore.
+		let candidate_hash = candidate_receipt.hash();
+		collations.fetched_candidates.insert(candidate_hash);
+		collations
+			.unfetched_collations
+			.retain(|(pc, _)| pc.candidate_hash != Some(candidate_hash));
+
 		if let CollationStatus::Seconded = collations.status {
 			gum::debug!(
 				target: LOG_TARGET,
@@ -1424,6 +1970,8 @@ enum CollationFetchResult {
 	/// An error occurred when fetching a collation or it was invalid.
 	/// A given reputation change should be applied to the peer.
 	Error(Option<Rep>),
+	/// The request timed out.
+	TimedOut,
 }
 
 impl CollationFetchResult {
@@ -1439,6 +1987,7 @@ impl CollationFetchResult {
 async fn poll_collation_response(
 	metrics: &Metrics,
 	spans: &HashMap<Hash, PerLeafSpan>,
+	fetch_policy: &dyn CollationFetchPolicy,
 	pending_collation: &PendingCollation,
 	per_req: &mut PerRequest,
 ) -> CollationFetchResult {
@@ -1481,7 +2030,8 @@ async fn poll_collation_response(
 				);
 				// For now we don't want to change reputation on timeout, to mitigate issues like
 				// this: https://github.com/paritytech/polkadot/issues/4617
-				CollationFetchResult::Error(None)
+				// The timeout is only noted in the score of the collator.
+				CollationFetchResult::TimedOut
 			},
 			Err(RequestError::NetworkError(err)) => {
 				gum::debug!(
@@ -1522,6 +2072,36 @@ async fn poll_collation_response(
 
 				CollationFetchResult::Error(Some(COST_WRONG_PARA))
 			},
+			Ok(CollationFetchingResponse::Collation(receipt, _))
+				if pending_collation
+					.candidate_hash
+					.map_or(false, |hash| hash != receipt.hash()) =>
+			{
+				gum::debug!(
+					target: LOG_TARGET,
+					para_id = ?pending_collation.para_id,
+					peer_id = ?pending_collation.peer_id,
+					advertised_candidate_hash = ?pending_collation.candidate_hash,
+					candidate_hash = ?receipt.hash(),
+					"Got a collation not matching the advertised candidate hash."
+				);
+
+				CollationFetchResult::Error(Some(COST_WRONG_CANDIDATE))
+			},
+			Ok(CollationFetchingResponse::Collation(_, pov))
+				if pov.encoded_size() > fetch_policy.max_pov_size(pending_collation.para_id) =>
+			{
+				gum::debug!(
+					target: LOG_TARGET,
+					para_id = ?pending_collation.para_id,
+					peer_id = ?pending_collation.peer_id,
+					pov_size = pov.encoded_size(),
+					max_pov_size = fetch_policy.max_pov_size(pending_collation.para_id),
+					"Got an oversized PoV for requested collation."
+				);
+
+				CollationFetchResult::Error(Some(COST_OVERSIZED_POV))
+			},
 			Ok(CollationFetchingResponse::Collation(receipt, pov)) => {
 				gum::debug!(
 					target: LOG_TARGET,
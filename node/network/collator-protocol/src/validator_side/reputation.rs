@@ -0,0 +1,270 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Persistent scoring of collators per para.
+//!
+//! Unlike the reputation changes reported to the network bridge, which only affect the
+//! connection to a peer, the scores tracked here are keyed by collator id and persisted
+//! in the DB, so they survive both reconnections and restarts. They are used to order the
+//! advertisements to fetch, so collators with a history of seconded collations are fetched
+//! from before unknown collators, which in turn are fetched from before those with a history
+//! of invalid collations or timeouts.
+//!
+//! Scores decay over time, so a collator is mostly judged by its recent collations. Updated
+//! scores are not written right away, but periodically by the subsystem.
+
+use std::{
+	collections::{HashMap, HashSet},
+	sync::Arc,
+	time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use parity_scale_codec::{Decode, Encode};
+
+use polkadot_node_subsystem_util::database::{DBTransaction, Database};
+use polkadot_primitives::v2::{CollatorId, Id as ParaId};
+
+use crate::LOG_TARGET;
+
+/// The maximum number of collators scored per para. When exceeded, the collator whose score
+/// was updated least recently is forgotten.
+const MAX_COLLATORS_PER_PARA: usize = 256;
+
+/// The time after which a score without new outcomes has decayed to half its value.
+const SCORE_HALF_LIFE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// The score of collators without recorded outcomes.
+///
+/// Below the score of any collator with more seconded collations than failures, recently.
+pub(crate) const UNKNOWN_COLLATOR_SCORE: i64 = -1;
+
+const SECONDED_WEIGHT: i64 = 10;
+const INVALID_WEIGHT: i64 = 100;
+const TIMED_OUT_WEIGHT: i64 = 5;
+
+const SCORES_PREFIX: &[u8; 14] = b"CollatorScores";
+
+/// Configuration of the persistent collator reputation.
+#[derive(Debug, Clone)]
+pub struct CollatorReputationConfig {
+	/// The column of the DB the scores are stored in.
+	pub col_data: u32,
+}
+
+/// The outcome of a collation fetched from a collator.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum CollationOutcome {
+	/// The collation was seconded.
+	Seconded,
+	/// The collation was found to be invalid.
+	Invalid,
+	/// The collation couldn't be fetched in time.
+	TimedOut,
+}
+
+impl CollationOutcome {
+	pub(crate) fn as_str(&self) -> &'static str {
+		match self {
+			Self::Seconded => "seconded",
+			Self::Invalid => "invalid",
+			Self::TimedOut => "timed-out",
+		}
+	}
+
+	fn weight(&self) -> i64 {
+		match self {
+			Self::Seconded => SECONDED_WEIGHT,
+			Self::Invalid => -INVALID_WEIGHT,
+			Self::TimedOut => -TIMED_OUT_WEIGHT,
+		}
+	}
+}
+
+/// The decaying score of a collator.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Encode, Decode)]
+pub(crate) struct CollatorScore {
+	/// The score as of `updated_at`.
+	value: i64,
+	/// Seconds since the unix epoch.
+	updated_at: u64,
+}
+
+impl CollatorScore {
+	/// The score at `now`, decayed since it was last updated.
+	fn value_at(&self, now: u64) -> i64 {
+		let half_lives =
+			now.saturating_sub(self.updated_at) as f64 / SCORE_HALF_LIFE.as_secs() as f64;
+		(self.value as f64 * 0.5f64.powf(half_lives)).round() as i64
+	}
+
+	fn note(&mut self, outcome: CollationOutcome, now: u64) {
+		self.value = self.value_at(now).saturating_add(outcome.weight());
+		self.updated_at = now;
+	}
+}
+
+/// Scores of collators per para, backed by the DB.
+///
+/// The scores of a para are loaded on first use. Updated scores are only kept in memory until
+/// the next [`ReputationStore::dirty_transaction`] or [`ReputationStore::flush`]. Without a
+/// DB, the scores are only kept in memory.
+#[derive(Default)]
+pub(crate) struct ReputationStore {
+	db: Option<(Arc<dyn Database>, CollatorReputationConfig)>,
+	scores: HashMap<ParaId, HashMap<CollatorId, CollatorScore>>,
+	/// The paras with scores updated since they were last written.
+	dirty: HashSet<ParaId>,
+}
+
+impl ReputationStore {
+	/// Create a store persisting scores in the given DB.
+	pub(crate) fn new(db: Arc<dyn Database>, config: CollatorReputationConfig) -> Self {
+		ReputationStore { db: Some((db, config)), scores: HashMap::new(), dirty: HashSet::new() }
+	}
+
+	/// The score of a collator for a para.
+	pub(crate) fn score(&mut self, para_id: ParaId, collator_id: &CollatorId) -> i64 {
+		self.score_at(para_id, collator_id, now())
+	}
+
+	/// The score of a collator for a para at `now`, in seconds since the unix epoch.
+	pub(crate) fn score_at(&mut self, para_id: ParaId, collator_id: &CollatorId, now: u64) -> i64 {
+		self.para_scores(para_id)
+			.get(collator_id)
+			.map_or(UNKNOWN_COLLATOR_SCORE, |s| s.value_at(now))
+	}
+
+	/// The number of collators scored across all loaded paras.
+	pub(crate) fn len(&self) -> usize {
+		self.scores.values().map(|s| s.len()).sum()
+	}
+
+	/// Record the outcome of a collation fetched from a collator for a para.
+	pub(crate) fn note_outcome(
+		&mut self,
+		para_id: ParaId,
+		collator_id: CollatorId,
+		outcome: CollationOutcome,
+	) {
+		self.note_outcome_at(para_id, collator_id, outcome, now())
+	}
+
+	/// Record the outcome of a collation at `now`, in seconds since the unix epoch.
+	pub(crate) fn note_outcome_at(
+		&mut self,
+		para_id: ParaId,
+		collator_id: CollatorId,
+		outcome: CollationOutcome,
+		now: u64,
+	) {
+		let scores = self.para_scores(para_id);
+		let score = scores
+			.entry(collator_id.clone())
+			.or_insert_with(|| CollatorScore { value: UNKNOWN_COLLATOR_SCORE, updated_at: now });
+		score.note(outcome, now);
+
+		if scores.len() > MAX_COLLATORS_PER_PARA {
+			if let Some(least_recent) = scores
+				.iter()
+				.filter(|(c, _)| **c != collator_id)
+				.min_by_key(|(_, s)| s.updated_at)
+				.map(|(c, _)| c.clone())
+			{
+				scores.remove(&least_recent);
+			}
+		}
+
+		self.dirty.insert(para_id);
+	}
+
+	/// The transaction writing the scores of all paras updated since they were last written.
+	///
+	/// The transaction is meant to be written off the task of the subsystem, see
+	/// [`write_transaction`].
+	pub(crate) fn dirty_transaction(&mut self) -> Option<(Arc<dyn Database>, DBTransaction)> {
+		let (db, config) = self.db.as_ref()?;
+		if self.dirty.is_empty() {
+			return None
+		}
+
+		let mut tx = DBTransaction::new();
+		for para_id in self.dirty.drain() {
+			let scores: Vec<_> = self
+				.scores
+				.get(&para_id)
+				.into_iter()
+				.flat_map(|s| s.iter().map(|(c, s)| (c.clone(), *s)))
+				.collect();
+			tx.put_vec(config.col_data, &para_scores_key(para_id), scores.encode());
+		}
+		Some((db.clone(), tx))
+	}
+
+	/// Write the scores updated since they were last written.
+	pub(crate) fn flush(&mut self) {
+		if let Some((db, tx)) = self.dirty_transaction() {
+			write_transaction(&*db, tx);
+		}
+	}
+
+	fn para_scores(&mut self, para_id: ParaId) -> &mut HashMap<CollatorId, CollatorScore> {
+		let db = &self.db;
+		self.scores.entry(para_id).or_insert_with(|| match db {
+			Some((db, config)) => load_para_scores(&**db, config, para_id),
+			None => HashMap::new(),
+		})
+	}
+}
+
+/// Write a transaction of collator scores.
+pub(crate) fn write_transaction(db: &dyn Database, tx: DBTransaction) {
+	if let Err(err) = db.write(tx) {
+		gum::warn!(target: LOG_TARGET, ?err, "Failed to write collator scores");
+	}
+}
+
+fn now() -> u64 {
+	SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+fn para_scores_key(para_id: ParaId) -> Vec<u8> {
+	(SCORES_PREFIX, para_id).encode()
+}
+
+fn load_para_scores(
+	db: &dyn Database,
+	config: &CollatorReputationConfig,
+	para_id: ParaId,
+) -> HashMap<CollatorId, CollatorScore> {
+	let raw = match db.get(config.col_data, &para_scores_key(para_id)) {
+		Ok(raw) => raw,
+		Err(err) => {
+			gum::warn!(target: LOG_TARGET, ?para_id, ?err, "Failed to load collator scores");
+			return HashMap::new()
+		},
+	};
+
+	match raw
+		.map(|raw| Vec::<(CollatorId, CollatorScore)>::decode(&mut &raw[..]))
+		.transpose()
+	{
+		Ok(scores) => scores.into_iter().flatten().collect(),
+		Err(err) => {
+			gum::warn!(target: LOG_TARGET, ?para_id, ?err, "Failed to decode collator scores");
+			HashMap::new()
+		},
+	}
+}
@@ -14,9 +14,11 @@
 // You should have received a copy of the GNU General Public License
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
-use super::*;
+use super::{reputation::UNKNOWN_COLLATOR_SCORE, *};
 use assert_matches::assert_matches;
-use futures::{executor, future, Future};
+use futures::{executor, future, Future, SinkExt};
+use parity_scale_codec::Decode;
+use sc_network::config::{IncomingRequest as RawIncomingRequest, RequestResponseConfig};
 use sp_core::{crypto::Pair, Encode};
 use sp_keyring::Sr25519Keyring;
 use sp_keystore::{testing::KeyStore as TestKeyStore, SyncCryptoStore};
@@ -24,14 +26,15 @@ use std::{iter, sync::Arc, time::Duration};
 
 use polkadot_node_network_protocol::{
 	our_view,
+	peer_set::CollationVersion,
 	request_response::{Requests, ResponseSender},
 	ObservedRole,
 };
 use polkadot_node_primitives::BlockData;
-use polkadot_node_subsystem_util::TimeoutExt;
+use polkadot_node_subsystem_util::{database::Database, TimeoutExt};
 use polkadot_primitives::v2::{
-	CollatorPair, CoreState, GroupIndex, GroupRotationInfo, OccupiedCore, ScheduledCore,
-	ValidatorId, ValidatorIndex,
+	CollatorPair, CoreState, GroupIndex, GroupRotationInfo, HeadData, OccupiedCore,
+	PersistedValidationData, ScheduledCore, ValidatorId, ValidatorIndex,
 };
 use polkadot_primitives_test_helpers::{
 	dummy_candidate_descriptor, dummy_candidate_receipt_bad_sig, dummy_hash,
@@ -51,6 +54,26 @@ struct TestState {
 	validator_groups: Vec<Vec<ValidatorIndex>>,
 	group_rotation_info: GroupRotationInfo,
 	cores: Vec<CoreState>,
+	head_data: HeadData,
+}
+
+impl TestState {
+	/// The para our validator is assigned to, if any.
+	fn assigned_para(&self) -> Option<ParaId> {
+		let group = self
+			.validator_groups
+			.iter()
+			.position(|group| group.contains(&ValidatorIndex(0)))
+			.expect("Our validator is in a group; qed");
+		let core = self
+			.group_rotation_info
+			.core_for_group(GroupIndex(group as _), self.cores.len());
+		self.cores.get(core.0 as usize).and_then(|core| core.para_id())
+	}
+
+	fn parent_head_data_hash(&self) -> Hash {
+		self.head_data.hash()
+	}
 }
 
 impl Default for TestState {
@@ -108,6 +131,7 @@ impl Default for TestState {
 			validator_groups,
 			group_rotation_info,
 			cores,
+			head_data: HeadData(vec![7, 8, 9]),
 		}
 	}
 }
@@ -116,9 +140,17 @@ type VirtualOverseer = test_helpers::TestSubsystemContextHandle<CollatorProtocol
 
 struct TestHarness {
 	virtual_overseer: VirtualOverseer,
+	push_cfg: RequestResponseConfig,
 }
 
 fn test_harness<T: Future<Output = VirtualOverseer>>(test: impl FnOnce(TestHarness) -> T) {
+	test_harness_with_fetch_policy(Box::new(CollationFetchLimits::default()), test)
+}
+
+fn test_harness_with_fetch_policy<T: Future<Output = VirtualOverseer>>(
+	fetch_policy: Box<dyn CollationFetchPolicy>,
+	test: impl FnOnce(TestHarness) -> T,
+) {
 	let _ = env_logger::builder()
 		.is_test(true)
 		.filter(Some("polkadot_collator_protocol"), log::LevelFilter::Trace)
@@ -137,6 +169,7 @@ fn test_harness<T: Future<Output = VirtualOverseer>>(test: impl FnOnce(TestHarne
 		)
 		.unwrap();
 
+	let (push_receiver, push_cfg) = IncomingRequest::get_config_receiver();
 	let subsystem = run(
 		context,
 		Arc::new(keystore),
@@ -144,10 +177,13 @@ fn test_harness<T: Future<Output = VirtualOverseer>>(test: impl FnOnce(TestHarne
 			inactive_collator: ACTIVITY_TIMEOUT,
 			undeclared: DECLARE_TIMEOUT,
 		},
+		ReputationStore::default(),
+		fetch_policy,
+		push_receiver,
 		Metrics::default(),
 	);
 
-	let test_fut = test(TestHarness { virtual_overseer });
+	let test_fut = test(TestHarness { virtual_overseer, push_cfg });
 
 	futures::pin_mut!(test_fut);
 	futures::pin_mut!(subsystem);
@@ -236,6 +272,27 @@ async fn respond_to_core_info_queries(
 			let _ = tx.send(Ok(test_state.cores.clone()));
 		}
 	);
+
+	if let Some(para_id) = test_state.assigned_para() {
+		for expected_assumption in
+			[OccupiedCoreAssumption::Included, OccupiedCoreAssumption::TimedOut]
+		{
+			assert_matches!(
+				overseer_recv(virtual_overseer).await,
+				AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+					_,
+					RuntimeApiRequest::PersistedValidationData(id, assumption, tx),
+				)) => {
+					assert_eq!(id, para_id);
+					assert_eq!(assumption, expected_assumption);
+					let _ = tx.send(Ok(Some(PersistedValidationData {
+						parent_head: test_state.head_data.clone(),
+						..Default::default()
+					})));
+				}
+			);
+		}
+	}
 }
 
 /// Assert that the next message is a `CandidateBacking(Second())`.
@@ -303,9 +360,10 @@ async fn connect_and_declare_collator(
 ) {
 	overseer_send(
 		virtual_overseer,
-		CollatorProtocolMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::PeerConnected(
+		CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::PeerConnected(
 			peer.clone(),
 			ObservedRole::Full,
+			CollationVersion::V2.into(),
 			None,
 		)),
 	)
@@ -313,13 +371,13 @@ async fn connect_and_declare_collator(
 
 	overseer_send(
 		virtual_overseer,
-		CollatorProtocolMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::PeerMessage(
+		CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::PeerMessage(
 			peer.clone(),
-			protocol_v1::CollatorProtocolMessage::Declare(
+			VersionedCollatorProtocolMessage::V1(protocol_v1::CollatorProtocolMessage::Declare(
 				collator.public(),
 				para_id,
 				collator.sign(&protocol_v1::declare_signature_payload(&peer)),
-			),
+			)),
 		)),
 	)
 	.await;
@@ -333,28 +391,76 @@ async fn advertise_collation(
 ) {
 	overseer_send(
 		virtual_overseer,
-		CollatorProtocolMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::PeerMessage(
+		CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::PeerMessage(
+			peer,
+			VersionedCollatorProtocolMessage::V1(
+				protocol_v1::CollatorProtocolMessage::AdvertiseCollation(relay_parent),
+			),
+		)),
+	)
+	.await;
+}
+
+/// Advertise a collation with the `v2` protocol.
+async fn advertise_collation_v2(
+	virtual_overseer: &mut VirtualOverseer,
+	peer: PeerId,
+	relay_parent: Hash,
+	candidate_hash: CandidateHash,
+	parent_head_data_hash: Hash,
+) {
+	overseer_send(
+		virtual_overseer,
+		CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::PeerMessage(
 			peer,
-			protocol_v1::CollatorProtocolMessage::AdvertiseCollation(relay_parent),
+			VersionedCollatorProtocolMessage::V2(
+				protocol_v2::CollatorProtocolMessage::AdvertiseCollation {
+					relay_parent,
+					candidate_hash,
+					parent_head_data_hash,
+				},
+			),
 		)),
 	)
 	.await;
 }
 
+/// Push a collation from the given peer, returning the receiver for the response.
+async fn push_collation(
+	push_cfg: &mut RequestResponseConfig,
+	peer: PeerId,
+	receipt: CandidateReceipt,
+	pov: PoV,
+) -> oneshot::Receiver<sc_network::config::OutgoingResponse> {
+	let (pending_response, rx) = oneshot::channel();
+	push_cfg
+		.inbound_queue
+		.as_mut()
+		.unwrap()
+		.send(RawIncomingRequest {
+			peer,
+			payload: CollationPushingRequest { receipt, pov }.encode(),
+			pending_response,
+		})
+		.await
+		.unwrap();
+	rx
+}
+
 // As we receive a relevant advertisement act on it and issue a collation request.
 #[test]
 fn act_on_advertisement() {
 	let test_state = TestState::default();
 
 	test_harness(|test_harness| async move {
-		let TestHarness { mut virtual_overseer } = test_harness;
+		let TestHarness { mut virtual_overseer, .. } = test_harness;
 
 		let pair = CollatorPair::generate().0;
 		gum::trace!("activating");
 
 		overseer_send(
 			&mut virtual_overseer,
-			CollatorProtocolMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::OurViewChange(
+			CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::OurViewChange(
 				our_view![test_state.relay_parent],
 			)),
 		)
@@ -391,11 +497,11 @@ fn collator_reporting_works() {
 	let test_state = TestState::default();
 
 	test_harness(|test_harness| async move {
-		let TestHarness { mut virtual_overseer } = test_harness;
+		let TestHarness { mut virtual_overseer, .. } = test_harness;
 
 		overseer_send(
 			&mut virtual_overseer,
-			CollatorProtocolMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::OurViewChange(
+			CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::OurViewChange(
 				our_view![test_state.relay_parent],
 			)),
 		)
@@ -448,15 +554,16 @@ fn collator_authentication_verification_works() {
 	let test_state = TestState::default();
 
 	test_harness(|test_harness| async move {
-		let TestHarness { mut virtual_overseer } = test_harness;
+		let TestHarness { mut virtual_overseer, .. } = test_harness;
 
 		let peer_b = PeerId::random();
 
 		overseer_send(
 			&mut virtual_overseer,
-			CollatorProtocolMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::PeerConnected(
+			CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::PeerConnected(
 				peer_b,
 				ObservedRole::Full,
+				CollationVersion::V2.into(),
 				None,
 			)),
 		)
@@ -465,12 +572,14 @@ fn collator_authentication_verification_works() {
 		// the peer sends a declare message but sign the wrong payload
 		overseer_send(
 			&mut virtual_overseer,
-			CollatorProtocolMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::PeerMessage(
+			CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::PeerMessage(
 				peer_b.clone(),
-				protocol_v1::CollatorProtocolMessage::Declare(
-					test_state.collators[0].public(),
-					test_state.chain_ids[0],
-					test_state.collators[0].sign(&[42]),
+				VersionedCollatorProtocolMessage::V1(
+					protocol_v1::CollatorProtocolMessage::Declare(
+						test_state.collators[0].public(),
+						test_state.chain_ids[0],
+						test_state.collators[0].sign(&[42]),
+					),
 				),
 			)),
 		)
@@ -504,13 +613,13 @@ fn fetch_collations_works() {
 	let test_state = TestState::default();
 
 	test_harness(|test_harness| async move {
-		let TestHarness { mut virtual_overseer } = test_harness;
+		let TestHarness { mut virtual_overseer, .. } = test_harness;
 
 		let second = Hash::random();
 
 		overseer_send(
 			&mut virtual_overseer,
-			CollatorProtocolMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::OurViewChange(
+			CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::OurViewChange(
 				our_view![test_state.relay_parent, second],
 			)),
 		)
@@ -574,7 +683,7 @@ fn fetch_collations_works() {
 
 		overseer_send(
 			&mut virtual_overseer,
-			CollatorProtocolMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::PeerDisconnected(
+			CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::PeerDisconnected(
 				peer_b.clone(),
 			)),
 		)
@@ -582,7 +691,7 @@ fn fetch_collations_works() {
 
 		overseer_send(
 			&mut virtual_overseer,
-			CollatorProtocolMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::PeerDisconnected(
+			CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::PeerDisconnected(
 				peer_c.clone(),
 			)),
 		)
@@ -671,11 +780,11 @@ fn reject_connection_to_next_group() {
 	let test_state = TestState::default();
 
 	test_harness(|test_harness| async move {
-		let TestHarness { mut virtual_overseer } = test_harness;
+		let TestHarness { mut virtual_overseer, .. } = test_harness;
 
 		overseer_send(
 			&mut virtual_overseer,
-			CollatorProtocolMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::OurViewChange(
+			CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::OurViewChange(
 				our_view![test_state.relay_parent],
 			)),
 		)
@@ -716,13 +825,13 @@ fn fetch_next_collation_on_invalid_collation() {
 	let test_state = TestState::default();
 
 	test_harness(|test_harness| async move {
-		let TestHarness { mut virtual_overseer } = test_harness;
+		let TestHarness { mut virtual_overseer, .. } = test_harness;
 
 		let second = Hash::random();
 
 		overseer_send(
 			&mut virtual_overseer,
-			CollatorProtocolMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::OurViewChange(
+			CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::OurViewChange(
 				our_view![test_state.relay_parent, second],
 			)),
 		)
@@ -814,7 +923,7 @@ fn inactive_disconnected() {
 	let test_state = TestState::default();
 
 	test_harness(|test_harness| async move {
-		let TestHarness { mut virtual_overseer } = test_harness;
+		let TestHarness { mut virtual_overseer, .. } = test_harness;
 
 		let pair = CollatorPair::generate().0;
 
@@ -822,7 +931,7 @@ fn inactive_disconnected() {
 
 		overseer_send(
 			&mut virtual_overseer,
-			CollatorProtocolMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::OurViewChange(
+			CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::OurViewChange(
 				our_view![hash_a],
 			)),
 		)
@@ -860,7 +969,7 @@ fn activity_extends_life() {
 	let test_state = TestState::default();
 
 	test_harness(|test_harness| async move {
-		let TestHarness { mut virtual_overseer } = test_harness;
+		let TestHarness { mut virtual_overseer, .. } = test_harness;
 
 		let pair = CollatorPair::generate().0;
 
@@ -870,7 +979,7 @@ fn activity_extends_life() {
 
 		overseer_send(
 			&mut virtual_overseer,
-			CollatorProtocolMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::OurViewChange(
+			CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::OurViewChange(
 				our_view![hash_a, hash_b, hash_c],
 			)),
 		)
@@ -925,11 +1034,11 @@ fn disconnect_if_no_declare() {
 	let test_state = TestState::default();
 
 	test_harness(|test_harness| async move {
-		let TestHarness { mut virtual_overseer } = test_harness;
+		let TestHarness { mut virtual_overseer, .. } = test_harness;
 
 		overseer_send(
 			&mut virtual_overseer,
-			CollatorProtocolMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::OurViewChange(
+			CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::OurViewChange(
 				our_view![test_state.relay_parent],
 			)),
 		)
@@ -941,9 +1050,10 @@ fn disconnect_if_no_declare() {
 
 		overseer_send(
 			&mut virtual_overseer,
-			CollatorProtocolMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::PeerConnected(
+			CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::PeerConnected(
 				peer_b.clone(),
 				ObservedRole::Full,
+				CollationVersion::V2.into(),
 				None,
 			)),
 		)
@@ -960,13 +1070,13 @@ fn disconnect_if_wrong_declare() {
 	let test_state = TestState::default();
 
 	test_harness(|test_harness| async move {
-		let TestHarness { mut virtual_overseer } = test_harness;
+		let TestHarness { mut virtual_overseer, .. } = test_harness;
 
 		let pair = CollatorPair::generate().0;
 
 		overseer_send(
 			&mut virtual_overseer,
-			CollatorProtocolMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::OurViewChange(
+			CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::OurViewChange(
 				our_view![test_state.relay_parent],
 			)),
 		)
@@ -978,9 +1088,10 @@ fn disconnect_if_wrong_declare() {
 
 		overseer_send(
 			&mut virtual_overseer,
-			CollatorProtocolMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::PeerConnected(
+			CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::PeerConnected(
 				peer_b.clone(),
 				ObservedRole::Full,
+				CollationVersion::V2.into(),
 				None,
 			)),
 		)
@@ -988,12 +1099,14 @@ fn disconnect_if_wrong_declare() {
 
 		overseer_send(
 			&mut virtual_overseer,
-			CollatorProtocolMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::PeerMessage(
+			CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::PeerMessage(
 				peer_b.clone(),
-				protocol_v1::CollatorProtocolMessage::Declare(
-					pair.public(),
-					ParaId::from(69),
-					pair.sign(&protocol_v1::declare_signature_payload(&peer_b)),
+				VersionedCollatorProtocolMessage::V1(
+					protocol_v1::CollatorProtocolMessage::Declare(
+						pair.public(),
+						ParaId::from(69),
+						pair.sign(&protocol_v1::declare_signature_payload(&peer_b)),
+					),
 				),
 			)),
 		)
@@ -1021,13 +1134,13 @@ fn view_change_clears_old_collators() {
 	let mut test_state = TestState::default();
 
 	test_harness(|test_harness| async move {
-		let TestHarness { mut virtual_overseer } = test_harness;
+		let TestHarness { mut virtual_overseer, .. } = test_harness;
 
 		let pair = CollatorPair::generate().0;
 
 		overseer_send(
 			&mut virtual_overseer,
-			CollatorProtocolMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::OurViewChange(
+			CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::OurViewChange(
 				our_view![test_state.relay_parent],
 			)),
 		)
@@ -1049,7 +1162,7 @@ fn view_change_clears_old_collators() {
 
 		overseer_send(
 			&mut virtual_overseer,
-			CollatorProtocolMessage::NetworkBridgeUpdateV1(NetworkBridgeEvent::OurViewChange(
+			CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::OurViewChange(
 				our_view![hash_b],
 			)),
 		)
@@ -1063,3 +1176,862 @@ fn view_change_clears_old_collators() {
 		virtual_overseer
 	})
 }
+
+#[test]
+fn collator_scores_survive_restart() {
+	let db: Arc<dyn Database> =
+		Arc::new(polkadot_node_subsystem_util::database::kvdb_impl::DbAdapter::new(
+			kvdb_memorydb::create(1),
+			&[],
+		));
+	let config = CollatorReputationConfig { col_data: 0 };
+
+	let para_id = ParaId::from(1);
+	let good_collator = CollatorPair::generate().0.public();
+	let bad_collator = CollatorPair::generate().0.public();
+
+	let mut reputation = ReputationStore::new(db.clone(), config.clone());
+	reputation.note_outcome(para_id, good_collator.clone(), CollationOutcome::Seconded);
+	reputation.note_outcome(para_id, bad_collator.clone(), CollationOutcome::Invalid);
+	reputation.note_outcome(para_id, bad_collator.clone(), CollationOutcome::TimedOut);
+
+	// Scores are only written on flushes.
+	assert_eq!(
+		ReputationStore::new(db.clone(), config.clone()).score(para_id, &good_collator),
+		UNKNOWN_COLLATOR_SCORE
+	);
+	reputation.flush();
+
+	let mut reputation = ReputationStore::new(db, config);
+	assert!(reputation.score(para_id, &good_collator) > 0);
+	assert!(reputation.score(para_id, &bad_collator) < UNKNOWN_COLLATOR_SCORE);
+	assert_eq!(reputation.score(ParaId::from(2), &good_collator), UNKNOWN_COLLATOR_SCORE);
+	assert_eq!(reputation.len(), 2);
+}
+
+#[test]
+fn collator_scores_decay() {
+	let para_id = ParaId::from(1);
+	let collator = CollatorPair::generate().0.public();
+	let day = 24 * 60 * 60;
+
+	let mut reputation = ReputationStore::default();
+	reputation.note_outcome_at(para_id, collator.clone(), CollationOutcome::Seconded, 0);
+	reputation.note_outcome_at(para_id, collator.clone(), CollationOutcome::Seconded, 0);
+	let score = reputation.score_at(para_id, &collator, 0);
+	assert!(score > UNKNOWN_COLLATOR_SCORE);
+
+	assert_eq!(reputation.score_at(para_id, &collator, day), (score + 1) / 2);
+	assert_eq!(reputation.score_at(para_id, &collator, 30 * day), 0);
+
+	// Recent outcomes outweigh old ones.
+	reputation.note_outcome_at(para_id, collator.clone(), CollationOutcome::TimedOut, 30 * day);
+	assert!(reputation.score_at(para_id, &collator, 30 * day) < UNKNOWN_COLLATOR_SCORE);
+}
+
+#[test]
+fn least_recently_scored_collators_are_evicted() {
+	let para_id = ParaId::from(1);
+	let collators: Vec<_> =
+		iter::repeat_with(|| CollatorPair::generate().0.public()).take(257).collect();
+
+	let mut reputation = ReputationStore::default();
+	for (i, collator) in collators.iter().enumerate().take(256) {
+		// Plenty of outcomes, but long ago for the first collator.
+		for _ in 0..10 {
+			reputation.note_outcome_at(
+				para_id,
+				collator.clone(),
+				CollationOutcome::Seconded,
+				i as u64,
+			);
+		}
+	}
+	assert_eq!(reputation.len(), 256);
+
+	// The new collator is not evicted right away, despite its single outcome.
+	reputation.note_outcome_at(para_id, collators[256].clone(), CollationOutcome::Seconded, 256);
+	assert_eq!(reputation.len(), 256);
+	assert!(reputation.score_at(para_id, &collators[256], 256) > UNKNOWN_COLLATOR_SCORE);
+	assert_eq!(reputation.score_at(para_id, &collators[0], 256), UNKNOWN_COLLATOR_SCORE);
+	assert!(reputation.score_at(para_id, &collators[1], 256) > UNKNOWN_COLLATOR_SCORE);
+}
+
+#[test]
+fn advertisements_of_best_scored_collators_are_fetched_first() {
+	let para_id = ParaId::from(1);
+	let relay_parent = Hash::repeat_byte(0x01);
+
+	let collators: Vec<_> =
+		iter::repeat_with(|| CollatorPair::generate().0.public()).take(3).collect();
+	let pending_collations: Vec<_> = collators
+		.iter()
+		.map(|collator_id| {
+			(
+				PendingCollation::new(relay_parent, &para_id, &PeerId::random(), None),
+				collator_id.clone(),
+			)
+		})
+		.collect();
+
+	let mut reputation = ReputationStore::default();
+	reputation.note_outcome(para_id, collators[0].clone(), CollationOutcome::Seconded);
+	reputation.note_outcome(para_id, collators[2].clone(), CollationOutcome::Invalid);
+
+	let mut collations = CollationsPerRelayParent {
+		unfetched_collations: pending_collations.clone(),
+		..Default::default()
+	};
+
+	let fetch_order: Vec<_> = iter::from_fn(|| {
+		let finished_one = collations.waiting_collation.clone();
+		collations
+			.get_next_collation_to_fetch(finished_one.as_ref(), &mut reputation, |_| true)
+			.map(|(_, collator_id)| collator_id)
+	})
+	.collect();
+
+	// The unscored collator is preferred over the one which provided an invalid collation.
+	assert_eq!(fetch_order, vec![collators[0].clone(), collators[1].clone(), collators[2].clone()]);
+}
+
+/// Create a candidate of `para_id` at `relay_parent`.
+fn make_candidate(para_id: ParaId, relay_parent: Hash) -> CandidateReceipt {
+	let mut candidate = dummy_candidate_receipt_bad_sig(dummy_hash(), Some(Default::default()));
+	candidate.descriptor.para_id = para_id;
+	candidate.descriptor.relay_parent = relay_parent;
+	candidate
+}
+
+// A candidate advertised by several `v2` collators is only fetched once.
+#[test]
+fn v2_advertisements_of_known_candidates_are_fetched_once() {
+	let test_state = TestState::default();
+
+	test_harness(|test_harness| async move {
+		let TestHarness { mut virtual_overseer, .. } = test_harness;
+
+		overseer_send(
+			&mut virtual_overseer,
+			CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::OurViewChange(
+				our_view![test_state.relay_parent],
+			)),
+		)
+		.await;
+
+		respond_to_core_info_queries(&mut virtual_overseer, &test_state).await;
+
+		let peer_b = PeerId::random();
+		let peer_c = PeerId::random();
+		let peer_d = PeerId::random();
+
+		for (peer, collator) in [peer_b, peer_c, peer_d].iter().zip(&test_state.collators) {
+			connect_and_declare_collator(
+				&mut virtual_overseer,
+				peer.clone(),
+				collator.clone(),
+				test_state.chain_ids[0],
+			)
+			.await;
+		}
+
+		let candidate_a = make_candidate(test_state.chain_ids[0], test_state.relay_parent);
+
+		advertise_collation_v2(
+			&mut virtual_overseer,
+			peer_b.clone(),
+			test_state.relay_parent,
+			candidate_a.hash(),
+			test_state.parent_head_data_hash(),
+		)
+		.await;
+
+		let response_channel = assert_fetch_collation_request(
+			&mut virtual_overseer,
+			test_state.relay_parent,
+			test_state.chain_ids[0],
+		)
+		.await;
+
+		// The candidate is not fetched yet, so the advertisement is queued.
+		advertise_collation_v2(
+			&mut virtual_overseer,
+			peer_c.clone(),
+			test_state.relay_parent,
+			candidate_a.hash(),
+			test_state.parent_head_data_hash(),
+		)
+		.await;
+
+		let pov = PoV { block_data: BlockData(vec![]) };
+		response_channel
+			.send(Ok(
+				CollationFetchingResponse::Collation(candidate_a.clone(), pov.clone()).encode()
+			))
+			.expect("Sending response should succeed");
+
+		let receipt = assert_candidate_backing_second(
+			&mut virtual_overseer,
+			test_state.relay_parent,
+			test_state.chain_ids[0],
+			&pov,
+		)
+		.await;
+
+		// The candidate was fetched already, so this advertisement is ignored.
+		advertise_collation_v2(
+			&mut virtual_overseer,
+			peer_d.clone(),
+			test_state.relay_parent,
+			candidate_a.hash(),
+			test_state.parent_head_data_hash(),
+		)
+		.await;
+
+		overseer_send(
+			&mut virtual_overseer,
+			CollatorProtocolMessage::Invalid(test_state.relay_parent, receipt),
+		)
+		.await;
+
+		assert_matches!(
+			overseer_recv(&mut virtual_overseer).await,
+			AllMessages::NetworkBridge(NetworkBridgeMessage::ReportPeer(
+				peer,
+				rep,
+			)) => {
+				assert_eq!(peer, peer_b);
+				assert_eq!(rep, COST_REPORT_BAD);
+			}
+		);
+
+		// The queued advertisement was dropped once the candidate was fetched, so there is
+		// nothing left to fetch.
+		assert!(
+			overseer_recv_with_timeout(&mut virtual_overseer, Duration::from_millis(30))
+				.await
+				.is_none(),
+			"The candidate should not be fetched twice",
+		);
+
+		virtual_overseer
+	});
+}
+
+// A candidate which failed to be fetched is fetched from the other collators advertising it.
+#[test]
+fn v2_advertisements_are_fetched_again_after_failed_fetch() {
+	let test_state = TestState::default();
+
+	test_harness(|test_harness| async move {
+		let TestHarness { mut virtual_overseer, .. } = test_harness;
+
+		overseer_send(
+			&mut virtual_overseer,
+			CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::OurViewChange(
+				our_view![test_state.relay_parent],
+			)),
+		)
+		.await;
+
+		respond_to_core_info_queries(&mut virtual_overseer, &test_state).await;
+
+		let peer_b = PeerId::random();
+		let peer_c = PeerId::random();
+
+		connect_and_declare_collator(
+			&mut virtual_overseer,
+			peer_b.clone(),
+			test_state.collators[0].clone(),
+			test_state.chain_ids[0],
+		)
+		.await;
+
+		connect_and_declare_collator(
+			&mut virtual_overseer,
+			peer_c.clone(),
+			test_state.collators[1].clone(),
+			test_state.chain_ids[0],
+		)
+		.await;
+
+		let candidate_a = make_candidate(test_state.chain_ids[0], test_state.relay_parent);
+
+		advertise_collation_v2(
+			&mut virtual_overseer,
+			peer_b.clone(),
+			test_state.relay_parent,
+			candidate_a.hash(),
+			test_state.parent_head_data_hash(),
+		)
+		.await;
+
+		let response_channel = assert_fetch_collation_request(
+			&mut virtual_overseer,
+			test_state.relay_parent,
+			test_state.chain_ids[0],
+		)
+		.await;
+
+		advertise_collation_v2(
+			&mut virtual_overseer,
+			peer_c.clone(),
+			test_state.relay_parent,
+			candidate_a.hash(),
+			test_state.parent_head_data_hash(),
+		)
+		.await;
+
+		// The first fetch fails.
+		drop(response_channel);
+
+		let response_channel = assert_fetch_collation_request(
+			&mut virtual_overseer,
+			test_state.relay_parent,
+			test_state.chain_ids[0],
+		)
+		.await;
+
+		let pov = PoV { block_data: BlockData(vec![]) };
+		response_channel
+			.send(Ok(
+				CollationFetchingResponse::Collation(candidate_a.clone(), pov.clone()).encode()
+			))
+			.expect("Sending response should succeed");
+
+		assert_candidate_backing_second(
+			&mut virtual_overseer,
+			test_state.relay_parent,
+			test_state.chain_ids[0],
+			&pov,
+		)
+		.await;
+
+		virtual_overseer
+	});
+}
+
+// Collations not matching the advertised candidate hash are rejected and their collators
+// reported.
+#[test]
+fn v2_collations_not_matching_the_advertisement_are_rejected() {
+	let test_state = TestState::default();
+
+	test_harness(|test_harness| async move {
+		let TestHarness { mut virtual_overseer, .. } = test_harness;
+
+		overseer_send(
+			&mut virtual_overseer,
+			CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::OurViewChange(
+				our_view![test_state.relay_parent],
+			)),
+		)
+		.await;
+
+		respond_to_core_info_queries(&mut virtual_overseer, &test_state).await;
+
+		let peer_b = PeerId::random();
+
+		connect_and_declare_collator(
+			&mut virtual_overseer,
+			peer_b.clone(),
+			test_state.collators[0].clone(),
+			test_state.chain_ids[0],
+		)
+		.await;
+
+		advertise_collation_v2(
+			&mut virtual_overseer,
+			peer_b.clone(),
+			test_state.relay_parent,
+			CandidateHash(Hash::repeat_byte(0xCC)),
+			test_state.parent_head_data_hash(),
+		)
+		.await;
+
+		let response_channel = assert_fetch_collation_request(
+			&mut virtual_overseer,
+			test_state.relay_parent,
+			test_state.chain_ids[0],
+		)
+		.await;
+
+		let candidate_a = make_candidate(test_state.chain_ids[0], test_state.relay_parent);
+		let pov = PoV { block_data: BlockData(vec![]) };
+		response_channel
+			.send(Ok(CollationFetchingResponse::Collation(candidate_a, pov).encode()))
+			.expect("Sending response should succeed");
+
+		assert_matches!(
+			overseer_recv(&mut virtual_overseer).await,
+			AllMessages::NetworkBridge(
+				NetworkBridgeMessage::ReportPeer(peer, rep),
+			) => {
+				assert_eq!(peer, peer_b);
+				assert_eq!(rep, COST_WRONG_CANDIDATE);
+			}
+		);
+
+		assert!(
+			overseer_recv_with_timeout(&mut virtual_overseer, Duration::from_millis(30))
+				.await
+				.is_none(),
+			"The collation should not be seconded",
+		);
+
+		virtual_overseer
+	});
+}
+
+// A `v2` advertisement of a collation not building on the para head is not fetched.
+#[test]
+fn stale_v2_advertisements_are_not_fetched() {
+	let test_state = TestState::default();
+
+	test_harness(|test_harness| async move {
+		let TestHarness { mut virtual_overseer, .. } = test_harness;
+
+		overseer_send(
+			&mut virtual_overseer,
+			CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::OurViewChange(
+				our_view![test_state.relay_parent],
+			)),
+		)
+		.await;
+
+		respond_to_core_info_queries(&mut virtual_overseer, &test_state).await;
+
+		let peer_b = PeerId::random();
+
+		connect_and_declare_collator(
+			&mut virtual_overseer,
+			peer_b.clone(),
+			test_state.collators[0].clone(),
+			test_state.chain_ids[0],
+		)
+		.await;
+
+		let candidate_a = make_candidate(test_state.chain_ids[0], test_state.relay_parent);
+
+		advertise_collation_v2(
+			&mut virtual_overseer,
+			peer_b.clone(),
+			test_state.relay_parent,
+			candidate_a.hash(),
+			HeadData(vec![1, 2, 3]).hash(),
+		)
+		.await;
+
+		assert_matches!(
+			overseer_recv(&mut virtual_overseer).await,
+			AllMessages::NetworkBridge(
+				NetworkBridgeMessage::ReportPeer(peer, rep),
+			) => {
+				assert_eq!(peer, peer_b);
+				assert_eq!(rep, COST_STALE_ADVERTISEMENT);
+			}
+		);
+
+		assert!(
+			overseer_recv_with_timeout(&mut virtual_overseer, Duration::from_millis(30))
+				.await
+				.is_none(),
+			"The stale collation should not be fetched",
+		);
+
+		// The same collator may still advertise a collation building on the para head.
+		advertise_collation_v2(
+			&mut virtual_overseer,
+			peer_b.clone(),
+			test_state.relay_parent,
+			candidate_a.hash(),
+			test_state.parent_head_data_hash(),
+		)
+		.await;
+
+		let _response_channel = assert_fetch_collation_request(
+			&mut virtual_overseer,
+			test_state.relay_parent,
+			test_state.chain_ids[0],
+		)
+		.await;
+
+		virtual_overseer
+	});
+}
+
+// Fetches exceeding the per para limit of the fetch policy are deferred until another fetch
+// finished.
+#[test]
+fn fetches_exceeding_para_limit_are_deferred() {
+	let test_state = TestState::default();
+	let fetch_policy =
+		CollationFetchLimits { max_fetches_per_para: 1, ..CollationFetchLimits::default() };
+
+	test_harness_with_fetch_policy(Box::new(fetch_policy), |test_harness| async move {
+		let TestHarness { mut virtual_overseer, .. } = test_harness;
+
+		let second = Hash::random();
+
+		overseer_send(
+			&mut virtual_overseer,
+			CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::OurViewChange(
+				our_view![test_state.relay_parent, second],
+			)),
+		)
+		.await;
+
+		respond_to_core_info_queries(&mut virtual_overseer, &test_state).await;
+		respond_to_core_info_queries(&mut virtual_overseer, &test_state).await;
+
+		let peer_b = PeerId::random();
+		let peer_c = PeerId::random();
+
+		connect_and_declare_collator(
+			&mut virtual_overseer,
+			peer_b.clone(),
+			test_state.collators[0].clone(),
+			test_state.chain_ids[0],
+		)
+		.await;
+
+		connect_and_declare_collator(
+			&mut virtual_overseer,
+			peer_c.clone(),
+			test_state.collators[1].clone(),
+			test_state.chain_ids[0],
+		)
+		.await;
+
+		advertise_collation(&mut virtual_overseer, peer_b.clone(), test_state.relay_parent).await;
+		advertise_collation(&mut virtual_overseer, peer_c.clone(), second).await;
+
+		let response_channel = assert_fetch_collation_request(
+			&mut virtual_overseer,
+			test_state.relay_parent,
+			test_state.chain_ids[0],
+		)
+		.await;
+
+		assert!(
+			overseer_recv_with_timeout(&mut virtual_overseer, Duration::from_millis(30))
+				.await
+				.is_none(),
+			"The para is at its fetch limit",
+		);
+
+		// Once the first fetch finished, the deferred one is started.
+		drop(response_channel);
+
+		assert_fetch_collation_request(&mut virtual_overseer, second, test_state.chain_ids[0])
+			.await;
+
+		virtual_overseer
+	});
+}
+
+// PoVs exceeding the maximum size of the fetch policy are rejected.
+#[test]
+fn oversized_povs_are_rejected() {
+	let test_state = TestState::default();
+	let fetch_policy = CollationFetchLimits { max_pov_size: 16, ..CollationFetchLimits::default() };
+
+	test_harness_with_fetch_policy(Box::new(fetch_policy), |test_harness| async move {
+		let TestHarness { mut virtual_overseer, .. } = test_harness;
+
+		overseer_send(
+			&mut virtual_overseer,
+			CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::OurViewChange(
+				our_view![test_state.relay_parent],
+			)),
+		)
+		.await;
+
+		respond_to_core_info_queries(&mut virtual_overseer, &test_state).await;
+
+		let peer_b = PeerId::random();
+
+		connect_and_declare_collator(
+			&mut virtual_overseer,
+			peer_b.clone(),
+			test_state.collators[0].clone(),
+			test_state.chain_ids[0],
+		)
+		.await;
+
+		advertise_collation(&mut virtual_overseer, peer_b.clone(), test_state.relay_parent).await;
+
+		let response_channel = assert_fetch_collation_request(
+			&mut virtual_overseer,
+			test_state.relay_parent,
+			test_state.chain_ids[0],
+		)
+		.await;
+
+		let pov = PoV { block_data: BlockData(vec![42; 64]) };
+		let mut candidate_a =
+			dummy_candidate_receipt_bad_sig(dummy_hash(), Some(Default::default()));
+		candidate_a.descriptor.para_id = test_state.chain_ids[0];
+		candidate_a.descriptor.relay_parent = test_state.relay_parent;
+		response_channel
+			.send(Ok(CollationFetchingResponse::Collation(candidate_a, pov).encode()))
+			.expect("Sending response should succeed");
+
+		assert_matches!(
+			overseer_recv(&mut virtual_overseer).await,
+			AllMessages::NetworkBridge(NetworkBridgeMessage::ReportPeer(
+				peer,
+				rep,
+			)) => {
+				assert_eq!(peer, peer_b);
+				assert_eq!(rep, COST_OVERSIZED_POV);
+			}
+		);
+
+		virtual_overseer
+	});
+}
+
+// Responses exceeding the maximum PoV size by more than a candidate receipt are not decoded.
+#[test]
+fn oversized_responses_are_rejected() {
+	let test_state = TestState::default();
+	let fetch_policy = CollationFetchLimits { max_pov_size: 16, ..CollationFetchLimits::default() };
+
+	test_harness_with_fetch_policy(Box::new(fetch_policy), |test_harness| async move {
+		let TestHarness { mut virtual_overseer, .. } = test_harness;
+
+		overseer_send(
+			&mut virtual_overseer,
+			CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::OurViewChange(
+				our_view![test_state.relay_parent],
+			)),
+		)
+		.await;
+
+		respond_to_core_info_queries(&mut virtual_overseer, &test_state).await;
+
+		let peer_b = PeerId::random();
+
+		connect_and_declare_collator(
+			&mut virtual_overseer,
+			peer_b.clone(),
+			test_state.collators[0].clone(),
+			test_state.chain_ids[0],
+		)
+		.await;
+
+		advertise_collation(&mut virtual_overseer, peer_b.clone(), test_state.relay_parent).await;
+
+		let response_channel = assert_fetch_collation_request(
+			&mut virtual_overseer,
+			test_state.relay_parent,
+			test_state.chain_ids[0],
+		)
+		.await;
+
+		let pov = PoV { block_data: BlockData(vec![42; 16 + MAX_CANDIDATE_RECEIPT_OVERHEAD]) };
+		let candidate_a = make_candidate(test_state.chain_ids[0], test_state.relay_parent);
+		response_channel
+			.send(Ok(CollationFetchingResponse::Collation(candidate_a, pov).encode()))
+			.expect("Sending response should succeed");
+
+		assert_matches!(
+			overseer_recv(&mut virtual_overseer).await,
+			AllMessages::NetworkBridge(NetworkBridgeMessage::ReportPeer(
+				peer,
+				rep,
+			)) => {
+				assert_eq!(peer, peer_b);
+				assert_eq!(rep, COST_CORRUPTED_MESSAGE);
+			}
+		);
+
+		virtual_overseer
+	});
+}
+
+// A collation pushed by a declared collator is seconded without fetching it.
+#[test]
+fn pushed_collations_are_seconded_without_fetching() {
+	let test_state = TestState::default();
+
+	test_harness(|test_harness| async move {
+		let TestHarness { mut virtual_overseer, mut push_cfg } = test_harness;
+
+		overseer_send(
+			&mut virtual_overseer,
+			CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::OurViewChange(
+				our_view![test_state.relay_parent],
+			)),
+		)
+		.await;
+
+		respond_to_core_info_queries(&mut virtual_overseer, &test_state).await;
+
+		let peer_b = PeerId::random();
+		let peer_c = PeerId::random();
+
+		for (peer, collator) in [peer_b, peer_c].iter().zip(&test_state.collators) {
+			connect_and_declare_collator(
+				&mut virtual_overseer,
+				peer.clone(),
+				collator.clone(),
+				test_state.chain_ids[0],
+			)
+			.await;
+		}
+
+		let candidate_a = make_candidate(test_state.chain_ids[0], test_state.relay_parent);
+		let pov = PoV { block_data: BlockData(vec![1]) };
+
+		let response =
+			push_collation(&mut push_cfg, peer_b.clone(), candidate_a.clone(), pov.clone()).await;
+
+		assert_candidate_backing_second(
+			&mut virtual_overseer,
+			test_state.relay_parent,
+			test_state.chain_ids[0],
+			&pov,
+		)
+		.await;
+
+		let response = response.await.expect("Push should be answered");
+		assert_eq!(
+			CollationPushingResponse::decode(&mut response.result.unwrap().as_slice()).unwrap(),
+			CollationPushingResponse::Received,
+		);
+
+		// The candidate was pushed already, so the advertisement is ignored.
+		advertise_collation_v2(
+			&mut virtual_overseer,
+			peer_c.clone(),
+			test_state.relay_parent,
+			candidate_a.hash(),
+			test_state.parent_head_data_hash(),
+		)
+		.await;
+
+		assert!(
+			overseer_recv_with_timeout(&mut virtual_overseer, Duration::from_millis(30))
+				.await
+				.is_none(),
+			"The pushed candidate should not be fetched",
+		);
+
+		virtual_overseer
+	});
+}
+
+// Pushes are declined while the para is at the fetch limit of the fetch policy.
+#[test]
+fn pushes_exceeding_para_limit_are_declined() {
+	let test_state = TestState::default();
+	let fetch_policy =
+		CollationFetchLimits { max_fetches_per_para: 1, ..CollationFetchLimits::default() };
+
+	test_harness_with_fetch_policy(Box::new(fetch_policy), |test_harness| async move {
+		let TestHarness { mut virtual_overseer, mut push_cfg } = test_harness;
+
+		let second = Hash::random();
+
+		overseer_send(
+			&mut virtual_overseer,
+			CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::OurViewChange(
+				our_view![test_state.relay_parent, second],
+			)),
+		)
+		.await;
+
+		respond_to_core_info_queries(&mut virtual_overseer, &test_state).await;
+		respond_to_core_info_queries(&mut virtual_overseer, &test_state).await;
+
+		let peer_b = PeerId::random();
+		let peer_c = PeerId::random();
+
+		for (peer, collator) in [peer_b, peer_c].iter().zip(&test_state.collators) {
+			connect_and_declare_collator(
+				&mut virtual_overseer,
+				peer.clone(),
+				collator.clone(),
+				test_state.chain_ids[0],
+			)
+			.await;
+		}
+
+		advertise_collation(&mut virtual_overseer, peer_b.clone(), test_state.relay_parent).await;
+
+		let _response_channel = assert_fetch_collation_request(
+			&mut virtual_overseer,
+			test_state.relay_parent,
+			test_state.chain_ids[0],
+		)
+		.await;
+
+		let candidate = make_candidate(test_state.chain_ids[0], second);
+		let pov = PoV { block_data: BlockData(vec![1]) };
+
+		let response = push_collation(&mut push_cfg, peer_c.clone(), candidate, pov).await;
+
+		let response = response.await.expect("Push should be answered");
+		assert!(response.result.is_err());
+		assert!(response.reputation_changes.is_empty());
+
+		assert!(
+			overseer_recv_with_timeout(&mut virtual_overseer, Duration::from_millis(30))
+				.await
+				.is_none(),
+			"The declined collation should not be seconded",
+		);
+
+		virtual_overseer
+	});
+}
+
+// Pushes of collations for a para the collator did not declare are rejected.
+#[test]
+fn pushed_collations_for_wrong_para_are_rejected() {
+	let test_state = TestState::default();
+
+	test_harness(|test_harness| async move {
+		let TestHarness { mut virtual_overseer, mut push_cfg } = test_harness;
+
+		overseer_send(
+			&mut virtual_overseer,
+			CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::OurViewChange(
+				our_view![test_state.relay_parent],
+			)),
+		)
+		.await;
+
+		respond_to_core_info_queries(&mut virtual_overseer, &test_state).await;
+
+		let peer_b = PeerId::random();
+
+		connect_and_declare_collator(
+			&mut virtual_overseer,
+			peer_b.clone(),
+			test_state.collators[0].clone(),
+			test_state.chain_ids[0],
+		)
+		.await;
+
+		let candidate = make_candidate(test_state.chain_ids[1], test_state.relay_parent);
+		let pov = PoV { block_data: BlockData(vec![1]) };
+
+		let response = push_collation(&mut push_cfg, peer_b.clone(), candidate, pov).await;
+
+		let response = response.await.expect("Push should be answered");
+		assert!(response.result.is_err());
+		assert_eq!(response.reputation_changes, vec![COST_WRONG_PARA.into_base_rep()]);
+
+		assert!(
+			overseer_recv_with_timeout(&mut virtual_overseer, Duration::from_millis(30))
+				.await
+				.is_none(),
+			"The rejected collation should not be seconded",
+		);
+
+		virtual_overseer
+	});
+}
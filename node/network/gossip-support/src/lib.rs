@@ -26,7 +26,7 @@
 
 use std::{
 	collections::{HashMap, HashSet},
-	fmt,
+	fmt, io,
 	time::{Duration, Instant},
 };
 
@@ -60,8 +60,13 @@ use polkadot_primitives::v2::{
 mod tests;
 
 mod metrics;
+mod topology;
 
 use metrics::Metrics;
+pub use polkadot_node_network_protocol::grid_topology::{
+	GossipTopologyIntrospection, TopologySnapshot,
+};
+pub use topology::TopologyOverride;
 
 const LOG_TARGET: &str = "parachain::gossip-support";
 // How much time should we wait to reissue a connection request
@@ -80,7 +85,17 @@ const LOW_CONNECTIVITY_WARN_DELAY: Duration = Duration::from_secs(600);
 /// If connectivity is lower than this in percent, issue warning in logs.
 const LOW_CONNECTIVITY_WARN_THRESHOLD: usize = 90;
 
+/// Configuration of the gossip support subsystem.
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+	/// Arrange the authorities in the grid topology in a fixed order instead of a random one.
+	pub topology_override: Option<TopologyOverride>,
+	/// Where the subsystem publishes the gossip topology and the connected authorities.
+	pub introspection: GossipTopologyIntrospection,
+}
+
 /// The Gossip Support subsystem.
+#[derive(Clone)]
 pub struct GossipSupport<AD> {
 	keystore: SyncCryptoStorePtr,
 
@@ -111,6 +126,11 @@ pub struct GossipSupport<AD> {
 	/// Authority discovery service.
 	authority_discovery: AD,
 
+	/// The fixed order of the authorities in the grid topology, if any.
+	topology_override: Option<TopologyOverride>,
+	/// Published topology and connected authorities.
+	introspection: GossipTopologyIntrospection,
+
 	/// Subsystem metrics.
 	metrics: Metrics,
 }
@@ -121,6 +141,16 @@ where
 {
 	/// Create a new instance of the [`GossipSupport`] subsystem.
 	pub fn new(keystore: SyncCryptoStorePtr, authority_discovery: AD, metrics: Metrics) -> Self {
+		Self::with_config(Config::default(), keystore, authority_discovery, metrics)
+	}
+
+	/// Create a new instance of the [`GossipSupport`] subsystem with the given configuration.
+	pub fn with_config(
+		config: Config,
+		keystore: SyncCryptoStorePtr,
+		authority_discovery: AD,
+		metrics: Metrics,
+	) -> Self {
 		// Initialize metrics to `0`.
 		metrics.on_is_not_authority();
 		metrics.on_is_not_parachain_validator();
@@ -134,6 +164,8 @@ where
 			connected_authorities: HashMap::new(),
 			connected_authorities_by_peer_id: HashMap::new(),
 			authority_discovery,
+			topology_override: config.topology_override,
+			introspection: config.introspection,
 			metrics,
 		}
 	}
@@ -278,6 +310,8 @@ where
 						session_info.discovery_keys,
 						relay_parent,
 						session_index,
+						self.topology_override.as_ref(),
+						&self.introspection,
 					)
 					.await?;
 				}
@@ -385,7 +419,7 @@ where
 
 	fn handle_connect_disconnect(&mut self, ev: NetworkBridgeEvent<GossipSuppportNetworkMessage>) {
 		match ev {
-			NetworkBridgeEvent::PeerConnected(peer_id, _, o_authority) => {
+			NetworkBridgeEvent::PeerConnected(peer_id, _, _, o_authority) => {
 				if let Some(authority_ids) = o_authority {
 					authority_ids.iter().for_each(|a| {
 						self.connected_authorities.insert(a.clone(), peer_id);
@@ -408,6 +442,7 @@ where
 				match v {};
 			},
 		}
+		self.introspection.note_connected_authorities(&self.connected_authorities);
 	}
 
 	/// Check connectivity and report on it in logs.
@@ -500,69 +535,144 @@ async fn remove_all_controlled(
 /// This limits the amount of gossip peers to 2 * `sqrt(len)` and ensures the diameter of 2.
 ///
 /// [web3]: https://research.web3.foundation/en/latest/polkadot/networking/3-avail-valid.html#topology
+///
+/// With a `topology_override` the authorities are arranged in the given order instead.
 async fn update_gossip_topology<Context>(
 	ctx: &mut Context,
 	our_index: usize,
 	authorities: Vec<AuthorityDiscoveryId>,
 	relay_parent: Hash,
 	session_index: SessionIndex,
+	topology_override: Option<&TopologyOverride>,
+	introspection: &GossipTopologyIntrospection,
 ) -> Result<(), util::Error>
 where
 	Context: SubsystemContext<Message = GossipSupportMessage>,
 	Context: overseer::SubsystemContext<Message = GossipSupportMessage>,
 {
-	// retrieve BABE randomness
-	let random_seed = {
-		let (tx, rx) = oneshot::channel();
-
-		// TODO https://github.com/paritytech/polkadot/issues/5316:
-		// get the random seed from the `SessionInfo` instead.
-		ctx.send_message(RuntimeApiMessage::Request(
-			relay_parent,
-			RuntimeApiRequest::CurrentBabeEpoch(tx),
-		))
-		.await;
+	let fixed_order = match topology_override {
+		None => None,
+		Some(topology_override) => match override_order(ctx, topology_override).await {
+			Ok(order) => Some(order),
+			Err(err) => {
+				gum::warn!(
+				target: LOG_TARGET,
+				?err,
+				"Failed to read the gossip topology override, falling back to a random topology",
+				);
+				None
+			},
+		},
+	};
 
-		let randomness = rx.await??.randomness;
-		let mut subject = [0u8; 40];
-		subject[..8].copy_from_slice(b"gossipsu");
-		subject[8..].copy_from_slice(&randomness);
-		sp_core::blake2_256(&subject)
+	let overridden = fixed_order.is_some();
+	let indices = match fixed_order {
+		Some(order) => topology::arrange(&order, &authorities),
+		None => random_order(ctx, relay_parent, authorities.len()).await?,
 	};
 
-	// shuffle the indices
-	let mut rng: ChaCha20Rng = SeedableRng::from_seed(random_seed);
 	let len = authorities.len();
-	let mut indices: Vec<usize> = (0..len).collect();
-	indices.shuffle(&mut rng);
 	let our_shuffled_position = indices
 		.iter()
 		.position(|i| *i == our_index)
 		.expect("our_index < len; indices contains it; qed");
 
 	let neighbors = matrix_neighbors(our_shuffled_position, len);
-	let row_neighbors = neighbors
+	let row_neighbors: Vec<_> = neighbors
 		.row_neighbors
 		.map(|i| indices[i])
 		.map(|i| (authorities[i].clone(), ValidatorIndex::from(i as u32)))
 		.collect();
 
-	let column_neighbors = neighbors
+	let column_neighbors: Vec<_> = neighbors
 		.column_neighbors
 		.map(|i| indices[i])
 		.map(|i| (authorities[i].clone(), ValidatorIndex::from(i as u32)))
 		.collect();
 
+	introspection.note_topology(TopologySnapshot {
+		session: session_index,
+		grid: indices.iter().map(|i| authorities[*i].clone()).collect(),
+		overridden,
+		our_neighbors_x: row_neighbors.clone(),
+		our_neighbors_y: column_neighbors.clone(),
+	});
+
 	ctx.send_message(NetworkBridgeMessage::NewGossipTopology {
 		session: session_index,
-		our_neighbors_x: row_neighbors,
-		our_neighbors_y: column_neighbors,
+		our_neighbors_x: row_neighbors.into_iter().collect(),
+		our_neighbors_y: column_neighbors.into_iter().collect(),
 	})
 	.await;
 
 	Ok(())
 }
 
+/// Get the order of the authorities given by `topology_override`.
+///
+/// Override files are read on a blocking task, to not stall the subsystem on the file system.
+async fn override_order<Context>(
+	ctx: &mut Context,
+	topology_override: &TopologyOverride,
+) -> io::Result<Vec<AuthorityDiscoveryId>>
+where
+	Context: SubsystemContext<Message = GossipSupportMessage>,
+	Context: overseer::SubsystemContext<Message = GossipSupportMessage>,
+{
+	let path = match topology_override {
+		TopologyOverride::Fixed(order) => return Ok(order.clone()),
+		TopologyOverride::File(path) => path.clone(),
+	};
+
+	let (tx, rx) = oneshot::channel();
+	ctx.spawn_blocking(
+		"gossip-topology-override",
+		Box::pin(async move {
+			let _ = tx.send(topology::read_order(&path));
+		}),
+	)
+	.map_err(|err| io::Error::new(io::ErrorKind::Other, err.to_string()))?;
+
+	rx.await.map_err(|_| {
+		io::Error::new(io::ErrorKind::Other, "Reading the topology override was canceled")
+	})?
+}
+
+/// Shuffle the indices of the authorities with the BABE randomness of the session.
+async fn random_order<Context>(
+	ctx: &mut Context,
+	relay_parent: Hash,
+	len: usize,
+) -> Result<Vec<usize>, util::Error>
+where
+	Context: SubsystemContext<Message = GossipSupportMessage>,
+	Context: overseer::SubsystemContext<Message = GossipSupportMessage>,
+{
+	// retrieve BABE randomness
+	let random_seed = {
+		let (tx, rx) = oneshot::channel();
+
+		// TODO https://github.com/paritytech/polkadot/issues/5316:
+		// get the random seed from the `SessionInfo` instead.
+		ctx.send_message(RuntimeApiMessage::Request(
+			relay_parent,
+			RuntimeApiRequest::CurrentBabeEpoch(tx),
+		))
+		.await;
+
+		let randomness = rx.await??.randomness;
+		let mut subject = [0u8; 40];
+		subject[..8].copy_from_slice(b"gossipsu");
+		subject[8..].copy_from_slice(&randomness);
+		sp_core::blake2_256(&subject)
+	};
+
+	let mut rng: ChaCha20Rng = SeedableRng::from_seed(random_seed);
+	let mut indices: Vec<usize> = (0..len).collect();
+	indices.shuffle(&mut rng);
+	Ok(indices)
+}
+
 struct MatrixNeighbors<R, C> {
 	row_neighbors: R,
 	column_neighbors: C,
@@ -659,3 +659,102 @@ fn test_matrix_neighbors() {
 		assert_eq!(column_result, expected_column);
 	}
 }
+
+#[test]
+fn fixed_topology_override_is_used() {
+	// Alice is missing from the order, so she is placed last.
+	let order: Vec<AuthorityDiscoveryId> = AUTHORITIES.iter().skip(1).rev().cloned().collect();
+	let introspection = GossipTopologyIntrospection::default();
+	let subsystem = GossipSupport::with_config(
+		Config {
+			topology_override: Some(TopologyOverride::Fixed(order)),
+			introspection: introspection.clone(),
+		},
+		make_ferdie_keystore(),
+		MOCK_AUTHORITY_DISCOVERY.clone(),
+		Metrics::new_dummy(),
+	);
+
+	// [6 5]
+	// [4 3]
+	// [2 1]
+	// [0  ]
+
+	let expected_row = vec![(Sr25519Keyring::Two.public().into(), ValidatorIndex::from(5))];
+	let expected_column = vec![
+		(Sr25519Keyring::Alice.public().into(), ValidatorIndex::from(0)),
+		(Sr25519Keyring::Charlie.public().into(), ValidatorIndex::from(2)),
+		(Sr25519Keyring::One.public().into(), ValidatorIndex::from(4)),
+	];
+
+	let hash = Hash::repeat_byte(0xAA);
+	let expected = (expected_row.clone(), expected_column.clone());
+	let state = test_harness(subsystem, |mut virtual_overseer| async move {
+		let overseer = &mut virtual_overseer;
+		overseer_signal_active_leaves(overseer, hash).await;
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+				_,
+				RuntimeApiRequest::SessionIndexForChild(tx),
+			)) => {
+				tx.send(Ok(1)).unwrap();
+			}
+		);
+
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+				_,
+				RuntimeApiRequest::SessionInfo(_, tx),
+			)) => {
+				tx.send(Ok(Some(make_session_info()))).unwrap();
+			}
+		);
+
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+				_,
+				RuntimeApiRequest::Authorities(tx),
+			)) => {
+				tx.send(Ok(AUTHORITIES.clone())).unwrap();
+			}
+		);
+
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::NetworkBridge(NetworkBridgeMessage::ConnectToResolvedValidators { .. })
+		);
+
+		// No BABE randomness is requested for a fixed topology.
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::NetworkBridge(NetworkBridgeMessage::NewGossipTopology {
+				session: 1,
+				our_neighbors_x,
+				our_neighbors_y,
+			}) => {
+				let mut got_row: Vec<_> = our_neighbors_x.into_iter().collect();
+				let mut got_column: Vec<_> = our_neighbors_y.into_iter().collect();
+				got_row.sort();
+				got_column.sort();
+				assert_eq!(got_row, expected.0);
+				assert_eq!(got_column, expected.1);
+			}
+		);
+
+		virtual_overseer
+	});
+
+	assert_eq!(state.last_session_index, Some(1));
+
+	let topology = introspection.topology().expect("topology is noted");
+	assert_eq!(topology.session, 1);
+	assert!(topology.overridden);
+	assert_eq!(topology.grid, AUTHORITIES.iter().rev().cloned().collect::<Vec<_>>());
+	assert_eq!(topology.our_neighbors_x, expected_row);
+	let mut got_column = topology.our_neighbors_y;
+	got_column.sort();
+	assert_eq!(got_column, expected_column);
+}
@@ -0,0 +1,79 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Overriding the gossip topology.
+//!
+//! On live networks the authorities are arranged in the grid in a random order, derived from
+//! BABE randomness. For deterministic local testing the order can be fixed instead.
+
+use std::{
+	collections::HashSet,
+	fs, io,
+	path::{Path, PathBuf},
+};
+
+use sp_core::crypto::Ss58Codec;
+
+use polkadot_primitives::v2::AuthorityDiscoveryId;
+
+/// The order to arrange the authorities of a session in the grid topology.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TopologyOverride {
+	/// Arrange the authorities in the given order, row by row.
+	Fixed(Vec<AuthorityDiscoveryId>),
+	/// Read the order from a file, holding one SS58 encoded authority discovery key per line.
+	///
+	/// Empty lines and lines starting with `#` are ignored. The file is read anew for every
+	/// session, so it can be changed while the node is running.
+	File(PathBuf),
+}
+
+/// Read the order of the authorities from the file at `path`.
+///
+/// This blocks on the file system, so it should not be called from the subsystem loop.
+pub(crate) fn read_order(path: &Path) -> io::Result<Vec<AuthorityDiscoveryId>> {
+	fs::read_to_string(path)?
+		.lines()
+		.map(str::trim)
+		.filter(|line| !line.is_empty() && !line.starts_with('#'))
+		.map(|line| {
+			AuthorityDiscoveryId::from_ss58check(line).map_err(|err| {
+				io::Error::new(
+					io::ErrorKind::InvalidData,
+					format!("Invalid authority discovery key {}: {:?}", line, err),
+				)
+			})
+		})
+		.collect()
+}
+
+/// Arrange the session `authorities` by `order`, returning their indices in the session.
+///
+/// Authorities missing from `order` follow in the order of the session, such that the grid
+/// always covers all authorities. Authorities of other sessions in `order` are ignored.
+pub(crate) fn arrange(
+	order: &[AuthorityDiscoveryId],
+	authorities: &[AuthorityDiscoveryId],
+) -> Vec<usize> {
+	let mut placed = HashSet::with_capacity(authorities.len());
+	let mut indices: Vec<usize> = order
+		.iter()
+		.filter_map(|authority| authorities.iter().position(|a| a == authority))
+		.filter(|i| placed.insert(*i))
+		.collect();
+	indices.extend((0..authorities.len()).filter(|i| !placed.contains(i)));
+	indices
+}
@@ -0,0 +1,97 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Observing the gossip topology computed by the gossip support subsystem.
+//!
+//! Kept apart from the subsystem, such that e.g. RPCs can read the topology without depending
+//! on the subsystem.
+
+use std::{
+	collections::HashMap,
+	fmt,
+	sync::{Arc, Mutex},
+};
+
+use polkadot_primitives::v2::{AuthorityDiscoveryId, SessionIndex, ValidatorIndex};
+
+use crate::PeerId;
+
+/// The gossip topology of a session, as computed by the gossip support subsystem.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TopologySnapshot {
+	/// The session the topology is used in.
+	pub session: SessionIndex,
+	/// The authorities of the session in the order of the grid, row by row.
+	pub grid: Vec<AuthorityDiscoveryId>,
+	/// Whether the order of the grid was fixed by the node configuration.
+	pub overridden: bool,
+	/// Our neighbors in the row of the grid.
+	pub our_neighbors_x: Vec<(AuthorityDiscoveryId, ValidatorIndex)>,
+	/// Our neighbors in the column of the grid.
+	pub our_neighbors_y: Vec<(AuthorityDiscoveryId, ValidatorIndex)>,
+}
+
+#[derive(Default)]
+struct IntrospectionState {
+	topology: Option<TopologySnapshot>,
+	connected_authorities: HashMap<AuthorityDiscoveryId, PeerId>,
+}
+
+/// Shared access to the gossip topology and the connected authorities known to the gossip
+/// support subsystem, e.g. for RPCs.
+#[derive(Clone, Default)]
+pub struct GossipTopologyIntrospection(Arc<Mutex<IntrospectionState>>);
+
+impl GossipTopologyIntrospection {
+	fn state(&self) -> std::sync::MutexGuard<'_, IntrospectionState> {
+		self.0.lock().unwrap_or_else(|e| e.into_inner())
+	}
+
+	/// The topology of the latest session we are an authority in, if any.
+	pub fn topology(&self) -> Option<TopologySnapshot> {
+		self.state().topology.clone()
+	}
+
+	/// The authorities we are connected to on the validation peer-set, with their peer ids.
+	pub fn connected_authorities(&self) -> Vec<(AuthorityDiscoveryId, PeerId)> {
+		self.state()
+			.connected_authorities
+			.iter()
+			.map(|(authority, peer)| (authority.clone(), *peer))
+			.collect()
+	}
+
+	/// Note the topology of a new session, called by the gossip support subsystem.
+	pub fn note_topology(&self, topology: TopologySnapshot) {
+		self.state().topology = Some(topology);
+	}
+
+	/// Note the authorities we are connected to, called by the gossip support subsystem.
+	pub fn note_connected_authorities(
+		&self,
+		connected_authorities: &HashMap<AuthorityDiscoveryId, PeerId>,
+	) {
+		self.state().connected_authorities = connected_authorities.clone();
+	}
+}
+
+impl fmt::Debug for GossipTopologyIntrospection {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("GossipTopologyIntrospection")
+			.field("session", &self.state().topology.as_ref().map(|t| t.session))
+			.finish()
+	}
+}
@@ -41,6 +41,9 @@ pub mod request_response;
 /// Accessing authority discovery service
 pub mod authority_discovery;
 
+/// Observing the gossip topology.
+pub mod grid_topology;
+
 /// A version of the protocol.
 pub type ProtocolVersion = u32;
 /// The minimum amount of peers to send gossip messages to.
@@ -386,6 +389,21 @@ pub mod v1 {
 	#[derive(Debug, Clone, PartialEq, Eq)]
 	pub enum GossipSuppportNetworkMessage {}
 
+	// Never sent over the wire, but network events carrying it can be recorded.
+	impl Encode for GossipSuppportNetworkMessage {
+		fn encode_to<T: parity_scale_codec::Output + ?Sized>(&self, _dest: &mut T) {
+			match *self {}
+		}
+	}
+
+	impl Decode for GossipSuppportNetworkMessage {
+		fn decode<I: parity_scale_codec::Input>(
+			_input: &mut I,
+		) -> Result<Self, parity_scale_codec::Error> {
+			Err("GossipSuppportNetworkMessage has no variants".into())
+		}
+	}
+
 	/// Network messages used by the collator protocol subsystem
 	#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
 	pub enum CollatorProtocolMessage {
@@ -454,3 +472,393 @@ pub mod v1 {
 		payload
 	}
 }
+
+/// v2 protocol types.
+///
+/// Messages which didn't change compared to the first version are re-exported from [`v1`].
+pub mod v2 {
+	use parity_scale_codec::{Decode, Encode};
+
+	use polkadot_primitives::v2::{
+		CandidateHash, CandidateIndex, CollatorId, CollatorSignature, Hash, Id as ParaId,
+	};
+
+	use polkadot_node_primitives::{
+		approval::{
+			CandidateBitfield, IndirectAssignmentCert, IndirectAssignmentCertV2,
+			IndirectSignedApprovalVote, IndirectSignedApprovalVoteV2,
+		},
+		UncheckedSignedFullStatement,
+	};
+
+	use crate::WrongVariant;
+
+	pub use super::v1::{
+		declare_signature_payload, BitfieldDistributionMessage, GossipSuppportNetworkMessage,
+		StatementDistributionMessage,
+	};
+
+	/// Network messages used by the approval distribution subsystem.
+	#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+	pub enum ApprovalDistributionMessage {
+		/// Assignments for candidates in recent, unfinalized blocks, where each certificate
+		/// may claim multiple candidates of the same block.
+		///
+		/// Actually checking the assignment may yield a different result.
+		#[codec(index = 0)]
+		Assignments(Vec<(IndirectAssignmentCertV2, CandidateBitfield)>),
+		/// Approvals for candidates in some recent, unfinalized block, where each vote
+		/// may approve multiple candidates of the same block with a single signature.
+		#[codec(index = 1)]
+		Approvals(Vec<IndirectSignedApprovalVoteV2>),
+	}
+
+	impl From<super::v1::ApprovalDistributionMessage> for ApprovalDistributionMessage {
+		/// Upgrade a message of a peer which only speaks the first version of the protocol.
+		fn from(message: super::v1::ApprovalDistributionMessage) -> Self {
+			match message {
+				super::v1::ApprovalDistributionMessage::Assignments(assignments) =>
+					Self::Assignments(
+						assignments
+							.into_iter()
+							.map(|(cert, candidate_index)| (cert.into(), candidate_index.into()))
+							.collect(),
+					),
+				super::v1::ApprovalDistributionMessage::Approvals(approvals) =>
+					Self::Approvals(approvals.into_iter().map(Into::into).collect()),
+			}
+		}
+	}
+
+	impl ApprovalDistributionMessage {
+		/// Downgrade the message for a peer which only speaks the first version of the protocol.
+		///
+		/// Returns `None` if any of the assignments or approvals can't be expressed in the first
+		/// version.
+		pub fn into_v1(self) -> Option<super::v1::ApprovalDistributionMessage> {
+			Some(match self {
+				Self::Assignments(assignments) =>
+					super::v1::ApprovalDistributionMessage::Assignments(
+						assignments
+							.into_iter()
+							.map(|(cert, claimed_candidates)| {
+								assignment_into_v1(cert, &claimed_candidates)
+							})
+							.collect::<Option<_>>()?,
+					),
+				Self::Approvals(approvals) => super::v1::ApprovalDistributionMessage::Approvals(
+					approvals
+						.into_iter()
+						.map(|vote| IndirectSignedApprovalVote::try_from(vote).ok())
+						.collect::<Option<_>>()?,
+				),
+			})
+		}
+	}
+
+	/// Convert an assignment into the format of the first version of the protocol.
+	///
+	/// Returns `None` if the certificate is of a kind unknown to the first version or claims more
+	/// than one candidate.
+	pub fn assignment_into_v1(
+		cert: IndirectAssignmentCertV2,
+		claimed_candidates: &CandidateBitfield,
+	) -> Option<(IndirectAssignmentCert, CandidateIndex)> {
+		if claimed_candidates.count_ones() != 1 {
+			return None
+		}
+
+		let candidate_index = claimed_candidates.first_one()? as CandidateIndex;
+		IndirectAssignmentCert::try_from(cert).ok().map(|cert| (cert, candidate_index))
+	}
+
+	/// Network messages used by the collator protocol subsystem
+	#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+	pub enum CollatorProtocolMessage {
+		/// Declare the intent to advertise collations under a collator ID, attaching a
+		/// signature of the `PeerId` of the node using the given collator ID key.
+		#[codec(index = 0)]
+		Declare(CollatorId, ParaId, CollatorSignature),
+		/// Advertise a collation to a validator. Can only be sent once the peer has
+		/// declared that they are a collator with given ID.
+		#[codec(index = 1)]
+		AdvertiseCollation {
+			/// Hash of the relay parent advertised collation is based on.
+			relay_parent: Hash,
+			/// Candidate hash.
+			candidate_hash: CandidateHash,
+			/// Parachain head data hash before candidate execution.
+			parent_head_data_hash: Hash,
+		},
+		/// A collation sent to a validator was seconded.
+		#[codec(index = 4)]
+		CollationSeconded(Hash, UncheckedSignedFullStatement),
+	}
+
+	impl From<CollatorProtocolMessage> for super::v1::CollatorProtocolMessage {
+		/// Downgrade a message for a peer which only speaks the first version of the protocol.
+		fn from(message: CollatorProtocolMessage) -> Self {
+			match message {
+				CollatorProtocolMessage::Declare(collator_id, para_id, signature) =>
+					Self::Declare(collator_id, para_id, signature),
+				CollatorProtocolMessage::AdvertiseCollation { relay_parent, .. } =>
+					Self::AdvertiseCollation(relay_parent),
+				CollatorProtocolMessage::CollationSeconded(relay_parent, statement) =>
+					Self::CollationSeconded(relay_parent, statement),
+			}
+		}
+	}
+
+	/// All network messages on the collation peer-set.
+	#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+	pub enum CollationProtocol {
+		/// Collator protocol messages
+		#[codec(index = 0)]
+		CollatorProtocol(CollatorProtocolMessage),
+	}
+
+	impl_try_from!(CollationProtocol, CollatorProtocol, CollatorProtocolMessage);
+
+	impl From<CollationProtocol> for super::v1::CollationProtocol {
+		fn from(message: CollationProtocol) -> Self {
+			match message {
+				CollationProtocol::CollatorProtocol(message) =>
+					Self::CollatorProtocol(message.into()),
+			}
+		}
+	}
+
+	/// All network messages on the validation peer-set.
+	#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+	pub enum ValidationProtocol {
+		/// Bitfield distribution messages
+		#[codec(index = 1)]
+		BitfieldDistribution(BitfieldDistributionMessage),
+		/// Statement distribution messages
+		#[codec(index = 3)]
+		StatementDistribution(StatementDistributionMessage),
+		/// Approval distribution messages
+		#[codec(index = 4)]
+		ApprovalDistribution(ApprovalDistributionMessage),
+	}
+
+	impl_try_from!(ValidationProtocol, BitfieldDistribution, BitfieldDistributionMessage);
+	impl_try_from!(ValidationProtocol, StatementDistribution, StatementDistributionMessage);
+	impl_try_from!(ValidationProtocol, ApprovalDistribution, ApprovalDistributionMessage);
+
+	impl TryFrom<ValidationProtocol> for GossipSuppportNetworkMessage {
+		type Error = WrongVariant;
+		fn try_from(_: ValidationProtocol) -> Result<Self, Self::Error> {
+			Err(WrongVariant)
+		}
+	}
+
+	impl<'a> TryFrom<&'a ValidationProtocol> for &'a GossipSuppportNetworkMessage {
+		type Error = WrongVariant;
+		fn try_from(_: &'a ValidationProtocol) -> Result<Self, Self::Error> {
+			Err(WrongVariant)
+		}
+	}
+
+	impl From<super::v1::ValidationProtocol> for ValidationProtocol {
+		/// Upgrade a message of a peer which only speaks the first version of the protocol.
+		fn from(message: super::v1::ValidationProtocol) -> Self {
+			match message {
+				super::v1::ValidationProtocol::BitfieldDistribution(m) =>
+					Self::BitfieldDistribution(m),
+				super::v1::ValidationProtocol::StatementDistribution(m) =>
+					Self::StatementDistribution(m),
+				super::v1::ValidationProtocol::ApprovalDistribution(m) =>
+					Self::ApprovalDistribution(m.into()),
+			}
+		}
+	}
+
+	impl ValidationProtocol {
+		/// Downgrade the message for a peer which only speaks the first version of the protocol.
+		///
+		/// Returns `None` if the message can't be expressed in the first version.
+		pub fn into_v1(self) -> Option<super::v1::ValidationProtocol> {
+			Some(match self {
+				Self::BitfieldDistribution(m) =>
+					super::v1::ValidationProtocol::BitfieldDistribution(m),
+				Self::StatementDistribution(m) =>
+					super::v1::ValidationProtocol::StatementDistribution(m),
+				Self::ApprovalDistribution(m) =>
+					super::v1::ValidationProtocol::ApprovalDistribution(m.into_v1()?),
+			})
+		}
+	}
+}
+
+/// A collator protocol message of any of the versions supported on the collation peer-set.
+///
+/// The encoding includes the version, it is only used for recordings of the overseer.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub enum VersionedCollatorProtocolMessage {
+	/// A message of a peer speaking the first version of the protocol.
+	#[codec(index = 1)]
+	V1(v1::CollatorProtocolMessage),
+	/// A message of a peer speaking the second version of the protocol.
+	#[codec(index = 2)]
+	V2(v2::CollatorProtocolMessage),
+}
+
+impl From<CollationProtocols> for VersionedCollatorProtocolMessage {
+	fn from(message: CollationProtocols) -> Self {
+		match message {
+			CollationProtocols::V1(v1::CollationProtocol::CollatorProtocol(m)) => Self::V1(m),
+			CollationProtocols::V2(v2::CollationProtocol::CollatorProtocol(m)) => Self::V2(m),
+		}
+	}
+}
+
+/// The network messages of a peer-set, of any of the protocol versions supported on it.
+///
+/// The version is not part of the encoding, it is negotiated with each peer when the
+/// notification stream is opened.
+pub trait VersionedProtocol: Encode + Clone + Sized {
+	/// Decode a message sent by a peer speaking the given protocol version.
+	fn decode_versioned(
+		version: ProtocolVersion,
+		input: &mut &[u8],
+	) -> Result<Self, parity_scale_codec::Error>;
+
+	/// The protocol version of the message.
+	fn version(&self) -> ProtocolVersion;
+
+	/// Convert the message for a peer speaking the given protocol version.
+	///
+	/// Returns `None` if the message can not be expressed in that version.
+	fn into_version(self, version: ProtocolVersion) -> Option<Self>;
+}
+
+/// All network messages on the validation peer-set, of any supported protocol version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationProtocols {
+	/// A message of the first version of the protocol.
+	V1(v1::ValidationProtocol),
+	/// A message of the second version of the protocol.
+	V2(v2::ValidationProtocol),
+}
+
+impl ValidationProtocols {
+	/// The peer-set the message is sent on to peers connected on it.
+	///
+	/// Approval traffic has a dedicated peer-set, it falls back to the validation peer-set for
+	/// peers which are not connected on that one.
+	pub fn peer_set(&self) -> peer_set::PeerSet {
+		match self {
+			Self::V1(v1::ValidationProtocol::ApprovalDistribution(_)) |
+			Self::V2(v2::ValidationProtocol::ApprovalDistribution(_)) => peer_set::PeerSet::Approval,
+			Self::V1(_) | Self::V2(_) => peer_set::PeerSet::Validation,
+		}
+	}
+}
+
+impl Encode for ValidationProtocols {
+	fn size_hint(&self) -> usize {
+		match self {
+			Self::V1(m) => m.size_hint(),
+			Self::V2(m) => m.size_hint(),
+		}
+	}
+
+	fn encode_to<T: parity_scale_codec::Output + ?Sized>(&self, dest: &mut T) {
+		match self {
+			Self::V1(m) => m.encode_to(dest),
+			Self::V2(m) => m.encode_to(dest),
+		}
+	}
+}
+
+impl VersionedProtocol for ValidationProtocols {
+	fn decode_versioned(
+		version: ProtocolVersion,
+		input: &mut &[u8],
+	) -> Result<Self, parity_scale_codec::Error> {
+		if version == peer_set::ValidationVersion::V1.into() {
+			v1::ValidationProtocol::decode(input).map(Self::V1)
+		} else if version == peer_set::ValidationVersion::V2.into() {
+			v2::ValidationProtocol::decode(input).map(Self::V2)
+		} else {
+			Err("Unsupported validation protocol version".into())
+		}
+	}
+
+	fn version(&self) -> ProtocolVersion {
+		match self {
+			Self::V1(_) => peer_set::ValidationVersion::V1.into(),
+			Self::V2(_) => peer_set::ValidationVersion::V2.into(),
+		}
+	}
+
+	fn into_version(self, version: ProtocolVersion) -> Option<Self> {
+		match self {
+			m if m.version() == version => Some(m),
+			// All messages of the first version can be expressed in the second one.
+			Self::V1(m) if version == peer_set::ValidationVersion::V2.into() =>
+				Some(Self::V2(m.into())),
+			Self::V2(m) if version == peer_set::ValidationVersion::V1.into() =>
+				m.into_v1().map(Self::V1),
+			_ => None,
+		}
+	}
+}
+
+/// All network messages on the collation peer-set, of any supported protocol version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CollationProtocols {
+	/// A message of the first version of the protocol.
+	V1(v1::CollationProtocol),
+	/// A message of the second version of the protocol.
+	V2(v2::CollationProtocol),
+}
+
+impl Encode for CollationProtocols {
+	fn size_hint(&self) -> usize {
+		match self {
+			Self::V1(m) => m.size_hint(),
+			Self::V2(m) => m.size_hint(),
+		}
+	}
+
+	fn encode_to<T: parity_scale_codec::Output + ?Sized>(&self, dest: &mut T) {
+		match self {
+			Self::V1(m) => m.encode_to(dest),
+			Self::V2(m) => m.encode_to(dest),
+		}
+	}
+}
+
+impl VersionedProtocol for CollationProtocols {
+	fn decode_versioned(
+		version: ProtocolVersion,
+		input: &mut &[u8],
+	) -> Result<Self, parity_scale_codec::Error> {
+		if version == peer_set::CollationVersion::V1.into() {
+			v1::CollationProtocol::decode(input).map(Self::V1)
+		} else if version == peer_set::CollationVersion::V2.into() {
+			v2::CollationProtocol::decode(input).map(Self::V2)
+		} else {
+			Err("Unsupported collation protocol version".into())
+		}
+	}
+
+	fn version(&self) -> ProtocolVersion {
+		match self {
+			Self::V1(_) => peer_set::CollationVersion::V1.into(),
+			Self::V2(_) => peer_set::CollationVersion::V2.into(),
+		}
+	}
+
+	fn into_version(self, version: ProtocolVersion) -> Option<Self> {
+		match self {
+			m if m.version() == version => Some(m),
+			// Messages of the second version can be downgraded for peers speaking the first one.
+			Self::V2(m) if version == peer_set::CollationVersion::V1.into() =>
+				Some(Self::V1(m.into())),
+			_ => None,
+		}
+	}
+}
@@ -16,6 +16,7 @@
 
 //! All peersets and protocols used for parachains.
 
+use super::ProtocolVersion;
 use sc_network::config::{NonDefaultSetConfig, SetConfig};
 use std::{
 	borrow::Cow,
@@ -31,6 +32,44 @@ pub enum PeerSet {
 	Validation,
 	/// The collation peer-set is used for validator<>collator communication.
 	Collation,
+	/// The approval peer-set carries the approval and dispute related gossip among validators,
+	/// such that floods of it don't compete with the rest of the validation traffic.
+	///
+	/// It connects to the same validators as the validation peer-set. Messages on it are
+	/// validation protocol messages.
+	Approval,
+}
+
+/// The versions of the validation protocol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ValidationVersion {
+	/// The first version.
+	V1 = 1,
+	/// The second version, adding assignment certificates which claim multiple candidates and
+	/// approvals covering multiple candidates.
+	V2 = 2,
+}
+
+impl From<ValidationVersion> for ProtocolVersion {
+	fn from(version: ValidationVersion) -> ProtocolVersion {
+		version as ProtocolVersion
+	}
+}
+
+/// The versions of the collation protocol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CollationVersion {
+	/// The first version, advertising collations by relay parent only.
+	V1 = 1,
+	/// The second version, advertising collations with their candidate hash and parent head data
+	/// hash.
+	V2 = 2,
+}
+
+impl From<CollationVersion> for ProtocolVersion {
+	fn from(version: CollationVersion) -> ProtocolVersion {
+		version as ProtocolVersion
+	}
 }
 
 /// Whether a node is an authority or not.
@@ -56,7 +95,7 @@ impl PeerSet {
 		match self {
 			PeerSet::Validation => NonDefaultSetConfig {
 				notifications_protocol: protocol,
-				fallback_names: Vec::new(),
+				fallback_names: self.get_fallback_protocol_names(),
 				max_notification_size,
 				set_config: sc_network::config::SetConfig {
 					// we allow full nodes to connect to validators for gossip
@@ -71,7 +110,7 @@ impl PeerSet {
 			},
 			PeerSet::Collation => NonDefaultSetConfig {
 				notifications_protocol: protocol,
-				fallback_names: Vec::new(),
+				fallback_names: self.get_fallback_protocol_names(),
 				max_notification_size,
 				set_config: SetConfig {
 					// Non-authority nodes don't need to accept incoming connections on this peer set:
@@ -85,30 +124,106 @@ impl PeerSet {
 					},
 				},
 			},
+			PeerSet::Approval => NonDefaultSetConfig {
+				notifications_protocol: protocol,
+				fallback_names: self.get_fallback_protocol_names(),
+				max_notification_size,
+				set_config: SetConfig {
+					// Only the validators we are connected to as reserved peers take part,
+					// everybody else is sent approval traffic on the validation peer-set.
+					in_peers: 0,
+					out_peers: 0,
+					reserved_nodes: Vec::new(),
+					non_reserved_mode: sc_network::config::NonReservedPeerMode::Deny,
+				},
+			},
 		}
 	}
 
 	/// Get the protocol name associated with each peer set as static str.
+	///
+	/// This is the name of the main version of the peer set's protocol.
 	pub const fn get_protocol_name_static(self) -> &'static str {
 		match self {
-			PeerSet::Validation => "/polkadot/validation/1",
-			PeerSet::Collation => "/polkadot/collation/1",
+			PeerSet::Validation => "/polkadot/validation/2",
+			PeerSet::Collation => "/polkadot/collation/2",
+			PeerSet::Approval => "/polkadot/approval/2",
 		}
 	}
 
+	/// Get the main, i.e. latest, protocol version of the peer set.
+	pub const fn get_main_version(self) -> ProtocolVersion {
+		match self {
+			PeerSet::Validation | PeerSet::Approval => ValidationVersion::V2 as ProtocolVersion,
+			PeerSet::Collation => CollationVersion::V2 as ProtocolVersion,
+		}
+	}
+
+	/// Get the older protocol versions of the peer set, which are still supported, together with
+	/// their protocol names.
+	pub const fn get_fallback_versions(self) -> &'static [(ProtocolVersion, &'static str)] {
+		match self {
+			PeerSet::Validation =>
+				&[(ValidationVersion::V1 as ProtocolVersion, "/polkadot/validation/1")],
+			PeerSet::Approval =>
+				&[(ValidationVersion::V1 as ProtocolVersion, "/polkadot/approval/1")],
+			PeerSet::Collation =>
+				&[(CollationVersion::V1 as ProtocolVersion, "/polkadot/collation/1")],
+		}
+	}
+
+	/// Get the protocol names of the older protocol versions of the peer set.
+	pub fn get_fallback_protocol_names(self) -> Vec<Cow<'static, str>> {
+		self.get_fallback_versions()
+			.iter()
+			.map(|(_, name)| Cow::Borrowed(*name))
+			.collect()
+	}
+
 	/// Convert a peer set into a protocol name as understood by Substrate.
 	pub fn into_protocol_name(self) -> Cow<'static, str> {
 		self.get_protocol_name_static().into()
 	}
 
 	/// Try parsing a protocol name into a peer set.
+	///
+	/// Only the main protocol names are recognized, as Substrate reports the protocols of
+	/// notification streams by the main name, no matter which version was negotiated.
 	pub fn try_from_protocol_name(name: &Cow<'static, str>) -> Option<PeerSet> {
 		match name {
 			n if n == &PeerSet::Validation.into_protocol_name() => Some(PeerSet::Validation),
 			n if n == &PeerSet::Collation.into_protocol_name() => Some(PeerSet::Collation),
+			n if n == &PeerSet::Approval.into_protocol_name() => Some(PeerSet::Approval),
 			_ => None,
 		}
 	}
+
+	/// Get the peer-sets which connect to the same peers as this one, carrying part of its
+	/// traffic.
+	pub const fn get_dedicated_peer_sets(self) -> &'static [PeerSet] {
+		match self {
+			PeerSet::Validation => &[PeerSet::Approval],
+			PeerSet::Collation | PeerSet::Approval => &[],
+		}
+	}
+
+	/// Get the protocol version negotiated on a notification stream of the peer set, given the
+	/// negotiated fallback name reported by Substrate, if any.
+	///
+	/// Returns `None` if the fallback name is unknown.
+	pub fn get_negotiated_version(
+		self,
+		negotiated_fallback: Option<&Cow<'static, str>>,
+	) -> Option<ProtocolVersion> {
+		match negotiated_fallback {
+			None => Some(self.get_main_version()),
+			Some(name) => self
+				.get_fallback_versions()
+				.iter()
+				.find(|(_, fallback)| name == fallback)
+				.map(|(version, _)| *version),
+		}
+	}
 }
 
 /// A small and nifty collection that allows to store data pertaining to each peer set.
@@ -116,6 +231,7 @@ impl PeerSet {
 pub struct PerPeerSet<T> {
 	validation: T,
 	collation: T,
+	approval: T,
 }
 
 impl<T> Index<PeerSet> for PerPeerSet<T> {
@@ -124,6 +240,7 @@ impl<T> Index<PeerSet> for PerPeerSet<T> {
 		match index {
 			PeerSet::Validation => &self.validation,
 			PeerSet::Collation => &self.collation,
+			PeerSet::Approval => &self.approval,
 		}
 	}
 }
@@ -133,6 +250,7 @@ impl<T> IndexMut<PeerSet> for PerPeerSet<T> {
 		match index {
 			PeerSet::Validation => &mut self.validation,
 			PeerSet::Collation => &mut self.collation,
+			PeerSet::Approval => &mut self.approval,
 		}
 	}
 }
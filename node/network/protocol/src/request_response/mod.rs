@@ -63,6 +63,8 @@ pub enum Protocol {
 	ChunkFetching,
 	/// Protocol for fetching collations from collators.
 	CollationFetching,
+	/// Protocol for collators pushing collations to the validators of the backing group.
+	CollationPushing,
 	/// Protocol for fetching seconded PoVs from validators of the same group.
 	PoVFetching,
 	/// Protocol for fetching available data.
@@ -111,7 +113,8 @@ pub const MAX_PARALLEL_STATEMENT_REQUESTS: u32 = 3;
 /// Response size limit for responses of POV like data.
 ///
 /// This is larger than `MAX_POV_SIZE` to account for protocol overhead and for additional data in
-/// `CollationFetching` or `AvailableDataFetching` for example. We try to err on larger limits here
+/// `CollationFetching` or `AvailableDataFetching` for example. It also limits the size of
+/// `CollationPushing` requests, which carry a whole collation. We try to err on larger limits here
 /// as a too large limit only allows an attacker to waste our bandwidth some more, a too low limit
 /// might have more severe effects.
 const POV_RESPONSE_SIZE: u64 = MAX_POV_SIZE as u64 + 10_000;
@@ -146,6 +149,15 @@ impl Protocol {
 				request_timeout: POV_REQUEST_TIMEOUT_CONNECTED,
 				inbound_queue: Some(tx),
 			},
+			Protocol::CollationPushing => RequestResponseConfig {
+				name: p_name,
+				// The request carries the whole collation.
+				max_request_size: POV_RESPONSE_SIZE,
+				// Responses are just confirmations.
+				max_response_size: 100,
+				request_timeout: POV_REQUEST_TIMEOUT_CONNECTED,
+				inbound_queue: Some(tx),
+			},
 			Protocol::PoVFetching => RequestResponseConfig {
 				name: p_name,
 				max_request_size: 1_000,
@@ -204,6 +216,9 @@ impl Protocol {
 			Protocol::ChunkFetching => 100,
 			// 10 seems reasonable, considering group sizes of max 10 validators.
 			Protocol::CollationFetching => 10,
+			// Only collators of the paras assigned to our group push collations to us, which
+			// should be in the same ballpark as collation fetches.
+			Protocol::CollationPushing => 10,
 			// 10 seems reasonable, considering group sizes of max 10 validators.
 			Protocol::PoVFetching => 10,
 			// Validators are constantly self-selecting to request available data which may lead
@@ -247,6 +262,7 @@ impl Protocol {
 		match self {
 			Protocol::ChunkFetching => "/polkadot/req_chunk/1",
 			Protocol::CollationFetching => "/polkadot/req_collation/1",
+			Protocol::CollationPushing => "/polkadot/push_collation/1",
 			Protocol::PoVFetching => "/polkadot/req_pov/1",
 			Protocol::AvailableDataFetching => "/polkadot/req_available_data/1",
 			Protocol::StatementFetching => "/polkadot/req_statement/1",
@@ -32,6 +32,8 @@ pub enum Requests {
 	ChunkFetching(OutgoingRequest<v1::ChunkFetchingRequest>),
 	/// Fetch a collation from a collator which previously announced it.
 	CollationFetching(OutgoingRequest<v1::CollationFetchingRequest>),
+	/// Push a collation to a validator of the backing group.
+	CollationPushing(OutgoingRequest<v1::CollationPushingRequest>),
 	/// Fetch a PoV from a validator which previously sent out a seconded statement.
 	PoVFetching(OutgoingRequest<v1::PoVFetchingRequest>),
 	/// Request full available data from a node.
@@ -48,6 +50,7 @@ impl Requests {
 		match self {
 			Self::ChunkFetching(_) => Protocol::ChunkFetching,
 			Self::CollationFetching(_) => Protocol::CollationFetching,
+			Self::CollationPushing(_) => Protocol::CollationPushing,
 			Self::PoVFetching(_) => Protocol::PoVFetching,
 			Self::AvailableDataFetching(_) => Protocol::AvailableDataFetching,
 			Self::StatementFetching(_) => Protocol::StatementFetching,
@@ -66,6 +69,7 @@ impl Requests {
 		match self {
 			Self::ChunkFetching(r) => r.encode_request(),
 			Self::CollationFetching(r) => r.encode_request(),
+			Self::CollationPushing(r) => r.encode_request(),
 			Self::PoVFetching(r) => r.encode_request(),
 			Self::AvailableDataFetching(r) => r.encode_request(),
 			Self::StatementFetching(r) => r.encode_request(),
@@ -154,7 +158,21 @@ where
 	) -> (Self, impl Future<Output = OutgoingResult<Req::Response>>) {
 		let (tx, rx) = oneshot::channel();
 		let r = Self { peer, payload, pending_response: tx };
-		(r, receive_response::<Req>(rx))
+		(r, receive_response::<Req>(rx, None))
+	}
+
+	/// Create a new `OutgoingRequest`, rejecting responses larger than `max_response_size`.
+	///
+	/// The limit of the protocol still applies, this only allows for a tighter one. Oversized
+	/// responses are not decoded, but result in a `RequestError::InvalidResponse`.
+	pub fn new_with_max_response_size(
+		peer: Recipient,
+		payload: Req,
+		max_response_size: usize,
+	) -> (Self, impl Future<Output = OutgoingResult<Req::Response>>) {
+		let (tx, rx) = oneshot::channel();
+		let r = Self { peer, payload, pending_response: tx };
+		(r, receive_response::<Req>(rx, Some(max_response_size)))
 	}
 
 	/// Encode a request into a `Vec<u8>`.
@@ -171,11 +189,15 @@ where
 /// Future for actually receiving a typed response for an `OutgoingRequest`.
 async fn receive_response<Req>(
 	rec: oneshot::Receiver<Result<Vec<u8>, network::RequestFailure>>,
+	max_response_size: Option<usize>,
 ) -> OutgoingResult<Req::Response>
 where
 	Req: IsRequest,
 	Req::Response: Decode,
 {
 	let raw = rec.await??;
+	if max_response_size.map_or(false, |max| raw.len() > max) {
+		return Err(DecodingError::from("Response exceeds the maximum size").into())
+	}
 	Ok(Decode::decode(&mut raw.as_ref())?)
 }
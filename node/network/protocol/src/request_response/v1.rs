@@ -110,6 +110,28 @@ impl IsRequest for CollationFetchingRequest {
 	const PROTOCOL: Protocol = Protocol::CollationFetching;
 }
 
+/// Push a collation to a validator of the backing group, without waiting for it to request it.
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct CollationPushingRequest {
+	/// The candidate receipt of the collation.
+	pub receipt: CandidateReceipt,
+	/// The PoV of the collation.
+	pub pov: PoV,
+}
+
+/// Responses to a `CollationPushingRequest`.
+#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+pub enum CollationPushingResponse {
+	/// The validator received the collation, which doesn't imply it is going to second it.
+	#[codec(index = 0)]
+	Received,
+}
+
+impl IsRequest for CollationPushingRequest {
+	type Response = CollationPushingResponse;
+	const PROTOCOL: Protocol = Protocol::CollationPushing;
+}
+
 /// Request the advertised collation at that relay-parent.
 #[derive(Debug, Clone, Encode, Decode)]
 pub struct PoVFetchingRequest {
@@ -68,19 +68,130 @@ pub(crate) fn impl_builder(info: &OverseerInfo) -> proc_macro2::TokenStream {
 	let error_ty = &info.extern_error_ty;
 
 	let support_crate = info.support_crate_name();
+	let signal = &info.extern_signal_ty;
 
-	let blocking = &info
+	// Subsystems that fail are either restarted, or conclude the overseer.
+	let spawn_subsystem = &info
 		.subsystems()
 		.iter()
-		.map(|x| {
-			if x.blocking {
+		.filter(|ssf| !ssf.wip)
+		.map(|ssf| {
+			let subsystem_name = &ssf.name;
+			let consumes = &ssf.consumes;
+			let channel_name_tx = Ident::new(&(ssf.name.to_string() + "_tx"), ssf.name.span());
+			let blocking = if ssf.blocking {
 				quote! { Blocking }
 			} else {
 				quote! { Regular }
+			};
+			let recorder = if ssf.recordable {
+				quote! {
+					recorder
+						.as_ref()
+						.filter(|recorder| recorder.records(subsystem_static_str))
+						.map(|recorder| #support_crate ::recording::SubsystemRecorder::< #consumes, #signal >::new(
+							recorder.clone(),
+							subsystem_static_str,
+						))
+				}
+			} else {
+				quote! { None }
+			};
+			if ssf.restart {
+				quote! {
+					spawn_restartable::<_,_, #blocking, _, _>(
+						&mut spawner,
+						#channel_name_tx,
+						signal_tx,
+						unbounded_meter,
+						#support_crate ::SubsystemIncoming {
+							signals: signal_rx,
+							messages: message_rx,
+							signals_received: 0,
+							pending_incoming: None,
+							recorder: #recorder,
+						},
+						channels_out.clone(),
+						to_overseer_tx.clone(),
+						#subsystem_name,
+						subsystem_static_str,
+						&mut running_subsystems,
+					)?
+				}
+			} else {
+				quote! {
+					spawn::<_,_, #blocking, _, _, _>(
+						&mut spawner,
+						#channel_name_tx,
+						signal_tx,
+						unbounded_meter,
+						#subsystem_ctx_name::< #consumes >::new(
+							signal_rx,
+							message_rx,
+							channels_out.clone(),
+							to_overseer_tx.clone(),
+							subsystem_static_str,
+							#recorder,
+						),
+						#subsystem_name,
+						subsystem_static_str,
+						&mut running_subsystems,
+					)?
+				}
+			}
+		})
+		.collect::<Vec<_>>();
+
+	// Selects the lane of each message sent to a subsystem.
+	let prioritize = &info
+		.subsystems()
+		.iter()
+		.filter(|ssf| !ssf.wip)
+		.map(|ssf| {
+			let consumes = &ssf.consumes;
+			if ssf.prioritized {
+				quote! { #support_crate ::prioritize_packet::< #consumes > }
+			} else {
+				quote! { #support_crate ::normal_priority }
 			}
 		})
 		.collect::<Vec<_>>();
 
+	// Sample the time of flight per message type on both channels of `labelled` subsystems.
+	let label_messages = &info
+		.subsystems()
+		.iter()
+		.filter(|ssf| !ssf.wip && ssf.labelled)
+		.map(|ssf| {
+			let consumes = &ssf.consumes;
+			let channel_name_rx = Ident::new(&(ssf.name.to_string() + "_rx"), ssf.name.span());
+			let channel_name_unbounded_rx =
+				Ident::new(&(ssf.name.to_string() + "_unbounded_rx"), ssf.name.span());
+			quote! {
+				let #channel_name_rx = #channel_name_rx
+					.with_message_label(#support_crate ::label_packet::< #consumes >);
+				let #channel_name_unbounded_rx = #channel_name_unbounded_rx
+					.with_message_label(#support_crate ::label_packet::< #consumes >);
+			}
+		})
+		.collect::<Vec<_>>();
+
+	// Names of the subsystems which can be selected for recording.
+	let recordable_names = &info
+		.subsystems()
+		.iter()
+		.filter(|ssf| !ssf.wip && ssf.recordable)
+		.map(|ssf| ssf.name.to_string().replace('_', "-"))
+		.collect::<Vec<_>>();
+
+	// Restarting a subsystem starts a clone of the instance passed to the builder.
+	let restart_generics = &info
+		.subsystems()
+		.iter()
+		.filter(|ssf| !ssf.wip && ssf.restart)
+		.map(|ssf| ssf.generic.clone())
+		.collect::<Vec<_>>();
+
 	// Helpers to use within quote! macros
 	let spawner_where_clause: syn::TypeParam = parse_quote! {
 			S: #support_crate ::SpawnNamed + Send
@@ -442,6 +553,9 @@ pub(crate) fn impl_builder(info: &OverseerInfo) -> proc_macro2::TokenStream {
 			#(
 				#subsystem_generics : Subsystem<#subsystem_ctx_name< #consumes >, #error_ty>,
 			)*
+			#(
+				#restart_generics : Clone + Send + 'static,
+			)*
 		{
 			/// Complete the construction and create the overseer type.
 			pub fn build(self)
@@ -454,6 +568,40 @@ pub(crate) fn impl_builder(info: &OverseerInfo) -> proc_macro2::TokenStream {
 			pub fn build_with_connector(self, connector: #connector)
 				-> ::std::result::Result<(#overseer_name<S, #( #baggage_generic_ty, )*>, #handle), #error_ty>
 			{
+				self.build_with_connector_and_recorder(connector, None)
+			}
+
+			/// Complete the construction and create the overseer type, recording the
+			/// subsystems selected by `recorder`.
+			pub fn build_with_recorder(self, recorder: #support_crate ::recording::Recorder)
+				-> ::std::result::Result<(#overseer_name<S, #( #baggage_generic_ty, )*>, #handle), #error_ty>
+			{
+				let connector = #connector ::default();
+				self.build_with_connector_and_recorder(connector, Some(recorder))
+			}
+
+			/// Complete the construction and create the overseer type based on an existing `connector`,
+			/// recording the subsystems selected by `recorder`, if any.
+			///
+			/// Only subsystems annotated with `recordable` can be recorded.
+			pub fn build_with_connector_and_recorder(
+				self,
+				connector: #connector,
+				recorder: Option<#support_crate ::recording::Recorder>,
+			)
+				-> ::std::result::Result<(#overseer_name<S, #( #baggage_generic_ty, )*>, #handle), #error_ty>
+			{
+				if let Some(ref recorder) = recorder {
+					const RECORDABLE: &[&str] = &[ #( #recordable_names, )* ];
+					for subsystem in recorder.subsystems().filter(|s| !RECORDABLE.contains(s)) {
+						#support_crate ::gum::warn!(
+							target: LOG_TARGET,
+							%subsystem,
+							"Subsystem can not be recorded, it is unknown or not `recordable`",
+						);
+					}
+				}
+
 				let #connector {
 					handle: events_tx,
 					consumer: events_rx,
@@ -468,9 +616,9 @@ pub(crate) fn impl_builder(info: &OverseerInfo) -> proc_macro2::TokenStream {
 				#(
 					let (#channel_name_tx, #channel_name_rx)
 					=
-						#support_crate ::metered::channel::<
+						#support_crate ::metered::channel_with_priority::<
 							MessagePacket< #consumes >
-						>(CHANNEL_CAPACITY);
+						>(CHANNEL_CAPACITY, #prioritize);
 				)*
 
 				#(
@@ -480,6 +628,10 @@ pub(crate) fn impl_builder(info: &OverseerInfo) -> proc_macro2::TokenStream {
 						>();
 				)*
 
+				#(
+					#label_messages
+				)*
+
 				let channels_out =
 					ChannelsOut {
 						#(
@@ -517,25 +669,7 @@ pub(crate) fn impl_builder(info: &OverseerInfo) -> proc_macro2::TokenStream {
 					// Convert owned `snake case` string to a `kebab case` static str.
 					let subsystem_static_str = Box::leak(subsystem_string.replace("_", "-").into_boxed_str());
 
-					let ctx = #subsystem_ctx_name::< #consumes >::new(
-						signal_rx,
-						message_rx,
-						channels_out.clone(),
-						to_overseer_tx.clone(),
-						subsystem_static_str
-					);
-
-					let #subsystem_name: OverseenSubsystem< #consumes > =
-						spawn::<_,_, #blocking, _, _, _>(
-							&mut spawner,
-							#channel_name_tx,
-							signal_tx,
-							unbounded_meter,
-							ctx,
-							#subsystem_name,
-							subsystem_static_str,
-							&mut running_subsystems,
-						)?;
+					let #subsystem_name: OverseenSubsystem< #consumes > = #spawn_subsystem;
 				)*
 
 				use #support_crate ::StreamExt;
@@ -571,6 +705,7 @@ pub(crate) fn impl_builder(info: &OverseerInfo) -> proc_macro2::TokenStream {
 }
 
 pub(crate) fn impl_task_kind(info: &OverseerInfo) -> proc_macro2::TokenStream {
+	let subsystem_ctx_name = format_ident!("{}SubsystemContext", info.overseer_name);
 	let signal = &info.extern_signal_ty;
 	let error_ty = &info.extern_error_ty;
 	let support_crate = info.support_crate_name();
@@ -601,7 +736,7 @@ pub(crate) fn impl_task_kind(info: &OverseerInfo) -> proc_macro2::TokenStream {
 		/// Spawn task of kind `self` using spawner `S`.
 		pub fn spawn<S, M, TK, Ctx, E, SubSys>(
 			spawner: &mut S,
-			message_tx: #support_crate ::metered::MeteredSender<MessagePacket<M>>,
+			message_tx: #support_crate ::metered::MeteredPrioritySender<MessagePacket<M>>,
 			signal_tx: #support_crate ::metered::MeteredSender< #signal >,
 			// meter for the unbounded channel
 			unbounded_meter: #support_crate ::metered::Meter,
@@ -643,8 +778,10 @@ pub(crate) fn impl_task_kind(info: &OverseerInfo) -> proc_macro2::TokenStream {
 			let instance = Some(SubsystemInstance {
 				meters: #support_crate ::SubsystemMeters {
 					unbounded: unbounded_meter,
-					bounded: message_tx.meter().clone(),
+					bounded: message_tx.meter(#support_crate ::metered::Priority::Normal).clone(),
+					bounded_high: message_tx.meter(#support_crate ::metered::Priority::High).clone(),
 					signals: signal_tx.meter().clone(),
+					restarts: #support_crate ::Restarts::default(),
 				},
 				tx_signal: signal_tx,
 				tx_bounded: message_tx,
@@ -656,6 +793,111 @@ pub(crate) fn impl_task_kind(info: &OverseerInfo) -> proc_macro2::TokenStream {
 				instance,
 			})
 		}
+
+		/// Spawn a subsystem of kind `TK` using spawner `S`, which is restarted with an
+		/// increasing delay whenever it fails, i.e. exits with an error or panics.
+		///
+		/// Exiting without an error is how subsystems conclude and ends the subsystem for good.
+		pub fn spawn_restartable<S, M, TK, E, SubSys>(
+			spawner: &mut S,
+			message_tx: #support_crate ::metered::MeteredPrioritySender<MessagePacket<M>>,
+			signal_tx: #support_crate ::metered::MeteredSender< #signal >,
+			// meter for the unbounded channel
+			unbounded_meter: #support_crate ::metered::Meter,
+			incoming: #support_crate ::SubsystemIncoming<M, #signal>,
+			channels_out: ChannelsOut,
+			to_overseer: #support_crate ::metered::UnboundedMeteredSender<#support_crate ::ToOverseer>,
+			s: SubSys,
+			subsystem_name: &'static str,
+			futures: &mut #support_crate ::FuturesUnordered<BoxFuture<'static, ::std::result::Result<(), #error_ty> >>,
+		) -> ::std::result::Result<OverseenSubsystem<M>, #error_ty >
+		where
+			S: #support_crate ::SpawnNamed,
+			M: std::fmt::Debug + Send + 'static,
+			TK: TaskKind,
+			E: std::error::Error + Send + Sync + 'static + From<#support_crate ::OverseerError>,
+			SubSys: #support_crate ::Subsystem<#subsystem_ctx_name<M>, E> + Clone + Send + 'static,
+			#subsystem_ctx_name<M>: #support_crate ::SubsystemContext<Message=M>,
+		{
+			let restarts = #support_crate ::Restarts::default();
+
+			let (tx, rx) = #support_crate ::oneshot::channel();
+
+			let restart_count = restarts.clone();
+			let fut = Box::pin(async move {
+				let mut incoming = incoming;
+				let mut backoff = #support_crate ::RestartBackoff::default();
+				loop {
+					let (ctx, recycled) = #subsystem_ctx_name::<M>::resume(
+						incoming,
+						channels_out.clone(),
+						to_overseer.clone(),
+						subsystem_name,
+					);
+					let #support_crate ::SpawnedSubsystem::<E> { future, name } = s.clone().start(ctx);
+					let started = ::std::time::Instant::now();
+
+					match #support_crate ::FutureExt::catch_unwind(::std::panic::AssertUnwindSafe(future)).await {
+						Ok(Ok(())) => {
+							#support_crate ::gum::debug!(subsystem=name, "subsystem exited without an error");
+							break
+						},
+						Ok(Err(e)) => {
+							#support_crate ::gum::error!(subsystem=name, err = ?e, "subsystem exited with error");
+						},
+						Err(_) => {
+							#support_crate ::gum::error!(subsystem=name, "subsystem panicked");
+						},
+					}
+
+					// The overseer is gone, there is nobody to talk to anymore.
+					if to_overseer.is_closed() {
+						break
+					}
+
+					incoming = match recycled.await {
+						Ok(incoming) => incoming,
+						Err(_) => {
+							#support_crate ::gum::error!(subsystem=name, "subsystem channels are lost, not restarting");
+							break
+						},
+					};
+
+					let delay = backoff.next_delay(started.elapsed());
+					#support_crate ::gum::warn!(subsystem=name, ?delay, "restarting subsystem");
+					#support_crate ::Delay::new(delay).await;
+					restart_count.inc();
+				}
+				let _ = tx.send(());
+			});
+
+			<TK as TaskKind>::launch_task(spawner, subsystem_name, subsystem_name, fut);
+
+			futures.push(Box::pin(
+				rx.map(|e| {
+					gum::warn!(err = ?e, "dropping error");
+					Ok(())
+				})
+			));
+
+			let instance = Some(SubsystemInstance {
+				meters: #support_crate ::SubsystemMeters {
+					unbounded: unbounded_meter,
+					bounded: message_tx.meter(#support_crate ::metered::Priority::Normal).clone(),
+					bounded_high: message_tx.meter(#support_crate ::metered::Priority::High).clone(),
+					signals: signal_tx.meter().clone(),
+					restarts,
+				},
+				tx_signal: signal_tx,
+				tx_bounded: message_tx,
+				signals_received: 0,
+				name: subsystem_name,
+			});
+
+			Ok(OverseenSubsystem {
+				instance,
+			})
+		}
 	};
 
 	ts
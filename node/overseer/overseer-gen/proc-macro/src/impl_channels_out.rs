@@ -42,7 +42,7 @@ pub(crate) fn impl_channels_out_struct(info: &OverseerInfo) -> Result<proc_macro
 			#(
 				/// Bounded channel sender, connected to a subsystem.
 				pub #channel_name:
-					#support_crate ::metered::MeteredSender<
+					#support_crate ::metered::MeteredPrioritySender<
 						MessagePacket< #consumes >
 					>,
 			)*
@@ -112,7 +112,13 @@ pub(crate) fn impl_misc(info: &OverseerInfo) -> proc_macro2::TokenStream {
 				>,
 			signals_received: SignalsReceived,
 			pending_incoming: Option<(usize, M)>,
-			name: &'static str
+			name: &'static str,
+			/// Hands back the incoming channels once dropped, if the subsystem is restartable.
+			recycle: Option<#support_crate ::oneshot::Sender<
+				#support_crate ::SubsystemIncoming<M, #signal>
+				>>,
+			/// Records all received signals and messages, if the subsystem is recorded.
+			recorder: Option<#support_crate ::recording::SubsystemRecorder<M, #signal>>,
 		}
 
 		impl<M> #subsystem_ctx_name<M> {
@@ -122,7 +128,8 @@ pub(crate) fn impl_misc(info: &OverseerInfo) -> proc_macro2::TokenStream {
 				messages: SubsystemIncomingMessages<M>,
 				to_subsystems: ChannelsOut,
 				to_overseer: #support_crate ::metered::UnboundedMeteredSender<#support_crate:: ToOverseer>,
-				name: &'static str
+				name: &'static str,
+				recorder: Option<#support_crate ::recording::SubsystemRecorder<M, #signal>>,
 			) -> Self {
 				let signals_received = SignalsReceived::default();
 				#subsystem_ctx_name {
@@ -135,35 +142,55 @@ pub(crate) fn impl_misc(info: &OverseerInfo) -> proc_macro2::TokenStream {
 					to_overseer,
 					signals_received,
 					pending_incoming: None,
-					name
+					name,
+					recycle: None,
+					recorder,
 				}
 			}
 
-			fn name(&self) -> &'static str {
-				self.name
+			/// Create a context continuing with the incoming channels of a previous instance
+			/// of a restartable subsystem.
+			///
+			/// The incoming channels are handed back by the returned receiver once the
+			/// context is dropped.
+			fn resume(
+				incoming: #support_crate ::SubsystemIncoming<M, #signal>,
+				to_subsystems: ChannelsOut,
+				to_overseer: #support_crate ::metered::UnboundedMeteredSender<#support_crate:: ToOverseer>,
+				name: &'static str
+			) -> (Self, #support_crate ::oneshot::Receiver<#support_crate ::SubsystemIncoming<M, #signal>>) {
+				let #support_crate ::SubsystemIncoming {
+					signals,
+					messages,
+					signals_received,
+					pending_incoming,
+					recorder,
+				} = incoming;
+				let signals_received = SignalsReceived::new(signals_received);
+				let (recycle_tx, recycle_rx) = #support_crate ::oneshot::channel();
+				let ctx = #subsystem_ctx_name {
+					signals,
+					messages,
+					to_subsystems: #subsystem_sender_name {
+						channels: to_subsystems,
+						signals_received: signals_received.clone(),
+					},
+					to_overseer,
+					signals_received,
+					pending_incoming,
+					name,
+					recycle: Some(recycle_tx),
+					recorder,
+				};
+				(ctx, recycle_rx)
 			}
-		}
 
-		#[#support_crate ::async_trait]
-		impl<M: std::fmt::Debug + Send + 'static> #support_crate ::SubsystemContext for #subsystem_ctx_name<M>
-		where
-			#subsystem_sender_name: #support_crate ::SubsystemSender< #wrapper_message >,
-			#wrapper_message: From<M>,
-		{
-			type Message = M;
-			type Signal = #signal;
-			type Sender = #subsystem_sender_name;
-			type AllMessages = #wrapper_message;
-			type Error = #error_ty;
-
-			async fn try_recv(&mut self) -> ::std::result::Result<Option<FromOverseer<M, #signal>>, ()> {
-				match #support_crate ::poll!(self.recv()) {
-					#support_crate ::Poll::Ready(msg) => Ok(Some(msg.map_err(|_| ())?)),
-					#support_crate ::Poll::Pending => Ok(None),
-				}
+			fn name(&self) -> &'static str {
+				self.name
 			}
 
-			async fn recv(&mut self) -> ::std::result::Result<FromOverseer<M, #signal>, #error_ty> {
+			/// Receive the next signal or message, without recording it.
+			async fn recv_unrecorded(&mut self) -> ::std::result::Result<FromOverseer<M, #signal>, #error_ty> {
 				loop {
 					// If we have a message pending an overseer signal, we only poll for signals
 					// in the meantime.
@@ -226,6 +253,57 @@ pub(crate) fn impl_misc(info: &OverseerInfo) -> proc_macro2::TokenStream {
 					return Ok(from_overseer);
 				}
 			}
+		}
+
+		impl<M> Drop for #subsystem_ctx_name<M> {
+			fn drop(&mut self) {
+				if let Some(recycle) = self.recycle.take() {
+					let (_, signals) = #support_crate ::metered::channel(1);
+					let (_, bounded) = #support_crate ::metered::channel_with_priority(
+						1,
+						#support_crate ::normal_priority,
+					);
+					let (_, unbounded) = #support_crate ::metered::unbounded();
+					let _ = recycle.send(#support_crate ::SubsystemIncoming {
+						signals: ::std::mem::replace(&mut self.signals, signals),
+						messages: ::std::mem::replace(
+							&mut self.messages,
+							#support_crate ::select(bounded, unbounded),
+						),
+						signals_received: self.signals_received.load(),
+						pending_incoming: self.pending_incoming.take(),
+						recorder: self.recorder.take(),
+					});
+				}
+			}
+		}
+
+		#[#support_crate ::async_trait]
+		impl<M: std::fmt::Debug + Send + 'static> #support_crate ::SubsystemContext for #subsystem_ctx_name<M>
+		where
+			#subsystem_sender_name: #support_crate ::SubsystemSender< #wrapper_message >,
+			#wrapper_message: From<M>,
+		{
+			type Message = M;
+			type Signal = #signal;
+			type Sender = #subsystem_sender_name;
+			type AllMessages = #wrapper_message;
+			type Error = #error_ty;
+
+			async fn try_recv(&mut self) -> ::std::result::Result<Option<FromOverseer<M, #signal>>, ()> {
+				match #support_crate ::poll!(self.recv()) {
+					#support_crate ::Poll::Ready(msg) => Ok(Some(msg.map_err(|_| ())?)),
+					#support_crate ::Poll::Pending => Ok(None),
+				}
+			}
+
+			async fn recv(&mut self) -> ::std::result::Result<FromOverseer<M, #signal>, #error_ty> {
+				let from_overseer = self.recv_unrecorded().await?;
+				if let Some(ref recorder) = self.recorder {
+					recorder.record(&from_overseer, self.signals_received.load());
+				}
+				Ok(from_overseer)
+			}
 
 			fn sender(&mut self) -> &mut Self::Sender {
 				&mut self.to_subsystems
@@ -30,6 +30,11 @@ mod kw {
 	syn::custom_keyword!(wip);
 	syn::custom_keyword!(no_dispatch);
 	syn::custom_keyword!(blocking);
+	syn::custom_keyword!(restart);
+	syn::custom_keyword!(fatal);
+	syn::custom_keyword!(recordable);
+	syn::custom_keyword!(prioritized);
+	syn::custom_keyword!(labelled);
 }
 
 #[derive(Clone, Debug)]
@@ -43,6 +48,16 @@ enum SubSysAttrItem {
 	/// External messages should not be - after being converted -
 	/// be dispatched to the annotated subsystem.
 	NoDispatch(kw::no_dispatch),
+	/// The subsystem is restarted with a backoff if it fails.
+	Restart(kw::restart),
+	/// A failure of the subsystem shuts down the overseer, the default.
+	Fatal(kw::fatal),
+	/// Signals and messages received by the subsystem can be recorded.
+	Recordable(kw::recordable),
+	/// Messages to the subsystem are received by priority.
+	Prioritized(kw::prioritized),
+	/// The time of flight of messages to the subsystem is sampled per message type.
+	Labelled(kw::labelled),
 }
 
 impl Parse for SubSysAttrItem {
@@ -54,6 +69,16 @@ impl Parse for SubSysAttrItem {
 			Self::Blocking(input.parse::<kw::blocking>()?)
 		} else if lookahead.peek(kw::no_dispatch) {
 			Self::NoDispatch(input.parse::<kw::no_dispatch>()?)
+		} else if lookahead.peek(kw::restart) {
+			Self::Restart(input.parse::<kw::restart>()?)
+		} else if lookahead.peek(kw::fatal) {
+			Self::Fatal(input.parse::<kw::fatal>()?)
+		} else if lookahead.peek(kw::recordable) {
+			Self::Recordable(input.parse::<kw::recordable>()?)
+		} else if lookahead.peek(kw::prioritized) {
+			Self::Prioritized(input.parse::<kw::prioritized>()?)
+		} else if lookahead.peek(kw::labelled) {
+			Self::Labelled(input.parse::<kw::labelled>()?)
 		} else {
 			return Err(lookahead.error())
 		})
@@ -72,6 +97,21 @@ impl ToTokens for SubSysAttrItem {
 			Self::NoDispatch(no_dispatch) => {
 				quote! { #no_dispatch }
 			},
+			Self::Restart(restart) => {
+				quote! { #restart }
+			},
+			Self::Fatal(fatal) => {
+				quote! { #fatal }
+			},
+			Self::Recordable(recordable) => {
+				quote! { #recordable }
+			},
+			Self::Prioritized(prioritized) => {
+				quote! { #prioritized }
+			},
+			Self::Labelled(labelled) => {
+				quote! { #labelled }
+			},
 		};
 		tokens.extend(ts.into_iter());
 	}
@@ -99,6 +139,18 @@ pub(crate) struct SubSysField {
 	/// Avoids dispatching `Wrapper` type messages, but generates the variants.
 	/// Does not require the subsystem to be instantiated with the builder pattern.
 	pub(crate) wip: bool,
+	/// If the subsystem is restarted with a backoff once it fails, instead of
+	/// shutting down the overseer.
+	pub(crate) restart: bool,
+	/// If the signals and messages received by the subsystem can be recorded,
+	/// which requires both to implement `Recordable`.
+	pub(crate) recordable: bool,
+	/// If messages to the subsystem are sent on the lane given by their `Prioritized`
+	/// implementation, rather than all on the normal lane.
+	pub(crate) prioritized: bool,
+	/// If the time of flight of messages to the subsystem is sampled per message type,
+	/// which requires the message to implement `MessageLabel`.
+	pub(crate) labelled: bool,
 }
 
 fn try_type_to_path(ty: Type, span: Span) -> Result<Path> {
@@ -135,6 +187,14 @@ pub(crate) struct SubSystemTags {
 	/// and also not include the subsystem in the list of subsystems.
 	pub(crate) wip: bool,
 	pub(crate) blocking: bool,
+	/// The subsystem is restarted once it fails, rather than being fatal to the overseer.
+	pub(crate) restart: bool,
+	/// The received signals and messages can be recorded.
+	pub(crate) recordable: bool,
+	/// The consumed messages are received by priority.
+	pub(crate) prioritized: bool,
+	/// The time of flight of the consumed messages is sampled per message type.
+	pub(crate) labelled: bool,
 	pub(crate) consumes: Path,
 }
 
@@ -175,8 +235,30 @@ impl Parse for SubSystemTags {
 		let no_dispatch = extract_variant!(unique, NoDispatch; default = false);
 		let blocking = extract_variant!(unique, Blocking; default = false);
 		let wip = extract_variant!(unique, Wip; default = false);
+		let restart = extract_variant!(unique, Restart; default = false);
+		let fatal = extract_variant!(unique, Fatal; default = false);
+		let recordable = extract_variant!(unique, Recordable; default = false);
+		let prioritized = extract_variant!(unique, Prioritized; default = false);
+		let labelled = extract_variant!(unique, Labelled; default = false);
+
+		if restart && fatal {
+			return Err(Error::new(
+				consumes.span(),
+				"A subsystem can not be annotated with both `restart` and `fatal`.",
+			))
+		}
 
-		Ok(Self { attrs, no_dispatch, blocking, consumes, wip })
+		Ok(Self {
+			attrs,
+			no_dispatch,
+			blocking,
+			restart,
+			recordable,
+			prioritized,
+			labelled,
+			consumes,
+			wip,
+		})
 	}
 }
 
@@ -389,6 +471,10 @@ impl OverseerGuts {
 					no_dispatch: variant.no_dispatch,
 					wip: variant.wip,
 					blocking: variant.blocking,
+					restart: variant.restart,
+					recordable: variant.recordable,
+					prioritized: variant.prioritized,
+					labelled: variant.labelled,
 				});
 			} else {
 				let field_ty = try_type_to_path(ty, ident.span())?;
@@ -44,6 +44,9 @@ fn print() {
 			#[subsystem(Rope)]
 			mc: MountainCave,
 
+			#[subsystem(restart, Thread)]
+			spd: Spider,
+
 			metrics: Metrics,
 		}
 	};
@@ -86,6 +89,86 @@ fn struct_parse_basic() {
 	let _ = dbg!(item);
 }
 
+#[test]
+fn struct_parse_restart() {
+	let item: OverseerGuts = parse_quote! {
+		pub struct Ooooh {
+			#[subsystem(restart, Foo)]
+			sub0: FooSubsystem,
+
+			#[subsystem(fatal, blocking, Bar)]
+			yyy: BaersBuyBilliardBalls,
+
+			#[subsystem(Rope)]
+			mc: MountainCave,
+		}
+	};
+	let restart = item.subsystems.iter().map(|ssf| ssf.restart).collect::<Vec<_>>();
+	assert_eq!(restart, vec![true, false, false]);
+}
+
+#[test]
+fn struct_parse_recordable() {
+	let item: OverseerGuts = parse_quote! {
+		pub struct Ooooh {
+			#[subsystem(recordable, Foo)]
+			sub0: FooSubsystem,
+
+			#[subsystem(restart, recordable, Bar)]
+			yyy: BaersBuyBilliardBalls,
+
+			#[subsystem(Rope)]
+			mc: MountainCave,
+		}
+	};
+	let recordable = item.subsystems.iter().map(|ssf| ssf.recordable).collect::<Vec<_>>();
+	assert_eq!(recordable, vec![true, true, false]);
+}
+
+#[test]
+fn struct_parse_prioritized() {
+	let item: OverseerGuts = parse_quote! {
+		pub struct Ooooh {
+			#[subsystem(prioritized, Foo)]
+			sub0: FooSubsystem,
+
+			#[subsystem(blocking, Bar)]
+			yyy: BaersBuyBilliardBalls,
+		}
+	};
+	let prioritized = item.subsystems.iter().map(|ssf| ssf.prioritized).collect::<Vec<_>>();
+	assert_eq!(prioritized, vec![true, false]);
+}
+
+#[test]
+fn struct_parse_labelled() {
+	let item: OverseerGuts = parse_quote! {
+		pub struct Ooooh {
+			#[subsystem(labelled, Foo)]
+			sub0: FooSubsystem,
+
+			#[subsystem(prioritized, labelled, Bar)]
+			yyy: BaersBuyBilliardBalls,
+
+			#[subsystem(Rope)]
+			mc: MountainCave,
+		}
+	};
+	let labelled = item.subsystems.iter().map(|ssf| ssf.labelled).collect::<Vec<_>>();
+	assert_eq!(labelled, vec![true, true, false]);
+}
+
+#[test]
+fn struct_parse_restart_and_fatal_conflict() {
+	let item = quote! {
+		pub struct Ooooh {
+			#[subsystem(restart, fatal, Foo)]
+			sub0: FooSubsystem,
+		}
+	};
+	assert!(syn::parse2::<OverseerGuts>(item).is_err());
+}
+
 #[test]
 fn attr_full() {
 	let attr: AttrArgs = parse_quote! {
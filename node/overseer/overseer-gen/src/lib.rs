@@ -98,6 +98,8 @@ pub use polkadot_node_network_protocol::WrongVariant;
 
 use std::fmt;
 
+pub mod recording;
+
 #[cfg(test)]
 mod tests;
 
@@ -183,9 +185,40 @@ pub fn make_packet<T>(signals_received: usize, message: T) -> MessagePacket<T> {
 	MessagePacket { signals_received, message }
 }
 
+/// A message type delivered by priority, to a subsystem annotated with `prioritized`.
+///
+/// Messages on the high priority lane are received before any others, so urgent messages
+/// are not delayed by a burst of bulk messages.
+pub trait Prioritized {
+	/// The lane the message is sent on.
+	fn priority(&self) -> metered::Priority;
+}
+
+/// Select the lane of a packet to a subsystem annotated with `prioritized`.
+pub fn prioritize_packet<M: Prioritized>(packet: &MessagePacket<M>) -> metered::Priority {
+	packet.message.priority()
+}
+
+/// Select the normal lane for every packet.
+pub fn normal_priority<T>(_: &T) -> metered::Priority {
+	metered::Priority::Normal
+}
+
+/// A message type whose time of flight is sampled per message type, for subsystems
+/// annotated with `labelled`.
+pub trait MessageLabel {
+	/// The label of the message in the time of flight samples, usually the variant name.
+	fn message_label(&self) -> &'static str;
+}
+
+/// Label a packet to a subsystem annotated with `labelled`.
+pub fn label_packet<M: MessageLabel>(packet: &MessagePacket<M>) -> &'static str {
+	packet.message.message_label()
+}
+
 /// Incoming messages from both the bounded and unbounded channel.
 pub type SubsystemIncomingMessages<M> = self::stream::Select<
-	self::metered::MeteredReceiver<MessagePacket<M>>,
+	self::metered::MeteredPriorityReceiver<MessagePacket<M>>,
 	self::metered::UnboundedMeteredReceiver<MessagePacket<M>>,
 >;
 
@@ -194,6 +227,11 @@ pub type SubsystemIncomingMessages<M> = self::stream::Select<
 pub struct SignalsReceived(Arc<AtomicUsize>);
 
 impl SignalsReceived {
+	/// Create a watermark starting at the given number of received signals.
+	pub fn new(signals_received: usize) -> Self {
+		Self(Arc::new(AtomicUsize::new(signals_received)))
+	}
+
 	/// Load the current value of received signals.
 	pub fn load(&self) -> usize {
 		// It's imperative that we prevent reading a stale value from memory because of reordering.
@@ -208,6 +246,74 @@ impl SignalsReceived {
 	}
 }
 
+/// The receiving ends of the channels towards a subsystem, together with the
+/// progress of the subsystem instance that owned them.
+///
+/// Handed back by the context of a subsystem annotated with `restart` once it is
+/// dropped, such that the restarted instance continues where the failed one stopped.
+pub struct SubsystemIncoming<M, Signal> {
+	/// Incoming signals.
+	pub signals: metered::MeteredReceiver<Signal>,
+	/// Incoming messages.
+	pub messages: SubsystemIncomingMessages<M>,
+	/// The number of signals already received.
+	pub signals_received: usize,
+	/// A message that was received but awaits more signals to be received first.
+	pub pending_incoming: Option<(usize, M)>,
+	/// Records what the subsystem receives, if selected for recording.
+	pub recorder: Option<recording::SubsystemRecorder<M, Signal>>,
+}
+
+/// Number of times a subsystem was restarted after failing.
+#[derive(Debug, Default, Clone)]
+pub struct Restarts(Arc<AtomicUsize>);
+
+impl Restarts {
+	/// Load the current number of restarts.
+	pub fn load(&self) -> usize {
+		self.0.load(atomic::Ordering::Relaxed)
+	}
+
+	/// Increase the number of restarts by one.
+	pub fn inc(&self) {
+		self.0.fetch_add(1, atomic::Ordering::Relaxed);
+	}
+}
+
+/// Delays between restarts of a failing subsystem.
+///
+/// The delay doubles with every restart up to [`RestartBackoff::MAX_DELAY`], and starts
+/// over once an instance of the subsystem kept running for [`RestartBackoff::RESET_AFTER`].
+#[derive(Debug)]
+pub struct RestartBackoff {
+	next: Duration,
+}
+
+impl RestartBackoff {
+	/// Delay before the first restart.
+	pub const INITIAL_DELAY: Duration = Duration::from_secs(1);
+	/// Upper bound of the delay between restarts.
+	pub const MAX_DELAY: Duration = Duration::from_secs(60);
+	/// Running for this long resets the delay to [`RestartBackoff::INITIAL_DELAY`].
+	pub const RESET_AFTER: Duration = Duration::from_secs(5 * 60);
+
+	/// The delay before restarting a subsystem, which failed after running for `ran_for`.
+	pub fn next_delay(&mut self, ran_for: Duration) -> Duration {
+		if ran_for >= Self::RESET_AFTER {
+			self.next = Self::INITIAL_DELAY;
+		}
+		let delay = self.next;
+		self.next = std::cmp::min(self.next * 2, Self::MAX_DELAY);
+		delay
+	}
+}
+
+impl Default for RestartBackoff {
+	fn default() -> Self {
+		Self { next: Self::INITIAL_DELAY }
+	}
+}
+
 /// A trait to support the origin annotation
 /// such that errors across subsystems can be easier tracked.
 pub trait AnnotateErrorOrigin: 'static + Send + Sync + std::error::Error {
@@ -279,12 +385,16 @@ pub type OverseerResult<T> = std::result::Result<T, self::OverseerError>;
 /// Collection of meters related to a subsystem.
 #[derive(Clone)]
 pub struct SubsystemMeters {
-	#[allow(missing_docs)]
+	/// The normal lane of the bounded channel.
 	pub bounded: metered::Meter,
+	/// The high priority lane of the bounded channel.
+	pub bounded_high: metered::Meter,
 	#[allow(missing_docs)]
 	pub unbounded: metered::Meter,
 	#[allow(missing_docs)]
 	pub signals: metered::Meter,
+	/// Restarts of a subsystem annotated with `restart`.
+	pub restarts: Restarts,
 }
 
 impl SubsystemMeters {
@@ -292,20 +402,26 @@ impl SubsystemMeters {
 	pub fn read(&self) -> SubsystemMeterReadouts {
 		SubsystemMeterReadouts {
 			bounded: self.bounded.read(),
+			bounded_high: self.bounded_high.read(),
 			unbounded: self.unbounded.read(),
 			signals: self.signals.read(),
+			restarts: self.restarts.load(),
 		}
 	}
 }
 
 /// Set of readouts of the `Meter`s of a subsystem.
 pub struct SubsystemMeterReadouts {
-	#[allow(missing_docs)]
+	/// The normal lane of the bounded channel.
 	pub bounded: metered::Readout,
+	/// The high priority lane of the bounded channel.
+	pub bounded_high: metered::Readout,
 	#[allow(missing_docs)]
 	pub unbounded: metered::Readout,
 	#[allow(missing_docs)]
 	pub signals: metered::Readout,
+	/// Number of times the subsystem was restarted.
+	pub restarts: usize,
 }
 
 /// A running instance of some [`Subsystem`].
@@ -317,7 +433,7 @@ pub struct SubsystemInstance<Message, Signal> {
 	/// Send sink for `Signal`s to be sent to a subsystem.
 	pub tx_signal: crate::metered::MeteredSender<Signal>,
 	/// Send sink for `Message`s to be sent to a subsystem.
-	pub tx_bounded: crate::metered::MeteredSender<MessagePacket<Message>>,
+	pub tx_bounded: crate::metered::MeteredPrioritySender<MessagePacket<Message>>,
 	/// All meters of the particular subsystem instance.
 	pub meters: SubsystemMeters,
 	/// The number of signals already received.
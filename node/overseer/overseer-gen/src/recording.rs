@@ -0,0 +1,303 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Recording of the messages and signals received by subsystems.
+//!
+//! Subsystems annotated with `recordable` can have everything they receive written to a binary
+//! log, if selected when building the overseer with a [`Recorder`]. Entries are written in the
+//! order the subsystem received them, such that the log can be replayed into the subsystem
+//! offline.
+//!
+//! The log starts with [`MAGIC`], followed by SCALE encoded [`RecordedEntry`]s.
+//!
+//! Subsystems only queue their entries, the log is written by a dedicated thread.
+
+use futures::{
+	channel::{mpsc, oneshot},
+	executor, StreamExt,
+};
+use parity_scale_codec::{Decode, Encode};
+use std::{
+	collections::HashSet,
+	fmt,
+	fs::File,
+	io::{self, BufReader, BufWriter, Read, Write},
+	path::Path,
+	sync::Arc,
+	thread,
+	time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::FromOverseer;
+
+/// The first bytes of every recording.
+pub const MAGIC: &[u8; 8] = b"OVRREC01";
+
+const LOG_TARGET: &str = "overseer::recording";
+
+/// A message or signal that can be written to a recording and read back from it.
+pub trait Recordable: Sized {
+	/// Encode the value for a recording.
+	///
+	/// Returns `None` if the value can not be recorded, e.g. because it carries a channel
+	/// for a response.
+	fn encode_recorded(&self) -> Option<Vec<u8>>;
+
+	/// Decode a value encoded by [`Recordable::encode_recorded`].
+	fn decode_recorded(encoded: &[u8]) -> Option<Self>;
+}
+
+/// What an entry of a recording holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+pub enum RecordedKind {
+	/// A signal, encoded with [`Recordable::encode_recorded`].
+	Signal,
+	/// A message, encoded with [`Recordable::encode_recorded`].
+	Message,
+	/// A signal that can not be recorded, the payload is its `Debug` representation.
+	OpaqueSignal,
+	/// A message that can not be recorded, the payload is its `Debug` representation.
+	OpaqueMessage,
+}
+
+/// A single signal or message received by a subsystem.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct RecordedEntry {
+	/// Microseconds since the unix epoch at which the subsystem received the entry.
+	pub timestamp_micros: u64,
+	/// The subsystem which received the entry.
+	pub subsystem: String,
+	/// The number of signals the subsystem received, including this entry.
+	pub signals_received: u64,
+	/// What the payload holds.
+	pub kind: RecordedKind,
+	/// The encoded signal or message.
+	pub payload: Vec<u8>,
+}
+
+enum WriterCommand {
+	/// Append an encoded entry.
+	Entry(Vec<u8>),
+	/// Flush everything queued before and notify the sender.
+	Flush(oneshot::Sender<()>),
+}
+
+/// Write the commands queued by a [`Recorder`] until all its clones are dropped.
+///
+/// The writer is flushed whenever the queue runs empty.
+fn run_writer(mut writer: impl Write, mut commands: mpsc::UnboundedReceiver<WriterCommand>) {
+	executor::block_on(async move {
+		while let Some(command) = commands.next().await {
+			let mut res = Ok(());
+			let mut flushed = Vec::new();
+			let mut next = Some(command);
+			while let Some(command) = next {
+				match command {
+					WriterCommand::Entry(entry) => res = res.and_then(|_| writer.write_all(&entry)),
+					WriterCommand::Flush(tx) => flushed.push(tx),
+				}
+				next = commands.try_next().ok().flatten();
+			}
+
+			if let Err(err) = res.and_then(|_| writer.flush()) {
+				gum::warn!(target: LOG_TARGET, ?err, "Failed to write recorded entries");
+			}
+			for tx in flushed {
+				let _ = tx.send(());
+			}
+		}
+	})
+}
+
+/// Writes the entries of the selected subsystems to a single recording.
+#[derive(Clone)]
+pub struct Recorder {
+	commands: mpsc::UnboundedSender<WriterCommand>,
+	subsystems: Arc<HashSet<String>>,
+}
+
+impl Recorder {
+	/// Create a recording at `path`, recording the given subsystems.
+	pub fn create(
+		path: impl AsRef<Path>,
+		subsystems: impl IntoIterator<Item = String>,
+	) -> io::Result<Self> {
+		Self::new(BufWriter::new(File::create(path)?), subsystems)
+	}
+
+	/// Create a recording written to `writer`, recording the given subsystems.
+	///
+	/// The entries are written by a dedicated thread, which ends once all clones of the
+	/// recorder are dropped.
+	pub fn new(
+		mut writer: impl Write + Send + 'static,
+		subsystems: impl IntoIterator<Item = String>,
+	) -> io::Result<Self> {
+		writer.write_all(MAGIC)?;
+		writer.flush()?;
+		let (commands, rx) = mpsc::unbounded();
+		thread::Builder::new()
+			.name("overseer-recorder".into())
+			.spawn(move || run_writer(writer, rx))?;
+		Ok(Self { commands, subsystems: Arc::new(subsystems.into_iter().collect()) })
+	}
+
+	/// Whether the given subsystem is recorded.
+	pub fn records(&self, subsystem: &str) -> bool {
+		self.subsystems.contains(subsystem)
+	}
+
+	/// The names of all recorded subsystems.
+	pub fn subsystems(&self) -> impl Iterator<Item = &str> {
+		self.subsystems.iter().map(|s| s.as_str())
+	}
+
+	/// Queue an entry to be appended to the recording.
+	///
+	/// Entries are flushed as soon as the writer catches up, so the recording is mostly
+	/// complete even if the node crashes.
+	pub fn write(&self, entry: &RecordedEntry) {
+		if self.commands.unbounded_send(WriterCommand::Entry(entry.encode())).is_err() {
+			gum::warn!(target: LOG_TARGET, "Recording writer stopped, dropping entry");
+		}
+	}
+
+	/// Block until all entries queued before were written and flushed.
+	///
+	/// Blocks the calling thread, so it must not be called from async code.
+	pub fn flush(&self) {
+		let (tx, rx) = oneshot::channel();
+		if self.commands.unbounded_send(WriterCommand::Flush(tx)).is_ok() {
+			let _ = executor::block_on(rx);
+		}
+	}
+}
+
+impl fmt::Debug for Recorder {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("Recorder").field("subsystems", &self.subsystems).finish()
+	}
+}
+
+/// Encode `value` for the recording, or its `Debug` representation if it can not be recorded.
+fn encode_or_debug<T: Recordable + fmt::Debug>(value: &T) -> Result<Vec<u8>, Vec<u8>> {
+	value.encode_recorded().ok_or_else(|| format!("{:?}", value).into_bytes())
+}
+
+/// Records the signals and messages received by a single subsystem.
+pub struct SubsystemRecorder<M, Signal> {
+	recorder: Recorder,
+	subsystem: &'static str,
+	encode_message: fn(&M) -> Result<Vec<u8>, Vec<u8>>,
+	encode_signal: fn(&Signal) -> Result<Vec<u8>, Vec<u8>>,
+}
+
+impl<M, Signal> SubsystemRecorder<M, Signal>
+where
+	M: Recordable + fmt::Debug,
+	Signal: Recordable + fmt::Debug,
+{
+	/// Record the given subsystem with `recorder`.
+	pub fn new(recorder: Recorder, subsystem: &'static str) -> Self {
+		Self {
+			recorder,
+			subsystem,
+			encode_message: encode_or_debug::<M>,
+			encode_signal: encode_or_debug::<Signal>,
+		}
+	}
+}
+
+impl<M, Signal> SubsystemRecorder<M, Signal> {
+	/// Record a signal or message received by the subsystem.
+	pub fn record(&self, from_overseer: &FromOverseer<M, Signal>, signals_received: usize) {
+		let (kind, payload) = match from_overseer {
+			FromOverseer::Signal(signal) => match (self.encode_signal)(signal) {
+				Ok(payload) => (RecordedKind::Signal, payload),
+				Err(debug) => (RecordedKind::OpaqueSignal, debug),
+			},
+			FromOverseer::Communication { msg } => match (self.encode_message)(msg) {
+				Ok(payload) => (RecordedKind::Message, payload),
+				Err(debug) => (RecordedKind::OpaqueMessage, debug),
+			},
+		};
+
+		let timestamp_micros =
+			SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_micros() as u64;
+
+		self.recorder.write(&RecordedEntry {
+			timestamp_micros,
+			subsystem: self.subsystem.to_owned(),
+			signals_received: signals_received as u64,
+			kind,
+			payload,
+		});
+	}
+}
+
+impl<M, Signal> Clone for SubsystemRecorder<M, Signal> {
+	fn clone(&self) -> Self {
+		Self {
+			recorder: self.recorder.clone(),
+			subsystem: self.subsystem,
+			encode_message: self.encode_message,
+			encode_signal: self.encode_signal,
+		}
+	}
+}
+
+impl<M, Signal> fmt::Debug for SubsystemRecorder<M, Signal> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		f.debug_struct("SubsystemRecorder").field("subsystem", &self.subsystem).finish()
+	}
+}
+
+/// An error reading a recording.
+#[derive(thiserror::Error, Debug)]
+pub enum RecordingError {
+	/// Reading the recording failed.
+	#[error(transparent)]
+	Io(#[from] io::Error),
+
+	/// The recording does not start with [`MAGIC`].
+	#[error("Not a recording, the magic bytes are missing")]
+	MissingMagic,
+
+	/// An entry could not be decoded.
+	#[error("Malformed entry: {0}")]
+	Malformed(#[from] parity_scale_codec::Error),
+}
+
+/// Read all entries of the recording at `path`.
+pub fn read_recording(path: impl AsRef<Path>) -> Result<Vec<RecordedEntry>, RecordingError> {
+	let mut bytes = Vec::new();
+	BufReader::new(File::open(path)?).read_to_end(&mut bytes)?;
+	decode_recording(&bytes)
+}
+
+/// Decode all entries of a recording.
+pub fn decode_recording(mut bytes: &[u8]) -> Result<Vec<RecordedEntry>, RecordingError> {
+	if !bytes.starts_with(MAGIC) {
+		return Err(RecordingError::MissingMagic)
+	}
+	bytes = &bytes[MAGIC.len()..];
+
+	let mut entries = Vec::new();
+	while !bytes.is_empty() {
+		entries.push(RecordedEntry::decode(&mut bytes)?);
+	}
+	Ok(entries)
+}
@@ -37,3 +37,84 @@ fn ui_pass() {
 	let t = trybuild::TestCases::new();
 	t.pass("tests/ui/ok-*.rs");
 }
+
+#[test]
+fn restart_backoff_doubles_up_to_max_and_resets() {
+	use super::{Duration, RestartBackoff};
+
+	let mut backoff = RestartBackoff::default();
+	let short = Duration::from_secs(1);
+
+	let delays = (0..8).map(|_| backoff.next_delay(short)).collect::<Vec<_>>();
+	assert_eq!(
+		delays,
+		[1, 2, 4, 8, 16, 32, 60, 60]
+			.iter()
+			.map(|s| Duration::from_secs(*s))
+			.collect::<Vec<_>>(),
+	);
+
+	assert_eq!(backoff.next_delay(RestartBackoff::RESET_AFTER), RestartBackoff::INITIAL_DELAY);
+	assert_eq!(backoff.next_delay(short), Duration::from_secs(2));
+}
+
+#[test]
+fn recording_round_trips() {
+	use super::{
+		recording::{decode_recording, Recordable, RecordedKind, Recorder, SubsystemRecorder},
+		FromOverseer,
+	};
+	use std::{
+		io::{self, Write},
+		sync::{Arc, Mutex},
+	};
+
+	#[derive(Clone, Default)]
+	struct SharedBuf(Arc<Mutex<Vec<u8>>>);
+
+	impl Write for SharedBuf {
+		fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+			self.0.lock().unwrap().write(buf)
+		}
+
+		fn flush(&mut self) -> io::Result<()> {
+			Ok(())
+		}
+	}
+
+	#[derive(Debug, PartialEq)]
+	struct Num(u8);
+
+	impl Recordable for Num {
+		fn encode_recorded(&self) -> Option<Vec<u8>> {
+			// Odd numbers can not be recorded.
+			(self.0 % 2 == 0).then(|| vec![self.0])
+		}
+
+		fn decode_recorded(encoded: &[u8]) -> Option<Self> {
+			encoded.first().copied().map(Num)
+		}
+	}
+
+	let buf = SharedBuf::default();
+	let recorder = Recorder::new(buf.clone(), vec!["foo".to_owned()]).unwrap();
+	assert!(recorder.records("foo"));
+	assert!(!recorder.records("bar"));
+
+	let subsystem = SubsystemRecorder::<Num, Num>::new(recorder.clone(), "foo");
+	subsystem.record(&FromOverseer::Signal(Num(2)), 1);
+	subsystem.record(&FromOverseer::Communication { msg: Num(4) }, 1);
+	subsystem.record(&FromOverseer::Communication { msg: Num(5) }, 1);
+	recorder.flush();
+
+	let entries = decode_recording(&buf.0.lock().unwrap()).unwrap();
+	let kinds = entries.iter().map(|entry| entry.kind).collect::<Vec<_>>();
+	assert_eq!(kinds, [RecordedKind::Signal, RecordedKind::Message, RecordedKind::OpaqueMessage]);
+	assert!(entries
+		.iter()
+		.all(|entry| entry.subsystem == "foo" && entry.signals_received == 1));
+	assert_eq!(Num::decode_recorded(&entries[1].payload), Some(Num(4)));
+	assert_eq!(entries[2].payload, b"Num(5)".to_vec());
+
+	assert!(decode_recording(b"garbage").is_err());
+}
@@ -32,13 +32,21 @@ struct MetricsInner {
 	to_subsystem_bounded_sent: prometheus::GaugeVec<prometheus::U64>,
 	to_subsystem_bounded_received: prometheus::GaugeVec<prometheus::U64>,
 
+	to_subsystem_bounded_high_tof: prometheus::HistogramVec,
+	to_subsystem_bounded_high_sent: prometheus::GaugeVec<prometheus::U64>,
+	to_subsystem_bounded_high_received: prometheus::GaugeVec<prometheus::U64>,
+
 	to_subsystem_unbounded_tof: prometheus::HistogramVec,
 	to_subsystem_unbounded_sent: prometheus::GaugeVec<prometheus::U64>,
 	to_subsystem_unbounded_received: prometheus::GaugeVec<prometheus::U64>,
 
+	to_subsystem_message_tof: metrics::MessageTofHistogram,
+
 	signals_sent: prometheus::GaugeVec<prometheus::U64>,
 	signals_received: prometheus::GaugeVec<prometheus::U64>,
 
+	subsystem_restarts: prometheus::GaugeVec<prometheus::U64>,
+
 	memory_stats_resident: prometheus::Gauge<prometheus::U64>,
 	memory_stats_allocated: prometheus::Gauge<prometheus::U64>,
 }
@@ -91,6 +99,16 @@ impl Metrics {
 						.with_label_values(&[name])
 						.set(readouts.bounded.received as u64);
 
+					metrics
+						.to_subsystem_bounded_high_sent
+						.with_label_values(&[name])
+						.set(readouts.bounded_high.sent as u64);
+
+					metrics
+						.to_subsystem_bounded_high_received
+						.with_label_values(&[name])
+						.set(readouts.bounded_high.received as u64);
+
 					metrics
 						.to_subsystem_unbounded_sent
 						.with_label_values(&[name])
@@ -111,11 +129,27 @@ impl Metrics {
 						.with_label_values(&[name])
 						.set(readouts.signals.received as u64);
 
+					metrics
+						.subsystem_restarts
+						.with_label_values(&[name])
+						.set(readouts.restarts as u64);
+
+					let by_message = &metrics.to_subsystem_message_tof;
+					by_message.observe(name, &readouts.bounded);
+					by_message.observe(name, &readouts.bounded_high);
+					by_message.observe(name, &readouts.unbounded);
+
 					let hist_bounded = metrics.to_subsystem_bounded_tof.with_label_values(&[name]);
 					for tof in readouts.bounded.tof {
 						hist_bounded.observe(tof.as_f64());
 					}
 
+					let hist_bounded_high =
+						metrics.to_subsystem_bounded_high_tof.with_label_values(&[name]);
+					for tof in readouts.bounded_high.tof {
+						hist_bounded_high.observe(tof.as_f64());
+					}
+
 					let hist_unbounded =
 						metrics.to_subsystem_unbounded_tof.with_label_values(&[name]);
 					for tof in readouts.unbounded.tof {
@@ -180,6 +214,36 @@ impl MetricsTrait for Metrics {
 				)?,
 				registry,
 			)?,
+			to_subsystem_bounded_high_tof: prometheus::register(
+				prometheus::HistogramVec::new(
+					prometheus::HistogramOpts::new(
+						"polkadot_parachain_subsystem_bounded_high_priority_tof",
+						"Duration spent in the high priority lane of a bounded channel from entrance to removal",
+					),
+					&["subsystem_name"],
+				)?,
+				registry,
+			)?,
+			to_subsystem_bounded_high_sent: prometheus::register(
+				prometheus::GaugeVec::<prometheus::U64>::new(
+					prometheus::Opts::new(
+						"polkadot_parachain_subsystem_bounded_high_priority_sent",
+						"Number of elements sent to the high priority lane of subsystems' bounded queues",
+					),
+					&["subsystem_name"],
+				)?,
+				registry,
+			)?,
+			to_subsystem_bounded_high_received: prometheus::register(
+				prometheus::GaugeVec::<prometheus::U64>::new(
+					prometheus::Opts::new(
+						"polkadot_parachain_subsystem_bounded_high_priority_received",
+						"Number of elements received by the high priority lane of subsystems' bounded queues",
+					),
+					&["subsystem_name"],
+				)?,
+				registry,
+			)?,
 			to_subsystem_unbounded_tof: prometheus::register(
 				prometheus::HistogramVec::new(
 					prometheus::HistogramOpts::new(
@@ -210,6 +274,11 @@ impl MetricsTrait for Metrics {
 				)?,
 				registry,
 			)?,
+			to_subsystem_message_tof: metrics::MessageTofHistogram::try_register(
+				"polkadot_parachain_subsystem_message_tof",
+				"Duration spent in the channels of a subsystem from entrance to removal, per message type",
+				registry,
+			)?,
 			signals_sent: prometheus::register(
 				prometheus::GaugeVec::<prometheus::U64>::new(
 					prometheus::Opts::new(
@@ -230,6 +299,16 @@ impl MetricsTrait for Metrics {
 				)?,
 				registry,
 			)?,
+			subsystem_restarts: prometheus::register(
+				prometheus::GaugeVec::<prometheus::U64>::new(
+					prometheus::Opts::new(
+						"polkadot_parachain_subsystem_restarts",
+						"Number of times a subsystem was restarted after failing",
+					),
+					&["subsystem_name"],
+				)?,
+				registry,
+			)?,
 
 			memory_stats_allocated: prometheus::register(
 				prometheus::Gauge::<prometheus::U64>::new(
@@ -312,6 +312,80 @@ fn overseer_ends_on_subsystem_exit() {
 	})
 }
 
+#[derive(Clone)]
+struct FailOnFirstStart {
+	starts: Arc<atomic::AtomicUsize>,
+	started: metered::MeteredSender<usize>,
+}
+
+impl<C> overseer::Subsystem<C, SubsystemError> for FailOnFirstStart
+where
+	C: overseer::SubsystemContext<
+		Message = GossipSupportMessage,
+		Signal = OverseerSignal,
+		AllMessages = AllMessages,
+	>,
+{
+	fn start(self, mut ctx: C) -> SpawnedSubsystem {
+		let start = self.starts.fetch_add(1, atomic::Ordering::SeqCst) + 1;
+		let mut started = self.started;
+
+		SpawnedSubsystem {
+			name: "test-subsystem-restart",
+			future: Box::pin(async move {
+				started.send(start).await.unwrap();
+				if start == 1 {
+					return Err(SubsystemError::Context("fail on first start".to_owned()))
+				}
+
+				loop {
+					match ctx.recv().await {
+						Ok(FromOverseer::Signal(OverseerSignal::Conclude)) | Err(_) => break,
+						Ok(_) => continue,
+					}
+				}
+
+				Ok(())
+			}),
+		}
+	}
+}
+
+// Spawn a restartable subsystem that fails right away.
+//
+// Should restart the subsystem rather than conclude the overseer.
+#[test]
+fn overseer_restarts_failed_subsystem() {
+	let spawner = sp_core::testing::TaskExecutor::new();
+
+	executor::block_on(async move {
+		let (started_tx, mut started_rx) = metered::channel(2);
+		let subsystem = FailOnFirstStart { starts: Default::default(), started: started_tx };
+
+		let (overseer, handle) = dummy_overseer_builder(spawner, MockSupportsParachains, None)
+			.unwrap()
+			.replace_gossip_support(|_| subsystem)
+			.build()
+			.unwrap();
+
+		let mut handle = Handle::new(handle);
+		let overseer_fut = overseer.run().fuse();
+		pin_mut!(overseer_fut);
+
+		let mut starts = Vec::new();
+		while starts.len() < 2 {
+			select! {
+				res = overseer_fut => panic!("Overseer concluded with a failed subsystem: {:?}", res),
+				start = started_rx.next() => starts.push(start.unwrap()),
+			}
+		}
+		assert_eq!(starts, vec![1, 2]);
+
+		handle.stop().await;
+		assert!(overseer_fut.await.is_ok());
+	})
+}
+
 struct TestSubsystem5(metered::MeteredSender<OverseerSignal>);
 
 impl<C> overseer::Subsystem<C, SubsystemError> for TestSubsystem5
@@ -825,6 +899,8 @@ fn test_collator_generation_msg() -> CollationGenerationMessage {
 		key: CollatorPair::generate().0,
 		collator: Box::new(|_, _| TestCollator.boxed()),
 		para_id: Default::default(),
+		pov_compression: Default::default(),
+		pov_dictionary: None,
 	})
 }
 struct TestCollator;
@@ -1046,29 +1122,36 @@ fn overseer_all_subsystems_receive_signals_and_messages() {
 	});
 }
 
+fn bounded_channel<M>() -> (
+	metered::MeteredPrioritySender<MessagePacket<M>>,
+	metered::MeteredPriorityReceiver<MessagePacket<M>>,
+) {
+	metered::channel_with_priority(CHANNEL_CAPACITY, overseer::gen::normal_priority)
+}
+
 #[test]
 fn context_holds_onto_message_until_enough_signals_received() {
-	let (candidate_validation_bounded_tx, _) = metered::channel(CHANNEL_CAPACITY);
-	let (candidate_backing_bounded_tx, _) = metered::channel(CHANNEL_CAPACITY);
-	let (statement_distribution_bounded_tx, _) = metered::channel(CHANNEL_CAPACITY);
-	let (availability_distribution_bounded_tx, _) = metered::channel(CHANNEL_CAPACITY);
-	let (availability_recovery_bounded_tx, _) = metered::channel(CHANNEL_CAPACITY);
-	let (bitfield_signing_bounded_tx, _) = metered::channel(CHANNEL_CAPACITY);
-	let (bitfield_distribution_bounded_tx, _) = metered::channel(CHANNEL_CAPACITY);
-	let (provisioner_bounded_tx, _) = metered::channel(CHANNEL_CAPACITY);
-	let (runtime_api_bounded_tx, _) = metered::channel(CHANNEL_CAPACITY);
-	let (availability_store_bounded_tx, _) = metered::channel(CHANNEL_CAPACITY);
-	let (network_bridge_bounded_tx, _) = metered::channel(CHANNEL_CAPACITY);
-	let (chain_api_bounded_tx, _) = metered::channel(CHANNEL_CAPACITY);
-	let (collator_protocol_bounded_tx, _) = metered::channel(CHANNEL_CAPACITY);
-	let (collation_generation_bounded_tx, _) = metered::channel(CHANNEL_CAPACITY);
-	let (approval_distribution_bounded_tx, _) = metered::channel(CHANNEL_CAPACITY);
-	let (approval_voting_bounded_tx, _) = metered::channel(CHANNEL_CAPACITY);
-	let (gossip_support_bounded_tx, _) = metered::channel(CHANNEL_CAPACITY);
-	let (dispute_coordinator_bounded_tx, _) = metered::channel(CHANNEL_CAPACITY);
-	let (dispute_distribution_bounded_tx, _) = metered::channel(CHANNEL_CAPACITY);
-	let (chain_selection_bounded_tx, _) = metered::channel(CHANNEL_CAPACITY);
-	let (pvf_checker_bounded_tx, _) = metered::channel(CHANNEL_CAPACITY);
+	let (candidate_validation_bounded_tx, _) = bounded_channel();
+	let (candidate_backing_bounded_tx, _) = bounded_channel();
+	let (statement_distribution_bounded_tx, _) = bounded_channel();
+	let (availability_distribution_bounded_tx, _) = bounded_channel();
+	let (availability_recovery_bounded_tx, _) = bounded_channel();
+	let (bitfield_signing_bounded_tx, _) = bounded_channel();
+	let (bitfield_distribution_bounded_tx, _) = bounded_channel();
+	let (provisioner_bounded_tx, _) = bounded_channel();
+	let (runtime_api_bounded_tx, _) = bounded_channel();
+	let (availability_store_bounded_tx, _) = bounded_channel();
+	let (network_bridge_bounded_tx, _) = bounded_channel();
+	let (chain_api_bounded_tx, _) = bounded_channel();
+	let (collator_protocol_bounded_tx, _) = bounded_channel();
+	let (collation_generation_bounded_tx, _) = bounded_channel();
+	let (approval_distribution_bounded_tx, _) = bounded_channel();
+	let (approval_voting_bounded_tx, _) = bounded_channel();
+	let (gossip_support_bounded_tx, _) = bounded_channel();
+	let (dispute_coordinator_bounded_tx, _) = bounded_channel();
+	let (dispute_distribution_bounded_tx, _) = bounded_channel();
+	let (chain_selection_bounded_tx, _) = bounded_channel();
+	let (pvf_checker_bounded_tx, _) = bounded_channel();
 
 	let (candidate_validation_unbounded_tx, _) = metered::unbounded();
 	let (candidate_backing_unbounded_tx, _) = metered::unbounded();
@@ -1139,7 +1222,7 @@ fn context_holds_onto_message_until_enough_signals_received() {
 	};
 
 	let (mut signal_tx, signal_rx) = metered::channel(CHANNEL_CAPACITY);
-	let (mut bounded_tx, bounded_rx) = metered::channel(CHANNEL_CAPACITY);
+	let (mut bounded_tx, bounded_rx) = bounded_channel();
 	let (unbounded_tx, unbounded_rx) = metered::unbounded();
 	let (to_overseer_tx, _to_overseer_rx) = metered::unbounded();
 
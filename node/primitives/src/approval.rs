@@ -19,6 +19,7 @@
 pub use sp_consensus_babe::Slot;
 pub use sp_consensus_vrf::schnorrkel::{Randomness, VRFOutput, VRFProof};
 
+use bitvec::{order::Lsb0 as BitOrderLsb0, vec::BitVec};
 use parity_scale_codec::{Decode, Encode};
 use polkadot_primitives::v2::{
 	BlockNumber, CandidateHash, CandidateIndex, CoreIndex, Hash, Header, SessionIndex,
@@ -26,6 +27,7 @@ use polkadot_primitives::v2::{
 };
 use sp_application_crypto::ByteArray;
 use sp_consensus_babe as babe_primitives;
+use std::marker::PhantomData;
 
 /// Validators assigning to check a particular candidate are split up into tranches.
 /// Earlier tranches of validators check first, with later tranches serving as backup.
@@ -50,6 +52,15 @@ pub const CORE_RANDOMNESS_CONTEXT: &[u8] = b"A&V CORE";
 /// A static context associated with producing randomness for a tranche.
 pub const TRANCHE_RANDOMNESS_CONTEXT: &[u8] = b"A&V TRANCHE";
 
+/// A static context used for all relay-vrf-modulo-compact VRFs.
+pub const RELAY_VRF_MODULO_COMPACT_CONTEXT: &[u8] = b"A&V MOD COMPACT";
+
+/// A static context used for transcripts indicating the set of assigned availability cores.
+pub const ASSIGNED_CORES_CONTEXT: &[u8] = b"A&V ASSIGNED CORES";
+
+/// A static context associated with producing randomness for a set of cores.
+pub const CORES_RANDOMNESS_CONTEXT: &[u8] = b"A&V CORES";
+
 /// random bytes derived from the VRF submitted within the block by the
 /// block author as a credential and used as input to approval assignment criteria.
 #[derive(Debug, Clone, Encode, Decode, PartialEq)]
@@ -98,6 +109,231 @@ pub struct IndirectAssignmentCert {
 	pub cert: AssignmentCert,
 }
 
+/// Types which can be used as the index of a bit in a [`Bitfield`].
+pub trait AsBitIndex {
+	/// Returns the index of the corresponding bit.
+	fn as_bit_index(&self) -> usize;
+}
+
+impl AsBitIndex for CandidateIndex {
+	fn as_bit_index(&self) -> usize {
+		*self as usize
+	}
+}
+
+impl AsBitIndex for CoreIndex {
+	fn as_bit_index(&self) -> usize {
+		self.0 as usize
+	}
+}
+
+/// A non-empty bitfield addressed by indices of type `T`.
+///
+/// The last bit is always set, so that bitfields with the same set bits are equal.
+#[derive(Debug, Clone, Encode, PartialEq, Eq, Hash)]
+pub struct Bitfield<T>(BitVec<u8, BitOrderLsb0>, PhantomData<T>);
+
+impl<T> Decode for Bitfield<T> {
+	fn decode<I: parity_scale_codec::Input>(
+		input: &mut I,
+	) -> Result<Self, parity_scale_codec::Error> {
+		let mut bits = BitVec::<u8, BitOrderLsb0>::decode(input)?;
+
+		// Trailing zero bits are dropped, to keep the encoding of equal bitfields unique.
+		match bits.last_one() {
+			Some(last) => bits.truncate(last + 1),
+			None => return Err("Bitfield must have at least one bit set".into()),
+		}
+
+		Ok(Bitfield(bits, PhantomData))
+	}
+}
+
+/// A bitfield of candidate indices within a relay chain block.
+pub type CandidateBitfield = Bitfield<CandidateIndex>;
+
+/// A bitfield of availability cores.
+pub type CoreBitfield = Bitfield<CoreIndex>;
+
+/// Errors that can occur when building a [`Bitfield`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum BitfieldError {
+	/// The bitfield would not have any bits set.
+	#[error("Bitfield must have at least one bit set")]
+	NullAssignment,
+}
+
+impl<T: AsBitIndex> Bitfield<T> {
+	/// Returns the bit at `index`, `false` if out of bounds.
+	pub fn bit_at(&self, index: T) -> bool {
+		self.0.get(index.as_bit_index()).map_or(false, |b| *b)
+	}
+
+	/// Returns the number of bits in the bitfield.
+	pub fn len(&self) -> usize {
+		self.0.len()
+	}
+
+	/// Returns the number of bits which are set.
+	pub fn count_ones(&self) -> usize {
+		self.0.count_ones()
+	}
+
+	/// Returns the index of the first set bit.
+	pub fn first_one(&self) -> Option<usize> {
+		self.0.first_one()
+	}
+
+	/// Returns an iterator over the indices of all set bits.
+	pub fn iter_ones(&self) -> bitvec::slice::IterOnes<'_, u8, BitOrderLsb0> {
+		self.0.iter_ones()
+	}
+
+	/// Access the underlying bits.
+	pub fn inner(&self) -> &BitVec<u8, BitOrderLsb0> {
+		&self.0
+	}
+}
+
+impl<T: AsBitIndex> From<T> for Bitfield<T> {
+	fn from(index: T) -> Self {
+		let index = index.as_bit_index();
+		let mut bits = bitvec::bitvec![u8, BitOrderLsb0; 0; index + 1];
+		bits.set(index, true);
+		Bitfield(bits, PhantomData)
+	}
+}
+
+impl<T: AsBitIndex> TryFrom<Vec<T>> for Bitfield<T> {
+	type Error = BitfieldError;
+
+	fn try_from(indices: Vec<T>) -> Result<Self, Self::Error> {
+		let max = match indices.iter().map(|i| i.as_bit_index()).max() {
+			Some(max) => max,
+			None => return Err(BitfieldError::NullAssignment),
+		};
+
+		let mut bits = bitvec::bitvec![u8, BitOrderLsb0; 0; max + 1];
+		for index in indices {
+			bits.set(index.as_bit_index(), true);
+		}
+
+		Ok(Bitfield(bits, PhantomData))
+	}
+}
+
+/// Different kinds of input data or criteria that can prove a validator's assignment
+/// to check one or more parachains.
+///
+/// The first two variants encode identically to [`AssignmentCertKind`], so data persisted
+/// or transmitted in the older format can be decoded as this type.
+#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+pub enum AssignmentCertKindV2 {
+	/// See [`AssignmentCertKind::RelayVRFModulo`].
+	#[codec(index = 0)]
+	RelayVRFModulo {
+		/// The sample number used in this cert.
+		sample: u32,
+	},
+	/// See [`AssignmentCertKind::RelayVRFDelay`].
+	#[codec(index = 1)]
+	RelayVRFDelay {
+		/// The core index chosen in this cert.
+		core_index: CoreIndex,
+	},
+	/// An assignment story based on the VRF that authorized the relay-chain block where the
+	/// candidates were included. A single VRF output assigns the validator to all cores in
+	/// `core_bitfield` at tranche 0.
+	///
+	/// The context used to produce bytes is [`RELAY_VRF_MODULO_COMPACT_CONTEXT`]
+	#[codec(index = 2)]
+	RelayVRFModuloCompact {
+		/// The cores the validator is assigned to.
+		core_bitfield: CoreBitfield,
+	},
+}
+
+/// A certification of assignment to one or more cores.
+#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+pub struct AssignmentCertV2 {
+	/// The criterion which is claimed to be met by this cert.
+	pub kind: AssignmentCertKindV2,
+	/// The VRF showing the criterion is met.
+	pub vrf: (VRFOutput, VRFProof),
+}
+
+/// An assignment certificate which can't be expressed in the [`AssignmentCert`] format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AssignmentConversionError;
+
+impl From<AssignmentCertKind> for AssignmentCertKindV2 {
+	fn from(kind: AssignmentCertKind) -> Self {
+		match kind {
+			AssignmentCertKind::RelayVRFModulo { sample } =>
+				AssignmentCertKindV2::RelayVRFModulo { sample },
+			AssignmentCertKind::RelayVRFDelay { core_index } =>
+				AssignmentCertKindV2::RelayVRFDelay { core_index },
+		}
+	}
+}
+
+impl From<AssignmentCert> for AssignmentCertV2 {
+	fn from(cert: AssignmentCert) -> Self {
+		AssignmentCertV2 { kind: cert.kind.into(), vrf: cert.vrf }
+	}
+}
+
+impl TryFrom<AssignmentCertV2> for AssignmentCert {
+	type Error = AssignmentConversionError;
+
+	fn try_from(cert: AssignmentCertV2) -> Result<Self, Self::Error> {
+		let kind = match cert.kind {
+			AssignmentCertKindV2::RelayVRFModulo { sample } =>
+				AssignmentCertKind::RelayVRFModulo { sample },
+			AssignmentCertKindV2::RelayVRFDelay { core_index } =>
+				AssignmentCertKind::RelayVRFDelay { core_index },
+			AssignmentCertKindV2::RelayVRFModuloCompact { .. } =>
+				return Err(AssignmentConversionError),
+		};
+
+		Ok(AssignmentCert { kind, vrf: cert.vrf })
+	}
+}
+
+/// An assignment criterion which refers to the candidates under which the assignment is
+/// relevant by block hash.
+#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+pub struct IndirectAssignmentCertV2 {
+	/// A block hash where the candidates appear.
+	pub block_hash: Hash,
+	/// The validator index.
+	pub validator: ValidatorIndex,
+	/// The cert itself.
+	pub cert: AssignmentCertV2,
+}
+
+impl From<IndirectAssignmentCert> for IndirectAssignmentCertV2 {
+	fn from(indirect_cert: IndirectAssignmentCert) -> Self {
+		IndirectAssignmentCertV2 {
+			block_hash: indirect_cert.block_hash,
+			validator: indirect_cert.validator,
+			cert: indirect_cert.cert.into(),
+		}
+	}
+}
+
+impl TryFrom<IndirectAssignmentCertV2> for IndirectAssignmentCert {
+	type Error = AssignmentConversionError;
+
+	fn try_from(indirect_cert: IndirectAssignmentCertV2) -> Result<Self, Self::Error> {
+		Ok(IndirectAssignmentCert {
+			block_hash: indirect_cert.block_hash,
+			validator: indirect_cert.validator,
+			cert: indirect_cert.cert.try_into()?,
+		})
+	}
+}
+
 /// A signed approval vote which references the candidate indirectly via the block.
 ///
 /// In practice, we have a look-up from block hash and candidate index to candidate hash,
@@ -114,8 +350,63 @@ pub struct IndirectSignedApprovalVote {
 	pub signature: ValidatorSignature,
 }
 
-/// Metadata about a block which is now live in the approval protocol.
+/// A signed approval vote which references one or more candidates indirectly via the block.
+///
+/// When `candidate_indices` has a single bit set, the signature is over
+/// [`ApprovalVote`](polkadot_primitives::v2::ApprovalVote) and the vote can be converted
+/// into an [`IndirectSignedApprovalVote`]. Otherwise the signature is over
+/// [`ApprovalVoteMultipleCandidates`](polkadot_primitives::vstaging::ApprovalVoteMultipleCandidates),
+/// listing the candidate hashes in ascending order of their index in the block.
+#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+pub struct IndirectSignedApprovalVoteV2 {
+	/// A block hash where the candidates appear.
+	pub block_hash: Hash,
+	/// The indices of the candidates in the list of candidates fully included as-of the block.
+	pub candidate_indices: CandidateBitfield,
+	/// The validator index.
+	pub validator: ValidatorIndex,
+	/// The signature by the validator.
+	pub signature: ValidatorSignature,
+}
+
+/// Errors that can occur when trying to convert an approval vote covering several
+/// candidates into a single-candidate one.
 #[derive(Debug)]
+pub struct ApprovalConversionError;
+
+impl From<IndirectSignedApprovalVote> for IndirectSignedApprovalVoteV2 {
+	fn from(vote: IndirectSignedApprovalVote) -> Self {
+		IndirectSignedApprovalVoteV2 {
+			block_hash: vote.block_hash,
+			candidate_indices: vote.candidate_index.into(),
+			validator: vote.validator,
+			signature: vote.signature,
+		}
+	}
+}
+
+impl TryFrom<IndirectSignedApprovalVoteV2> for IndirectSignedApprovalVote {
+	type Error = ApprovalConversionError;
+
+	fn try_from(vote: IndirectSignedApprovalVoteV2) -> Result<Self, Self::Error> {
+		if vote.candidate_indices.count_ones() != 1 {
+			return Err(ApprovalConversionError)
+		}
+
+		let candidate_index =
+			vote.candidate_indices.first_one().ok_or(ApprovalConversionError)? as CandidateIndex;
+
+		Ok(IndirectSignedApprovalVote {
+			block_hash: vote.block_hash,
+			candidate_index,
+			validator: vote.validator,
+			signature: vote.signature,
+		})
+	}
+}
+
+/// Metadata about a block which is now live in the approval protocol.
+#[derive(Debug, Clone, Encode, Decode, PartialEq)]
 pub struct BlockApprovalMeta {
 	/// The hash of the block.
 	pub hash: Hash,
@@ -0,0 +1,188 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Compression of PoVs.
+//!
+//! The block data of a PoV is either raw, compressed with zstd by `sp_maybe_compressed_blob`,
+//! or compressed with a zstd dictionary trained for the para. The latter is prefixed with its own
+//! magic number and the hash of the dictionary.
+//!
+//! Validators need the dictionary in order to decompress such a PoV, including when recovering it
+//! from availability for approval checking or disputes. Dictionaries are therefore only used once
+//! they are registered for the para on-chain, from where validators fetch them at the relay parent
+//! of the candidate.
+
+use std::io::Read;
+
+use polkadot_primitives::{v2::Hash, vstaging::pov_dictionary_hash};
+
+use crate::{BlockData, PoV, POV_BOMB_LIMIT};
+
+/// The magic number block data compressed with a dictionary is prefixed with, followed by the
+/// hash of the dictionary. It differs from the one of `sp_maybe_compressed_blob` in the last byte.
+const DICTIONARY_PREFIX: [u8; 8] = [82, 188, 83, 118, 70, 219, 142, 6];
+
+/// How PoVs are compressed by a collator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoVCompressionConfig {
+	/// The zstd compression level of PoVs compressed with a dictionary. Other PoVs are compressed
+	/// by `sp_maybe_compressed_blob`, at its fixed level.
+	pub level: i32,
+	/// PoVs with block data smaller than this are not compressed.
+	pub threshold: usize,
+}
+
+impl Default for PoVCompressionConfig {
+	fn default() -> Self {
+		// The level used by `sp_maybe_compressed_blob`.
+		PoVCompressionConfig { level: 3, threshold: 0 }
+	}
+}
+
+/// A zstd dictionary trained for compressing the PoVs of a para.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoVDictionary {
+	data: Vec<u8>,
+	hash: Hash,
+}
+
+impl PoVDictionary {
+	/// Create a dictionary from a raw zstd dictionary.
+	pub fn new(data: Vec<u8>) -> Self {
+		let hash = pov_dictionary_hash(&data);
+		PoVDictionary { data, hash }
+	}
+
+	/// The raw zstd dictionary.
+	pub fn data(&self) -> &[u8] {
+		&self.data
+	}
+
+	/// The hash the dictionary is registered under on-chain and referred to by compressed PoVs.
+	pub fn hash(&self) -> Hash {
+		self.hash
+	}
+}
+
+/// Errors decompressing the block data of a PoV.
+#[derive(Debug, thiserror::Error)]
+pub enum PoVDecompressionError {
+	/// The block data was compressed with a dictionary which isn't available.
+	#[error("PoV was compressed with unknown dictionary {0}")]
+	UnknownDictionary(Hash),
+	/// The decompressed block data exceeds the bomb limit.
+	#[error("Decompressed PoV exceeds the bomb limit")]
+	BombLimitExceeded,
+	/// The block data couldn't be decompressed.
+	#[error("Invalid compressed PoV: {0}")]
+	Invalid(String),
+}
+
+/// The hash of the dictionary the block data of a PoV was compressed with, if any.
+pub fn pov_dictionary_hash_of(block_data: &[u8]) -> Option<Hash> {
+	let rest = block_data.strip_prefix(&DICTIONARY_PREFIX[..])?;
+	let hash = rest.get(..Hash::len_bytes())?;
+	Some(Hash::from_slice(hash))
+}
+
+/// Whether block data would be treated as compressed by validators.
+fn looks_compressed(block_data: &[u8]) -> bool {
+	// `sp_maybe_compressed_blob` returns blobs without its magic number as they are, without
+	// attempting to decompress them.
+	block_data.starts_with(&DICTIONARY_PREFIX) ||
+		!matches!(
+			sp_maybe_compressed_blob::decompress(block_data, 0),
+			Ok(std::borrow::Cow::Borrowed(_))
+		)
+}
+
+/// Compress a PoV according to `config`, using `dictionary` if given.
+///
+/// The dictionary must be registered for the para on-chain at the relay parent of the candidate,
+/// otherwise validators can't decompress the PoV.
+///
+/// PoVs whose block data exceeds the [`POV_BOMB_LIMIT`] are left uncompressed, as validators
+/// couldn't decompress them. So are PoVs smaller than the threshold, unless their block data
+/// happens to start with a compression magic number, which would make validators treat them as
+/// compressed.
+pub fn compress_pov(
+	pov: PoV,
+	config: &PoVCompressionConfig,
+	dictionary: Option<&PoVDictionary>,
+) -> PoV {
+	let PoV { block_data: BlockData(raw) } = pov;
+
+	if raw.len() > POV_BOMB_LIMIT || (raw.len() < config.threshold && !looks_compressed(&raw)) {
+		return PoV { block_data: BlockData(raw) }
+	}
+
+	let compressed = match dictionary {
+		Some(dictionary) =>
+			zstd::bulk::Compressor::with_dictionary(config.level, dictionary.data())
+				.and_then(|mut compressor| compressor.compress(&raw))
+				.ok()
+				.map(|frame| {
+					let mut compressed = DICTIONARY_PREFIX.to_vec();
+					compressed.extend_from_slice(dictionary.hash().as_ref());
+					compressed.extend(frame);
+					compressed
+				}),
+		None => sp_maybe_compressed_blob::compress(&raw, POV_BOMB_LIMIT),
+	};
+
+	PoV { block_data: BlockData(compressed.unwrap_or(raw)) }
+}
+
+/// Decompress the block data of a PoV, which may be raw or compressed in any of the supported
+/// ways.
+///
+/// Block data compressed with a dictionary requires the `dictionary` it refers to. Decompression
+/// fails if the decompressed block data would exceed `bomb_limit`.
+pub fn decompress_pov(
+	block_data: &[u8],
+	bomb_limit: usize,
+	dictionary: Option<&PoVDictionary>,
+) -> Result<Vec<u8>, PoVDecompressionError> {
+	let hash = match pov_dictionary_hash_of(block_data) {
+		Some(hash) => hash,
+		None =>
+			return sp_maybe_compressed_blob::decompress(block_data, bomb_limit)
+				.map(|raw| raw.into_owned())
+				.map_err(|err| match err {
+					sp_maybe_compressed_blob::Error::PossibleBomb =>
+						PoVDecompressionError::BombLimitExceeded,
+					err => PoVDecompressionError::Invalid(err.to_string()),
+				}),
+	};
+	let dictionary = dictionary
+		.filter(|dictionary| dictionary.hash() == hash)
+		.ok_or(PoVDecompressionError::UnknownDictionary(hash))?;
+
+	let frame = &block_data[DICTIONARY_PREFIX.len() + Hash::len_bytes()..];
+	let decoder = zstd::stream::read::Decoder::with_dictionary(frame, dictionary.data())
+		.map_err(|err| PoVDecompressionError::Invalid(err.to_string()))?;
+
+	let mut raw = Vec::new();
+	decoder
+		.take(bomb_limit as u64 + 1)
+		.read_to_end(&mut raw)
+		.map_err(|err| PoVDecompressionError::Invalid(err.to_string()))?;
+	if raw.len() > bomb_limit {
+		return Err(PoVDecompressionError::BombLimitExceeded)
+	}
+
+	Ok(raw)
+}
@@ -45,6 +45,12 @@ pub use polkadot_parachain::primitives::BlockData;
 
 pub mod approval;
 
+/// Configurable PoV compression.
+#[cfg(not(target_os = "unknown"))]
+pub mod compression;
+#[cfg(not(target_os = "unknown"))]
+pub use compression::{compress_pov, PoVCompressionConfig, PoVDictionary};
+
 /// Disputes related types.
 pub mod disputes;
 pub use disputes::{
@@ -280,8 +286,20 @@ impl MaybeCompressedPoV {
 	///
 	/// If `self == Raw` it is compressed using [`maybe_compress_pov`].
 	pub fn into_compressed(self) -> PoV {
+		self.into_compressed_with(&PoVCompressionConfig::default(), None)
+	}
+
+	/// Convert into a compressed [`PoV`], according to the given compression config and with
+	/// the given dictionary, if any.
+	///
+	/// If `self == Raw` it is compressed using [`compress_pov`].
+	pub fn into_compressed_with(
+		self,
+		config: &PoVCompressionConfig,
+		dictionary: Option<&PoVDictionary>,
+	) -> PoV {
 		match self {
-			Self::Raw(raw) => maybe_compress_pov(raw),
+			Self::Raw(raw) => compress_pov(raw, config, dictionary),
 			Self::Compressed(compressed) => compressed,
 		}
 	}
@@ -322,6 +340,10 @@ pub struct CollationSecondedSignal {
 	///
 	/// Anything else than [`Statement::Seconded`](Statement::Seconded) is forbidden here.
 	pub statement: SignedFullStatement,
+	/// The validators seconding the collation after the signer of [`Self::statement`], as they
+	/// second it. The stream ends once all validators the collation was uploaded to seconded it,
+	/// or the collator stopped tracking the collation.
+	pub further_seconded_by: futures::channel::mpsc::UnboundedReceiver<ValidatorIndex>,
 }
 
 /// Result of the [`CollatorFn`] invocation.
@@ -372,6 +394,11 @@ pub struct CollationGenerationConfig {
 	pub collator: CollatorFn,
 	/// The parachain that this collator collates for
 	pub para_id: ParaId,
+	/// How the PoVs of the parachain are compressed.
+	pub pov_compression: PoVCompressionConfig,
+	/// A zstd dictionary trained for the PoVs of the parachain. It is only used while it is
+	/// registered for the parachain on-chain.
+	pub pov_dictionary: Option<PoVDictionary>,
 }
 
 #[cfg(not(target_os = "unknown"))]
@@ -504,12 +531,9 @@ impl ErasureChunk {
 	}
 }
 
-/// Compress a PoV, unless it exceeds the [`POV_BOMB_LIMIT`].
+/// Compress a PoV with the default [`PoVCompressionConfig`], unless it exceeds the
+/// [`POV_BOMB_LIMIT`].
 #[cfg(not(target_os = "unknown"))]
 pub fn maybe_compress_pov(pov: PoV) -> PoV {
-	let PoV { block_data: BlockData(raw) } = pov;
-	let raw = sp_maybe_compressed_blob::compress(&raw, POV_BOMB_LIMIT).unwrap_or(raw);
-
-	let pov = PoV { block_data: BlockData(raw) };
-	pov
+	compress_pov(pov, &PoVCompressionConfig::default(), None)
 }
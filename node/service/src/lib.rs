@@ -37,15 +37,23 @@ use {
 	beefy_gadget::notification::{BeefyBestBlockSender, BeefySignedCommitmentSender},
 	grandpa::{self, FinalityProofProvider as GrandpaFinalityProofProvider},
 	gum::info,
-	polkadot_node_core_approval_voting::Config as ApprovalVotingConfig,
+	polkadot_collator_protocol::{CollationFetchLimits, CollatorReputationConfig},
+	polkadot_gossip_support::{Config as GossipSupportConfig, TopologyOverride},
+	polkadot_network_bridge::AddressBookConfig,
+	polkadot_node_core_approval_voting::{
+		approval_db::migration::{self as approval_db_migration, MigrationSummary},
+		Config as ApprovalVotingConfig,
+	},
 	polkadot_node_core_av_store::Config as AvailabilityConfig,
 	polkadot_node_core_av_store::Error as AvailabilityError,
+	polkadot_node_core_backing::IssuedStatementsConfig,
 	polkadot_node_core_candidate_validation::Config as CandidateValidationConfig,
 	polkadot_node_core_chain_selection::{
 		self as chain_selection_subsystem, Config as ChainSelectionConfig,
 	},
 	polkadot_node_core_dispute_coordinator::Config as DisputeCoordinatorConfig,
-	polkadot_overseer::BlockInfo,
+	polkadot_node_core_provisioner::{InherentDataDumpConfig, ProvisionerConfig},
+	polkadot_overseer::{gen::recording::Recorder, BlockInfo},
 	sc_client_api::{BlockBackend, ExecutorProvider},
 	sp_trie::PrefixedMemoryDB,
 };
@@ -55,6 +63,7 @@ pub use sp_core::traits::SpawnNamed;
 pub use {
 	polkadot_overseer::{Handle, Overseer, OverseerConnector, OverseerHandle},
 	polkadot_primitives::runtime_api::ParachainHost,
+	polkadot_subsystem::jaeger::{JaegerBackend, OtlpProtocol},
 	relay_chain_selection::SelectRelayChain,
 	sc_client_api::AuxStore,
 	sp_authority_discovery::AuthorityDiscoveryApi,
@@ -95,6 +104,8 @@ pub use polkadot_client::{
 	RuntimeApiCollection,
 };
 pub use polkadot_primitives::v2::{Block, BlockId, CollatorPair, Hash, Id as ParaId};
+#[cfg(feature = "full-node")]
+use sc_chain_spec::ChainType;
 pub use sc_client_api::{Backend, CallExecutor, ExecutionStrategy};
 pub use sc_consensus::{BlockImport, LongestChain};
 use sc_executor::NativeElseWasmExecutor;
@@ -225,9 +236,17 @@ pub enum Error {
 	#[error(transparent)]
 	Availability(#[from] AvailabilityError),
 
+	#[cfg(feature = "full-node")]
+	#[error("Failed to upgrade the approval-voting DB")]
+	ApprovalDbMigration(#[from] approval_db_migration::Error),
+
 	#[error("Authorities require the real overseer implementation")]
 	AuthoritiesRequireRealOverseer,
 
+	#[cfg(feature = "full-node")]
+	#[error("Overriding the gossip topology is only allowed on development and local chains")]
+	GossipTopologyOverrideRequiresTestChain,
+
 	#[cfg(feature = "full-node")]
 	#[error("Creating a custom database is required for validators")]
 	DatabasePathRequired,
@@ -279,17 +298,18 @@ impl IdentifyVariant for Box<dyn ChainSpec> {
 	}
 }
 
-/// Initialize the `Jeager` collector. The destination must listen
-/// on the given address and port for `UDP` packets.
+/// Initialize the `Jeager` collector. The destination must either listen
+/// on the given address and port for `UDP` packets, or be an OpenTelemetry
+/// collector receiving spans via OTLP.
 #[cfg(any(test, feature = "full-node"))]
-fn jaeger_launch_collector_with_agent(
+fn jaeger_launch_collector(
 	spawner: impl SpawnNamed,
 	config: &Configuration,
-	agent: Option<std::net::SocketAddr>,
+	backend: Option<jaeger::JaegerBackend>,
 ) -> Result<(), Error> {
-	if let Some(agent) = agent {
+	if let Some(backend) = backend {
 		let cfg = jaeger::JaegerConfig::builder()
-			.agent(agent)
+			.backend(backend)
 			.named(&config.network.node_name)
 			.build();
 
@@ -330,7 +350,7 @@ where
 #[cfg(feature = "full-node")]
 fn new_partial_basics<RuntimeApi, ExecutorDispatch>(
 	config: &mut Configuration,
-	jaeger_agent: Option<std::net::SocketAddr>,
+	jaeger_backend: Option<jaeger::JaegerBackend>,
 	telemetry_worker_handle: Option<TelemetryWorkerHandle>,
 ) -> Result<Basics<RuntimeApi, ExecutorDispatch>, Error>
 where
@@ -385,7 +405,7 @@ where
 		telemetry
 	});
 
-	jaeger_launch_collector_with_agent(task_manager.spawn_handle(), &*config, jaeger_agent)?;
+	jaeger_launch_collector(task_manager.spawn_handle(), &*config, jaeger_backend)?;
 
 	Ok(Basics { task_manager, client, backend, keystore_container, telemetry })
 }
@@ -504,7 +524,8 @@ where
 	let slot_duration = babe_config.slot_duration();
 
 	let import_setup = (block_import, grandpa_link, babe_link, beefy_links);
-	let rpc_setup = shared_voter_state.clone();
+	let gossip_topology = polkadot_rpc::GossipTopologyIntrospection::default();
+	let rpc_setup = (shared_voter_state.clone(), gossip_topology.clone());
 
 	let rpc_extensions_builder = {
 		let client = client.clone();
@@ -540,6 +561,7 @@ where
 					beefy_best_block_stream: beefy_best_block_stream.clone(),
 					subscription_executor,
 				},
+				gossip_topology: gossip_topology.clone(),
 			};
 
 			polkadot_rpc::create_full(deps, backend.clone()).map_err(Into::into)
@@ -612,6 +634,45 @@ impl IsCollator {
 	}
 }
 
+/// Operator settings of the parachain subsystems.
+///
+/// The defaults are those of a regular node.
+#[cfg(feature = "full-node")]
+#[derive(Debug, Clone, Default)]
+pub struct SubsystemsConfig {
+	/// Enables the detection of stagnant blocks by the chain selection subsystem, checking at the
+	/// given non-zero interval. Stagnant blocks are not detected by default.
+	pub stagnant_check_interval: Option<Duration>,
+	/// Overrides how long blocks may remain unapproved before being considered stagnant. This
+	/// is rounded up to whole seconds.
+	pub stagnant_timeout: Option<Duration>,
+	/// Makes the provisioner write a record of the inherent data of every authored block to the
+	/// given directory.
+	pub inherent_data_dump_dir: Option<std::path::PathBuf>,
+	/// Fixes the order of the authorities in the gossip topology to the one listed in the given
+	/// file, for deterministic test networks. Only allowed on development and local chains.
+	pub gossip_topology_file: Option<std::path::PathBuf>,
+	/// Makes approval voting issue assignments claiming multiple candidates. These are only
+	/// understood by peers speaking the second version of the validation protocol.
+	pub enable_v2_assignments: bool,
+	/// Lets approval voting sign approvals for up to the given number of candidates at once.
+	/// Approvals are signed one by one by default.
+	pub max_approval_coalesce_count: Option<u32>,
+	/// Overrides the maximum number of ticks a local approval is held back to be signed together
+	/// with the approvals of other candidates of the same relay block. Defaults to 2.
+	pub max_approval_coalesce_wait_ticks: Option<u32>,
+	/// Makes approval voting log the unapproved candidates and their no-shows whenever the
+	/// highest approved ancestor of a chain lags behind its head by more than the given number
+	/// of blocks. Not reported by default.
+	pub approval_lag_report_threshold: Option<u32>,
+	/// Overrides the maximum number of collations fetched concurrently for a single para.
+	pub max_collation_fetches_per_para: Option<usize>,
+	/// Overrides the maximum number of collations fetched concurrently across all paras.
+	pub max_collation_fetches: Option<usize>,
+	/// Records everything the listed subsystems receive to the given file.
+	pub overseer_recording: Option<(std::path::PathBuf, Vec<String>)>,
+}
+
 /// Returns the active leaves the overseer should start with.
 #[cfg(feature = "full-node")]
 async fn active_leaves<RuntimeApi, ExecutorDispatch>(
@@ -662,6 +723,112 @@ where
 	Ok(leaves.into_iter().rev().take(MAX_ACTIVE_LEAVES).collect())
 }
 
+/// Open the parachains DB for the given database source.
+#[cfg(feature = "full-node")]
+fn open_parachains_db(
+	database: &DatabaseSource,
+) -> Result<Arc<dyn polkadot_node_subsystem_util::database::Database>, Error> {
+	let parachains_db = match database {
+		DatabaseSource::RocksDb { path, .. } => crate::parachains_db::open_creating_rocksdb(
+			path.clone(),
+			crate::parachains_db::CacheSizes::default(),
+		)?,
+		DatabaseSource::ParityDb { path, .. } => crate::parachains_db::open_creating_paritydb(
+			path.parent().ok_or(Error::DatabasePathRequired)?.into(),
+			crate::parachains_db::CacheSizes::default(),
+		)?,
+		DatabaseSource::Auto { paritydb_path, rocksdb_path, .. } =>
+			if paritydb_path.is_dir() && paritydb_path.exists() {
+				crate::parachains_db::open_creating_paritydb(
+					paritydb_path.parent().ok_or(Error::DatabasePathRequired)?.into(),
+					crate::parachains_db::CacheSizes::default(),
+				)?
+			} else {
+				crate::parachains_db::open_creating_rocksdb(
+					rocksdb_path.clone(),
+					crate::parachains_db::CacheSizes::default(),
+				)?
+			},
+		DatabaseSource::Custom { .. } => {
+			unimplemented!("No polkadot subsystem db for custom source.");
+		},
+	};
+
+	Ok(parachains_db)
+}
+
+/// Open the existing parachains DB for the given database source read-only, without upgrading it.
+#[cfg(feature = "full-node")]
+fn open_parachains_db_read_only(
+	database: &DatabaseSource,
+) -> Result<Arc<dyn polkadot_node_subsystem_util::database::Database>, Error> {
+	let parachains_db = match database {
+		DatabaseSource::RocksDb { path, .. } =>
+			crate::parachains_db::open_rocksdb_read_only(path.clone())?,
+		DatabaseSource::ParityDb { path, .. } => crate::parachains_db::open_paritydb_read_only(
+			path.parent().ok_or(Error::DatabasePathRequired)?.into(),
+		)?,
+		DatabaseSource::Auto { paritydb_path, rocksdb_path, .. } =>
+			if paritydb_path.is_dir() && paritydb_path.exists() {
+				crate::parachains_db::open_paritydb_read_only(
+					paritydb_path.parent().ok_or(Error::DatabasePathRequired)?.into(),
+				)?
+			} else {
+				crate::parachains_db::open_rocksdb_read_only(rocksdb_path.clone())?
+			},
+		DatabaseSource::Custom { .. } => {
+			unimplemented!("No polkadot subsystem db for custom source.");
+		},
+	};
+
+	Ok(parachains_db)
+}
+
+/// Upgrade the approval-voting entries of the parachains DB to the current schema version.
+///
+/// With `dry_run` set, the DB is opened read-only and the pending migration is evaluated
+/// without writing anything. This requires the parachains DB itself to be up to date.
+#[cfg(feature = "full-node")]
+pub fn upgrade_approval_voting_db(
+	database: &DatabaseSource,
+	dry_run: bool,
+) -> Result<MigrationSummary, Error> {
+	let parachains_db = if dry_run {
+		open_parachains_db_read_only(database)?
+	} else {
+		open_parachains_db(database)?
+	};
+	upgrade_approval_voting_db_in(&*parachains_db, dry_run)
+}
+
+#[cfg(feature = "full-node")]
+fn upgrade_approval_voting_db_in(
+	parachains_db: &dyn polkadot_node_subsystem_util::database::Database,
+	dry_run: bool,
+) -> Result<MigrationSummary, Error> {
+	let config = polkadot_node_core_approval_voting::approval_db::v2::Config {
+		col_data: crate::parachains_db::REAL_COLUMNS.col_approval_data,
+	};
+	let summary = approval_db_migration::try_upgrade(parachains_db, &config, dry_run)?;
+	if summary.from != summary.to {
+		let outcome = if dry_run {
+			"Approval-voting DB can be upgraded"
+		} else {
+			"Upgraded approval-voting DB"
+		};
+		info!(
+			from = summary.from,
+			to = summary.to,
+			block_entries = summary.block_entries,
+			candidate_entries = summary.candidate_entries,
+			"{}",
+			outcome,
+		);
+	}
+
+	Ok(summary)
+}
+
 /// Create a new full node of arbitrary runtime and executor.
 ///
 /// This is an advanced feature and not recommended for general use. Generally, `build_full` is
@@ -670,16 +837,19 @@ where
 /// `overseer_enable_anyways` always enables the overseer, based on the provided `OverseerGenerator`,
 /// regardless of the role the node has. The relay chain selection (longest or disputes-aware) is
 /// still determined based on the role of the node. Likewise for authority discovery.
+///
+/// `subsystems_config` holds the operator settings of the parachain subsystems.
 #[cfg(feature = "full-node")]
 pub fn new_full<RuntimeApi, ExecutorDispatch, OverseerGenerator>(
 	mut config: Configuration,
 	is_collator: IsCollator,
 	grandpa_pause: Option<(u32, u32)>,
 	enable_beefy: bool,
-	jaeger_agent: Option<std::net::SocketAddr>,
+	jaeger_backend: Option<jaeger::JaegerBackend>,
 	telemetry_worker_handle: Option<TelemetryWorkerHandle>,
 	program_path: Option<std::path::PathBuf>,
 	overseer_enable_anyways: bool,
+	subsystems_config: SubsystemsConfig,
 	overseer_gen: OverseerGenerator,
 ) -> Result<NewFull<Arc<FullClient<RuntimeApi, ExecutorDispatch>>>, Error>
 where
@@ -694,6 +864,12 @@ where
 {
 	use polkadot_node_network_protocol::request_response::IncomingRequest;
 
+	if subsystems_config.gossip_topology_file.is_some() &&
+		!matches!(config.chain_spec.chain_type(), ChainType::Development | ChainType::Local)
+	{
+		return Err(Error::GossipTopologyOverrideRequiresTestChain)
+	}
+
 	let role = config.role.clone();
 	let force_authoring = config.force_authoring;
 	let backoff_authoring_blocks = {
@@ -717,7 +893,7 @@ where
 
 	let basics = new_partial_basics::<RuntimeApi, ExecutorDispatch>(
 		&mut config,
-		jaeger_agent,
+		jaeger_backend,
 		telemetry_worker_handle,
 	)?;
 
@@ -762,7 +938,7 @@ where
 		select_chain,
 	)?;
 
-	let shared_voter_state = rpc_setup;
+	let (shared_voter_state, gossip_topology) = rpc_setup;
 	let auth_disc_publish_non_global_ips = config.network.allow_non_globals_in_dht;
 
 	// Note: GrandPa is pushed before the Polkadot-specific protocols. This doesn't change
@@ -800,6 +976,8 @@ where
 	config.network.request_response_protocols.push(cfg);
 	let (collation_req_receiver, cfg) = IncomingRequest::get_config_receiver();
 	config.network.request_response_protocols.push(cfg);
+	let (collation_push_receiver, cfg) = IncomingRequest::get_config_receiver();
+	config.network.request_response_protocols.push(cfg);
 	let (available_data_req_receiver, cfg) = IncomingRequest::get_config_receiver();
 	config.network.request_response_protocols.push(cfg);
 	let (statement_req_receiver, cfg) = IncomingRequest::get_config_receiver();
@@ -850,31 +1028,8 @@ where
 		);
 	}
 
-	let parachains_db = match &config.database {
-		DatabaseSource::RocksDb { path, .. } => crate::parachains_db::open_creating_rocksdb(
-			path.clone(),
-			crate::parachains_db::CacheSizes::default(),
-		)?,
-		DatabaseSource::ParityDb { path, .. } => crate::parachains_db::open_creating_paritydb(
-			path.parent().ok_or(Error::DatabasePathRequired)?.into(),
-			crate::parachains_db::CacheSizes::default(),
-		)?,
-		DatabaseSource::Auto { paritydb_path, rocksdb_path, .. } =>
-			if paritydb_path.is_dir() && paritydb_path.exists() {
-				crate::parachains_db::open_creating_paritydb(
-					paritydb_path.parent().ok_or(Error::DatabasePathRequired)?.into(),
-					crate::parachains_db::CacheSizes::default(),
-				)?
-			} else {
-				crate::parachains_db::open_creating_rocksdb(
-					rocksdb_path.clone(),
-					crate::parachains_db::CacheSizes::default(),
-				)?
-			},
-		DatabaseSource::Custom { .. } => {
-			unimplemented!("No polkadot subsystem db for custom source.");
-		},
-	};
+	let parachains_db = open_parachains_db(&config.database)?;
+	upgrade_approval_voting_db_in(&*parachains_db, false)?;
 
 	let availability_config = AvailabilityConfig {
 		col_data: crate::parachains_db::REAL_COLUMNS.col_availability_data,
@@ -884,6 +1039,12 @@ where
 	let approval_voting_config = ApprovalVotingConfig {
 		col_data: crate::parachains_db::REAL_COLUMNS.col_approval_data,
 		slot_duration_millis: slot_duration.as_millis() as u64,
+		enable_v2_assignments: subsystems_config.enable_v2_assignments,
+		max_approval_coalesce_count: subsystems_config.max_approval_coalesce_count.unwrap_or(1),
+		max_approval_coalesce_wait_ticks: subsystems_config
+			.max_approval_coalesce_wait_ticks
+			.unwrap_or(2),
+		approval_lag_report_threshold: subsystems_config.approval_lag_report_threshold,
 	};
 
 	let candidate_validation_config = CandidateValidationConfig {
@@ -900,13 +1061,57 @@ where
 
 	let chain_selection_config = ChainSelectionConfig {
 		col_data: crate::parachains_db::REAL_COLUMNS.col_chain_selection_data,
-		stagnant_check_interval: chain_selection_subsystem::StagnantCheckInterval::never(),
+		stagnant_check_interval: subsystems_config.stagnant_check_interval.map_or_else(
+			chain_selection_subsystem::StagnantCheckInterval::never,
+			chain_selection_subsystem::StagnantCheckInterval::new,
+		),
+		stagnant_timeout: subsystems_config
+			.stagnant_timeout
+			.unwrap_or(chain_selection_subsystem::DEFAULT_STAGNANT_TIMEOUT),
 	};
 
 	let dispute_coordinator_config = DisputeCoordinatorConfig {
 		col_data: crate::parachains_db::REAL_COLUMNS.col_dispute_coordinator_data,
 	};
 
+	let issued_statements_config =
+		IssuedStatementsConfig { col_data: crate::parachains_db::REAL_COLUMNS.col_backing_data };
+
+	let collator_reputation_config = CollatorReputationConfig {
+		col_data: crate::parachains_db::REAL_COLUMNS.col_collator_reputation_data,
+	};
+
+	let recorder = subsystems_config
+		.overseer_recording
+		.map(|(path, subsystems)| Recorder::create(path, subsystems))
+		.transpose()?;
+
+	let collation_fetch_limits = {
+		let defaults = CollationFetchLimits::default();
+		CollationFetchLimits {
+			max_fetches_per_para: subsystems_config
+				.max_collation_fetches_per_para
+				.unwrap_or(defaults.max_fetches_per_para),
+			max_fetches: subsystems_config.max_collation_fetches.unwrap_or(defaults.max_fetches),
+			..defaults
+		}
+	};
+
+	let provisioner_config = ProvisionerConfig {
+		inherent_data_dump: subsystems_config
+			.inherent_data_dump_dir
+			.map(InherentDataDumpConfig::new),
+	};
+
+	let address_book_config = AddressBookConfig {
+		col_data: crate::parachains_db::REAL_COLUMNS.col_authority_discovery_data,
+	};
+
+	let gossip_support_config = GossipSupportConfig {
+		topology_override: subsystems_config.gossip_topology_file.map(TopologyOverride::File),
+		introspection: gossip_topology,
+	};
+
 	let rpc_handlers = service::spawn_tasks(service::SpawnTasksParams {
 		config,
 		backend: backend.clone(),
@@ -989,6 +1194,7 @@ where
 					pov_req_receiver,
 					chunk_req_receiver,
 					collation_req_receiver,
+					collation_push_receiver,
 					available_data_req_receiver,
 					statement_req_receiver,
 					dispute_req_receiver,
@@ -1000,7 +1206,14 @@ where
 					candidate_validation_config,
 					chain_selection_config,
 					dispute_coordinator_config,
+					issued_statements_config,
+					collator_reputation_config,
+					collation_fetch_policy: Box::new(collation_fetch_limits),
+					provisioner_config,
+					gossip_support_config,
+					address_book_config,
 					pvf_checker_enabled,
+					recorder,
 				},
 			)
 			.map_err(|e| {
@@ -1204,13 +1417,13 @@ where
 
 #[cfg(feature = "full-node")]
 macro_rules! chain_ops {
-	($config:expr, $jaeger_agent:expr, $telemetry_worker_handle:expr; $scope:ident, $executor:ident, $variant:ident) => {{
+	($config:expr, $jaeger_backend:expr, $telemetry_worker_handle:expr; $scope:ident, $executor:ident, $variant:ident) => {{
 		let telemetry_worker_handle = $telemetry_worker_handle;
-		let jaeger_agent = $jaeger_agent;
+		let jaeger_backend = $jaeger_backend;
 		let mut config = $config;
 		let basics = new_partial_basics::<$scope::RuntimeApi, $executor>(
 			config,
-			jaeger_agent,
+			jaeger_backend,
 			telemetry_worker_handle,
 		)?;
 
@@ -1232,7 +1445,7 @@ macro_rules! chain_ops {
 #[cfg(feature = "full-node")]
 pub fn new_chain_ops(
 	mut config: &mut Configuration,
-	jaeger_agent: Option<std::net::SocketAddr>,
+	jaeger_backend: Option<jaeger::JaegerBackend>,
 ) -> Result<
 	(
 		Arc<Client>,
@@ -1251,22 +1464,22 @@ pub fn new_chain_ops(
 		config.chain_spec.is_wococo() ||
 		config.chain_spec.is_versi()
 	{
-		return chain_ops!(config, jaeger_agent, telemetry_worker_handle; rococo_runtime, RococoExecutorDispatch, Rococo)
+		return chain_ops!(config, jaeger_backend, telemetry_worker_handle; rococo_runtime, RococoExecutorDispatch, Rococo)
 	}
 
 	#[cfg(feature = "kusama-native")]
 	if config.chain_spec.is_kusama() {
-		return chain_ops!(config, jaeger_agent, telemetry_worker_handle; kusama_runtime, KusamaExecutorDispatch, Kusama)
+		return chain_ops!(config, jaeger_backend, telemetry_worker_handle; kusama_runtime, KusamaExecutorDispatch, Kusama)
 	}
 
 	#[cfg(feature = "westend-native")]
 	if config.chain_spec.is_westend() {
-		return chain_ops!(config, jaeger_agent, telemetry_worker_handle; westend_runtime, WestendExecutorDispatch, Westend)
+		return chain_ops!(config, jaeger_backend, telemetry_worker_handle; westend_runtime, WestendExecutorDispatch, Westend)
 	}
 
 	#[cfg(feature = "polkadot-native")]
 	{
-		return chain_ops!(config, jaeger_agent, telemetry_worker_handle; polkadot_runtime, PolkadotExecutorDispatch, Polkadot)
+		return chain_ops!(config, jaeger_backend, telemetry_worker_handle; polkadot_runtime, PolkadotExecutorDispatch, Polkadot)
 	}
 	#[cfg(not(feature = "polkadot-native"))]
 	Err(Error::NoRuntime)
@@ -1280,15 +1493,18 @@ pub fn new_chain_ops(
 /// `overseer_enable_anyways` always enables the overseer, based on the provided `OverseerGenerator`,
 /// regardless of the role the node has. The relay chain selection (longest or disputes-aware) is
 /// still determined based on the role of the node. Likewise for authority discovery.
+///
+/// `subsystems_config` holds the operator settings of the parachain subsystems.
 #[cfg(feature = "full-node")]
 pub fn build_full(
 	config: Configuration,
 	is_collator: IsCollator,
 	grandpa_pause: Option<(u32, u32)>,
 	enable_beefy: bool,
-	jaeger_agent: Option<std::net::SocketAddr>,
+	jaeger_backend: Option<jaeger::JaegerBackend>,
 	telemetry_worker_handle: Option<TelemetryWorkerHandle>,
 	overseer_enable_anyways: bool,
+	subsystems_config: SubsystemsConfig,
 	overseer_gen: impl OverseerGen,
 ) -> Result<NewFull<Client>, Error> {
 	#[cfg(feature = "rococo-native")]
@@ -1301,10 +1517,11 @@ pub fn build_full(
 			is_collator,
 			grandpa_pause,
 			enable_beefy,
-			jaeger_agent,
+			jaeger_backend,
 			telemetry_worker_handle,
 			None,
 			overseer_enable_anyways,
+			subsystems_config.clone(),
 			overseer_gen,
 		)
 		.map(|full| full.with_client(Client::Rococo))
@@ -1317,10 +1534,11 @@ pub fn build_full(
 			is_collator,
 			grandpa_pause,
 			enable_beefy,
-			jaeger_agent,
+			jaeger_backend,
 			telemetry_worker_handle,
 			None,
 			overseer_enable_anyways,
+			subsystems_config.clone(),
 			overseer_gen,
 		)
 		.map(|full| full.with_client(Client::Kusama))
@@ -1333,10 +1551,11 @@ pub fn build_full(
 			is_collator,
 			grandpa_pause,
 			enable_beefy,
-			jaeger_agent,
+			jaeger_backend,
 			telemetry_worker_handle,
 			None,
 			overseer_enable_anyways,
+			subsystems_config.clone(),
 			overseer_gen,
 		)
 		.map(|full| full.with_client(Client::Westend))
@@ -1349,10 +1568,11 @@ pub fn build_full(
 			is_collator,
 			grandpa_pause,
 			enable_beefy,
-			jaeger_agent,
+			jaeger_backend,
 			telemetry_worker_handle,
 			None,
 			overseer_enable_anyways,
+			subsystems_config,
 			overseer_gen,
 		)
 		.map(|full| full.with_client(Client::Polkadot))
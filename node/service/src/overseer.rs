@@ -17,8 +17,12 @@
 use super::{AuthorityDiscoveryApi, Block, Error, Hash, IsCollator, Registry, SpawnNamed};
 use lru::LruCache;
 use polkadot_availability_distribution::IncomingRequestReceivers;
+use polkadot_collator_protocol::{CollationFetchPolicy, CollatorReputationConfig};
+use polkadot_gossip_support::Config as GossipSupportConfig;
+use polkadot_network_bridge::{AddressBook, AddressBookConfig};
 use polkadot_node_core_approval_voting::Config as ApprovalVotingConfig;
 use polkadot_node_core_av_store::Config as AvailabilityConfig;
+use polkadot_node_core_backing::{CandidateBackingConfig, IssuedStatementsConfig};
 use polkadot_node_core_candidate_validation::Config as CandidateValidationConfig;
 use polkadot_node_core_chain_selection::Config as ChainSelectionConfig;
 use polkadot_node_core_dispute_coordinator::Config as DisputeCoordinatorConfig;
@@ -30,8 +34,8 @@ pub use polkadot_overseer::{
 	HeadSupportsParachains,
 };
 use polkadot_overseer::{
-	metrics::Metrics as OverseerMetrics, BlockInfo, InitializedOverseerBuilder, MetricsTrait,
-	Overseer, OverseerConnector, OverseerHandle,
+	gen::recording::Recorder, metrics::Metrics as OverseerMetrics, BlockInfo,
+	InitializedOverseerBuilder, MetricsTrait, Overseer, OverseerConnector, OverseerHandle,
 };
 
 use polkadot_primitives::runtime_api::ParachainHost;
@@ -89,6 +93,7 @@ where
 	pub pov_req_receiver: IncomingRequestReceiver<request_v1::PoVFetchingRequest>,
 	pub chunk_req_receiver: IncomingRequestReceiver<request_v1::ChunkFetchingRequest>,
 	pub collation_req_receiver: IncomingRequestReceiver<request_v1::CollationFetchingRequest>,
+	pub collation_push_receiver: IncomingRequestReceiver<request_v1::CollationPushingRequest>,
 	pub available_data_req_receiver:
 		IncomingRequestReceiver<request_v1::AvailableDataFetchingRequest>,
 	pub statement_req_receiver: IncomingRequestReceiver<request_v1::StatementFetchingRequest>,
@@ -109,8 +114,22 @@ where
 	pub chain_selection_config: ChainSelectionConfig,
 	/// Configuration for the dispute coordinator subsystem.
 	pub dispute_coordinator_config: DisputeCoordinatorConfig,
+	/// Configuration for the statements persisted by the candidate backing subsystem.
+	pub issued_statements_config: IssuedStatementsConfig,
+	/// Configuration for the scores of collators kept by the collator protocol subsystem.
+	pub collator_reputation_config: CollatorReputationConfig,
+	/// The policy limiting the collations fetched by the collator protocol subsystem.
+	pub collation_fetch_policy: Box<dyn CollationFetchPolicy>,
+	/// Configuration for the provisioner subsystem.
+	pub provisioner_config: ProvisionerConfig,
+	/// Configuration for the gossip support subsystem.
+	pub gossip_support_config: GossipSupportConfig,
+	/// Configuration for the persistent cache of the addresses resolved by the authority discovery.
+	pub address_book_config: AddressBookConfig,
 	/// Enable PVF pre-checking
 	pub pvf_checker_enabled: bool,
+	/// Records the messages received by the subsystems it selects.
+	pub recorder: Option<Recorder>,
 }
 
 /// Obtain a prepared `OverseerBuilder`, that is initialized
@@ -126,6 +145,7 @@ pub fn prepared_overseer_builder<'a, Spawner, RuntimeClient>(
 		pov_req_receiver,
 		chunk_req_receiver,
 		collation_req_receiver,
+		collation_push_receiver,
 		available_data_req_receiver,
 		statement_req_receiver,
 		dispute_req_receiver,
@@ -137,7 +157,15 @@ pub fn prepared_overseer_builder<'a, Spawner, RuntimeClient>(
 		candidate_validation_config,
 		chain_selection_config,
 		dispute_coordinator_config,
+		issued_statements_config,
+		collator_reputation_config,
+		collation_fetch_policy,
+		provisioner_config,
+		gossip_support_config,
+		address_book_config,
 		pvf_checker_enabled,
+		// Only used when building the overseer.
+		recorder: _,
 	}: OverseerGenArgs<'a, Spawner, RuntimeClient>,
 ) -> Result<
 	InitializedOverseerBuilder<
@@ -156,16 +184,16 @@ pub fn prepared_overseer_builder<'a, Spawner, RuntimeClient>(
 		AvailabilityStoreSubsystem,
 		NetworkBridgeSubsystem<
 			Arc<sc_network::NetworkService<Block, Hash>>,
-			AuthorityDiscoveryService,
+			AddressBook<AuthorityDiscoveryService>,
 		>,
 		ChainApiSubsystem<RuntimeClient>,
 		CollationGenerationSubsystem,
 		CollatorProtocolSubsystem,
 		ApprovalDistributionSubsystem,
 		ApprovalVotingSubsystem,
-		GossipSupportSubsystem<AuthorityDiscoveryService>,
+		GossipSupportSubsystem<AddressBook<AuthorityDiscoveryService>>,
 		DisputeCoordinatorSubsystem,
-		DisputeDistributionSubsystem<AuthorityDiscoveryService>,
+		DisputeDistributionSubsystem<AddressBook<AuthorityDiscoveryService>>,
 		ChainSelectionSubsystem,
 	>,
 	Error,
@@ -179,6 +207,18 @@ where
 
 	let metrics = <OverseerMetrics as MetricsTrait>::register(registry)?;
 
+	// Addresses resolved before a restart are used until the DHT has been queried again.
+	let authority_discovery_service = AddressBook::with_db(
+		authority_discovery_service,
+		parachains_db.clone(),
+		address_book_config,
+	);
+	spawner.spawn(
+		"authority-discovery-address-book",
+		Some("networking"),
+		Box::pin(authority_discovery_service.clone().run_refresh()),
+	);
+
 	let builder = Overseer::builder()
 		.availability_distribution(AvailabilityDistributionSubsystem::new(
 			keystore.clone(),
@@ -202,7 +242,11 @@ where
 		))
 		.candidate_backing(CandidateBackingSubsystem::new(
 			spawner.clone(),
-			keystore.clone(),
+			CandidateBackingConfig::new(
+				keystore.clone(),
+				parachains_db.clone(),
+				issued_statements_config,
+			),
 			Metrics::register(registry)?,
 		))
 		.candidate_validation(CandidateValidationSubsystem::with_config(
@@ -228,6 +272,10 @@ where
 				IsCollator::No => ProtocolSide::Validator {
 					keystore: keystore.clone(),
 					eviction_policy: Default::default(),
+					db: parachains_db.clone(),
+					reputation_config: collator_reputation_config,
+					fetch_policy: collation_fetch_policy,
+					push_receiver: collation_push_receiver,
 					metrics: Metrics::register(registry)?,
 				},
 			};
@@ -241,7 +289,7 @@ where
 		))
 		.provisioner(ProvisionerSubsystem::new(
 			spawner.clone(),
-			ProvisionerConfig,
+			provisioner_config,
 			Metrics::register(registry)?,
 		))
 		.runtime_api(RuntimeApiSubsystem::new(
@@ -263,7 +311,8 @@ where
 			Box::new(network_service.clone()),
 			Metrics::register(registry)?,
 		))
-		.gossip_support(GossipSupportSubsystem::new(
+		.gossip_support(GossipSupportSubsystem::with_config(
+			gossip_support_config,
 			keystore.clone(),
 			authority_discovery_service.clone(),
 			Metrics::register(registry)?,
@@ -280,7 +329,11 @@ where
 			authority_discovery_service.clone(),
 			Metrics::register(registry)?,
 		))
-		.chain_selection(ChainSelectionSubsystem::new(chain_selection_config, parachains_db))
+		.chain_selection(ChainSelectionSubsystem::new(
+			chain_selection_config,
+			parachains_db,
+			Metrics::register(registry)?,
+		))
 		.leaves(Vec::from_iter(
 			leaves
 				.into_iter()
@@ -336,8 +389,9 @@ impl OverseerGen for RealOverseerGen {
 		RuntimeClient::Api: ParachainHost<Block> + BabeApi<Block> + AuthorityDiscoveryApi<Block>,
 		Spawner: 'static + SpawnNamed + Clone + Unpin,
 	{
+		let recorder = args.recorder.clone();
 		prepared_overseer_builder(args)?
-			.build_with_connector(connector)
+			.build_with_connector_and_recorder(connector, recorder)
 			.map_err(|e| e.into())
 	}
 }
@@ -15,7 +15,10 @@
 
 #[cfg(feature = "full-node")]
 use {
-	polkadot_node_subsystem_util::database::Database, std::io, std::path::PathBuf, std::sync::Arc,
+	polkadot_node_subsystem_util::database::Database,
+	std::io,
+	std::path::{Path, PathBuf},
+	std::sync::Arc,
 };
 
 #[cfg(feature = "full-node")]
@@ -26,14 +29,27 @@ pub(crate) mod columns {
 	pub mod v0 {
 		pub const NUM_COLUMNS: u32 = 3;
 	}
-	pub const NUM_COLUMNS: u32 = 5;
+	pub mod v1 {
+		pub const NUM_COLUMNS: u32 = 5;
+	}
+	pub mod v2 {
+		pub const NUM_COLUMNS: u32 = 6;
+	}
+	pub mod v3 {
+		pub const NUM_COLUMNS: u32 = 7;
+	}
+	pub const NUM_COLUMNS: u32 = 8;
 
 	pub const COL_AVAILABILITY_DATA: u32 = 0;
 	pub const COL_AVAILABILITY_META: u32 = 1;
 	pub const COL_APPROVAL_DATA: u32 = 2;
 	pub const COL_CHAIN_SELECTION_DATA: u32 = 3;
 	pub const COL_DISPUTE_COORDINATOR_DATA: u32 = 4;
-	pub const ORDERED_COL: &[u32] = &[COL_AVAILABILITY_META, COL_CHAIN_SELECTION_DATA];
+	pub const COL_COLLATOR_REPUTATION_DATA: u32 = 5;
+	pub const COL_BACKING_DATA: u32 = 6;
+	pub const COL_AUTHORITY_DISCOVERY_DATA: u32 = 7;
+	pub const ORDERED_COL: &[u32] =
+		&[COL_AVAILABILITY_META, COL_CHAIN_SELECTION_DATA, COL_BACKING_DATA];
 }
 
 /// Columns used by different subsystems.
@@ -50,6 +66,12 @@ pub struct ColumnsConfig {
 	pub col_chain_selection_data: u32,
 	/// The column used by dispute coordinator for data.
 	pub col_dispute_coordinator_data: u32,
+	/// The column used by the collator protocol for the scores of collators.
+	pub col_collator_reputation_data: u32,
+	/// The column used by candidate backing for the statements it issued.
+	pub col_backing_data: u32,
+	/// The column used by the network bridge for the addresses of authorities.
+	pub col_authority_discovery_data: u32,
 }
 
 /// The real columns used by the parachains DB.
@@ -60,6 +82,9 @@ pub const REAL_COLUMNS: ColumnsConfig = ColumnsConfig {
 	col_approval_data: columns::COL_APPROVAL_DATA,
 	col_chain_selection_data: columns::COL_CHAIN_SELECTION_DATA,
 	col_dispute_coordinator_data: columns::COL_DISPUTE_COORDINATOR_DATA,
+	col_collator_reputation_data: columns::COL_COLLATOR_REPUTATION_DATA,
+	col_backing_data: columns::COL_BACKING_DATA,
+	col_authority_discovery_data: columns::COL_AUTHORITY_DISCOVERY_DATA,
 };
 
 /// The cache size for each column, in megabytes.
@@ -111,7 +136,7 @@ pub fn open_creating_rocksdb(
 		.ok_or_else(|| other_io_error(format!("Bad database path: {:?}", path)))?;
 
 	std::fs::create_dir_all(&path_str)?;
-	upgrade::try_upgrade_db(&path)?;
+	upgrade::try_upgrade_db(&path, upgrade::DatabaseKind::RocksDB)?;
 	let db = Database::open(&db_config, &path_str)?;
 	let db =
 		polkadot_node_subsystem_util::database::kvdb_impl::DbAdapter::new(db, columns::ORDERED_COL);
@@ -131,6 +156,7 @@ pub fn open_creating_paritydb(
 		.ok_or_else(|| other_io_error(format!("Bad database path: {:?}", path)))?;
 
 	std::fs::create_dir_all(&path_str)?;
+	upgrade::try_upgrade_db(&path, upgrade::DatabaseKind::ParityDB)?;
 
 	let mut options = parity_db::Options::with_columns(&path, columns::NUM_COLUMNS as u8);
 	for i in columns::ORDERED_COL {
@@ -146,3 +172,66 @@ pub fn open_creating_paritydb(
 	);
 	Ok(Arc::new(db))
 }
+
+/// Open an existing database on disk read-only, without upgrading it.
+///
+/// RocksDB opens the database as a secondary instance, keeping its own logs in a temporary
+/// directory.
+#[cfg(feature = "full-node")]
+pub fn open_rocksdb_read_only(root: PathBuf) -> io::Result<Arc<dyn Database>> {
+	use kvdb_rocksdb::{Database, DatabaseConfig};
+
+	let path = root.join("parachains").join("db");
+	let path_str = existing_db_path(&path)?;
+	upgrade::ensure_current_version(&path, upgrade::DatabaseKind::RocksDB)?;
+
+	let secondary_path = std::env::temp_dir().join("polkadot-parachains-db-secondary");
+	let mut db_config = DatabaseConfig::with_columns(columns::NUM_COLUMNS);
+	db_config.secondary = Some(
+		secondary_path
+			.to_str()
+			.ok_or_else(|| other_io_error(format!("Bad database path: {:?}", secondary_path)))?
+			.to_owned(),
+	);
+
+	let db = Database::open(&db_config, path_str)?;
+	let db =
+		polkadot_node_subsystem_util::database::kvdb_impl::DbAdapter::new(db, columns::ORDERED_COL);
+
+	Ok(Arc::new(db))
+}
+
+/// Open an existing parity db database read-only, without upgrading it.
+#[cfg(feature = "full-node")]
+pub fn open_paritydb_read_only(root: PathBuf) -> io::Result<Arc<dyn Database>> {
+	let path = root.join("parachains");
+	existing_db_path(&path)?;
+	upgrade::ensure_current_version(&path, upgrade::DatabaseKind::ParityDB)?;
+
+	let mut options = parity_db::Options::with_columns(&path, columns::NUM_COLUMNS as u8);
+	for i in columns::ORDERED_COL {
+		options.columns[*i as usize].btree_index = true;
+	}
+
+	let db = parity_db::Db::open_read_only(&options)
+		.map_err(|err| io::Error::new(io::ErrorKind::Other, format!("{:?}", err)))?;
+
+	let db = polkadot_node_subsystem_util::database::paritydb_impl::DbAdapter::new(
+		db,
+		columns::ORDERED_COL,
+	);
+	Ok(Arc::new(db))
+}
+
+#[cfg(feature = "full-node")]
+fn existing_db_path(path: &Path) -> io::Result<&str> {
+	if !path.is_dir() {
+		return Err(io::Error::new(
+			io::ErrorKind::NotFound,
+			format!("No database found at {:?}", path),
+		))
+	}
+
+	path.to_str()
+		.ok_or_else(|| other_io_error(format!("Bad database path: {:?}", path)))
+}
@@ -26,8 +26,23 @@ type Version = u32;
 /// Version file name.
 const VERSION_FILE_NAME: &'static str = "parachain_db_version";
 
+/// The number of columns of every db version, indexed by the version.
+///
+/// Every version added columns to the previous one:
+/// * version 1 added the chain selection and dispute coordinator columns;
+/// * version 2 added the collator reputation column;
+/// * version 3 added the backing column;
+/// * version 4 added the authority discovery column.
+const NUM_COLUMNS_PER_VERSION: &[u32] = &[
+	super::columns::v0::NUM_COLUMNS,
+	super::columns::v1::NUM_COLUMNS,
+	super::columns::v2::NUM_COLUMNS,
+	super::columns::v3::NUM_COLUMNS,
+	super::columns::NUM_COLUMNS,
+];
+
 /// Current db version.
-const CURRENT_VERSION: Version = 1;
+const CURRENT_VERSION: Version = NUM_COLUMNS_PER_VERSION.len() as Version - 1;
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
@@ -37,6 +52,12 @@ pub enum Error {
 	CorruptedVersionFile,
 	#[error("Future version (expected {current:?}, found {got:?})")]
 	FutureVersion { current: Version, got: Version },
+	#[error(
+		"Outdated version, the database needs to be upgraded (expected {current:?}, found {got:?})"
+	)]
+	OutdatedVersion { current: Version, got: Version },
+	#[error("The database has an unknown number of columns: {0}")]
+	UnknownNumberOfColumns(usize),
 }
 
 impl From<Error> for io::Error {
@@ -48,25 +69,59 @@ impl From<Error> for io::Error {
 	}
 }
 
+/// The backend of the parachain's database.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum DatabaseKind {
+	RocksDB,
+	ParityDB,
+}
+
 /// Try upgrading parachain's database to the current version.
-pub fn try_upgrade_db(db_path: &Path) -> Result<(), Error> {
+pub(crate) fn try_upgrade_db(db_path: &Path, db_kind: DatabaseKind) -> Result<(), Error> {
 	let is_empty = db_path.read_dir().map_or(true, |mut d| d.next().is_none());
 	if !is_empty {
-		match current_version(db_path)? {
-			0 => migrate_from_version_0_to_1(db_path)?,
-			CURRENT_VERSION => (),
-			v => return Err(Error::FutureVersion { current: CURRENT_VERSION, got: v }),
+		let version = current_version(db_path, db_kind)?;
+		if version > CURRENT_VERSION {
+			return Err(Error::FutureVersion { current: CURRENT_VERSION, got: version })
+		}
+
+		for version in version..CURRENT_VERSION {
+			let num_columns = NUM_COLUMNS_PER_VERSION[version as usize];
+			let new_columns = NUM_COLUMNS_PER_VERSION[version as usize + 1] - num_columns;
+
+			match db_kind {
+				DatabaseKind::RocksDB => rocksdb_add_columns(db_path, num_columns, new_columns)?,
+				DatabaseKind::ParityDB => paritydb_add_columns(db_path, new_columns)?,
+			}
 		}
 	}
 
 	update_version(db_path)
 }
 
+/// Check that parachain's database is of the current version, without upgrading it.
+pub(crate) fn ensure_current_version(db_path: &Path, db_kind: DatabaseKind) -> Result<(), Error> {
+	let version = current_version(db_path, db_kind)?;
+	if version > CURRENT_VERSION {
+		return Err(Error::FutureVersion { current: CURRENT_VERSION, got: version })
+	}
+	if version < CURRENT_VERSION {
+		return Err(Error::OutdatedVersion { current: CURRENT_VERSION, got: version })
+	}
+
+	Ok(())
+}
+
 /// Reads current database version from the file at given path.
-/// If the file does not exist, assumes the current version.
-fn current_version(path: &Path) -> Result<Version, Error> {
+///
+/// If the file does not exist, assumes the current version. ParityDB databases did not have a
+/// version file before, so their version is derived from the number of columns instead.
+fn current_version(path: &Path, db_kind: DatabaseKind) -> Result<Version, Error> {
 	match fs::read_to_string(version_file_path(path)) {
-		Err(ref err) if err.kind() == io::ErrorKind::NotFound => Ok(CURRENT_VERSION),
+		Err(ref err) if err.kind() == io::ErrorKind::NotFound => match db_kind {
+			DatabaseKind::RocksDB => Ok(CURRENT_VERSION),
+			DatabaseKind::ParityDB => paritydb_version(path),
+		},
 		Err(err) => Err(err.into()),
 		Ok(content) => u32::from_str(&content).map_err(|_| Error::CorruptedVersionFile),
 	}
@@ -86,19 +141,61 @@ fn version_file_path(path: &Path) -> PathBuf {
 	file_path
 }
 
-/// Migration from version 0 to version 1:
-/// * the number of columns has changed from 3 to 5;
-fn migrate_from_version_0_to_1(path: &Path) -> Result<(), Error> {
+/// Add `new_columns` columns to the RocksDB database with `num_columns` columns at `path`.
+fn rocksdb_add_columns(path: &Path, num_columns: u32, new_columns: u32) -> Result<(), Error> {
 	use kvdb_rocksdb::{Database, DatabaseConfig};
 
 	let db_path = path
 		.to_str()
 		.ok_or_else(|| super::other_io_error("Invalid database path".into()))?;
-	let db_cfg = DatabaseConfig::with_columns(super::columns::v0::NUM_COLUMNS);
+	let db_cfg = DatabaseConfig::with_columns(num_columns);
 	let db = Database::open(&db_cfg, db_path)?;
 
-	db.add_column()?;
-	db.add_column()?;
+	for _ in 0..new_columns {
+		db.add_column()?;
+	}
+
+	Ok(())
+}
+
+/// The version of the ParityDB database at `path`, according to its number of columns.
+fn paritydb_version(path: &Path) -> Result<Version, Error> {
+	let metadata = parity_db::Options::load_metadata(path)
+		.map_err(|e| super::other_io_error(format!("Error reading metadata {:?}", e)))?;
+	let metadata = match metadata {
+		Some(metadata) => metadata,
+		None => return Ok(CURRENT_VERSION),
+	};
+
+	NUM_COLUMNS_PER_VERSION
+		.iter()
+		.position(|num_columns| *num_columns as usize == metadata.columns.len())
+		.map(|version| version as Version)
+		.ok_or(Error::UnknownNumberOfColumns(metadata.columns.len()))
+}
+
+/// Add `new_columns` columns with the default options to the ParityDB database at `path`.
+///
+/// The tables of the new columns are created once they are written to.
+fn paritydb_add_columns(path: &Path, new_columns: u32) -> Result<(), Error> {
+	let metadata = parity_db::Options::load_metadata(path)
+		.map_err(|e| super::other_io_error(format!("Error reading metadata {:?}", e)))?;
+	let metadata = match metadata {
+		Some(metadata) => metadata,
+		None => return Ok(()),
+	};
+
+	let mut options = parity_db::Options::with_columns(path, 0);
+	options.columns = metadata.columns;
+	let first_new_column = options.columns.len() as u32;
+	options.columns.extend((0..new_columns).map(|_| Default::default()));
+	for i in super::columns::ORDERED_COL.iter().filter(|i| **i >= first_new_column) {
+		options.columns[*i as usize].btree_index = true;
+	}
+
+	options
+		.write_metadata(path, &metadata.salt)
+		.map_err(|e| super::other_io_error(format!("Error writing metadata {:?}", e)))?;
 
 	Ok(())
 }
@@ -19,8 +19,13 @@
 #![warn(missing_docs)]
 
 use polkadot_node_subsystem::{
-	messages::AllMessages, overseer, FromOverseer, OverseerSignal, SpawnedSubsystem,
-	SubsystemContext, SubsystemError, SubsystemResult,
+	messages::AllMessages,
+	overseer::{
+		self,
+		gen::recording::{Recordable, RecordedEntry, RecordedKind},
+	},
+	FromOverseer, OverseerSignal, SpawnedSubsystem, SubsystemContext, SubsystemError,
+	SubsystemResult,
 };
 use polkadot_node_subsystem_util::TimeoutExt;
 
@@ -306,6 +311,78 @@ pub fn subsystem_test_harness<M, OverseerFactory, Overseer, TestFactory, Test>(
 	});
 }
 
+/// Feed the recorded entries of `subsystem_name` to the subsystem, in the recorded order.
+///
+/// Entries which could not be recorded are skipped. Unless the recording ends with
+/// [`OverseerSignal::Conclude`], the subsystem is concluded afterwards.
+async fn feed_recording<M: Recordable>(
+	handle: &mut TestSubsystemContextHandle<M>,
+	entries: &[RecordedEntry],
+	subsystem_name: &str,
+) {
+	for entry in entries.iter().filter(|entry| entry.subsystem == subsystem_name) {
+		let from_overseer = match entry.kind {
+			RecordedKind::Signal => FromOverseer::Signal(
+				OverseerSignal::decode_recorded(&entry.payload)
+					.expect("recorded signals can be decoded"),
+			),
+			RecordedKind::Message => FromOverseer::Communication {
+				msg: M::decode_recorded(&entry.payload).expect("recorded messages can be decoded"),
+			},
+			RecordedKind::OpaqueSignal | RecordedKind::OpaqueMessage => continue,
+		};
+
+		let concluded = matches!(from_overseer, FromOverseer::Signal(OverseerSignal::Conclude));
+		handle.send(from_overseer).await;
+		if concluded {
+			return
+		}
+	}
+
+	handle.send(FromOverseer::Signal(OverseerSignal::Conclude)).await;
+}
+
+/// Replay a recording of the overseer into a single subsystem, mocking the overseer.
+///
+/// The signals and messages recorded for `subsystem_name` are sent to the subsystem in
+/// the order it received them while recording. Replaying stops early if the subsystem exits.
+///
+/// Returns the result of the subsystem and all messages it sent.
+pub fn replay_recording<M, Sub>(
+	entries: &[RecordedEntry],
+	subsystem_name: &str,
+	subsystem: Sub,
+) -> (SubsystemResult<()>, Vec<AllMessages>)
+where
+	M: Recordable + std::fmt::Debug + Send + 'static,
+	AllMessages: From<M>,
+	Sub: overseer::Subsystem<TestSubsystemContext<M, TaskExecutor>, SubsystemError>,
+{
+	let pool = TaskExecutor::new();
+	let (context, mut handle) = make_subsystem_context(pool);
+	let SpawnedSubsystem { future, .. } = subsystem.start(context);
+
+	let result = futures::executor::block_on(async {
+		let feed = feed_recording(&mut handle, entries, subsystem_name).fuse();
+		let subsystem = future.fuse();
+		futures::pin_mut!(feed, subsystem);
+
+		loop {
+			futures::select! {
+				() = feed => {},
+				res = subsystem => break res,
+			}
+		}
+	});
+
+	let mut messages = Vec::new();
+	while let Ok(Some(msg)) = handle.rx.try_next() {
+		messages.push(msg);
+	}
+
+	(result, messages)
+}
+
 /// A forward subsystem that implements [`Subsystem`].
 ///
 /// It forwards all communication from the overseer to the internal message
@@ -370,8 +447,12 @@ macro_rules! arbitrary_order {
 #[cfg(test)]
 mod tests {
 	use super::*;
+	use assert_matches::assert_matches;
 	use futures::executor::block_on;
-	use polkadot_node_subsystem::messages::CollatorProtocolMessage;
+	use polkadot_node_network_protocol::{peer_set::ValidationVersion, ObservedRole, PeerId};
+	use polkadot_node_subsystem::messages::{
+		BitfieldDistributionMessage, CollatorProtocolMessage, NetworkBridgeEvent,
+	};
 	use polkadot_overseer::{dummy::dummy_overseer_builder, Handle, HeadSupportsParachains};
 	use polkadot_primitives::v2::Hash;
 
@@ -405,6 +486,66 @@ mod tests {
 		));
 	}
 
+	#[test]
+	fn replay_recording_feeds_recorded_messages() {
+		let peer = PeerId::random();
+		let entry = |subsystem: &str, kind, payload| RecordedEntry {
+			timestamp_micros: 0,
+			subsystem: subsystem.to_owned(),
+			signals_received: 0,
+			kind,
+			payload,
+		};
+		let message = |event| {
+			BitfieldDistributionMessage::NetworkBridgeUpdateV1(event)
+				.encode_recorded()
+				.unwrap()
+		};
+
+		let entries = vec![
+			entry(
+				"bitfield-distribution",
+				RecordedKind::Message,
+				message(NetworkBridgeEvent::PeerConnected(
+					peer.clone(),
+					ObservedRole::Full,
+					ValidationVersion::V1.into(),
+					None,
+				)),
+			),
+			entry("bitfield-distribution", RecordedKind::OpaqueMessage, b"opaque".to_vec()),
+			entry(
+				"gossip-support",
+				RecordedKind::Message,
+				message(NetworkBridgeEvent::PeerDisconnected(peer.clone())),
+			),
+			entry(
+				"bitfield-distribution",
+				RecordedKind::Message,
+				message(NetworkBridgeEvent::PeerDisconnected(peer)),
+			),
+		];
+
+		let (tx, rx) = mpsc::channel(entries.len());
+		let (result, messages) =
+			replay_recording(&entries, "bitfield-distribution", ForwardSubsystem(tx));
+		assert!(result.is_ok());
+		assert!(messages.is_empty());
+
+		let forwarded = block_on(rx.collect::<Vec<_>>());
+		assert_matches!(
+			&forwarded[..],
+			[
+				BitfieldDistributionMessage::NetworkBridgeUpdateV1(
+					NetworkBridgeEvent::PeerConnected(..)
+				),
+				BitfieldDistributionMessage::NetworkBridgeUpdateV1(
+					NetworkBridgeEvent::PeerDisconnected(..)
+				),
+			]
+		);
+	}
+
 	#[test]
 	fn macro_arbitrary_order() {
 		let mut vals = vec![Some(15_usize), None];
@@ -29,6 +29,7 @@ use smallvec::SmallVec;
 
 pub mod errors;
 pub mod messages;
+pub mod recording;
 
 pub use jaeger::*;
 pub use polkadot_node_jaeger as jaeger;
@@ -29,15 +29,19 @@ use thiserror::Error;
 pub use sc_network::IfDisconnected;
 
 use polkadot_node_network_protocol::{
-	peer_set::PeerSet, request_response::Requests, v1 as protocol_v1, PeerId,
-	UnifiedReputationChange,
+	peer_set::PeerSet, request_response::Requests, v1 as protocol_v1, v2 as protocol_v2, PeerId,
+	UnifiedReputationChange, ValidationProtocols, VersionedCollatorProtocolMessage,
 };
 use polkadot_node_primitives::{
-	approval::{BlockApprovalMeta, IndirectAssignmentCert, IndirectSignedApprovalVote},
+	approval::{
+		BlockApprovalMeta, CandidateBitfield, DelayTranche, IndirectAssignmentCertV2,
+		IndirectSignedApprovalVoteV2,
+	},
 	AvailableData, BabeEpoch, BlockWeight, CandidateVotes, CollationGenerationConfig,
 	CollationSecondedSignal, DisputeMessage, ErasureChunk, PoV, SignedDisputeStatement,
 	SignedFullStatement, ValidationResult,
 };
+use polkadot_overseer_gen::{metered::Priority, MessageLabel, Prioritized};
 use polkadot_primitives::v2::{
 	AuthorityDiscoveryId, BackedCandidate, BlockNumber, CandidateEvent, CandidateHash,
 	CandidateIndex, CandidateReceipt, CollatorId, CommittedCandidateReceipt, CoreState, GroupIndex,
@@ -65,7 +69,7 @@ pub trait BoundToRelayParent {
 }
 
 /// Messages received by the Candidate Backing subsystem.
-#[derive(Debug)]
+#[derive(Debug, strum::IntoStaticStr)]
 pub enum CandidateBackingMessage {
 	/// Requests a set of backable candidates that could be backed in a child of the given
 	/// relay-parent, referenced by its hash.
@@ -120,7 +124,7 @@ pub enum PreCheckOutcome {
 /// Validation requests made to the subsystem should return an error only on internal error.
 /// Otherwise, they should return either `Ok(ValidationResult::Valid(_))`
 /// or `Ok(ValidationResult::Invalid)`.
-#[derive(Debug)]
+#[derive(Debug, strum::IntoStaticStr)]
 pub enum CandidateValidationMessage {
 	/// Validate a candidate with provided parameters using relay-chain state.
 	///
@@ -182,26 +186,36 @@ impl CandidateValidationMessage {
 }
 
 /// Messages received by the Collator Protocol subsystem.
-#[derive(Debug, derive_more::From)]
+#[derive(Debug, derive_more::From, strum::IntoStaticStr)]
 pub enum CollatorProtocolMessage {
 	/// Signal to the collator protocol that it should connect to validators with the expectation
-	/// of collating on the given para. This is only expected to be called once, early on, if at all,
-	/// and only by the Collation Generation subsystem. As such, it will overwrite the value of
-	/// the previous signal.
+	/// of collating on the given para. This is only expected to be called once per para, early on,
+	/// if at all, and only by the Collation Generation subsystem. Every signal adds the given para
+	/// to the paras we are collating on.
 	///
 	/// This should be sent before any `DistributeCollation` message.
 	CollateOn(ParaId),
 	/// Provide a collation to distribute to validators with an optional result sender.
 	///
+	/// The hash is the hash of the parent head data of the candidate, which is advertised to
+	/// validators speaking the second version of the collation protocol.
+	///
 	/// The result sender should be informed when at least one parachain validator seconded the collation. It is also
 	/// completely okay to just drop the sender.
-	DistributeCollation(CandidateReceipt, PoV, Option<oneshot::Sender<CollationSecondedSignal>>),
+	DistributeCollation(
+		CandidateReceipt,
+		Hash,
+		PoV,
+		Option<oneshot::Sender<CollationSecondedSignal>>,
+	),
 	/// Report a collator as having provided an invalid collation. This should lead to disconnect
 	/// and blacklist of the collator.
 	ReportCollator(CollatorId),
 	/// Get a network bridge update.
+	///
+	/// Messages of peers are tagged with the version of the collation protocol the peer speaks.
 	#[from]
-	NetworkBridgeUpdateV1(NetworkBridgeEvent<protocol_v1::CollatorProtocolMessage>),
+	NetworkBridgeUpdate(NetworkBridgeEvent<VersionedCollatorProtocolMessage>),
 	/// We recommended a particular candidate to be seconded, but it was invalid; penalize the collator.
 	///
 	/// The hash is the relay parent.
@@ -228,7 +242,7 @@ impl BoundToRelayParent for CollatorProtocolMessage {
 ///
 /// NOTE: Any response oneshots might get cancelled if the `DisputeCoordinator` was not yet
 /// properly initialized for some reason.
-#[derive(Debug)]
+#[derive(Debug, strum::IntoStaticStr)]
 pub enum DisputeCoordinatorMessage {
 	/// Import statements by validators about a candidate.
 	///
@@ -311,7 +325,7 @@ pub enum ImportStatementsResult {
 }
 
 /// Messages going to the dispute distribution subsystem.
-#[derive(Debug)]
+#[derive(Debug, strum::IntoStaticStr)]
 pub enum DisputeDistributionMessage {
 	/// Tell dispute distribution to distribute an explicit dispute statement to
 	/// validators.
@@ -319,7 +333,7 @@ pub enum DisputeDistributionMessage {
 }
 
 /// Messages received by the network bridge subsystem.
-#[derive(Debug)]
+#[derive(Debug, strum::IntoStaticStr)]
 pub enum NetworkBridgeMessage {
 	/// Report a peer for their actions.
 	ReportPeer(PeerId, UnifiedReputationChange),
@@ -328,20 +342,26 @@ pub enum NetworkBridgeMessage {
 	DisconnectPeer(PeerId, PeerSet),
 
 	/// Send a message to one or more peers on the validation peer-set.
-	SendValidationMessage(Vec<PeerId>, protocol_v1::ValidationProtocol),
+	///
+	/// The message is converted to the protocol version of each peer. Peers whose version can't
+	/// express the message are skipped.
+	SendValidationMessage(Vec<PeerId>, ValidationProtocols),
 
 	/// Send a message to one or more peers on the collation peer-set.
-	SendCollationMessage(Vec<PeerId>, protocol_v1::CollationProtocol),
+	///
+	/// The message is downgraded to the first version of the protocol for peers which don't
+	/// speak the second one.
+	SendCollationMessage(Vec<PeerId>, protocol_v2::CollationProtocol),
 
 	/// Send a batch of validation messages.
 	///
 	/// NOTE: Messages will be processed in order (at least statement distribution relies on this).
-	SendValidationMessages(Vec<(Vec<PeerId>, protocol_v1::ValidationProtocol)>),
+	SendValidationMessages(Vec<(Vec<PeerId>, ValidationProtocols)>),
 
 	/// Send a batch of collation messages.
 	///
 	/// NOTE: Messages will be processed in order.
-	SendCollationMessages(Vec<(Vec<PeerId>, protocol_v1::CollationProtocol)>),
+	SendCollationMessages(Vec<(Vec<PeerId>, protocol_v2::CollationProtocol)>),
 
 	/// Send requests via substrate request/response.
 	/// Second parameter, tells what to do if we are not yet connected to the peer.
@@ -414,7 +434,7 @@ impl NetworkBridgeMessage {
 }
 
 /// Availability Distribution Message.
-#[derive(Debug)]
+#[derive(Debug, strum::IntoStaticStr)]
 pub enum AvailabilityDistributionMessage {
 	/// Instruct availability distribution to fetch a remote PoV.
 	///
@@ -436,7 +456,7 @@ pub enum AvailabilityDistributionMessage {
 }
 
 /// Availability Recovery Message.
-#[derive(Debug, derive_more::From)]
+#[derive(Debug, derive_more::From, strum::IntoStaticStr)]
 pub enum AvailabilityRecoveryMessage {
 	/// Recover available data from validators on the network.
 	RecoverAvailableData(
@@ -448,7 +468,7 @@ pub enum AvailabilityRecoveryMessage {
 }
 
 /// Bitfield distribution message.
-#[derive(Debug, derive_more::From)]
+#[derive(Debug, derive_more::From, strum::IntoStaticStr)]
 pub enum BitfieldDistributionMessage {
 	/// Distribute a bitfield via gossip to other validators.
 	DistributeBitfield(Hash, SignedAvailabilityBitfield),
@@ -481,7 +501,7 @@ impl BoundToRelayParent for BitfieldSigningMessage {
 }
 
 /// Availability store subsystem message.
-#[derive(Debug)]
+#[derive(Debug, strum::IntoStaticStr)]
 pub enum AvailabilityStoreMessage {
 	/// Query a `AvailableData` from the AV store.
 	QueryAvailableData(CandidateHash, oneshot::Sender<Option<AvailableData>>),
@@ -546,7 +566,7 @@ impl AvailabilityStoreMessage {
 pub type ChainApiResponseChannel<T> = oneshot::Sender<Result<T, crate::errors::ChainApiError>>;
 
 /// Chain API request subsystem message.
-#[derive(Debug)]
+#[derive(Debug, strum::IntoStaticStr)]
 pub enum ChainApiMessage {
 	/// Request the block number by hash.
 	/// Returns `None` if a block with the given hash is not present in the db.
@@ -591,8 +611,36 @@ impl ChainApiMessage {
 	}
 }
 
+/// The reason for a block not being viable to build upon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NonViableReason {
+	/// The block was reverted by one of its descendants, as a dispute concluded against
+	/// a candidate included by it.
+	RevertedByDispute,
+	/// The block wasn't approved in time.
+	Stagnant,
+	/// An ancestor of the block isn't viable. The earliest such ancestor is given.
+	NonViableAncestor(Hash),
+}
+
+/// The viability of an unfinalized block, as tracked by the chain selection subsystem.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockViability {
+	/// Whether the block has been approved.
+	pub approved: bool,
+	/// The reasons for the block not being viable. Empty if the block is viable.
+	pub non_viable_reasons: Vec<NonViableReason>,
+}
+
+impl BlockViability {
+	/// Whether the block is viable to build upon.
+	pub fn is_viable(&self) -> bool {
+		self.non_viable_reasons.is_empty()
+	}
+}
+
 /// Chain selection subsystem messages
-#[derive(Debug)]
+#[derive(Debug, strum::IntoStaticStr)]
 pub enum ChainSelectionMessage {
 	/// Signal to the chain selection subsystem that a specific block has been approved.
 	Approved(Hash),
@@ -601,6 +649,9 @@ pub enum ChainSelectionMessage {
 	/// Request the best leaf containing the given block in its ancestry. Return `None` if
 	/// there is no such leaf.
 	BestLeafContaining(Hash, oneshot::Sender<Option<Hash>>),
+	/// Request the viability of the given block. Return `None` if the block is unknown
+	/// or already finalized.
+	Viability(Hash, oneshot::Sender<Option<BlockViability>>),
 }
 
 impl ChainSelectionMessage {
@@ -613,6 +664,7 @@ impl ChainSelectionMessage {
 			ChainSelectionMessage::Approved(_) => None,
 			ChainSelectionMessage::Leaves(_) => None,
 			ChainSelectionMessage::BestLeafContaining(..) => None,
+			ChainSelectionMessage::Viability(..) => None,
 		}
 	}
 }
@@ -693,10 +745,40 @@ pub enum RuntimeApiRequest {
 		OccupiedCoreAssumption,
 		RuntimeApiSender<Option<ValidationCodeHash>>,
 	),
+	/// Get the weights charged by the runtime for the contents of the parachains inherent.
+	/// This is a staging API, which is not available on all runtimes.
+	StagingInherentWeightModel(
+		RuntimeApiSender<polkadot_primitives::vstaging::InherentWeightModel>,
+	),
+	/// Submits a report of misbehavior by a validator during backing into the transaction pool.
+	/// This is a staging API, which is not available on all runtimes.
+	StagingSubmitBackingMisbehaviorReport(
+		polkadot_primitives::vstaging::BackingMisbehaviorReport,
+		RuntimeApiSender<()>,
+	),
+	/// Get the zstd dictionary with the given hash registered for compressing the PoVs of the
+	/// para, if any.
+	/// This is a staging API, which is not available on all runtimes.
+	StagingPoVDictionary(ParaId, Hash, RuntimeApiSender<Option<Vec<u8>>>),
+}
+
+impl RuntimeApiRequest {
+	/// The runtime API version from which validators may sign approval votes covering multiple
+	/// candidates, which are a staging feature.
+	pub const APPROVAL_COALESCING_RUNTIME_REQUIREMENT: u32 = 3;
+
+	/// The runtime API version from which `StagingInherentWeightModel` is available.
+	pub const INHERENT_WEIGHT_MODEL_RUNTIME_REQUIREMENT: u32 = 3;
+
+	/// The runtime API version from which `StagingSubmitBackingMisbehaviorReport` is available.
+	pub const BACKING_MISBEHAVIOR_REPORT_RUNTIME_REQUIREMENT: u32 = 3;
+
+	/// The runtime API version from which `StagingPoVDictionary` is available.
+	pub const POV_DICTIONARY_RUNTIME_REQUIREMENT: u32 = 3;
 }
 
 /// A message to the Runtime API subsystem.
-#[derive(Debug)]
+#[derive(Debug, strum::IntoStaticStr)]
 pub enum RuntimeApiMessage {
 	/// Make a request of the runtime API against the post-state of the given relay-parent.
 	Request(Hash, RuntimeApiRequest),
@@ -712,7 +794,7 @@ impl RuntimeApiMessage {
 }
 
 /// Statement distribution message.
-#[derive(Debug, derive_more::From)]
+#[derive(Debug, derive_more::From, strum::IntoStaticStr)]
 pub enum StatementDistributionMessage {
 	/// We have originated a signed statement in the context of
 	/// given relay-parent hash and it should be distributed to other validators.
@@ -750,7 +832,7 @@ pub struct ProvisionerInherentData {
 /// Message to the Provisioner.
 ///
 /// In all cases, the Hash is that of the relay parent.
-#[derive(Debug)]
+#[derive(Debug, strum::IntoStaticStr)]
 pub enum ProvisionerMessage {
 	/// This message allows external subsystems to request the set of bitfields and backed candidates
 	/// associated with a particular potential block hash.
@@ -772,9 +854,11 @@ impl BoundToRelayParent for ProvisionerMessage {
 }
 
 /// Message to the Collation Generation subsystem.
-#[derive(Debug)]
+#[derive(Debug, strum::IntoStaticStr)]
 pub enum CollationGenerationMessage {
-	/// Initialize the collation generation subsystem
+	/// Initialize the collation generation subsystem for the para of the given config.
+	///
+	/// Sent once for every para the collator collates for.
 	Initialize(CollationGenerationConfig),
 }
 
@@ -814,6 +898,8 @@ pub enum AssignmentCheckError {
 	InvalidCert(ValidatorIndex),
 	#[error("Internal state mismatch: {0:?}, {1:?}")]
 	Internal(Hash, CandidateHash),
+	#[error("Invalid claimed candidate bitfield for block {0:?}")]
+	InvalidBitfield(Hash),
 }
 
 /// The result type of [`ApprovalVotingMessage::CheckAndImportApproval`] request.
@@ -845,6 +931,8 @@ pub enum ApprovalCheckError {
 	NoAssignment(ValidatorIndex),
 	#[error("Internal state mismatch: {0:?}, {1:?}")]
 	Internal(Hash, CandidateHash),
+	#[error("Invalid approved candidate bitfield for block {0:?}")]
+	InvalidBitfield(Hash),
 }
 
 /// Describes a relay-chain block by the para-chain candidates
@@ -873,21 +961,48 @@ pub struct HighestApprovedAncestorBlock {
 	pub descriptions: Vec<BlockDescription>,
 }
 
+/// The assigned checkers of a candidate which didn't approve it in time.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CandidateNoShows {
+	/// The hash of the candidate.
+	pub candidate_hash: CandidateHash,
+	/// The highest tranche of assignments which was needed to approve the candidate.
+	/// `None` if the candidate wasn't approved by its assigned checkers, e.g. because one
+	/// third of all validators approved it.
+	pub needed_tranche: Option<DelayTranche>,
+	/// The assigned validators which didn't approve the candidate before becoming no-shows.
+	pub no_shows: Vec<ValidatorIndex>,
+}
+
+/// Response type to `ApprovalVotingMessage::GetBlockNoShows`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BlockNoShows {
+	/// The hash of the finalized block.
+	pub block_hash: Hash,
+	/// The number of the finalized block.
+	pub block_number: BlockNumber,
+	/// The session the candidates of the block were checked in.
+	pub session: SessionIndex,
+	/// The candidates included by the block, in the order of their index in the block.
+	pub candidates: Vec<CandidateNoShows>,
+}
+
 /// Message to the Approval Voting subsystem.
-#[derive(Debug)]
+#[derive(Debug, strum::IntoStaticStr)]
 pub enum ApprovalVotingMessage {
 	/// Check if the assignment is valid and can be accepted by our view of the protocol.
+	/// The assignment claims all candidates in the bitfield, indexed by their position in the block.
 	/// Should not be sent unless the block hash is known.
 	CheckAndImportAssignment(
-		IndirectAssignmentCert,
-		CandidateIndex,
+		IndirectAssignmentCertV2,
+		CandidateBitfield,
 		oneshot::Sender<AssignmentCheckResult>,
 	),
 	/// Check if the approval vote is valid and can be accepted by our view of the
-	/// protocol.
+	/// protocol. The vote approves all candidates in its bitfield.
 	///
 	/// Should not be sent unless the block hash within the indirect vote is known.
-	CheckAndImportApproval(IndirectSignedApprovalVote, oneshot::Sender<ApprovalCheckResult>),
+	CheckAndImportApproval(IndirectSignedApprovalVoteV2, oneshot::Sender<ApprovalCheckResult>),
 	/// Returns the highest possible ancestor hash of the provided block hash which is
 	/// acceptable to vote on finality for.
 	/// The `BlockNumber` provided is the number of the block's ancestor which is the
@@ -896,28 +1011,64 @@ pub enum ApprovalVotingMessage {
 	/// It can also return the same block hash, if that is acceptable to vote upon.
 	/// Return `None` if the input hash is unrecognized.
 	ApprovedAncestor(Hash, BlockNumber, oneshot::Sender<Option<HighestApprovedAncestorBlock>>),
+	/// Returns the no-shows recorded for the candidates of a recently finalized block.
+	///
+	/// Returns `None` if the block is unknown or was finalized too long ago.
+	GetBlockNoShows(Hash, oneshot::Sender<Option<BlockNoShows>>),
+	/// Returns the number of no-shows of every validator of the given session which
+	/// no-showed at least once in a block finalized since the subsystem started.
+	///
+	/// Counts are only kept for the sessions within the approval window.
+	GetValidatorNoShows(SessionIndex, oneshot::Sender<Vec<(ValidatorIndex, u32)>>),
+}
+
+impl Prioritized for ApprovalVotingMessage {
+	fn priority(&self) -> Priority {
+		match self {
+			// Finality votes wait for the answer, it must not queue behind gossiped votes.
+			ApprovalVotingMessage::ApprovedAncestor(..) => Priority::High,
+			ApprovalVotingMessage::CheckAndImportAssignment(..) |
+			ApprovalVotingMessage::CheckAndImportApproval(..) |
+			ApprovalVotingMessage::GetBlockNoShows(..) |
+			ApprovalVotingMessage::GetValidatorNoShows(..) => Priority::Normal,
+		}
+	}
 }
 
 /// Message to the Approval Distribution subsystem.
-#[derive(Debug, derive_more::From)]
+#[derive(Debug, derive_more::From, strum::IntoStaticStr)]
 pub enum ApprovalDistributionMessage {
 	/// Notify the `ApprovalDistribution` subsystem about new blocks
 	/// and the candidates contained within them.
 	NewBlocks(Vec<BlockApprovalMeta>),
 	/// Distribute an assignment cert from the local validator. The cert is assumed
 	/// to be valid, relevant, and for the given relay-parent and validator index.
-	DistributeAssignment(IndirectAssignmentCert, CandidateIndex),
+	/// The bitfield holds the indices of all candidates in the block claimed by the cert.
+	DistributeAssignment(IndirectAssignmentCertV2, CandidateBitfield),
 	/// Distribute an approval vote for the local validator. The approval vote is assumed to be
 	/// valid, relevant, and the corresponding approval already issued.
 	/// If not, the subsystem is free to drop the message.
-	DistributeApproval(IndirectSignedApprovalVote),
+	/// The vote may approve several candidates of the block at once.
+	DistributeApproval(IndirectSignedApprovalVoteV2),
 	/// An update from the network bridge.
 	#[from]
-	NetworkBridgeUpdateV1(NetworkBridgeEvent<protocol_v1::ApprovalDistributionMessage>),
+	NetworkBridgeUpdate(NetworkBridgeEvent<protocol_v2::ApprovalDistributionMessage>),
+}
+
+impl Prioritized for ApprovalDistributionMessage {
+	fn priority(&self) -> Priority {
+		match self {
+			// Local blocks and votes are received before the gossip of our peers.
+			ApprovalDistributionMessage::NewBlocks(..) |
+			ApprovalDistributionMessage::DistributeAssignment(..) |
+			ApprovalDistributionMessage::DistributeApproval(..) => Priority::High,
+			ApprovalDistributionMessage::NetworkBridgeUpdate(..) => Priority::Normal,
+		}
+	}
 }
 
 /// Message to the Gossip Support subsystem.
-#[derive(Debug, derive_more::From)]
+#[derive(Debug, derive_more::From, strum::IntoStaticStr)]
 pub enum GossipSupportMessage {
 	/// Dummy constructor, so we can receive networking events.
 	#[from]
@@ -929,3 +1080,50 @@ pub enum GossipSupportMessage {
 /// Currently non-instantiable.
 #[derive(Debug)]
 pub enum PvfCheckerMessage {}
+
+macro_rules! impl_message_label {
+	($($message:ty),* $(,)?) => {
+		$(
+			impl MessageLabel for $message {
+				fn message_label(&self) -> &'static str {
+					self.into()
+				}
+			}
+		)*
+	};
+}
+
+// Time of flight samples are labelled by the variant name.
+impl_message_label!(
+	ApprovalDistributionMessage,
+	ApprovalVotingMessage,
+	AvailabilityDistributionMessage,
+	AvailabilityRecoveryMessage,
+	AvailabilityStoreMessage,
+	BitfieldDistributionMessage,
+	CandidateBackingMessage,
+	CandidateValidationMessage,
+	ChainApiMessage,
+	ChainSelectionMessage,
+	CollationGenerationMessage,
+	CollatorProtocolMessage,
+	DisputeCoordinatorMessage,
+	DisputeDistributionMessage,
+	GossipSupportMessage,
+	NetworkBridgeMessage,
+	ProvisionerMessage,
+	RuntimeApiMessage,
+	StatementDistributionMessage,
+);
+
+impl MessageLabel for BitfieldSigningMessage {
+	fn message_label(&self) -> &'static str {
+		match *self {}
+	}
+}
+
+impl MessageLabel for PvfCheckerMessage {
+	fn message_label(&self) -> &'static str {
+		match *self {}
+	}
+}
@@ -0,0 +1,421 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Encoding of signals and messages for recordings of the overseer.
+//!
+//! Spans can not be recorded, they are replaced by [`jaeger::Span::Disabled`] when read back.
+
+use std::{collections::HashMap, sync::Arc};
+
+use parity_scale_codec::{Decode, Encode};
+use polkadot_node_network_protocol::{ObservedRole, OurView, ProtocolVersion, View};
+use polkadot_node_primitives::approval::{
+	BlockApprovalMeta, CandidateBitfield, IndirectAssignmentCertV2, IndirectSignedApprovalVoteV2,
+};
+use polkadot_overseer_gen::recording::Recordable;
+use polkadot_primitives::v2::{
+	AuthorityDiscoveryId, CandidateReceipt, CollatorId, Id as ParaId, SessionIndex, ValidatorIndex,
+};
+use sc_network::PeerId;
+
+use crate::{
+	jaeger,
+	messages::{
+		network_bridge_event::{NewGossipTopology, TopologyPeerInfo},
+		ApprovalDistributionMessage, BitfieldDistributionMessage, CollatorProtocolMessage,
+		GossipSupportMessage, NetworkBridgeEvent, StatementDistributionMessage,
+	},
+	ActivatedLeaf, ActiveLeavesUpdate, BlockNumber, Hash, LeafStatus, OverseerSignal,
+};
+
+#[derive(Encode, Decode)]
+struct RecordedLeaf {
+	hash: Hash,
+	number: BlockNumber,
+	fresh: bool,
+}
+
+#[derive(Encode, Decode)]
+enum RecordedSignal {
+	ActiveLeaves { activated: Option<RecordedLeaf>, deactivated: Vec<Hash> },
+	BlockFinalized(Hash, BlockNumber),
+	Conclude,
+}
+
+impl Recordable for OverseerSignal {
+	fn encode_recorded(&self) -> Option<Vec<u8>> {
+		let recorded = match self {
+			OverseerSignal::ActiveLeaves(update) => RecordedSignal::ActiveLeaves {
+				activated: update.activated.as_ref().map(|leaf| RecordedLeaf {
+					hash: leaf.hash,
+					number: leaf.number,
+					fresh: leaf.status.is_fresh(),
+				}),
+				deactivated: update.deactivated.to_vec(),
+			},
+			OverseerSignal::BlockFinalized(hash, number) =>
+				RecordedSignal::BlockFinalized(*hash, *number),
+			OverseerSignal::Conclude => RecordedSignal::Conclude,
+		};
+		Some(recorded.encode())
+	}
+
+	fn decode_recorded(mut encoded: &[u8]) -> Option<Self> {
+		Some(match RecordedSignal::decode(&mut encoded).ok()? {
+			RecordedSignal::ActiveLeaves { activated, deactivated } =>
+				OverseerSignal::ActiveLeaves(ActiveLeavesUpdate {
+					activated: activated.map(|leaf| ActivatedLeaf {
+						hash: leaf.hash,
+						number: leaf.number,
+						status: if leaf.fresh { LeafStatus::Fresh } else { LeafStatus::Stale },
+						span: Arc::new(jaeger::Span::Disabled),
+					}),
+					deactivated: deactivated.into(),
+				}),
+			RecordedSignal::BlockFinalized(hash, number) =>
+				OverseerSignal::BlockFinalized(hash, number),
+			RecordedSignal::Conclude => OverseerSignal::Conclude,
+		})
+	}
+}
+
+#[derive(Encode, Decode)]
+enum RecordedRole {
+	Light,
+	Full,
+	Authority,
+}
+
+#[derive(Encode, Decode)]
+struct RecordedTopologyPeer {
+	authority: AuthorityDiscoveryId,
+	peer_ids: Vec<Vec<u8>>,
+	validator_index: ValidatorIndex,
+}
+
+/// Peer ids are recorded by their byte representation.
+#[derive(Encode, Decode)]
+enum RecordedNetworkEvent<M> {
+	PeerConnected(Vec<u8>, RecordedRole, ProtocolVersion, Option<Vec<AuthorityDiscoveryId>>),
+	PeerDisconnected(Vec<u8>),
+	NewGossipTopology {
+		session: SessionIndex,
+		our_neighbors_x: Vec<RecordedTopologyPeer>,
+		our_neighbors_y: Vec<RecordedTopologyPeer>,
+	},
+	PeerMessage(Vec<u8>, M),
+	PeerViewChange(Vec<u8>, View),
+	OurViewChange(View),
+}
+
+fn record_neighbors(
+	neighbors: &HashMap<AuthorityDiscoveryId, TopologyPeerInfo>,
+) -> Vec<RecordedTopologyPeer> {
+	neighbors
+		.iter()
+		.map(|(authority, info)| RecordedTopologyPeer {
+			authority: authority.clone(),
+			peer_ids: info.peer_ids.iter().map(|peer| peer.to_bytes()).collect(),
+			validator_index: info.validator_index,
+		})
+		.collect()
+}
+
+fn restore_neighbors(
+	neighbors: Vec<RecordedTopologyPeer>,
+) -> Option<HashMap<AuthorityDiscoveryId, TopologyPeerInfo>> {
+	neighbors
+		.into_iter()
+		.map(|peer| {
+			let peer_ids = peer
+				.peer_ids
+				.iter()
+				.map(|bytes| PeerId::from_bytes(bytes).ok())
+				.collect::<Option<Vec<_>>>()?;
+			Some((
+				peer.authority,
+				TopologyPeerInfo { peer_ids, validator_index: peer.validator_index },
+			))
+		})
+		.collect()
+}
+
+impl<M: Encode + Decode> Recordable for NetworkBridgeEvent<M> {
+	fn encode_recorded(&self) -> Option<Vec<u8>> {
+		let recorded: RecordedNetworkEvent<&M> = match self {
+			NetworkBridgeEvent::PeerConnected(peer, role, version, authority_ids) =>
+				RecordedNetworkEvent::PeerConnected(
+					peer.to_bytes(),
+					match role {
+						ObservedRole::Light => RecordedRole::Light,
+						ObservedRole::Full => RecordedRole::Full,
+						ObservedRole::Authority => RecordedRole::Authority,
+					},
+					*version,
+					authority_ids.as_ref().map(|ids| ids.iter().cloned().collect()),
+				),
+			NetworkBridgeEvent::PeerDisconnected(peer) =>
+				RecordedNetworkEvent::PeerDisconnected(peer.to_bytes()),
+			NetworkBridgeEvent::NewGossipTopology(topology) =>
+				RecordedNetworkEvent::NewGossipTopology {
+					session: topology.session,
+					our_neighbors_x: record_neighbors(&topology.our_neighbors_x),
+					our_neighbors_y: record_neighbors(&topology.our_neighbors_y),
+				},
+			NetworkBridgeEvent::PeerMessage(peer, msg) =>
+				RecordedNetworkEvent::PeerMessage(peer.to_bytes(), msg),
+			NetworkBridgeEvent::PeerViewChange(peer, view) =>
+				RecordedNetworkEvent::PeerViewChange(peer.to_bytes(), view.clone()),
+			NetworkBridgeEvent::OurViewChange(view) =>
+				RecordedNetworkEvent::OurViewChange(View::clone(view)),
+		};
+		Some(recorded.encode())
+	}
+
+	fn decode_recorded(mut encoded: &[u8]) -> Option<Self> {
+		let peer = |bytes: Vec<u8>| PeerId::from_bytes(&bytes).ok();
+		Some(match RecordedNetworkEvent::<M>::decode(&mut encoded).ok()? {
+			RecordedNetworkEvent::PeerConnected(bytes, role, version, authority_ids) =>
+				NetworkBridgeEvent::PeerConnected(
+					peer(bytes)?,
+					match role {
+						RecordedRole::Light => ObservedRole::Light,
+						RecordedRole::Full => ObservedRole::Full,
+						RecordedRole::Authority => ObservedRole::Authority,
+					},
+					version,
+					authority_ids.map(|ids| ids.into_iter().collect()),
+				),
+			RecordedNetworkEvent::PeerDisconnected(bytes) =>
+				NetworkBridgeEvent::PeerDisconnected(peer(bytes)?),
+			RecordedNetworkEvent::NewGossipTopology {
+				session,
+				our_neighbors_x,
+				our_neighbors_y,
+			} => NetworkBridgeEvent::NewGossipTopology(NewGossipTopology {
+				session,
+				our_neighbors_x: restore_neighbors(our_neighbors_x)?,
+				our_neighbors_y: restore_neighbors(our_neighbors_y)?,
+			}),
+			RecordedNetworkEvent::PeerMessage(bytes, msg) =>
+				NetworkBridgeEvent::PeerMessage(peer(bytes)?, msg),
+			RecordedNetworkEvent::PeerViewChange(bytes, view) =>
+				NetworkBridgeEvent::PeerViewChange(peer(bytes)?, view),
+			RecordedNetworkEvent::OurViewChange(view) =>
+				NetworkBridgeEvent::OurViewChange(OurView::new(
+					view.iter().map(|head| (*head, Arc::new(jaeger::Span::Disabled))),
+					view.finalized_number,
+				)),
+		})
+	}
+}
+
+impl Recordable for BitfieldDistributionMessage {
+	fn encode_recorded(&self) -> Option<Vec<u8>> {
+		match self {
+			// A signed bitfield can not be restored without checking its signature again.
+			BitfieldDistributionMessage::DistributeBitfield(..) => None,
+			BitfieldDistributionMessage::NetworkBridgeUpdateV1(event) => event.encode_recorded(),
+		}
+	}
+
+	fn decode_recorded(encoded: &[u8]) -> Option<Self> {
+		NetworkBridgeEvent::decode_recorded(encoded)
+			.map(BitfieldDistributionMessage::NetworkBridgeUpdateV1)
+	}
+}
+
+impl Recordable for StatementDistributionMessage {
+	fn encode_recorded(&self) -> Option<Vec<u8>> {
+		match self {
+			// A signed statement can not be restored without checking its signature again.
+			StatementDistributionMessage::Share(..) => None,
+			StatementDistributionMessage::NetworkBridgeUpdateV1(event) => event.encode_recorded(),
+		}
+	}
+
+	fn decode_recorded(encoded: &[u8]) -> Option<Self> {
+		NetworkBridgeEvent::decode_recorded(encoded)
+			.map(StatementDistributionMessage::NetworkBridgeUpdateV1)
+	}
+}
+
+impl Recordable for GossipSupportMessage {
+	fn encode_recorded(&self) -> Option<Vec<u8>> {
+		match self {
+			GossipSupportMessage::NetworkBridgeUpdateV1(event) => event.encode_recorded(),
+		}
+	}
+
+	fn decode_recorded(encoded: &[u8]) -> Option<Self> {
+		NetworkBridgeEvent::decode_recorded(encoded)
+			.map(GossipSupportMessage::NetworkBridgeUpdateV1)
+	}
+}
+
+/// Network events are nested in their recorded encoding.
+#[derive(Encode, Decode)]
+enum RecordedCollatorProtocolMessage {
+	CollateOn(ParaId),
+	ReportCollator(CollatorId),
+	NetworkBridgeUpdate(Vec<u8>),
+	Invalid(Hash, CandidateReceipt),
+}
+
+impl Recordable for CollatorProtocolMessage {
+	fn encode_recorded(&self) -> Option<Vec<u8>> {
+		let recorded = match self {
+			CollatorProtocolMessage::CollateOn(para_id) =>
+				RecordedCollatorProtocolMessage::CollateOn(*para_id),
+			CollatorProtocolMessage::ReportCollator(collator) =>
+				RecordedCollatorProtocolMessage::ReportCollator(collator.clone()),
+			CollatorProtocolMessage::NetworkBridgeUpdate(event) =>
+				RecordedCollatorProtocolMessage::NetworkBridgeUpdate(event.encode_recorded()?),
+			CollatorProtocolMessage::Invalid(relay_parent, receipt) =>
+				RecordedCollatorProtocolMessage::Invalid(*relay_parent, receipt.clone()),
+			// The result sender of a collation can not be recorded.
+			CollatorProtocolMessage::DistributeCollation(..) => return None,
+			// A signed statement can not be restored without checking its signature again.
+			CollatorProtocolMessage::Seconded(..) => return None,
+		};
+		Some(recorded.encode())
+	}
+
+	fn decode_recorded(mut encoded: &[u8]) -> Option<Self> {
+		Some(match RecordedCollatorProtocolMessage::decode(&mut encoded).ok()? {
+			RecordedCollatorProtocolMessage::CollateOn(para_id) =>
+				CollatorProtocolMessage::CollateOn(para_id),
+			RecordedCollatorProtocolMessage::ReportCollator(collator) =>
+				CollatorProtocolMessage::ReportCollator(collator),
+			RecordedCollatorProtocolMessage::NetworkBridgeUpdate(event) =>
+				CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::decode_recorded(
+					&event,
+				)?),
+			RecordedCollatorProtocolMessage::Invalid(relay_parent, receipt) =>
+				CollatorProtocolMessage::Invalid(relay_parent, receipt),
+		})
+	}
+}
+
+/// Network events are nested in their recorded encoding.
+#[derive(Encode, Decode)]
+enum RecordedApprovalDistributionMessage {
+	NewBlocks(Vec<BlockApprovalMeta>),
+	DistributeAssignment(IndirectAssignmentCertV2, CandidateBitfield),
+	DistributeApproval(IndirectSignedApprovalVoteV2),
+	NetworkBridgeUpdate(Vec<u8>),
+}
+
+impl Recordable for ApprovalDistributionMessage {
+	fn encode_recorded(&self) -> Option<Vec<u8>> {
+		let recorded = match self {
+			ApprovalDistributionMessage::NewBlocks(metas) =>
+				RecordedApprovalDistributionMessage::NewBlocks(metas.clone()),
+			ApprovalDistributionMessage::DistributeAssignment(cert, claimed) =>
+				RecordedApprovalDistributionMessage::DistributeAssignment(
+					cert.clone(),
+					claimed.clone(),
+				),
+			ApprovalDistributionMessage::DistributeApproval(vote) =>
+				RecordedApprovalDistributionMessage::DistributeApproval(vote.clone()),
+			ApprovalDistributionMessage::NetworkBridgeUpdate(event) =>
+				RecordedApprovalDistributionMessage::NetworkBridgeUpdate(event.encode_recorded()?),
+		};
+		Some(recorded.encode())
+	}
+
+	fn decode_recorded(mut encoded: &[u8]) -> Option<Self> {
+		Some(match RecordedApprovalDistributionMessage::decode(&mut encoded).ok()? {
+			RecordedApprovalDistributionMessage::NewBlocks(metas) =>
+				ApprovalDistributionMessage::NewBlocks(metas),
+			RecordedApprovalDistributionMessage::DistributeAssignment(cert, claimed) =>
+				ApprovalDistributionMessage::DistributeAssignment(cert, claimed),
+			RecordedApprovalDistributionMessage::DistributeApproval(vote) =>
+				ApprovalDistributionMessage::DistributeApproval(vote),
+			RecordedApprovalDistributionMessage::NetworkBridgeUpdate(event) =>
+				ApprovalDistributionMessage::NetworkBridgeUpdate(
+					NetworkBridgeEvent::decode_recorded(&event)?,
+				),
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use polkadot_node_network_protocol::{peer_set::ValidationVersion, v1 as protocol_v1};
+
+	#[test]
+	fn signals_round_trip() {
+		let signals = vec![
+			OverseerSignal::ActiveLeaves(ActiveLeavesUpdate {
+				activated: Some(ActivatedLeaf {
+					hash: Hash::repeat_byte(1),
+					number: 1,
+					status: LeafStatus::Stale,
+					span: Arc::new(jaeger::Span::Disabled),
+				}),
+				deactivated: vec![Hash::repeat_byte(2)].into(),
+			}),
+			OverseerSignal::BlockFinalized(Hash::repeat_byte(3), 3),
+			OverseerSignal::Conclude,
+		];
+
+		for signal in signals {
+			let encoded = signal.encode_recorded().unwrap();
+			assert_eq!(OverseerSignal::decode_recorded(&encoded), Some(signal));
+		}
+	}
+
+	#[test]
+	fn network_bridge_events_round_trip() {
+		let peer = PeerId::random();
+		let events: Vec<NetworkBridgeEvent<protocol_v1::BitfieldDistributionMessage>> = vec![
+			NetworkBridgeEvent::PeerConnected(
+				peer.clone(),
+				ObservedRole::Authority,
+				ValidationVersion::V1.into(),
+				None,
+			),
+			NetworkBridgeEvent::PeerViewChange(
+				peer.clone(),
+				View::new(vec![Hash::repeat_byte(4)], 2),
+			),
+			NetworkBridgeEvent::PeerDisconnected(peer),
+		];
+
+		for event in events {
+			let encoded = event.encode_recorded().unwrap();
+			assert_eq!(NetworkBridgeEvent::decode_recorded(&encoded), Some(event));
+		}
+	}
+
+	#[test]
+	fn collator_protocol_messages_round_trip() {
+		let messages = vec![
+			CollatorProtocolMessage::CollateOn(ParaId::from(1)),
+			CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::PeerDisconnected(
+				PeerId::random(),
+			)),
+		];
+
+		for message in messages {
+			let encoded = message.encode_recorded().unwrap();
+			let decoded = CollatorProtocolMessage::decode_recorded(&encoded).unwrap();
+			assert_eq!(decoded.encode_recorded(), Some(encoded));
+		}
+	}
+}
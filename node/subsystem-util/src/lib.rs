@@ -49,12 +49,15 @@ use futures::{
 use parity_scale_codec::Encode;
 use pin_project::pin_project;
 
-use polkadot_primitives::v2::{
-	AuthorityDiscoveryId, CandidateEvent, CommittedCandidateReceipt, CoreState, EncodeAs,
-	GroupIndex, GroupRotationInfo, Hash, Id as ParaId, OccupiedCoreAssumption,
-	PersistedValidationData, ScrapedOnChainVotes, SessionIndex, SessionInfo, Signed,
-	SigningContext, ValidationCode, ValidationCodeHash, ValidatorId, ValidatorIndex,
-	ValidatorSignature,
+use polkadot_primitives::{
+	v2::{
+		AuthorityDiscoveryId, CandidateEvent, CommittedCandidateReceipt, CoreState, EncodeAs,
+		GroupIndex, GroupRotationInfo, Hash, Id as ParaId, OccupiedCoreAssumption,
+		PersistedValidationData, ScrapedOnChainVotes, SessionIndex, SessionInfo, Signed,
+		SigningContext, ValidationCode, ValidationCodeHash, ValidatorId, ValidatorIndex,
+		ValidatorSignature,
+	},
+	vstaging::InherentWeightModel,
 };
 pub use rand;
 use sp_application_crypto::AppKey;
@@ -219,6 +222,28 @@ specialize_requests! {
 	fn request_validation_code_hash(para_id: ParaId, assumption: OccupiedCoreAssumption)
 		-> Option<ValidationCodeHash>; ValidationCodeHash;
 	fn request_on_chain_votes() -> Option<ScrapedOnChainVotes>; FetchOnChainVotes;
+	fn request_inherent_weight_model() -> InherentWeightModel; StagingInherentWeightModel;
+	fn request_pov_dictionary(para_id: ParaId, hash: Hash) -> Option<Vec<u8>>; StagingPoVDictionary;
+	fn request_runtime_api_version() -> u32; Version;
+}
+
+/// Whether the runtime API at the given relay parent has at least the required version.
+///
+/// Staging runtime APIs should only be requested once this holds, as runtimes which don't
+/// implement them yet fail to execute them.
+pub async fn has_required_runtime(
+	parent: Hash,
+	required_runtime_version: u32,
+	sender: &mut impl SubsystemSender,
+) -> bool {
+	match request_runtime_api_version(parent, sender).await.await {
+		Ok(Ok(runtime_version)) => runtime_version >= required_runtime_version,
+		Ok(Err(error)) => {
+			gum::debug!(?parent, ?error, "Failed to request the runtime API version");
+			false
+		},
+		Err(_) => false,
+	}
 }
 
 /// From the given set of validators, find the first key we can sign with, if any.
@@ -96,6 +96,7 @@ pub fn new_full(
 		None,
 		worker_program_path,
 		false,
+		Default::default(),
 		polkadot_service::RealOverseerGen,
 	)
 }
@@ -318,7 +319,13 @@ impl PolkadotTestNode {
 		para_id: ParaId,
 		collator: CollatorFn,
 	) {
-		let config = CollationGenerationConfig { key: collator_key, collator, para_id };
+		let config = CollationGenerationConfig {
+			key: collator_key,
+			collator,
+			para_id,
+			pov_compression: Default::default(),
+			pov_dictionary: None,
+		};
 
 		self.overseer_handle
 			.send_msg(CollationGenerationMessage::Initialize(config), "Collator")
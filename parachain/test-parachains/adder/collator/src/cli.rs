@@ -18,6 +18,7 @@
 
 use clap::Parser;
 use sc_cli::{RuntimeVersion, SubstrateCli};
+use std::path::PathBuf;
 
 /// Sub-commands supported by the collator.
 #[derive(Debug, Parser)]
@@ -49,6 +50,19 @@ pub struct RunCmd {
 	/// Id of the parachain this collator collates for.
 	#[clap(long)]
 	pub parachain_id: Option<u32>,
+
+	/// The zstd compression level of PoVs compressed with the dictionary.
+	#[clap(long)]
+	pub pov_compression_level: Option<i32>,
+
+	/// PoVs smaller than this many bytes are not compressed.
+	#[clap(long)]
+	pub pov_compression_threshold: Option<usize>,
+
+	/// Path to a zstd dictionary for compressing PoVs. It is only used while it is registered
+	/// for the parachain on-chain.
+	#[clap(long)]
+	pub pov_dictionary: Option<PathBuf>,
 }
 
 #[allow(missing_docs)]
@@ -17,7 +17,7 @@
 //! Collator for the adder test parachain.
 
 use polkadot_cli::{Error, Result};
-use polkadot_node_primitives::CollationGenerationConfig;
+use polkadot_node_primitives::{CollationGenerationConfig, PoVCompressionConfig, PoVDictionary};
 use polkadot_node_subsystem::messages::{CollationGenerationMessage, CollatorProtocolMessage};
 use polkadot_primitives::v2::Id as ParaId;
 use sc_cli::{Error as SubstrateCliError, Role, SubstrateCli};
@@ -53,6 +53,21 @@ fn main() -> Result<()> {
 				)
 			})?;
 
+			let mut pov_compression = PoVCompressionConfig::default();
+			if let Some(level) = cli.run.pov_compression_level {
+				pov_compression.level = level;
+			}
+			if let Some(threshold) = cli.run.pov_compression_threshold {
+				pov_compression.threshold = threshold;
+			}
+			let pov_dictionary = cli
+				.run
+				.pov_dictionary
+				.as_ref()
+				.map(|path| std::fs::read(path).map(PoVDictionary::new))
+				.transpose()
+				.map_err(|e| Error::Other(format!("Cannot read the PoV dictionary: {}", e)))?;
+
 			runner.run_node_until_exit(|config| async move {
 				let role = config.role.clone();
 
@@ -69,6 +84,7 @@ fn main() -> Result<()> {
 							None,
 							None,
 							false,
+							Default::default(),
 							polkadot_service::RealOverseerGen,
 						)
 						.map_err(|e| e.to_string())?;
@@ -93,6 +109,8 @@ fn main() -> Result<()> {
 							collator: collator
 								.create_collation_function(full_node.task_manager.spawn_handle()),
 							para_id,
+							pov_compression,
+							pov_dictionary,
 						};
 						overseer_handle
 							.send_msg(CollationGenerationMessage::Initialize(config), "Collator")
@@ -69,6 +69,7 @@ fn main() -> Result<()> {
 							None,
 							None,
 							false,
+							Default::default(),
 							polkadot_service::RealOverseerGen,
 						)
 						.map_err(|e| e.to_string())?;
@@ -92,6 +93,8 @@ fn main() -> Result<()> {
 							collator: collator
 								.create_collation_function(full_node.task_manager.spawn_handle()),
 							para_id,
+							pov_compression: Default::default(),
+							pov_dictionary: None,
 						};
 						overseer_handle
 							.send_msg(CollationGenerationMessage::Initialize(config), "Collator")
@@ -44,7 +44,7 @@
 //! For more details about how the API versioning works refer to `spi_api`
 //! documentation [here](https://docs.substrate.io/rustdocs/latest/sp_api/macro.decl_runtime_apis.html).
 
-use crate::v2;
+use crate::{v2, vstaging};
 use parity_scale_codec::{Decode, Encode};
 use polkadot_core_primitives as pcp;
 use polkadot_parachain::primitives as ppp;
@@ -155,5 +155,19 @@ sp_api::decl_runtime_apis! {
 		/// Returns all onchain disputes.
 		/// This is a staging method! Do not use on production runtimes!
 		fn staging_get_disputes() -> Vec<(v2::SessionIndex, v2::CandidateHash, v2::DisputeState<v2::BlockNumber>)>;
+
+		/// Returns the weights charged for the contents of the parachains inherent.
+		/// This is a staging method! Do not use on production runtimes!
+		fn staging_inherent_weight_model() -> vstaging::InherentWeightModel;
+
+		/// Submits a report of misbehavior by a validator during backing as an unsigned
+		/// extrinsic.
+		/// This is a staging method! Do not use on production runtimes!
+		fn staging_submit_backing_misbehavior_report(report: vstaging::BackingMisbehaviorReport);
+
+		/// Get the zstd dictionary with the given hash registered for compressing the PoVs of
+		/// the para, if any.
+		/// This is a staging method! Do not use on production runtimes!
+		fn staging_pov_dictionary(para_id: ppp::Id, hash: v2::Hash) -> Option<Vec<u8>>;
 	}
 }
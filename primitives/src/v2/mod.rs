@@ -1223,22 +1223,26 @@ pub enum DisputeStatement {
 impl DisputeStatement {
 	/// Get the payload data for this type of dispute statement.
 	pub fn payload_data(&self, candidate_hash: CandidateHash, session: SessionIndex) -> Vec<u8> {
-		match *self {
+		match self {
 			DisputeStatement::Valid(ValidDisputeStatementKind::Explicit) =>
 				ExplicitDisputeStatement { valid: true, candidate_hash, session }.signing_payload(),
 			DisputeStatement::Valid(ValidDisputeStatementKind::BackingSeconded(
 				inclusion_parent,
 			)) => CompactStatement::Seconded(candidate_hash).signing_payload(&SigningContext {
 				session_index: session,
-				parent_hash: inclusion_parent,
+				parent_hash: *inclusion_parent,
 			}),
 			DisputeStatement::Valid(ValidDisputeStatementKind::BackingValid(inclusion_parent)) =>
 				CompactStatement::Valid(candidate_hash).signing_payload(&SigningContext {
 					session_index: session,
-					parent_hash: inclusion_parent,
+					parent_hash: *inclusion_parent,
 				}),
 			DisputeStatement::Valid(ValidDisputeStatementKind::ApprovalChecking) =>
 				ApprovalVote(candidate_hash).signing_payload(session),
+			DisputeStatement::Valid(
+				ValidDisputeStatementKind::ApprovalCheckingMultipleCandidates(candidate_hashes),
+			) => crate::vstaging::ApprovalVoteMultipleCandidates(candidate_hashes)
+				.signing_payload(session),
 			DisputeStatement::Invalid(InvalidDisputeStatementKind::Explicit) =>
 				ExplicitDisputeStatement { valid: false, candidate_hash, session }.signing_payload(),
 		}
@@ -1252,6 +1256,17 @@ impl DisputeStatement {
 		session: SessionIndex,
 		validator_signature: &ValidatorSignature,
 	) -> Result<(), ()> {
+		if let DisputeStatement::Valid(
+			ValidDisputeStatementKind::ApprovalCheckingMultipleCandidates(candidate_hashes),
+		) = self
+		{
+			if candidate_hashes.len() > crate::vstaging::MAX_APPROVAL_COALESCE_COUNT as usize ||
+				!candidate_hashes.contains(&candidate_hash)
+			{
+				return Err(())
+			}
+		}
+
 		let payload = self.payload_data(candidate_hash, session);
 
 		if validator_signature.verify(&payload[..], &validator_public) {
@@ -1284,6 +1299,7 @@ impl DisputeStatement {
 			Self::Valid(ValidDisputeStatementKind::BackingValid(_)) => true,
 			Self::Valid(ValidDisputeStatementKind::Explicit) |
 			Self::Valid(ValidDisputeStatementKind::ApprovalChecking) |
+			Self::Valid(ValidDisputeStatementKind::ApprovalCheckingMultipleCandidates(_)) |
 			Self::Invalid(_) => false,
 		}
 	}
@@ -1305,6 +1321,15 @@ pub enum ValidDisputeStatementKind {
 	/// An approval vote from the approval checking phase.
 	#[codec(index = 3)]
 	ApprovalChecking,
+	/// An approval vote from the approval checking phase, signed once for several
+	/// candidates at the same time. The candidate the statement refers to must be one
+	/// of the listed candidates, of which there may be at most
+	/// [`MAX_APPROVAL_COALESCE_COUNT`](crate::vstaging::MAX_APPROVAL_COALESCE_COUNT).
+	///
+	/// This is a staging statement kind, which is only accepted by runtimes built with the
+	/// `vstaging` feature.
+	#[codec(index = 4)]
+	ApprovalCheckingMultipleCandidates(Vec<CandidateHash>),
 }
 
 /// Different kinds of statements of invalidity on a candidate.
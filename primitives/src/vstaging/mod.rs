@@ -17,3 +17,166 @@
 //! Staging Primitives.
 
 // Put any primitives used by staging API functions here
+
+use crate::v2::{
+	BackedCandidate, BlakeTwo256, CandidateHash, CompactStatement, DisputeStatement,
+	DisputeStatementSet, Hash, HashT, SessionIndex, SigningContext, ValidDisputeStatementKind,
+	ValidatorId, ValidatorIndex, ValidatorSignature,
+};
+use parity_scale_codec::{Decode, Encode};
+use primitives::RuntimeDebug;
+use runtime_primitives::traits::AppVerify;
+use scale_info::TypeInfo;
+use sp_std::prelude::*;
+
+/// The weights charged by the runtime for the contents of the parachains inherent, linearised
+/// over their variable components.
+///
+/// This allows block authors to assemble an inherent which fits into the block as is, instead
+/// of relying on the runtime to randomly drop parts of an overweight inherent.
+#[derive(Clone, Copy, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, Default)]
+pub struct InherentWeightModel {
+	/// The maximum weight of a block.
+	pub max_block_weight: u64,
+	/// The weight of a single signed availability bitfield.
+	pub bitfield_weight: u64,
+	/// The weight of a backed candidate without any validity votes.
+	pub backed_candidate_base_weight: u64,
+	/// The additional weight of every validity vote of a backed candidate.
+	pub backed_candidate_per_vote_weight: u64,
+	/// The weight of a backed candidate carrying a code upgrade.
+	pub backed_candidate_code_upgrade_weight: u64,
+	/// The weight of a dispute statement set without any statements.
+	pub dispute_statement_set_base_weight: u64,
+	/// The additional weight of every statement of a dispute statement set.
+	pub dispute_statement_weight: u64,
+}
+
+impl InherentWeightModel {
+	/// The weight of `n` signed availability bitfields.
+	pub fn bitfields_weight(&self, n: usize) -> u64 {
+		self.bitfield_weight.saturating_mul(n as u64)
+	}
+
+	/// The weight of a single backed candidate.
+	pub fn backed_candidate_weight<H>(&self, candidate: &BackedCandidate<H>) -> u64 {
+		if candidate.candidate.commitments.new_validation_code.is_some() {
+			self.backed_candidate_code_upgrade_weight
+		} else {
+			self.backed_candidate_per_vote_weight
+				.saturating_mul(candidate.validity_votes.len() as u64)
+				.saturating_add(self.backed_candidate_base_weight)
+		}
+	}
+
+	/// The weight of a single dispute statement set.
+	pub fn dispute_statement_set_weight(&self, set: &DisputeStatementSet) -> u64 {
+		self.dispute_statement_weight
+			.saturating_mul(dispute_statements_charged(set) as u64)
+			.saturating_add(self.dispute_statement_set_base_weight)
+	}
+}
+
+/// The maximum number of candidates an approval vote covering multiple candidates may list.
+pub const MAX_APPROVAL_COALESCE_COUNT: u32 = 16;
+
+/// A vote of approval for multiple candidates, signed once.
+///
+/// Validators which finish several approval checks close together may coalesce the
+/// resulting votes into a single signature over all the approved candidates.
+#[derive(Clone, RuntimeDebug)]
+pub struct ApprovalVoteMultipleCandidates<'a>(pub &'a [CandidateHash]);
+
+impl<'a> ApprovalVoteMultipleCandidates<'a> {
+	/// Yields the signing payload for this approval vote.
+	pub fn signing_payload(&self, session_index: SessionIndex) -> Vec<u8> {
+		const MAGIC: [u8; 4] = *b"APPM";
+
+		(MAGIC, &self.0, session_index).encode()
+	}
+}
+
+/// The number of statements a dispute statement set is charged for.
+///
+/// Approval votes covering multiple candidates are charged once per listed candidate, as the
+/// whole list needs to be encoded to check their signature.
+pub fn dispute_statements_charged(set: &DisputeStatementSet) -> u32 {
+	set.statements
+		.iter()
+		.map(|(statement, _, _)| match statement {
+			DisputeStatement::Valid(
+				ValidDisputeStatementKind::ApprovalCheckingMultipleCandidates(candidate_hashes),
+			) => candidate_hashes.len().max(1) as u32,
+			_ => 1,
+		})
+		.fold(0, |acc, n| acc.saturating_add(n))
+}
+
+/// Two conflicting backing statements signed by the same validator for the same relay parent.
+#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub enum BackingMisbehavior {
+	/// The validator seconded two different candidates.
+	MultipleCandidates {
+		/// The first candidate seconded and the signature of the `Seconded` statement.
+		first: (CandidateHash, ValidatorSignature),
+		/// The second candidate seconded and the signature of the `Seconded` statement.
+		second: (CandidateHash, ValidatorSignature),
+	},
+}
+
+/// A report of misbehavior by a validator during backing, which can be verified on-chain by
+/// the two signed statements it carries.
+#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct BackingMisbehaviorReport {
+	/// The session the statements were signed in.
+	pub session_index: SessionIndex,
+	/// The relay parent the statements were signed for.
+	pub relay_parent: Hash,
+	/// The index of the misbehaving validator in the validator set of the session.
+	pub validator_index: ValidatorIndex,
+	/// The conflicting statements.
+	pub misbehavior: BackingMisbehavior,
+}
+
+impl BackingMisbehaviorReport {
+	/// The signing context of both statements.
+	pub fn signing_context(&self) -> SigningContext {
+		SigningContext { session_index: self.session_index, parent_hash: self.relay_parent }
+	}
+
+	/// The two statements along with their signatures.
+	pub fn statements(&self) -> [(CompactStatement, &ValidatorSignature); 2] {
+		match self.misbehavior {
+			BackingMisbehavior::MultipleCandidates { ref first, ref second } => [
+				(CompactStatement::Seconded(first.0), &first.1),
+				(CompactStatement::Seconded(second.0), &second.1),
+			],
+		}
+	}
+
+	/// Whether the statements actually conflict with each other.
+	pub fn is_conflicting(&self) -> bool {
+		match self.misbehavior {
+			BackingMisbehavior::MultipleCandidates { ref first, ref second } => first.0 != second.0,
+		}
+	}
+
+	/// Check that both statements are signed by the given validator key.
+	pub fn check_signatures(&self, validator: &ValidatorId) -> bool {
+		let context = self.signing_context();
+		self.statements().iter().all(|(statement, signature)| {
+			signature.verify(&statement.signing_payload(&context)[..], validator)
+		})
+	}
+}
+
+/// The maximum size of a zstd dictionary registered for compressing the PoVs of a para.
+pub const MAX_POV_DICTIONARY_SIZE: u32 = 128 * 1024;
+
+/// The maximum number of PoV dictionaries registered for a para at a time.
+pub const MAX_POV_DICTIONARIES_PER_PARA: u32 = 4;
+
+/// The hash a PoV dictionary is registered under, and which PoVs compressed with it refer to.
+pub fn pov_dictionary_hash(dictionary: &[u8]) -> Hash {
+	BlakeTwo256::hash(dictionary)
+}
@@ -24,7 +24,7 @@
 use polkadot_primitives::v2::{
 	CandidateCommitments, CandidateDescriptor, CandidateReceipt, CollatorId, CollatorSignature,
 	CommittedCandidateReceipt, Hash, HeadData, Id as ParaId, ValidationCode, ValidationCodeHash,
-	ValidatorId,
+	ValidatorId, ValidatorSignature,
 };
 pub use rand;
 use sp_application_crypto::sr25519;
@@ -146,6 +146,11 @@ pub fn dummy_collator_signature() -> CollatorSignature {
 	CollatorSignature::from(sr25519::Signature([0u8; 64]))
 }
 
+/// Create a meaningless validator signature.
+pub fn dummy_validator_signature() -> ValidatorSignature {
+	ValidatorSignature::from(sr25519::Signature([0u8; 64]))
+}
+
 /// Create a new candidate descriptor, and apply a valid signature
 /// using the provided `collator` key.
 pub fn make_valid_candidate_descriptor<H: AsRef<[u8]>>(
@@ -0,0 +1,132 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPC exposing the gossip topology computed by the gossip support subsystem.
+
+use jsonrpc_core::Result;
+use jsonrpc_derive::rpc;
+use serde::Serialize;
+
+use polkadot_node_network_protocol::grid_topology::{
+	GossipTopologyIntrospection, TopologySnapshot,
+};
+use polkadot_primitives::v2::{AuthorityDiscoveryId, SessionIndex, ValidatorIndex};
+use sc_rpc::DenyUnsafe;
+
+/// A neighbor of ours in the grid topology.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Neighbor {
+	/// The authority discovery key of the neighbor.
+	pub authority: AuthorityDiscoveryId,
+	/// The index of the neighbor in the validator set of the session.
+	pub validator_index: u32,
+}
+
+/// The grid topology of a session.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Topology {
+	/// The session the topology is used in.
+	pub session: SessionIndex,
+	/// The authorities of the session in the order of the grid, row by row.
+	pub grid: Vec<AuthorityDiscoveryId>,
+	/// Whether the order of the grid was fixed by the node configuration.
+	pub overridden: bool,
+	/// Our neighbors in the row of the grid.
+	pub neighbors_x: Vec<Neighbor>,
+	/// Our neighbors in the column of the grid.
+	pub neighbors_y: Vec<Neighbor>,
+}
+
+/// An authority we are connected to.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectedAuthority {
+	/// The authority discovery key of the authority.
+	pub authority: AuthorityDiscoveryId,
+	/// The base58 encoded peer id the authority is connected with.
+	pub peer_id: String,
+}
+
+/// The gossip topology and the authorities we are connected to.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GossipTopology {
+	/// The topology of the latest session we are an authority in, if any.
+	pub topology: Option<Topology>,
+	/// The authorities we are connected to on the validation peer-set.
+	pub connected_authorities: Vec<ConnectedAuthority>,
+}
+
+fn neighbors(neighbors: Vec<(AuthorityDiscoveryId, ValidatorIndex)>) -> Vec<Neighbor> {
+	neighbors
+		.into_iter()
+		.map(|(authority, index)| Neighbor { authority, validator_index: index.0 })
+		.collect()
+}
+
+impl From<TopologySnapshot> for Topology {
+	fn from(snapshot: TopologySnapshot) -> Self {
+		Topology {
+			session: snapshot.session,
+			grid: snapshot.grid,
+			overridden: snapshot.overridden,
+			neighbors_x: neighbors(snapshot.our_neighbors_x),
+			neighbors_y: neighbors(snapshot.our_neighbors_y),
+		}
+	}
+}
+
+/// Gossip topology RPC methods, meant for debugging test networks.
+#[rpc]
+pub trait GossipTopologyApi {
+	/// Get the current gossip topology and the authorities we are connected to.
+	#[rpc(name = "parachain_gossipTopology")]
+	fn gossip_topology(&self) -> Result<GossipTopology>;
+}
+
+/// Implements the [`GossipTopologyApi`] RPC trait.
+pub struct GossipTopologyRpcHandler {
+	introspection: GossipTopologyIntrospection,
+	deny_unsafe: DenyUnsafe,
+}
+
+impl GossipTopologyRpcHandler {
+	/// Create a new handler, reading from the given introspection handle.
+	pub fn new(introspection: GossipTopologyIntrospection, deny_unsafe: DenyUnsafe) -> Self {
+		Self { introspection, deny_unsafe }
+	}
+}
+
+impl GossipTopologyApi for GossipTopologyRpcHandler {
+	fn gossip_topology(&self) -> Result<GossipTopology> {
+		self.deny_unsafe.check_if_safe()?;
+
+		let mut connected_authorities: Vec<_> = self
+			.introspection
+			.connected_authorities()
+			.into_iter()
+			.map(|(authority, peer)| ConnectedAuthority { authority, peer_id: peer.to_base58() })
+			.collect();
+		connected_authorities.sort_by(|a, b| a.peer_id.cmp(&b.peer_id));
+
+		Ok(GossipTopology {
+			topology: self.introspection.topology().map(Into::into),
+			connected_authorities,
+		})
+	}
+}
@@ -34,6 +34,10 @@ use sp_consensus_babe::BabeApi;
 use sp_keystore::SyncCryptoStorePtr;
 use txpool_api::TransactionPool;
 
+pub mod gossip_topology;
+
+pub use polkadot_node_network_protocol::grid_topology::GossipTopologyIntrospection;
+
 /// A type representing all RPC extensions.
 pub type RpcExtension = jsonrpc_core::IoHandler<sc_rpc::Metadata>;
 
@@ -90,6 +94,8 @@ pub struct FullDeps<C, P, SC, B> {
 	pub grandpa: GrandpaDeps<B>,
 	/// BEEFY specific dependencies.
 	pub beefy: BeefyDeps,
+	/// The gossip topology as seen by the gossip support subsystem.
+	pub gossip_topology: GossipTopologyIntrospection,
 }
 
 /// Instantiate all RPC extensions.
@@ -116,14 +122,24 @@ where
 	B::State: sc_client_api::StateBackend<sp_runtime::traits::HashFor<Block>>,
 {
 	use frame_rpc_system::{FullSystem, SystemApi};
+	use gossip_topology::{GossipTopologyApi, GossipTopologyRpcHandler};
 	use pallet_mmr_rpc::{Mmr, MmrApi};
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApi};
 	use sc_consensus_babe_rpc::BabeRpcHandler;
 	use sc_finality_grandpa_rpc::{GrandpaApi, GrandpaRpcHandler};
 
 	let mut io = jsonrpc_core::IoHandler::default();
-	let FullDeps { client, pool, select_chain, chain_spec, deny_unsafe, babe, grandpa, beefy } =
-		deps;
+	let FullDeps {
+		client,
+		pool,
+		select_chain,
+		chain_spec,
+		deny_unsafe,
+		babe,
+		grandpa,
+		beefy,
+		gossip_topology,
+	} = deps;
 	let BabeDeps { keystore, babe_config, shared_epoch_changes } = babe;
 	let GrandpaDeps {
 		shared_voter_state,
@@ -169,5 +185,10 @@ where
 	)?;
 	io.extend_with(beefy_gadget_rpc::BeefyApi::to_delegate(handler));
 
+	io.extend_with(GossipTopologyApi::to_delegate(GossipTopologyRpcHandler::new(
+		gossip_topology,
+		deny_unsafe,
+	)));
+
 	Ok(io)
 }
@@ -1774,6 +1774,20 @@ sp_api::impl_runtime_apis! {
 		fn staging_get_disputes() -> Vec<(SessionIndex, CandidateHash, DisputeState<BlockNumber>)> {
 			unimplemented!()
 		}
+
+		fn staging_inherent_weight_model() -> primitives::vstaging::InherentWeightModel {
+			unimplemented!()
+		}
+
+		fn staging_submit_backing_misbehavior_report(
+			_: primitives::vstaging::BackingMisbehaviorReport,
+		) {
+			unimplemented!()
+		}
+
+		fn staging_pov_dictionary(_: ParaId, _: Hash) -> Option<Vec<u8>> {
+			unimplemented!()
+		}
 	}
 
 	impl beefy_primitives::BeefyApi<Block> for Runtime {
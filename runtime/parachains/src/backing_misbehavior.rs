@@ -0,0 +1,260 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! The backing misbehavior pallet accepts reports of validators which signed two conflicting
+//! backing statements for the same relay parent.
+//!
+//! Reports are submitted by validators as unsigned extrinsics, via the
+//! `staging_submit_backing_misbehavior_report` runtime API. A report carries both signed
+//! statements, so it is checked against the validator set of the session it refers to and no
+//! trust in the submitter is needed. Every offence is only reported once to the
+//! [`HandleBackingMisbehavior`] hook of the runtime.
+
+use crate::session_info;
+use frame_support::pallet_prelude::*;
+use frame_system::pallet_prelude::*;
+use primitives::{
+	v2::{Hash, SessionIndex, ValidatorIndex},
+	vstaging::BackingMisbehaviorReport,
+};
+use sp_runtime::transaction_validity::{
+	InvalidTransaction, TransactionPriority, TransactionSource, TransactionValidity,
+	ValidTransaction,
+};
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod tests;
+
+const LOG_TARGET: &str = "runtime::backing-misbehavior";
+
+/// The weight of including a report, which is dominated by the verification of two signatures.
+const REPORT_WEIGHT: Weight = 100_000_000;
+
+/// Hooks for misbehavior reported during backing.
+pub trait HandleBackingMisbehavior {
+	/// Report an offence by the validator with the given index in the given session. This is
+	/// expected to be a major punishment, as the validator equivocated.
+	fn report_offence(session: SessionIndex, validator: ValidatorIndex);
+}
+
+impl HandleBackingMisbehavior for () {
+	fn report_offence(_: SessionIndex, _: ValidatorIndex) {}
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::without_storage_info]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config:
+		frame_system::Config
+		+ session_info::Config
+		+ frame_system::offchain::SendTransactionTypes<Call<Self>>
+	{
+		type Event: From<Event> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// What to do with validated reports.
+		type HandleMisbehavior: HandleBackingMisbehavior;
+
+		#[pallet::constant]
+		type UnsignedPriority: Get<TransactionPriority>;
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event {
+		/// A validator signed conflicting backing statements. `session` `validator_index`
+		BackingMisbehaviorReported(SessionIndex, ValidatorIndex),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The session of the report is unknown or already pruned.
+		UnknownSession,
+		/// Claimed validator index is out of bounds.
+		ValidatorIndexOutOfBounds,
+		/// The statements of the report don't conflict.
+		NotConflicting,
+		/// The signatures of the report are invalid.
+		InvalidSignature,
+		/// The misbehavior has already been reported.
+		AlreadyReported,
+	}
+
+	/// The validators already reported per session, along with the relay parent of the
+	/// conflicting statements.
+	#[pallet::storage]
+	pub(super) type Reported<T> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		SessionIndex,
+		Blake2_128Concat,
+		(ValidatorIndex, Hash),
+		(),
+	>;
+
+	/// The session up to which reports were pruned.
+	#[pallet::storage]
+	pub(super) type LastPrunedSession<T> = StorageValue<_, SessionIndex>;
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Includes a report of conflicting backing statements and reports the offence of the
+		/// validator which signed them.
+		#[pallet::weight(REPORT_WEIGHT.saturating_add(T::DbWeight::get().reads_writes(3, 2)))]
+		pub fn report_backing_misbehavior(
+			origin: OriginFor<T>,
+			report: BackingMisbehaviorReport,
+		) -> DispatchResult {
+			ensure_none(origin)?;
+
+			Self::check_report(&report)?;
+
+			Self::prune_old_reports();
+			Reported::<T>::insert(
+				report.session_index,
+				(report.validator_index, report.relay_parent),
+				(),
+			);
+
+			T::HandleMisbehavior::report_offence(report.session_index, report.validator_index);
+			Self::deposit_event(Event::BackingMisbehaviorReported(
+				report.session_index,
+				report.validator_index,
+			));
+
+			Ok(())
+		}
+	}
+
+	#[pallet::validate_unsigned]
+	impl<T: Config> ValidateUnsigned for Pallet<T> {
+		type Call = Call<T>;
+
+		fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			let report = match call {
+				Call::report_backing_misbehavior { report } => report,
+				_ => return InvalidTransaction::Call.into(),
+			};
+
+			if let Err(e) = Self::check_report(report) {
+				return Self::invalid_transaction(e).into()
+			}
+
+			ValidTransaction::with_tag_prefix("BackingMisbehaviorReport")
+				.priority(T::UnsignedPriority::get())
+				.longevity(64_u64)
+				.and_provides((report.session_index, report.validator_index, report.relay_parent))
+				.propagate(true)
+				.build()
+		}
+
+		fn pre_dispatch(call: &Self::Call) -> Result<(), TransactionValidityError> {
+			let report = match call {
+				Call::report_backing_misbehavior { report } => report,
+				_ => return Err(InvalidTransaction::Call.into()),
+			};
+
+			// Reports included by the block author weren't necessarily validated through the
+			// transaction pool, so they are checked again.
+			Self::check_report(report).map_err(|e| Self::invalid_transaction(e).into())
+		}
+	}
+}
+
+// custom transaction error codes
+const INVALID_TX_BAD_VALIDATOR_IDX: u8 = 1;
+const INVALID_TX_NOT_CONFLICTING: u8 = 2;
+const INVALID_TX_ALREADY_REPORTED: u8 = 3;
+
+impl<T: Config> Pallet<T> {
+	/// Submits a report of backing misbehavior as an unsigned transaction into the memory pool.
+	///
+	/// This function expects an offchain context and cannot be callable from the on-chain logic.
+	pub(crate) fn submit_report(report: BackingMisbehaviorReport) {
+		use frame_system::offchain::SubmitTransaction;
+
+		if let Err(e) = SubmitTransaction::<T, Call<T>>::submit_unsigned_transaction(
+			Call::report_backing_misbehavior { report }.into(),
+		) {
+			log::error!(target: LOG_TARGET, "Error submitting backing misbehavior report: {:?}", e);
+		}
+	}
+
+	fn check_report(report: &BackingMisbehaviorReport) -> Result<(), Error<T>> {
+		let session_info = session_info::Pallet::<T>::session_info(report.session_index)
+			.ok_or(Error::<T>::UnknownSession)?;
+		let validator = session_info
+			.validators
+			.get(report.validator_index.0 as usize)
+			.ok_or(Error::<T>::ValidatorIndexOutOfBounds)?;
+
+		ensure!(report.is_conflicting(), Error::<T>::NotConflicting);
+		ensure!(
+			!Reported::<T>::contains_key(
+				report.session_index,
+				(report.validator_index, report.relay_parent),
+			),
+			Error::<T>::AlreadyReported,
+		);
+		ensure!(report.check_signatures(validator), Error::<T>::InvalidSignature);
+
+		Ok(())
+	}
+
+	/// The transaction validity error for a report failing the given check.
+	fn invalid_transaction(error: Error<T>) -> InvalidTransaction {
+		match error {
+			Error::<T>::UnknownSession => InvalidTransaction::Stale,
+			Error::<T>::ValidatorIndexOutOfBounds =>
+				InvalidTransaction::Custom(INVALID_TX_BAD_VALIDATOR_IDX),
+			Error::<T>::NotConflicting => InvalidTransaction::Custom(INVALID_TX_NOT_CONFLICTING),
+			Error::<T>::AlreadyReported => InvalidTransaction::Custom(INVALID_TX_ALREADY_REPORTED),
+			_ => InvalidTransaction::BadProof,
+		}
+	}
+
+	/// Prune the reports of sessions of which the session info was pruned as well.
+	fn prune_old_reports() {
+		let earliest_session = session_info::Pallet::<T>::earliest_stored_session();
+		if earliest_session == 0 {
+			return
+		}
+		let pruning_target = earliest_session - 1;
+
+		LastPrunedSession::<T>::mutate(|last_pruned| {
+			let to_prune = match *last_pruned {
+				Some(last_pruned) if last_pruned >= pruning_target => return,
+				Some(last_pruned) => last_pruned + 1..=pruning_target,
+				None => pruning_target..=pruning_target,
+			};
+
+			for to_prune in to_prune {
+				// Misbehavior is rare, so `None` is fine.
+				Reported::<T>::remove_prefix(to_prune, None);
+			}
+
+			*last_pruned = Some(pruning_target);
+		});
+	}
+}
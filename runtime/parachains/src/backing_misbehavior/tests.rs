@@ -0,0 +1,217 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+use crate::mock::{new_test_ext, BackingMisbehavior, MockGenesisConfig, Test, BACKING_OFFENCES};
+use frame_support::{assert_noop, assert_ok};
+use keyring::Sr25519Keyring;
+use primitives::{
+	v2::{CandidateHash, CompactStatement, SessionInfo, ValidatorId},
+	vstaging::BackingMisbehavior as Misbehavior,
+};
+
+const SESSION: SessionIndex = 3;
+
+static VALIDATORS: &[Sr25519Keyring] =
+	&[Sr25519Keyring::Alice, Sr25519Keyring::Bob, Sr25519Keyring::Charlie];
+
+fn insert_session_info(session: SessionIndex) {
+	let info = SessionInfo {
+		active_validator_indices: (0..VALIDATORS.len() as u32).map(ValidatorIndex).collect(),
+		random_seed: [0; 32],
+		dispute_period: 6,
+		validators: VALIDATORS.iter().map(|v| ValidatorId::from(v.public())).collect(),
+		discovery_keys: Vec::new(),
+		assignment_keys: Vec::new(),
+		validator_groups: Vec::new(),
+		n_cores: 0,
+		zeroth_delay_tranche_width: 0,
+		relay_vrf_modulo_samples: 0,
+		n_delay_tranches: 0,
+		no_show_slots: 0,
+		needed_approvals: 0,
+	};
+	session_info::Sessions::<Test>::insert(session, info);
+}
+
+fn sign(
+	validator: ValidatorIndex,
+	statement: CompactStatement,
+	session_index: SessionIndex,
+	relay_parent: Hash,
+) -> primitives::v2::ValidatorSignature {
+	let context = primitives::v2::SigningContext { session_index, parent_hash: relay_parent };
+	VALIDATORS[validator.0 as usize]
+		.sign(&statement.signing_payload(&context))
+		.into()
+}
+
+fn multiple_candidates_report(
+	validator: ValidatorIndex,
+	signer: ValidatorIndex,
+) -> BackingMisbehaviorReport {
+	let relay_parent = Hash::repeat_byte(1);
+	let (a, b) = (CandidateHash(Hash::repeat_byte(2)), CandidateHash(Hash::repeat_byte(3)));
+	BackingMisbehaviorReport {
+		session_index: SESSION,
+		relay_parent,
+		validator_index: validator,
+		misbehavior: Misbehavior::MultipleCandidates {
+			first: (a, sign(signer, CompactStatement::Seconded(a), SESSION, relay_parent)),
+			second: (b, sign(signer, CompactStatement::Seconded(b), SESSION, relay_parent)),
+		},
+	}
+}
+
+#[test]
+fn valid_report_is_included_once() {
+	new_test_ext(MockGenesisConfig::default()).execute_with(|| {
+		insert_session_info(SESSION);
+		let report = multiple_candidates_report(ValidatorIndex(1), ValidatorIndex(1));
+
+		let call = Call::report_backing_misbehavior { report: report.clone() };
+		assert!(<BackingMisbehavior as ValidateUnsigned>::validate_unsigned(
+			TransactionSource::External,
+			&call,
+		)
+		.is_ok());
+
+		assert_ok!(BackingMisbehavior::report_backing_misbehavior(None.into(), report.clone()));
+		assert_eq!(
+			BACKING_OFFENCES.with(|r| r.borrow().clone()),
+			vec![(SESSION, ValidatorIndex(1))]
+		);
+
+		assert_noop!(
+			BackingMisbehavior::report_backing_misbehavior(None.into(), report),
+			Error::<Test>::AlreadyReported,
+		);
+		assert_eq!(
+			<BackingMisbehavior as ValidateUnsigned>::validate_unsigned(
+				TransactionSource::External,
+				&call,
+			),
+			InvalidTransaction::Custom(INVALID_TX_ALREADY_REPORTED).into(),
+		);
+	});
+}
+
+#[test]
+fn pre_dispatch_checks_reports() {
+	new_test_ext(MockGenesisConfig::default()).execute_with(|| {
+		insert_session_info(SESSION);
+
+		// Signed by another validator than the reported one.
+		let call = Call::report_backing_misbehavior {
+			report: multiple_candidates_report(ValidatorIndex(1), ValidatorIndex(0)),
+		};
+		assert_eq!(
+			<BackingMisbehavior as ValidateUnsigned>::pre_dispatch(&call),
+			Err(InvalidTransaction::BadProof.into()),
+		);
+
+		let report = multiple_candidates_report(ValidatorIndex(1), ValidatorIndex(1));
+		let call = Call::report_backing_misbehavior { report: report.clone() };
+		assert_ok!(<BackingMisbehavior as ValidateUnsigned>::pre_dispatch(&call));
+
+		assert_ok!(BackingMisbehavior::report_backing_misbehavior(None.into(), report));
+		assert_eq!(
+			<BackingMisbehavior as ValidateUnsigned>::pre_dispatch(&call),
+			Err(InvalidTransaction::Custom(INVALID_TX_ALREADY_REPORTED).into()),
+		);
+	});
+}
+
+#[test]
+fn invalid_reports_are_rejected() {
+	new_test_ext(MockGenesisConfig::default()).execute_with(|| {
+		insert_session_info(SESSION);
+
+		// Signed by another validator than the reported one.
+		assert_noop!(
+			BackingMisbehavior::report_backing_misbehavior(
+				None.into(),
+				multiple_candidates_report(ValidatorIndex(1), ValidatorIndex(0)),
+			),
+			Error::<Test>::InvalidSignature,
+		);
+
+		// Validator index out of bounds.
+		assert_noop!(
+			BackingMisbehavior::report_backing_misbehavior(
+				None.into(),
+				multiple_candidates_report(ValidatorIndex(3), ValidatorIndex(0)),
+			),
+			Error::<Test>::ValidatorIndexOutOfBounds,
+		);
+
+		// The same candidate seconded twice.
+		let mut report = multiple_candidates_report(ValidatorIndex(0), ValidatorIndex(0));
+		if let Misbehavior::MultipleCandidates { ref first, ref mut second } = report.misbehavior {
+			*second = first.clone();
+		}
+		assert_noop!(
+			BackingMisbehavior::report_backing_misbehavior(None.into(), report),
+			Error::<Test>::NotConflicting,
+		);
+
+		// Unknown session.
+		let mut report = multiple_candidates_report(ValidatorIndex(0), ValidatorIndex(0));
+		report.session_index = SESSION + 1;
+		assert_noop!(
+			BackingMisbehavior::report_backing_misbehavior(None.into(), report),
+			Error::<Test>::UnknownSession,
+		);
+
+		assert!(BACKING_OFFENCES.with(|r| r.borrow().is_empty()));
+	});
+}
+
+#[test]
+fn reports_of_pruned_sessions_are_pruned() {
+	new_test_ext(MockGenesisConfig::default()).execute_with(|| {
+		insert_session_info(SESSION);
+		let report = multiple_candidates_report(ValidatorIndex(0), ValidatorIndex(0));
+		assert_ok!(BackingMisbehavior::report_backing_misbehavior(None.into(), report));
+		assert_eq!(Reported::<Test>::iter_prefix(SESSION).count(), 1);
+
+		session_info::EarliestStoredSession::<Test>::set(SESSION + 1);
+		insert_session_info(SESSION + 1);
+		let mut report = multiple_candidates_report(ValidatorIndex(0), ValidatorIndex(0));
+		report.session_index = SESSION + 1;
+		// Re-sign for the new session.
+		if let Misbehavior::MultipleCandidates { ref mut first, ref mut second } =
+			report.misbehavior
+		{
+			first.1 = sign(
+				ValidatorIndex(0),
+				CompactStatement::Seconded(first.0),
+				SESSION + 1,
+				report.relay_parent,
+			);
+			second.1 = sign(
+				ValidatorIndex(0),
+				CompactStatement::Seconded(second.0),
+				SESSION + 1,
+				report.relay_parent,
+			);
+		}
+		assert_ok!(BackingMisbehavior::report_backing_misbehavior(None.into(), report));
+
+		assert_eq!(Reported::<Test>::iter_prefix(SESSION).count(), 0);
+		assert_eq!(Reported::<Test>::iter_prefix(SESSION + 1).count(), 1);
+	});
+}
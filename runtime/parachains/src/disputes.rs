@@ -21,12 +21,16 @@ use bitvec::{bitvec, order::Lsb0 as BitOrderLsb0};
 use frame_support::{ensure, traits::Get, weights::Weight};
 use frame_system::pallet_prelude::*;
 use parity_scale_codec::{Decode, Encode};
-use primitives::v2::{
-	byzantine_threshold, supermajority_threshold, ApprovalVote, CandidateHash,
-	CheckedDisputeStatementSet, CheckedMultiDisputeStatementSet, CompactStatement, ConsensusLog,
-	DisputeState, DisputeStatement, DisputeStatementSet, ExplicitDisputeStatement,
-	InvalidDisputeStatementKind, MultiDisputeStatementSet, SessionIndex, SigningContext,
-	ValidDisputeStatementKind, ValidatorId, ValidatorIndex, ValidatorSignature,
+use primitives::{
+	v2::{
+		byzantine_threshold, supermajority_threshold, ApprovalVote, CandidateHash,
+		CheckedDisputeStatementSet, CheckedMultiDisputeStatementSet, CompactStatement,
+		ConsensusLog, DisputeState, DisputeStatement, DisputeStatementSet,
+		ExplicitDisputeStatement, InvalidDisputeStatementKind, MultiDisputeStatementSet,
+		SessionIndex, SigningContext, ValidDisputeStatementKind, ValidatorId, ValidatorIndex,
+		ValidatorSignature,
+	},
+	vstaging::{ApprovalVoteMultipleCandidates, MAX_APPROVAL_COALESCE_COUNT},
 };
 use scale_info::TypeInfo;
 use sp_runtime::{
@@ -1284,21 +1288,34 @@ fn check_signature(
 	statement: &DisputeStatement,
 	validator_signature: &ValidatorSignature,
 ) -> Result<(), ()> {
-	let payload = match *statement {
+	let payload = match statement {
 		DisputeStatement::Valid(ValidDisputeStatementKind::Explicit) =>
 			ExplicitDisputeStatement { valid: true, candidate_hash, session }.signing_payload(),
 		DisputeStatement::Valid(ValidDisputeStatementKind::BackingSeconded(inclusion_parent)) =>
 			CompactStatement::Seconded(candidate_hash).signing_payload(&SigningContext {
 				session_index: session,
-				parent_hash: inclusion_parent,
+				parent_hash: *inclusion_parent,
 			}),
 		DisputeStatement::Valid(ValidDisputeStatementKind::BackingValid(inclusion_parent)) =>
 			CompactStatement::Valid(candidate_hash).signing_payload(&SigningContext {
 				session_index: session,
-				parent_hash: inclusion_parent,
+				parent_hash: *inclusion_parent,
 			}),
 		DisputeStatement::Valid(ValidDisputeStatementKind::ApprovalChecking) =>
 			ApprovalVote(candidate_hash).signing_payload(session),
+		DisputeStatement::Valid(ValidDisputeStatementKind::ApprovalCheckingMultipleCandidates(
+			candidate_hashes,
+		)) => {
+			// Approval votes covering multiple candidates are a staging feature.
+			if !cfg!(feature = "vstaging") ||
+				candidate_hashes.len() > MAX_APPROVAL_COALESCE_COUNT as usize ||
+				!candidate_hashes.contains(&candidate_hash)
+			{
+				return Err(())
+			}
+
+			ApprovalVoteMultipleCandidates(candidate_hashes).signing_payload(session)
+		},
 		DisputeStatement::Invalid(InvalidDisputeStatementKind::Explicit) =>
 			ExplicitDisputeStatement { valid: false, candidate_hash, session }.signing_payload(),
 	};
@@ -1599,6 +1599,111 @@ fn test_check_signature() {
 	.is_err());
 }
 
+#[test]
+#[cfg(feature = "vstaging")]
+fn test_check_signature_multiple_candidates() {
+	let validator_id = <ValidatorId as CryptoType>::Pair::generate().0;
+
+	let session = 0;
+	let candidate_hash = CandidateHash(sp_core::H256::repeat_byte(1));
+	let other_candidate_hash = CandidateHash(sp_core::H256::repeat_byte(2));
+	let unrelated_candidate_hash = CandidateHash(sp_core::H256::repeat_byte(3));
+	let candidate_hashes = vec![candidate_hash, other_candidate_hash];
+
+	let statement = DisputeStatement::Valid(
+		ValidDisputeStatementKind::ApprovalCheckingMultipleCandidates(candidate_hashes.clone()),
+	);
+	let single_statement = DisputeStatement::Valid(ValidDisputeStatementKind::ApprovalChecking);
+
+	let signed = validator_id
+		.sign(&ApprovalVoteMultipleCandidates(&candidate_hashes).signing_payload(session));
+
+	assert!(check_signature(&validator_id.public(), candidate_hash, session, &statement, &signed)
+		.is_ok());
+	assert!(check_signature(
+		&validator_id.public(),
+		other_candidate_hash,
+		session,
+		&statement,
+		&signed
+	)
+	.is_ok());
+	assert!(check_signature(
+		&validator_id.public(),
+		unrelated_candidate_hash,
+		session,
+		&statement,
+		&signed
+	)
+	.is_err());
+	assert!(check_signature(
+		&validator_id.public(),
+		candidate_hash,
+		session + 1,
+		&statement,
+		&signed
+	)
+	.is_err());
+	assert!(check_signature(
+		&validator_id.public(),
+		candidate_hash,
+		session,
+		&single_statement,
+		&signed
+	)
+	.is_err());
+
+	// A statement listing a different set of candidates doesn't match the signature.
+	let wrong_statement = DisputeStatement::Valid(
+		ValidDisputeStatementKind::ApprovalCheckingMultipleCandidates(vec![candidate_hash]),
+	);
+	assert!(check_signature(
+		&validator_id.public(),
+		candidate_hash,
+		session,
+		&wrong_statement,
+		&signed
+	)
+	.is_err());
+
+	// Statements listing too many candidates are rejected, even if signed correctly.
+	let too_many: Vec<_> = (0..=MAX_APPROVAL_COALESCE_COUNT)
+		.map(|i| CandidateHash(sp_core::H256::from_low_u64_be(i as u64 + 1)))
+		.collect();
+	let too_many_statement = DisputeStatement::Valid(
+		ValidDisputeStatementKind::ApprovalCheckingMultipleCandidates(too_many.clone()),
+	);
+	let signed_too_many =
+		validator_id.sign(&ApprovalVoteMultipleCandidates(&too_many).signing_payload(session));
+	assert!(check_signature(
+		&validator_id.public(),
+		too_many[0],
+		session,
+		&too_many_statement,
+		&signed_too_many
+	)
+	.is_err());
+}
+
+#[test]
+#[cfg(not(feature = "vstaging"))]
+fn test_check_signature_multiple_candidates_requires_vstaging() {
+	let validator_id = <ValidatorId as CryptoType>::Pair::generate().0;
+
+	let session = 0;
+	let candidate_hash = CandidateHash(sp_core::H256::repeat_byte(1));
+	let candidate_hashes = vec![candidate_hash, CandidateHash(sp_core::H256::repeat_byte(2))];
+
+	let statement = DisputeStatement::Valid(
+		ValidDisputeStatementKind::ApprovalCheckingMultipleCandidates(candidate_hashes.clone()),
+	);
+	let signed = validator_id
+		.sign(&ApprovalVoteMultipleCandidates(&candidate_hashes).signing_payload(session));
+
+	assert!(check_signature(&validator_id.public(), candidate_hash, session, &statement, &signed)
+		.is_err());
+}
+
 #[test]
 fn deduplication_and_sorting_works() {
 	new_test_ext(Default::default()).execute_with(|| {
@@ -23,6 +23,7 @@
 #![cfg_attr(feature = "runtime-benchmarks", recursion_limit = "256")]
 #![cfg_attr(not(feature = "std"), no_std)]
 
+pub mod backing_misbehavior;
 pub mod configuration;
 pub mod disputes;
 pub mod dmp;
@@ -33,6 +34,7 @@ pub mod metrics;
 pub mod origin;
 pub mod paras;
 pub mod paras_inherent;
+pub mod pov_dictionaries;
 pub mod reward_points;
 pub mod scheduler;
 pub mod session_info;
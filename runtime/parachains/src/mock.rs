@@ -67,6 +67,8 @@ frame_support::construct_runtime!(
 		ParachainsOrigin: origin,
 		SessionInfo: session_info,
 		Disputes: disputes,
+		BackingMisbehavior: backing_misbehavior,
+		PoVDictionaries: pov_dictionaries,
 		Babe: pallet_babe,
 	}
 );
@@ -289,6 +291,26 @@ impl crate::disputes::PunishValidators for Test {
 	}
 }
 
+impl crate::backing_misbehavior::Config for Test {
+	type Event = Event;
+	type HandleMisbehavior = Self;
+	type UnsignedPriority = ParasUnsignedPriority;
+}
+
+thread_local! {
+	pub static BACKING_OFFENCES: RefCell<Vec<(SessionIndex, ValidatorIndex)>> = RefCell::new(Vec::new());
+}
+
+impl crate::backing_misbehavior::HandleBackingMisbehavior for Test {
+	fn report_offence(session: SessionIndex, validator: ValidatorIndex) {
+		BACKING_OFFENCES.with(|r| r.borrow_mut().push((session, validator)))
+	}
+}
+
+impl crate::pov_dictionaries::Config for Test {
+	type Event = Event;
+}
+
 impl crate::scheduler::Config for Test {}
 
 impl crate::inclusion::Config for Test {
@@ -403,6 +425,7 @@ pub fn new_test_ext(state: MockGenesisConfig) -> TestExternalities {
 
 	BACKING_REWARDS.with(|r| r.borrow_mut().clear());
 	AVAILABILITY_REWARDS.with(|r| r.borrow_mut().clear());
+	BACKING_OFFENCES.with(|r| r.borrow_mut().clear());
 
 	let mut t = state.system.build_storage::<Test>().unwrap();
 	state.configuration.assimilate_storage(&mut t).unwrap();
@@ -16,10 +16,12 @@
 use super::{
 	BackedCandidate, Config, DisputeStatementSet, UncheckedSignedAvailabilityBitfield, Weight,
 };
+use primitives::vstaging::dispute_statements_charged;
 
 pub trait WeightInfo {
 	/// Variant over `v`, the count of dispute statements in a dispute statement set. This gives the
-	/// weight of a single dispute statement set.
+	/// weight of a single dispute statement set. Approval votes covering multiple candidates count
+	/// once per candidate.
 	fn enter_variable_disputes(v: u32) -> Weight;
 	/// The weight of one bitfield.
 	fn enter_bitfields() -> Weight;
@@ -83,9 +85,9 @@ pub fn paras_inherent_total_weight<T: Config>(
 pub fn dispute_statement_set_weight<T: Config, S: AsRef<DisputeStatementSet>>(
 	statement_set: S,
 ) -> Weight {
-	<<T as Config>::WeightInfo as WeightInfo>::enter_variable_disputes(
-		statement_set.as_ref().statements.len() as u32,
-	)
+	<<T as Config>::WeightInfo as WeightInfo>::enter_variable_disputes(dispute_statements_charged(
+		statement_set.as_ref(),
+	))
 }
 
 pub fn multi_dispute_statement_sets_weight<
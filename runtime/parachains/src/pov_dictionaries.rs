@@ -0,0 +1,168 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! The PoV dictionaries pallet stores the zstd dictionaries paras may compress their PoVs with.
+//!
+//! A PoV compressed with a dictionary only refers to it by hash. Every validator checking the
+//! candidate, whether while backing, approval checking or participating in a dispute, fetches
+//! the dictionary from the state of the relay parent of the candidate via the
+//! `staging_pov_dictionary` runtime API. Registering a dictionary therefore makes it available
+//! for as long as candidates relying on it can be checked.
+
+use frame_support::pallet_prelude::*;
+use frame_system::pallet_prelude::*;
+use primitives::{
+	v2::{Hash, Id as ParaId},
+	vstaging::{pov_dictionary_hash, MAX_POV_DICTIONARIES_PER_PARA, MAX_POV_DICTIONARY_SIZE},
+};
+use sp_std::prelude::*;
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod tests;
+
+/// The weight of registering a dictionary, apart from hashing and storing its contents.
+const REGISTER_WEIGHT: Weight = 20_000_000;
+
+/// The weight of hashing and storing a byte of a dictionary.
+const REGISTER_PER_BYTE_WEIGHT: Weight = 2_000;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::without_storage_info]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		type Event: From<Event> + IsType<<Self as frame_system::Config>::Event>;
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event {
+		/// A PoV dictionary was registered for a para. `para_id` `dictionary_hash`
+		PoVDictionaryRegistered(ParaId, Hash),
+		/// A PoV dictionary of a para was removed. `para_id` `dictionary_hash`
+		PoVDictionaryRemoved(ParaId, Hash),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The dictionary is empty.
+		EmptyDictionary,
+		/// The dictionary exceeds `MAX_POV_DICTIONARY_SIZE`.
+		DictionaryTooLarge,
+		/// The dictionary is already registered for the para.
+		AlreadyRegistered,
+		/// No dictionary with the given hash is registered for the para.
+		UnknownDictionary,
+	}
+
+	/// The registered dictionaries by para and hash.
+	#[pallet::storage]
+	pub(super) type Dictionaries<T> =
+		StorageDoubleMap<_, Twox64Concat, ParaId, Identity, Hash, Vec<u8>>;
+
+	/// The hashes of the dictionaries registered for a para, oldest first.
+	#[pallet::storage]
+	pub(super) type DictionaryHashes<T> =
+		StorageMap<_, Twox64Concat, ParaId, Vec<Hash>, ValueQuery>;
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Register a zstd dictionary for compressing the PoVs of a para.
+		///
+		/// If the para already has `MAX_POV_DICTIONARIES_PER_PARA` dictionaries, the oldest one
+		/// is removed.
+		#[pallet::weight(
+			REGISTER_WEIGHT
+				.saturating_add(REGISTER_PER_BYTE_WEIGHT.saturating_mul(dictionary.len() as Weight))
+				.saturating_add(T::DbWeight::get().reads_writes(2, 3))
+		)]
+		pub fn register_pov_dictionary(
+			origin: OriginFor<T>,
+			para: ParaId,
+			dictionary: Vec<u8>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(!dictionary.is_empty(), Error::<T>::EmptyDictionary);
+			ensure!(
+				dictionary.len() <= MAX_POV_DICTIONARY_SIZE as usize,
+				Error::<T>::DictionaryTooLarge,
+			);
+
+			let hash = pov_dictionary_hash(&dictionary);
+			ensure!(!Dictionaries::<T>::contains_key(para, hash), Error::<T>::AlreadyRegistered);
+
+			let evicted = DictionaryHashes::<T>::mutate(para, |hashes| {
+				hashes.push(hash);
+				if hashes.len() > MAX_POV_DICTIONARIES_PER_PARA as usize {
+					Some(hashes.remove(0))
+				} else {
+					None
+				}
+			});
+			if let Some(evicted) = evicted {
+				Dictionaries::<T>::remove(para, evicted);
+				Self::deposit_event(Event::PoVDictionaryRemoved(para, evicted));
+			}
+
+			Dictionaries::<T>::insert(para, hash, dictionary);
+			Self::deposit_event(Event::PoVDictionaryRegistered(para, hash));
+
+			Ok(())
+		}
+
+		/// Remove a PoV dictionary of a para.
+		#[pallet::weight(T::DbWeight::get().reads_writes(2, 2))]
+		pub fn remove_pov_dictionary(
+			origin: OriginFor<T>,
+			para: ParaId,
+			dictionary_hash: Hash,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+			ensure!(
+				Dictionaries::<T>::contains_key(para, dictionary_hash),
+				Error::<T>::UnknownDictionary,
+			);
+
+			Dictionaries::<T>::remove(para, dictionary_hash);
+			DictionaryHashes::<T>::mutate_exists(para, |hashes| {
+				if let Some(ref mut inner) = hashes {
+					inner.retain(|h| h != &dictionary_hash);
+					if inner.is_empty() {
+						*hashes = None;
+					}
+				}
+			});
+			Self::deposit_event(Event::PoVDictionaryRemoved(para, dictionary_hash));
+
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The dictionary with the given hash registered for the para, if any.
+	pub(crate) fn pov_dictionary(para: ParaId, dictionary_hash: Hash) -> Option<Vec<u8>> {
+		Dictionaries::<T>::get(para, dictionary_hash)
+	}
+}
@@ -0,0 +1,99 @@
+// Copyright 2022 Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+use crate::mock::{new_test_ext, MockGenesisConfig, Origin, PoVDictionaries, Test};
+use frame_support::{assert_noop, assert_ok};
+use sp_runtime::DispatchError;
+
+#[test]
+fn dictionaries_are_registered_per_para() {
+	new_test_ext(MockGenesisConfig::default()).execute_with(|| {
+		let (a, b) = (ParaId::from(1), ParaId::from(2));
+		let dictionary = vec![1; 64];
+		let hash = pov_dictionary_hash(&dictionary);
+
+		assert_noop!(
+			PoVDictionaries::register_pov_dictionary(Origin::signed(1), a, dictionary.clone()),
+			DispatchError::BadOrigin,
+		);
+
+		assert_ok!(PoVDictionaries::register_pov_dictionary(Origin::root(), a, dictionary.clone()));
+		assert_eq!(Pallet::<Test>::pov_dictionary(a, hash), Some(dictionary.clone()));
+		assert_eq!(Pallet::<Test>::pov_dictionary(b, hash), None);
+
+		assert_noop!(
+			PoVDictionaries::register_pov_dictionary(Origin::root(), a, dictionary),
+			Error::<Test>::AlreadyRegistered,
+		);
+	});
+}
+
+#[test]
+fn dictionaries_are_bounded() {
+	new_test_ext(MockGenesisConfig::default()).execute_with(|| {
+		let para = ParaId::from(1);
+
+		assert_noop!(
+			PoVDictionaries::register_pov_dictionary(Origin::root(), para, Vec::new()),
+			Error::<Test>::EmptyDictionary,
+		);
+		assert_noop!(
+			PoVDictionaries::register_pov_dictionary(
+				Origin::root(),
+				para,
+				vec![1; MAX_POV_DICTIONARY_SIZE as usize + 1],
+			),
+			Error::<Test>::DictionaryTooLarge,
+		);
+
+		let dictionaries: Vec<_> =
+			(0..=MAX_POV_DICTIONARIES_PER_PARA as u8).map(|i| vec![i; 64]).collect();
+		for dictionary in &dictionaries {
+			assert_ok!(PoVDictionaries::register_pov_dictionary(
+				Origin::root(),
+				para,
+				dictionary.clone()
+			));
+		}
+
+		// The oldest dictionary was evicted.
+		let hashes: Vec<_> = dictionaries.iter().map(|d| pov_dictionary_hash(d)).collect();
+		assert_eq!(DictionaryHashes::<Test>::get(para), hashes[1..].to_vec());
+		assert_eq!(Pallet::<Test>::pov_dictionary(para, hashes[0]), None);
+		assert_eq!(Pallet::<Test>::pov_dictionary(para, hashes[1]), Some(dictionaries[1].clone()));
+	});
+}
+
+#[test]
+fn dictionaries_can_be_removed() {
+	new_test_ext(MockGenesisConfig::default()).execute_with(|| {
+		let para = ParaId::from(1);
+		let dictionary = vec![1; 64];
+		let hash = pov_dictionary_hash(&dictionary);
+
+		assert_noop!(
+			PoVDictionaries::remove_pov_dictionary(Origin::root(), para, hash),
+			Error::<Test>::UnknownDictionary,
+		);
+
+		assert_ok!(PoVDictionaries::register_pov_dictionary(Origin::root(), para, dictionary));
+		assert_ok!(PoVDictionaries::remove_pov_dictionary(Origin::root(), para, hash));
+
+		assert_eq!(Pallet::<Test>::pov_dictionary(para, hash), None);
+		assert!(!DictionaryHashes::<Test>::contains_key(para));
+	});
+}
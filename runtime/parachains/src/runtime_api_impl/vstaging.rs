@@ -16,8 +16,13 @@
 
 // Put implementations of functions from staging API here.
 
-use crate::disputes;
-use primitives::v2::{CandidateHash, DisputeState, SessionIndex};
+use crate::{backing_misbehavior, disputes, paras_inherent, pov_dictionaries};
+use frame_support::traits::Get;
+use paras_inherent::WeightInfo;
+use primitives::{
+	v2::{CandidateHash, DisputeState, Hash, Id as ParaId, SessionIndex},
+	vstaging::{BackingMisbehaviorReport, InherentWeightModel},
+};
 use sp_std::prelude::*;
 
 /// Implementation for `get_session_disputes` function from the runtime API
@@ -25,3 +30,38 @@ pub fn get_session_disputes<T: disputes::Config>(
 ) -> Vec<(SessionIndex, CandidateHash, DisputeState<T::BlockNumber>)> {
 	<disputes::Pallet<T>>::disputes()
 }
+
+/// Implementation for `staging_inherent_weight_model` function from the runtime API
+pub fn inherent_weight_model<T: paras_inherent::Config>() -> InherentWeightModel {
+	type Weights<T> = <T as paras_inherent::Config>::WeightInfo;
+
+	let backed_candidate_base_weight = Weights::<T>::enter_backed_candidates_variable(0);
+	let dispute_statement_set_base_weight = Weights::<T>::enter_variable_disputes(0);
+
+	InherentWeightModel {
+		max_block_weight: <T as frame_system::Config>::BlockWeights::get().max_block,
+		bitfield_weight: Weights::<T>::enter_bitfields(),
+		backed_candidate_base_weight,
+		backed_candidate_per_vote_weight: Weights::<T>::enter_backed_candidates_variable(1)
+			.saturating_sub(backed_candidate_base_weight),
+		backed_candidate_code_upgrade_weight: Weights::<T>::enter_backed_candidate_code_upgrade(),
+		dispute_statement_set_base_weight,
+		dispute_statement_weight: Weights::<T>::enter_variable_disputes(1)
+			.saturating_sub(dispute_statement_set_base_weight),
+	}
+}
+
+/// Implementation for `staging_submit_backing_misbehavior_report` function from the runtime API
+pub fn submit_backing_misbehavior_report<T: backing_misbehavior::Config>(
+	report: BackingMisbehaviorReport,
+) {
+	<backing_misbehavior::Pallet<T>>::submit_report(report)
+}
+
+/// Implementation for `staging_pov_dictionary` function from the runtime API
+pub fn pov_dictionary<T: pov_dictionaries::Config>(
+	para_id: ParaId,
+	dictionary_hash: Hash,
+) -> Option<Vec<u8>> {
+	<pov_dictionaries::Pallet<T>>::pov_dictionary(para_id, dictionary_hash)
+}
@@ -1256,6 +1256,20 @@ sp_api::impl_runtime_apis! {
 		fn staging_get_disputes() -> Vec<(SessionIndex, CandidateHash, DisputeState<BlockNumber>)> {
 			unimplemented!()
 		}
+
+		fn staging_inherent_weight_model() -> primitives::vstaging::InherentWeightModel {
+			unimplemented!()
+		}
+
+		fn staging_submit_backing_misbehavior_report(
+			_: primitives::vstaging::BackingMisbehaviorReport,
+		) {
+			unimplemented!()
+		}
+
+		fn staging_pov_dictionary(_: ParaId, _: Hash) -> Option<Vec<u8>> {
+			unimplemented!()
+		}
 	}
 
 	impl fg_primitives::GrandpaApi<Block> for Runtime {
@@ -25,12 +25,13 @@ use parity_scale_codec::Encode;
 use sp_std::{collections::btree_map::BTreeMap, prelude::*};
 
 use polkadot_runtime_parachains::{
+	backing_misbehavior as parachains_backing_misbehavior,
 	configuration as parachains_configuration, disputes as parachains_disputes,
 	dmp as parachains_dmp, hrmp as parachains_hrmp, inclusion as parachains_inclusion,
 	initializer as parachains_initializer, origin as parachains_origin, paras as parachains_paras,
-	paras_inherent as parachains_paras_inherent, runtime_api_impl::v2 as runtime_impl,
-	scheduler as parachains_scheduler, session_info as parachains_session_info,
-	shared as parachains_shared, ump as parachains_ump,
+	paras_inherent as parachains_paras_inherent, pov_dictionaries as parachains_pov_dictionaries,
+	runtime_api_impl::v2 as runtime_impl, scheduler as parachains_scheduler,
+	session_info as parachains_session_info, shared as parachains_shared, ump as parachains_ump,
 };
 
 use authority_discovery_primitives::AuthorityId as AuthorityDiscoveryId;
@@ -479,6 +480,16 @@ impl parachains_inclusion::Config for Runtime {
 	type RewardValidators = RewardValidatorsWithEraPoints<Runtime>;
 }
 
+impl parachains_pov_dictionaries::Config for Runtime {
+	type Event = Event;
+}
+
+impl parachains_backing_misbehavior::Config for Runtime {
+	type Event = Event;
+	type HandleMisbehavior = ();
+	type UnsignedPriority = ParasUnsignedPriority;
+}
+
 impl parachains_disputes::Config for Runtime {
 	type Event = Event;
 	type RewardValidators = ();
@@ -697,6 +708,8 @@ construct_runtime! {
 		Dmp: parachains_dmp::{Pallet, Call, Storage},
 		Xcm: pallet_xcm::{Pallet, Call, Event<T>, Origin},
 		ParasDisputes: parachains_disputes::{Pallet, Storage, Event<T>},
+		ParasBackingMisbehavior: parachains_backing_misbehavior::{Pallet, Call, Storage, Event, ValidateUnsigned},
+		ParasPoVDictionaries: parachains_pov_dictionaries::{Pallet, Call, Storage, Event},
 
 		Sudo: pallet_sudo::{Pallet, Call, Storage, Config<T>, Event<T>},
 
@@ -903,6 +916,20 @@ sp_api::impl_runtime_apis! {
 		fn staging_get_disputes() -> Vec<(SessionIndex, CandidateHash, DisputeState<BlockNumber>)> {
 			polkadot_runtime_parachains::runtime_api_impl::vstaging::get_session_disputes::<Runtime>()
 		}
+
+		fn staging_inherent_weight_model() -> primitives::vstaging::InherentWeightModel {
+			polkadot_runtime_parachains::runtime_api_impl::vstaging::inherent_weight_model::<Runtime>()
+		}
+
+		fn staging_submit_backing_misbehavior_report(
+			report: primitives::vstaging::BackingMisbehaviorReport,
+		) {
+			polkadot_runtime_parachains::runtime_api_impl::vstaging::submit_backing_misbehavior_report::<Runtime>(report)
+		}
+
+		fn staging_pov_dictionary(para_id: ParaId, hash: Hash) -> Option<Vec<u8>> {
+			polkadot_runtime_parachains::runtime_api_impl::vstaging::pov_dictionary::<Runtime>(para_id, hash)
+		}
 	}
 
 	impl beefy_primitives::BeefyApi<Block> for Runtime {
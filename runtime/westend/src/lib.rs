@@ -48,12 +48,14 @@ use runtime_common::{
 	CurrencyToVote, SlowAdjustingFeeUpdate,
 };
 use runtime_parachains::{
+	backing_misbehavior as parachains_backing_misbehavior,
 	configuration as parachains_configuration, disputes as parachains_disputes,
 	dmp as parachains_dmp, hrmp as parachains_hrmp, inclusion as parachains_inclusion,
 	initializer as parachains_initializer, origin as parachains_origin, paras as parachains_paras,
-	paras_inherent as parachains_paras_inherent, reward_points as parachains_reward_points,
-	runtime_api_impl::v2 as parachains_runtime_api_impl, scheduler as parachains_scheduler,
-	session_info as parachains_session_info, shared as parachains_shared, ump as parachains_ump,
+	paras_inherent as parachains_paras_inherent, pov_dictionaries as parachains_pov_dictionaries,
+	reward_points as parachains_reward_points, runtime_api_impl::v2 as parachains_runtime_api_impl,
+	scheduler as parachains_scheduler, session_info as parachains_session_info,
+	shared as parachains_shared, ump as parachains_ump,
 };
 use scale_info::TypeInfo;
 use sp_core::{OpaqueMetadata, RuntimeDebug};
@@ -897,6 +899,16 @@ impl assigned_slots::Config for Runtime {
 	type MaxTemporarySlotPerLeasePeriod = MaxTemporarySlotPerLeasePeriod;
 }
 
+impl parachains_pov_dictionaries::Config for Runtime {
+	type Event = Event;
+}
+
+impl parachains_backing_misbehavior::Config for Runtime {
+	type Event = Event;
+	type HandleMisbehavior = ();
+	type UnsignedPriority = ParasUnsignedPriority;
+}
+
 impl parachains_disputes::Config for Runtime {
 	type Event = Event;
 	type RewardValidators = ();
@@ -1049,6 +1061,8 @@ construct_runtime! {
 		Hrmp: parachains_hrmp::{Pallet, Call, Storage, Event<T>, Config} = 51,
 		ParaSessionInfo: parachains_session_info::{Pallet, Storage} = 52,
 		ParasDisputes: parachains_disputes::{Pallet, Call, Storage, Event<T>} = 53,
+		ParasBackingMisbehavior: parachains_backing_misbehavior::{Pallet, Call, Storage, Event, ValidateUnsigned} = 54,
+		ParasPoVDictionaries: parachains_pov_dictionaries::{Pallet, Call, Storage, Event} = 55,
 
 		// Parachain Onboarding Pallets. Start indices at 60 to leave room.
 		Registrar: paras_registrar::{Pallet, Call, Storage, Event<T>, Config} = 60,
@@ -1326,6 +1340,20 @@ sp_api::impl_runtime_apis! {
 		fn staging_get_disputes() -> Vec<(SessionIndex, CandidateHash, DisputeState<BlockNumber>)> {
 			runtime_parachains::runtime_api_impl::vstaging::get_session_disputes::<Runtime>()
 		}
+
+		fn staging_inherent_weight_model() -> primitives::vstaging::InherentWeightModel {
+			runtime_parachains::runtime_api_impl::vstaging::inherent_weight_model::<Runtime>()
+		}
+
+		fn staging_submit_backing_misbehavior_report(
+			report: primitives::vstaging::BackingMisbehaviorReport,
+		) {
+			runtime_parachains::runtime_api_impl::vstaging::submit_backing_misbehavior_report::<Runtime>(report)
+		}
+
+		fn staging_pov_dictionary(para_id: ParaId, hash: Hash) -> Option<Vec<u8>> {
+			runtime_parachains::runtime_api_impl::vstaging::pov_dictionary::<Runtime>(para_id, hash)
+		}
 	}
 
 	impl beefy_primitives::BeefyApi<Block> for Runtime {